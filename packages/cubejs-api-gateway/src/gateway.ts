@@ -2087,6 +2087,7 @@ class ApiGateway {
         {
           type: e.type,
           error: e.message,
+          meta: e.meta,
           plainError,
           stack: e.stack,
           requestId
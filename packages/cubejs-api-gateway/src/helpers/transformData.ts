@@ -28,6 +28,7 @@ import {
 
 const COMPARE_DATE_RANGE_FIELD = 'compareDateRange';
 const COMPARE_DATE_RANGE_SEPARATOR = ' - ';
+const GROUPING_ID_FIELD = '__grouping_id';
 const BLENDING_QUERY_KEY_PREFIX = 'time.';
 const BLENDING_QUERY_RES_SEPARATOR = '.';
 const MEMBER_SEPARATOR = '.';
@@ -142,6 +143,10 @@ function getMembers(
   }
   const columns = Object.keys(dbData[0]);
   columns.forEach((column) => {
+    if (column === GROUPING_ID_FIELD) {
+      members[GROUPING_ID_FIELD] = column;
+      return;
+    }
     if (!aliasToMemberNameMap[column] || !annotation[aliasToMemberNameMap[column]]) {
       throw new UserError(
         `You requested hidden member: '${
@@ -190,7 +195,9 @@ function getCompactRow(
 ): DBResponsePrimitive[] {
   const row: DBResponsePrimitive[] = [];
   members.forEach((m: string) => {
-    if (annotation[m]) {
+    if (m === GROUPING_ID_FIELD) {
+      row.push(Number(dbRow[GROUPING_ID_FIELD]));
+    } else if (annotation[m]) {
       row.push(
         transformValue(
           dbRow[membersToAliasMap[m]],
@@ -230,6 +237,9 @@ function getVanilaRow(
   const row = R.pipe(
     R.toPairs,
     R.map(p => {
+      if (p[0] === GROUPING_ID_FIELD) {
+        return [[GROUPING_ID_FIELD, Number(p[1])]];
+      }
       const memberName = aliasToMemberNameMap[p[0]];
       const annotationForMember = annotation[memberName];
       if (!annotationForMember) {
@@ -360,6 +370,7 @@ export {
   AliasToMemberMap,
   COMPARE_DATE_RANGE_FIELD,
   COMPARE_DATE_RANGE_SEPARATOR,
+  GROUPING_ID_FIELD,
   BLENDING_QUERY_KEY_PREFIX,
   BLENDING_QUERY_RES_SEPARATOR,
   MEMBER_SEPARATOR,
@@ -41,6 +41,9 @@ const getPivotQuery = (queryType, queries) => {
 const id = Joi.string().regex(/^[a-zA-Z0-9_]+\.[a-zA-Z0-9_]+$/);
 const idOrMemberExpressionName = Joi.string().regex(/^[a-zA-Z0-9_]+\.[a-zA-Z0-9_]+$|^[a-zA-Z0-9_]+$/);
 const dimensionWithTime = Joi.string().regex(/^[a-zA-Z0-9_]+\.[a-zA-Z0-9_]+(\.(second|minute|hour|day|week|month|year))?$/);
+// Numeric attributes derived from any time dimension, e.g. `orders.createdAt.dow`
+const timeAttributes = ['dow', 'hour_of_day', 'month_of_year', 'week_of_year'];
+const timeAttribute = Joi.string().regex(new RegExp(`^[a-zA-Z0-9_]+\\.[a-zA-Z0-9_]+\\.(${timeAttributes.join('|')})$`));
 const parsedMemberExpression = Joi.object().keys({
   expression: Joi.array().items(Joi.string()).min(1).required(),
   cubeName: Joi.string().required(),
@@ -66,12 +69,16 @@ const operators = [
   'notStartsWith',
   'endsWith',
   'notEndsWith',
+  'regex',
+  'notRegex',
   'in',
   'notIn',
   'gt',
   'gte',
   'lt',
   'lte',
+  'between',
+  'notBetween',
   'set',
   'notSet',
   'inDateRange',
@@ -82,13 +89,22 @@ const operators = [
   'afterDate',
   'afterOrOnDate',
   'measureFilter',
+  'businessDays',
+  'excludeHolidays',
+  'inBuckets',
+  'arrayContains',
+  'arrayContainsAny',
+  'arrayOverlaps',
 ];
 
 const oneFilter = Joi.object().keys({
-  dimension: id,
-  member: id,
+  dimension: Joi.alternatives(id, timeAttribute),
+  member: Joi.alternatives(id, timeAttribute),
   operator: Joi.valid(...operators).required(),
-  values: Joi.array().items(Joi.string().allow('', null), Joi.number(), Joi.boolean(), Joi.link('...'))
+  values: Joi.array().items(Joi.string().allow('', null), Joi.number(), Joi.boolean(), Joi.link('...')),
+  trim: Joi.boolean(),
+  caseSensitive: Joi.boolean(),
+  placeholder: Joi.string(),
 }).xor('dimension', 'member');
 
 const oneCondition = Joi.object().keys({
@@ -99,7 +115,7 @@ const oneCondition = Joi.object().keys({
 const querySchema = Joi.object().keys({
   // TODO add member expression alternatives only for SQL API queries?
   measures: Joi.array().items(Joi.alternatives(id, memberExpression, parsedMemberExpression)),
-  dimensions: Joi.array().items(Joi.alternatives(dimensionWithTime, memberExpression, parsedMemberExpression)),
+  dimensions: Joi.array().items(Joi.alternatives(dimensionWithTime, timeAttribute, memberExpression, parsedMemberExpression)),
   filters: Joi.array().items(oneFilter, oneCondition),
   timeDimensions: Joi.array().items(Joi.object().keys({
     dimension: id.required(),
@@ -108,11 +124,13 @@ const querySchema = Joi.object().keys({
       Joi.array().items(Joi.string()).min(1).max(2),
       Joi.string()
     ],
-    compareDateRange: Joi.array()
+    compareDateRange: Joi.array(),
+    calendar: Joi.string().valid('businessDays', 'excludeHolidays'),
+    shift: Joi.string().pattern(/^-?\d+\s+(second|minute|hour|day|week|month|quarter|year)s?$/, 'time shift interval'),
   }).oxor('dateRange', 'compareDateRange')),
   order: Joi.alternatives(
     Joi.object().pattern(idOrMemberExpressionName, Joi.valid('asc', 'desc')),
-    Joi.array().items(Joi.array().min(2).ordered(idOrMemberExpressionName, Joi.valid('asc', 'desc')))
+    Joi.array().items(Joi.array().min(2).ordered(idOrMemberExpressionName, Joi.valid('asc', 'desc'), Joi.valid('first', 'last')))
   ),
   segments: Joi.array().items(Joi.alternatives(id, memberExpression, parsedMemberExpression)),
   timezone: Joi.string(),
@@ -121,15 +139,24 @@ const querySchema = Joi.object().keys({
   total: Joi.boolean(),
   renewQuery: Joi.boolean(),
   ungrouped: Joi.boolean(),
+  subtotals: Joi.valid('rollup', 'cube'),
+  groupingSets: Joi.array().items(Joi.array().items(id)),
+  asOf: Joi.string(),
+  preview: Joi.boolean(),
   responseFormat: Joi.valid('default', 'compact'),
+  units: Joi.object().pattern(id, Joi.string()),
+  fillPolicies: Joi.object().pattern(id, Joi.valid('previous', 'zero', 'linear')),
+  deterministic: Joi.boolean(),
+  nullLabel: Joi.string(),
+  valueMaps: Joi.object().pattern(id, Joi.object().pattern(Joi.string(), Joi.string())),
 });
 
 const normalizeQueryOrder = order => {
   let result = [];
-  const normalizeOrderItem = (k, direction) => ([k, direction]);
+  const normalizeOrderItem = (k, direction, nulls) => (nulls ? [k, direction, nulls] : [k, direction]);
   if (order) {
     result = Array.isArray(order) ?
-      order.map(([k, direction]) => normalizeOrderItem(k, direction)) :
+      order.map(([k, direction, nulls]) => normalizeOrderItem(k, direction, nulls)) :
       Object.keys(order).map(k => normalizeOrderItem(k, order[k]));
   }
   return result;
@@ -157,7 +184,7 @@ const normalizeQueryFilters = (filter) => (
       throw new UserError(`Operator ${f.operator} not supported for filter: ${JSON.stringify(f)}`);
     }
 
-    if ((!f.values || f.values.length === 0) && ['set', 'notSet', 'measureFilter'].indexOf(f.operator) === -1) {
+    if ((!f.values || f.values.length === 0) && ['set', 'notSet', 'measureFilter', 'businessDays', 'excludeHolidays'].indexOf(f.operator) === -1) {
       throw new UserError(`Values required for filter: ${JSON.stringify(f)}`);
     }
 
@@ -195,7 +222,10 @@ const normalizeQuery = (query, persistent) => {
     );
   }
 
-  const regularToTimeDimension = (query.dimensions || []).filter(d => typeof d === 'string' && d.split('.').length === 3).map(d => ({
+  // `orders.createdAt.day` is a time dimension with a granularity, time attributes are kept as dimensions
+  const isDimensionWithGranularity = d => typeof d === 'string' && d.split('.').length === 3 &&
+    !timeAttributes.includes(d.split('.')[2]);
+  const regularToTimeDimension = (query.dimensions || []).filter(isDimensionWithGranularity).map(d => ({
     dimension: d.split('.').slice(0, 2).join('.'),
     granularity: d.split('.')[2]
   }));
@@ -226,7 +256,7 @@ const normalizeQuery = (query, persistent) => {
     limit: newLimit,
     timezone,
     filters: normalizeQueryFilters(query.filters || []),
-    dimensions: (query.dimensions || []).filter(d => typeof d !== 'string' || d.split('.').length !== 3),
+    dimensions: (query.dimensions || []).filter(d => !isDimensionWithGranularity(d)),
     timeDimensions: (query.timeDimensions || []).map(td => {
       let dateRange;
 
@@ -254,13 +284,14 @@ const normalizeQuery = (query, persistent) => {
 
 const remapQueryOrder = order => {
   let result = [];
-  const normalizeOrderItem = (k, direction) => ({
+  const normalizeOrderItem = (k, direction, nulls) => ({
     id: k,
-    desc: direction === 'desc'
+    desc: direction === 'desc',
+    ...(nulls ? { nulls } : {})
   });
   if (order) {
     result = Array.isArray(order) ?
-      order.map(([k, direction]) => normalizeOrderItem(k, direction)) :
+      order.map(([k, direction, nulls]) => normalizeOrderItem(k, direction, nulls)) :
       Object.keys(order).map(k => normalizeOrderItem(k, order[k]));
   }
   return result;
@@ -84,6 +84,14 @@ interface Query {
   timezone?: string;
   renewQuery?: boolean;
   ungrouped?: boolean;
+  // Subtotal rows of all dimensions, exclusive with groupingSets
+  subtotals?: 'rollup' | 'cube';
+  // Subtotal rows by the listed groups of dimensions
+  groupingSets?: Member[][];
+  // Point in time snapshot cubes are queried at
+  asOf?: string;
+  // Fast but approximate SQL for schema development
+  preview?: boolean;
   responseFormat?: ResultType;
 }
 
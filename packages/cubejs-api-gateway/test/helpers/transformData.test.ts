@@ -1125,6 +1125,20 @@ describe('transformData helpers', () => {
       'https://cube.dev/docs/schema/reference/joins#setting-a-primary-key.'
     );
   });
+
+  test('getVanilaRow helper keeps grouping id of subtotal rows', () => {
+    const data = JSON.parse(
+      JSON.stringify(mockData.regular_discount_by_city.data)
+    );
+    const row = getVanilaRow(
+      data.aliasToMemberNameMap,
+      data.annotation as unknown as { [member: string]: ConfigItem },
+      data.queryType as QueryType,
+      data.query as unknown as NormalizedQuery,
+      { ...data.data[0], __grouping_id: '1' },
+    );
+    expect(row.__grouping_id).toEqual(1);
+  });
 });
 
 describe('transformData default mode', () => {
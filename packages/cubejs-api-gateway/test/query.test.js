@@ -0,0 +1,61 @@
+/* globals describe,test,expect */
+
+import { normalizeQuery } from '../src/query';
+
+describe('normalizeQuery', () => {
+  test('moves dimensions with granularities to time dimensions', () => {
+    const query = normalizeQuery({
+      measures: ['orders.count'],
+      dimensions: ['orders.status', 'orders.createdAt.month'],
+    });
+
+    expect(query.dimensions).toEqual(['orders.status']);
+    expect(query.timeDimensions).toEqual([
+      { dimension: 'orders.createdAt', granularity: 'month' },
+    ]);
+  });
+
+  test('keeps time attributes as dimensions', () => {
+    const query = normalizeQuery({
+      measures: ['orders.count'],
+      dimensions: [
+        'orders.createdAt.dow',
+        'orders.createdAt.hour_of_day',
+        'orders.createdAt.month_of_year',
+        'orders.createdAt.week_of_year',
+      ],
+    });
+
+    expect(query.dimensions).toEqual([
+      'orders.createdAt.dow',
+      'orders.createdAt.hour_of_day',
+      'orders.createdAt.month_of_year',
+      'orders.createdAt.week_of_year',
+    ]);
+    expect(query.timeDimensions).toEqual([]);
+  });
+
+  test('accepts planner options', () => {
+    const query = {
+      measures: ['orders.amount'],
+      dimensions: ['orders.status'],
+      timeDimensions: [{ dimension: 'orders.createdAt', granularity: 'day' }],
+      filters: [{ member: 'orders.createdAt', operator: 'inDateRange', placeholder: 'partition' }],
+      units: { 'orders.amount': 'kg' },
+      fillPolicies: { 'orders.amount': 'previous' },
+      deterministic: true,
+      nullLabel: 'Unknown',
+      valueMaps: { 'orders.status': { c: 'Completed' } },
+    };
+
+    expect(() => normalizeQuery(query)).not.toThrow();
+  });
+
+  test('rejects invalid planner options', () => {
+    const query = { measures: ['orders.amount'] };
+
+    expect(() => normalizeQuery({ ...query, fillPolicies: { 'orders.amount': 'next' } })).toThrow(/Invalid query format/);
+    expect(() => normalizeQuery({ ...query, valueMaps: { 'orders.status': { c: 1 } } })).toThrow(/Invalid query format/);
+    expect(() => normalizeQuery({ ...query, units: { amount: 'kg' } })).toThrow(/Invalid query format/);
+  });
+});
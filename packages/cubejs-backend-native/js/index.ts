@@ -348,6 +348,111 @@ export const buildSqlAndParams = (cubeEvaluator: any): String => {
   return native.buildSqlAndParams(cubeEvaluator);
 };
 
+export const buildSqlAndParamsBatch = (queries: any[]): Array<[String, unknown[]] | { error: string, meta?: Record<string, string> }> => {
+  const native = loadNative();
+
+  return native.buildSqlAndParamsBatch(queries);
+};
+
+export const buildSqlAndParamsVariants = (query: any, variants: any[]): Array<[String, unknown[]] | { error: string, meta?: Record<string, string> }> => {
+  const native = loadNative();
+
+  return native.buildSqlAndParamsVariants(query, variants);
+};
+
+export interface SavedQuery {
+  name: string,
+  description?: string,
+  query: Record<string, unknown>,
+}
+
+export interface SavedQueryValidation {
+  name: string,
+  valid: boolean,
+  error?: string,
+}
+
+/**
+ * Plans the saved query of the given name against the current schema. Saved queries are stored
+ * by the host for the tenant of the request and passed on each call.
+ */
+export const buildSavedQuerySqlAndParams = (query: any, savedQueries: SavedQuery[], name: string): [String, unknown[]] => {
+  const native = loadNative();
+
+  return native.buildSavedQuerySqlAndParams(query, savedQueries, name);
+};
+
+/**
+ * Re-plans saved queries against the current schema.
+ */
+export const validateSavedQueries = (query: any, savedQueries: SavedQuery[]): SavedQueryValidation[] => {
+  const native = loadNative();
+
+  return native.validateSavedQueries(query, savedQueries);
+};
+
+export interface PlannerSchemaRuntime {
+  // JSON of `{ cubes: [...] }` with cube and member definitions, member sql is plain text
+  schema: () => string,
+  // JSON of `{ [type]: { [name]: template } }`, e.g. `sqlTemplates()` of the query class of the dialect
+  templates: () => string,
+}
+
+/**
+ * Handles a JSON-RPC request of the versioned planner protocol: plan, validate, introspect or batch.
+ * The protocol is meant for hosts passing serialized requests, other planner functions of this module
+ * take the compiled schema objects directly and don't go through it.
+ * `plan` requests are planned by the native planner against the schema and sql templates
+ * of `runtime`. Errors thrown by its callbacks are returned as protocol errors.
+ */
+export const plannerProtocolCall = (request: string, runtime?: PlannerSchemaRuntime): string => {
+  const native = loadNative();
+
+  return native.plannerProtocolCall(request, runtime);
+};
+
+export const buildDateRangeProbeSqlAndParams = (cubeEvaluator: any, dimension: string): String => {
+  const native = loadNative();
+
+  return native.buildDateRangeProbeSqlAndParams(cubeEvaluator, dimension);
+};
+
+export const buildFreshnessSqlAndParams = (cubeEvaluator: any, cube: string): String | undefined => {
+  const native = loadNative();
+
+  return native.buildFreshnessSqlAndParams(cubeEvaluator, cube);
+};
+
+export const buildRowCountEstimateSqlAndParams = (cubeEvaluator: any): String => {
+  const native = loadNative();
+
+  return native.buildRowCountEstimateSqlAndParams(cubeEvaluator);
+};
+
+export const buildTotalCountSqlAndParams = (cubeEvaluator: any): String => {
+  const native = loadNative();
+
+  return native.buildTotalCountSqlAndParams(cubeEvaluator);
+};
+
+export const buildExplain = (cubeEvaluator: any): string => {
+  const native = loadNative();
+
+  return native.buildExplain(cubeEvaluator);
+};
+
+export const buildSqlHashes = (cubeEvaluator: any): [string, string] => {
+  const native = loadNative();
+
+  return native.buildSqlHashes(cubeEvaluator);
+};
+
+export const buildFilterPredicateSqlAndParams = (cubeEvaluator: any): String | undefined => {
+  const native = loadNative();
+
+  return native.buildFilterPredicateSqlAndParams(cubeEvaluator);
+};
+
 export interface PyConfiguration {
   repositoryFactory?: (ctx: unknown) => Promise<unknown>,
   logger?: (msg: string, params: Record<string, any>) => void,
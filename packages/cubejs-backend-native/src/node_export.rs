@@ -9,6 +9,7 @@ use futures::StreamExt;
 use serde_json::Map;
 use tokio::sync::Semaphore;
 
+use std::cell::RefCell;
 use std::net::SocketAddr;
 use std::rc::Rc;
 use std::str::FromStr;
@@ -26,13 +27,20 @@ use crate::utils::batch_to_rows;
 use cubenativeutils::wrappers::neon::context::ContextHolder;
 use cubenativeutils::wrappers::neon::inner_types::NeonInnerTypes;
 use cubenativeutils::wrappers::neon::object::NeonObject;
+use cubenativeutils::wrappers::object::NativeArray;
 use cubenativeutils::wrappers::object_handle::NativeObjectHandle;
-use cubenativeutils::wrappers::serializer::NativeDeserialize;
+use cubenativeutils::wrappers::serializer::{NativeDeserialize, NativeSerialize};
 use cubenativeutils::wrappers::NativeContextHolder;
-use cubesqlplanner::cube_bridge::base_query_options::NativeBaseQueryOptions;
+use cubesqlplanner::cube_bridge::base_query_options::{
+    BaseQueryOptions, NativeBaseQueryOptions, QueryVariant,
+};
 use cubesqlplanner::planner::base_query::BaseQuery;
+use cubesqlplanner::planner::batch_query::BatchQuery;
+use cubesqlplanner::planner::incremental_query::IncrementalQuery;
+use cubesqlplanner::planner::query_registry::{QueryRegistry, SavedQuery};
+use cubesqlplanner::protocol::{self as planner_protocol, NoSchemaRuntime, SchemaRuntime};
 
-use cubesql::{telemetry::ReportingLogger, CubeError};
+use cubesql::{telemetry::ReportingLogger, CubeError, CubeErrorCauseType};
 
 use neon::prelude::*;
 
@@ -462,34 +470,295 @@ pub fn setup_logger(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
 //============ sql planner ===================
 
-fn build_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+// Planner errors are thrown as JS errors instead of aborting the process. User errors are
+// flagged with `isUserError` and carry the structured meta of the error, if any
+fn throw_planner_error<'a, C: Context<'a>, T>(cx: &mut C, err: CubeError) -> NeonResult<T> {
+    let (is_user_error, meta) = match err.cause {
+        CubeErrorCauseType::User(meta) => (true, meta),
+        CubeErrorCauseType::Internal(meta) => (false, meta),
+    };
+    let js_error = cx.error(err.message)?;
+    let js_is_user_error = cx.boolean(is_user_error);
+    js_error.set(cx, "isUserError", js_is_user_error)?;
+    if let Some(meta) = meta {
+        let js_meta = cx.empty_object();
+        for (key, value) in meta {
+            let js_value = cx.string(value);
+            js_meta.set(cx, key.as_str(), js_value)?;
+        }
+        js_error.set(cx, "meta", js_meta)?;
+    }
+    cx.throw(js_error)
+}
+
+fn planner_result<T>(
+    neon_context_holder: &ContextHolder<'static, FunctionContext<'static>>,
+    result: Result<T, CubeError>,
+) -> NeonResult<T> {
+    result.or_else(|err| neon_context_holder.with_context(|cx| throw_planner_error(cx, err)))
+}
+
+type PlannerTypes = NeonInnerTypes<'static, FunctionContext<'static>>;
+
+// Runs the planner within the JS context of the call. `args` reads the arguments of the call
+// before the context is handed over to the planner, `plan` returns the result of the call.
+// Errors of both are thrown as JS errors
+fn run_planner<A>(
+    cx: FunctionContext,
+    args: impl FnOnce(&mut FunctionContext<'static>) -> NeonResult<A>,
+    plan: impl FnOnce(
+        &ContextHolder<'static, FunctionContext<'static>>,
+        NativeContextHolder<PlannerTypes>,
+        A,
+    ) -> Result<NativeObjectHandle<PlannerTypes>, CubeError>,
+) -> JsResult<JsValue> {
     //IMPORTANT It seems to be safe here, because context lifetime is bound to function, but this
     //context should be used only inside function
     let mut cx = extend_function_context_lifetime(cx);
-    let options = cx.argument::<JsValue>(0)?;
+    let args = args(&mut cx)?;
 
     let neon_context_holder = ContextHolder::new(cx);
+    let context_holder = NativeContextHolder::<PlannerTypes>::new(neon_context_holder.clone());
 
-    let options = NativeObjectHandle::<NeonInnerTypes<'static, FunctionContext<'static>>>::new(
-        NeonObject::new(neon_context_holder.clone(), options),
-    );
+    let res = planner_result(
+        &neon_context_holder,
+        plan(&neon_context_holder, context_holder, args),
+    )?;
 
-    let context_holder =
-        NativeContextHolder::<NeonInnerTypes<'static, FunctionContext<'static>>>::new(
-            neon_context_holder,
-        );
+    let result: NeonObject<'static, FunctionContext<'static>> = res.into_object();
+    let result = result.into_object();
 
-    let base_query_options = Rc::new(NativeBaseQueryOptions::from_native(options).unwrap());
+    Ok(result)
+}
 
-    let base_query = BaseQuery::try_new(context_holder.clone(), base_query_options).unwrap();
+fn native_handle(
+    neon_context_holder: &ContextHolder<'static, FunctionContext<'static>>,
+    value: Handle<'static, JsValue>,
+) -> NativeObjectHandle<PlannerTypes> {
+    NativeObjectHandle::<PlannerTypes>::new(NeonObject::new(neon_context_holder.clone(), value))
+}
 
-    //arg_clrep.into_js(&mut cx)
-    let res = base_query.build_sql_and_params().unwrap();
+fn native_query_options(
+    neon_context_holder: &ContextHolder<'static, FunctionContext<'static>>,
+    options: Handle<'static, JsValue>,
+) -> Result<Rc<NativeBaseQueryOptions<PlannerTypes>>, CubeError> {
+    Ok(Rc::new(NativeBaseQueryOptions::from_native(
+        native_handle(neon_context_holder, options),
+    )?))
+}
 
-    let result: NeonObject<'static, FunctionContext<'static>> = res.into_object();
-    let result = result.into_object();
+// Plans the query of options passed as the first argument, `args` reads the rest of them
+fn run_base_query<A>(
+    cx: FunctionContext,
+    args: impl FnOnce(&mut FunctionContext<'static>) -> NeonResult<A>,
+    plan: impl FnOnce(BaseQuery<PlannerTypes>, A) -> Result<NativeObjectHandle<PlannerTypes>, CubeError>,
+) -> JsResult<JsValue> {
+    run_planner(
+        cx,
+        |cx| Ok((cx.argument::<JsValue>(0)?, args(cx)?)),
+        |neon_context_holder, context_holder, (options, args)| {
+            let options = native_query_options(neon_context_holder, options)?;
+            plan(BaseQuery::try_new(context_holder, options)?, args)
+        },
+    )
+}
 
-    Ok(result)
+fn build_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(
+        cx,
+        |_| Ok(()),
+        |base_query, _| base_query.build_sql_and_params(),
+    )
+}
+
+fn build_date_range_probe_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(
+        cx,
+        |cx| Ok(cx.argument::<JsString>(1)?.value(cx)),
+        |base_query, dimension| base_query.build_date_range_probe_sql_and_params(dimension),
+    )
+}
+
+fn build_freshness_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(
+        cx,
+        |cx| Ok(cx.argument::<JsString>(1)?.value(cx)),
+        |base_query, cube_name| base_query.build_freshness_sql_and_params(cube_name),
+    )
+}
+
+fn build_row_count_estimate_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(
+        cx,
+        |_| Ok(()),
+        |base_query, _| base_query.build_row_count_estimate_sql_and_params(),
+    )
+}
+
+fn build_total_count_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(
+        cx,
+        |_| Ok(()),
+        |base_query, _| base_query.build_total_count_sql_and_params(),
+    )
+}
+
+fn build_explain(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(cx, |_| Ok(()), |base_query, _| base_query.build_explain())
+}
+
+fn build_sql_hashes(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(
+        cx,
+        |_| Ok(()),
+        |base_query, _| base_query.build_sql_hashes(),
+    )
+}
+
+fn build_filter_predicate_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    run_base_query(
+        cx,
+        |_| Ok(()),
+        |base_query, _| base_query.build_filter_predicate_sql_and_params(),
+    )
+}
+
+fn build_sql_and_params_batch(cx: FunctionContext) -> JsResult<JsValue> {
+    run_planner(
+        cx,
+        |cx| cx.argument::<JsValue>(0),
+        |neon_context_holder, context_holder, options| {
+            let base_query_options = native_handle(neon_context_holder, options)
+                .into_array()?
+                .to_vec()?
+                .into_iter()
+                .map(|options| {
+                    Ok(Rc::new(NativeBaseQueryOptions::from_native(options)?)
+                        as Rc<dyn BaseQueryOptions>)
+                })
+                .collect::<Result<Vec<_>, CubeError>>()?;
+            BatchQuery::try_new(context_holder, base_query_options)?.build_sql_and_params()
+        },
+    )
+}
+
+fn build_sql_and_params_variants(cx: FunctionContext) -> JsResult<JsValue> {
+    run_planner(
+        cx,
+        |cx| Ok((cx.argument::<JsValue>(0)?, cx.argument::<JsValue>(1)?)),
+        |neon_context_holder, context_holder, (options, variants)| {
+            let options = native_query_options(neon_context_holder, options)?;
+            let variants =
+                Vec::<QueryVariant>::from_native(native_handle(neon_context_holder, variants))?;
+            IncrementalQuery::try_new(context_holder, options)?.build_sql_and_params(&variants)
+        },
+    )
+}
+
+fn build_saved_query_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    run_planner(
+        cx,
+        |cx| {
+            Ok((
+                cx.argument::<JsValue>(0)?,
+                cx.argument::<JsValue>(1)?,
+                cx.argument::<JsString>(2)?.value(cx),
+            ))
+        },
+        |neon_context_holder, context_holder, (options, saved_queries, name)| {
+            let options = native_query_options(neon_context_holder, options)?;
+            let saved_queries =
+                Vec::<SavedQuery>::from_native(native_handle(neon_context_holder, saved_queries))?;
+            QueryRegistry::try_new(saved_queries)?
+                .plan(context_holder, options, &name)?
+                .build_sql_and_params()
+        },
+    )
+}
+
+fn validate_saved_queries(cx: FunctionContext) -> JsResult<JsValue> {
+    run_planner(
+        cx,
+        |cx| Ok((cx.argument::<JsValue>(0)?, cx.argument::<JsValue>(1)?)),
+        |neon_context_holder, context_holder, (options, saved_queries)| {
+            let options = native_query_options(neon_context_holder, options)?;
+            let saved_queries =
+                Vec::<SavedQuery>::from_native(native_handle(neon_context_holder, saved_queries))?;
+            QueryRegistry::try_new(saved_queries)?
+                .validate(context_holder.clone(), options)?
+                .to_native(context_holder)
+        },
+    )
+}
+
+// Schema runtime of the host for protocol `plan` requests: `schema` and `templates` callbacks
+// return JSON of cube definitions and sql templates, queries are planned by the planner
+struct JsSchemaRuntime<'a, 'cx> {
+    cx: RefCell<&'a mut FunctionContext<'cx>>,
+    runtime: Handle<'cx, JsObject>,
+}
+
+impl JsSchemaRuntime<'_, '_> {
+    fn call(&self, name: &str) -> Result<String, CubeError> {
+        let mut cx = self.cx.borrow_mut();
+        let cx = &mut **cx;
+        let runtime = self.runtime;
+        let result = cx.try_catch(|cx| {
+            let callback = runtime.get::<JsFunction, _, _>(cx, name)?;
+            callback
+                .call(cx, runtime, Vec::new())?
+                .downcast_or_throw::<JsString, _>(cx)
+        });
+        match result {
+            Ok(value) => Ok(value.value(cx)),
+            Err(error) => {
+                let error = error.downcast::<JsObject, _>(cx).ok();
+                let message = error
+                    .and_then(|e| e.get_opt::<JsString, _, _>(cx, "message").ok().flatten())
+                    .map(|m| m.value(cx))
+                    .unwrap_or_else(|| format!("Schema runtime failed to return {}", name));
+                let is_user_error = error
+                    .and_then(|e| e.get_opt::<JsString, _, _>(cx, "type").ok().flatten())
+                    .map_or(false, |t| t.value(cx) == "UserError");
+                if is_user_error {
+                    Err(CubeError::user(message))
+                } else {
+                    Err(CubeError::internal(message))
+                }
+            }
+        }
+    }
+}
+
+impl SchemaRuntime for JsSchemaRuntime<'_, '_> {
+    fn schema(&self) -> Result<String, CubeError> {
+        self.call("schema")
+    }
+
+    fn templates(&self) -> Result<String, CubeError> {
+        self.call("templates")
+    }
+}
+
+// Planner protocol request, `plan` requests are answered with an error unless the host
+// passes its schema runtime
+fn planner_protocol_call(mut cx: FunctionContext) -> JsResult<JsString> {
+    let request = cx.argument::<JsString>(0)?.value(&mut cx);
+    let runtime = match cx.argument_opt(1) {
+        Some(runtime) => runtime.downcast::<JsObject, _>(&mut cx).ok(),
+        None => None,
+    };
+    let response = match runtime {
+        Some(runtime) => {
+            let runtime = JsSchemaRuntime {
+                cx: RefCell::new(&mut cx),
+                runtime,
+            };
+            planner_protocol::handle_request(&request, &runtime)
+        }
+        None => planner_protocol::handle_request(&request, &NoSchemaRuntime),
+    };
+    Ok(cx.string(response))
 }
 
 fn extend_function_context_lifetime<'a>(cx: FunctionContext<'a>) -> FunctionContext<'static> {
@@ -514,6 +783,33 @@ pub fn register_module_exports<C: NodeConfiguration + 'static>(
     cx.export_function("__js_to_clrepr_to_js", debug_js_to_clrepr_to_js)?;
 
     cx.export_function("buildSqlAndParams", build_sql_and_params)?;
+    cx.export_function("buildSqlAndParamsBatch", build_sql_and_params_batch)?;
+    cx.export_function("buildSqlAndParamsVariants", build_sql_and_params_variants)?;
+    cx.export_function("plannerProtocolCall", planner_protocol_call)?;
+    cx.export_function(
+        "buildSavedQuerySqlAndParams",
+        build_saved_query_sql_and_params,
+    )?;
+    cx.export_function("validateSavedQueries", validate_saved_queries)?;
+    cx.export_function(
+        "buildDateRangeProbeSqlAndParams",
+        build_date_range_probe_sql_and_params,
+    )?;
+    cx.export_function("buildFreshnessSqlAndParams", build_freshness_sql_and_params)?;
+    cx.export_function("buildSqlHashes", build_sql_hashes)?;
+    cx.export_function("buildExplain", build_explain)?;
+    cx.export_function(
+        "buildRowCountEstimateSqlAndParams",
+        build_row_count_estimate_sql_and_params,
+    )?;
+    cx.export_function(
+        "buildTotalCountSqlAndParams",
+        build_total_count_sql_and_params,
+    )?;
+    cx.export_function(
+        "buildFilterPredicateSqlAndParams",
+        build_filter_predicate_sql_and_params,
+    )?;
 
     crate::template::template_register_module(&mut cx)?;
 
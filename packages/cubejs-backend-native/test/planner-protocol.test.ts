@@ -0,0 +1,73 @@
+import fs from 'fs';
+import path from 'path';
+
+import { plannerProtocolCall, PlannerSchemaRuntime } from '../js';
+
+class UserError extends Error {
+  protected readonly type: string = 'UserError';
+}
+
+// `orders` and `customers` cubes with Postgres templates, shared with tests of the planner
+function fixture(fileName: string) {
+  return fs.readFileSync(
+    path.join(process.cwd(), '..', '..', 'rust', 'cubesqlplanner', 'cubesqlplanner', 'tests', 'fixtures', 'static_schema', fileName),
+    'utf8'
+  );
+}
+
+const runtime: PlannerSchemaRuntime = {
+  schema: () => fixture('schema.json'),
+  templates: () => fixture('templates.json'),
+};
+
+function plan(query: any, schemaRuntime?: PlannerSchemaRuntime) {
+  return JSON.parse(plannerProtocolCall(JSON.stringify({
+    jsonrpc: '2.0',
+    id: 1,
+    method: 'plan',
+    params: { query },
+  }), schemaRuntime));
+}
+
+describe('Planner protocol', () => {
+  it('plans queries against the schema of the host', () => {
+    const response = plan({
+      measures: ['orders.count'],
+      dimensions: ['customers.city'],
+      filters: [{ member: 'orders.status', operator: 'equals', values: ['shipped'] }],
+      limit: 10,
+    }, runtime);
+
+    expect(response.result.sql).toContain('FROM public.orders');
+    expect(response.result.sql).toContain('LEFT JOIN public.customers');
+    expect(response.result.params).toEqual(['shipped']);
+    expect(response.result.metadata.memberParams).toEqual({ 'orders.status': [0] });
+  });
+
+  it('returns errors of the planner and of the schema runtime', () => {
+    expect(plan({ measures: ['users.count'] }, runtime).error).toEqual({
+      code: -32001,
+      message: 'Cube \'users\' not found for path \'users.count\'',
+    });
+
+    const userError = plan({ measures: ['orders.count'] }, {
+      ...runtime,
+      schema: () => {
+        throw new UserError('Schema of the tenant not found');
+      },
+    });
+    expect(userError.error).toEqual({ code: -32001, message: 'Schema of the tenant not found' });
+
+    const internalError = plan({ measures: ['orders.count'] }, {
+      ...runtime,
+      templates: () => {
+        throw new Error('Compilation failed');
+      },
+    });
+    expect(internalError.error).toEqual({ code: -32603, message: 'Compilation failed' });
+  });
+
+  it('rejects plan requests without the schema runtime', () => {
+    expect(plan({ measures: ['orders.count'] }).error.code).toEqual(-32001);
+  });
+});
@@ -193,6 +193,30 @@ const variables: Record<string, (...args: any) => any> = {
     .default('1')
     .asInt(),
   nativeSqlPlanner: () => get('CUBEJS_TESSERACT_SQL_PLANNER').asBool(),
+  nativeSqlPlannerFlags: () => get('CUBEJS_TESSERACT_SQL_PLANNER_FLAGS')
+    .asArray(','),
+
+  /**
+   * Tables which cube tables can be overridden with by the compile context,
+   * e.g. `sandbox.orders` or `sandbox.*`.
+   */
+  tableOverridesAllowList: () => get('CUBEJS_TABLE_OVERRIDES_ALLOW_LIST')
+    .asArray(','),
+
+  /**
+   * Secret of the watermark comment appended to the SQL generated by Tesseract.
+   * The comment is a short HMAC of the principal from the security context,
+   * so leaked query text can be traced back to a tenant or user.
+   */
+  queryWatermarkSecret: () => get('CUBEJS_QUERY_WATERMARK_SECRET')
+    .asString(),
+
+  /**
+   * Secret of the `hash` redaction mode: filter values of sensitive members
+   * are replaced with their HMAC in errors, traces and plan dumps.
+   */
+  redactionHashSecret: () => get('CUBEJS_REDACTION_HASH_SECRET')
+    .asString(),
 
   /** ****************************************************************
    * Common db options                                               *
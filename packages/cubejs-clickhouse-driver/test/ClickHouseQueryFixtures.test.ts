@@ -0,0 +1,21 @@
+import { ClickhouseDBRunner, createQueryFixturesTestCase } from '@cubejs-backend/testing-shared';
+import { ClickHouseDriver } from '../src';
+
+let container: any;
+
+createQueryFixturesTestCase({
+  name: 'ClickHouse',
+  dbType: 'clickhouse',
+  connectionFactory: async () => {
+    container = await ClickhouseDBRunner.startContainer({});
+    return new ClickHouseDriver({
+      host: 'localhost',
+      port: container.getMappedPort(8123),
+    });
+  },
+  teardown: async () => {
+    if (container) {
+      await container.stop();
+    }
+  },
+});
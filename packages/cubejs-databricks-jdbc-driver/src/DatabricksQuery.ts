@@ -189,6 +189,11 @@ export class DatabricksQuery extends BaseQuery {
     templates.functions.CURRENTDATE = 'CURRENT_DATE';
     templates.functions.DATETRUNC = 'DATE_TRUNC({{ args_concat }})';
     templates.functions.DATEPART = 'DATE_PART({{ args_concat }})';
+    templates.filters.regex_match = '{{ column }} {% if negated %}NOT {% endif %}RLIKE {{ value }}';
+    templates.expressions.hex_encode = 'lower(hex({{ expr }}))';
+    templates.filters.binary_param = 'unhex({{ param }})';
+    // UUIDs are stored as strings
+    templates.filters.uuid_param = '{{ param }}';
     templates.functions.BTRIM = 'TRIM({% if args[1] is defined %}{{ args[1] }} FROM {% endif %}{{ args[0] }})';
     templates.functions.LTRIM = 'LTRIM({{ args|reverse|join(", ") }})';
     templates.functions.RTRIM = 'RTRIM({{ args|reverse|join(", ") }})';
@@ -201,6 +206,7 @@ export class DatabricksQuery extends BaseQuery {
     templates.expressions.interval_single_date_part = 'INTERVAL \'{{ num }}\' {{ date_part }}';
     templates.quotes.identifiers = '`';
     templates.quotes.escape = '``';
+    templates.quotes.literal_backslash_escape = '\\\\';
     // TODO: Databricks has `TIMESTAMP_NTZ` with logic similar to Pg's `TIMESTAMP`
     // but that requires Runtime 13.3+. Should this be enabled?
     // templates.types.timestamp = 'TIMESTAMP_NTZ';
@@ -53,6 +53,22 @@ export class DuckDBQuery extends BaseQuery {
     templates.functions.DATETRUNC = 'DATE_TRUNC({{ args_concat }})';
     templates.functions.LEAST = 'LEAST({{ args_concat }})';
     templates.functions.GREATEST = 'GREATEST({{ args_concat }})';
+    templates.filters.regex_match = '{% if negated %}NOT {% endif %}regexp_matches({{ column }}, {{ value }})';
+    templates.statements.as_of_join = 'ASOF LEFT JOIN {{ source }} ON {{ condition }} AND {{ match_time }} <= {{ time }}';
+    templates.statements.table_sample = '{{ table }} TABLESAMPLE SYSTEM ({{ percent }}%)';
+    templates.expressions.hex_encode = 'lower(hex({{ expr }}))';
+    templates.filters.binary_param = 'unhex({{ param }})';
+    templates.filters.uuid_param = 'CAST({{ param }} AS UUID)';
+    templates.filters.array_contains_all = 'list_has_all({{ column }}, [{{ values_concat }}])';
+    templates.filters.array_overlaps = 'list_has_any({{ column }}, [{{ values_concat }}])';
+    templates.expressions.unnest = 'UNNEST({{ expr }})';
+    templates.expressions.unnest_distinct = 'UNNEST(list_distinct({{ expr }}))';
+    templates.time_attributes = {
+      dow: 'EXTRACT(ISODOW FROM {{ expr }})',
+      hour_of_day: 'EXTRACT(HOUR FROM {{ expr }})',
+      month_of_year: 'EXTRACT(MONTH FROM {{ expr }})',
+      week_of_year: 'EXTRACT(WEEK FROM {{ expr }})',
+    };
     return templates;
   }
 }
@@ -0,0 +1,9 @@
+import { createQueryFixturesTestCase } from '@cubejs-backend/testing-shared';
+import { DuckDBDriver } from '../src';
+
+createQueryFixturesTestCase({
+  name: 'DuckDB',
+  dbType: 'duckdb',
+  dialectClass: DuckDBDriver.dialectClass(),
+  connectionFactory: async () => new DuckDBDriver({}),
+});
@@ -0,0 +1,25 @@
+import { createQueryFixturesTestCase, PostgresDBRunner } from '@cubejs-backend/testing-shared';
+import { StartedTestContainer } from 'testcontainers';
+import { PostgresDriver } from '../src';
+
+let container: StartedTestContainer;
+
+createQueryFixturesTestCase({
+  name: 'Postgres',
+  dbType: 'postgres',
+  connectionFactory: async () => {
+    container = await PostgresDBRunner.startContainer({ volumes: [] });
+    return new PostgresDriver({
+      host: container.getHost(),
+      port: container.getMappedPort(5432),
+      user: 'test',
+      password: 'test',
+      database: 'test',
+    });
+  },
+  teardown: async () => {
+    if (container) {
+      await container.stop();
+    }
+  },
+});
@@ -38,6 +38,13 @@ export class QuestQuery extends BaseQuery {
     return new QuestParamAllocator();
   }
 
+  public sqlTemplates() {
+    const templates = super.sqlTemplates();
+    // eslint-disable-next-line no-template-curly-in-string
+    templates.params.param = '${{ param_index + 1 }}';
+    return templates;
+  }
+
   public concatStringsSql(strings: string[]): string {
     return `concat(${strings.join(', ')})`;
   }
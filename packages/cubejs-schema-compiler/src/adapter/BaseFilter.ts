@@ -5,6 +5,7 @@ import { FROM_PARTITION_RANGE, TO_PARTITION_RANGE } from '@cubejs-backend/shared
 
 import { BaseDimension } from './BaseDimension';
 import type { BaseQuery } from './BaseQuery';
+import { UserError } from '../compiler/UserError';
 
 const DATE_OPERATORS = ['inDateRange', 'notInDateRange', 'onTheDate', 'beforeDate', 'beforeOrOnDate', 'afterDate', 'afterOrOnDate'];
 const dateTimeLocalMsRegex = /^\d\d\d\d-\d\d-\d\dT\d\d:\d\d:\d\d\.\d\d\d$/;
@@ -22,12 +23,15 @@ export class BaseFilter extends BaseDimension {
 
   public readonly camelizeOperator: any;
 
+  public readonly caseSensitive: boolean;
+
   public constructor(query: BaseQuery, filter: any) {
     super(query, filter.dimension);
     this.measure = filter.measure;
 
     this.operator = filter.operator;
     this.values = filter.values;
+    this.caseSensitive = !!filter.caseSensitive;
     this.camelizeOperator = inlection.camelize(this.operator).replace(
       /[A-Z]/,
       (c) => (c != null ? c : '').toLowerCase(),
@@ -229,7 +233,7 @@ export class BaseFilter extends BaseDimension {
   public likeOr(column, not, type) {
     type = type || 'contains';
     return `${join(not ? ' AND ' : ' OR ', this.filterParams().map(
-      p => this.likeIgnoreCase(column, not, p, type)
+      p => (this.caseSensitive ? this.likeCaseSensitive(column, not, p, type) : this.likeIgnoreCase(column, not, p, type))
     ))}${this.orIsNullCheck(column, not)}`;
   }
 
@@ -247,6 +251,51 @@ export class BaseFilter extends BaseDimension {
     return `${column}${not ? ' NOT' : ''} ILIKE ${p}${this.allocateParam(param)}${s}`;
   }
 
+  /**
+   * Returns case-sensitive SQL LIKE statement for `caseSensitive` filters.
+   * @param {string} column Column name.
+   * @param {boolean} not Flag to build NOT LIKE statement.
+   * @param {*} param Value for statement.
+   * @param {string} type Type of the condition (i.e. contains/startsWith/endsWith).
+   * @returns string
+   */
+  public likeCaseSensitive(column, not, param, type) {
+    const parts = [this.allocateParam(param)];
+    if (!type || type === 'contains' || type === 'ends') {
+      parts.unshift('\'%\'');
+    }
+    if (!type || type === 'contains' || type === 'starts') {
+      parts.push('\'%\'');
+    }
+    const pattern = parts.length > 1 ? this.query.concatStringsSql(parts) : parts[0];
+    return `${column}${not ? ' NOT' : ''} LIKE ${pattern}`;
+  }
+
+  public regexWhere(column) {
+    return this.regexOr(column, false);
+  }
+
+  public notRegexWhere(column) {
+    return this.regexOr(column, true);
+  }
+
+  public regexOr(column, not) {
+    return `(${join(not ? ' AND ' : ' OR ', this.filterParams().map(
+      p => this.regexMatch(column, not, this.allocateParam(p))
+    ))})${this.orIsNullCheck(column, not)}`;
+  }
+
+  /**
+   * Returns SQL partial match of a regular expression, dialects override it with their operators.
+   * @param {string} column Column name.
+   * @param {boolean} not Flag to build a non-matching condition.
+   * @param {string} param Allocated param with the regular expression.
+   * @returns string
+   */
+  public regexMatch(column, not, param) {
+    return `${column} ${not ? '!' : ''}~ ${param}`;
+  }
+
   public orIsNullCheck(column, not) {
     return `${this.shouldAddOrIsNull(not) ? ` OR ${column} IS NULL` : ''}`;
   }
@@ -319,11 +368,32 @@ export class BaseFilter extends BaseDimension {
     return `${column} <= ${this.firstParameter()}`;
   }
 
+  public betweenWhere(column) {
+    const [from, to] = this.betweenParams();
+    return `${column} BETWEEN ${from} AND ${to}`;
+  }
+
+  public notBetweenWhere(column) {
+    const [from, to] = this.betweenParams();
+    return `${column} NOT BETWEEN ${from} AND ${to}${this.orIsNullCheck(column, true)}`;
+  }
+
+  protected betweenParams() {
+    const params = this.filterParams();
+    if (params.length !== 2 || this.valuesContainNull()) {
+      throw new UserError(`Filter ${this.operator} for member '${this.measure || this.dimension}' expects two non-null values: the start and the end of the range`);
+    }
+    return params.map(p => this.allocateCastParam(p));
+  }
+
   public expressionEqualsWhere(column) {
     return `${column} = ${this.values[0]}`;
   }
 
   public inDateRangeWhere(column) {
+    if (this.isOpenDateRange()) {
+      return this.openDateRangeWhere(column);
+    }
     const [from, to] = this.allocateTimestampParams();
     if (!from || !to) {
       return BaseFilter.ALWAYS_TRUE;
@@ -331,6 +401,20 @@ export class BaseFilter extends BaseDimension {
     return this.query.timeRangeFilter(column, from, to);
   }
 
+  /**
+   * Date range with one of the bounds null, e.g. everything since a date.
+   */
+  protected isOpenDateRange() {
+    return this.values && this.values.length >= 2 && (this.values[0] == null) !== (this.values[1] == null);
+  }
+
+  protected openDateRangeWhere(column) {
+    if (this.values[0] != null) {
+      return this.query.afterOrOnDateFilter(column, this.allocateTimestampParam(this.inDbTimeZoneDateFrom(this.values[0])));
+    }
+    return this.query.beforeOrOnDateFilter(column, this.allocateTimestampParam(this.inDbTimeZoneDateTo(this.values[1])));
+  }
+
   public notInDateRangeWhere(column) {
     const [from, to] = this.allocateTimestampParams();
     if (!from || !to) {
@@ -15,6 +15,17 @@ import { FROM_PARTITION_RANGE, inDbTimeZone, MAX_SOURCE_ROW_LIMIT, QueryAlias, g
 
 import {
   buildSqlAndParams as nativeBuildSqlAndParams,
+  buildSqlAndParamsBatch as nativeBuildSqlAndParamsBatch,
+  buildSqlAndParamsVariants as nativeBuildSqlAndParamsVariants,
+  buildDateRangeProbeSqlAndParams as nativeBuildDateRangeProbeSqlAndParams,
+  buildFreshnessSqlAndParams as nativeBuildFreshnessSqlAndParams,
+  buildRowCountEstimateSqlAndParams as nativeBuildRowCountEstimateSqlAndParams,
+  buildTotalCountSqlAndParams as nativeBuildTotalCountSqlAndParams,
+  buildSqlHashes as nativeBuildSqlHashes,
+  buildExplain as nativeBuildExplain,
+  buildSavedQuerySqlAndParams as nativeBuildSavedQuerySqlAndParams,
+  validateSavedQueries as nativeValidateSavedQueries,
+  buildFilterPredicateSqlAndParams as nativeBuildFilterPredicateSqlAndParams,
 } from '@cubejs-backend/native';
 import { UserError } from '../compiler/UserError';
 import { BaseMeasure } from './BaseMeasure';
@@ -48,6 +59,21 @@ const SecondsDurations = {
   second: 1
 };
 
+/**
+ * Calls the native planner. Its user errors are rethrown as `UserError` along with their
+ * structured `meta`, e.g. the member and the suggested value of an invalid filter value.
+ */
+function callNativePlanner(fn, ...args) {
+  try {
+    return fn(...args);
+  } catch (e) {
+    if (e.isUserError) {
+      throw new UserError(e.message, e.meta);
+    }
+    throw e;
+  }
+}
+
 /**
  * Set of the schema compilers.
  * @typedef {Object} Compilers
@@ -217,6 +243,9 @@ export class BaseQuery {
       cubeLatticeCache: this.options.cubeLatticeCache, // TODO too heavy for key
       historyQueries: this.options.historyQueries, // TODO too heavy for key
       ungrouped: this.options.ungrouped,
+      allowUngroupedWithoutPrimaryKey: this.options.allowUngroupedWithoutPrimaryKey,
+      subtotals: this.options.subtotals,
+      groupingSets: this.options.groupingSets,
       memberToAlias: this.options.memberToAlias,
       expressionParams: this.options.expressionParams,
       convertTzForRawTimeDimension: this.options.convertTzForRawTimeDimension,
@@ -264,6 +293,8 @@ export class BaseQuery {
     if (!getEnv('nativeSqlPlanner')) {
       // Tesseract doesn't require join to be prebuilt and there's a case where single join can't be built for multi-fact query
       this.join = this.joinGraph.buildJoin(this.allJoinHints);
+      this.checkTesseractOnlyDimensionTypes();
+      this.checkTesseractOnlyMemberOptions();
     }
     this.cubeAliasPrefix = this.options.cubeAliasPrefix;
     this.preAggregationsSchemaOption = this.options.preAggregationsSchema ?? DEFAULT_PREAGGREGATIONS_SCHEMA;
@@ -349,10 +380,53 @@ export class BaseQuery {
     );
   }
 
+  /**
+   * Binary and UUID dimensions are supported by Tesseract only: this planner neither
+   * validates their filter values nor hex encodes binary values it returns.
+   */
+  checkTesseractOnlyDimensionTypes() {
+    const member = this.flattenAllMembers().find(
+      m => m instanceof BaseDimension && !m.isMemberExpression && ['binary', 'uuid'].includes(m.definition().type)
+    );
+    if (member) {
+      throw new UserError(
+        `Dimension '${member.dimension}' of type '${member.definition().type}' requires Tesseract SQL planner: set CUBEJS_TESSERACT_SQL_PLANNER=true`
+      );
+    }
+  }
+
+  /**
+   * Member options and filter value templates below are applied by Tesseract only:
+   * this planner would silently return unrounded, unconverted or unredacted values.
+   */
+  checkTesseractOnlyMemberOptions() {
+    const options = ['rounding', 'currency', 'unit', 'collation', 'sensitive', 'valueMap', 'startsWithRange', 'duration'];
+    this.flattenAllMembers(true)
+      .filter(m => (m instanceof BaseMeasure || m instanceof BaseDimension) && !m.isMemberExpression)
+      .forEach(m => {
+        const option = options.find(o => m.definition()[o] !== undefined);
+        if (option) {
+          throw new UserError(
+            `Member '${m.measure || m.dimension}' with '${option}' option requires Tesseract SQL planner: set CUBEJS_TESSERACT_SQL_PLANNER=true`
+          );
+        }
+      });
+
+    const template = this.flattenAllMembers(true)
+      .filter(m => m instanceof BaseFilter)
+      .flatMap(f => f.valuesArray())
+      .find(v => typeof v === 'string' && /^\{\{\s*[_a-zA-Z][_a-zA-Z0-9.]*\s*\}\}$/.test(v));
+    if (template) {
+      throw new UserError(
+        `Filter value template '${template}' requires Tesseract SQL planner: set CUBEJS_TESSERACT_SQL_PLANNER=true`
+      );
+    }
+  }
+
   initUngrouped() {
     this.ungrouped = this.options.ungrouped;
     if (this.ungrouped) {
-      // this.join is not defined for Tesseract
+      // this.join is not defined for Tesseract, it validates primary keys itself
       if (!this.options.allowUngroupedWithoutPrimaryKey && !getEnv('nativeSqlPlanner')) {
         const cubes = R.uniq([this.join.root].concat(this.join.joins.map(j => j.originalTo)));
         const primaryKeyNames = cubes.flatMap(c => this.primaryKeyNames(c));
@@ -589,6 +663,7 @@ export class BaseQuery {
     if (getEnv('nativeSqlPlanner')) {
       return this.buildSqlAndParamsRust(exportAnnotatedSql);
     } else {
+      this.recordJsPlan();
       if (!this.options.preAggregationQuery && !this.options.disableExternalPreAggregations && this.externalQueryClass) {
         if (this.externalPreAggregationQuery()) { // TODO performance
           return this.externalQuery().buildSqlAndParams(exportAnnotatedSql);
@@ -609,7 +684,401 @@ export class BaseQuery {
     }
   }
 
+  /**
+   * Counts a plan built by this planner as `plans_built` and `planner_fallbacks` once per top level query:
+   * repeated calls and queries built for it, e.g. of pre-aggregations and rollups, aren't counted.
+   * @private
+   */
+  recordJsPlan() {
+    if (this.jsPlanRecorded || this.options.preAggregationQuery || this.safeEvaluateSymbolContext().preAggregationQuery) {
+      return;
+    }
+    this.jsPlanRecorded = true;
+    if (this.options.metricsRecorder) {
+      this.options.metricsRecorder.incrementCounter('plans_built', 1, { planner: 'js' });
+    }
+    this.recordJsFallback('native_planner_disabled');
+  }
+
+  /**
+   * Counts SQL rendered by this planner instead of Tesseract as `planner_fallbacks`,
+   * so a rollout of Tesseract can track what is still planned in JS and why.
+   * @param {string} reason
+   * @protected
+   */
+  recordJsFallback(reason) {
+    if (this.options.metricsRecorder) {
+      this.options.metricsRecorder.incrementCounter('planner_fallbacks', 1, { planner: 'js', reason });
+    }
+  }
+
+  /**
+   * Params are ordered by their position in SQL, so the same query shape always produces the same SQL.
+   * The third item maps filtered members to positions of their params, so drivers can
+   * reuse prepared statements and rebind values across requests.
+   * If `fillPolicies` are set, the fourth item lists measures whose gaps have to be filled in post-processing.
+   * @returns {[string, Array<unknown>, Record<string, Array<number>>, Array<string>?]}
+   */
   buildSqlAndParamsRust(exportAnnotatedSql) {
+    const res = callNativePlanner(nativeBuildSqlAndParams, this.rustQueryParams());
+    // FIXME
+    res[1] = [...res[1]];
+    return res;
+  }
+
+  /**
+   * Plans several queries compiled against the same schema at once, e.g. all widgets of a dashboard.
+   * Tesseract shares compiled members across the batch instead of compiling them per query.
+   * A query that fails to plan is returned as `{ error, meta? }` and doesn't fail the whole batch.
+   * @param {Array<BaseQuery>} queries
+   * @returns {Array<[string, Array<unknown>] | { error: string, meta?: Record<string, string> }>}
+   */
+  static buildSqlAndParamsBatch(queries) {
+    if (getEnv('nativeSqlPlanner')) {
+      // Compiled members are shared by a native batch, so queries of other schemas are planned separately
+      const results = new Array(queries.length);
+      const batches = [];
+      queries.forEach((query, index) => {
+        const batch = batches.find(b => b.cubeEvaluator === query.cubeEvaluator);
+        if (batch) {
+          batch.indexes.push(index);
+        } else {
+          batches.push({ cubeEvaluator: query.cubeEvaluator, indexes: [index] });
+        }
+      });
+      batches.forEach(({ indexes }) => {
+        callNativePlanner(nativeBuildSqlAndParamsBatch, indexes.map(i => queries[i].rustQueryParams()))
+          .forEach((res, i) => {
+            results[indexes[i]] = res.error ? res : [res[0], [...res[1]], res[2]];
+          });
+      });
+      return results;
+    }
+    return queries.map(q => {
+      try {
+        return q.buildSqlAndParams();
+      } catch (e) {
+        return { error: e.message || e.toString(), ...(e.meta && { meta: e.meta }) };
+      }
+    });
+  }
+
+  /**
+   * Plans this query followed by its variants, e.g. for cross-filtering dashboards
+   * which change a single predicate per click.
+   * Tesseract derives variants from this query and compiles only members they introduce.
+   * A variant that fails to plan is returned as `{ error, meta? }`.
+   * `keepMeasures` prunes measures of this query a caller doesn't need along with joins only they use.
+   * @param {Array<{ addFilters?: Array<Object>, dateRanges?: Record<string, Array<string>>, swapMeasures?: Record<string, string>, keepMeasures?: Array<string> }>} variants
+   * @returns {Array<[string, Array<unknown>] | { error: string, meta?: Record<string, string> }>}
+   */
+  buildSqlAndParamsVariants(variants) {
+    if (getEnv('nativeSqlPlanner')) {
+      return callNativePlanner(nativeBuildSqlAndParamsVariants, this.rustQueryParams(), variants)
+        .map(res => (res.error ? res : [res[0], [...res[1]], res[2]]));
+    }
+    return [this.buildSqlAndParams()].concat(variants.map(variant => {
+      try {
+        const QueryClass = this.constructor;
+        return new QueryClass(this.compilers, this.variantOptions(variant)).buildSqlAndParams();
+      } catch (e) {
+        return { error: e.message || e.toString(), ...(e.meta && { meta: e.meta }) };
+      }
+    }));
+  }
+
+  variantOptions({ addFilters, dateRanges, swapMeasures, keepMeasures }) {
+    const options = { ...this.options };
+    if (addFilters) {
+      options.filters = (options.filters || []).concat(addFilters);
+    }
+    if (dateRanges) {
+      Object.keys(dateRanges).forEach(dimension => {
+        if (!(options.timeDimensions || []).find(td => td.dimension === dimension)) {
+          throw new UserError(`Can't change date range of '${dimension}': it isn't a time dimension of the query`);
+        }
+      });
+      options.timeDimensions = options.timeDimensions.map(
+        td => (dateRanges[td.dimension] ? { ...td, dateRange: dateRanges[td.dimension] } : td)
+      );
+    }
+    if (swapMeasures) {
+      Object.keys(swapMeasures).forEach(measure => {
+        if (!(options.measures || []).includes(measure)) {
+          throw new UserError(`Can't swap '${measure}': it isn't a measure of the query`);
+        }
+      });
+      options.measures = options.measures.map(m => swapMeasures[m] || m);
+      if (Array.isArray(options.order)) {
+        options.order = options.order.map(o => (swapMeasures[o.id] ? { ...o, id: swapMeasures[o.id] } : o));
+      }
+    }
+    if (keepMeasures) {
+      const measures = options.measures || [];
+      keepMeasures.forEach(measure => {
+        if (!measures.includes(measure)) {
+          throw new UserError(`Can't keep '${measure}': it isn't a measure of the query`);
+        }
+      });
+      const pruned = measures.filter(m => !keepMeasures.includes(m));
+      options.measures = measures.filter(m => keepMeasures.includes(m));
+      if (Array.isArray(options.order)) {
+        options.order = options.order.filter(o => !pruned.includes(o.id));
+      }
+    }
+    return options;
+  }
+
+  /**
+   * Merges queries that differ only in measures into a single query selecting all of them.
+   * Each merged query lists its `sources`: indexes of original queries and their output columns
+   * mapped to members, so results can be split back per source query.
+   * @param {Array<BaseQuery>} queries
+   * @returns {Array<{ query: BaseQuery, sources: Array<{ index: number, columns: Record<string, string> }> }>}
+   */
+  static mergeCompatibleQueries(queries) {
+    const groups = [];
+    queries.forEach((query, index) => {
+      const group = groups.find(g => g.query.canBeMergedWith(query));
+      if (group) {
+        group.indexes.push(index);
+      } else {
+        groups.push({ query, indexes: [index] });
+      }
+    });
+    return groups.map(({ query, indexes }) => {
+      const merged = indexes.length === 1 ? query : query.newMergedQuery(
+        R.uniq(R.unnest(indexes.map(i => queries[i].options.measures || [])))
+      );
+      return {
+        query: merged,
+        sources: indexes.map(index => ({ index, columns: queries[index].aliasNameToMember })),
+      };
+    });
+  }
+
+  /**
+   * Plans a batch of queries merging compatible ones into a single SQL statement.
+   * @param {Array<BaseQuery>} queries
+   * @returns {Array<{ sql?: string, params?: Array<unknown>, error?: string, meta?: Record<string, string>, sources: Array<Object> }>}
+   */
+  static buildMergedSqlAndParamsBatch(queries) {
+    const merged = BaseQuery.mergeCompatibleQueries(queries);
+    const results = BaseQuery.buildSqlAndParamsBatch(merged.map(m => m.query));
+    return merged.map(({ sources }, i) => (
+      results[i].error ?
+        { ...results[i], sources } :
+        { sql: results[i][0], params: results[i][1], sources }
+    ));
+  }
+
+  /**
+   * Merging changes measures and default order, so it's allowed only when neither affects
+   * which rows are returned.
+   * @protected
+   */
+  isMergeableQuery() {
+    if (this.options.ungrouped || this.options.total || this.options.multiStageQuery) {
+      return false;
+    }
+    const isLimited = this.options.limit || this.options.offset || this.rowLimit;
+    if (isLimited && !this.options.order) {
+      return false;
+    }
+    return !this.order.some(o => this.measures.find(m => m.measure === o.id));
+  }
+
+  /**
+   * Measures of other cubes may change the join tree and so the rows returned for the dimensions.
+   * Queries are merged only when their measures share one cube or they're joined from the same root.
+   * @param {BaseQuery} query
+   * @returns {boolean}
+   */
+  canBeMergedWith(query) {
+    return this.constructor === query.constructor &&
+      this.compilers === query.compilers &&
+      this.isMergeableQuery() &&
+      query.isMergeableQuery() &&
+      R.equals(R.omit(['measures'], this.options), R.omit(['measures'], query.options)) &&
+      this.hasSameMeasureSource(query);
+  }
+
+  /**
+   * @protected
+   * @param {BaseQuery} query
+   * @returns {boolean}
+   */
+  hasSameMeasureSource(query) {
+    const measureCubes = R.uniq(this.measures.concat(query.measures).map(m => m.cube().name));
+    if (measureCubes.length === 1) {
+      return true;
+    }
+    return !!this.join && !!query.join && this.join.root === query.join.root;
+  }
+
+  /**
+   * @protected
+   */
+  newMergedQuery(measures) {
+    const QueryClass = this.constructor;
+    return new QueryClass(this.compilers, { ...this.options, measures });
+  }
+
+  /**
+   * Returns `SELECT MIN(dimension), MAX(dimension)` probe query with query filters applied.
+   * Used to resolve build ranges and date picker bounds.
+   * @param {string} dimension
+   * @returns {[string, Array<unknown>]}
+   */
+  buildDateRangeProbeSqlAndParams(dimension) {
+    const res = callNativePlanner(nativeBuildDateRangeProbeSqlAndParams, this.rustQueryParams(), dimension);
+    res[1] = [...res[1]];
+    return res;
+  }
+
+  /**
+   * Returns freshness query of the cube: its refresh key sql or `updatedAt` watermark.
+   * Undefined is returned for cubes refreshed by `every` interval only.
+   * @param {string} cube
+   * @returns {[string, Array<unknown>] | undefined}
+   */
+  buildFreshnessSqlAndParams(cube) {
+    const res = callNativePlanner(nativeBuildFreshnessSqlAndParams, this.rustQueryParams(), cube);
+    if (!res) {
+      return res;
+    }
+    res[1] = [...res[1]];
+    return res;
+  }
+
+  /**
+   * Returns cheap row count estimate query for this query along with
+   * the strategy to read the estimate from its result, see `rowCountEstimateFromResult`.
+   * @returns {[string, Array<unknown>, string]}
+   */
+  buildRowCountEstimateSqlAndParams() {
+    const res = callNativePlanner(nativeBuildRowCountEstimateSqlAndParams, this.rustQueryParams());
+    res[1] = [...res[1]];
+    return res;
+  }
+
+  /**
+   * Returns query counting all rows of this query regardless of its order, limit and offset,
+   * so paginated requests can get `total` without planning a separate total query.
+   * @returns {[string, Array<unknown>]}
+   */
+  buildTotalCountSqlAndParams() {
+    const res = callNativePlanner(nativeBuildTotalCountSqlAndParams, this.rustQueryParams());
+    res[1] = [...res[1]];
+    return res;
+  }
+
+  /**
+   * Plans a saved query against the current schema. Saved queries are stored by the host
+   * for the tenant of this query, e.g. in its database, and passed on each call.
+   * Members, order and paging come from the saved query, other options from this query.
+   * Filters and segments of the saved query are added to ones of this query,
+   * so filters added by `queryRewrite` still apply.
+   * @param {Array<{ name: string, description?: string, query: Object }>} savedQueries
+   * @param {string} name
+   * @returns {[string, Array<unknown>]}
+   */
+  buildSavedQuerySqlAndParams(savedQueries, name) {
+    const res = callNativePlanner(nativeBuildSavedQuerySqlAndParams, this.rustQueryParams(), savedQueries, name);
+    res[1] = [...res[1]];
+    return res;
+  }
+
+  /**
+   * Re-plans saved queries against the current schema, e.g. after a schema change.
+   * @param {Array<{ name: string, description?: string, query: Object }>} savedQueries
+   * @returns {Array<{ name: string, valid: boolean, error?: string }>}
+   */
+  validateSavedQueries(savedQueries) {
+    return callNativePlanner(nativeValidateSavedQueries, this.rustQueryParams(), savedQueries);
+  }
+
+  /**
+   * Returns hashes of this query: `planHash` of the normalized query is the same for every dialect,
+   * `sqlHash` of the generated SQL text without param values changes with the dialect.
+   * Used for dedupe, alerting on plan changes and correlating warehouse query history with queries.
+   * @returns {{ planHash: string, sqlHash: string }}
+   */
+  sqlHashes() {
+    const [planHash, sqlHash] = callNativePlanner(nativeBuildSqlHashes, this.rustQueryParams());
+    return { planHash, sqlHash };
+  }
+
+  /**
+   * Returns the logical plan of this query before SQL rendering: members, joins of each join group,
+   * filters with operators and values, and the structure of the planned selects and subqueries.
+   * Values of sensitive members are redacted. `nullSemantics` tells whether negative operators,
+   * e.g. `notContains`, match NULL values: see the `strict_negative_null_checks` planner flag.
+   * @returns {Object}
+   */
+  explainPlan() {
+    return JSON.parse(callNativePlanner(nativeBuildExplain, this.rustQueryParams()));
+  }
+
+  /**
+   * Compiles query filters into a single predicate once and returns a function
+   * rendering it for placeholder values, e.g. `{ partition_from, partition_to }`
+   * of an `inDateRange` filter with `placeholder: 'partition'`.
+   * Placeholder values are bound as is, so they should be already in db time zone.
+   * Returns undefined if query has no filters.
+   * @returns {((values: Record<string, string>) => [string, Array<unknown>]) | undefined}
+   */
+  compileFilterPredicate() {
+    const res = callNativePlanner(nativeBuildFilterPredicateSqlAndParams, this.rustQueryParams());
+    if (!res) {
+      return res;
+    }
+    const [sql, params, placeholders] = res;
+    return (values) => {
+      const boundParams = [...params];
+      Object.keys(placeholders).forEach((name) => {
+        if (values[name] === undefined) {
+          throw new UserError(`Value for placeholder '${name}' is not provided`);
+        }
+        placeholders[name].forEach((position) => {
+          boundParams[position] = values[name];
+        });
+      });
+      return [sql, boundParams];
+    };
+  }
+
+  /**
+   * Exports compiled join graph along with the join path chosen for this query.
+   * @param {'json' | 'dot'} format
+   * @returns {Object | string}
+   */
+  exportJoinGraph(format) {
+    return this.joinGraph.exportGraph(format, this.allJoinHints);
+  }
+
+  /**
+   * @param {string} strategy
+   * @param {Array<Object>} rows
+   * @returns {number | null}
+   */
+  rowCountEstimateFromResult(strategy, rows) {
+    if (!rows || !rows.length) {
+      return null;
+    }
+    if (strategy === 'explain_json') {
+      const plan = Object.values(rows[0])[0];
+      const parsed = typeof plan === 'string' ? JSON.parse(plan) : plan;
+      return parsed[0].Plan['Plan Rows'];
+    }
+    if (strategy === 'scalar') {
+      const value = Object.values(rows[0])[0];
+      return value != null ? Number(value) : null;
+    }
+    throw new UserError(`Unknown row count estimate strategy: ${strategy}`);
+  }
+
+  rustQueryParams() {
     const order = this.options.order && R.pipe(
       R.map((hash) => ((!hash || !hash.id) ? null : hash)),
       R.reject(R.isNil),
@@ -624,17 +1093,83 @@ export class BaseQuery {
       cubeEvaluator: this.cubeEvaluator,
       order,
       filters: this.options.filters,
+      segments: this.options.segments,
       limit: this.options.limit ? this.options.limit.toString() : null,
       rowLimit: this.options.rowLimit ? this.options.rowLimit.toString() : null,
       offset: this.options.offset ? this.options.offset.toString() : null,
       baseTools: this,
-      ungrouped: this.options.ungrouped
+      ungrouped: this.options.ungrouped,
+      allowUngroupedWithoutPrimaryKey: this.options.allowUngroupedWithoutPrimaryKey,
+      subtotals: this.options.subtotals,
+      groupingSets: this.options.groupingSets,
+      securityContext: this.contextSymbols.securityContext,
+      auditLogSink: this.options.auditLogSink,
+      cancellationToken: this.options.cancellationToken,
+      tableNameResolver: this.options.tableNameResolver,
+      metricsRecorder: this.options.metricsRecorder,
+      // W3C `{ traceparent, tracestate }` of the caller to link planning spans to its trace
+      traceContext: this.options.traceContext,
+      watermarkSecret: getEnv('queryWatermarkSecret'),
+      planningTimeoutMs: this.options.planningTimeoutMs,
+      planningMemoryBudgetBytes: this.options.planningMemoryBudgetBytes,
+      maxSelectMembers: this.options.maxSelectMembers,
+      deterministic: this.options.deterministic,
+      plannerFlags: this.plannerFlags(),
+      redactionMode: this.options.redactionMode,
+      redactionSecret: getEnv('redactionHashSecret'),
+      castPolicy: this.options.castPolicy,
+      emptyInListPolicy: this.options.emptyInListPolicy,
+      collation: this.options.collation,
+      valuesNormalization: this.options.valuesNormalization,
+      currencyConversion: this.options.currencyConversion,
+      units: this.options.units,
+      fillPolicies: this.options.fillPolicies,
+      nullLabel: this.options.nullLabel,
+      asOf: this.options.asOf,
+      preview: this.options.preview,
+      inlineParams: this.options.inlineParams,
+      overflowSafeAggregation: this.options.overflowSafeAggregation,
+      tableOverrides: this.tableOverrides(),
+      valueMaps: this.options.valueMaps,
 
     };
-    const res = nativeBuildSqlAndParams(queryParams);
-    // FIXME
-    res[1] = [...res[1]];
-    return res;
+    return queryParams;
+  }
+
+  /**
+   * Planner flags enabled for the deployment by `CUBEJS_TESSERACT_SQL_PLANNER_FLAGS`
+   * and overridden per request by `plannerFlags` option.
+   * @returns {Record<string, boolean>}
+   */
+  plannerFlags() {
+    const deploymentFlags = R.fromPairs(
+      (getEnv('nativeSqlPlannerFlags') || []).map(flag => [flag.trim(), true])
+    );
+    return { ...deploymentFlags, ...this.options.plannerFlags };
+  }
+
+  /**
+   * Per-request cube table overrides taken from the compile context,
+   * e.g. `{ orders: 'sandbox.orders' }`. Every table has to be a `schema.table`
+   * or `table` name allowed by `CUBEJS_TABLE_OVERRIDES_ALLOW_LIST`.
+   * @returns {Record<string, string>}
+   */
+  tableOverrides() {
+    const compileContext = this.compilers.compiler && this.compilers.compiler.compileContext;
+    const overrides = (compileContext && compileContext.tableOverrides) || {};
+    const allowList = getEnv('tableOverridesAllowList') || [];
+    Object.entries(overrides).forEach(([cube, table]) => {
+      if (typeof table !== 'string' || !/^[A-Za-z_][A-Za-z0-9_$]*(\.[A-Za-z_][A-Za-z0-9_$]*)?$/.test(table)) {
+        throw new UserError(`Table override '${table}' of '${cube}' cube isn't a 'schema.table' name`);
+      }
+      const allowed = allowList.map(pattern => pattern.trim()).some(
+        pattern => (pattern.endsWith('.*') ? table.startsWith(pattern.slice(0, -1)) : table === pattern)
+      );
+      if (!allowed) {
+        throw new UserError(`Table '${table}' isn't allowed as an override of '${cube}' cube table`);
+      }
+    });
+    return overrides;
   }
 
   allCubeMembers(path) {
@@ -704,7 +1239,9 @@ export class BaseQuery {
     const ExternalQuery = this.externalQueryClass;
     return new ExternalQuery(this.compilers, {
       ...this.options,
-      externalQueryClass: null
+      externalQueryClass: null,
+      // Plans of the external query are counted by this one
+      metricsRecorder: null
     });
   }
 
@@ -1593,7 +2130,11 @@ export class BaseQuery {
         return [{
           sql: cubeSql,
           alias: cubeAlias,
-          on: `${this.evaluateSql(j.originalFrom, j.join.sql)}${conditions ? ` AND (${conditions})` : ''}`
+          on: this.snapshotJoinCondition(
+            j,
+            cubeAlias,
+            `${this.evaluateSql(j.originalFrom, j.join.sql)}${conditions ? ` AND (${conditions})` : ''}`
+          )
           // TODO handle the case when sub query referenced by a foreign cube on other side of a join
         }].concat((subQueryDimensionsByCube[j.originalTo] || []).map(d => this.subQueryJoin(d)));
       }
@@ -1608,6 +2149,36 @@ export class BaseQuery {
     ]);
   }
 
+  /**
+   * Joins to snapshot cubes are matched by time of the joining row unless
+   * the query is a point in time one, which already filters snapshot cubes.
+   * @param {{ originalFrom: string, originalTo: string, join: { validAt?: Function } }} j
+   * @param {string} cubeAlias
+   * @param {string} on
+   * @returns {string}
+   */
+  snapshotJoinCondition(j, cubeAlias, on) {
+    const { validity } = this.cubeEvaluator.cubeFromPath(j.originalTo);
+    if (!validity || !j.join.validAt || this.options.asOf) {
+      return on;
+    }
+    return `(${on}) AND ${this.validityRangeCondition(
+      this.evaluateSql(j.originalFrom, j.join.validAt),
+      `${cubeAlias}.${validity.validFrom}`,
+      `${cubeAlias}.${validity.validTo}`
+    )}`;
+  }
+
+  /**
+   * @param {string} value
+   * @param {string} validFrom
+   * @param {string} validTo
+   * @returns {string}
+   */
+  validityRangeCondition(value, validFrom, validTo) {
+    return `${value} >= ${validFrom} AND (${validTo} IS NULL OR ${value} < ${validTo})`;
+  }
+
   joinSql(toJoin) {
     const [root, ...rest] = toJoin;
     const joins = rest.map(
@@ -1872,18 +2443,82 @@ export class BaseQuery {
       return this.preAggregations.originalSqlPreAggregationTable(foundPreAggregation);
     }
 
+    return this.snapshotSql(cube, this.cubeTableSql(cube));
+  }
+
+  cubeTableSql(cube) {
+    const tableOverride = this.tableOverrides()[cube];
+    if (tableOverride) {
+      const quotedTable = tableOverride.split('.').map(part => this.escapeColumnName(part)).join('.');
+      return this.resolveTableName(cube, quotedTable);
+    }
+
     const fromPath = this.cubeEvaluator.cubeFromPath(cube);
     if (fromPath.sqlTable) {
-      return this.evaluateSql(cube, fromPath.sqlTable);
+      return this.resolveTableName(cube, this.evaluateSql(cube, fromPath.sqlTable));
+    }
+
+    if (fromPath.sourceQuery) {
+      return this.resolveTableName(cube, this.sourceQuerySql(cube, fromPath.sourceQuery));
+    }
+
+    if (fromPath.union) {
+      const branches = fromPath.union.tables.map(({ table }) => `SELECT * FROM ${table}`);
+      return this.resolveTableName(cube, `(${branches.join(' UNION ALL ')})`);
     }
 
     const evaluatedSql = this.evaluateSql(cube, fromPath.sql);
     const selectAsterisk = evaluatedSql.match(/^\s*select\s+\*\s+from\s+([a-zA-Z0-9_\-`".*]+)\s*$/i);
     if (selectAsterisk) {
-      return selectAsterisk[1];
+      return this.resolveTableName(cube, selectAsterisk[1]);
     }
 
-    return `(${evaluatedSql})`;
+    return this.resolveTableName(cube, `(${evaluatedSql})`);
+  }
+
+  /**
+   * Point in time queries see rows of snapshot cubes which are valid at the `asOf` time.
+   * @param {string} cube
+   * @param {string} sql
+   * @returns {string}
+   */
+  snapshotSql(cube, sql) {
+    const { validity } = this.cubeEvaluator.cubeFromPath(cube);
+    if (!validity || !this.options.asOf) {
+      return sql;
+    }
+    const asOf = this.timeStampCast(this.paramAllocator.allocateParam(this.inDbTimeZone(this.options.asOf)));
+    const alias = this.escapeColumnName(`${cube}_snapshot`);
+    return `(SELECT * FROM ${sql} ${this.asSyntaxTable} ${alias} WHERE ${this.validityRangeCondition(asOf, validity.validFrom, validity.validTo)})`;
+  }
+
+  sourceQuerySql(cube, { materialized, ...query }) {
+    if (materialized) {
+      return materialized;
+    }
+    const sourceQueryCubes = this.options.sourceQueryCubes || [];
+    if (sourceQueryCubes.includes(cube)) {
+      throw new UserError(`Source query of cube ${cube} depends on itself: ${sourceQueryCubes.join(' -> ')} -> ${cube}`);
+    }
+    const subQuery = this.newSubQuery({
+      ...query,
+      sourceQueryCubes: [...sourceQueryCubes, cube],
+    });
+    return `(${subQuery.buildParamAnnotatedSql()})`;
+  }
+
+  /**
+   * Routes cube source to a physical table chosen at plan time by `tableNameResolver` option,
+   * e.g. per-tenant shards. Resolver gets the rendered source and returns it as is to keep it.
+   * @param {string} cube
+   * @param {string} table
+   * @returns {string}
+   */
+  resolveTableName(cube, table) {
+    if (this.options.tableNameResolver) {
+      return this.options.tableNameResolver.resolveTableName(cube, table);
+    }
+    return table;
   }
 
   traverseSymbol(s) {
@@ -2300,6 +2935,13 @@ export class BaseQuery {
                 this.concatStringsSql(primaryKeys.map((pk) => this.castToString(this.primaryKeySql(pk, cubeName))))
                 : this.primaryKeySql(primaryKeys[0], cubeName)
             ) || '*';
+          if (symbol.denominatorSql) {
+            sql = this.safeDivide(
+              sql,
+              this.evaluateSql(cubeName, symbol.denominatorSql),
+              symbol.divisionByZero === 'zero'
+            );
+          }
         }
         const result = this.renderSqlMeasure(
           name,
@@ -2694,6 +3336,22 @@ export class BaseQuery {
     return `CAST(${sql} as TEXT)`;
   }
 
+  /**
+   * Division of ratio measures with `denominatorSql`. Yields NULL when the denominator is 0
+   * or 0 with `divisionByZero: 'zero'`. NULL numerator or denominator always yields NULL.
+   * @param {string} numerator
+   * @param {string} denominator
+   * @param {boolean} zeroOnDivisionByZero
+   * @returns {string}
+   */
+  safeDivide(numerator, denominator, zeroOnDivisionByZero) {
+    const result = `1.0 * (${numerator}) / NULLIF(${denominator}, 0)`;
+    if (zeroOnDivisionByZero) {
+      return `CASE WHEN (${denominator}) = 0 THEN 0 ELSE ${result} END`;
+    }
+    return result;
+  }
+
   // eslint-disable-next-line @typescript-eslint/no-unused-vars
   countDistinctApprox(sql) {
     throw new UserError('Approximate distinct count is not supported by this DB');
@@ -2921,6 +3579,7 @@ export class BaseQuery {
       historyQueries: this.options.historyQueries,
       externalQueryClass: this.options.externalQueryClass,
       queryFactory: this.options.queryFactory,
+      tableNameResolver: this.options.tableNameResolver,
       ...options,
     };
   }
@@ -2952,7 +3611,22 @@ export class BaseQuery {
             this
           ];
         }
+      }
 
+      if (cubeFromPath.updatedAt) {
+        return [
+          this.updatedAtRefreshKeySql(cube, cubeFromPath.updatedAt),
+          {
+            external: false,
+            renewalThreshold: cubeFromPath.refreshKey && cubeFromPath.refreshKey.every
+              ? this.refreshKeyRenewalThresholdForInterval(cubeFromPath.refreshKey, false)
+              : this.defaultRefreshKeyRenewalThreshold()
+          },
+          this
+        ];
+      }
+
+      if (cubeFromPath.refreshKey) {
         if (cubeFromPath.refreshKey.every) {
           const [sql, external, query] = this.everyRefreshKeySql(cubeFromPath.refreshKey);
           return [
@@ -2977,11 +3651,31 @@ export class BaseQuery {
       ];
     };
 
+    if (getEnv('nativeSqlPlanner') && !transformFn) {
+      return cubes.map(cube => {
+        const [sql, options, query] = refreshKeyQueryByCube(cube);
+        const freshness = this.buildFreshnessSqlAndParams(cube);
+        if (!freshness) {
+          // Refresh keys of `every` interval only are rendered by this planner
+          this.recordJsFallback('refresh_key_every');
+        }
+        return (freshness || query.paramAllocator.buildSqlAndParams(sql)).concat(options);
+      });
+    }
+    if (getEnv('nativeSqlPlanner')) {
+      this.recordJsFallback('refresh_key_transform');
+    }
+
     return cubes.map(cube => [cube, refreshKeyQueryByCube(cube)])
       .map(([cube, refreshKeyTuple]) => (transformFn ? transformFn(cube, refreshKeyTuple) : refreshKeyTuple))
       .map(([sql, options, query]) => query.paramAllocator.buildSqlAndParams(sql).concat(options));
   }
 
+  updatedAtRefreshKeySql(cube, updatedAt) {
+    const dimensionSql = this.dimensionSql(this.newDimension(`${cube}.${updatedAt}`));
+    return `SELECT MAX(${dimensionSql}) as refresh_key FROM ${this.cubeSql(cube)} ${this.asSyntaxTable} ${this.cubeAlias(cube)}`;
+  }
+
   aggSelectForDimension(cube, dimension, aggFunction) {
     const cubeNamesForTimeDimension = this.collectFrom(
       [dimension],
@@ -3259,8 +3953,14 @@ export class BaseQuery {
           '{% if limit is not none %}\nLIMIT {{ limit }}{% endif %}' +
           '{% if offset is not none %}\nOFFSET {{ offset }}{% endif %}',
         group_by_exprs: '{{ group_by | map(attribute=\'index\') | join(\', \') }}',
+        grouping_sets: '{% if rollup %}ROLLUP({{ group_by | map(attribute=\'expr\') | join(\', \') }})' +
+          '{% elif cube %}CUBE({{ group_by | map(attribute=\'expr\') | join(\', \') }})' +
+          '{% else %}GROUPING SETS ({% for set in sets %}({{ set | join(\', \') }}){% if not loop.last %}, {% endif %}{% endfor %}){% endif %}',
         join: '{{ join_type }} JOIN {{ source }} ON {{ condition }}',
+        table_sample: '{{ table }} TABLESAMPLE SYSTEM ({{ percent }})',
         cte: '{{ alias }} AS ({{ query | indent(2, true) }})',
+        comment: '/* {{ text }} */',
+        total_count: 'select count(*) {{ total_alias }} from (\n{{ sql }}\n) {{ original_alias }}',
         time_series_select: 'SELECT date_from::timestamp AS "date_from",\n' +
         'date_to::timestamp AS "date_to" \n' +
         'FROM(\n' +
@@ -3277,10 +3977,19 @@ export class BaseQuery {
         query_aliased: '{{ query }} AS {{ quoted_alias }}',
         case: 'CASE{% if expr %} {{ expr }}{% endif %}{% for when, then in when_then %} WHEN {{ when }} THEN {{ then }}{% endfor %}{% if else_expr %} ELSE {{ else_expr }}{% endif %} END',
         is_null: '{{ expr }} IS {% if negate %}NOT {% endif %}NULL',
+        grouping: 'GROUPING({{ exprs | join(\', \') }})',
         binary: '({{ left }} {{ op }} {{ right }})',
         sort: '{{ expr }} {% if asc %}ASC{% else %}DESC{% endif %} NULLS {% if nulls_first %}FIRST{% else %}LAST{% endif %}',
-        order_by: '{% if index %} {{ index }} {% else %} {{ expr }} {% endif %} {% if asc %}ASC{% else %}DESC{% endif %}{% if nulls_first %} NULLS FIRST{% endif %}',
+        order_by: '{% if index %} {{ index }} {% else %} {{ expr }} {% endif %} {% if asc %}ASC{% else %}DESC{% endif %}{% if nulls_first %} NULLS FIRST{% elif nulls_last %} NULLS LAST{% endif %}',
         cast: 'CAST({{ expr }} AS {{ data_type }})',
+        round: 'ROUND({{ expr }}, {{ scale }})',
+        unit_conversion: '({{ expr }}) * {{ factor }}',
+        null_label: 'COALESCE({{ expr }}, {{ label }})',
+        // Numerator is multiplied by 1.0 so integer inputs aren't divided as integers
+        safe_divide: '1.0 * ({{ numerator }}) / NULLIF({{ denominator }}, 0)',
+        collate: '{{ expr }} COLLATE {{ collation }}',
+        collation_name: '"{{ name }}"',
+        trim: 'TRIM({{ expr }})',
         window_function: '{{ fun_call }} OVER ({% if partition_by_concat %}PARTITION BY {{ partition_by_concat }}{% if order_by_concat or window_frame %} {% endif %}{% endif %}{% if order_by_concat %}ORDER BY {{ order_by_concat }}{% if window_frame %} {% endif %}{% endif %}{% if window_frame %}{{ window_frame }}{% endif %})',
         window_frame_bounds: '{{ frame_type }} BETWEEN {{ frame_start }} AND {{ frame_end }}',
         in_list: '{{ expr }} {% if negated %}NOT {% endif %}IN ({{ in_exprs_concat }})',
@@ -3307,21 +4016,55 @@ export class BaseQuery {
         in: '{{ column }} IN ({{ values_concat }}){{ is_null_check }}',
         not_in: '{{ column }} NOT IN ({{ values_concat }}){{ is_null_check }}',
         time_range_filter: '{{ column }} >= {{ from_timestamp }} AND {{ column }} <= {{ to_timestamp }}',
+        time_range_filter_exclusive: '{{ column }} >= {{ from_timestamp }} AND {{ column }} < {{ to_timestamp }}',
+        time_range_end: 'inclusive',
+        // Resolved date range bounds are stringified with it before param allocation
+        timestamp_value: '{{ year }}-{{ month }}-{{ day }}T{{ hour }}:{{ minute }}:{{ second }}{% if fraction %}.{{ fraction }}{% endif %}{{ zone }}',
         gt: '{{ column }} > {{ param }}',
         gte: '{{ column }} >= {{ param }}',
         lt: '{{ column }} < {{ param }}',
         lte: '{{ column }} <= {{ param }}',
+        between: '{{ column }} BETWEEN {{ from }} AND {{ to }}',
+        not_between: '{{ column }} NOT BETWEEN {{ from }} AND {{ to }}{{ is_null_check }}',
         like_pattern: '{% if start_wild %}\'%\' || {% endif %}{{ value }}{% if end_wild %}|| \'%\'{% endif %}',
-        always_true: '1 = 1'
+        validity_range: '{{ value }} >= {{ valid_from }} AND ({{ valid_to }} IS NULL OR {{ value }} < {{ valid_to }})',
+        // Dialects cast timestamp params the way `timeStampCast` does
+        timestamp_param: this.timeStampCast('{{ param }}'),
+        // `binary_param` and `expressions.hex_encode` are defined by dialects with a binary type,
+        // `uuid_param` by dialects which filter on UUIDs
+        always_true: '1 = 1',
+        always_false: '1 = 0',
+        array_contains: '{{ value }} = ANY({{ column }})',
+        calendar_exists: 'EXISTS (SELECT 1 FROM {{ table }} AS {{ calendar_alias }} WHERE {{ calendar_alias }}.{{ date_column }} = CAST({{ column }} AS DATE) AND {{ calendar_alias }}.{{ flag_column }} = {{ flag_value }})',
+        sub_query_exists: 'EXISTS (SELECT 1 FROM {{ source }} WHERE {% for column, outer in keys %}{{ column }} = {{ outer }} AND {% endfor %}({{ condition }}))',
 
       },
       operators: {},
+      gap_fill: {
+        zero: 'COALESCE({{ expr }}, 0)',
+        previous_group: 'COUNT({{ expr }}) OVER (ORDER BY {{ order_by }})',
+        previous: 'MAX({{ expr }}) OVER (PARTITION BY {{ group }})',
+      },
+      // ISO semantics: weeks start on Monday, dow is 1 (Monday) to 7 (Sunday).
+      // There is no portable ISO day of week, so dialects define them
+      time_attributes: {},
       quotes: {
         identifiers: '"',
-        escape: '""'
+        escape: '""',
+        literal: '\'',
+        literal_escape: '\'\'',
       },
+      currency_conversion: {
+        rates_source: 'SELECT {{ currency_column }} AS currency, {{ rate_column }} AS rate, {{ effective_date_column }} AS valid_from, ' +
+          'LEAD({{ effective_date_column }}) OVER (PARTITION BY {{ currency_column }} ORDER BY {{ effective_date_column }}) AS valid_to ' +
+          'FROM {{ rates_table }} WHERE {{ target_currency_column }} = {{ target_currency }}',
+        join_condition: '{{ rates }}.currency = {{ currency }} AND {{ date }} >= {{ rates }}.valid_from AND ({{ rates }}.valid_to IS NULL OR {{ date }} < {{ rates }}.valid_to)',
+        convert: '({{ expr }}) * {{ rates }}.rate',
+      },
+      // Placeholders referring to `param_index` are reused for repeated params, positional ones aren't
       params: {
-        param: '?'
+        param: '?',
+        named_param: ':{{ param_name }}'
       },
       join_types: {
         inner: 'INNER',
@@ -3915,6 +4658,55 @@ export class BaseQuery {
     };
   }
 
+  /**
+   * FILTER_PARAMS for Tesseract. Filters are rendered by the Rust planner, so references
+   * are kept in the sql as markers with hex encoded columns.
+   * Function columns are called with markers of the values they receive.
+   */
+  filtersProxyForRust() {
+    const { cubeEvaluator } = this;
+    return new Proxy({}, {
+      get: (_target, name) => {
+        if (name === '_objectWithResolvedProperties') {
+          return true;
+        }
+        const cubeName = cubeEvaluator.cubeNameFromPath(name);
+        return new Proxy({ cube: cubeName }, {
+          get: (cubeNameObj, propertyName) => ({
+            filter: (column) => {
+              const member = cubeEvaluator.pathFromArray([cubeNameObj.cube, propertyName]);
+              return {
+                __column: () => column,
+                __member: () => member,
+                toString: () => BaseQuery.filterParamsMarker(member, column),
+              };
+            }
+          })
+        });
+      }
+    });
+  }
+
+  static filterParamsMarker(member, column) {
+    if (typeof column === 'function') {
+      const values = R.range(0, Math.max(column.length, 1)).map(i => `$FILTER_PARAM_VALUE:${i}$`);
+      return `$FILTER_PARAMS:${member}:fn:${Buffer.from(column(...values)).toString('hex')}$`;
+    }
+    return `$FILTER_PARAMS:${member}:column:${Buffer.from(column).toString('hex')}$`;
+  }
+
+  filterGroupFunctionForRust() {
+    return (...filterParamArgs) => {
+      const markers = filterParamArgs.map(f => {
+        if (!f.__member) {
+          throw new UserError(`FILTER_GROUP expects FILTER_PARAMS args to be passed. For example FILTER_GROUP(FILTER_PARAMS.foo.bar.filter('bar'), FILTER_PARAMS.foo.jar.filter('jar')). But found: ${f}`);
+        }
+        return f.toString();
+      });
+      return `$FILTER_GROUP:${Buffer.from(markers.join('')).toString('hex')}$`;
+    };
+  }
+
   static filterProxyFromAllFilters(allFilters, cubeEvaluator, allocateParam, newGroupFilter) {
     return new Proxy({}, {
       get: (_target, name) => {
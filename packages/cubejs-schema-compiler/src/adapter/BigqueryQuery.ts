@@ -21,6 +21,10 @@ class BigqueryFilter extends BaseFilter {
     return `LOWER(${column})${not ? ' NOT' : ''} LIKE CONCAT('${p}', LOWER(${this.allocateParam(param)}) , '${s}')`;
   }
 
+  public regexMatch(column, not, param) {
+    return `${not ? 'NOT ' : ''}REGEXP_CONTAINS(${column}, ${param})`;
+  }
+
   public castParameter() {
     if (this.definition().type === 'boolean') {
       return 'CAST(? AS BOOL)';
@@ -218,6 +222,14 @@ export class BigqueryQuery extends BaseQuery {
     return `APPROX_COUNT_DISTINCT(${sql})`;
   }
 
+  public safeDivide(numerator, denominator, zeroOnDivisionByZero) {
+    const result = `SAFE_DIVIDE(${numerator}, ${denominator})`;
+    if (zeroOnDivisionByZero) {
+      return `CASE WHEN (${denominator}) = 0 THEN 0 ELSE ${result} END`;
+    }
+    return result;
+  }
+
   public concatStringsSql(strings) {
     return `CONCAT(${strings.join(', ')})`;
   }
@@ -239,6 +251,17 @@ export class BigqueryQuery extends BaseQuery {
     templates.functions.DATETRUNC = 'DATETIME_TRUNC(CAST({{ args[1] }} AS DATETIME), {{ date_part }})';
     templates.functions.LOG = 'LOG({{ args_concat }}{% if args[1] is undefined %}, 10{% endif %})';
     templates.functions.BTRIM = 'TRIM({{ args_concat }})';
+    templates.filters.array_contains = '{{ value }} IN UNNEST({{ column }})';
+    templates.time_attributes = {
+      // DAYOFWEEK is 1 (Sunday) to 7 (Saturday)
+      dow: 'MOD(EXTRACT(DAYOFWEEK FROM {{ expr }}) + 5, 7) + 1',
+      hour_of_day: 'EXTRACT(HOUR FROM {{ expr }})',
+      month_of_year: 'EXTRACT(MONTH FROM {{ expr }})',
+      week_of_year: 'EXTRACT(ISOWEEK FROM {{ expr }})',
+    };
+    templates.filters.regex_match = '{% if negated %}NOT {% endif %}REGEXP_CONTAINS({{ column }}, {{ value }})';
+    templates.expressions.collation_name = '\'{{ name }}\'';
+    templates.expressions.normalize_unicode = '{% if case_fold %}LOWER(NORMALIZE({{ expr }}, NFC)){% else %}NORMALIZE({{ expr }}, NFC){% endif %}';
     templates.functions.STRPOS = 'STRPOS({{ args_concat }})';
     templates.functions.DATEDIFF = 'DATETIME_DIFF(CAST({{ args[2] }} AS DATETIME), CAST({{ args[1] }} AS DATETIME), {{ date_part }})';
     // DATEADD is being rewritten to DATE_ADD
@@ -251,6 +274,14 @@ export class BigqueryQuery extends BaseQuery {
     templates.expressions.timestamp_literal = 'TIMESTAMP(\'{{ value }}\')';
     delete templates.expressions.ilike;
     delete templates.expressions.like_escape;
+    templates.statements.table_sample = '{{ table }} TABLESAMPLE SYSTEM ({{ percent }} PERCENT)';
+    templates.expressions.hex_encode = 'TO_HEX({{ expr }})';
+    templates.expressions.safe_divide = 'SAFE_DIVIDE({{ numerator }}, {{ denominator }})';
+    templates.filters.binary_param = 'FROM_HEX({{ param }})';
+    // UUIDs are stored as strings
+    templates.filters.uuid_param = '{{ param }}';
+    templates.quotes.literal_escape = '\\\'';
+    templates.quotes.literal_backslash_escape = '\\\\';
     templates.types.boolean = 'BOOL';
     templates.types.float = 'FLOAT64';
     templates.types.double = 'FLOAT64';
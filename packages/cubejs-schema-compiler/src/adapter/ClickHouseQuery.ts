@@ -21,6 +21,10 @@ class ClickHouseFilter extends BaseFilter {
     return `lower(${column}) ${not ? 'NOT' : ''} LIKE CONCAT('${p}', lower(${this.allocateParam(param)}), '${s}')`;
   }
 
+  public regexMatch(column, not, param) {
+    return `${not ? 'NOT ' : ''}match(${column}, ${param})`;
+  }
+
   public castParameter() {
     if (this.measure || this.definition().type === 'number') {
       // TODO here can be measure type of string actually
@@ -269,10 +273,18 @@ export class ClickHouseQuery extends BaseQuery {
   public sqlTemplates() {
     const templates = super.sqlTemplates();
     templates.functions.DATETRUNC = 'DATE_TRUNC({{ args_concat }})';
+    templates.functions.DATEDIFF = 'dateDiff(\'{{ date_part }}\', {{ args[1] }}, {{ args[2] }})';
     // TODO: Introduce additional filter in jinja? or parseDateTimeBestEffort?
     // https://github.com/ClickHouse/ClickHouse/issues/19351
     templates.expressions.timestamp_literal = 'parseDateTimeBestEffort(\'{{ value }}\')';
     delete templates.expressions.like_escape;
+    templates.statements.as_of_join = 'ASOF LEFT JOIN {{ source }} ON {{ condition }} AND {{ match_time }} <= {{ time }}';
+    // Sampling needs a sampling key of the table
+    delete templates.statements.table_sample;
+    templates.expressions.hex_encode = 'lower(hex({{ expr }}))';
+    templates.filters.binary_param = 'unhex({{ param }})';
+    templates.filters.uuid_param = 'toUUID({{ param }})';
+    templates.quotes.literal_backslash_escape = '\\\\';
     templates.quotes.identifiers = '`';
     templates.quotes.escape = '\\`';
     templates.types.boolean = 'BOOL';
@@ -281,6 +293,16 @@ export class ClickHouseQuery extends BaseQuery {
     // ClickHouse intervals have a distinct type for each granularity
     delete templates.types.interval;
     delete templates.types.binary;
+    templates.expressions.unnest = 'arrayJoin({{ expr }})';
+    templates.expressions.unnest_distinct = 'arrayJoin(arrayDistinct({{ expr }}))';
+    templates.filters.array_contains = 'has({{ column }}, {{ value }})';
+    templates.filters.array_contains_all = 'hasAll({{ column }}, [{{ values_concat }}])';
+    templates.filters.array_overlaps = 'hasAny({{ column }}, [{{ values_concat }}])';
+    templates.filters.regex_match = '{% if negated %}NOT {% endif %}match({{ column }}, {{ value }})';
+    templates.time_attributes.dow = 'toDayOfWeek({{ expr }})';
+    templates.time_attributes.hour_of_day = 'toHour({{ expr }})';
+    templates.time_attributes.month_of_year = 'toMonth({{ expr }})';
+    templates.time_attributes.week_of_year = 'toISOWeek({{ expr }})';
     return templates;
   }
 }
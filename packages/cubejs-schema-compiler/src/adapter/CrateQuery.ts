@@ -14,4 +14,17 @@ export class CrateQuery extends PostgresQuery {
   public countDistinctApprox(_sql): string {
     throw new UserError('Not implemented yet');
   }
+
+  public sqlTemplates() {
+    const templates = super.sqlTemplates();
+    // No lateral joins
+    delete templates.statements.as_of_join;
+    delete templates.expressions.normalize_unicode;
+    // No binary type
+    delete templates.expressions.hex_encode;
+    delete templates.filters.binary_param;
+    // UUIDs are stored as strings
+    templates.filters.uuid_param = '{{ param }}';
+    return templates;
+  }
 }
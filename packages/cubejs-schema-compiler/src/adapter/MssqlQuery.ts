@@ -224,8 +224,18 @@ export class MssqlQuery extends BaseQuery {
     templates.functions.LEAST = 'LEAST({{ args_concat }})';
     templates.functions.GREATEST = 'GREATEST({{ args_concat }})';
     delete templates.expressions.ilike;
+    templates.statements.as_of_join = 'OUTER APPLY (SELECT TOP 1 * FROM {{ source }} WHERE {{ condition }} AND {{ match_time }} <= {{ time }} ' +
+      'ORDER BY {{ match_time }} DESC) AS {{ alias }}';
+    templates.statements.table_sample = '{{ table }} TABLESAMPLE ({{ percent }} PERCENT)';
+    templates.expressions.collation_name = '{{ name }}';
+    templates.expressions.hex_encode = 'LOWER(CONVERT(VARCHAR(MAX), {{ expr }}, 2))';
+    templates.filters.binary_param = 'CONVERT(VARBINARY(MAX), {{ param }}, 2)';
+    templates.filters.uuid_param = 'CAST({{ param }} AS UNIQUEIDENTIFIER)';
+    // Same placeholders as `MssqlParamAllocator`
+    templates.params.param = '@_{{ param_index + 1 }}';
     // NOTE: this template contains a comma; two order expressions are being generated
-    templates.expressions.sort = '{{ expr }} IS NULL {% if nulls_first %}DESC{% else %}ASC{% endif %}, {{ expr }} {% if asc %}ASC{% else %}DESC{% endif %}';
+    templates.expressions.sort = 'CASE WHEN {{ expr }} IS NULL THEN {% if nulls_first %}0 ELSE 1{% else %}1 ELSE 0{% endif %} END, {{ expr }} {% if asc %}ASC{% else %}DESC{% endif %}';
+    templates.expressions.order_by = '{% if nulls_first or nulls_last %}CASE WHEN {{ expr }} IS NULL THEN {% if nulls_first %}0 ELSE 1{% else %}1 ELSE 0{% endif %} END, {% endif %}{% if index %} {{ index }} {% else %} {{ expr }} {% endif %} {% if asc %}ASC{% else %}DESC{% endif %}';
     templates.types.string = 'VARCHAR';
     templates.types.boolean = 'BIT';
     templates.types.integer = 'INT';
@@ -234,6 +244,11 @@ export class MssqlQuery extends BaseQuery {
     templates.types.timestamp = 'DATETIME2';
     delete templates.types.interval;
     templates.types.binary = 'VARBINARY';
+    // weekday depends on DATEFIRST setting so it's shifted to make Monday 1
+    templates.time_attributes.dow = '((DATEPART(weekday, {{ expr }}) + @@DATEFIRST - 2) % 7) + 1';
+    templates.time_attributes.hour_of_day = 'DATEPART(hour, {{ expr }})';
+    templates.time_attributes.month_of_year = 'DATEPART(month, {{ expr }})';
+    templates.time_attributes.week_of_year = 'DATEPART(iso_week, {{ expr }})';
     return templates;
   }
 }
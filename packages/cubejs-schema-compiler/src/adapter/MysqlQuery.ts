@@ -23,6 +23,10 @@ class MysqlFilter extends BaseFilter {
     const s = (!type || type === 'contains' || type === 'starts') ? '%' : '';
     return `${column}${not ? ' NOT' : ''} LIKE CONCAT('${p}', ${this.allocateParam(param)}, '${s}')`;
   }
+
+  public regexMatch(column, not, param) {
+    return `${column}${not ? ' NOT' : ''} REGEXP ${param}`;
+  }
 }
 
 export class MysqlQuery extends BaseQuery {
@@ -160,12 +164,37 @@ export class MysqlQuery extends BaseQuery {
     templates.quotes.escape = '\\`';
     // NOTE: this template contains a comma; two order expressions are being generated
     templates.expressions.sort = '{{ expr }} IS NULL {% if nulls_first %}DESC{% else %}ASC{% endif %}, {{ expr }} {% if asc %}ASC{% else %}DESC{% endif %}';
+    templates.expressions.order_by = '{% if nulls_first or nulls_last %}{{ expr }} IS NULL {% if nulls_first %}DESC{% else %}ASC{% endif %}, {% endif %}{% if index %} {{ index }} {% else %} {{ expr }} {% endif %} {% if asc %}ASC{% else %}DESC{% endif %}';
     delete templates.expressions.ilike;
+    // Only `WITH ROLLUP` is supported, subtotals are planned as a union of groupings
+    delete templates.statements.grouping_sets;
     templates.types.string = 'VARCHAR';
     templates.types.boolean = 'TINYINT';
     templates.types.timestamp = 'DATETIME';
     delete templates.types.interval;
     templates.types.binary = 'BLOB';
+    // MySQL can't CAST AS BIGINT
+    templates.types.bigint = 'SIGNED';
+    // Table statistics as in SHOW TABLE STATUS, an upper bound for the query row count
+    templates.row_count_estimate = {
+      query: 'SELECT MAX(TABLE_ROWS) AS row_count FROM information_schema.TABLES WHERE CONCAT(TABLE_SCHEMA, \'.\', TABLE_NAME) IN ({% for table in tables %}\'{{ table | replace("`", "") }}\'{% if not loop.last %}, {% endif %}{% endfor %})',
+      strategy: 'scalar',
+    };
+    templates.filters.regex_match = '{{ column }} {% if negated %}NOT {% endif %}REGEXP {{ value }}';
+    delete templates.statements.table_sample;
+    templates.expressions.collation_name = '`{{ name }}`';
+    templates.expressions.hex_encode = 'LOWER(HEX({{ expr }}))';
+    templates.filters.binary_param = 'UNHEX({{ param }})';
+    // UUIDs are stored as strings
+    templates.filters.uuid_param = '{{ param }}';
+    templates.quotes.literal_backslash_escape = '\\\\';
+    templates.time_attributes = {
+      dow: 'WEEKDAY({{ expr }}) + 1',
+      hour_of_day: 'HOUR({{ expr }})',
+      month_of_year: 'MONTH({{ expr }})',
+      // Mode 3 is ISO: weeks start on Monday, the first week has 4 or more days
+      week_of_year: 'WEEK({{ expr }}, 3)',
+    };
     return templates;
   }
 }
@@ -0,0 +1,77 @@
+import R from 'ramda';
+import { createQuery } from './QueryBuilder';
+
+const planSummary = (compilers, dbType, queryOptions) => {
+  const query = createQuery(compilers, dbType, queryOptions);
+  if (!query) {
+    throw new Error(`Dialect for '${dbType}' is not found`);
+  }
+  return compilers.compiler.withQuery(query, () => {
+    const [sql] = query.buildSqlAndParams();
+    const join = query.join || query.joinGraph.buildJoin(query.allJoinHints);
+    return {
+      sql,
+      joins: join ? join.joins.map(j => `${j.from} -> ${j.to}`) : [],
+      measures: R.fromPairs(query.measures.map(m => [m.measure, m.measureSql()])),
+      filters: query.allFilters.map(f => f.filterToWhere()).filter(R.identity),
+    };
+  });
+};
+
+const safePlanSummary = (compilers, dbType, queryOptions) => {
+  try {
+    return { summary: planSummary(compilers, dbType, queryOptions) };
+  } catch (e) {
+    return { error: e.message || e.toString() };
+  }
+};
+
+const listDiff = (before, after) => ({
+  added: R.difference(after, before),
+  removed: R.difference(before, after),
+});
+
+const measuresDiff = (before, after) => {
+  const names = R.uniq(Object.keys(before).concat(Object.keys(after)));
+  return {
+    added: names.filter(name => !(name in before)),
+    removed: names.filter(name => !(name in after)),
+    changed: names
+      .filter(name => name in before && name in after && before[name] !== after[name])
+      .map(name => ({ measure: name, before: before[name], after: after[name] })),
+  };
+};
+
+/**
+ * Plans the same queries against two compiled schema versions and reports
+ * structural differences in generated SQL: joins, filters and measures.
+ * Planning errors are reported per query instead of failing the whole diff.
+ * @param {Object} beforeCompilers
+ * @param {Object} afterCompilers
+ * @param {string} dbType
+ * @param {Array<Object>} queries
+ * @param {Object} [queryOptions] options shared by all queries, e.g. `dialectClass`
+ */
+export const diffQueryPlans = (beforeCompilers, afterCompilers, dbType, queries, queryOptions = {}) => (
+  queries.map(query => {
+    const before = safePlanSummary(beforeCompilers, dbType, { ...queryOptions, ...query });
+    const after = safePlanSummary(afterCompilers, dbType, { ...queryOptions, ...query });
+    if (before.error || after.error) {
+      return {
+        query,
+        changed: before.error !== after.error,
+        errors: { before: before.error, after: after.error },
+      };
+    }
+    const joins = listDiff(before.summary.joins, after.summary.joins);
+    const filters = listDiff(before.summary.filters, after.summary.filters);
+    const measures = measuresDiff(before.summary.measures, after.summary.measures);
+    return {
+      query,
+      changed: before.summary.sql !== after.summary.sql,
+      joins,
+      filters,
+      measures,
+    };
+  })
+);
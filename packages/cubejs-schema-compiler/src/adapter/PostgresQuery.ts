@@ -61,6 +61,22 @@ export class PostgresQuery extends BaseQuery {
     const templates = super.sqlTemplates();
     // eslint-disable-next-line no-template-curly-in-string
     templates.params.param = '${{ param_index + 1 }}';
+    templates.filters.array_contains_all = '{{ column }} @> ARRAY[{{ values_concat }}]';
+    templates.filters.array_overlaps = '{{ column }} && ARRAY[{{ values_concat }}]';
+    templates.filters.regex_match = '{{ column }} {% if negated %}!{% endif %}~ {{ value }}';
+    templates.expressions.unnest = 'UNNEST({{ expr }})';
+    templates.expressions.unnest_distinct = 'UNNEST(ARRAY(SELECT DISTINCT UNNEST({{ expr }})))';
+    templates.expressions.hex_encode = 'encode({{ expr }}, \'hex\')';
+    templates.filters.binary_param = 'decode({{ param }}, \'hex\')';
+    templates.filters.uuid_param = 'CAST({{ param }} AS UUID)';
+    templates.time_attributes = {
+      dow: 'EXTRACT(ISODOW FROM {{ expr }})',
+      hour_of_day: 'EXTRACT(HOUR FROM {{ expr }})',
+      month_of_year: 'EXTRACT(MONTH FROM {{ expr }})',
+      week_of_year: 'EXTRACT(WEEK FROM {{ expr }})',
+    };
+    templates.statements.as_of_join = 'LEFT JOIN LATERAL (SELECT * FROM {{ source }} WHERE {{ condition }} AND {{ match_time }} <= {{ time }} ' +
+      'ORDER BY {{ match_time }} DESC LIMIT 1) AS {{ alias }} ON 1 = 1';
     templates.functions.DATETRUNC = 'DATE_TRUNC({{ args_concat }})';
     templates.functions.CONCAT = 'CONCAT({% for arg in args %}CAST({{arg}} AS TEXT){% if not loop.last %},{% endif %}{% endfor %})';
     templates.functions.DATEPART = 'DATE_PART({{ args_concat }})';
@@ -75,6 +91,7 @@ export class PostgresQuery extends BaseQuery {
     templates.expressions.interval = 'INTERVAL \'{{ interval }}\'';
     templates.expressions.extract = 'EXTRACT({{ date_part }} FROM {{ expr }})';
     templates.expressions.timestamp_literal = 'timestamptz \'{{ value }}\'';
+    templates.expressions.normalize_unicode = '{% if case_fold %}LOWER(NORMALIZE({{ expr }}, NFC)){% else %}NORMALIZE({{ expr }}, NFC){% endif %}';
     templates.window_frame_types.groups = 'GROUPS';
     templates.types.string = 'TEXT';
     templates.types.tinyint = 'SMALLINT';
@@ -82,6 +99,10 @@ export class PostgresQuery extends BaseQuery {
     templates.types.double = 'DOUBLE PRECISION';
     templates.types.binary = 'BYTEA';
     templates.operators.is_not_distinct_from = 'IS NOT DISTINCT FROM';
+    templates.row_count_estimate = {
+      query: 'EXPLAIN (FORMAT JSON) {{ sql }}',
+      strategy: 'explain_json',
+    };
     return templates;
   }
 
@@ -112,6 +112,7 @@ export class PrestodbQuery extends BaseQuery {
     const templates = super.sqlTemplates();
     templates.functions.DATETRUNC = 'DATE_TRUNC({{ args_concat }})';
     templates.functions.DATEPART = 'DATE_PART({{ args_concat }})';
+    templates.filters.regex_match = '{% if negated %}NOT {% endif %}regexp_like({{ column }}, {{ value }})';
     templates.statements.select = 'SELECT {{ select_concat | map(attribute=\'aliased\') | join(\', \') }} \n' +
       'FROM (\n  {{ from }}\n) AS {{ from_alias }} \n' +
       '{% if group_by %} GROUP BY {{ group_by }}{% endif %}' +
@@ -127,6 +128,15 @@ export class PrestodbQuery extends BaseQuery {
     // Presto intervals have a YearMonth or DayTime type variants, but no universal type
     delete templates.types.interval;
     templates.types.binary = 'VARBINARY';
+    templates.expressions.hex_encode = 'lower(to_hex({{ expr }}))';
+    templates.filters.binary_param = 'from_hex({{ param }})';
+    templates.filters.uuid_param = 'CAST({{ param }} AS UUID)';
+    templates.time_attributes = {
+      dow: 'day_of_week({{ expr }})',
+      hour_of_day: 'hour({{ expr }})',
+      month_of_year: 'month({{ expr }})',
+      week_of_year: 'week_of_year({{ expr }})',
+    };
     return templates;
   }
 
@@ -17,10 +17,25 @@ export class RedshiftQuery extends PostgresQuery {
     templates.functions.DLOG10 = 'LOG(10, {{ args_concat }})';
     templates.functions.DATEDIFF = 'DATEDIFF({{ date_part }}, {{ args[1] }}, {{ args[2] }})';
     delete templates.functions.COVAR_POP;
+    // No lateral joins
+    delete templates.statements.as_of_join;
     delete templates.functions.COVAR_SAMP;
     delete templates.window_frame_types.range;
     delete templates.window_frame_types.groups;
+    // No NORMALIZE function
+    delete templates.expressions.normalize_unicode;
     templates.types.binary = 'VARBINARY';
+    templates.expressions.hex_encode = 'TO_HEX({{ expr }})';
+    templates.filters.binary_param = 'FROM_HEX({{ param }})';
+    // UUIDs are stored as strings
+    templates.filters.uuid_param = '{{ param }}';
+    // Arrays are SUPER values which can't be unnested in expressions
+    delete templates.expressions.unnest;
+    delete templates.expressions.unnest_distinct;
+    // No ISODOW, DOW is 0 (Sunday) to 6 (Saturday)
+    templates.time_attributes.dow = '(EXTRACT(DOW FROM {{ expr }}) + 6) % 7 + 1';
+    // Redshift doesn't support EXPLAIN output formats
+    delete templates.row_count_estimate;
     return templates;
   }
 }
@@ -27,6 +27,11 @@ class SnowflakeFilter extends BaseFilter {
     // That is why here are FOUR backslashes.
     return `${column}${not ? ' NOT' : ''} ILIKE ${p}${this.allocateParam(param)}${s} ESCAPE '\\\\'`;
   }
+
+  // REGEXP_LIKE and RLIKE match the whole string
+  public regexMatch(column, not, param) {
+    return `REGEXP_INSTR(${column}, ${param}) ${not ? '=' : '>'} 0`;
+  }
 }
 
 export class SnowflakeQuery extends BaseQuery {
@@ -108,16 +113,32 @@ export class SnowflakeQuery extends BaseQuery {
     templates.functions.DATEPART = 'DATE_PART({{ args_concat }})';
     templates.functions.CURRENTDATE = 'CURRENT_DATE';
     templates.functions.NOW = 'CURRENT_TIMESTAMP';
+    templates.statements.as_of_join = 'ASOF JOIN {{ source }} MATCH_CONDITION ({{ time }} >= {{ match_time }}) ON {{ condition }}';
     templates.functions.LOG = 'LOG({% if args[1] is undefined %}10, {% endif %}{{ args_concat }})';
     templates.functions.DLOG10 = 'LOG(10, {{ args_concat }})';
     templates.functions.CHARACTERLENGTH = 'LENGTH({{ args[0] }})';
     templates.functions.BTRIM = 'TRIM({{ args_concat }})';
+    templates.expressions.collation_name = '\'{{ name }}\'';
     templates.expressions.extract = 'EXTRACT({{ date_part }} FROM {{ expr }})';
     templates.expressions.interval = 'INTERVAL \'{{ interval }}\'';
     templates.expressions.timestamp_literal = '\'{{ value }}\'::timestamp_tz';
     templates.operators.is_not_distinct_from = 'IS NOT DISTINCT FROM';
     templates.join_types.full = 'FULL';
+    templates.statements.table_sample = '{{ table }} SAMPLE SYSTEM ({{ percent }})';
+    templates.quotes.literal_backslash_escape = '\\\\';
+    templates.expressions.hex_encode = 'LOWER(HEX_ENCODE({{ expr }}))';
+    templates.filters.binary_param = 'TO_BINARY({{ param }}, \'HEX\')';
+    // UUIDs are stored as strings
+    templates.filters.uuid_param = '{{ param }}';
+    templates.filters.array_overlaps = 'ARRAYS_OVERLAP({{ column }}, ARRAY_CONSTRUCT({{ values_concat }}))';
     delete templates.types.interval;
+    templates.filters.regex_match = 'REGEXP_INSTR({{ column }}, {{ value }}) {% if negated %}={% else %}>{% endif %} 0';
+    templates.time_attributes = {
+      dow: 'DAYOFWEEKISO({{ expr }})',
+      hour_of_day: 'HOUR({{ expr }})',
+      month_of_year: 'MONTH({{ expr }})',
+      week_of_year: 'WEEKISO({{ expr }})',
+    };
     return templates;
   }
 }
@@ -6,6 +6,7 @@ export * from './BaseQuery';
 export * from './BaseSegment';
 export * from './BaseTimeDimension';
 export * from './ParamAllocator';
+export * from './PlanDiff';
 export * from './PreAggregations';
 export * from './QueryBuilder';
 export * from './QueryCache';
@@ -106,6 +106,13 @@ export class CubeToMetaTransformer {
           })),
           R.toPairs
         )(cube.segments || {}),
+        ...(cube.joins ? {
+          joins: R.toPairs(cube.joins).map(([joinName, join]) => ({
+            name: joinName,
+            relationship: join.relationship,
+            sql: this.joinSql(join.sql),
+          })),
+        } : {}),
         hierarchies: (cube.hierarchies || []).map((it) => ({
           ...it,
           public: it.public ?? true,
@@ -119,6 +126,15 @@ export class CubeToMetaTransformer {
     };
   }
 
+  /**
+   * SQL API checks join keys of views against the join condition, so it's passed as written.
+   * @protected
+   */
+  joinSql(sql) {
+    const sqlStr = sql.toString();
+    return sqlStr.substring(sqlStr.indexOf('=>') + 3);
+  }
+
   queriesForContext(contextId) {
     // return All queries if no context pass
     if (R.isNil(contextId) || R.isEmpty(contextId)) {
@@ -86,14 +86,27 @@ const GranularityInterval = Joi.string().pattern(/^\d+\s+(second|minute|hour|day
 // Do not allow negative intervals for granularities, while offsets could be negative
 const GranularityOffset = Joi.string().pattern(/^-?(\d+\s+)(second|minute|hour|day|week|month|quarter|year)s?(\s-?\d+\s+(second|minute|hour|day|week|month|quarter|year)s?){0,7}$/, 'granularity offset');
 
+const Unit = Joi.string().valid(
+  'bytes', 'KB', 'MB', 'GB', 'TB',
+  'millimeters', 'centimeters', 'meters', 'kilometers', 'feet', 'miles',
+  'grams', 'kilograms', 'pounds'
+);
+
 const BaseDimensionWithoutSubQuery = {
   aliases: Joi.array().items(Joi.string()),
-  type: Joi.any().valid('string', 'number', 'boolean', 'time', 'geo').required(),
+  type: Joi.any().valid('string', 'number', 'boolean', 'time', 'geo', 'binary', 'uuid').required(),
   fieldType: Joi.any().valid('string'),
   valuesAsSegments: Joi.boolean().strict(),
   primaryKey: Joi.boolean().strict(),
   shown: Joi.boolean().strict(),
   public: Joi.boolean().strict(),
+  sensitive: Joi.boolean().strict(),
+  collation: Joi.string(),
+  unit: Unit,
+  valueMap: Joi.object().pattern(/.*/, Joi.string()),
+  startsWithRange: Joi.boolean().strict(),
+  multiValue: Joi.boolean().strict(),
+  multiValueDedupe: Joi.boolean().strict(),
   title: Joi.string(),
   description: Joi.string(),
   suggestFilterValues: Joi.boolean().strict(),
@@ -199,7 +212,24 @@ const ToDate = {
 const BaseMeasure = {
   aliases: Joi.array().items(Joi.string()),
   format: Joi.any().valid('percent', 'currency', 'number'),
+  rounding: Joi.object().keys({
+    scale: Joi.number().integer().min(0).required(),
+    precision: Joi.number().integer().min(1),
+  }),
+  decimal: Joi.object().keys({
+    precision: Joi.number().integer().min(1).required(),
+    scale: Joi.number().integer().min(0).required(),
+  }),
+  overflowSafe: Joi.boolean().strict(),
+  denominatorSql: Joi.func(),
+  divisionByZero: Joi.any().valid('null', 'zero'),
+  currency: Joi.object().keys({
+    dimension: Joi.string().required(),
+    timeDimension: Joi.string().required(),
+  }),
+  unit: Unit,
   public: Joi.boolean().strict(),
+  sensitive: Joi.boolean().strict(),
   // TODO: Deprecate and remove, please use public
   visible: Joi.boolean().strict(),
   // TODO: Deprecate and remove, please use public
@@ -700,8 +730,11 @@ const baseSchema = {
       'belongsTo', 'belongs_to', 'many_to_one', 'manyToOne',
       'hasMany', 'has_many', 'one_to_many', 'oneToMany',
       'hasOne', 'has_one', 'one_to_one', 'oneToOne'
-    ).required()
-  })),
+    ).required(),
+    validAt: Joi.func(),
+    asOfTime: Joi.func(),
+    asOfMatchTime: Joi.func(),
+  }).and('asOfTime', 'asOfMatchTime')),
   measures: MeasuresSchema,
   dimensions: Joi.object().pattern(identifierRegex, Joi.alternatives().try(
     inherit(BaseDimensionWithoutSubQuery, {
@@ -733,6 +766,14 @@ const baseSchema = {
         sql: Joi.func().required()
       }).required()
     }),
+    inherit(BaseDimensionWithoutSubQuery, {
+      type: Joi.any().valid('number').required(),
+      duration: Joi.object().keys({
+        from: Joi.string().required(),
+        to: Joi.string().required(),
+        unit: Joi.any().valid('second', 'minute', 'hour', 'day', 'week', 'month', 'quarter', 'year').required(),
+      }).required()
+    }),
     inherit(BaseDimension, {
       sql: Joi.func().required()
     }),
@@ -758,13 +799,43 @@ const baseSchema = {
 const cubeSchema = inherit(baseSchema, {
   sql: Joi.func(),
   sqlTable: Joi.func(),
+  updatedAt: identifier,
+  calendar: Joi.object().keys({
+    table: Joi.string().required(),
+    dateColumn: Joi.string().required(),
+    businessDayColumn: Joi.string(),
+    holidayColumn: Joi.string(),
+  }),
   hierarchies: Joi.array().items(Joi.object().keys({
     name: identifier,
     title: Joi.string(),
     public: Joi.boolean().strict(),
     levels: Joi.func()
   })),
-}).xor('sql', 'sqlTable').messages({
+  union: Joi.object().keys({
+    timeColumn: Joi.string(),
+    tables: Joi.array().items(Joi.object().keys({
+      table: Joi.string().required(),
+      since: Joi.string(),
+      until: Joi.string(),
+    })).min(1).required(),
+  }),
+  validity: Joi.object().keys({
+    validFrom: Joi.string().required(),
+    validTo: Joi.string().required(),
+  }),
+  sourceQuery: Joi.object().keys({
+    measures: Joi.array().items(Joi.string()),
+    dimensions: Joi.array().items(Joi.string()),
+    timeDimensions: Joi.array().items(Joi.object()),
+    filters: Joi.array().items(Joi.object()),
+    order: Joi.array().items(Joi.object()),
+    limit: Joi.number().integer().min(0),
+    offset: Joi.number().integer().min(0),
+    ungrouped: Joi.boolean(),
+    materialized: Joi.string(),
+  }),
+}).xor('sql', 'sqlTable', 'union', 'sourceQuery').messages({
   'object.xor': 'You must use either sql or sqlTable within a model, but not both'
 });
 
@@ -214,6 +214,57 @@ export class JoinGraph {
     return R.range(0, path.length - 1).map(i => this.edges[`${path[i]}-${path[i + 1]}`]);
   }
 
+  /**
+   * Exports compiled join graph for debugging join path selection.
+   * If `cubesToJoin` is passed, join path chosen for them is exported as well.
+   * @param {'json' | 'dot'} format
+   * @param {Array<string | Array<string>>} [cubesToJoin]
+   * @returns {Object | string}
+   */
+  exportGraph(format, cubesToJoin) {
+    const edges = R.values(this.edges).map(edge => ({
+      from: edge.from,
+      to: edge.to,
+      relationship: edge.join.relationship,
+    }));
+    const nodes = R.uniq(
+      this.cubeEvaluator.cubeList.filter(cube => !cube.isView).map(cube => cube.name)
+        .concat(edges.map(edge => edge.from))
+        .concat(edges.map(edge => edge.to))
+    );
+    const join = cubesToJoin && cubesToJoin.length ? this.buildJoin(cubesToJoin) : null;
+    const path = join && {
+      root: join.root,
+      joins: join.joins.map(j => ({ from: j.from, to: j.to })),
+    };
+
+    if (format === 'json') {
+      return { nodes, edges, path };
+    }
+    if (format === 'dot') {
+      return this.graphToDot(nodes, edges, path);
+    }
+    throw new UserError(`Unsupported join graph export format: ${format}`);
+  }
+
+  graphToDot(nodes, edges, path) {
+    const quote = (name) => `"${name.replace(/"/g, '\\"')}"`;
+    const inPath = (edge) => !!path && !!path.joins.find(j => j.from === edge.from && j.to === edge.to);
+    const lines = [
+      'digraph JoinGraph {',
+      ...nodes.map(node => (
+        path && node === path.root ? `  ${quote(node)} [style=bold, color=blue];` : `  ${quote(node)};`
+      )),
+      ...edges.map(edge => {
+        const attributes = [`label=${quote(edge.relationship || '')}`]
+          .concat(inPath(edge) ? ['color=blue', 'penwidth=2'] : []);
+        return `  ${quote(edge.from)} -> ${quote(edge.to)} [${attributes.join(', ')}];`;
+      }),
+      '}',
+    ];
+    return lines.join('\n');
+  }
+
   connectedComponents() {
     if (!this.cachedConnectedComponents) {
       let componentId = 1;
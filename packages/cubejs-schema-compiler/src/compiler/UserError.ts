@@ -1,7 +1,7 @@
 export class UserError extends Error {
   protected readonly type: string = 'UserError';
 
-  public constructor(message: string) {
+  public constructor(message: string, public readonly meta?: Record<string, string>) {
     super(message);
   }
 }
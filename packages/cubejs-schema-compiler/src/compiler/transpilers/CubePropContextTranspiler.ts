@@ -11,6 +11,7 @@ export const transpiledFieldsPatterns: Array<RegExp> = [
   /\.sql$/,
   /sql$/,
   /(sqlTable|sql_table)$/,
+  /^measures\.[_a-zA-Z][_a-zA-Z0-9]*\.(denominatorSql|denominator_sql)$/,
   /^measures\.[_a-zA-Z][_a-zA-Z0-9]*\.(drillMemberReferences|drillMembers|drill_members)$/,
   /^measures\.[_a-zA-Z][_a-zA-Z0-9]*\.(orderBy|order_by)\.[0-9]+\.sql$/,
   /^measures\.[_a-zA-Z][_a-zA-Z0-9]*\.(timeShift|time_shift)\.[0-9]+\.(timeDimension|time_dimension)$/,
@@ -20,6 +21,7 @@ export const transpiledFieldsPatterns: Array<RegExp> = [
   /^(preAggregations|pre_aggregations)\.[_a-zA-Z][_a-zA-Z0-9]*\.(timeDimensionReference|timeDimension|time_dimension|segments|dimensions|measures|rollups|segmentReferences|dimensionReferences|measureReferences|rollupReferences)$/,
   /^(preAggregations|pre_aggregations)\.[_a-zA-Z][_a-zA-Z0-9]*\.(timeDimensions|time_dimensions)\.\d+\.dimension$/,
   /^(preAggregations|pre_aggregations)\.[_a-zA-Z][_a-zA-Z0-9]*\.(outputColumnTypes|output_column_types)\.\d+\.member$/,
+  /^joins\.[_a-zA-Z][_a-zA-Z0-9]*\.(validAt)$/,
   /^contextMembers$/,
   /^includes$/,
   /^excludes$/,
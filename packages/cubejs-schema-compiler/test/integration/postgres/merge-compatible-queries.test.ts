@@ -0,0 +1,150 @@
+import R from 'ramda';
+import { BaseQuery } from '../../../src/adapter/BaseQuery';
+import { PostgresQuery } from '../../../src/adapter/PostgresQuery';
+import { prepareCompiler } from '../../unit/PrepareCompiler';
+import { dbRunner } from './PostgresDBRunner';
+
+describe('Merge compatible queries', () => {
+  jest.setTimeout(200000);
+
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+cube(\`orders\`, {
+  sql: \`
+    SELECT 79 AS id, 1 AS amount, 1 AS city_id UNION ALL
+    SELECT 80 AS id, 2 AS amount, 1 AS city_id UNION ALL
+    SELECT 81 AS id, 3 AS amount, 1 AS city_id UNION ALL
+    SELECT 82 AS id, 4 AS amount, 2 AS city_id UNION ALL
+    SELECT 83 AS id, 5 AS amount, 2 AS city_id UNION ALL
+    SELECT 84 AS id, 6 AS amount, 3 AS city_id
+  \`,
+
+  joins: {
+    city: {
+      relationship: \`many_to_one\`,
+      sql: \`\${orders}.city_id = \${city}.id\`,
+    },
+  },
+
+  measures: {
+    amount: {
+      sql: \`amount\`,
+      type: 'sum'
+    },
+    count: {
+      type: 'count'
+    },
+  },
+
+  dimensions: {
+    id: {
+      sql: \`id\`,
+      type: \`number\`,
+      primaryKey: true,
+    },
+  },
+});
+
+cube(\`shipments\`, {
+  sql: \`
+    SELECT 100 AS id, 1 AS city_id UNION ALL
+    SELECT 101 AS id, 2 AS city_id UNION ALL
+    SELECT 102 AS id, 2 AS city_id UNION ALL
+    SELECT 103 AS id, 4 AS city_id
+  \`,
+
+  joins: {
+    city: {
+      relationship: \`many_to_one\`,
+      sql: \`\${shipments}.city_id = \${city}.id\`,
+    },
+  },
+
+  measures: {
+    count: {
+      type: \`count\`
+    },
+  },
+
+  dimensions: {
+    id: {
+      sql: \`id\`,
+      type: \`number\`,
+      primaryKey: true,
+    },
+  }
+});
+
+cube(\`city\`, {
+  sql: \`
+    SELECT 1 AS id, 'San Francisco' AS name UNION ALL
+    SELECT 2 AS id, 'New York City' AS name
+  \`,
+
+  dimensions: {
+    id: {
+      sql: \`id\`,
+      type: \`number\`,
+      primaryKey: true,
+    },
+
+    name: {
+      sql: \`\${CUBE}.name\`,
+      type: \`string\`,
+    },
+  },
+});
+    `);
+
+  const byCity = {
+    dimensions: ['city.name'],
+    order: [{ id: 'city.name' }],
+  };
+
+  async function newQueries(queries) {
+    await compiler.compile();
+    return queries.map(q => new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, q));
+  }
+
+  it('merges queries with measures of the same cube', async () => {
+    const queries = await newQueries([
+      { ...byCity, measures: ['orders.amount'] },
+      { ...byCity, measures: ['orders.count'] },
+    ]);
+
+    const merged = BaseQuery.mergeCompatibleQueries(queries);
+
+    expect(merged.length).toEqual(1);
+    expect(merged[0].sources.map(s => s.index)).toEqual([0, 1]);
+  });
+
+  it('keeps queries joined from other cubes apart', async () => {
+    const queries = await newQueries([
+      { ...byCity, measures: ['orders.amount'] },
+      { ...byCity, measures: ['shipments.count'] },
+    ]);
+
+    const merged = BaseQuery.mergeCompatibleQueries(queries);
+
+    expect(merged.map(m => m.sources.map(s => s.index))).toEqual([[0], [1]]);
+  });
+
+  it('split back results equal results of the source queries', async () => {
+    const queries = await newQueries([
+      { ...byCity, measures: ['orders.amount'] },
+      { ...byCity, measures: ['orders.count'] },
+      { ...byCity, measures: ['shipments.count'] },
+    ]);
+
+    const unmerged = await Promise.all(queries.map(q => dbRunner.testQuery(q.buildSqlAndParams())));
+
+    const split = [];
+    await Promise.all(BaseQuery.buildMergedSqlAndParamsBatch(queries).map(async ({ sql, params, sources }) => {
+      const rows = await dbRunner.testQuery([sql, params]);
+      sources.forEach(({ index, columns }) => {
+        split[index] = rows.map(row => R.pick(Object.keys(columns), row));
+      });
+    }));
+
+    expect(split).toEqual(unmerged);
+  });
+});
@@ -0,0 +1,89 @@
+import { BaseQuery } from '../../../src';
+
+export type PlannerDiffFixture = {
+  name: string,
+  query: any,
+};
+
+export type PlannerDiffDivergence = {
+  name: string,
+  js?: [string, unknown[]],
+  rust?: [string, unknown[]],
+  errors?: { js?: string, rust?: string },
+};
+
+/**
+ * Normalizes SQL so that only meaningful differences between planners remain:
+ * whitespace, keyword case and positional param placeholders are unified.
+ */
+export function normalizeSql(sql: string): string {
+  return sql
+    .replace(/\$\d+/g, '?')
+    .replace(/\s+/g, ' ')
+    .replace(/\(\s+/g, '(')
+    .replace(/\s+\)/g, ')')
+    .trim()
+    .toLowerCase();
+}
+
+/**
+ * Feeds identical query fixtures to the JS BaseQuery and the Rust planner
+ * (through the native bridge) and reports fixtures with diverging normalized SQL or params.
+ * The Rust planner runs with `legacy_js_compatibility` flag so SQL shape differences
+ * that are intentional aren't reported.
+ */
+export class PlannerDiffHarness {
+  public constructor(
+    protected readonly compilers: any,
+    protected readonly queryClass: any,
+    protected readonly queryOptions: any = {},
+  ) {
+  }
+
+  public async run(fixtures: PlannerDiffFixture[]): Promise<PlannerDiffDivergence[]> {
+    await this.compilers.compiler.compile();
+
+    const divergences: PlannerDiffDivergence[] = [];
+    for (const fixture of fixtures) {
+      const divergence = this.diffFixture(fixture);
+      if (divergence) {
+        divergences.push(divergence);
+      }
+    }
+    return divergences;
+  }
+
+  protected diffFixture(fixture: PlannerDiffFixture): PlannerDiffDivergence | null {
+    const js = this.plan(() => this.newQuery(fixture.query).buildSqlAndParams());
+    const rust = this.plan(() => this.newQuery(fixture.query, {
+      plannerFlags: { legacy_js_compatibility: true },
+    }).buildSqlAndParamsRust());
+
+    if (js.error || rust.error) {
+      return { name: fixture.name, js: js.result, rust: rust.result, errors: { js: js.error, rust: rust.error } };
+    }
+
+    const [jsSql, jsParams] = js.result!;
+    const [rustSql, rustParams] = rust.result!;
+    if (
+      normalizeSql(jsSql) !== normalizeSql(rustSql) ||
+      JSON.stringify(jsParams) !== JSON.stringify(rustParams)
+    ) {
+      return { name: fixture.name, js: js.result, rust: rust.result };
+    }
+    return null;
+  }
+
+  protected newQuery(query: any, options: any = {}): BaseQuery {
+    return new this.queryClass(this.compilers, { ...this.queryOptions, ...query, ...options });
+  }
+
+  protected plan(fn: () => [string, unknown[]]): { result?: [string, unknown[]], error?: string } {
+    try {
+      const [sql, params] = fn();
+      return { result: [sql, [...params]] };
+    } catch (e: any) {
+      return { error: e.message || e.toString() };
+    }
+  }
+}
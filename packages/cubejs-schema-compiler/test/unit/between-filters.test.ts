@@ -0,0 +1,66 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+describeNative('Between filters', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        amount: {
+          type: 'number',
+          sql: 'amount'
+        }
+      }
+    })
+  `);
+
+  const buildSqlAndParams = async (filter) => {
+    await compiler.compile();
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['orders.count'],
+      filters: [{ member: 'orders.amount', ...filter }],
+    }).buildSqlAndParams();
+  };
+
+  it('renders between with both bounds', async () => {
+    const [sql, params] = await buildSqlAndParams({ operator: 'between', values: ['10', '20'] });
+
+    expect(sql).toMatch(/\("orders"\.amount BETWEEN \$1(::\w+)? AND \$2(::\w+)?\)/);
+    expect(params).toEqual(['10', '20']);
+  });
+
+  it('keeps nulls in notBetween', async () => {
+    const [sql, params] = await buildSqlAndParams({ operator: 'notBetween', values: ['10', '20'] });
+
+    expect(sql).toMatch(/"orders"\.amount NOT BETWEEN \$1(::\w+)? AND \$2(::\w+)? OR "orders"\.amount IS NULL/);
+    expect(params).toEqual(['10', '20']);
+  });
+
+  it('rejects a missing bound', async () => {
+    await expect(buildSqlAndParams({ operator: 'between', values: ['10'] }))
+      .rejects.toThrow(/Filter between for member 'orders.amount' expects 2 values, got 1/);
+    await expect(buildSqlAndParams({ operator: 'notBetween', values: ['10', null] }))
+      .rejects.toThrow(/Filter notBetween for member 'orders.amount' expects two non-null values/);
+  });
+
+  it('rejects non-numeric bounds of a number member', async () => {
+    await expect(buildSqlAndParams({ operator: 'between', values: ['10', 'abc'] }))
+      .rejects.toThrow(/abc/);
+  });
+});
@@ -0,0 +1,110 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+describeNative('Deterministic SQL', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`customers\`, {
+      sql: \`select * from customers\`,
+
+      joins: {
+        orders: {
+          relationship: 'hasMany',
+          sql: \`\${CUBE}.id = \${orders}.customer_id\`
+        },
+        tickets: {
+          relationship: 'hasMany',
+          sql: \`\${CUBE}.id = \${tickets}.customer_id\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        city: {
+          type: 'string',
+          sql: 'city'
+        }
+      }
+    })
+
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        total: {
+          type: 'sum',
+          sql: 'amount'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+
+    cube(\`tickets\`, {
+      sql: \`select * from tickets\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+  `);
+
+  // Multiplied measures of several cubes are planned as separate subqueries
+  const multiFact = {
+    measures: ['tickets.count', 'customers.count', 'orders.total'],
+    dimensions: ['customers.city'],
+    filters: [{ member: 'customers.city', operator: 'equals', values: ['Berlin'] }],
+    deterministic: true,
+  };
+
+  function buildSqlAndParams(query) {
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query).buildSqlAndParams();
+  }
+
+  it('renders byte-identical sql for identical queries', async () => {
+    await compiler.compile();
+
+    const [sql, params] = buildSqlAndParams(multiFact);
+    for (let i = 0; i < 10; i++) {
+      expect(buildSqlAndParams(multiFact)).toEqual([sql, params]);
+    }
+  });
+
+  it('keeps subqueries in the order of measures of the query', async () => {
+    await compiler.compile();
+
+    const [sql] = buildSqlAndParams(multiFact);
+
+    expect(sql.indexOf('tickets')).toBeGreaterThan(-1);
+    expect(sql.indexOf('tickets')).toBeLessThan(sql.indexOf('orders'));
+  });
+});
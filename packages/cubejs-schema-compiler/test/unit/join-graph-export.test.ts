@@ -0,0 +1,96 @@
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Join graph export', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      joins: {
+        customers: {
+          relationship: 'belongsTo',
+          sql: \`\${CUBE}.customer_id = \${customers}.id\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+
+    cube(\`customers\`, {
+      sql: \`select * from customers\`,
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        name: {
+          type: 'string',
+          sql: 'name'
+        }
+      }
+    })
+
+    cube(\`products\`, {
+      sql: \`select * from products\`,
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+  `);
+
+  function newQuery(query) {
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query);
+  }
+
+  const ordersByCustomer = { measures: ['orders.count'], dimensions: ['customers.name'] };
+
+  it('exports nodes, edges and join path of the query as json', async () => {
+    await compiler.compile();
+
+    const graph = newQuery(ordersByCustomer).exportJoinGraph('json');
+
+    expect(graph.nodes.sort()).toEqual(['customers', 'orders', 'products']);
+    expect(graph.edges).toEqual([{ from: 'orders', to: 'customers', relationship: 'belongsTo' }]);
+    expect(graph.path).toEqual({ root: 'orders', joins: [{ from: 'orders', to: 'customers' }] });
+    expect(joinGraph.exportGraph('json').path).toBeNull();
+  });
+
+  it('highlights join path of the query in dot', async () => {
+    await compiler.compile();
+
+    const lines = newQuery(ordersByCustomer).exportJoinGraph('dot').split('\n');
+
+    expect(lines[0]).toEqual('digraph JoinGraph {');
+    expect(lines).toContain('  "orders" [style=bold, color=blue];');
+    expect(lines).toContain('  "customers";');
+    expect(lines).toContain('  "products";');
+    expect(lines).toContain('  "orders" -> "customers" [label="belongsTo", color=blue, penwidth=2];');
+    expect(lines[lines.length - 1]).toEqual('}');
+  });
+
+  it('rejects unknown formats', async () => {
+    await compiler.compile();
+
+    expect(() => newQuery(ordersByCustomer).exportJoinGraph('svg'))
+      .toThrow(/Unsupported join graph export format: svg/);
+  });
+});
@@ -0,0 +1,66 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Measure filters', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        total: {
+          type: 'sum',
+          sql: 'amount'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        status: {
+          type: 'string',
+          sql: 'status'
+        }
+      }
+    })
+  `);
+
+  const totalFilter = { member: 'orders.total', operator: 'gt', values: ['100'] };
+  const statusFilter = { member: 'orders.status', operator: 'equals', values: ['shipped'] };
+
+  function buildSql(query) {
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query).buildSqlAndParams()[0];
+  }
+
+  // Routing of filters by their type is done by Tesseract only
+  const nativeIt = (name, fn) => (getEnv('nativeSqlPlanner') ? it : it.skip)(name, async () => {
+    await compiler.compile();
+    await fn();
+  });
+
+  nativeIt('applies measure filters to aggregated values in HAVING', () => {
+    const sql = buildSql({
+      measures: ['orders.total'],
+      dimensions: ['orders.status'],
+      filters: [totalFilter, statusFilter],
+    });
+
+    expect(sql).toMatch(/WHERE\s+\("orders"\.status = \$1\)/);
+    expect(sql).toMatch(/HAVING\s+\(sum\("orders"\.amount\) > \$2(::\w+)?\)/i);
+  });
+
+  nativeIt('applies measure filters of ungrouped queries in WHERE', () => {
+    const sql = buildSql({
+      measures: ['orders.total'],
+      dimensions: ['orders.id'],
+      filters: [totalFilter, statusFilter],
+      ungrouped: true,
+    });
+
+    expect(sql).not.toMatch(/HAVING/);
+    expect(sql).toMatch(/WHERE\s+\("orders"\.status = \$1\) AND \("orders"\.amount > \$2(::\w+)?\)/);
+  });
+});
@@ -1,8 +1,10 @@
-import { QueryAlias } from '@cubejs-backend/shared';
+import { getEnv, QueryAlias } from '@cubejs-backend/shared';
 import { MssqlQuery } from '../../src/adapter/MssqlQuery';
 import { prepareCompiler } from './PrepareCompiler';
 import { createJoinedCubesSchema } from './utils';
 
+const nativeIt = getEnv('nativeSqlPlanner') ? it : it.skip;
+
 describe('MssqlQuery', () => {
   const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
     cube(\`visitors\`, {
@@ -217,4 +219,19 @@ describe('MssqlQuery', () => {
     const re = new RegExp(`(GROUP BY)(\n|.)+("${QueryAlias.AGG_SUB_QUERY_KEYS}"\."e__eval")`);
     expect(re.test(sql[0])).toBeTruthy();
   });
+
+  nativeIt('orders nulls with a CASE expression', async () => {
+    await compiler.compile();
+    const [sql] = new MssqlQuery(
+      { joinGraph, cubeEvaluator, compiler },
+      {
+        measures: ['visitors.count'],
+        dimensions: ['visitors.source'],
+        order: [{ id: 'visitors.source', desc: true, nulls: 'first' }],
+      }
+    ).buildSqlAndParams();
+
+    expect(sql).toMatch(/ORDER BY CASE WHEN .+ IS NULL THEN 0 ELSE 1 END,\s+\d+\s+DESC/);
+    expect(sql).not.toMatch(/IS NULL (ASC|DESC)/);
+  });
 });
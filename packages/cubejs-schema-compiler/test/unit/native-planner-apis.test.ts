@@ -0,0 +1,311 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { MysqlQuery } from '../../src/adapter/MysqlQuery';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Native planner APIs', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`visitors\`, {
+      sql: \`select * from visitors\`,
+
+      refreshKey: {
+        sql: \`SELECT MAX(created_at) FROM visitors\`
+      },
+
+      joins: {
+        sessions: {
+          relationship: 'hasMany',
+          sql: \`\${CUBE}.id = \${sessions}.visitor_id\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        },
+        revenue: {
+          type: 'sum',
+          sql: 'amount'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        source: {
+          type: 'string',
+          sql: 'source'
+        },
+        createdAt: {
+          type: 'time',
+          sql: 'created_at'
+        }
+      }
+    })
+
+    cube(\`sessions\`, {
+      sqlTable: \`analytics.sessions\`,
+
+      updatedAt: 'updatedAt',
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        updatedAt: {
+          type: 'time',
+          sql: 'updated_at'
+        }
+      }
+    })
+
+    cube(\`page_views\`, {
+      sql: \`select * from page_views\`,
+
+      refreshKey: {
+        every: '1 hour'
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      }
+    })
+  `);
+
+  const byMonth = {
+    measures: ['visitors.count'],
+    timeDimensions: [{
+      dimension: 'visitors.createdAt',
+      granularity: 'month',
+      dateRange: ['2024-01-01', '2024-03-31'],
+    }],
+  };
+
+  function newQuery(query, QueryClass: any = PostgresQuery) {
+    return new QueryClass({ joinGraph, cubeEvaluator, compiler }, query);
+  }
+
+  // These APIs are served by Tesseract only
+  const nativeIt = (name, fn) => (getEnv('nativeSqlPlanner') ? it : it.skip)(name, async () => {
+    await compiler.compile();
+    await fn();
+  });
+
+  nativeIt('date range probe selects bounds of the dimension with filters applied', () => {
+    const [sql, params] = newQuery({
+      measures: ['visitors.count'],
+      filters: [{ member: 'visitors.source', operator: 'equals', values: ['google'] }],
+    }).buildDateRangeProbeSqlAndParams('visitors.createdAt');
+
+    expect(sql).toMatch(/min\(/i);
+    expect(sql).toMatch(/max\(/i);
+    expect(sql).toContain('created_at');
+    expect(params).toEqual(['google']);
+  });
+
+  nativeIt('freshness query is the refresh key sql of the cube', () => {
+    const [sql] = newQuery({ measures: ['visitors.count'] }).buildFreshnessSqlAndParams('visitors');
+
+    expect(sql).toContain('SELECT MAX(created_at) FROM visitors');
+  });
+
+  nativeIt('freshness query is the updatedAt watermark of the cube without refresh key sql', () => {
+    const [sql, params] = newQuery({ measures: ['sessions.count'] }).buildFreshnessSqlAndParams('sessions');
+
+    expect(sql).toMatch(/MAX\(/);
+    expect(sql).toContain('updated_at');
+    expect(sql).toContain('sessions');
+    expect(params).toEqual([]);
+  });
+
+  nativeIt('freshness query is undefined for cubes refreshed by interval only', () => {
+    expect(newQuery({ measures: ['page_views.count'] }).buildFreshnessSqlAndParams('page_views')).toBeUndefined();
+  });
+
+  it('refresh key of the cube with updatedAt is its watermark', async () => {
+    await compiler.compile();
+
+    const [[sql]] = newQuery({ measures: ['sessions.count'] }).cacheKeyQueries();
+
+    expect(sql).toMatch(/MAX\(/);
+    expect(sql).toContain('updated_at');
+  });
+
+  nativeIt('row count estimate wraps the query into the dialect estimate', () => {
+    const [sql, params, strategy] = newQuery(byMonth).buildRowCountEstimateSqlAndParams();
+
+    expect(sql).toMatch(/^EXPLAIN \(FORMAT JSON\)/);
+    expect(sql).toContain('visitors');
+    expect(params.length).toBeGreaterThan(0);
+    expect(strategy).toEqual('explain_json');
+  });
+
+  nativeIt('row count estimate of MySQL reads statistics of tables of the query', () => {
+    const [sql, , strategy] = newQuery({ measures: ['sessions.count'] }, MysqlQuery).buildRowCountEstimateSqlAndParams();
+
+    expect(sql).toMatch(/^SELECT MAX\(TABLE_ROWS\)/);
+    expect(sql).toContain('IN (\'analytics.sessions\')');
+    expect(strategy).toEqual('scalar');
+  });
+
+  it('row count estimate is read from the result by its strategy', async () => {
+    await compiler.compile();
+    const query = newQuery(byMonth);
+
+    expect(query.rowCountEstimateFromResult('explain_json', [
+      { 'QUERY PLAN': JSON.stringify([{ Plan: { 'Plan Rows': 42 } }]) },
+    ])).toEqual(42);
+    expect(query.rowCountEstimateFromResult('scalar', [{ row_count: '1000' }])).toEqual(1000);
+    expect(query.rowCountEstimateFromResult('scalar', [])).toBeNull();
+    expect(() => query.rowCountEstimateFromResult('unknown', [{ row_count: 1 }]))
+      .toThrow(/Unknown row count estimate strategy/);
+  });
+
+  nativeIt('total count ignores paging of the query', () => {
+    const [sql] = newQuery({ ...byMonth, limit: 10, offset: 20 }).buildTotalCountSqlAndParams();
+
+    expect(sql).toMatch(/count\(\*\)/i);
+    expect(sql).not.toMatch(/LIMIT 10/);
+    expect(sql).not.toMatch(/OFFSET 20/);
+  });
+
+  nativeIt('gaps of the time series are filled by policies of measures', () => {
+    const [sql, , , postProcessing] = newQuery({
+      ...byMonth,
+      measures: ['visitors.count', 'visitors.revenue'],
+      fillPolicies: { 'visitors.count': 'zero', 'visitors.revenue': 'linear' },
+    }).buildSqlAndParams();
+
+    expect(sql).toContain('COALESCE(');
+    expect(sql).toMatch(/LEFT JOIN/i);
+    expect(postProcessing).toEqual(['visitors.revenue']);
+  });
+
+  nativeIt('gaps of queries with other dimensions are filled in post-processing', () => {
+    const [sql, , , postProcessing] = newQuery({
+      ...byMonth,
+      dimensions: ['visitors.source'],
+      fillPolicies: { 'visitors.count': 'previous' },
+    }).buildSqlAndParams();
+
+    expect(sql).not.toMatch(/OVER \(/);
+    expect(postProcessing).toEqual(['visitors.count']);
+  });
+
+  nativeIt('plan hash is the same for every dialect while sql hash is not', () => {
+    const postgres = newQuery(byMonth).sqlHashes();
+    const mysql = newQuery(byMonth, MysqlQuery).sqlHashes();
+
+    expect(postgres.planHash).toEqual(mysql.planHash);
+    expect(postgres.sqlHash).not.toEqual(mysql.sqlHash);
+    expect(newQuery({ ...byMonth, measures: ['visitors.revenue'] }).sqlHashes().planHash)
+      .not.toEqual(postgres.planHash);
+  });
+
+  nativeIt('plan hash ignores order of members and filters', () => {
+    const source = { member: 'visitors.source', operator: 'equals', values: ['google'] };
+    const revenue = { member: 'visitors.revenue', operator: 'gt', values: ['100'] };
+    const hash = newQuery({
+      ...byMonth,
+      measures: ['visitors.count', 'visitors.revenue'],
+      filters: [source, revenue],
+    }).sqlHashes().planHash;
+
+    expect(newQuery({
+      ...byMonth,
+      measures: ['visitors.revenue', 'visitors.count'],
+      filters: [revenue, source],
+    }).sqlHashes().planHash).toEqual(hash);
+  });
+
+  nativeIt('explain returns members and filters of the plan', () => {
+    const explain = newQuery({
+      ...byMonth,
+      filters: [{ member: 'visitors.source', operator: 'equals', values: ['google'] }],
+    }).explainPlan();
+
+    expect(explain.measures).toEqual(['visitors.count']);
+    expect(explain.timeDimensions).toEqual([expect.objectContaining({
+      dimension: 'visitors.createdAt',
+      granularity: 'month',
+    })]);
+    expect(explain.filters.dimensions.length).toEqual(1);
+    expect(explain.plan).toBeDefined();
+  });
+
+  nativeIt('compiled predicate binds placeholder values on each call', () => {
+    const predicate = newQuery({
+      measures: ['visitors.count'],
+      filters: [{ member: 'visitors.createdAt', operator: 'inDateRange', placeholder: 'partition' }],
+    }).compileFilterPredicate();
+
+    const [sql, params] = predicate({ partition_from: '2024-01-01T00:00:00.000', partition_to: '2024-01-31T23:59:59.999' });
+    const [, otherParams] = predicate({ partition_from: '2024-02-01T00:00:00.000', partition_to: '2024-02-29T23:59:59.999' });
+
+    expect(sql).toContain('created_at');
+    expect(params).toEqual(['2024-01-01T00:00:00.000', '2024-01-31T23:59:59.999']);
+    expect(otherParams).toEqual(['2024-02-01T00:00:00.000', '2024-02-29T23:59:59.999']);
+    expect(() => predicate({ partition_from: '2024-01-01T00:00:00.000' })).toThrow(/partition_to/);
+    expect(newQuery({ measures: ['visitors.count'] }).compileFilterPredicate()).toBeUndefined();
+  });
+
+  nativeIt('saved queries are planned with filters of the request', () => {
+    const savedQueries = [
+      { name: 'by_source', query: { measures: ['visitors.count'], dimensions: ['visitors.source'] } },
+      { name: 'broken', query: { measures: ['visitors.unknown'] } },
+    ];
+    const query = newQuery({
+      measures: ['visitors.count'],
+      filters: [{ member: 'visitors.id', operator: 'equals', values: ['42'] }],
+    });
+
+    const [sql, params] = query.buildSavedQuerySqlAndParams(savedQueries, 'by_source');
+
+    expect(sql).toContain('source');
+    expect(params).toEqual(['42']);
+    expect(() => query.buildSavedQuerySqlAndParams(savedQueries, 'missing')).toThrow(/Saved query 'missing' is not found/);
+    expect(query.validateSavedQueries(savedQueries)).toEqual([
+      expect.objectContaining({ name: 'broken', valid: false }),
+      expect.objectContaining({ name: 'by_source', valid: true }),
+    ]);
+  });
+
+  nativeIt('variants are planned after the base query and fail independently', () => {
+    const results = newQuery(byMonth).buildSqlAndParamsVariants([
+      { addFilters: [{ member: 'visitors.source', operator: 'equals', values: ['google'] }] },
+      { swapMeasures: { 'visitors.count': 'visitors.unknown' } },
+      { dateRanges: { 'visitors.createdAt': ['2024-02-01', '2024-02-29'] } },
+    ]);
+
+    expect(results.length).toEqual(4);
+    expect(results[0][1]).not.toContain('google');
+    expect(results[1][1]).toContain('google');
+    expect(results[2].error).toBeDefined();
+    expect(results[3][1]).toContain('2024-02-01T00:00:00.000');
+  });
+
+  nativeIt('variants keeping some of the measures prune joins of the rest', () => {
+    const [base, sparkline] = newQuery({
+      measures: ['visitors.count', 'sessions.count'],
+    }).buildSqlAndParamsVariants([
+      { keepMeasures: ['visitors.count'] },
+    ]);
+
+    expect(base[0]).toContain('analytics.sessions');
+    expect(sparkline[0]).not.toContain('analytics.sessions');
+    expect(sparkline[0]).toMatch(/count\(/i);
+  });
+});
@@ -0,0 +1,93 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { UserError } from '../../src/compiler/UserError';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+describeNative('Native planner errors', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`visitors\`, {
+      sql: \`select * from visitors\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        createdAt: {
+          type: 'time',
+          sql: 'created_at'
+        }
+      }
+    })
+  `);
+
+  function buildError(query) {
+    try {
+      new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query).buildSqlAndParams();
+    } catch (e) {
+      return e;
+    }
+    throw new Error('Query is expected to fail');
+  }
+
+  it('invalid filter value is a user error with suggestion', async () => {
+    await compiler.compile();
+
+    const error = buildError({
+      measures: ['visitors.count'],
+      filters: [{
+        member: 'visitors.createdAt',
+        operator: 'inDateRange',
+        values: ['2024/1/5', '2024-02-01'],
+      }],
+    });
+
+    expect(error).toBeInstanceOf(UserError);
+    expect(error.message).toContain('Did you mean \'2024-01-05\'?');
+    expect(error.meta).toEqual(expect.objectContaining({
+      member: 'visitors.createdAt',
+      valueIndex: '0',
+      suggestion: '2024-01-05',
+    }));
+  });
+
+  it('ordering comparison with NULL is a user error with code', async () => {
+    await compiler.compile();
+
+    const error = buildError({
+      measures: ['visitors.count'],
+      filters: [{
+        member: 'visitors.id',
+        operator: 'gt',
+        values: [null],
+      }],
+    });
+
+    expect(error).toBeInstanceOf(UserError);
+    expect(error.meta).toEqual(expect.objectContaining({
+      member: 'visitors.id',
+      code: 'NULL_ORDERING_COMPARISON',
+    }));
+  });
+
+  it('unknown member is not a process crash', async () => {
+    await compiler.compile();
+
+    const error = buildError({
+      measures: ['visitors.count'],
+      dimensions: ['visitors.unknown'],
+    });
+
+    expect(error).toBeInstanceOf(Error);
+  });
+});
@@ -0,0 +1,52 @@
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Open date ranges', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        createdAt: {
+          type: 'time',
+          sql: 'created_at'
+        }
+      }
+    })
+  `);
+
+  const buildSqlAndParams = async (dateRange) => {
+    await compiler.compile();
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['orders.count'],
+      filters: [{ member: 'orders.createdAt', operator: 'inDateRange', values: dateRange }],
+    }).buildSqlAndParams();
+  };
+
+  it('filters everything since the start of the range', async () => {
+    const [sql, params] = await buildSqlAndParams(['2024-01-01', null]);
+
+    expect(sql).toMatch(/"orders"\.created_at >= \$1::timestamptz/);
+    expect(sql).not.toMatch(/created_at <=? /);
+    expect(params).toEqual(['2024-01-01T00:00:00.000Z']);
+  });
+
+  it('filters everything until the end of the range', async () => {
+    const [sql, params] = await buildSqlAndParams([null, '2024-01-31']);
+
+    expect(sql).toMatch(/"orders"\.created_at <= \$1::timestamptz/);
+    expect(sql).not.toMatch(/created_at >= /);
+    expect(params).toEqual(['2024-01-31T23:59:59.999Z']);
+  });
+});
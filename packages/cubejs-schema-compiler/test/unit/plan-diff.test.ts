@@ -0,0 +1,75 @@
+import { diffQueryPlans } from '../../src/adapter/PlanDiff';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Plan diff', () => {
+  const schema = (amountSql) => `
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        },
+        total: {
+          type: 'sum',
+          sql: '${amountSql}'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        status: {
+          type: 'string',
+          sql: 'status'
+        }
+      }
+    })
+  `;
+
+  const before = prepareCompiler(schema('amount'));
+  const after = prepareCompiler(schema('amount_usd'));
+
+  beforeAll(async () => {
+    await before.compiler.compile();
+    await after.compiler.compile();
+  });
+
+  it('reports changed measures of queries affected by the schema change', () => {
+    const [total, count] = diffQueryPlans(before, after, 'postgres', [
+      { measures: ['orders.total'], dimensions: ['orders.status'] },
+      { measures: ['orders.count'], dimensions: ['orders.status'] },
+    ]);
+
+    expect(total.changed).toBe(true);
+    expect(total.measures).toEqual({
+      added: [],
+      removed: [],
+      changed: [{
+        measure: 'orders.total',
+        before: expect.stringMatching(/\bamount\b/),
+        after: expect.stringContaining('amount_usd'),
+      }],
+    });
+    expect(total.joins).toEqual({ added: [], removed: [] });
+    expect(total.filters).toEqual({ added: [], removed: [] });
+
+    expect(count.changed).toBe(false);
+    expect(count.measures.changed).toEqual([]);
+  });
+
+  it('reports planning errors per query', () => {
+    const [missing, count] = diffQueryPlans(before, after, 'postgres', [
+      { measures: ['orders.missing'] },
+      { measures: ['orders.count'] },
+    ]);
+
+    expect(missing.changed).toBe(false);
+    expect(missing.errors.before).toBeDefined();
+    expect(missing.errors.after).toEqual(missing.errors.before);
+    expect(count.changed).toBe(false);
+  });
+});
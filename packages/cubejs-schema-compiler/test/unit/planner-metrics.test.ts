@@ -0,0 +1,123 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+const nativeIt = getEnv('nativeSqlPlanner') ? it : it.skip;
+const jsIt = getEnv('nativeSqlPlanner') ? it.skip : it;
+
+describe('Planner metrics', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`visitors\`, {
+      sql: \`select * from visitors\`,
+
+      refreshKey: {
+        every: '1 hour'
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        status: {
+          type: 'string',
+          sql: 'status'
+        }
+      },
+
+      preAggregations: {
+        byStatus: {
+          type: 'rollup',
+          measures: [CUBE.count],
+          dimensions: [CUBE.status],
+          external: true
+        }
+      }
+    })
+  `);
+
+  function newQuery(options: any = { measures: ['visitors.count'] }) {
+    const counters = [];
+    const metricsRecorder = {
+      incrementCounter: (name, value, labels) => counters.push({ name, value, labels }),
+      recordHistogram: () => undefined,
+    };
+    const query = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      ...options,
+      metricsRecorder,
+    });
+    return {
+      query,
+      counters: () => counters,
+      fallbacks: () => counters.filter(c => c.name === 'planner_fallbacks'),
+    };
+  }
+
+  jsIt('counts queries planned in JS without Tesseract', async () => {
+    await compiler.compile();
+
+    const { query, fallbacks } = newQuery();
+    query.buildSqlAndParams();
+
+    expect(fallbacks()).toEqual([
+      { name: 'planner_fallbacks', value: 1, labels: { planner: 'js', reason: 'native_planner_disabled' } },
+    ]);
+  });
+
+  jsIt('counts a query with a pre-aggregation once', async () => {
+    await compiler.compile();
+
+    const { query, counters } = newQuery({
+      measures: ['orders.count'],
+      dimensions: ['orders.status'],
+      externalQueryClass: PostgresQuery,
+    });
+    // Planned by the external query of the pre-aggregation
+    query.buildSqlAndParams();
+    query.buildSqlAndParams();
+    // Builds SQL of the rollup with a pre-aggregation query
+    query.preAggregations.preAggregationsDescription();
+
+    expect(counters()).toEqual([
+      { name: 'plans_built', value: 1, labels: { planner: 'js' } },
+      { name: 'planner_fallbacks', value: 1, labels: { planner: 'js', reason: 'native_planner_disabled' } },
+    ]);
+  });
+
+  nativeIt('counts refresh keys rendered in JS with Tesseract', async () => {
+    await compiler.compile();
+
+    const { query, fallbacks } = newQuery();
+    query.buildSqlAndParams();
+    query.cacheKeyQueries();
+
+    expect(fallbacks()).toEqual([
+      { name: 'planner_fallbacks', value: 1, labels: { planner: 'js', reason: 'refresh_key_every' } },
+    ]);
+  });
+});
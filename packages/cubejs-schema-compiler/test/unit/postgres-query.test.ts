@@ -114,4 +114,33 @@ describe('PostgresQuery', () => {
       expect(queryAndParams[0]).toContain(expected);
     }
   });
+
+  it('test case sensitive like filters', async () => {
+    await compiler.compile();
+
+    const operatorVariants = [
+      ['contains', /"visitors"\.name LIKE '%' \|\| \$1 ?\|\| '%'/],
+      ['notStartsWith', /"visitors"\.name NOT LIKE \$1 ?\|\| '%'/],
+      ['endsWith', /"visitors"\.name LIKE '%' \|\| \$1/],
+    ];
+
+    for (const [operator, expected] of operatorVariants) {
+      const query = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+        measures: [
+          'visitors.count'
+        ],
+        filters: [{
+          member: 'visitors.name',
+          operator,
+          values: ['Alice'],
+          caseSensitive: true,
+        }],
+      });
+
+      const [sql, params] = query.buildSqlAndParams();
+      expect(sql).toMatch(expected);
+      expect(sql).not.toMatch(/ILIKE/);
+      expect(params).toEqual(['Alice']);
+    }
+  });
 });
@@ -268,6 +268,39 @@ describe('Schema Testing', () => {
     expect(dimensions.find((dimension) => dimension.name === 'CubeA.type').primaryKey).toBe(false);
   });
 
+  it('joins in meta', async () => {
+    const { compiler, metaTransformer } = prepareCompiler(`
+      cube('orders', {
+        sql_table: 'orders',
+        joins: {
+          customers: {
+            relationship: 'many_to_one',
+            sql: \`\${CUBE}.customer_id = \${customers}.id\`,
+          },
+        },
+        dimensions: {
+          id: { sql: 'id', type: 'number', primary_key: true },
+        },
+      });
+
+      cube('customers', {
+        sql_table: 'customers',
+        dimensions: {
+          id: { sql: 'id', type: 'number', primary_key: true },
+        },
+      });
+    `);
+    await compiler.compile();
+
+    const orders = metaTransformer.cubes.find((cube) => cube.config.name === 'orders');
+    const customers = metaTransformer.cubes.find((cube) => cube.config.name === 'customers');
+
+    expect(orders.config.joins).toMatchObject([{ name: 'customers', relationship: 'belongsTo' }]);
+    // eslint-disable-next-line no-template-curly-in-string
+    expect(orders.config.joins[0].sql).toContain('${CUBE}.customer_id = ${customers}.id');
+    expect(customers.config.joins).toBeUndefined();
+  });
+
   it('descriptions', async () => {
     const { compiler, metaTransformer } = prepareCompiler([
       createCubeSchema({
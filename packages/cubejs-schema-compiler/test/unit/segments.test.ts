@@ -0,0 +1,134 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+describeNative('Segments', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      joins: {
+        customers: {
+          relationship: 'many_to_one',
+          sql: \`\${CUBE}.customer_id = \${customers}.id\`
+        }
+      },
+
+      segments: {
+        completed: {
+          sql: \`\${CUBE}.status = 'completed'\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        },
+        revenue: {
+          type: 'sum',
+          sql: 'amount'
+        },
+        revenueDayAgo: {
+          multi_stage: true,
+          type: 'sum',
+          sql: \`\${revenue}\`,
+          time_shift: [{
+            time_dimension: createdAt,
+            interval: '1 day',
+            type: 'prior',
+          }]
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        amount: {
+          type: 'number',
+          sql: 'amount'
+        },
+        createdAt: {
+          type: 'time',
+          sql: 'created_at'
+        }
+      }
+    })
+
+    cube(\`customers\`, {
+      sql: \`select * from customers\`,
+
+      segments: {
+        vip: {
+          sql: \`\${CUBE}.tier = 'vip'\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+  `);
+
+  const buildSqlAndParams = async (query) => {
+    await compiler.compile();
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query).buildSqlAndParams();
+  };
+
+  it('ANDs a segment with filters', async () => {
+    const [sql, params] = await buildSqlAndParams({
+      measures: ['orders.count'],
+      segments: ['orders.completed'],
+      filters: [{ member: 'orders.amount', operator: 'gt', values: ['10'] }],
+    });
+
+    const where = sql.slice(sql.indexOf('WHERE'));
+    expect(where).toMatch(/"orders"\.amount > \$1/);
+    expect(where).toMatch(/\("orders"\.status = 'completed'\)/);
+    expect(where).toMatch(/\)\s+AND\s+\(/);
+    expect(params).toEqual(['10']);
+  });
+
+  it('joins the cube of a segment', async () => {
+    const [sql, params] = await buildSqlAndParams({
+      measures: ['orders.count'],
+      segments: ['customers.vip'],
+    });
+
+    expect(sql).toMatch(/LEFT JOIN \(select \* from customers\) AS "customers"/);
+    expect(sql).toMatch(/WHERE \("customers"\.tier = 'vip'\)/);
+    expect(params).toEqual([]);
+  });
+
+  it('applies a segment to every scan of a multi-stage query', async () => {
+    const [sql] = await buildSqlAndParams({
+      measures: ['orders.revenue', 'orders.revenueDayAgo'],
+      timeDimensions: [{
+        dimension: 'orders.createdAt',
+        granularity: 'day',
+        dateRange: ['2024-01-01', '2024-01-31'],
+      }],
+      segments: ['orders.completed'],
+    });
+
+    const scans = sql.match(/FROM \(select \* from orders\) AS "orders"/g) || [];
+    const segments = sql.match(/"orders"\.status = 'completed'/g) || [];
+    expect(scans.length).toBeGreaterThan(1);
+    expect(segments.length).toEqual(scans.length);
+  });
+});
@@ -0,0 +1,88 @@
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Snapshot cubes', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      joins: {
+        customers: {
+          relationship: 'many_to_one',
+          sql: \`\${CUBE}.customer_id = \${customers}.id\`,
+          validAt: \`\${CUBE}.created_at\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+
+    cube(\`customers\`, {
+      sql: \`select * from customers\`,
+
+      validity: {
+        validFrom: 'valid_from',
+        validTo: 'valid_to'
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        tier: {
+          type: 'string',
+          sql: 'tier'
+        }
+      }
+    })
+  `);
+
+  async function buildSqlAndParams(query) {
+    await compiler.compile();
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query).buildSqlAndParams();
+  }
+
+  it('joins the version valid at the joining row time', async () => {
+    const [sql] = await buildSqlAndParams({
+      measures: ['orders.count'],
+      dimensions: ['customers.tier'],
+    });
+
+    expect(sql).toMatch(/"orders"\.created_at >= "customers"\.valid_from AND \("customers"\.valid_to IS NULL OR "orders"\.created_at < "customers"\.valid_to\)/);
+  });
+
+  it('filters snapshot cubes at the asOf time', async () => {
+    const [sql, params] = await buildSqlAndParams({
+      measures: ['orders.count'],
+      dimensions: ['customers.tier'],
+      asOf: '2024-03-01T00:00:00.000',
+    });
+
+    expect(sql).toMatch(/>= valid_from AND \(valid_to IS NULL OR \$\d+::timestamptz < valid_to\)/);
+    expect(sql).not.toMatch(/"orders"\.created_at >= "customers"\.valid_from/);
+    expect(params).toContain('2024-03-01T00:00:00.000Z');
+  });
+
+  it('keeps cubes without validity unfiltered', async () => {
+    const [sql] = await buildSqlAndParams({
+      measures: ['orders.count'],
+      asOf: '2024-03-01T00:00:00.000',
+    });
+
+    expect(sql).not.toMatch(/valid_from/);
+  });
+});
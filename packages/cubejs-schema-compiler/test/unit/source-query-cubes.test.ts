@@ -0,0 +1,89 @@
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Cubes sourced from queries', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        total: {
+          type: 'sum',
+          sql: 'amount'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        customerId: {
+          type: 'number',
+          sql: 'customer_id'
+        }
+      }
+    })
+
+    cube(\`customer_totals\`, {
+      sourceQuery: {
+        measures: ['orders.total'],
+        dimensions: ['orders.customerId']
+      },
+
+      measures: {
+        avgTotal: {
+          type: 'avg',
+          sql: \`\${CUBE}.orders__total\`
+        }
+      },
+
+      dimensions: {
+        customerId: {
+          type: 'number',
+          sql: \`\${CUBE}.orders__customer_id\`,
+          primaryKey: true
+        }
+      }
+    })
+
+    cube(\`materialized_totals\`, {
+      sourceQuery: {
+        measures: ['orders.total'],
+        dimensions: ['orders.customerId'],
+        materialized: 'rollups.customer_totals'
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      }
+    })
+  `);
+
+  function buildSql(query) {
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query).buildSqlAndParams()[0];
+  }
+
+  it('plans the source query as a subquery of the cube', async () => {
+    await compiler.compile();
+
+    const sql = buildSql({ measures: ['customer_totals.avgTotal'] });
+
+    expect(sql).toMatch(/FROM \(\s*SELECT/i);
+    expect(sql).toContain('select * from orders');
+    expect(sql).toMatch(/sum\(/i);
+    expect(sql).toMatch(/avg\(/i);
+  });
+
+  it('reads materialized source queries from their table', async () => {
+    await compiler.compile();
+
+    const sql = buildSql({ measures: ['materialized_totals.count'] });
+
+    expect(sql).toContain('rollups.customer_totals');
+    expect(sql).not.toContain('select * from orders');
+  });
+});
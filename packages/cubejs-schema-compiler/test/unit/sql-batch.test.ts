@@ -0,0 +1,79 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { BaseQuery } from '../../src/adapter/BaseQuery';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+const nativeIt = getEnv('nativeSqlPlanner') ? it : it.skip;
+
+describe('SQL batch', () => {
+  const schema = (table) => `
+    cube(\`visitors\`, {
+      sql: \`select * from ${table}\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        createdAt: {
+          type: 'time',
+          sql: 'created_at'
+        }
+      }
+    })
+  `;
+
+  const first = prepareCompiler(schema('first_visitors'));
+  const second = prepareCompiler(schema('second_visitors'));
+
+  function newQuery({ compiler, joinGraph, cubeEvaluator }, query) {
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query);
+  }
+
+  beforeAll(async () => {
+    await first.compiler.compile();
+    await second.compiler.compile();
+  });
+
+  it('plans queries of other schemas against their own schema', async () => {
+    const results = BaseQuery.buildSqlAndParamsBatch([
+      newQuery(first, { measures: ['visitors.count'] }),
+      newQuery(second, { measures: ['visitors.count'] }),
+      newQuery(first, { measures: ['visitors.count'], dimensions: ['visitors.id'] }),
+    ]);
+
+    expect(results.length).toEqual(3);
+    expect(results[0][0]).toContain('first_visitors');
+    expect(results[1][0]).toContain('second_visitors');
+    expect(results[1][0]).not.toContain('first_visitors');
+    expect(results[2][0]).toContain('first_visitors');
+  });
+
+  nativeIt('failed query keeps the structured meta of the error', async () => {
+    const results = BaseQuery.buildSqlAndParamsBatch([
+      newQuery(first, {
+        measures: ['visitors.count'],
+        filters: [{
+          member: 'visitors.createdAt',
+          operator: 'inDateRange',
+          values: ['2024/1/5', '2024-02-01'],
+        }],
+      }),
+      newQuery(first, { measures: ['visitors.count'] }),
+    ]);
+
+    expect(results[1][0]).toContain('first_visitors');
+    expect(results[0].error).toContain('Did you mean \'2024-01-05\'?');
+    expect(results[0].meta).toEqual(expect.objectContaining({
+      member: 'visitors.createdAt',
+      suggestion: '2024-01-05',
+    }));
+  });
+});
@@ -0,0 +1,79 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+describeNative('startsWith range predicate', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`products\`, {
+      sql: \`select * from products\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        sku: {
+          type: 'string',
+          sql: 'sku',
+          startsWithRange: true
+        },
+        name: {
+          type: 'string',
+          sql: 'name'
+        }
+      }
+    })
+  `);
+
+  async function buildSqlAndParams(filter) {
+    await compiler.compile();
+    const query = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['products.count'],
+      filters: [filter],
+    });
+    return query.buildSqlAndParams();
+  }
+
+  it('narrows startsWith to a range on opted in members', async () => {
+    const [sql, params] = await buildSqlAndParams({
+      member: 'products.sku',
+      operator: 'startsWith',
+      values: ['ab', 'xz'],
+    });
+
+    expect(sql).toMatch(/"products"\.sku >= \$\d+ AND "products"\.sku < \$\d+/);
+    expect(sql).toMatch(/LIKE/);
+    expect(params).toEqual(expect.arrayContaining(['ab', 'ac', 'xz', 'x{']));
+  });
+
+  it('keeps LIKE only on other members', async () => {
+    const [sql] = await buildSqlAndParams({
+      member: 'products.name',
+      operator: 'startsWith',
+      values: ['ab'],
+    });
+
+    expect(sql).not.toMatch(/"products"\.name >= /);
+    expect(sql).toMatch(/LIKE/);
+  });
+
+  it('keeps LIKE only for null values', async () => {
+    const [sql] = await buildSqlAndParams({
+      member: 'products.sku',
+      operator: 'startsWith',
+      values: ['ab', null],
+    });
+
+    expect(sql).not.toMatch(/"products"\.sku >= /);
+  });
+});
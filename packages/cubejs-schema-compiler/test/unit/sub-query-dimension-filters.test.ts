@@ -0,0 +1,73 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+describeNative('Filters on subQuery dimensions', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`customers\`, {
+      sql: \`select * from customers\`,
+
+      joins: {
+        orders: {
+          relationship: 'one_to_many',
+          sql: \`\${CUBE}.id = \${orders}.customer_id\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        ordersAmount: {
+          type: 'number',
+          sql: \`\${orders.amount}\`,
+          subQuery: true
+        }
+      }
+    })
+
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        amount: {
+          type: 'sum',
+          sql: 'amount'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+  `);
+
+  it('filters by an EXISTS correlated on the primary key', async () => {
+    await compiler.compile();
+    const [sql, params] = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['customers.count'],
+      filters: [{ member: 'customers.ordersAmount', operator: 'gt', values: ['100'] }],
+    }).buildSqlAndParams();
+
+    expect(sql).toMatch(/EXISTS \(SELECT 1 FROM \(SELECT/);
+    expect(sql).toMatch(/"customers_sub_query_customers"\.id/);
+    expect(sql).toMatch(/GROUP BY/);
+    expect(sql).toMatch(/"sub_query"\."customers__id" = "customers"\.id AND \("sub_query"\."customers__orders_amount" > \$\d+/);
+    expect(params).toEqual(['100']);
+  });
+});
@@ -0,0 +1,91 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { MysqlQuery } from '../../src/adapter/MysqlQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+describeNative('Subtotals', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        status: {
+          type: 'string',
+          sql: 'status'
+        },
+        city: {
+          type: 'string',
+          sql: 'city'
+        },
+        country: {
+          type: 'string',
+          sql: 'country'
+        },
+        channel: {
+          type: 'string',
+          sql: 'channel'
+        }
+      }
+    })
+  `);
+
+  const query = {
+    measures: ['orders.count'],
+    dimensions: ['orders.status', 'orders.city'],
+  };
+
+  it('groups by rollup with a grouping id column', async () => {
+    await compiler.compile();
+    const [sql] = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      ...query,
+      subtotals: 'rollup',
+    }).buildSqlAndParams();
+
+    expect(sql).toMatch(/GROUP BY ROLLUP\("orders"\.status, "orders"\.city\)/);
+    expect(sql).toMatch(/GROUPING\("orders"\.status, "orders"\.city\) "__grouping_id"/);
+  });
+
+  it('adds the detailed grouping to grouping sets', async () => {
+    await compiler.compile();
+    const [sql] = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      ...query,
+      groupingSets: [['orders.status'], []],
+    }).buildSqlAndParams();
+
+    expect(sql).toMatch(/GROUPING SETS \(\("orders"\.status, "orders"\.city\), \("orders"\.status\), \(\)\)/);
+  });
+
+  it('falls back to a union of groupings without grouping sets support', async () => {
+    await compiler.compile();
+    const [sql] = new MysqlQuery({ joinGraph, cubeEvaluator, compiler }, {
+      ...query,
+      subtotals: 'rollup',
+    }).buildSqlAndParams();
+
+    expect(sql.split('UNION ALL').length).toEqual(3);
+    expect(sql).toMatch(/3 `__grouping_id`/);
+  });
+
+  it('rejects cube subtotals exceeding the union limit', async () => {
+    await compiler.compile();
+    expect(() => new MysqlQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['orders.count'],
+      dimensions: ['orders.id', 'orders.status', 'orders.city', 'orders.country', 'orders.channel'],
+      subtotals: 'cube',
+    }).buildSqlAndParams()).toThrow(/exceed 16 groupings/);
+  });
+});
@@ -0,0 +1,69 @@
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Table name resolver', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`events\`, {
+      sql_table: 'public.events',
+
+      joins: {
+        users: {
+          relationship: 'belongsTo',
+          sql: \`\${CUBE}.user_id = \${users}.id\`
+        }
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        }
+      }
+    })
+
+    cube(\`users\`, {
+      sql: \`select * from public.users\`,
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        name: {
+          type: 'string',
+          sql: 'name'
+        }
+      }
+    })
+  `);
+
+  it('routes cube sources to tables chosen by the resolver', async () => {
+    await compiler.compile();
+
+    const calls = [];
+    const tableNameResolver = {
+      resolveTableName: (cube, table) => {
+        calls.push([cube, table]);
+        return cube === 'events' ? `${table}_123` : table;
+      },
+    };
+    const [sql] = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['events.count'],
+      dimensions: ['users.name'],
+      tableNameResolver,
+    }).buildSqlAndParams();
+
+    expect(sql).toContain('public.events_123');
+    expect(sql).toContain('public.users');
+    expect(sql).not.toContain('public.users_123');
+    expect(calls).toContainEqual(['events', 'public.events']);
+  });
+});
@@ -0,0 +1,49 @@
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+describe('Table overrides', () => {
+  const schema = `
+    cube(\`orders\`, {
+      sql_table: 'public.orders',
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      }
+    })
+  `;
+
+  beforeAll(() => {
+    process.env.CUBEJS_TABLE_OVERRIDES_ALLOW_LIST = 'sandbox.*';
+  });
+
+  afterAll(() => {
+    delete process.env.CUBEJS_TABLE_OVERRIDES_ALLOW_LIST;
+  });
+
+  async function buildSql(table) {
+    const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(schema, {
+      compileContext: { tableOverrides: { orders: table } },
+    });
+    await compiler.compile();
+    const query = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, { measures: ['orders.count'] });
+    return query.buildSqlAndParams()[0];
+  }
+
+  it('quotes allowed overrides', async () => {
+    const sql = await buildSql('sandbox.orders');
+
+    expect(sql).toContain('FROM "sandbox"."orders"');
+    expect(sql).not.toContain('public.orders');
+  });
+
+  it('rejects overrides which are not table names', async () => {
+    await expect(buildSql('sandbox.orders; DROP TABLE users')).rejects.toThrow(/isn't a 'schema.table' name/);
+    await expect(buildSql('sandbox.(SELECT * FROM users)')).rejects.toThrow(/isn't a 'schema.table' name/);
+  });
+
+  it('rejects tables outside of the allow list', async () => {
+    await expect(buildSql('public.users')).rejects.toThrow(/isn't allowed as an override/);
+  });
+});
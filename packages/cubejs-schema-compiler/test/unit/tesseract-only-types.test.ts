@@ -0,0 +1,170 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { CrateQuery } from '../../src/adapter/CrateQuery';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+const nativeIt = getEnv('nativeSqlPlanner') ? it : it.skip;
+const jsIt = getEnv('nativeSqlPlanner') ? it.skip : it;
+
+describe('Tesseract only dimension types', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`files\`, {
+      sql: \`select * from files\`,
+
+      measures: {
+        count: {
+          type: 'count'
+        },
+        size: {
+          type: 'sum',
+          sql: 'size',
+          rounding: { scale: 2 }
+        },
+        averageSize: {
+          type: 'avg',
+          sql: 'size',
+          decimal: { precision: 18, scale: 4 }
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'uuid',
+          sql: 'id',
+          primaryKey: true
+        },
+        checksum: {
+          type: 'binary',
+          sql: 'checksum'
+        },
+        name: {
+          type: 'string',
+          sql: 'name'
+        },
+        owner: {
+          type: 'string',
+          sql: 'owner',
+          sensitive: true
+        },
+        createdAt: {
+          type: 'time',
+          sql: 'created_at'
+        },
+        updatedAt: {
+          type: 'time',
+          sql: 'updated_at'
+        },
+        ageDays: {
+          type: 'number',
+          duration: { from: 'createdAt', to: 'updatedAt', unit: 'day' }
+        }
+      }
+    })
+  `);
+
+  function buildQuery(query) {
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query);
+  }
+
+  jsIt('rejects binary and uuid dimensions in the JS planner', async () => {
+    await compiler.compile();
+
+    expect(() => buildQuery({
+      measures: ['files.count'],
+      dimensions: ['files.checksum'],
+    })).toThrow('Dimension \'files.checksum\' of type \'binary\' requires Tesseract SQL planner');
+
+    expect(() => buildQuery({
+      measures: ['files.count'],
+      filters: [{ member: 'files.id', operator: 'equals', values: ['0e8a8b9c-1d2e-4f3a-8b4c-5d6e7f8a9b0c'] }],
+    })).toThrow('Dimension \'files.id\' of type \'uuid\' requires Tesseract SQL planner');
+  });
+
+  jsIt('rejects Tesseract only member options and filter value templates in the JS planner', async () => {
+    await compiler.compile();
+
+    expect(() => buildQuery({
+      measures: ['files.size'],
+    })).toThrow('Member \'files.size\' with \'rounding\' option requires Tesseract SQL planner');
+
+    expect(() => buildQuery({
+      measures: ['files.count'],
+      filters: [{ member: 'files.owner', operator: 'equals', values: ['alice'] }],
+    })).toThrow('Member \'files.owner\' with \'sensitive\' option requires Tesseract SQL planner');
+
+    expect(() => buildQuery({
+      measures: ['files.count'],
+      filters: [{ member: 'files.name', operator: 'equals', values: ['{{ user.folder }}'] }],
+    })).toThrow('Filter value template \'{{ user.folder }}\' requires Tesseract SQL planner');
+
+    expect(() => buildQuery({
+      measures: ['files.count'],
+      dimensions: ['files.ageDays'],
+    })).toThrow('Member \'files.ageDays\' with \'duration\' option requires Tesseract SQL planner');
+  });
+
+  nativeIt('renders duration dimensions as difference of their time members', async () => {
+    await compiler.compile();
+
+    const [sql] = buildQuery({
+      measures: ['files.count'],
+      dimensions: ['files.ageDays'],
+      filters: [{ member: 'files.ageDays', operator: 'gt', values: ['30'] }],
+    }).buildSqlAndParams();
+
+    expect(sql).toContain('DATE_TRUNC(\'day\', "files".updated_at) - DATE_TRUNC(\'day\', "files".created_at)');
+    expect(sql).toMatch(/GROUP BY/);
+  });
+
+  nativeIt('casts inputs of sums and averages to the declared decimal type', async () => {
+    await compiler.compile();
+
+    const [sql] = buildQuery({
+      measures: ['files.count', 'files.averageSize'],
+    }).buildSqlAndParams();
+
+    expect(sql).toMatch(/avg\(CAST\("files"\.size AS DECIMAL\(18,4\)\)\)/i);
+    expect(sql).not.toMatch(/count\(CAST/i);
+  });
+
+  nativeIt('rejects binary dimensions on data sources without a binary type', async () => {
+    await compiler.compile();
+
+    const buildCrateQuery = (query) => new CrateQuery({ joinGraph, cubeEvaluator, compiler }, query);
+
+    expect(() => buildCrateQuery({
+      measures: ['files.count'],
+      dimensions: ['files.checksum'],
+    }).buildSqlAndParams()).toThrow('Binary dimensions are not supported by this data source');
+
+    expect(() => buildCrateQuery({
+      measures: ['files.count'],
+      filters: [{ member: 'files.checksum', operator: 'equals', values: ['0x0a1b'] }],
+    }).buildSqlAndParams()).toThrow('Filters on binary dimensions are not supported by this data source');
+  });
+
+  nativeIt('casts uuid filter values with the template of the dialect', async () => {
+    await compiler.compile();
+
+    const filters = [{ member: 'files.id', operator: 'equals', values: ['0e8a8b9c-1d2e-4f3a-8b4c-5d6e7f8a9b0c'] }];
+
+    const [postgresSql] = buildQuery({ measures: ['files.count'], filters }).buildSqlAndParams();
+    expect(postgresSql).toMatch(/CAST\(\$1 AS UUID\)/);
+
+    // Crate stores UUIDs as strings
+    const [crateSql] = new CrateQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['files.count'],
+      filters,
+    }).buildSqlAndParams();
+    expect(crateSql).not.toMatch(/AS UUID/);
+  });
+
+  jsIt('plans queries without binary and uuid dimensions', async () => {
+    await compiler.compile();
+
+    expect(() => buildQuery({
+      measures: ['files.count'],
+      dimensions: ['files.name'],
+    }).buildSqlAndParams()).not.toThrow();
+  });
+});
@@ -0,0 +1,70 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+const nativeIt = getEnv('nativeSqlPlanner') ? it : it.skip;
+
+describe('Union cubes', () => {
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`events\`, {
+      union: {
+        timeColumn: 'created_at',
+        tables: [
+          { table: 'hot.events', since: '2024-01-01' },
+          { table: 'cold.events', until: '2024-01-01' }
+        ]
+      },
+
+      measures: {
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        createdAt: {
+          type: 'time',
+          sql: 'created_at'
+        }
+      }
+    })
+  `);
+
+  function buildSqlAndParams(dateRange?: string[]) {
+    return new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['events.count'],
+      timeDimensions: [{ dimension: 'events.createdAt', granularity: 'month', dateRange }],
+    }).buildSqlAndParams();
+  }
+
+  it('unions all tables of the cube', async () => {
+    await compiler.compile();
+
+    const [sql] = buildSqlAndParams();
+
+    expect(sql).toContain('SELECT * FROM hot.events UNION ALL SELECT * FROM cold.events');
+  });
+
+  nativeIt('skips tables outside of the query date range', async () => {
+    await compiler.compile();
+
+    const [sql, params] = buildSqlAndParams(['2024-02-01', '2024-02-29']);
+
+    expect(sql).toContain('SELECT * FROM hot.events WHERE created_at >= ');
+    expect(sql).not.toContain('cold.events');
+    expect(params).toEqual(expect.arrayContaining(['2024-02-01T00:00:00.000Z', '2024-02-29T23:59:59.999Z']));
+  });
+
+  nativeIt('filters every table by the query date range across the boundary', async () => {
+    await compiler.compile();
+
+    const [sql] = buildSqlAndParams(['2023-12-01', '2024-01-31']);
+
+    expect(sql).toMatch(/FROM hot\.events WHERE created_at >= .* UNION ALL SELECT \* FROM cold\.events WHERE created_at >= /);
+  });
+});
@@ -0,0 +1,76 @@
+import { getEnv } from '@cubejs-backend/shared';
+import { PostgresQuery } from '../../src/adapter/PostgresQuery';
+import { prepareCompiler } from './PrepareCompiler';
+
+// Tesseract only: skipped in the JS planner
+const describeNative = getEnv('nativeSqlPlanner') ? describe : describe.skip;
+
+// Queries with many members sharing dependencies: every measure and dimension references
+// the same base dimensions. Render counts of shared members are tested in `sql_visitor.rs`
+describeNative('Wide query planning', () => {
+  const membersCount = 60;
+  const range = [...Array(membersCount).keys()];
+
+  const dimensions = range.map(i => `
+        dim${i}: {
+          type: 'string',
+          sql: \`\${CUBE.base} || \${CUBE.suffix} || '${i}'\`
+        },`).join('');
+  const measures = range.map(i => `
+        measure${i}: {
+          type: 'sum',
+          sql: \`\${CUBE.amount} * ${i}\`
+        },`).join('');
+
+  const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(`
+    cube(\`orders\`, {
+      sql: \`select * from orders\`,
+
+      measures: {${measures}
+        count: {
+          type: 'count'
+        }
+      },
+
+      dimensions: {${dimensions}
+        id: {
+          type: 'number',
+          sql: 'id',
+          primaryKey: true
+        },
+        base: {
+          type: 'string',
+          sql: \`lower(trim(\${CUBE}.status))\`
+        },
+        suffix: {
+          type: 'string',
+          sql: \`upper(\${CUBE.base})\`
+        },
+        amount: {
+          type: 'number',
+          sql: \`coalesce(\${CUBE}.amount, 0)\`
+        }
+      }
+    })
+  `);
+
+  const query = {
+    measures: range.map(i => `orders.measure${i}`),
+    dimensions: range.map(i => `orders.dim${i}`),
+    filters: range.slice(0, 10).map(i => ({ member: `orders.dim${i}`, operator: 'equals', values: ['a'] })),
+    order: range.slice(0, 10).map(i => [`orders.measure${i}`, 'desc']),
+  };
+
+  it(`plans ${membersCount * 2} members sharing dependencies`, async () => {
+    await compiler.compile();
+    const plan = () => new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, query).buildSqlAndParams();
+
+    const [sql, params] = plan();
+    range.forEach(i => {
+      expect(sql).toContain(`"orders__dim${i}"`);
+      expect(sql).toContain(`"orders__measure${i}"`);
+    });
+    expect(sql).toContain('upper(lower(trim("orders".status)))');
+    expect(plan()).toEqual([sql, params]);
+  });
+});
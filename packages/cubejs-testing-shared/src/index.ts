@@ -1,6 +1,7 @@
 import dedent from 'dedent';
 
 export * from './query-test.abstract';
+export * from './query-fixtures';
 export * from './DriverTests';
 export * from './utils';
 
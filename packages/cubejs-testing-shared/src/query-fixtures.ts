@@ -0,0 +1,204 @@
+import { BaseDriver } from '@cubejs-backend/query-orchestrator';
+import { createQuery } from '@cubejs-backend/schema-compiler';
+import { getEnv } from '@cubejs-backend/shared';
+
+import { prepareCompiler } from './query-test.abstract';
+
+export interface QueryFixture {
+  name: string,
+  query: any,
+  expected: Record<string, string | null>[],
+  // Behavior implemented only by Tesseract SQL planner
+  nativeSqlPlannerOnly?: boolean,
+}
+
+export const queryFixtureRows = [
+  { id: 1, status: 'discount 50%', amount: 100, createdAt: '2024-01-01 10:00:00' },
+  { id: 2, status: 'discount 50 off', amount: 200, createdAt: '2024-01-02 03:00:00' },
+  { id: 3, status: 'a_b', amount: 300, createdAt: '2024-01-02 12:00:00' },
+  { id: 4, status: 'axb', amount: 400, createdAt: '2024-01-03 07:30:00' },
+  { id: 5, status: 'completed', amount: 500, createdAt: '2024-01-03 23:00:00' },
+];
+
+// Dataset is inlined into cube SQL so the same fixtures load into every database without DDL
+const queryFixtureRowsSql = queryFixtureRows.map(
+  row => `SELECT ${row.id} AS id, '${row.status}' AS status, ${row.amount} AS amount, CAST('${row.createdAt}' AS TIMESTAMP) AS created_at`
+).join(' UNION ALL\n    ');
+
+export const queryFixturesCubeSchema = `
+cube(\`orders\`, {
+  sql: \`
+    ${queryFixtureRowsSql}
+  \`,
+
+  measures: {
+    count: {
+      type: \`count\`
+    },
+    amount: {
+      sql: \`amount\`,
+      type: \`sum\`
+    },
+  },
+
+  dimensions: {
+    id: {
+      sql: \`id\`,
+      type: \`number\`,
+      primaryKey: true,
+    },
+    status: {
+      sql: \`status\`,
+      type: \`string\`,
+    },
+    createdAt: {
+      sql: \`created_at\`,
+      type: \`time\`,
+    },
+  },
+});
+`;
+
+export const queryFixtures: QueryFixture[] = [
+  {
+    name: 'contains escapes percent sign',
+    query: {
+      measures: ['orders.count'],
+      filters: [{ member: 'orders.status', operator: 'contains', values: ['50%'] }],
+    },
+    expected: [{ orders__count: '1' }],
+    nativeSqlPlannerOnly: true,
+  },
+  {
+    name: 'contains escapes underscore',
+    query: {
+      measures: ['orders.count'],
+      filters: [{ member: 'orders.status', operator: 'contains', values: ['a_b'] }],
+    },
+    expected: [{ orders__count: '1' }],
+    nativeSqlPlannerOnly: true,
+  },
+  {
+    name: 'notContains escapes wildcards',
+    query: {
+      measures: ['orders.count'],
+      filters: [{ member: 'orders.status', operator: 'notContains', values: ['50%', 'a_b'] }],
+    },
+    expected: [{ orders__count: '3' }],
+    nativeSqlPlannerOnly: true,
+  },
+  {
+    name: 'day granularity in UTC',
+    query: {
+      measures: ['orders.amount'],
+      timeDimensions: [{
+        dimension: 'orders.createdAt',
+        granularity: 'day',
+        dateRange: ['2024-01-01', '2024-01-03'],
+      }],
+      order: [['orders.createdAt', 'asc']],
+      timezone: 'UTC',
+    },
+    expected: [
+      { orders__created_at_day: '2024-01-01T00:00:00', orders__amount: '100' },
+      { orders__created_at_day: '2024-01-02T00:00:00', orders__amount: '500' },
+      { orders__created_at_day: '2024-01-03T00:00:00', orders__amount: '900' },
+    ],
+  },
+  {
+    name: 'day granularity shifted by timezone',
+    query: {
+      measures: ['orders.amount'],
+      timeDimensions: [{
+        dimension: 'orders.createdAt',
+        granularity: 'day',
+        dateRange: ['2024-01-01', '2024-01-03'],
+      }],
+      order: [['orders.createdAt', 'asc']],
+      timezone: 'America/Los_Angeles',
+    },
+    expected: [
+      { orders__created_at_day: '2024-01-01T00:00:00', orders__amount: '300' },
+      { orders__created_at_day: '2024-01-02T00:00:00', orders__amount: '700' },
+      { orders__created_at_day: '2024-01-03T00:00:00', orders__amount: '500' },
+    ],
+  },
+];
+
+const TIMESTAMP_VALUE = /^\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}/;
+
+// Drivers return numbers and timestamps in different shapes, compare them as plain strings
+export function normalizeFixtureValue(value: unknown): string | null {
+  if (value === null || value === undefined) {
+    return null;
+  }
+  if (value instanceof Date) {
+    return value.toISOString().substring(0, 19);
+  }
+  const str = String(value);
+  if (TIMESTAMP_VALUE.test(str)) {
+    return str.substring(0, 19).replace(' ', 'T');
+  }
+  if (str.trim() !== '' && !Number.isNaN(Number(str))) {
+    return String(Number(str));
+  }
+  return str;
+}
+
+export function normalizeFixtureRows(rows: Record<string, unknown>[]): Record<string, string | null>[] {
+  return rows.map(row => Object.fromEntries(
+    Object.entries(row).map(([key, value]) => [key, normalizeFixtureValue(value)])
+  ));
+}
+
+export interface QueryFixturesTestCaseOptions {
+  name: string,
+  dbType: string,
+  dialectClass?: any,
+  connectionFactory: () => Promise<BaseDriver>,
+  teardown?: () => Promise<void>,
+}
+
+export function createQueryFixturesTestCase(opts: QueryFixturesTestCaseOptions) {
+  describe(`${opts.name} query fixtures`, () => {
+    jest.setTimeout(2 * 60 * 1000);
+
+    let connection: BaseDriver;
+
+    const { compiler, joinGraph, cubeEvaluator } = prepareCompiler(queryFixturesCubeSchema);
+
+    beforeAll(async () => {
+      connection = await opts.connectionFactory();
+      await compiler.compile();
+    });
+
+    afterAll(async () => {
+      if (connection) {
+        await connection.release();
+      }
+
+      if (opts.teardown) {
+        await opts.teardown();
+      }
+    });
+
+    queryFixtures.forEach(fixture => {
+      it(fixture.name, async () => {
+        if (fixture.nativeSqlPlannerOnly && !getEnv('nativeSqlPlanner')) {
+          return;
+        }
+
+        const query = createQuery(
+          { joinGraph, cubeEvaluator, compiler },
+          opts.dbType,
+          { ...fixture.query, dialectClass: opts.dialectClass }
+        );
+        const [sql, params] = query.buildSqlAndParams();
+
+        const rows = await connection.query<Record<string, unknown>>(sql, params, {});
+
+        expect(normalizeFixtureRows(rows)).toEqual(fixture.expected);
+      });
+    });
+  });
+}
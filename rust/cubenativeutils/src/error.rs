@@ -0,0 +1,68 @@
+use std::backtrace::Backtrace;
+use std::collections::HashMap;
+use std::fmt;
+use std::num::{ParseFloatError, ParseIntError};
+
+// Mirrors cubesql::CubeError for builds without Node.js bindings, where cubesql isn't available
+#[derive(Debug)]
+pub struct CubeError {
+    pub message: String,
+    pub cause: CubeErrorCauseType,
+    pub backtrace: Option<Backtrace>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CubeErrorCauseType {
+    User(Option<HashMap<String, String>>),
+    Internal(Option<HashMap<String, String>>),
+}
+
+impl CubeError {
+    pub fn user(message: String) -> Self {
+        Self {
+            message,
+            cause: CubeErrorCauseType::User(None),
+            backtrace: Some(Backtrace::capture()),
+        }
+    }
+
+    pub fn internal(message: String) -> Self {
+        Self {
+            message,
+            cause: CubeErrorCauseType::Internal(None),
+            backtrace: Some(Backtrace::capture()),
+        }
+    }
+}
+
+impl fmt::Display for CubeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CubeError {}
+
+impl From<ParseIntError> for CubeError {
+    fn from(v: ParseIntError) -> Self {
+        CubeError::internal(v.to_string())
+    }
+}
+
+impl From<ParseFloatError> for CubeError {
+    fn from(v: ParseFloatError) -> Self {
+        CubeError::internal(v.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CubeError {
+    fn from(v: serde_json::Error) -> Self {
+        CubeError::internal(v.to_string())
+    }
+}
+
+impl From<std::string::FromUtf8Error> for CubeError {
+    fn from(v: std::string::FromUtf8Error) -> Self {
+        CubeError::internal(v.to_string())
+    }
+}
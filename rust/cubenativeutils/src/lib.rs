@@ -1,2 +1,7 @@
+#[cfg(not(feature = "neon"))]
+mod error;
 pub mod wrappers;
-pub use cubesql::CubeError;
+#[cfg(feature = "neon")]
+pub use cubesql::{CubeError, CubeErrorCauseType};
+#[cfg(not(feature = "neon"))]
+pub use error::{CubeError, CubeErrorCauseType};
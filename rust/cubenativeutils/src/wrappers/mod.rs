@@ -1,5 +1,6 @@
 pub mod context;
 pub mod inner_types;
+#[cfg(feature = "neon")]
 pub mod neon;
 pub mod object;
 pub mod object_handle;
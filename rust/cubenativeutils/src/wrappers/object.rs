@@ -1,6 +1,6 @@
 use super::inner_types::InnerTypes;
 use super::object_handle::NativeObjectHandle;
-use cubesql::CubeError;
+use crate::CubeError;
 
 pub trait NativeObject<IT: InnerTypes>: Clone {
     fn get_context(&self) -> IT::Context;
@@ -1,6 +1,6 @@
 use super::inner_types::InnerTypes;
 use super::object::NativeObject;
-use cubesql::CubeError;
+use crate::CubeError;
 
 #[derive(Clone)]
 pub struct NativeObjectHandle<IT: InnerTypes> {
@@ -19,6 +19,8 @@ pub struct V1CubeMetaDimension {
     /// When dimension is defined in View, it keeps the original path: Cube.dimension
     #[serde(rename = "aliasMember", skip_serializing_if = "Option::is_none")]
     pub alias_member: Option<String>,
+    #[serde(rename = "primaryKey", skip_serializing_if = "Option::is_none")]
+    pub primary_key: Option<bool>,
     #[serde(rename = "granularities", skip_serializing_if = "Option::is_none")]
     pub granularities: Option<Vec<crate::models::V1CubeMetaDimensionGranularity>>,
     #[serde(rename = "meta", skip_serializing_if = "Option::is_none")]
@@ -32,6 +34,7 @@ impl V1CubeMetaDimension {
             description: None,
             r#type,
             alias_member: None,
+            primary_key: None,
             granularities: None,
             meta: None,
         }
@@ -14,10 +14,16 @@ pub struct V1CubeMetaJoin {
     pub name: String,
     #[serde(rename = "relationship")]
     pub relationship: String,
+    #[serde(rename = "sql", skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
 }
 
 impl V1CubeMetaJoin {
     pub fn new(name: String, relationship: String) -> V1CubeMetaJoin {
-        V1CubeMetaJoin { name, relationship }
+        V1CubeMetaJoin {
+            name,
+            relationship,
+            sql: None,
+        }
     }
 }
@@ -2715,6 +2715,7 @@ impl MemberRules {
         let right_on_var = var!(right_on_var);
         let left_aliases_var = var!(left_aliases_var);
         let right_aliases_var = var!(right_aliases_var);
+        let meta_context = self.meta_context.clone();
         move |egraph, subst| {
             if egraph
                 .index(subst[left_aliases_var])
@@ -2783,6 +2784,49 @@ impl MemberRules {
                             }
                         }
                     }
+
+                    // Views can't expose __cubeJoinField, but joining them on keys
+                    // of cubes related in the data model is still a single query
+                    let left_join_ons: Vec<Vec<_>> =
+                        var_iter!(egraph[subst[left_on_var]], JoinLeftOn)
+                            .map(|elem| elem.iter().cloned().collect())
+                            .collect();
+                    let right_join_ons: Vec<Vec<_>> =
+                        var_iter!(egraph[subst[right_on_var]], JoinRightOn)
+                            .map(|elem| elem.iter().cloned().collect())
+                            .collect();
+                    for left_join_on in left_join_ons.iter() {
+                        for right_join_on in right_join_ons.iter() {
+                            if left_join_on.is_empty() || left_join_on.len() != right_join_on.len()
+                            {
+                                continue;
+                            }
+
+                            let is_relationship_join = left_join_on
+                                .iter()
+                                .zip(right_join_on.iter())
+                                .all(|(left, right)| {
+                                    let left_member =
+                                        join_on_member_name(egraph, subst[left_aliases_var], left);
+                                    let right_member = join_on_member_name(
+                                        egraph,
+                                        subst[right_aliases_var],
+                                        right,
+                                    );
+                                    match (left_member, right_member) {
+                                        (Some(left_member), Some(right_member)) => meta_context
+                                            .views_joined_by_relationship(
+                                                &left_member,
+                                                &right_member,
+                                            ),
+                                        _ => false,
+                                    }
+                                });
+                            if is_relationship_join {
+                                return true;
+                            }
+                        }
+                    }
                 }
             }
 
@@ -2964,6 +3008,23 @@ fn find_column_by_alias(
     None
 }
 
+fn join_on_member_name(
+    egraph: &mut CubeEGraph,
+    aliases_id: Id,
+    join_on: &Column,
+) -> Option<String> {
+    let member_names_to_expr = egraph
+        .index_mut(aliases_id)
+        .data
+        .member_name_to_expr
+        .as_mut()?;
+    find_column_by_alias(
+        &join_on.name,
+        member_names_to_expr,
+        &join_on.relation.clone().unwrap_or_default(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,6 +151,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
             joins: Some(vec![CubeMetaJoin {
                 name: "Logs".to_string(),
                 relationship: "belongsTo".to_string(),
+                sql: None,
             }]),
             meta: None,
         },
@@ -198,6 +199,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
             joins: Some(vec![CubeMetaJoin {
                 name: "NumberCube".to_string(),
                 relationship: "belongsTo".to_string(),
+                sql: None,
             }]),
             meta: None,
         },
@@ -422,6 +424,119 @@ pub fn get_string_cube_meta() -> Vec<CubeMeta> {
     }]
 }
 
+pub fn get_joined_views_meta() -> Vec<CubeMeta> {
+    vec![
+        CubeMeta {
+            name: "Orders".to_string(),
+            description: None,
+            title: None,
+            r#type: V1CubeMetaType::Cube,
+            dimensions: vec![
+                CubeMetaDimension {
+                    name: "Orders.customer_id".to_string(),
+                    r#type: "number".to_string(),
+                    ..CubeMetaDimension::default()
+                },
+                CubeMetaDimension {
+                    name: "Orders.status".to_string(),
+                    r#type: "string".to_string(),
+                    ..CubeMetaDimension::default()
+                },
+                CubeMetaDimension {
+                    name: "Orders.amount".to_string(),
+                    r#type: "number".to_string(),
+                    ..CubeMetaDimension::default()
+                },
+            ],
+            measures: vec![],
+            segments: vec![],
+            joins: Some(vec![CubeMetaJoin {
+                name: "Customers".to_string(),
+                relationship: "belongsTo".to_string(),
+                sql: Some("`${CUBE}.customer_id = ${Customers}.id`".to_string()),
+            }]),
+            meta: None,
+        },
+        CubeMeta {
+            name: "Customers".to_string(),
+            description: None,
+            title: None,
+            r#type: V1CubeMetaType::Cube,
+            dimensions: vec![
+                CubeMetaDimension {
+                    name: "Customers.id".to_string(),
+                    r#type: "number".to_string(),
+                    primary_key: Some(true),
+                    ..CubeMetaDimension::default()
+                },
+                CubeMetaDimension {
+                    name: "Customers.name".to_string(),
+                    r#type: "string".to_string(),
+                    ..CubeMetaDimension::default()
+                },
+            ],
+            measures: vec![],
+            segments: vec![],
+            joins: None,
+            meta: None,
+        },
+        CubeMeta {
+            name: "OrdersView".to_string(),
+            description: None,
+            title: None,
+            r#type: V1CubeMetaType::View,
+            dimensions: vec![
+                CubeMetaDimension {
+                    name: "OrdersView.customer_id".to_string(),
+                    r#type: "number".to_string(),
+                    alias_member: Some("Orders.customer_id".to_string()),
+                    ..CubeMetaDimension::default()
+                },
+                CubeMetaDimension {
+                    name: "OrdersView.status".to_string(),
+                    r#type: "string".to_string(),
+                    alias_member: Some("Orders.status".to_string()),
+                    ..CubeMetaDimension::default()
+                },
+                CubeMetaDimension {
+                    name: "OrdersView.amount".to_string(),
+                    r#type: "number".to_string(),
+                    alias_member: Some("Orders.amount".to_string()),
+                    ..CubeMetaDimension::default()
+                },
+            ],
+            measures: vec![],
+            segments: vec![],
+            joins: None,
+            meta: None,
+        },
+        CubeMeta {
+            name: "CustomersView".to_string(),
+            description: None,
+            title: None,
+            r#type: V1CubeMetaType::View,
+            dimensions: vec![
+                CubeMetaDimension {
+                    name: "CustomersView.id".to_string(),
+                    r#type: "number".to_string(),
+                    alias_member: Some("Customers.id".to_string()),
+                    ..CubeMetaDimension::default()
+                },
+                CubeMetaDimension {
+                    name: "CustomersView.name".to_string(),
+                    r#type: "string".to_string(),
+                    alias_member: Some("Customers.name".to_string()),
+                    ..CubeMetaDimension::default()
+                },
+            ],
+            measures: vec![],
+            segments: vec![],
+            joins: None,
+            meta: None,
+        },
+    ]
+}
+
 pub fn get_sixteen_char_member_cube() -> Vec<CubeMeta> {
     vec![CubeMeta {
         name: "SixteenChar".to_string(),
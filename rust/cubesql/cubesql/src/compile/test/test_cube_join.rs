@@ -7,8 +7,9 @@ use serde_json::json;
 use crate::compile::{
     rewrite::rewriter::Rewriter,
     test::{
-        convert_select_to_query_plan, convert_sql_to_cube_query, get_test_session,
-        get_test_tenant_ctx, init_testing_logger, utils::LogicalPlanTestUtils,
+        convert_select_to_query_plan, convert_select_to_query_plan_with_meta,
+        convert_sql_to_cube_query, get_joined_views_meta, get_test_session, get_test_tenant_ctx,
+        get_test_tenant_ctx_with_meta, init_testing_logger, utils::LogicalPlanTestUtils,
     },
     DatabaseProtocol,
 };
@@ -676,3 +677,87 @@ async fn test_join_cubes_with_postprocessing_and_no_cubejoinfield() {
         true
     )
 }
+
+#[tokio::test]
+async fn test_join_views_on_modeled_relationship() {
+    init_testing_logger();
+
+    let logical_plan = convert_select_to_query_plan_with_meta(
+        r#"
+            SELECT OrdersView.status, CustomersView.name
+            FROM OrdersView
+            LEFT JOIN CustomersView ON (OrdersView.customer_id = CustomersView.id)
+            "#
+        .to_string(),
+        get_joined_views_meta(),
+    )
+    .await
+    .as_logical_plan();
+
+    assert_eq!(
+        logical_plan.find_cube_scan().request,
+        V1LoadRequestQuery {
+            measures: Some(vec![]),
+            dimensions: Some(vec![
+                "OrdersView.status".to_string(),
+                "CustomersView.name".to_string(),
+            ]),
+            segments: Some(vec![]),
+            order: Some(vec![]),
+            ungrouped: Some(true),
+            ..Default::default()
+        }
+    )
+}
+
+#[tokio::test]
+async fn test_join_views_of_same_cube_error() {
+    init_testing_logger();
+
+    let meta = get_test_tenant_ctx_with_meta(get_joined_views_meta());
+    let query = convert_sql_to_cube_query(
+        &r#"
+            SELECT *
+            FROM OrdersView
+            LEFT JOIN OrdersView AS SameView ON (OrdersView.customer_id = SameView.customer_id)
+            "#
+        .to_string(),
+        meta.clone(),
+        get_test_session(DatabaseProtocol::PostgreSQL, meta).await,
+    )
+    .await;
+
+    assert_eq!(
+        query.unwrap_err().message(),
+        "Error during rewrite: Use __cubeJoinField to join Cubes. Please check logs for additional information.".to_string()
+    )
+}
+
+#[tokio::test]
+async fn test_join_views_not_on_join_key_error() {
+    init_testing_logger();
+
+    let meta = get_test_tenant_ctx_with_meta(get_joined_views_meta());
+    for join_on in [
+        "OrdersView.status = CustomersView.name",
+        "OrdersView.customer_id = CustomersView.name",
+        "OrdersView.amount = CustomersView.id",
+    ] {
+        let query = convert_sql_to_cube_query(
+            &format!(
+                "SELECT OrdersView.status, CustomersView.name FROM OrdersView LEFT JOIN CustomersView ON ({})",
+                join_on
+            ),
+            meta.clone(),
+            get_test_session(DatabaseProtocol::PostgreSQL, meta.clone()).await,
+        )
+        .await;
+
+        assert_eq!(
+            query.unwrap_err().message(),
+            "Error during rewrite: Use __cubeJoinField to join Cubes. Please check logs for additional information.".to_string(),
+            "{}",
+            join_on
+        )
+    }
+}
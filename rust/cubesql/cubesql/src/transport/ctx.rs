@@ -5,7 +5,9 @@ use uuid::Uuid;
 
 use crate::{sql::ColumnType, transport::SqlGenerator};
 
-use super::{CubeMeta, CubeMetaDimension, CubeMetaMeasure, V1CubeMetaExt};
+use super::{
+    CubeMeta, CubeMetaDimension, CubeMetaJoin, CubeMetaMeasure, CubeMetaType, V1CubeMetaExt,
+};
 
 #[derive(Debug)]
 pub struct MetaContext {
@@ -215,6 +217,77 @@ impl MetaContext {
 
         return false;
     }
+
+    /// Checks that both members are view dimensions aliasing the join key of cubes
+    /// connected by a modeled join, so joining these views can be planned as a single query.
+    pub fn views_joined_by_relationship(&self, left_member: &str, right_member: &str) -> bool {
+        let (Some((left_cube, left_dimension)), Some((right_cube, right_dimension))) = (
+            self.view_member_source(left_member),
+            self.view_member_source(right_member),
+        ) else {
+            return false;
+        };
+
+        if left_cube == right_cube || left_dimension.r#type != right_dimension.r#type {
+            return false;
+        }
+
+        self.is_join_key(&left_cube, &left_dimension, &right_cube, &right_dimension)
+            || self.is_join_key(&right_cube, &right_dimension, &left_cube, &left_dimension)
+    }
+
+    /// Join of `cube` to `joined_cube` matches rows by the primary key of the cube
+    /// on the one side of the relationship and the foreign key referenced in the join `sql`
+    /// on the many side.
+    fn is_join_key(
+        &self,
+        cube: &str,
+        dimension: &CubeMetaDimension,
+        joined_cube: &str,
+        joined_dimension: &CubeMetaDimension,
+    ) -> bool {
+        let Some(join) = self
+            .find_cube_with_name(cube)
+            .and_then(|c| c.joins)
+            .and_then(|joins| joins.into_iter().find(|j| j.name == joined_cube))
+        else {
+            return false;
+        };
+
+        match join.relationship.to_lowercase().replace("_", "").as_str() {
+            "belongsto" | "manytoone" => {
+                joined_dimension.primary_key == Some(true)
+                    && Self::join_sql_references(&join, dimension)
+            }
+            "hasmany" | "onetomany" | "hasone" | "onetoone" => {
+                dimension.primary_key == Some(true)
+                    && Self::join_sql_references(&join, joined_dimension)
+            }
+            _ => false,
+        }
+    }
+
+    fn join_sql_references(join: &CubeMetaJoin, dimension: &CubeMetaDimension) -> bool {
+        let (Some(sql), Some(member_name)) = (&join.sql, dimension.name.split(".").nth(1)) else {
+            return false;
+        };
+        sql.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .any(|token| token == member_name)
+    }
+
+    fn view_member_source(&self, member_name: &str) -> Option<(String, CubeMetaDimension)> {
+        let view = self.find_cube_with_name(member_name.split(".").next()?)?;
+        if view.r#type != CubeMetaType::View {
+            return None;
+        }
+        let alias_member = view
+            .lookup_dimension_by_member_name(member_name)?
+            .alias_member
+            .clone()?;
+        let cube_name = alias_member.split(".").next()?.to_string();
+        let dimension = self.find_dimension_with_name(alias_member)?;
+        Some((cube_name, dimension))
+    }
 }
 
 #[cfg(test)]
@@ -357,7 +357,8 @@ impl V1CubeMetaExt for CubeMeta {
             return Some(match dimension.r#type.as_str() {
                 "number" => MemberType::Number,
                 "boolean" => MemberType::Boolean,
-                "string" => MemberType::String,
+                // Binary values are returned hex encoded
+                "string" | "binary" | "uuid" => MemberType::String,
                 "time" => MemberType::Time,
                 x => panic!("Unexpected dimension type: {}", x),
             });
@@ -0,0 +1,36 @@
+use cubenativeutils::wrappers::serializer::{
+    NativeDeserialize, NativeDeserializer, NativeSerialize,
+};
+use cubenativeutils::wrappers::NativeContextHolder;
+use cubenativeutils::wrappers::NativeObjectHandle;
+use cubenativeutils::CubeError;
+use serde::Serialize;
+use std::any::Any;
+use std::rc::Rc;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct QueryAuditFilter {
+    pub member: String,
+    pub operator: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct QueryAuditRecord {
+    pub principal: Option<String>,
+    pub measures: Vec<String>,
+    pub dimensions: Vec<String>,
+    #[serde(rename = "timeDimensions")]
+    pub time_dimensions: Vec<String>,
+    pub filters: Vec<QueryAuditFilter>,
+    #[serde(rename = "targetCubes")]
+    pub target_cubes: Vec<String>,
+    pub rollups: Vec<String>,
+    #[serde(rename = "plannerFlags")]
+    pub planner_flags: Vec<String>,
+}
+
+#[nativebridge::native_bridge]
+pub trait AuditLogSink {
+    fn log_query(&self, record: QueryAuditRecord) -> Result<(), CubeError>;
+}
@@ -1,6 +1,10 @@
 use super::join_graph::{JoinGraph, NativeJoinGraph};
+use crate::cube_bridge::audit_log_sink::{AuditLogSink, NativeAuditLogSink};
 use crate::cube_bridge::base_tools::{BaseTools, NativeBaseTools};
+use crate::cube_bridge::cancellation_token::{CancellationToken, NativeCancellationToken};
 use crate::cube_bridge::evaluator::{CubeEvaluator, NativeCubeEvaluator};
+use crate::cube_bridge::metrics_recorder::{MetricsRecorder, NativeMetricsRecorder};
+use crate::cube_bridge::table_name_resolver::{NativeTableNameResolver, TableNameResolver};
 use cubenativeutils::wrappers::serializer::{
     NativeDeserialize, NativeDeserializer, NativeSerialize,
 };
@@ -9,17 +13,20 @@ use cubenativeutils::wrappers::NativeObjectHandle;
 use cubenativeutils::CubeError;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TimeDimension {
     pub dimension: String,
     pub granularity: Option<String>,
     #[serde(rename = "dateRange")]
     pub date_range: Option<Vec<String>>,
+    pub shift: Option<String>,
+    pub calendar: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilterItem {
     pub or: Option<Vec<FilterItem>>,
     pub and: Option<Vec<FilterItem>>,
@@ -27,18 +34,37 @@ pub struct FilterItem {
     pub dimension: Option<String>,
     pub operator: Option<String>,
     pub values: Option<Vec<Option<String>>>,
+    pub placeholder: Option<String>,
+    pub trim: Option<bool>,
+    // String matching filters use LIKE instead of ILIKE
+    #[serde(rename = "caseSensitive")]
+    pub case_sensitive: Option<bool>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrderByItem {
     pub id: String,
     pub desc: Option<bool>,
+    // `first` or `last`, the database default is used when not set
+    pub nulls: Option<String>,
 }
 
 impl OrderByItem {
     pub fn is_desc(&self) -> bool {
         self.desc.unwrap_or(false)
     }
+
+    pub fn nulls_first(&self) -> Result<Option<bool>, CubeError> {
+        match self.nulls.as_deref() {
+            None => Ok(None),
+            Some("first") => Ok(Some(true)),
+            Some("last") => Ok(Some(false)),
+            Some(other) => Err(CubeError::user(format!(
+                "Unknown nulls ordering '{}' for '{}', expected 'first' or 'last'",
+                other, self.id
+            ))),
+        }
+    }
 }
 
 impl FilterItem {
@@ -47,7 +73,36 @@ impl FilterItem {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValuesNormalization {
+    #[serde(rename = "caseFold")]
+    pub case_fold: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CurrencyConversionOptions {
+    #[serde(rename = "targetCurrency")]
+    pub target_currency: String,
+    #[serde(rename = "ratesTable")]
+    pub rates_table: String,
+    #[serde(rename = "currencyColumn")]
+    pub currency_column: String,
+    #[serde(rename = "targetCurrencyColumn")]
+    pub target_currency_column: String,
+    #[serde(rename = "rateColumn")]
+    pub rate_column: String,
+    #[serde(rename = "effectiveDateColumn")]
+    pub effective_date_column: String,
+}
+
+// W3C trace context of the caller, e.g. the API request the query is planned for
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TraceContext {
+    pub traceparent: String,
+    pub tracestate: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct BaseQueryOptionsStatic {
     pub measures: Option<Vec<String>>,
     pub dimensions: Option<Vec<String>>,
@@ -55,12 +110,204 @@ pub struct BaseQueryOptionsStatic {
     pub time_dimensions: Option<Vec<TimeDimension>>,
     pub timezone: Option<String>,
     pub filters: Option<Vec<FilterItem>>,
+    pub segments: Option<Vec<String>>,
     pub order: Option<Vec<OrderByItem>>,
     pub limit: Option<String>,
     #[serde(rename = "rowLimit")]
     pub row_limit: Option<String>,
     pub offset: Option<String>,
     pub ungrouped: Option<bool>,
+    #[serde(rename = "allowUngroupedWithoutPrimaryKey")]
+    pub allow_ungrouped_without_primary_key: Option<bool>,
+    // `rollup` or `cube` of all dimensions of the query
+    pub subtotals: Option<String>,
+    // Dimensions of each subtotal grouping
+    #[serde(rename = "groupingSets")]
+    pub grouping_sets: Option<Vec<Vec<String>>>,
+    #[serde(rename = "securityContext")]
+    pub security_context: Option<serde_json::Value>,
+    #[serde(rename = "redactionMode")]
+    pub redaction_mode: Option<String>,
+    #[serde(rename = "redactionSecret")]
+    pub redaction_secret: Option<String>,
+    #[serde(rename = "castPolicy")]
+    pub cast_policy: Option<String>,
+    #[serde(rename = "emptyInListPolicy")]
+    pub empty_in_list_policy: Option<String>,
+    pub collation: Option<String>,
+    #[serde(rename = "valuesNormalization")]
+    pub values_normalization: Option<ValuesNormalization>,
+    #[serde(rename = "currencyConversion")]
+    pub currency_conversion: Option<CurrencyConversionOptions>,
+    pub units: Option<HashMap<String, String>>,
+    #[serde(rename = "fillPolicies")]
+    pub fill_policies: Option<HashMap<String, String>>,
+    #[serde(rename = "nullLabel")]
+    pub null_label: Option<String>,
+    #[serde(rename = "tableOverrides")]
+    pub table_overrides: Option<HashMap<String, String>>,
+    #[serde(rename = "valueMaps")]
+    pub value_maps: Option<HashMap<String, BTreeMap<String, String>>>,
+    #[serde(rename = "planningTimeoutMs")]
+    pub planning_timeout_ms: Option<u64>,
+    #[serde(rename = "planningMemoryBudgetBytes")]
+    pub planning_memory_budget_bytes: Option<u64>,
+    #[serde(rename = "maxSelectMembers")]
+    pub max_select_members: Option<u64>,
+    pub deterministic: Option<bool>,
+    #[serde(rename = "plannerFlags")]
+    pub planner_flags: Option<HashMap<String, bool>>,
+    #[serde(rename = "traceContext")]
+    pub trace_context: Option<TraceContext>,
+    #[serde(rename = "watermarkSecret")]
+    pub watermark_secret: Option<String>,
+    #[serde(rename = "asOf")]
+    pub as_of: Option<String>,
+    // Fast but approximate SQL for schema development
+    pub preview: Option<bool>,
+    // Filter values are rendered as literals for engines without bind params support
+    #[serde(rename = "inlineParams")]
+    pub inline_params: Option<bool>,
+    // Sums are computed in DECIMAL(38, 10) on dialects where integer sums overflow
+    #[serde(rename = "overflowSafeAggregation")]
+    pub overflow_safe_aggregation: Option<bool>,
+}
+
+// Change of a base query, e.g. one more filter for a click on a cross-filtering dashboard
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryVariant {
+    #[serde(rename = "addFilters")]
+    pub add_filters: Option<Vec<FilterItem>>,
+    // Time dimension to its new date range
+    #[serde(rename = "dateRanges")]
+    pub date_ranges: Option<HashMap<String, Vec<String>>>,
+    // Measure of the base query to the measure it's replaced with
+    #[serde(rename = "swapMeasures")]
+    pub swap_measures: Option<HashMap<String, String>>,
+    // Measures of the base query a caller needs, e.g. a single one for a sparkline.
+    // The rest are pruned along with joins only they need
+    #[serde(rename = "keepMeasures")]
+    pub keep_measures: Option<Vec<String>>,
+}
+
+// Query of a saved query: members, filters, order and paging.
+// Timezone, security context and other options come from the request planning it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedQueryDefinition {
+    pub measures: Option<Vec<String>>,
+    pub dimensions: Option<Vec<String>>,
+    #[serde(rename = "timeDimensions")]
+    pub time_dimensions: Option<Vec<TimeDimension>>,
+    pub filters: Option<Vec<FilterItem>>,
+    pub segments: Option<Vec<String>>,
+    pub order: Option<Vec<OrderByItem>>,
+    pub limit: Option<String>,
+    pub offset: Option<String>,
+    pub ungrouped: Option<bool>,
+    pub subtotals: Option<String>,
+    #[serde(rename = "groupingSets")]
+    pub grouping_sets: Option<Vec<Vec<String>>>,
+}
+
+impl BaseQueryOptionsStatic {
+    // Filters and segments of the saved query are added to ones of the request, so
+    // row level security filters of the request still apply
+    pub fn with_saved_query(&self, query: &SavedQueryDefinition) -> Self {
+        let mut result = self.clone();
+        result.measures = query.measures.clone();
+        result.dimensions = query.dimensions.clone();
+        result.time_dimensions = query.time_dimensions.clone();
+        if let Some(filters) = &query.filters {
+            result
+                .filters
+                .get_or_insert_with(Vec::new)
+                .extend(filters.iter().cloned());
+        }
+        if let Some(segments) = &query.segments {
+            result
+                .segments
+                .get_or_insert_with(Vec::new)
+                .extend(segments.iter().cloned());
+        }
+        result.order = query.order.clone();
+        result.limit = query.limit.clone();
+        result.offset = query.offset.clone();
+        result.ungrouped = query.ungrouped;
+        result.subtotals = query.subtotals.clone();
+        result.grouping_sets = query.grouping_sets.clone();
+        result
+    }
+
+    pub fn with_variant(&self, variant: &QueryVariant) -> Result<Self, CubeError> {
+        let mut result = self.clone();
+        if let Some(add_filters) = &variant.add_filters {
+            result
+                .filters
+                .get_or_insert_with(Vec::new)
+                .extend(add_filters.iter().cloned());
+        }
+        if let Some(date_ranges) = &variant.date_ranges {
+            for (dimension, date_range) in date_ranges.iter() {
+                let time_dimension = result
+                    .time_dimensions
+                    .iter_mut()
+                    .flatten()
+                    .find(|td| &td.dimension == dimension)
+                    .ok_or_else(|| {
+                        CubeError::user(format!(
+                            "Can't change date range of '{}': it isn't a time dimension of the query",
+                            dimension
+                        ))
+                    })?;
+                time_dimension.date_range = Some(date_range.clone());
+            }
+        }
+        if let Some(swap_measures) = &variant.swap_measures {
+            for (from, to) in swap_measures.iter() {
+                let measure = result
+                    .measures
+                    .iter_mut()
+                    .flatten()
+                    .find(|m| *m == from)
+                    .ok_or_else(|| {
+                        CubeError::user(format!(
+                            "Can't swap '{}': it isn't a measure of the query",
+                            from
+                        ))
+                    })?;
+                *measure = to.clone();
+                for order in result.order.iter_mut().flatten() {
+                    if &order.id == from {
+                        order.id = to.clone();
+                    }
+                }
+            }
+        }
+        if let Some(keep_measures) = &variant.keep_measures {
+            let measures = result.measures.clone().unwrap_or_default();
+            if let Some(unknown) = keep_measures.iter().find(|m| !measures.contains(m)) {
+                return Err(CubeError::user(format!(
+                    "Can't keep '{}': it isn't a measure of the query",
+                    unknown
+                )));
+            }
+            let pruned = measures
+                .iter()
+                .filter(|m| !keep_measures.contains(m))
+                .collect::<Vec<_>>();
+            if let Some(order) = &mut result.order {
+                order.retain(|o| !pruned.contains(&&o.id));
+            }
+            result.measures = Some(
+                measures
+                    .iter()
+                    .filter(|m| keep_measures.contains(m))
+                    .cloned()
+                    .collect(),
+            );
+        }
+        Ok(result)
+    }
 }
 
 #[nativebridge::native_bridge(BaseQueryOptionsStatic)]
@@ -75,4 +322,111 @@ pub trait BaseQueryOptions {
     fn base_tools(&self) -> Result<Rc<dyn BaseTools>, CubeError>;
     #[field]
     fn join_graph(&self) -> Result<Rc<dyn JoinGraph>, CubeError>;
+    #[optional]
+    #[field]
+    fn audit_log_sink(&self) -> Result<Option<Rc<dyn AuditLogSink>>, CubeError>;
+    #[optional]
+    #[field]
+    fn cancellation_token(&self) -> Result<Option<Rc<dyn CancellationToken>>, CubeError>;
+    #[optional]
+    #[field]
+    fn table_name_resolver(&self) -> Result<Option<Rc<dyn TableNameResolver>>, CubeError>;
+    #[optional]
+    #[field]
+    fn metrics_recorder(&self) -> Result<Option<Rc<dyn MetricsRecorder>>, CubeError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options() -> BaseQueryOptionsStatic {
+        serde_json::from_value(json!({
+            "measures": ["orders.count", "orders.total"],
+            "timeDimensions": [{ "dimension": "orders.createdAt", "granularity": "month" }],
+            "filters": [{ "member": "orders.status", "operator": "equals", "values": ["shipped"] }],
+            "order": [{ "id": "orders.total", "desc": true }],
+        }))
+        .unwrap()
+    }
+
+    fn variant(value: serde_json::Value) -> QueryVariant {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn saved_query_keeps_filters_and_options_of_the_request() {
+        let request = serde_json::from_value::<BaseQueryOptionsStatic>(json!({
+            "measures": ["orders.total"],
+            "timezone": "Europe/Berlin",
+            "filters": [{ "member": "orders.tenant", "operator": "equals", "values": ["42"] }],
+            "segments": ["orders.active"],
+        }))
+        .unwrap();
+        let saved_query = serde_json::from_value::<SavedQueryDefinition>(json!({
+            "measures": ["orders.count"],
+            "filters": [{ "member": "orders.status", "operator": "equals", "values": ["shipped"] }],
+            "limit": "10",
+        }))
+        .unwrap();
+        let result = request.with_saved_query(&saved_query);
+        assert_eq!(result.measures.unwrap(), vec!["orders.count".to_string()]);
+        assert_eq!(result.timezone.unwrap(), "Europe/Berlin");
+        assert_eq!(result.filters.unwrap().len(), 2);
+        assert_eq!(result.segments.unwrap(), vec!["orders.active".to_string()]);
+        assert_eq!(result.limit.unwrap(), "10");
+    }
+
+    #[test]
+    fn variant_adds_filters_and_changes_date_ranges() {
+        let result = options()
+            .with_variant(&variant(json!({
+                "addFilters": [{ "member": "orders.city", "operator": "equals", "values": ["Berlin"] }],
+                "dateRanges": { "orders.createdAt": ["2024-02-01", "2024-02-29"] },
+            })))
+            .unwrap();
+        assert_eq!(result.filters.unwrap().len(), 2);
+        assert_eq!(
+            result.time_dimensions.unwrap()[0].date_range,
+            Some(vec!["2024-02-01".to_string(), "2024-02-29".to_string()])
+        );
+    }
+
+    #[test]
+    fn variant_swaps_and_keeps_measures_with_their_order() {
+        let swapped = options()
+            .with_variant(&variant(
+                json!({ "swapMeasures": { "orders.total": "orders.avg" } }),
+            ))
+            .unwrap();
+        assert_eq!(
+            swapped.measures.unwrap(),
+            vec!["orders.count".to_string(), "orders.avg".to_string()]
+        );
+        assert_eq!(swapped.order.unwrap()[0].id, "orders.avg");
+
+        let kept = options()
+            .with_variant(&variant(json!({ "keepMeasures": ["orders.count"] })))
+            .unwrap();
+        assert_eq!(kept.measures.unwrap(), vec!["orders.count".to_string()]);
+        assert!(kept.order.unwrap().is_empty());
+    }
+
+    #[test]
+    fn variant_rejects_members_which_are_not_in_the_query() {
+        let error = |value| options().with_variant(&variant(value)).unwrap_err().message;
+        assert_eq!(
+            error(json!({ "dateRanges": { "orders.shippedAt": ["2024-01-01", "2024-01-31"] } })),
+            "Can't change date range of 'orders.shippedAt': it isn't a time dimension of the query"
+        );
+        assert_eq!(
+            error(json!({ "swapMeasures": { "orders.avg": "orders.total" } })),
+            "Can't swap 'orders.avg': it isn't a measure of the query"
+        );
+        assert_eq!(
+            error(json!({ "keepMeasures": ["orders.avg"] })),
+            "Can't keep 'orders.avg': it isn't a measure of the query"
+        );
+    }
 }
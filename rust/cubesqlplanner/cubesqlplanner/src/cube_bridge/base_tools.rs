@@ -34,8 +34,8 @@ pub trait BaseTools {
         sql: Rc<dyn MemberSql>,
     ) -> Result<Vec<CallDep>, CubeError>;
     fn security_context_for_rust(&self) -> Result<Rc<dyn SecurityContext>, CubeError>;
-    fn filters_proxy(&self) -> Result<Rc<dyn FilterParams>, CubeError>;
-    fn filter_group_function(&self) -> Result<Rc<dyn FilterGroup>, CubeError>;
+    fn filters_proxy_for_rust(&self) -> Result<Rc<dyn FilterParams>, CubeError>;
+    fn filter_group_function_for_rust(&self) -> Result<Rc<dyn FilterGroup>, CubeError>;
     fn timestamp_precision(&self) -> Result<u32, CubeError>;
     fn in_db_time_zone(&self, date: String) -> Result<String, CubeError>;
     fn generate_time_series(
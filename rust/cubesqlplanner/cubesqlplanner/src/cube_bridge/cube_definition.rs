@@ -1,4 +1,6 @@
+use super::base_query_options::{FilterItem, OrderByItem, SavedQueryDefinition, TimeDimension};
 use super::memeber_sql::{MemberSql, NativeMemberSql};
+use super::refresh_key::{NativeRefreshKey, RefreshKey};
 use cubenativeutils::wrappers::serializer::{
     NativeDeserialize, NativeDeserializer, NativeSerialize,
 };
@@ -9,9 +11,85 @@ use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::rc::Rc;
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CubeCalendar {
+    pub table: String,
+    #[serde(rename = "dateColumn")]
+    pub date_column: String,
+    #[serde(rename = "businessDayColumn")]
+    pub business_day_column: Option<String>,
+    #[serde(rename = "holidayColumn")]
+    pub holiday_column: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CubeUnionBranch {
+    pub table: String,
+    // Branch holds rows of [since, until) time range, e.g. hot and cold storage
+    pub since: Option<String>,
+    pub until: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CubeUnion {
+    // Column the query date range is pushed down to in every branch
+    #[serde(rename = "timeColumn")]
+    pub time_column: Option<String>,
+    pub tables: Vec<CubeUnionBranch>,
+}
+
+// Query of other cubes the cube is built on. It's planned as a subquery unless
+// its result is materialized into a table
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CubeSourceQuery {
+    pub measures: Option<Vec<String>>,
+    pub dimensions: Option<Vec<String>>,
+    #[serde(rename = "timeDimensions")]
+    pub time_dimensions: Option<Vec<TimeDimension>>,
+    pub filters: Option<Vec<FilterItem>>,
+    pub segments: Option<Vec<String>>,
+    pub order: Option<Vec<OrderByItem>>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub ungrouped: Option<bool>,
+    pub materialized: Option<String>,
+}
+
+impl CubeSourceQuery {
+    pub fn query(&self) -> SavedQueryDefinition {
+        SavedQueryDefinition {
+            measures: self.measures.clone(),
+            dimensions: self.dimensions.clone(),
+            time_dimensions: self.time_dimensions.clone(),
+            filters: self.filters.clone(),
+            segments: self.segments.clone(),
+            order: self.order.clone(),
+            limit: self.limit.map(|limit| limit.to_string()),
+            offset: self.offset.map(|offset| offset.to_string()),
+            ungrouped: self.ungrouped,
+        }
+    }
+}
+
+// Columns of the range each row of a snapshot (SCD type 2) cube is valid in
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CubeValidity {
+    #[serde(rename = "validFrom")]
+    pub valid_from: String,
+    #[serde(rename = "validTo")]
+    pub valid_to: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CubeDefinitionStatic {
     pub name: String,
+    pub calendar: Option<CubeCalendar>,
+    pub union: Option<CubeUnion>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
+    #[serde(rename = "sourceQuery")]
+    pub source_query: Option<CubeSourceQuery>,
+    pub validity: Option<CubeValidity>,
 }
 
 #[nativebridge::native_bridge(CubeDefinitionStatic)]
@@ -22,4 +100,7 @@ pub trait CubeDefinition {
     #[field]
     #[optional]
     fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
+    #[field]
+    #[optional]
+    fn refresh_key(&self) -> Result<Option<Rc<dyn RefreshKey>>, CubeError>;
 }
@@ -7,8 +7,16 @@ use cubenativeutils::wrappers::NativeObjectHandle;
 use cubenativeutils::CubeError;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct DimensionDuration {
+    pub from: String,
+    pub to: String,
+    pub unit: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DimenstionDefinitionStatic {
     #[serde(rename = "type")]
@@ -17,10 +25,25 @@ pub struct DimenstionDefinitionStatic {
     pub owned_by_cube: Option<bool>,
     #[serde(rename = "multiStage")]
     pub multi_stage: Option<bool>,
+    pub sensitive: Option<bool>,
+    pub collation: Option<String>,
+    pub unit: Option<String>,
+    pub duration: Option<DimensionDuration>,
+    #[serde(rename = "multiValue")]
+    pub multi_value: Option<bool>,
+    #[serde(rename = "multiValueDedupe")]
+    pub multi_value_dedupe: Option<bool>,
+    #[serde(rename = "valueMap")]
+    pub value_map: Option<BTreeMap<String, String>>,
+    #[serde(rename = "subQuery")]
+    pub sub_query: Option<bool>,
+    #[serde(rename = "startsWithRange")]
+    pub starts_with_range: Option<bool>,
 }
 
 #[nativebridge::native_bridge(DimenstionDefinitionStatic)]
 pub trait DimensionDefinition {
+    #[optional]
     #[field]
-    fn sql(&self) -> Result<Rc<dyn MemberSql>, CubeError>;
+    fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
 }
@@ -2,6 +2,7 @@ use super::cube_definition::{CubeDefinition, NativeCubeDefinition};
 use super::dimension_definition::{DimensionDefinition, NativeDimensionDefinition};
 use super::measure_definition::{MeasureDefinition, NativeMeasureDefinition};
 use super::memeber_sql::{MemberSql, NativeMemberSql};
+use super::segment_definition::{NativeSegmentDefinition, SegmentDefinition};
 use cubenativeutils::wrappers::serializer::{
     NativeDeserialize, NativeDeserializer, NativeSerialize,
 };
@@ -36,6 +37,8 @@ pub trait CubeEvaluator {
         &self,
         measure_path: String,
     ) -> Result<Rc<dyn DimensionDefinition>, CubeError>;
+    fn segment_by_path(&self, segment_path: String)
+        -> Result<Rc<dyn SegmentDefinition>, CubeError>;
     fn cube_from_path(&self, cube_path: String) -> Result<Rc<dyn CubeDefinition>, CubeError>;
     fn is_measure(&self, path: Vec<String>) -> Result<bool, CubeError>;
     fn is_dimension(&self, path: Vec<String>) -> Result<bool, CubeError>;
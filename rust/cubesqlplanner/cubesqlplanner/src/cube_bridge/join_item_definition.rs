@@ -18,4 +18,17 @@ pub struct JoinItemDefinitionStatic {
 pub trait JoinItemDefinition {
     #[field]
     fn sql(&self) -> Result<Rc<dyn MemberSql>, CubeError>;
+    // Time of a row of the joining cube matched against validity of a snapshot cube
+    #[field]
+    #[optional]
+    fn valid_at(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
+    // As-of join matches a row of the joining cube to the nearest preceding row of the
+    // joined one: `as_of_time` is the time of the joining row, `as_of_match_time` is
+    // the time of the joined one
+    #[field]
+    #[optional]
+    fn as_of_time(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
+    #[field]
+    #[optional]
+    fn as_of_match_time(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
 }
@@ -28,10 +28,32 @@ pub struct RollingWindow {
     pub offset: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MeasureRounding {
+    pub scale: u32,
+    pub precision: Option<u32>,
+}
+
+// Declared decimal type of the measure values. Sums and averages are computed in it,
+// not in the floating type some engines promote decimals to
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MeasureDecimal {
+    pub precision: u32,
+    pub scale: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MeasureCurrency {
+    pub dimension: String,
+    #[serde(rename = "timeDimension")]
+    pub time_dimension: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MeasureDefinitionStatic {
     #[serde(rename = "type")]
     pub measure_type: String,
+    pub sensitive: Option<bool>,
     #[serde(rename = "ownedByCube")]
     pub owned_by_cube: Option<bool>,
     #[serde(rename = "multiStage")]
@@ -46,6 +68,16 @@ pub struct MeasureDefinitionStatic {
     pub time_shift_references: Option<Vec<TimeShiftReference>>,
     #[serde(rename = "rollingWindow")]
     pub rolling_window: Option<RollingWindow>,
+    pub rounding: Option<MeasureRounding>,
+    pub decimal: Option<MeasureDecimal>,
+    // Overrides the query level overflow safe aggregation
+    #[serde(rename = "overflowSafe")]
+    pub overflow_safe: Option<bool>,
+    // `null` or `zero`, applies to measures with `denominatorSql`
+    #[serde(rename = "divisionByZero")]
+    pub division_by_zero: Option<String>,
+    pub currency: Option<MeasureCurrency>,
+    pub unit: Option<String>,
 }
 
 #[nativebridge::native_bridge(MeasureDefinitionStatic)]
@@ -54,6 +86,10 @@ pub trait MeasureDefinition {
     #[field]
     fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
 
+    #[optional]
+    #[field]
+    fn denominator_sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
+
     fn cube(&self) -> Result<Rc<dyn CubeDefinition>, CubeError>;
 
     #[optional]
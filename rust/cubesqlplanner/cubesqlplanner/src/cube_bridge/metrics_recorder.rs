@@ -0,0 +1,26 @@
+use cubenativeutils::wrappers::serializer::{
+    NativeDeserialize, NativeDeserializer, NativeSerialize,
+};
+use cubenativeutils::wrappers::NativeContextHolder;
+use cubenativeutils::wrappers::NativeObjectHandle;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Receives planning metrics, so embedders can export them to Prometheus, OpenTelemetry, etc.
+#[nativebridge::native_bridge]
+pub trait MetricsRecorder {
+    fn increment_counter(
+        &self,
+        name: String,
+        value: f64,
+        labels: HashMap<String, String>,
+    ) -> Result<(), CubeError>;
+    fn record_histogram(
+        &self,
+        name: String,
+        value: f64,
+        labels: HashMap<String, String>,
+    ) -> Result<(), CubeError>;
+}
@@ -1,5 +1,7 @@
+pub mod audit_log_sink;
 pub mod base_query_options;
 pub mod base_tools;
+pub mod cancellation_token;
 pub mod cube_definition;
 pub mod dimension_definition;
 pub mod evaluator;
@@ -14,5 +16,9 @@ pub mod measure_filter;
 pub mod member_definition;
 pub mod member_order_by;
 pub mod memeber_sql;
+pub mod metrics_recorder;
+pub mod refresh_key;
 pub mod security_context;
+pub mod segment_definition;
 pub mod sql_templates_render;
+pub mod table_name_resolver;
@@ -0,0 +1,23 @@
+use super::memeber_sql::{MemberSql, NativeMemberSql};
+use cubenativeutils::wrappers::serializer::{
+    NativeDeserialize, NativeDeserializer, NativeSerialize,
+};
+use cubenativeutils::wrappers::NativeContextHolder;
+use cubenativeutils::wrappers::NativeObjectHandle;
+use cubenativeutils::CubeError;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefreshKeyStatic {
+    pub every: Option<String>,
+    pub immutable: Option<bool>,
+}
+
+#[nativebridge::native_bridge(RefreshKeyStatic)]
+pub trait RefreshKey {
+    #[optional]
+    #[field]
+    fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError>;
+}
@@ -75,3 +75,97 @@ impl<IT: InnerTypes> NativeDeserialize<IT> for NativeSqlTemplatesRender<IT> {
         NativeSqlTemplatesRender::try_new(templates_map)
     }
 }
+
+// Templates of `BaseQuery.sqlTemplates()` provided by hosts without JS as
+// `{type: {name: template}}`, see `static_schema`
+pub struct StaticSqlTemplatesRender {
+    templates: HashMap<String, String>,
+    jinja: Environment<'static>,
+}
+
+impl StaticSqlTemplatesRender {
+    pub fn try_new(raw_data: HashMap<String, HashMap<String, String>>) -> Result<Self, CubeError> {
+        let mut templates = HashMap::new();
+        for (template_type, type_templates) in raw_data {
+            for (template_name, template) in type_templates {
+                templates.insert(format!("{}/{}", template_type, template_name), template);
+            }
+        }
+        let mut jinja = Environment::new();
+        for (name, template) in templates.iter() {
+            jinja
+                .add_template_owned(name.to_string(), template.to_string())
+                .map_err(|e| {
+                    CubeError::user(format!(
+                        "Error parsing template {} '{}': {}",
+                        name, template, e
+                    ))
+                })?;
+        }
+        Ok(Self { templates, jinja })
+    }
+}
+
+impl SqlTemplatesRender for StaticSqlTemplatesRender {
+    fn contains_template(&self, template_name: &str) -> bool {
+        self.templates.contains_key(template_name)
+    }
+
+    fn get_template(&self, template_name: &str) -> Result<&String, CubeError> {
+        self.templates
+            .get(template_name)
+            .ok_or_else(|| CubeError::user(format!("{template_name} template not found")))
+    }
+
+    fn render_template(&self, name: &str, ctx: Value) -> Result<String, CubeError> {
+        self.jinja
+            .get_template(name)
+            .map_err(|e| CubeError::internal(format!("Error getting {} template: {}", name, e)))?
+            .render(ctx)
+            .map_err(|e| CubeError::internal(format!("Error rendering {} template: {}", name, e)))
+    }
+}
+
+// Templates render backed by a plain map, for testing sql generation without JS
+#[cfg(test)]
+pub struct MockSqlTemplatesRender {
+    templates: HashMap<String, String>,
+    jinja: Environment<'static>,
+}
+
+#[cfg(test)]
+impl MockSqlTemplatesRender {
+    pub fn try_new(templates: Vec<(&str, &str)>) -> Result<Self, CubeError> {
+        let templates = templates
+            .into_iter()
+            .map(|(name, template)| (name.to_string(), template.to_string()))
+            .collect::<HashMap<_, _>>();
+        let mut jinja = Environment::new();
+        for (name, template) in templates.iter() {
+            jinja
+                .add_template_owned(name.to_string(), template.to_string())
+                .map_err(|e| CubeError::internal(e.to_string()))?;
+        }
+        Ok(Self { templates, jinja })
+    }
+}
+
+#[cfg(test)]
+impl SqlTemplatesRender for MockSqlTemplatesRender {
+    fn contains_template(&self, template_name: &str) -> bool {
+        self.templates.contains_key(template_name)
+    }
+
+    fn get_template(&self, template_name: &str) -> Result<&String, CubeError> {
+        self.templates
+            .get(template_name)
+            .ok_or_else(|| CubeError::user(format!("{template_name} template not found")))
+    }
+
+    fn render_template(&self, name: &str, ctx: Value) -> Result<String, CubeError> {
+        self.jinja
+            .get_template(name)
+            .and_then(|t| t.render(ctx))
+            .map_err(|e| CubeError::internal(e.to_string()))
+    }
+}
@@ -0,0 +1,15 @@
+use cubenativeutils::wrappers::serializer::{
+    NativeDeserialize, NativeDeserializer, NativeSerialize,
+};
+use cubenativeutils::wrappers::NativeContextHolder;
+use cubenativeutils::wrappers::NativeObjectHandle;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::rc::Rc;
+
+// Routes cube sources to physical tables chosen at plan time, e.g. per-tenant
+// shards `events_123`. Returns the table itself when it shouldn't be rerouted
+#[nativebridge::native_bridge]
+pub trait TableNameResolver {
+    fn resolve_table_name(&self, cube_name: String, table: String) -> Result<String, CubeError>;
+}
@@ -0,0 +1,150 @@
+// C ABI for embedders without Node.js, e.g. Python or Go services.
+// Queries, requests and responses are JSON documents of the planner protocol passed as
+// nul-terminated UTF-8 strings, see `protocol` module. Queries are planned against the schema
+// of the embedder, passed as `CubeSqlPlannerSchemaRuntime` callbacks, see `static_schema` module.
+// Returned strings are owned by the caller and must be released with `cubesqlplanner_free_string`.
+// Build with `cargo rustc --features ffi --crate-type cdylib` to get a shared library.
+use crate::protocol::{self, NoSchemaRuntime, SchemaRuntime, PROTOCOL_VERSION};
+use cubenativeutils::CubeError;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic;
+
+/// Schema runtime of the embedder.
+///
+/// `schema` gets `user_data` and returns JSON of the cubes (`{ "cubes": [...] }`), `templates`
+/// returns JSON of the sql templates of the dialect (`{ "<type>": { "<name>": "<template>" } }`).
+/// Both return null if they can't provide them. Returned strings are released with
+/// `free_string` of the runtime once the planner has read them.
+#[repr(C)]
+pub struct CubeSqlPlannerSchemaRuntime {
+    pub user_data: *mut c_void,
+    pub schema: extern "C" fn(user_data: *mut c_void) -> *mut c_char,
+    pub templates: extern "C" fn(user_data: *mut c_void) -> *mut c_char,
+    pub free_string: extern "C" fn(user_data: *mut c_void, s: *mut c_char),
+}
+
+impl CubeSqlPlannerSchemaRuntime {
+    fn read(
+        &self,
+        callback: extern "C" fn(user_data: *mut c_void) -> *mut c_char,
+        name: &str,
+    ) -> Result<String, CubeError> {
+        let s = callback(self.user_data);
+        if s.is_null() {
+            return Err(CubeError::user(format!(
+                "Schema runtime returned no {}",
+                name
+            )));
+        }
+        let result = unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned();
+        (self.free_string)(self.user_data, s);
+        Ok(result)
+    }
+}
+
+impl SchemaRuntime for CubeSqlPlannerSchemaRuntime {
+    fn schema(&self) -> Result<String, CubeError> {
+        self.read(self.schema, "schema")
+    }
+
+    fn templates(&self) -> Result<String, CubeError> {
+        self.read(self.templates, "sql templates")
+    }
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // Interior nul bytes can't be passed through C strings
+    CString::new(s.replace('\0', ""))
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+unsafe fn from_c_string(s: *const c_char) -> String {
+    // Invalid input is passed on as is to get a protocol parse error
+    if s.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+/// Schema runtime of a call, `NoSchemaRuntime` if the embedder passes none.
+///
+/// # Safety
+/// `runtime` must be null or point to a schema runtime which stays valid and isn't mutated
+/// for `'a`, i.e. the duration of the call it's passed to.
+unsafe fn schema_runtime<'a>(runtime: *const CubeSqlPlannerSchemaRuntime) -> &'a dyn SchemaRuntime {
+    match runtime.as_ref() {
+        Some(runtime) => runtime,
+        None => &NoSchemaRuntime,
+    }
+}
+
+// Panics must not unwind across the C boundary
+fn catch_unwind(f: impl FnOnce() -> String + panic::UnwindSafe) -> *mut c_char {
+    let response = panic::catch_unwind(f).unwrap_or_else(|_| {
+        format!(
+            "{{\"jsonrpc\":\"2.0\",\"version\":{},\"id\":null,\"error\":{{\"code\":{},\"message\":\"Unexpected panic\"}}}}",
+            PROTOCOL_VERSION,
+            protocol::INTERNAL_ERROR
+        )
+    });
+    into_c_string(response)
+}
+
+#[no_mangle]
+pub extern "C" fn cubesqlplanner_protocol_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+/// Handles a protocol request and returns its response.
+///
+/// # Safety
+/// `request` must be a valid pointer to a nul-terminated string. `runtime` must be null or
+/// point to a schema runtime valid for the duration of the call, `plan` requests fail without it.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_call(
+    request: *const c_char,
+    runtime: *const CubeSqlPlannerSchemaRuntime,
+) -> *mut c_char {
+    let request = from_c_string(request);
+    let runtime = panic::AssertUnwindSafe(schema_runtime(runtime));
+    catch_unwind(move || protocol::handle_request(&request, *runtime))
+}
+
+/// Plans a query against the schema of the runtime and returns the `plan` response: SQL, params
+/// and metadata.
+///
+/// # Safety
+/// `query` must be a valid pointer to a nul-terminated string. `runtime` must point to a schema
+/// runtime valid for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_plan(
+    query: *const c_char,
+    runtime: *const CubeSqlPlannerSchemaRuntime,
+) -> *mut c_char {
+    let query = from_c_string(query);
+    let runtime = panic::AssertUnwindSafe(schema_runtime(runtime));
+    catch_unwind(move || protocol::handle_query("plan", &query, *runtime))
+}
+
+/// Validates a query without the schema and returns the `validate` response.
+///
+/// # Safety
+/// `query` must be a valid pointer to a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_validate(query: *const c_char) -> *mut c_char {
+    let query = from_c_string(query);
+    catch_unwind(move || protocol::handle_query("validate", &query, &NoSchemaRuntime))
+}
+
+/// Releases a string returned by this library.
+///
+/// # Safety
+/// `s` must be returned by this library and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
@@ -1,3 +1,12 @@
 pub mod cube_bridge;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod plan;
 pub mod planner;
+pub mod protocol;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod static_schema;
+// Exported names are shared with the C ABI
+#[cfg(all(target_arch = "wasm32", not(feature = "ffi")))]
+pub mod wasm;
@@ -1,6 +1,6 @@
 use crate::plan::{
-    AliasedExpr, Cte, Expr, Filter, From, MemberExpression, OrderBy, QualifiedColumnName, Schema,
-    SchemaColumn, Select, SingleAliasedSource, SingleSource,
+    AliasedExpr, Cte, Expr, Filter, From, GroupingSets, MemberExpression, OrderBy,
+    QualifiedColumnName, Schema, SchemaColumn, Select, SingleAliasedSource, SingleSource,
 };
 
 use crate::plan::expression::FunctionExpression;
@@ -14,6 +14,7 @@ pub struct SelectBuilder {
     from: Rc<From>,
     filter: Option<Filter>,
     group_by: Vec<Expr>,
+    grouping_sets: Option<GroupingSets>,
     having: Option<Filter>,
     order_by: Vec<OrderBy>,
     ctes: Vec<Rc<Cte>>,
@@ -30,6 +31,7 @@ impl SelectBuilder {
             from,
             filter: None,
             group_by: vec![],
+            grouping_sets: None,
             having: None,
             order_by: vec![],
             ctes: vec![],
@@ -88,6 +90,29 @@ impl SelectBuilder {
             .add_column(SchemaColumn::new(alias.clone(), Some(member.full_name())));
     }
 
+    pub fn add_projection_function_expression(
+        &mut self,
+        function: &str,
+        args: Vec<Rc<dyn BaseMember>>,
+        alias: String,
+    ) {
+        let expr = Expr::Function(FunctionExpression {
+            function: function.to_string(),
+            arguments: args
+                .into_iter()
+                .map(|member| Expr::Member(MemberExpression::new(member)))
+                .collect(),
+        });
+        let aliased_expr = AliasedExpr {
+            expr,
+            alias: alias.clone(),
+        };
+
+        self.projection_columns.push(aliased_expr);
+        self.result_schema
+            .add_column(SchemaColumn::new(alias.clone(), None));
+    }
+
     pub fn set_filter(&mut self, filter: Option<Filter>) {
         self.filter = filter;
     }
@@ -96,6 +121,10 @@ impl SelectBuilder {
         self.group_by = group_by;
     }
 
+    pub fn set_grouping_sets(&mut self, grouping_sets: Option<GroupingSets>) {
+        self.grouping_sets = grouping_sets;
+    }
+
     pub fn set_having(&mut self, having: Option<Filter>) {
         self.having = having;
     }
@@ -179,6 +208,7 @@ impl SelectBuilder {
             from: self.from,
             filter: self.filter,
             group_by: self.group_by,
+            grouping_sets: self.grouping_sets,
             having: self.having,
             order_by: self.order_by,
             context: Rc::new(VisitorContext::new(&nodes_factory)),
@@ -1,6 +1,7 @@
 use super::{QueryPlan, Select};
 use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
+use serde_json::{json, Value};
 
 use std::rc::Rc;
 
@@ -30,6 +31,13 @@ impl Cte {
         &self.name
     }
 
+    pub fn explain(&self) -> Value {
+        json!({
+            "name": self.name,
+            "query": self.query.explain(),
+        })
+    }
+
     pub fn to_sql(&self, templates: &PlanSqlTemplates) -> Result<String, CubeError> {
         let sql = format!("({})", self.query.to_sql(templates)?);
         Ok(sql)
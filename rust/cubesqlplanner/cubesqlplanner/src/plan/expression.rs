@@ -1,4 +1,5 @@
-use super::QualifiedColumnName;
+use super::{GroupingSets, QualifiedColumnName};
+use crate::cube_bridge::measure_definition::MeasureRounding;
 use crate::planner::sql_templates::PlanSqlTemplates;
 use crate::planner::{BaseMember, VisitorContext};
 use cubenativeutils::CubeError;
@@ -29,11 +30,133 @@ pub struct FunctionExpression {
     pub arguments: Vec<Expr>,
 }
 
+#[derive(Clone)]
+pub struct RoundedExpression {
+    pub expr: Box<Expr>,
+    pub rounding: MeasureRounding,
+}
+
+impl RoundedExpression {
+    pub fn to_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        context: Rc<VisitorContext>,
+    ) -> Result<String, CubeError> {
+        let expr = self.expr.to_sql(templates, context)?;
+        if let Some(precision) = self.rounding.precision {
+            templates.cast_to_decimal(&expr, precision, self.rounding.scale)
+        } else {
+            templates.round(&expr, self.rounding.scale)
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct UnitConversionExpression {
+    pub expr: Box<Expr>,
+    pub factor: f64,
+}
+
+impl UnitConversionExpression {
+    pub fn to_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        context: Rc<VisitorContext>,
+    ) -> Result<String, CubeError> {
+        let expr = self.expr.to_sql(templates, context)?;
+        templates.unit_conversion(&expr, self.factor)
+    }
+}
+
+#[derive(Clone)]
+pub struct NullLabelExpression {
+    pub expr: Box<Expr>,
+    pub label: String,
+}
+
+impl NullLabelExpression {
+    pub fn to_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        context: Rc<VisitorContext>,
+    ) -> Result<String, CubeError> {
+        let expr = self.expr.to_sql(templates, context)?;
+        templates.null_label(&expr, &self.label)
+    }
+}
+
+#[derive(Clone)]
+pub struct HexEncodedExpression {
+    pub expr: Box<Expr>,
+}
+
+impl HexEncodedExpression {
+    pub fn to_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        context: Rc<VisitorContext>,
+    ) -> Result<String, CubeError> {
+        let expr = self.expr.to_sql(templates, context)?;
+        templates.hex_encode(&expr)
+    }
+}
+
+#[derive(Clone)]
+pub struct ValueMappedExpression {
+    pub expr: Box<Expr>,
+    // Params of the mapped values and their labels
+    pub when_then: Vec<(String, String)>,
+}
+
+impl ValueMappedExpression {
+    pub fn to_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        context: Rc<VisitorContext>,
+    ) -> Result<String, CubeError> {
+        let expr = self.expr.to_sql(templates, context)?;
+        templates.case(Some(expr.clone()), self.when_then.clone(), Some(expr))
+    }
+}
+
+// Tells subtotal rows apart: GROUPING() of the group by columns, or its value for a
+// single grouping when subtotals are planned as a union of groupings
+#[derive(Clone)]
+pub struct GroupingIdExpression {
+    pub group_by: Vec<Expr>,
+    pub grouping: Option<Vec<usize>>,
+}
+
+impl GroupingIdExpression {
+    pub fn to_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        context: Rc<VisitorContext>,
+    ) -> Result<String, CubeError> {
+        if let Some(grouping) = &self.grouping {
+            return Ok(GroupingSets::grouping_id(grouping, self.group_by.len()).to_string());
+        }
+        let group_by = self
+            .group_by
+            .iter()
+            .map(|expr| expr.to_sql(templates, context.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        templates.grouping(group_by)
+    }
+}
+
 #[derive(Clone)]
 pub enum Expr {
     Member(MemberExpression),
     Reference(QualifiedColumnName),
     Function(FunctionExpression),
+    Rounded(RoundedExpression),
+    UnitConversion(UnitConversionExpression),
+    NullLabel(NullLabelExpression),
+    HexEncoded(HexEncodedExpression),
+    ValueMapped(ValueMappedExpression),
+    GroupingId(GroupingIdExpression),
+    Null,
 }
 
 impl Expr {
@@ -65,6 +188,13 @@ impl Expr {
                 None,
                 None,
             ),
+            Self::Rounded(rounded) => rounded.to_sql(templates, context),
+            Self::UnitConversion(conversion) => conversion.to_sql(templates, context),
+            Self::NullLabel(null_label) => null_label.to_sql(templates, context),
+            Self::HexEncoded(hex_encoded) => hex_encoded.to_sql(templates, context),
+            Self::ValueMapped(value_mapped) => value_mapped.to_sql(templates, context),
+            Self::GroupingId(grouping_id) => grouping_id.to_sql(templates, context),
+            Self::Null => Ok("NULL".to_string()),
         }
     }
 }
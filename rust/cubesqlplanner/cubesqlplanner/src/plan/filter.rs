@@ -1,8 +1,10 @@
-use crate::planner::filter::BaseFilter;
+use crate::planner::filter::base_filter::FilterType;
+use crate::planner::filter::{BaseFilter, BaseSegment};
 use crate::planner::sql_evaluator::MemberSymbol;
 use crate::planner::sql_templates::PlanSqlTemplates;
 use crate::planner::VisitorContext;
 use cubenativeutils::CubeError;
+use serde_json::{json, Value};
 use std::fmt;
 use std::rc::Rc;
 
@@ -34,6 +36,7 @@ impl FilterGroup {
 pub enum FilterItem {
     Group(Rc<FilterGroup>),
     Item(Rc<BaseFilter>),
+    Segment(Rc<BaseSegment>),
 }
 
 pub struct Filter {
@@ -57,30 +60,77 @@ impl FilterItem {
     ) -> Result<String, CubeError> {
         let res = match self {
             FilterItem::Group(group) => {
-                let operator = format!(" {} ", group.operator.to_string());
                 let items_sql = group
                     .items
                     .iter()
                     .map(|itm| itm.to_sql(templates, context.clone()))
-                    .collect::<Result<Vec<_>, _>>()?
-                    .into_iter()
-                    .filter(|itm| !itm.is_empty())
-                    .collect::<Vec<_>>();
-                if items_sql.is_empty() {
-                    "".to_string()
-                } else {
-                    let result = items_sql.join(&operator);
-                    format!("({})", result)
-                }
+                    .collect::<Result<Vec<_>, _>>()?;
+                Self::group_sql(&group.operator, items_sql, templates)?
             }
             FilterItem::Item(item) => {
                 let sql = item.to_sql(context.clone())?;
                 format!("({})", sql)
             }
+            FilterItem::Segment(item) => {
+                let sql = item.to_sql(context.clone())?;
+                format!("({})", sql)
+            }
         };
         Ok(res)
     }
 
+    // An empty item has no constraint: it is dropped from an AND group, but makes
+    // the whole OR group always true
+    fn group_sql(
+        operator: &FilterGroupOperator,
+        items_sql: Vec<String>,
+        templates: &PlanSqlTemplates,
+    ) -> Result<String, CubeError> {
+        if operator == &FilterGroupOperator::Or && items_sql.iter().any(|itm| itm.is_empty()) {
+            return Ok(format!("({})", templates.always_true()?));
+        }
+        let items_sql = items_sql
+            .into_iter()
+            .filter(|itm| !itm.is_empty())
+            .collect::<Vec<_>>();
+        if items_sql.is_empty() {
+            Ok("".to_string())
+        } else {
+            let result = items_sql.join(&format!(" {} ", operator));
+            Ok(format!("({})", result))
+        }
+    }
+
+    pub fn explain(&self) -> Value {
+        match self {
+            FilterItem::Group(group) => json!({
+                "operator": group.operator.to_string(),
+                "items": group.items.iter().map(|itm| itm.explain()).collect::<Vec<_>>(),
+            }),
+            FilterItem::Item(item) => json!({
+                "member": item.member_name(),
+                "operator": item.filter_operator().to_string(),
+                "values": item.display_values(),
+                "placeholder": item.placeholder(),
+            }),
+            FilterItem::Segment(item) => json!({
+                "segment": item.member_name(),
+            }),
+        }
+    }
+
+    // Groups can't mix measures and dimensions, so the first filter of a group decides
+    pub fn is_measure_filter(&self) -> bool {
+        match self {
+            FilterItem::Group(group) => group
+                .items
+                .first()
+                .map_or(false, |item| item.is_measure_filter()),
+            FilterItem::Item(item) => item.filter_type() == &FilterType::Measure,
+            FilterItem::Segment(_) => false,
+        }
+    }
+
     pub fn all_member_evaluators(&self) -> Vec<Rc<MemberSymbol>> {
         let mut result = Vec::new();
         self.find_all_member_evaluators(&mut result);
@@ -95,11 +145,28 @@ impl FilterItem {
                 }
             }
             FilterItem::Item(item) => result.push(item.member_evaluator().clone()),
+            FilterItem::Segment(item) => result.push(item.member_evaluator().clone()),
         }
     }
 }
 
 impl Filter {
+    pub fn from_items(items: Vec<FilterItem>) -> Option<Self> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(Self { items })
+        }
+    }
+
+    pub fn explain(&self) -> Value {
+        json!(self
+            .items
+            .iter()
+            .map(|itm| itm.explain())
+            .collect::<Vec<_>>())
+    }
+
     pub fn to_sql(
         &self,
         templates: &PlanSqlTemplates,
@@ -110,7 +177,67 @@ impl Filter {
             .iter()
             .map(|itm| itm.to_sql(templates, context.clone()))
             .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|itm| !itm.is_empty())
+            .collect::<Vec<_>>()
             .join(" AND ");
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+
+    fn templates() -> PlanSqlTemplates {
+        PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![("filters/always_true", "1 = 1")]).unwrap(),
+        ))
+    }
+
+    fn items(items: &[&str]) -> Vec<String> {
+        items.iter().map(|itm| itm.to_string()).collect()
+    }
+
+    #[test]
+    fn and_group_drops_empty_items() {
+        let templates = templates();
+        assert_eq!(
+            FilterItem::group_sql(
+                &FilterGroupOperator::And,
+                items(&["(a)", "", "(b)"]),
+                &templates
+            )
+            .unwrap(),
+            "((a) AND (b))"
+        );
+        assert_eq!(
+            FilterItem::group_sql(&FilterGroupOperator::And, items(&["", ""]), &templates).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn or_group_with_empty_item_is_always_true() {
+        let templates = templates();
+        assert_eq!(
+            FilterItem::group_sql(
+                &FilterGroupOperator::Or,
+                items(&["(a)", "", "(b)"]),
+                &templates
+            )
+            .unwrap(),
+            "(1 = 1)"
+        );
+        assert_eq!(
+            FilterItem::group_sql(&FilterGroupOperator::Or, items(&["(a)", "(b)"]), &templates)
+                .unwrap(),
+            "((a) OR (b))"
+        );
+        assert_eq!(
+            FilterItem::group_sql(&FilterGroupOperator::Or, items(&[]), &templates).unwrap(),
+            ""
+        );
+    }
+}
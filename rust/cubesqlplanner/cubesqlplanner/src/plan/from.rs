@@ -2,6 +2,7 @@ use super::{Join, QueryPlan, Schema, Select};
 use crate::planner::sql_templates::PlanSqlTemplates;
 use crate::planner::{BaseCube, VisitorContext};
 use cubenativeutils::CubeError;
+use serde_json::{json, Value};
 use std::rc::Rc;
 
 #[derive(Clone)]
@@ -28,6 +29,14 @@ impl SingleSource {
         Ok(sql)
     }
 
+    pub fn explain(&self) -> Value {
+        match self {
+            SingleSource::Cube(cube) => json!({ "type": "cube", "cube": cube.name() }),
+            SingleSource::Subquery(s) => json!({ "type": "subquery", "query": s.explain() }),
+            SingleSource::TableReference(r, _) => json!({ "type": "table", "table": r }),
+        }
+    }
+
     pub fn schema(&self) -> Rc<Schema> {
         match self {
             SingleSource::Subquery(subquery) => subquery.schema(),
@@ -80,6 +89,13 @@ impl SingleAliasedSource {
 
         templates.query_aliased(&sql, &self.alias)
     }
+
+    pub fn explain(&self) -> Value {
+        json!({
+            "alias": self.alias,
+            "source": self.source.explain(),
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -146,4 +162,12 @@ impl From {
         };
         Ok(sql)
     }
+
+    pub fn explain(&self) -> Value {
+        match &self.source {
+            FromSource::Empty => Value::Null,
+            FromSource::Single(source) => source.explain(),
+            FromSource::Join(j) => j.explain(),
+        }
+    }
 }
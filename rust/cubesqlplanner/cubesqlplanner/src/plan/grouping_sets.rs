@@ -0,0 +1,111 @@
+// Alias of the column which tells subtotal rows apart, see `GroupingSets::grouping_id`
+pub const GROUPING_ID_ALIAS: &str = "__grouping_id";
+
+// Subtotal groupings of a select, in addition to the grouping by all of its group by columns
+#[derive(Clone, Debug, PartialEq)]
+pub enum GroupingSets {
+    // Each prefix of the group by columns down to the grand total
+    Rollup,
+    // Every combination of the group by columns
+    Cube,
+    // Explicit groupings as positions of the group by columns
+    Sets(Vec<Vec<usize>>),
+}
+
+impl GroupingSets {
+    // Number of groupings `expand` returns, None if it doesn't fit into usize
+    pub fn groupings_count(&self, columns_count: usize) -> Option<usize> {
+        match self {
+            Self::Rollup => Some(columns_count + 1),
+            Self::Cube => u32::try_from(columns_count)
+                .ok()
+                .and_then(|count| 1usize.checked_shl(count)),
+            Self::Sets(_) => Some(self.expand(columns_count).len()),
+        }
+    }
+
+    // Groupings as positions of the group by columns, the most detailed one first
+    pub fn expand(&self, columns_count: usize) -> Vec<Vec<usize>> {
+        let detailed = (0..columns_count).collect::<Vec<_>>();
+        match self {
+            Self::Rollup => (0..=columns_count)
+                .rev()
+                .map(|len| (0..len).collect())
+                .collect(),
+            Self::Cube => (0..1usize << columns_count)
+                .rev()
+                .map(|mask| {
+                    (0..columns_count)
+                        .filter(|i| mask & (1 << i) != 0)
+                        .collect()
+                })
+                .collect(),
+            Self::Sets(sets) => {
+                let mut result = vec![detailed];
+                for set in sets {
+                    let mut set = set.clone();
+                    set.sort();
+                    set.dedup();
+                    if !result.contains(&set) {
+                        result.push(set);
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    // Value of SQL `GROUPING()` of all group by columns for a grouping: a bit is set for
+    // each column which isn't grouped by, the first column is the most significant bit
+    pub fn grouping_id(grouping: &[usize], columns_count: usize) -> u64 {
+        (0..columns_count)
+            .filter(|i| !grouping.contains(i))
+            .fold(0, |id, i| id | (1 << (columns_count - 1 - i)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_rollup() {
+        assert_eq!(
+            GroupingSets::Rollup.expand(2),
+            vec![vec![0, 1], vec![0], vec![]]
+        );
+        assert_eq!(GroupingSets::Rollup.groupings_count(2), Some(3));
+    }
+
+    #[test]
+    fn expand_cube() {
+        assert_eq!(
+            GroupingSets::Cube.expand(2),
+            vec![vec![0, 1], vec![1], vec![0], vec![]]
+        );
+        assert_eq!(GroupingSets::Cube.groupings_count(2), Some(4));
+        assert_eq!(GroupingSets::Cube.groupings_count(200), None);
+    }
+
+    #[test]
+    fn expand_sets_adds_detailed_grouping() {
+        let sets = GroupingSets::Sets(vec![vec![1, 0], vec![0], vec![0], vec![]]);
+        assert_eq!(
+            sets.expand(3),
+            vec![vec![0, 1, 2], vec![0, 1], vec![0], vec![]]
+        );
+        assert_eq!(sets.groupings_count(3), Some(4));
+
+        let sets = GroupingSets::Sets(vec![vec![0, 1]]);
+        assert_eq!(sets.expand(2), vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn grouping_id_sets_bits_of_not_grouped_columns() {
+        assert_eq!(GroupingSets::grouping_id(&[0, 1, 2], 3), 0);
+        assert_eq!(GroupingSets::grouping_id(&[0, 1], 3), 0b001);
+        assert_eq!(GroupingSets::grouping_id(&[1], 3), 0b101);
+        assert_eq!(GroupingSets::grouping_id(&[], 3), 0b111);
+        assert_eq!(GroupingSets::grouping_id(&[], 0), 0);
+    }
+}
@@ -1,8 +1,9 @@
 use super::{Expr, SingleAliasedSource};
 use crate::planner::sql_templates::PlanSqlTemplates;
-use crate::planner::{BaseJoinCondition, VisitorContext};
+use crate::planner::{AsOfJoinCondition, BaseJoinCondition, VisitorContext};
 use cubenativeutils::CubeError;
 use lazy_static::lazy_static;
+use serde_json::{json, Value};
 
 use std::rc::Rc;
 
@@ -91,13 +92,15 @@ pub struct DimensionJoinCondition {
     // AND (... OR ...)
     conditions: Vec<Vec<(Expr, Expr)>>,
     null_check: bool,
+    legacy_format: bool,
 }
 
 impl DimensionJoinCondition {
-    pub fn new(conditions: Vec<Vec<(Expr, Expr)>>, null_check: bool) -> Self {
+    pub fn new(conditions: Vec<Vec<(Expr, Expr)>>, null_check: bool, legacy_format: bool) -> Self {
         Self {
             conditions,
             null_check,
+            legacy_format,
         }
     }
 
@@ -112,16 +115,19 @@ impl DimensionJoinCondition {
             self.conditions
                 .iter()
                 .map(|or_conditions| -> Result<_, CubeError> {
-                    Ok(format!(
-                        "({})",
-                        or_conditions
-                            .iter()
-                            .map(|(left, right)| -> Result<String, CubeError> {
-                                self.dimension_condition(templates, context.clone(), left, right)
-                            })
-                            .collect::<Result<Vec<_>, _>>()?
-                            .join(" OR ")
-                    ))
+                    let sql = or_conditions
+                        .iter()
+                        .map(|(left, right)| -> Result<String, CubeError> {
+                            self.dimension_condition(templates, context.clone(), left, right)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join(" OR ");
+                    // Legacy JS planner doesn't wrap single dimension condition
+                    if self.legacy_format && or_conditions.len() == 1 {
+                        Ok(sql)
+                    } else {
+                        Ok(format!("({})", sql))
+                    }
                 })
                 .collect::<Result<Vec<_>, _>>()?
                 .join(" AND ")
@@ -138,7 +144,12 @@ impl DimensionJoinCondition {
     ) -> Result<String, CubeError> {
         let left_sql = left_expr.to_sql(templates, context.clone())?;
         let right_sql = right_expr.to_sql(templates, context.clone())?;
-        templates.join_by_dimension_conditions(&left_sql, &right_sql, self.null_check)
+        templates.join_by_dimension_conditions(
+            &left_sql,
+            &right_sql,
+            self.null_check,
+            !self.legacy_format,
+        )
     }
 }
 
@@ -146,11 +157,20 @@ pub enum JoinCondition {
     DimensionJoinCondition(DimensionJoinCondition),
     BaseJoinCondition(Rc<dyn BaseJoinCondition>),
     RollingWindowJoinCondition(RollingWindowJoinCondition),
+    AsOfJoinCondition(Rc<AsOfJoinCondition>),
 }
 
 impl JoinCondition {
-    pub fn new_dimension_join(conditions: Vec<Vec<(Expr, Expr)>>, null_check: bool) -> Self {
-        Self::DimensionJoinCondition(DimensionJoinCondition::new(conditions, null_check))
+    pub fn new_dimension_join(
+        conditions: Vec<Vec<(Expr, Expr)>>,
+        null_check: bool,
+        legacy_format: bool,
+    ) -> Self {
+        Self::DimensionJoinCondition(DimensionJoinCondition::new(
+            conditions,
+            null_check,
+            legacy_format,
+        ))
     }
 
     pub fn new_rolling_join(
@@ -173,6 +193,10 @@ impl JoinCondition {
         Self::BaseJoinCondition(base)
     }
 
+    pub fn new_as_of_join(condition: Rc<AsOfJoinCondition>) -> Self {
+        Self::AsOfJoinCondition(condition)
+    }
+
     pub fn to_sql(
         &self,
         templates: &PlanSqlTemplates,
@@ -182,6 +206,7 @@ impl JoinCondition {
             JoinCondition::DimensionJoinCondition(cond) => cond.to_sql(templates, context),
             JoinCondition::BaseJoinCondition(cond) => cond.to_sql(context),
             JoinCondition::RollingWindowJoinCondition(cond) => cond.to_sql(templates, context),
+            JoinCondition::AsOfJoinCondition(cond) => cond.to_sql(context),
         }
     }
 }
@@ -203,12 +228,61 @@ pub enum JoinType {
     Full,
 }
 
+impl JoinType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JoinType::Inner => "inner",
+            JoinType::Left => "left",
+            JoinType::Full => "full",
+        }
+    }
+}
+
 impl JoinItem {
+    pub fn explain(&self) -> Value {
+        let condition = match &self.on {
+            JoinCondition::DimensionJoinCondition(_) => "dimensions",
+            JoinCondition::BaseJoinCondition(_) => "sql",
+            JoinCondition::RollingWindowJoinCondition(_) => "rolling_window",
+            JoinCondition::AsOfJoinCondition(_) => "as_of",
+        };
+        json!({
+            "type": self.join_type.name(),
+            "condition": condition,
+            "source": self.from.explain(),
+        })
+    }
+
     pub fn to_sql(
         &self,
         templates: &PlanSqlTemplates,
         context: Rc<VisitorContext>,
     ) -> Result<String, CubeError> {
+        if let JoinCondition::AsOfJoinCondition(cond) = &self.on {
+            let (condition, time, match_time) = cond.to_sql_parts(context.clone())?;
+            if !templates.supports_as_of_join() {
+                let from_source = SingleAliasedSource::new_from_cube(
+                    cond.from_cube().clone(),
+                    Some(cond.from_alias().clone()),
+                );
+                return templates.as_of_join_by_window(
+                    &from_source.to_sql(templates, context.clone())?,
+                    &self.from.to_sql(templates, context.clone())?,
+                    &self.from.alias,
+                    &cond.from_keys_sql(context)?,
+                    &condition,
+                    &time,
+                    &match_time,
+                );
+            }
+            return templates.as_of_join(
+                &self.from.to_sql(templates, context)?,
+                &self.from.alias,
+                &condition,
+                &time,
+                &match_time,
+            );
+        }
         let on_sql = self.on.to_sql(templates, context.clone())?;
         let result = templates.join(
             &self.from.to_sql(templates, context)?,
@@ -220,6 +294,14 @@ impl JoinItem {
 }
 
 impl Join {
+    pub fn explain(&self) -> Value {
+        json!({
+            "type": "join",
+            "root": self.root.explain(),
+            "joins": self.joins.iter().map(|j| j.explain()).collect::<Vec<_>>(),
+        })
+    }
+
     pub fn to_sql(
         &self,
         templates: &PlanSqlTemplates,
@@ -238,3 +320,70 @@ impl Join {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+    use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
+
+    fn templates() -> PlanSqlTemplates {
+        PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![
+                ("quotes/identifiers", "\""),
+                ("quotes/escape", "\"\""),
+                (
+                    "expressions/column_reference",
+                    "{% if table_name %}{{ table_name }}.{% endif %}{{ name }}",
+                ),
+                ("expressions/binary", "{{ left }} {{ op }} {{ right }}"),
+                (
+                    "expressions/is_null",
+                    "{{ expr }} IS {% if negate %}NOT {% endif %}NULL",
+                ),
+                ("operators/is_not_distinct_from", "IS NOT DISTINCT FROM"),
+            ])
+            .unwrap(),
+        ))
+    }
+
+    // `q.<name> = k.<name>` for every name, grouped into OR conditions
+    fn condition_sql(conditions: Vec<Vec<&str>>, legacy_format: bool) -> String {
+        let conditions = conditions
+            .into_iter()
+            .map(|or_conditions| {
+                or_conditions
+                    .into_iter()
+                    .map(|name| {
+                        (
+                            Expr::new_reference(Some("q".to_string()), name.to_string()),
+                            Expr::new_reference(Some("k".to_string()), name.to_string()),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        DimensionJoinCondition::new(conditions, true, legacy_format)
+            .to_sql(
+                &templates(),
+                Rc::new(VisitorContext::new(&SqlNodesFactory::new())),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn dimension_conditions_use_is_not_distinct_from() {
+        assert_eq!(
+            condition_sql(vec![vec!["id"], vec!["a", "b"]], false),
+            r#"("q"."id" IS NOT DISTINCT FROM "k"."id") AND ("q"."a" IS NOT DISTINCT FROM "k"."a" OR "q"."b" IS NOT DISTINCT FROM "k"."b")"#
+        );
+    }
+
+    #[test]
+    fn legacy_dimension_conditions_match_js_planner() {
+        assert_eq!(
+            condition_sql(vec![vec!["id"], vec!["a", "b"]], true),
+            r#"("q"."id" = "k"."id" OR ("q"."id" IS NULL AND "k"."id" IS NULL)) AND (("q"."a" = "k"."a" OR ("q"."a" IS NULL AND "k"."a" IS NULL)) OR ("q"."b" = "k"."b" OR ("q"."b" IS NULL AND "k"."b" IS NULL)))"#
+        );
+    }
+}
@@ -3,11 +3,13 @@ pub mod cte;
 pub mod expression;
 pub mod filter;
 pub mod from;
+pub mod grouping_sets;
 pub mod join;
 pub mod order;
 pub mod query_plan;
 pub mod schema;
 pub mod select;
+pub mod select_list;
 pub mod time_series;
 pub mod union;
 
@@ -16,10 +18,12 @@ pub use cte::Cte;
 pub use expression::{Expr, MemberExpression};
 pub use filter::{Filter, FilterGroup, FilterItem};
 pub use from::{From, FromSource, SingleAliasedSource, SingleSource};
+pub use grouping_sets::{GroupingSets, GROUPING_ID_ALIAS};
 pub use join::{Join, JoinCondition, JoinItem, RollingWindowJoinCondition};
 pub use order::OrderBy;
 pub use query_plan::QueryPlan;
 pub use schema::{QualifiedColumnName, Schema, SchemaColumn};
 pub use select::{AliasedExpr, Select};
+pub use select_list::SelectListWriter;
 pub use time_series::TimeSeries;
 pub use union::Union;
@@ -4,11 +4,22 @@ pub struct OrderBy {
     pub expr: Expr,
     pub pos: usize,
     pub desc: bool,
+    pub nulls_first: Option<bool>,
 }
 
 impl OrderBy {
     pub fn new(expr: Expr, pos: usize, desc: bool) -> OrderBy {
-        OrderBy { expr, pos, desc }
+        OrderBy {
+            expr,
+            pos,
+            desc,
+            nulls_first: None,
+        }
+    }
+
+    pub fn with_nulls_first(mut self, nulls_first: Option<bool>) -> OrderBy {
+        self.nulls_first = nulls_first;
+        self
     }
 
     pub fn asc_str(&self) -> &str {
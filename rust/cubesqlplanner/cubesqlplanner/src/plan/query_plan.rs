@@ -1,6 +1,7 @@
 use super::{Schema, Select, TimeSeries, Union};
 use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
+use serde_json::Value;
 use std::rc::Rc;
 
 pub enum QueryPlan {
@@ -17,6 +18,15 @@ impl QueryPlan {
             QueryPlan::TimeSeries(series) => series.schema(),
         }
     }
+
+    pub fn explain(&self) -> Value {
+        match self {
+            QueryPlan::Select(s) => s.explain(),
+            QueryPlan::Union(u) => u.explain(),
+            QueryPlan::TimeSeries(series) => series.explain(),
+        }
+    }
+
     pub fn to_sql(&self, templates: &PlanSqlTemplates) -> Result<String, CubeError> {
         match self {
             QueryPlan::Select(s) => s.to_sql(templates),
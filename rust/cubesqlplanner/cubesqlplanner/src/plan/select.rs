@@ -1,12 +1,24 @@
-use super::{Cte, Expr, Filter, From, OrderBy, Schema};
+use super::expression::GroupingIdExpression;
+use super::expression::{
+    HexEncodedExpression, NullLabelExpression, RoundedExpression, UnitConversionExpression,
+    ValueMappedExpression,
+};
+use super::select_list::SelectListWriter;
+use super::{Cte, Expr, Filter, From, GroupingSets, OrderBy, Schema, GROUPING_ID_ALIAS};
+use crate::cube_bridge::measure_definition::MeasureRounding;
 use crate::planner::sql_templates::PlanSqlTemplates;
 use crate::planner::sql_templates::{
     TemplateGroupByColumn, TemplateOrderByColumn, TemplateProjectionColumn,
 };
 use crate::planner::VisitorContext;
 use cubenativeutils::CubeError;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::rc::Rc;
 
+// Union fallback of subtotals runs a scan per grouping
+const MAX_UNION_GROUPINGS: usize = 16;
+
 pub struct AliasedExpr {
     pub expr: Expr,
     pub alias: String,
@@ -37,6 +49,7 @@ pub struct Select {
     pub(super) from: Rc<From>,
     pub(super) filter: Option<Filter>,
     pub(super) group_by: Vec<Expr>,
+    pub(super) grouping_sets: Option<GroupingSets>,
     pub(super) having: Option<Filter>,
     pub(super) order_by: Vec<OrderBy>,
     pub(super) context: Rc<VisitorContext>,
@@ -52,12 +65,218 @@ impl Select {
         self.schema.clone()
     }
 
+    // Structure of the select before rendering: sources, conditions and paging without sql
+    pub fn explain(&self) -> Value {
+        json!({
+            "type": "select",
+            "ctes": self.ctes.iter().map(|cte| cte.explain()).collect::<Vec<_>>(),
+            "columns": self
+                .projection_columns
+                .iter()
+                .map(|c| c.alias.clone())
+                .collect::<Vec<_>>(),
+            "from": self.from.explain(),
+            "filter": self.filter.as_ref().map(|f| f.explain()),
+            "groupBy": self.group_by.len(),
+            "groupingSets": self
+                .grouping_sets
+                .as_ref()
+                .map(|grouping_sets| format!("{:?}", grouping_sets)),
+            "having": self.having.as_ref().map(|f| f.explain()),
+            "orderBy": self.order_by.len(),
+            "distinct": self.is_distinct,
+            "limit": self.limit,
+            "offset": self.offset,
+        })
+    }
+
+    // Order and paging only pick which rows are returned, not how many there are
+    pub fn remove_pagination(&mut self) {
+        self.order_by = vec![];
+        self.limit = None;
+        self.offset = None;
+    }
+
+    // Should be applied only to the outermost select: intermediate values
+    // of multi-stage and multi-fact queries are kept at full precision
+    // and in source units
+    pub fn round_measures(&mut self, roundings: &HashMap<String, MeasureRounding>) {
+        for (member_name, rounding) in roundings.iter() {
+            self.wrap_member_projection(member_name, |expr| {
+                Expr::Rounded(RoundedExpression {
+                    expr: Box::new(expr),
+                    rounding: rounding.clone(),
+                })
+            });
+        }
+    }
+
+    pub fn convert_units(&mut self, factors: &HashMap<String, f64>) {
+        for (member_name, factor) in factors.iter() {
+            self.wrap_member_projection(member_name, |expr| {
+                Expr::UnitConversion(UnitConversionExpression {
+                    expr: Box::new(expr),
+                    factor: *factor,
+                })
+            });
+        }
+    }
+
+    // Ordering is wrapped as well so labeled rows are sorted by the label
+    // and not by NULL placement of the dialect
+    pub fn label_null_dimensions(&mut self, members: &Vec<String>, label: &String) {
+        let wrap = |expr| {
+            Expr::NullLabel(NullLabelExpression {
+                expr: Box::new(expr),
+                label: label.clone(),
+            })
+        };
+        for member_name in members.iter() {
+            for pos in self.wrap_member_projection(member_name, wrap) {
+                for order in self.order_by.iter_mut() {
+                    if order.pos == pos {
+                        order.expr = wrap(order.expr.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    // Values are ordered by their labels, the same as the rows are shown
+    pub fn map_dimension_values(&mut self, member_name: &String, when_then: Vec<(String, String)>) {
+        let wrap = |expr| {
+            Expr::ValueMapped(ValueMappedExpression {
+                expr: Box::new(expr),
+                when_then: when_then.clone(),
+            })
+        };
+        for pos in self.wrap_member_projection(member_name, wrap) {
+            for order in self.order_by.iter_mut() {
+                if order.pos == pos {
+                    order.expr = wrap(order.expr.clone());
+                }
+            }
+        }
+    }
+
+    // Hex sorts the same as the bytes it encodes, so ordering is kept on the raw values
+    pub fn hex_encode_dimensions(&mut self, members: &Vec<String>) {
+        for member_name in members.iter() {
+            self.wrap_member_projection(member_name, |expr| {
+                Expr::HexEncoded(HexEncodedExpression {
+                    expr: Box::new(expr),
+                })
+            });
+        }
+    }
+
+    // Returns 1-based positions of wrapped projection columns
+    fn wrap_member_projection(
+        &mut self,
+        member_name: &String,
+        wrap: impl Fn(Expr) -> Expr,
+    ) -> Vec<usize> {
+        let Some(column) = self.schema.find_column_for_member(member_name) else {
+            return vec![];
+        };
+        let mut positions = vec![];
+        for (i, projection) in self.projection_columns.iter_mut().enumerate() {
+            if &projection.alias == column.name() {
+                projection.expr = wrap(projection.expr.clone());
+                positions.push(i + 1);
+            }
+        }
+        positions
+    }
+
     pub fn to_sql(&self, templates: &PlanSqlTemplates) -> Result<String, CubeError> {
-        let projection = if !self.projection_columns.is_empty() {
-            self.projection_columns
+        match &self.grouping_sets {
+            Some(grouping_sets) if !templates.supports_grouping_sets() => {
+                self.grouping_sets_union_sql(grouping_sets, templates)
+            }
+            _ => self.grouping_to_sql(templates, None),
+        }
+    }
+
+    // Dialects without grouping sets get a union of a select per grouping. Columns which
+    // aren't grouped by are NULL in subtotal rows, order and paging apply to the whole union
+    fn grouping_sets_union_sql(
+        &self,
+        grouping_sets: &GroupingSets,
+        templates: &PlanSqlTemplates,
+    ) -> Result<String, CubeError> {
+        match grouping_sets.groupings_count(self.group_by.len()) {
+            Some(count) if count <= MAX_UNION_GROUPINGS => {}
+            _ => {
+                return Err(CubeError::user(format!(
+                    "Subtotals of {} dimensions exceed {} groupings, which is the limit for databases without grouping sets support",
+                    self.group_by.len(),
+                    MAX_UNION_GROUPINGS
+                )));
+            }
+        }
+        let union = grouping_sets
+            .expand(self.group_by.len())
+            .iter()
+            .map(|grouping| self.grouping_to_sql(templates, Some(grouping)))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(" UNION ALL ");
+        let from = templates.query_aliased(&format!("({})", union), "grouping_sets")?;
+        templates.select(
+            self.ctes_sql(templates)?,
+            &from,
+            vec![TemplateProjectionColumn {
+                expr: format!("*"),
+                alias: format!(""),
+                aliased: format!("*"),
+            }],
+            None,
+            vec![],
+            None,
+            None,
+            self.order_by_sql(templates, true)?,
+            self.limit,
+            self.offset,
+            false,
+        )
+    }
+
+    // Grouping is a subset of the group by positions to render a single union branch with
+    fn grouping_to_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        grouping: Option<&Vec<usize>>,
+    ) -> Result<String, CubeError> {
+        let is_grouped = |i: &usize| grouping.map_or(true, |grouping| grouping.contains(i));
+        let select_list = if !self.projection_columns.is_empty() {
+            let columns = self
+                .projection_columns
                 .iter()
-                .map(|p| p.to_sql(templates, self.context.clone()))
-                .collect::<Result<Vec<_>, _>>()?
+                .enumerate()
+                .map(|(i, column)| {
+                    let expr = if i < self.group_by.len() && !is_grouped(&i) {
+                        Expr::Null
+                    } else {
+                        column.expr.clone()
+                    };
+                    AliasedExpr::new(expr, column.alias.clone())
+                })
+                .chain(self.grouping_sets.is_some().then(|| {
+                    AliasedExpr::new(
+                        Expr::GroupingId(GroupingIdExpression {
+                            group_by: self.group_by.clone(),
+                            grouping: grouping.cloned(),
+                        }),
+                        GROUPING_ID_ALIAS.to_string(),
+                    )
+                }))
+                .collect::<Vec<_>>();
+            Some(SelectListWriter::new(templates, self.context.clone()).write(columns.iter())?)
+        } else {
+            None
+        };
+        let projection = if select_list.is_some() {
+            SelectListWriter::placeholder_projection()
         } else {
             vec![TemplateProjectionColumn {
                 expr: format!("*"),
@@ -66,8 +285,9 @@ impl Select {
             }]
         };
 
+        // Filter of empty groups only renders nothing
         let where_condition = if let Some(filter) = &self.filter {
-            Some(filter.to_sql(templates, self.context.clone())?)
+            Some(filter.to_sql(templates, self.context.clone())?).filter(|sql| !sql.is_empty())
         } else {
             None
         };
@@ -76,6 +296,7 @@ impl Select {
             .group_by
             .iter()
             .enumerate()
+            .filter(|(i, _)| is_grouped(i))
             .map(|(i, expr)| -> Result<_, CubeError> {
                 let expr = expr.to_sql(templates, self.context.clone())?;
                 Ok(TemplateGroupByColumn { expr, index: i + 1 })
@@ -83,53 +304,216 @@ impl Select {
             .collect::<Result<Vec<_>, _>>()?;
 
         let having = if let Some(having) = &self.having {
-            Some(having.to_sql(templates, self.context.clone())?)
+            Some(having.to_sql(templates, self.context.clone())?).filter(|sql| !sql.is_empty())
         } else {
             None
         };
 
-        let ctes = self
-            .ctes
+        let from = self.from.to_sql(templates, self.context.clone())?;
+
+        // Union branches are wrapped by the select which holds ctes, order and paging
+        let result = if grouping.is_some() {
+            templates.select(
+                vec![],
+                &from,
+                projection,
+                where_condition,
+                group_by,
+                None,
+                having,
+                vec![],
+                None,
+                None,
+                self.is_distinct,
+            )?
+        } else {
+            templates.select(
+                self.ctes_sql(templates)?,
+                &from,
+                projection,
+                where_condition,
+                group_by,
+                self.grouping_sets.as_ref(),
+                having,
+                self.order_by_sql(templates, false)?,
+                self.limit,
+                self.offset,
+                self.is_distinct,
+            )?
+        };
+        let result = if let Some(select_list) = select_list {
+            SelectListWriter::substitute(result, &select_list)?
+        } else {
+            result
+        };
+
+        /* let res = format!(
+            "{ctes}SELECT\
+            \n      {distinct}{projection}\
+            \n    FROM\
+            \n{from}{where_condition}{group_by}{having}{order_by}{limit}{offset}",
+        ); */
+        Ok(result)
+    }
+
+    fn ctes_sql(&self, templates: &PlanSqlTemplates) -> Result<Vec<String>, CubeError> {
+        self.ctes
             .iter()
             .map(|cte| -> Result<_, CubeError> {
                 templates.cte(&cte.query().to_sql(templates)?, &cte.name().clone())
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect()
+    }
 
-        let order_by = self
-            .order_by
+    // Selects wrapping this one can only order by the aliases of its columns
+    fn order_by_sql(
+        &self,
+        templates: &PlanSqlTemplates,
+        by_alias: bool,
+    ) -> Result<Vec<TemplateOrderByColumn>, CubeError> {
+        self.order_by
             .iter()
             .map(|itm| -> Result<_, CubeError> {
-                let expr = templates.order_by(
-                    &itm.expr.to_sql(templates, self.context.clone())?,
-                    Some(itm.pos),
-                    !itm.desc,
-                )?;
+                let column = by_alias
+                    .then(|| self.projection_columns.get(itm.pos - 1))
+                    .flatten();
+                let expr = if let Some(column) = column {
+                    templates.quote_identifier(&column.alias)?
+                } else {
+                    itm.expr.to_sql(templates, self.context.clone())?
+                };
+                let expr = templates.order_by(&expr, Some(itm.pos), !itm.desc, itm.nulls_first)?;
                 Ok(TemplateOrderByColumn { expr })
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect()
+    }
+}
 
-        let from = self.from.to_sql(templates, self.context.clone())?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+    use crate::plan::{FromSource, SchemaColumn};
+    use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
 
-        let result = templates.select(
-            ctes,
-            &from,
-            projection,
-            where_condition,
-            group_by,
-            having,
-            order_by,
-            self.limit,
-            self.offset,
-            self.is_distinct,
-        )?;
+    fn templates() -> PlanSqlTemplates {
+        PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![
+                ("quotes/identifiers", "\""),
+                ("quotes/escape", "\"\""),
+                (
+                    "expressions/column_reference",
+                    "{% if table_name %}{{ table_name }}.{% endif %}{{ name }}",
+                ),
+                ("expressions/cast", "CAST({{ expr }} AS {{ data_type }})"),
+                ("expressions/round", "ROUND({{ expr }}, {{ scale }})"),
+                ("expressions/unit_conversion", "({{ expr }}) * {{ factor }}"),
+                ("types/decimal", "DECIMAL({{ precision }},{{ scale }})"),
+            ])
+            .unwrap(),
+        ))
+    }
 
-        /* let res = format!(
-            "{ctes}SELECT\
-            \n      {distinct}{projection}\
-            \n    FROM\
-            \n{from}{where_condition}{group_by}{having}{order_by}{limit}{offset}",
-        ); */
-        Ok(result)
+    // Outer select of `orders.amount` and `orders.count` columns of the `q` subquery
+    fn outer_select() -> Select {
+        let columns = [
+            ("orders__amount", "orders.amount"),
+            ("orders__count", "orders.count"),
+        ];
+        Select {
+            projection_columns: columns
+                .iter()
+                .map(|(alias, _)| {
+                    AliasedExpr::new(
+                        Expr::new_reference(Some("q".to_string()), alias.to_string()),
+                        alias.to_string(),
+                    )
+                })
+                .collect(),
+            from: From::new(FromSource::Empty),
+            filter: None,
+            group_by: vec![],
+            grouping_sets: None,
+            having: None,
+            order_by: vec![],
+            context: Rc::new(VisitorContext::new(&SqlNodesFactory::new())),
+            ctes: vec![],
+            is_distinct: false,
+            limit: None,
+            offset: None,
+            schema: Rc::new(Schema::new(
+                columns
+                    .iter()
+                    .map(|(alias, member)| {
+                        SchemaColumn::new(alias.to_string(), Some(member.to_string()))
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    fn projection_sql(select: &Select) -> Vec<String> {
+        let templates = templates();
+        select
+            .projection_columns
+            .iter()
+            .map(|column| {
+                column
+                    .expr
+                    .to_sql(&templates, select.context.clone())
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_measures_wraps_only_rounded_members() {
+        let mut select = outer_select();
+        select.round_measures(&HashMap::from([(
+            "orders.amount".to_string(),
+            MeasureRounding {
+                scale: 2,
+                precision: None,
+            },
+        )]));
+        assert_eq!(
+            projection_sql(&select),
+            vec![
+                "ROUND(\"q\".\"orders__amount\", 2)",
+                "\"q\".\"orders__count\""
+            ]
+        );
+    }
+
+    #[test]
+    fn round_measures_with_precision_casts_to_decimal() {
+        let mut select = outer_select();
+        select.round_measures(&HashMap::from([(
+            "orders.amount".to_string(),
+            MeasureRounding {
+                scale: 2,
+                precision: Some(10),
+            },
+        )]));
+        assert_eq!(
+            projection_sql(&select),
+            vec![
+                "CAST(\"q\".\"orders__amount\" AS DECIMAL(10,2))",
+                "\"q\".\"orders__count\""
+            ]
+        );
+    }
+
+    #[test]
+    fn convert_units_multiplies_converted_members() {
+        let mut select = outer_select();
+        select.convert_units(&HashMap::from([("orders.amount".to_string(), 0.5)]));
+        assert_eq!(
+            projection_sql(&select),
+            vec![
+                "(\"q\".\"orders__amount\") * 0.5",
+                "\"q\".\"orders__count\""
+            ]
+        );
     }
 }
@@ -0,0 +1,62 @@
+use super::AliasedExpr;
+use crate::planner::sql_templates::{PlanSqlTemplates, TemplateProjectionColumn};
+use crate::planner::VisitorContext;
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+// Placeholder passed to the select template instead of the real projection.
+// Must not look like a param (`$_0_$`) or named param (`$:name:$`) placeholder
+const SELECT_LIST_PLACEHOLDER: &str = "$__select_list__$";
+
+// Writes projection columns directly into a single buffer instead of collecting
+// a vector of rendered columns first. Queries with hundreds of members otherwise
+// keep every rendered column alive twice until the select template is rendered
+pub struct SelectListWriter<'a> {
+    templates: &'a PlanSqlTemplates,
+    context: Rc<VisitorContext>,
+}
+
+impl<'a> SelectListWriter<'a> {
+    pub fn new(templates: &'a PlanSqlTemplates, context: Rc<VisitorContext>) -> Self {
+        Self { templates, context }
+    }
+
+    pub fn write<'b>(
+        &self,
+        columns: impl ExactSizeIterator<Item = &'b AliasedExpr>,
+    ) -> Result<String, CubeError> {
+        // Rough estimate of a rendered aliased column to avoid most reallocations
+        let mut buffer = String::with_capacity(columns.len() * 64);
+        for (i, column) in columns.enumerate() {
+            if i > 0 {
+                buffer.push_str(", ");
+            }
+            let expr = column.expr.to_sql(self.templates, self.context.clone())?;
+            buffer.push_str(&self.templates.column_aliased(&expr, &column.alias)?);
+        }
+        Ok(buffer)
+    }
+
+    // Projection passed to the select template; rendered select list is substituted
+    // with `substitute` once the template is rendered
+    pub fn placeholder_projection() -> Vec<TemplateProjectionColumn> {
+        vec![TemplateProjectionColumn {
+            expr: SELECT_LIST_PLACEHOLDER.to_string(),
+            alias: String::new(),
+            aliased: SELECT_LIST_PLACEHOLDER.to_string(),
+        }]
+    }
+
+    pub fn substitute(sql: String, select_list: &str) -> Result<String, CubeError> {
+        let Some(pos) = sql.find(SELECT_LIST_PLACEHOLDER) else {
+            return Err(CubeError::internal(
+                "Select template doesn't render projection columns".to_string(),
+            ));
+        };
+        let mut result = String::with_capacity(sql.len() + select_list.len());
+        result.push_str(&sql[..pos]);
+        result.push_str(select_list);
+        result.push_str(&sql[pos + SELECT_LIST_PLACEHOLDER.len()..]);
+        Ok(result)
+    }
+}
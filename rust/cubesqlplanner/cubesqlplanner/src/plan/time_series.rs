@@ -1,6 +1,7 @@
 use super::{Schema, SchemaColumn};
 use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
+use serde_json::{json, Value};
 use std::rc::Rc;
 
 pub struct TimeSeries {
@@ -33,6 +34,15 @@ impl TimeSeries {
         self.schema.clone()
     }
 
+    pub fn explain(&self) -> Value {
+        json!({
+            "type": "time_series",
+            "timeDimension": self.time_dimension_name,
+            "from": self.from_date,
+            "to": self.to_date,
+        })
+    }
+
     pub fn to_sql(&self, templates: &PlanSqlTemplates) -> Result<String, CubeError> {
         templates.time_series_select(
             self.from_date.clone(),
@@ -1,6 +1,7 @@
 use super::{QueryPlan, Schema};
 use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
+use serde_json::{json, Value};
 use std::rc::Rc;
 
 pub struct Union {
@@ -22,6 +23,13 @@ impl Union {
         self.schema.clone()
     }
 
+    pub fn explain(&self) -> Value {
+        json!({
+            "type": "union",
+            "queries": self.union.iter().map(|q| q.explain()).collect::<Vec<_>>(),
+        })
+    }
+
     pub fn to_sql(&self, templates: &PlanSqlTemplates) -> Result<String, CubeError> {
         let res = self
             .union
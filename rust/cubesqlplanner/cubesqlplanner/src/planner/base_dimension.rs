@@ -1,5 +1,6 @@
 use super::query_tools::QueryTools;
 use super::sql_evaluator::MemberSymbol;
+use super::sql_templates::PlanSqlTemplates;
 use super::{evaluate_with_context, BaseMember, VisitorContext};
 use cubenativeutils::CubeError;
 use std::rc::Rc;
@@ -14,7 +15,21 @@ pub struct BaseDimension {
 
 impl BaseMember for BaseDimension {
     fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
-        evaluate_with_context(&self.member_evaluator, self.query_tools.clone(), context)
+        let sql = evaluate_with_context(
+            &self.member_evaluator,
+            self.query_tools.clone(),
+            context.clone(),
+        )?;
+        match self.member_evaluator.as_ref() {
+            // Subquery columns of multi-value dimensions are already unnested
+            MemberSymbol::Dimension(dimension)
+                if dimension.is_multi_value() && !context.is_reference(&self.dimension) =>
+            {
+                PlanSqlTemplates::new(self.query_tools.templates_render())
+                    .unnest(&sql, dimension.is_multi_value_dedupe())
+            }
+            _ => Ok(sql),
+        }
     }
 
     fn alias_name(&self) -> String {
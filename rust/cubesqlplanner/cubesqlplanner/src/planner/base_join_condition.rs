@@ -1,6 +1,10 @@
 use super::query_tools::QueryTools;
 use super::sql_evaluator::SqlCall;
-use super::{evaluate_sql_call_with_context, VisitorContext};
+use super::sql_templates::{FilterTemplates, PlanSqlTemplates};
+use super::{
+    evaluate_sql_call_with_context, evaluate_with_context, BaseCube, BaseMember, VisitorContext,
+};
+use crate::cube_bridge::cube_definition::CubeValidity;
 use cubenativeutils::CubeError;
 use std::rc::Rc;
 pub trait BaseJoinCondition {
@@ -27,3 +31,122 @@ impl BaseJoinCondition for SqlJoinCondition {
         evaluate_sql_call_with_context(&self.sql_call, self.query_tools.clone(), context)
     }
 }
+
+// Join to a snapshot (SCD type 2) cube which matches a row to the version of the joined
+// cube valid at its time
+pub struct SnapshotJoinCondition {
+    condition: Rc<SqlJoinCondition>,
+    valid_at: Rc<SqlCall>,
+    alias: String,
+    validity: CubeValidity,
+    query_tools: Rc<QueryTools>,
+}
+
+impl SnapshotJoinCondition {
+    pub fn try_new(
+        query_tools: Rc<QueryTools>,
+        condition: Rc<SqlJoinCondition>,
+        valid_at: Rc<SqlCall>,
+        alias: String,
+        validity: CubeValidity,
+    ) -> Result<Rc<Self>, CubeError> {
+        Ok(Rc::new(Self {
+            condition,
+            valid_at,
+            alias,
+            validity,
+            query_tools,
+        }))
+    }
+}
+
+impl BaseJoinCondition for SnapshotJoinCondition {
+    fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        let condition = self.condition.to_sql(context.clone())?;
+        let valid_at =
+            evaluate_sql_call_with_context(&self.valid_at, self.query_tools.clone(), context)?;
+        let alias = PlanSqlTemplates::new(self.query_tools.templates_render())
+            .quote_identifier(&self.alias)?;
+        let validity = FilterTemplates::new(self.query_tools.templates_render()).validity_range(
+            &valid_at,
+            &format!("{}.{}", alias, self.validity.valid_from),
+            &format!("{}.{}", alias, self.validity.valid_to),
+        )?;
+        Ok(format!("({}) AND {}", condition, validity))
+    }
+}
+
+pub struct AsOfJoinCondition {
+    condition: Rc<SqlJoinCondition>,
+    time: Rc<SqlCall>,
+    match_time: Rc<SqlCall>,
+    // Joining cube and its primary keys, which identify joining rows when the nearest
+    // preceding row is picked by a window function
+    from_cube: Rc<BaseCube>,
+    from_alias: String,
+    from_keys: Vec<Rc<dyn BaseMember>>,
+    query_tools: Rc<QueryTools>,
+}
+
+impl AsOfJoinCondition {
+    pub fn try_new(
+        query_tools: Rc<QueryTools>,
+        condition: Rc<SqlJoinCondition>,
+        time: Rc<SqlCall>,
+        match_time: Rc<SqlCall>,
+        from_cube: Rc<BaseCube>,
+        from_alias: String,
+        from_keys: Vec<Rc<dyn BaseMember>>,
+    ) -> Result<Rc<Self>, CubeError> {
+        Ok(Rc::new(Self {
+            condition,
+            time,
+            match_time,
+            from_cube,
+            from_alias,
+            from_keys,
+            query_tools,
+        }))
+    }
+
+    pub fn from_cube(&self) -> &Rc<BaseCube> {
+        &self.from_cube
+    }
+
+    pub fn from_alias(&self) -> &String {
+        &self.from_alias
+    }
+
+    pub fn from_keys_sql(&self, context: Rc<VisitorContext>) -> Result<Vec<String>, CubeError> {
+        self.from_keys
+            .iter()
+            .map(|key| {
+                evaluate_with_context(
+                    &key.member_evaluator(),
+                    self.query_tools.clone(),
+                    context.clone(),
+                )
+            })
+            .collect()
+    }
+
+    // Returns the join condition, time of the joining row and time of the joined one
+    pub fn to_sql_parts(
+        &self,
+        context: Rc<VisitorContext>,
+    ) -> Result<(String, String, String), CubeError> {
+        let condition = self.condition.to_sql(context.clone())?;
+        let time =
+            evaluate_sql_call_with_context(&self.time, self.query_tools.clone(), context.clone())?;
+        let match_time =
+            evaluate_sql_call_with_context(&self.match_time, self.query_tools.clone(), context)?;
+        Ok((condition, time, match_time))
+    }
+}
+
+impl BaseJoinCondition for AsOfJoinCondition {
+    fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        let (condition, time, match_time) = self.to_sql_parts(context)?;
+        Ok(format!("({}) AND {} <= {}", condition, match_time, time))
+    }
+}
@@ -1,97 +1,620 @@
 use super::planners::{
-    FullKeyAggregateQueryPlanner, MultiStageQueryPlanner, MultipliedMeasuresQueryPlanner,
-    SimpleQueryPlanner,
+    DateRangeProbePlanner, FreshnessQueryPlanner, FullKeyAggregateQueryPlanner,
+    MultiStageQueryPlanner, MultipliedMeasuresQueryPlanner, SimpleQueryPlanner,
 };
 use super::query_tools::QueryTools;
-use super::QueryProperties;
+use super::{
+    CompiledFilterPredicate, DateTimeTools, GapFill, QueryAudit, QueryExplain, QueryHash,
+    QueryProperties, QueryWatermark, RowCountEstimate, TypedParam, UnitConversion,
+};
+use crate::cube_bridge::audit_log_sink::AuditLogSink;
 use crate::cube_bridge::base_query_options::BaseQueryOptions;
+use crate::cube_bridge::measure_definition::MeasureRounding;
 use crate::plan::Select;
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
+use crate::planner::sql_evaluator::{Compiler, MemberSymbol};
 use crate::planner::sql_templates::PlanSqlTemplates;
+use crate::planner::{BaseDimension, BaseMember};
 use cubenativeutils::wrappers::inner_types::InnerTypes;
 use cubenativeutils::wrappers::object::NativeArray;
 use cubenativeutils::wrappers::serializer::NativeSerialize;
 use cubenativeutils::wrappers::NativeType;
 use cubenativeutils::wrappers::{NativeContextHolder, NativeObjectHandle};
 use cubenativeutils::CubeError;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 
-pub struct BaseQuery<IT: InnerTypes> {
-    context: NativeContextHolder<IT>,
+// Sql of the query with its params, positions of params of filtered members
+// and measures to fill gaps of by the caller
+pub struct QuerySql {
+    pub sql: String,
+    pub params: Vec<TypedParam>,
+    pub member_params: BTreeMap<String, Vec<usize>>,
+    pub fill_post_processing: Option<Vec<String>>,
+}
+
+// Plans the query and builds its sql as Rust values. `BaseQuery` passes them to the Node.js
+// bridge, hosts without Node.js use the builder directly, see `protocol` module
+pub struct QueryBuilder {
     query_tools: Rc<QueryTools>,
     request: Rc<QueryProperties>,
+    audit_log_sink: Option<Rc<dyn AuditLogSink>>,
+    units: HashMap<String, String>,
+    fill_policies: Option<HashMap<String, String>>,
+    null_label: Option<String>,
+    value_maps: HashMap<String, BTreeMap<String, String>>,
+    watermark_secret: Option<String>,
 }
 
-impl<IT: InnerTypes> BaseQuery<IT> {
+impl QueryBuilder {
+    // Queries planned against the same schema can share compiled member symbols.
+    // Timestamps are handled by the dialect of the base tools unless `date_time_tools` is set
     pub fn try_new(
-        context: NativeContextHolder<IT>,
         options: Rc<dyn BaseQueryOptions>,
+        evaluator_compiler: Option<Rc<RefCell<Compiler>>>,
+        date_time_tools: Option<Rc<dyn DateTimeTools>>,
     ) -> Result<Self, CubeError> {
         let query_tools = QueryTools::try_new(
             options.cube_evaluator()?,
             options.base_tools()?,
             options.join_graph()?,
             options.static_data().timezone.clone(),
+            options.static_data().security_context.clone(),
+            options.static_data().redaction_mode.clone(),
+            options.static_data().redaction_secret.clone(),
+            options.static_data().cast_policy.clone(),
+            options.static_data().empty_in_list_policy.clone(),
+            options.static_data().collation.clone(),
+            options.static_data().values_normalization.clone(),
+            options.static_data().currency_conversion.clone(),
+            options.static_data().table_overrides.clone(),
+            options.cancellation_token()?,
+            options.table_name_resolver()?,
+            Self::source_date_ranges(&options),
+            options.static_data().planning_timeout_ms,
+            options.static_data().planning_memory_budget_bytes,
+            options.metrics_recorder()?,
+            options.static_data().trace_context.clone(),
+            options.static_data().deterministic.unwrap_or(false),
+            options.static_data().planner_flags.clone(),
+            options.static_data().as_of.clone(),
+            options.static_data().preview.unwrap_or(false),
+            options.static_data().inline_params.unwrap_or(false),
+            options
+                .static_data()
+                .overflow_safe_aggregation
+                .unwrap_or(false),
+            evaluator_compiler,
+            date_time_tools,
         )?;
 
-        let request = QueryProperties::try_new(query_tools.clone(), options)?;
+        let audit_log_sink = options.audit_log_sink()?;
+        let units = options.static_data().units.clone().unwrap_or_default();
+        let fill_policies = options.static_data().fill_policies.clone();
+        let null_label = options.static_data().null_label.clone();
+        let value_maps = options.static_data().value_maps.clone().unwrap_or_default();
+        check_value_maps(&value_maps)?;
+        let watermark_secret = options
+            .static_data()
+            .watermark_secret
+            .clone()
+            .filter(|secret| !secret.is_empty());
+        let cache_hits = query_tools.evaluator_compiler().borrow().cache_hits();
+        let request = query_tools.planning_phase("symbol resolution", || {
+            QueryProperties::try_new(query_tools.clone(), options)
+        })?;
+        let metrics = query_tools.metrics();
+        metrics.increment(
+            "symbol_cache_hits",
+            query_tools.evaluator_compiler().borrow().cache_hits() - cache_hits,
+            &[],
+        )?;
+        let filter_count = request.dimensions_filters().len()
+            + request.time_dimensions_filters().len()
+            + request.measures_filters().len();
+        metrics.observe("filter_count", filter_count as f64, &[])?;
 
         Ok(Self {
-            context,
             query_tools,
             request,
+            audit_log_sink,
+            units,
+            fill_policies,
+            null_label,
+            value_maps,
+            watermark_secret,
         })
     }
 
-    pub fn build_sql_and_params(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
+    // Covering date range of the time dimensions per cube
+    fn source_date_ranges(options: &Rc<dyn BaseQueryOptions>) -> HashMap<String, (String, String)> {
+        let mut result: HashMap<String, (String, String)> = HashMap::new();
+        let Some(time_dimensions) = &options.static_data().time_dimensions else {
+            return result;
+        };
+        for time_dimension in time_dimensions.iter() {
+            let Some(date_range) = &time_dimension.date_range else {
+                continue;
+            };
+            let (Some(from), Some(to)) = (date_range.first(), date_range.last()) else {
+                continue;
+            };
+            let Some((cube_name, _)) = time_dimension.dimension.split_once('.') else {
+                continue;
+            };
+            let range = result
+                .entry(cube_name.to_string())
+                .or_insert_with(|| (from.clone(), to.clone()));
+            if from < &range.0 {
+                range.0 = from.clone();
+            }
+            if to > &range.1 {
+                range.1 = to.clone();
+            }
+        }
+        result
+    }
+
+    pub fn build_sql_and_params(&self) -> Result<QuerySql, CubeError> {
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        let metrics = self.query_tools.metrics();
+        let mut query_sql = self.build_sql(&templates)?;
+        if let Some(secret) = &self.watermark_secret {
+            query_sql.sql = QueryWatermark::try_new(secret, &self.query_tools)?
+                .apply(&query_sql.sql, &templates)?;
+        }
+        metrics.observe("sql_length", query_sql.sql.len() as f64, &[])?;
+        metrics.increment("plans_built", 1, &[("planner", "tesseract")])?;
+
+        if let Some(audit_log_sink) = &self.audit_log_sink {
+            let record =
+                QueryAudit::new(self.query_tools.clone(), self.request.clone()).build_record()?;
+            audit_log_sink.log_query(record)?;
+        }
+
+        Ok(query_sql)
+    }
+
+    // Plan hash and sql hash. Watermark isn't a part of the sql hash: it differs between principals
+    pub fn build_sql_hashes(&self) -> Result<(String, String), CubeError> {
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        let query_sql = self.build_sql(&templates)?;
+        let query_hash = QueryHash::new(self.request.clone());
+        Ok((query_hash.plan_hash(), QueryHash::sql_hash(&query_sql.sql)))
+    }
+
+    // Plan is returned as json text before the sql is rendered
+    pub fn build_explain(&self) -> Result<String, CubeError> {
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        let plan = self.build_sql_and_params_impl(templates)?;
+        let explain =
+            QueryExplain::new(self.query_tools.clone(), self.request.clone()).build(&plan)?;
+        Ok(explain.to_string())
+    }
+
+    fn build_sql(&self, templates: &PlanSqlTemplates) -> Result<QuerySql, CubeError> {
+        let mut plan = self.query_tools.planning_phase("planning", || {
+            self.build_sql_and_params_impl(templates.clone())
+        })?;
+        plan.convert_units(&self.unit_conversion_factors()?);
+        plan.round_measures(&self.measures_rounding());
+        if let Some(null_label) = &self.null_label {
+            let label = self.query_tools.allocate_param(null_label);
+            plan.label_null_dimensions(&self.null_labeled_dimensions(), &label);
+        }
+        self.map_dimension_values(&mut plan);
+        plan.hex_encode_dimensions(&self.binary_dimensions());
+
+        self.query_tools.check_planning_stage("rendering")?;
+        let sql = self
+            .query_tools
+            .planning_phase("rendering", || plan.to_sql(templates))?;
+        let (sql, fill_post_processing) = if let Some(fill_policies) = &self.fill_policies {
+            let (sql, post_processing) = GapFill::try_new(
+                self.query_tools.clone(),
+                self.request.clone(),
+                fill_policies,
+            )?
+            .build(&sql, templates)?;
+            (sql, Some(post_processing))
+        } else {
+            (sql, None)
+        };
+        let (sql, params, member_params) = self
+            .query_tools
+            .build_sql_and_params_with_member_params(&sql, true)?;
+        Ok(QuerySql {
+            sql,
+            params,
+            member_params,
+            fill_post_processing,
+        })
+    }
+
+    // Estimate sql, its params and the strategy name which tells how the estimate should be read
+    pub fn build_row_count_estimate_sql_and_params(
+        &self,
+    ) -> Result<(String, Vec<TypedParam>, String), CubeError> {
         let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
         let plan = self.build_sql_and_params_impl(templates.clone())?;
+        self.query_tools.check_planning_stage("rendering")?;
+        let sql = plan.to_sql(&templates)?;
+
+        let (estimate_sql, strategy) =
+            RowCountEstimate::new(self.query_tools.clone(), self.request.clone())
+                .build(&sql, &templates)?;
+        let (result_sql, params) = self.query_tools.build_sql_and_params(&estimate_sql, true)?;
+        Ok((result_sql, params, strategy))
+    }
 
+    // Companion query returning the number of rows of this query regardless of its paging
+    pub fn build_total_count_sql_and_params(&self) -> Result<(String, Vec<TypedParam>), CubeError> {
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        let mut plan = self.build_sql_and_params_impl(templates.clone())?;
+        plan.remove_pagination();
+        self.query_tools.check_planning_stage("rendering")?;
         let sql = plan.to_sql(&templates)?;
-        let (result_sql, params) = self.query_tools.build_sql_and_params(&sql, true)?;
+        let total_sql = templates.total_count(&sql)?;
+        self.query_tools.build_sql_and_params(&total_sql, true)
+    }
+
+    pub fn build_date_range_probe_sql_and_params(
+        &self,
+        dimension: String,
+    ) -> Result<(String, Vec<TypedParam>), CubeError> {
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        let evaluator_compiler_cell = self.query_tools.evaluator_compiler().clone();
+        let evaluator = evaluator_compiler_cell
+            .borrow_mut()
+            .add_dimension_evaluator(dimension)?;
+        let dimension = BaseDimension::try_new_required(evaluator, self.query_tools.clone())?;
+
+        let planner = DateRangeProbePlanner::new(self.query_tools.clone(), self.request.clone());
+        let plan = planner.plan(dimension)?;
+
+        self.query_tools.check_planning_stage("rendering")?;
+        let sql = plan.to_sql(&templates)?;
+        self.query_tools.build_sql_and_params(&sql, true)
+    }
+
+    // `None` for cubes refreshed by `every` interval only
+    pub fn build_freshness_sql_and_params(
+        &self,
+        cube_name: String,
+    ) -> Result<Option<(String, Vec<TypedParam>)>, CubeError> {
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        self.query_tools.check_planning_stage("rendering")?;
+        let planner = FreshnessQueryPlanner::new(self.query_tools.clone());
+        let Some(sql) = planner.plan(&cube_name, &templates)? else {
+            return Ok(None);
+        };
+        Ok(Some(self.query_tools.build_sql_and_params(&sql, true)?))
+    }
+
+    pub fn build_filter_predicate_sql_and_params(
+        &self,
+    ) -> Result<Option<CompiledFilterPredicate>, CubeError> {
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        self.query_tools.check_planning_stage("rendering")?;
+        CompiledFilterPredicate::try_compile(
+            self.query_tools.clone(),
+            self.request.clone(),
+            &templates,
+        )
+    }
+
+    fn unit_conversion_factors(&self) -> Result<HashMap<String, f64>, CubeError> {
+        let mut result = HashMap::new();
+        for member in self.request.all_members(false).iter() {
+            let full_name = member.full_name();
+            let Some(target_unit) = self.units.get(&full_name) else {
+                continue;
+            };
+            let Some(source_unit) = member.member_evaluator().unit() else {
+                return Err(CubeError::user(format!(
+                    "Member '{}' has no unit defined and cannot be converted to {}",
+                    full_name, target_unit
+                )));
+            };
+            if &source_unit != target_unit {
+                let factor = UnitConversion::conversion_factor(&source_unit, target_unit)?;
+                result.insert(full_name, factor);
+            }
+        }
+        Ok(result)
+    }
+
+    // Value maps of the query relabel returned values only. Filters, including row level
+    // security ones, keep comparing against the values stored in the database
+    fn map_dimension_values(&self, plan: &mut Select) {
+        let mut value_maps = self.value_maps.iter().collect::<Vec<_>>();
+        value_maps.sort_by(|a, b| a.0.cmp(b.0));
+        for (member_name, value_map) in value_maps {
+            if value_map.is_empty() {
+                continue;
+            }
+            let when_then = value_map
+                .iter()
+                .map(|(code, label)| {
+                    (
+                        self.query_tools.allocate_param(code),
+                        self.query_tools.allocate_param(label),
+                    )
+                })
+                .collect();
+            plan.map_dimension_values(member_name, when_then);
+        }
+    }
+
+    // Only string dimensions are labeled: other types can't hold a text label
+    // without changing the type of the column
+    fn null_labeled_dimensions(&self) -> Vec<String> {
+        self.request
+            .dimensions()
+            .iter()
+            .filter(|d| match d.member_evaluator().as_ref() {
+                MemberSymbol::Dimension(dimension) => dimension.dimension_type() == "string",
+                _ => false,
+            })
+            .map(|d| d.full_name())
+            .collect()
+    }
+
+    // Binary values are returned hex encoded: drivers don't agree on how to pass raw bytes
+    fn binary_dimensions(&self) -> Vec<String> {
+        self.request
+            .dimensions()
+            .iter()
+            .filter(|d| match d.member_evaluator().as_ref() {
+                MemberSymbol::Dimension(dimension) => dimension.dimension_type() == "binary",
+                _ => false,
+            })
+            .map(|d| d.full_name())
+            .collect()
+    }
+
+    fn measures_rounding(&self) -> HashMap<String, MeasureRounding> {
+        self.request
+            .measures()
+            .iter()
+            .filter_map(|m| match m.member_evaluator().as_ref() {
+                MemberSymbol::Measure(measure) => measure
+                    .rounding()
+                    .as_ref()
+                    .map(|rounding| (m.full_name(), rounding.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn build_sql_and_params_impl(&self, templates: PlanSqlTemplates) -> Result<Select, CubeError> {
+        plan_query(self.query_tools.clone(), self.request.clone(), templates)
+    }
+}
+
+pub struct BaseQuery<IT: InnerTypes> {
+    context: NativeContextHolder<IT>,
+    builder: QueryBuilder,
+}
+
+impl<IT: InnerTypes> BaseQuery<IT> {
+    pub fn try_new(
+        context: NativeContextHolder<IT>,
+        options: Rc<dyn BaseQueryOptions>,
+    ) -> Result<Self, CubeError> {
+        Self::try_new_with_compiler(context, options, None)
+    }
+
+    // Queries planned against the same schema can share compiled member symbols
+    pub fn try_new_with_compiler(
+        context: NativeContextHolder<IT>,
+        options: Rc<dyn BaseQueryOptions>,
+        evaluator_compiler: Option<Rc<RefCell<Compiler>>>,
+    ) -> Result<Self, CubeError> {
+        let builder = QueryBuilder::try_new(options, evaluator_compiler, None)?;
+        Ok(Self { context, builder })
+    }
+
+    pub fn build_sql_and_params(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let query_sql = self.builder.build_sql_and_params()?;
+
+        let res = self.context.empty_array();
+        res.set(0, query_sql.sql.to_native(self.context.clone())?)?;
+        res.set(1, query_sql.params.to_native(self.context.clone())?)?;
+        res.set(2, query_sql.member_params.to_native(self.context.clone())?)?;
+        // Measures whose fill policies have to be applied to the result by the caller
+        if let Some(fill_post_processing) = query_sql.fill_post_processing {
+            res.set(3, fill_post_processing.to_native(self.context.clone())?)?;
+        }
+        let result = NativeObjectHandle::new(res.into_object());
+
+        Ok(result)
+    }
+
+    pub fn build_sql_hashes(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let (plan_hash, sql_hash) = self.builder.build_sql_hashes()?;
+
+        let res = self.context.empty_array();
+        res.set(0, plan_hash.to_native(self.context.clone())?)?;
+        res.set(1, sql_hash.to_native(self.context.clone())?)?;
+        let result = NativeObjectHandle::new(res.into_object());
+
+        Ok(result)
+    }
+
+    pub fn build_explain(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
+        self.builder
+            .build_explain()?
+            .to_native(self.context.clone())
+    }
+
+    pub fn build_row_count_estimate_sql_and_params(
+        &self,
+    ) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let (result_sql, params, strategy) =
+            self.builder.build_row_count_estimate_sql_and_params()?;
 
         let res = self.context.empty_array();
         res.set(0, result_sql.to_native(self.context.clone())?)?;
         res.set(1, params.to_native(self.context.clone())?)?;
+        res.set(2, strategy.to_native(self.context.clone())?)?;
         let result = NativeObjectHandle::new(res.into_object());
 
         Ok(result)
     }
 
-    fn build_sql_and_params_impl(&self, templates: PlanSqlTemplates) -> Result<Select, CubeError> {
-        let mut nodes_factory = SqlNodesFactory::new();
+    pub fn build_total_count_sql_and_params(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let (result_sql, params) = self.builder.build_total_count_sql_and_params()?;
+        self.sql_and_params(result_sql, params)
+    }
 
-        if self.request.ungrouped() {
-            nodes_factory.set_ungrouped(true)
-        }
+    pub fn build_date_range_probe_sql_and_params(
+        &self,
+        dimension: String,
+    ) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let (result_sql, params) = self
+            .builder
+            .build_date_range_probe_sql_and_params(dimension)?;
+        self.sql_and_params(result_sql, params)
+    }
 
-        if self.request.is_simple_query()? {
-            let planner = SimpleQueryPlanner::new(
-                self.query_tools.clone(),
-                self.request.clone(),
-                nodes_factory.clone(),
-            );
-            planner.plan()
-        } else {
-            let request = self.request.clone();
-            let multiplied_measures_query_planner = MultipliedMeasuresQueryPlanner::new(
-                self.query_tools.clone(),
-                request.clone(),
-                nodes_factory.clone(),
-            );
-            let multi_stage_query_planner =
-                MultiStageQueryPlanner::new(self.query_tools.clone(), request.clone());
-            let full_key_aggregate_planner = FullKeyAggregateQueryPlanner::new(
-                request.clone(),
-                nodes_factory.clone(),
-                templates,
-            );
-            let mut subqueries = multiplied_measures_query_planner.plan_queries()?;
-            let (multi_stage_ctes, multi_stage_subqueries) =
-                multi_stage_query_planner.plan_queries()?;
-            subqueries.extend(multi_stage_subqueries.into_iter());
-            let result = full_key_aggregate_planner.plan(subqueries, multi_stage_ctes)?;
-            Ok(result)
+    pub fn build_freshness_sql_and_params(
+        &self,
+        cube_name: String,
+    ) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let Some((result_sql, params)) = self.builder.build_freshness_sql_and_params(cube_name)?
+        else {
+            return Ok(self.context.undefined());
+        };
+        self.sql_and_params(result_sql, params)
+    }
+
+    pub fn build_filter_predicate_sql_and_params(
+        &self,
+    ) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let Some(predicate) = self.builder.build_filter_predicate_sql_and_params()? else {
+            return Ok(self.context.undefined());
+        };
+
+        let res = self.context.empty_array();
+        res.set(0, predicate.sql().to_native(self.context.clone())?)?;
+        res.set(1, predicate.params().to_native(self.context.clone())?)?;
+        res.set(2, predicate.placeholders().to_native(self.context.clone())?)?;
+        let result = NativeObjectHandle::new(res.into_object());
+
+        Ok(result)
+    }
+
+    fn sql_and_params(
+        &self,
+        sql: String,
+        params: Vec<TypedParam>,
+    ) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let res = self.context.empty_array();
+        res.set(0, sql.to_native(self.context.clone())?)?;
+        res.set(1, params.to_native(self.context.clone())?)?;
+        let result = NativeObjectHandle::new(res.into_object());
+
+        Ok(result)
+    }
+}
+
+// Plans the request into a select, shared with queries which are planned as a source of a cube
+pub(crate) fn plan_query(
+    query_tools: Rc<QueryTools>,
+    request: Rc<QueryProperties>,
+    templates: PlanSqlTemplates,
+) -> Result<Select, CubeError> {
+    let mut nodes_factory = SqlNodesFactory::new();
+
+    if request.ungrouped() {
+        nodes_factory.set_ungrouped(true)
+    }
+    nodes_factory.set_time_shifts(request.time_shifts_by_dimension()?);
+
+    if request.is_simple_query()? {
+        let planner =
+            SimpleQueryPlanner::new(query_tools.clone(), request.clone(), nodes_factory.clone());
+        planner.plan()
+    } else if request.grouping_sets().is_some() {
+        // Measures of other queries are already aggregated by the outer select
+        Err(CubeError::user(format!(
+            "Subtotals aren't supported for queries with multiplied, multi-stage or multi-fact measures"
+        )))
+    } else {
+        let multiplied_measures_query_planner = MultipliedMeasuresQueryPlanner::new(
+            query_tools.clone(),
+            request.clone(),
+            nodes_factory.clone(),
+        );
+        let multi_stage_query_planner =
+            MultiStageQueryPlanner::new(query_tools.clone(), request.clone());
+        let full_key_aggregate_planner = FullKeyAggregateQueryPlanner::new(
+            query_tools.clone(),
+            request.clone(),
+            nodes_factory.clone(),
+            templates,
+        );
+        let mut subqueries = multiplied_measures_query_planner.plan_queries()?;
+        let (multi_stage_ctes, multi_stage_subqueries) =
+            multi_stage_query_planner.plan_queries()?;
+        query_tools.record_planning_progress(format!(
+            "planned {} multiplied measure subqueries and {} multi-stage subqueries",
+            subqueries.len(),
+            multi_stage_subqueries.len()
+        ));
+        query_tools.check_planning_stage("multi-stage planning")?;
+        subqueries.extend(multi_stage_subqueries.into_iter());
+        let result = full_key_aggregate_planner.plan(subqueries, multi_stage_ctes)?;
+        Ok(result)
+    }
+}
+
+// Rows are grouped by the stored values, so labels shared by several values would be
+// returned as duplicate rows
+fn check_value_maps(
+    value_maps: &HashMap<String, BTreeMap<String, String>>,
+) -> Result<(), CubeError> {
+    for (member_name, value_map) in value_maps.iter() {
+        let mut labels = HashSet::new();
+        for label in value_map.values() {
+            if !labels.insert(label) {
+                return Err(CubeError::user(format!(
+                    "Value map of '{}' maps several values to '{}' label: value maps of the query must be one-to-one",
+                    member_name, label
+                )));
+            }
         }
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_maps_must_be_one_to_one() {
+        let one_to_one = HashMap::from([(
+            "orders.status".to_string(),
+            BTreeMap::from([
+                ("c".to_string(), "Completed".to_string()),
+                ("p".to_string(), "Processing".to_string()),
+            ]),
+        )]);
+        assert!(check_value_maps(&one_to_one).is_ok());
+
+        let many_to_one = HashMap::from([(
+            "orders.status".to_string(),
+            BTreeMap::from([
+                ("c".to_string(), "Done".to_string()),
+                ("s".to_string(), "Done".to_string()),
+            ]),
+        )]);
+        assert_eq!(
+            check_value_maps(&many_to_one).unwrap_err().message,
+            "Value map of 'orders.status' maps several values to 'Done' label: value maps of the query must be one-to-one"
+        );
+    }
 }
@@ -0,0 +1,78 @@
+use super::sql_evaluator::Compiler;
+use super::BaseQuery;
+use crate::cube_bridge::base_query_options::BaseQueryOptions;
+use cubenativeutils::wrappers::inner_types::InnerTypes;
+use cubenativeutils::wrappers::object::{NativeArray, NativeStruct};
+use cubenativeutils::wrappers::serializer::NativeSerialize;
+use cubenativeutils::wrappers::NativeType;
+use cubenativeutils::wrappers::{NativeContextHolder, NativeObjectHandle};
+use cubenativeutils::{CubeError, CubeErrorCauseType};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Plans several queries against the same schema, e.g. all widgets of a dashboard.
+// Member symbols are compiled once and shared by all queries of the batch, so all of them
+// must use the same cube evaluator: callers split queries of other schemas into separate batches.
+// Queries are planned one after another: symbols are bound to the JS context and can't cross threads.
+pub struct BatchQuery<IT: InnerTypes> {
+    context: NativeContextHolder<IT>,
+    queries: Vec<Result<BaseQuery<IT>, CubeError>>,
+}
+
+impl<IT: InnerTypes> BatchQuery<IT> {
+    pub fn try_new(
+        context: NativeContextHolder<IT>,
+        options: Vec<Rc<dyn BaseQueryOptions>>,
+    ) -> Result<Self, CubeError> {
+        let evaluator_compiler = if let Some(first) = options.first() {
+            Some(Rc::new(RefCell::new(Compiler::new(
+                first.cube_evaluator()?,
+            ))))
+        } else {
+            None
+        };
+        let queries = options
+            .into_iter()
+            .map(|options| {
+                BaseQuery::try_new_with_compiler(
+                    context.clone(),
+                    options,
+                    evaluator_compiler.clone(),
+                )
+            })
+            .collect();
+        Ok(Self { context, queries })
+    }
+
+    // Returns `[sql, params]` for each query, or `{ error, meta? }` if the query failed to plan,
+    // so a single broken query doesn't fail the whole batch
+    pub fn build_sql_and_params(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let res = self.context.empty_array();
+        for (i, query) in self.queries.iter().enumerate() {
+            let item = match query.as_ref().map(|q| q.build_sql_and_params()) {
+                Ok(Ok(item)) => item,
+                Ok(Err(e)) => error_result(self.context.clone(), &e)?,
+                Err(e) => error_result(self.context.clone(), e)?,
+            };
+            res.set(i as u32, item)?;
+        }
+        Ok(NativeObjectHandle::new(res.into_object()))
+    }
+}
+
+// `{ error, meta? }` of a query which failed to plan. Meta is the structured meta of the error,
+// e.g. the member and the suggested value of an invalid filter value
+pub(crate) fn error_result<IT: InnerTypes>(
+    context: NativeContextHolder<IT>,
+    err: &CubeError,
+) -> Result<NativeObjectHandle<IT>, CubeError> {
+    let res = context.empty_struct();
+    res.set_field("error", err.message.to_native(context.clone())?)?;
+    let meta = match &err.cause {
+        CubeErrorCauseType::User(meta) | CubeErrorCauseType::Internal(meta) => meta,
+    };
+    if let Some(meta) = meta {
+        res.set_field("meta", meta.to_native(context.clone())?)?;
+    }
+    Ok(NativeObjectHandle::new(res.into_object()))
+}
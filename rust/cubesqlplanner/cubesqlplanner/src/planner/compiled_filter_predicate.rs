@@ -0,0 +1,49 @@
+use super::query_tools::QueryTools;
+use super::sql_evaluator::sql_nodes::SqlNodesFactory;
+use super::sql_templates::PlanSqlTemplates;
+use super::{QueryProperties, TypedParam, VisitorContext};
+use cubenativeutils::CubeError;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub struct CompiledFilterPredicate {
+    sql: String,
+    params: Vec<TypedParam>,
+    placeholders: HashMap<String, Vec<usize>>,
+}
+
+impl CompiledFilterPredicate {
+    // Compiles dimension and time dimension filters of the query into a single predicate.
+    // Filters with a placeholder (e.g. partition date range) are bound to params, so the
+    // caller can render the predicate for any value of them without planning again
+    pub fn try_compile(
+        query_tools: Rc<QueryTools>,
+        request: Rc<QueryProperties>,
+        templates: &PlanSqlTemplates,
+    ) -> Result<Option<Self>, CubeError> {
+        let Some(filter) = request.all_filters() else {
+            return Ok(None);
+        };
+        let context = Rc::new(VisitorContext::new(&SqlNodesFactory::new()));
+        let sql = filter.to_sql(templates, context)?;
+        let (sql, params, placeholders) =
+            query_tools.build_sql_and_params_with_bound_placeholders(&sql, true)?;
+        Ok(Some(Self {
+            sql,
+            params,
+            placeholders,
+        }))
+    }
+
+    pub fn sql(&self) -> &String {
+        &self.sql
+    }
+
+    pub fn params(&self) -> &Vec<TypedParam> {
+        &self.params
+    }
+
+    pub fn placeholders(&self) -> &HashMap<String, Vec<usize>> {
+        &self.placeholders
+    }
+}
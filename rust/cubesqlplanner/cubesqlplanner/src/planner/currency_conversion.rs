@@ -0,0 +1,107 @@
+use super::query_tools::QueryTools;
+use super::{evaluate_with_context, BaseJoinCondition, BaseMeasure, BaseMember, VisitorContext};
+use crate::planner::sql_evaluator::MemberSymbol;
+use crate::planner::sql_templates::PlanSqlTemplates;
+use cubenativeutils::CubeError;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Join of the deployment rates table aligned on the rate effective date:
+// each row picks the latest rate with effective date not after the row date
+pub struct CurrencyRatesJoin {
+    query_tools: Rc<QueryTools>,
+    alias: String,
+    currency_dimension: Rc<MemberSymbol>,
+    time_dimension: Rc<MemberSymbol>,
+}
+
+impl CurrencyRatesJoin {
+    pub fn alias(&self) -> &String {
+        &self.alias
+    }
+
+    pub fn rates_source_sql(&self) -> Result<String, CubeError> {
+        let Some(options) = self.query_tools.currency_conversion() else {
+            return Err(CubeError::internal(format!(
+                "Currency conversion is not configured"
+            )));
+        };
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        let target_currency = self.query_tools.allocate_param(&options.target_currency);
+        let sql = templates.currency_rates_source(options, &target_currency)?;
+        Ok(format!("({})", sql))
+    }
+}
+
+impl BaseJoinCondition for CurrencyRatesJoin {
+    fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        let currency = evaluate_with_context(
+            &self.currency_dimension,
+            self.query_tools.clone(),
+            context.clone(),
+        )?;
+        let date = evaluate_with_context(&self.time_dimension, self.query_tools.clone(), context)?;
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        templates.currency_rates_join_condition(&self.alias, &currency, &date)
+    }
+}
+
+pub struct CurrencyConversion {
+    query_tools: Rc<QueryTools>,
+}
+
+impl CurrencyConversion {
+    pub fn new(query_tools: Rc<QueryTools>) -> Self {
+        Self { query_tools }
+    }
+
+    // Returns rates joins and the rates alias for each converted measure
+    pub fn plan_rates_joins(
+        &self,
+        measures: &Vec<Rc<BaseMeasure>>,
+        alias_prefix: &Option<String>,
+    ) -> Result<(Vec<Rc<CurrencyRatesJoin>>, HashMap<String, String>), CubeError> {
+        let mut joins: Vec<Rc<CurrencyRatesJoin>> = Vec::new();
+        let mut references = HashMap::new();
+        if self.query_tools.currency_conversion().is_none() {
+            return Ok((joins, references));
+        }
+        for measure in measures.iter() {
+            let evaluator = measure.member_evaluator();
+            let MemberSymbol::Measure(symbol) = evaluator.as_ref() else {
+                continue;
+            };
+            let Some(currency) = symbol.currency() else {
+                continue;
+            };
+            let existing = joins.iter().find(|j| {
+                j.currency_dimension.full_name() == currency.dimension
+                    && j.time_dimension.full_name() == currency.time_dimension
+            });
+            let alias = if let Some(join) = existing {
+                join.alias.clone()
+            } else {
+                let evaluator_compiler_cell = self.query_tools.evaluator_compiler().clone();
+                let mut evaluator_compiler = evaluator_compiler_cell.borrow_mut();
+                let currency_dimension =
+                    evaluator_compiler.add_dimension_evaluator(currency.dimension.clone())?;
+                let time_dimension =
+                    evaluator_compiler.add_dimension_evaluator(currency.time_dimension.clone())?;
+                let alias = if let Some(prefix) = alias_prefix {
+                    format!("{}_currency_rates_{}", prefix, joins.len())
+                } else {
+                    format!("currency_rates_{}", joins.len())
+                };
+                joins.push(Rc::new(CurrencyRatesJoin {
+                    query_tools: self.query_tools.clone(),
+                    alias: alias.clone(),
+                    currency_dimension,
+                    time_dimension,
+                }));
+                alias
+            };
+            references.insert(measure.full_name(), alias);
+        }
+        Ok((joins, references))
+    }
+}
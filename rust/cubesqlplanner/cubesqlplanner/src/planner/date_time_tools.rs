@@ -0,0 +1,305 @@
+use crate::cube_bridge::base_tools::BaseTools;
+use chrono::{DateTime, Duration, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use cubenativeutils::CubeError;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::rc::Rc;
+
+lazy_static! {
+    static ref DATE_TIME_LOCAL_MS_RE: Regex =
+        Regex::new(r"^\d\d\d\d-\d\d-\d\dT\d\d:\d\d:\d\d\.\d\d\d$").unwrap();
+    static ref DATE_TIME_LOCAL_U_RE: Regex =
+        Regex::new(r"^\d\d\d\d-\d\d-\d\dT\d\d:\d\d:\d\d\.\d\d\d\d\d\d$").unwrap();
+    static ref DATE_RE: Regex = Regex::new(r"^\d\d\d\d-\d\d-\d\d$").unwrap();
+}
+
+// System time of the planner. There is no clock on wasm32-unknown-unknown, hosts of the wasm
+// build pass the current time instead, see `StaticDateTimeTools::with_now`
+pub fn system_now() -> Result<DateTime<Utc>, CubeError> {
+    if cfg!(target_arch = "wasm32") {
+        return Err(CubeError::user(
+            "Relative date ranges need the current time, which the host hasn't passed".to_string(),
+        ));
+    }
+    Ok(Utc::now())
+}
+
+// Timestamp handling of the query dialect that filters depend on
+pub trait DateTimeTools {
+    // Fractional digits of local timestamps, 3 or 6
+    fn timestamp_precision(&self) -> Result<u32, CubeError>;
+    // Local timestamp of the query timezone converted to the timezone of the database
+    fn in_db_time_zone(&self, date: String) -> Result<String, CubeError>;
+
+    // Current instant relative date ranges are resolved against, system time by default
+    fn now(&self) -> Result<DateTime<Utc>, CubeError> {
+        system_now()
+    }
+
+    // Local timestamp of the start of a date range bound at the dialect precision.
+    // Returns `None` for values which are neither a date nor a local timestamp
+    fn format_from_date(&self, date: &str) -> Result<Option<String>, CubeError> {
+        self.format_date_bound(date, "00:00:00", '0')
+    }
+
+    // Local timestamp of the end of a date range bound, dates are extended to the end of the day
+    fn format_to_date(&self, date: &str) -> Result<Option<String>, CubeError> {
+        self.format_date_bound(date, "23:59:59", '9')
+    }
+
+    fn format_date_bound(
+        &self,
+        date: &str,
+        day_time: &str,
+        fraction_digit: char,
+    ) -> Result<Option<String>, CubeError> {
+        let precision = self.timestamp_precision()?;
+        if precision == 3 {
+            if DATE_TIME_LOCAL_MS_RE.is_match(date) {
+                return Ok(Some(date.to_string()));
+            }
+        } else if precision == 6 {
+            if date.len() == 23 && DATE_TIME_LOCAL_MS_RE.is_match(date) {
+                // `.999` marks the end of a second, which keeps covering it at microseconds
+                let suffix = if fraction_digit == '9' && date.ends_with(".999") {
+                    "999"
+                } else {
+                    "000"
+                };
+                return Ok(Some(format!("{}{}", date, suffix)));
+            } else if date.len() == 26 && DATE_TIME_LOCAL_U_RE.is_match(date) {
+                return Ok(Some(date.to_string()));
+            }
+        } else {
+            return Err(CubeError::user(format!(
+                "Unsupported timestamp precision: {}",
+                precision
+            )));
+        }
+
+        if DATE_RE.is_match(date) {
+            return Ok(Some(format!(
+                "{}T{}.{}",
+                date,
+                day_time,
+                fraction_digit.to_string().repeat(precision as usize)
+            )));
+        }
+        Ok(None)
+    }
+}
+
+// Dialect implementation of `BaseQuery` on the JS side
+pub struct NativeDateTimeTools {
+    base_tools: Rc<dyn BaseTools>,
+}
+
+impl NativeDateTimeTools {
+    pub fn new(base_tools: Rc<dyn BaseTools>) -> Rc<Self> {
+        Rc::new(Self { base_tools })
+    }
+}
+
+impl DateTimeTools for NativeDateTimeTools {
+    fn timestamp_precision(&self) -> Result<u32, CubeError> {
+        self.base_tools.timestamp_precision()
+    }
+
+    fn in_db_time_zone(&self, date: String) -> Result<String, CubeError> {
+        self.base_tools.in_db_time_zone(date)
+    }
+}
+
+// Pure Rust implementation of the default dialect behavior for static schemas and tests:
+// timestamps are stored in UTC and formatted as `YYYY-MM-DDTHH:mm:ss.SSSZ`
+pub struct StaticDateTimeTools {
+    timezone: Option<Tz>,
+    precision: u32,
+    now: Option<DateTime<Utc>>,
+}
+
+impl StaticDateTimeTools {
+    pub fn new(timezone: Option<Tz>, precision: u32) -> Rc<Self> {
+        Self::with_now(timezone, precision, None)
+    }
+
+    // Fixed clock for relative date ranges, system time if `None`
+    pub fn with_now(timezone: Option<Tz>, precision: u32, now: Option<DateTime<Utc>>) -> Rc<Self> {
+        Rc::new(Self {
+            timezone,
+            precision,
+            now,
+        })
+    }
+}
+
+impl DateTimeTools for StaticDateTimeTools {
+    fn timestamp_precision(&self) -> Result<u32, CubeError> {
+        Ok(self.precision)
+    }
+
+    fn now(&self) -> Result<DateTime<Utc>, CubeError> {
+        match self.now {
+            Some(now) => Ok(now),
+            None => system_now(),
+        }
+    }
+
+    fn in_db_time_zone(&self, date: String) -> Result<String, CubeError> {
+        if date.len() != 23 && date.len() != 26 {
+            return Ok(date);
+        }
+        let local = NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S%.f")
+            .map_err(|_| CubeError::user(format!("Can't parse timestamp {}", date)))?;
+        let utc = match self.timezone {
+            // Like moment-timezone, ambiguous times resolve to the earliest instant and times
+            // skipped by a DST transition are shifted forward by the gap: they are read with
+            // the offset in effect before the transition
+            Some(tz) => match tz.from_local_datetime(&local).earliest() {
+                Some(time) => time.naive_utc(),
+                None => {
+                    let offset = tz
+                        .offset_from_utc_datetime(&(local - Duration::days(1)))
+                        .fix()
+                        .local_minus_utc();
+                    local - Duration::seconds(offset as i64)
+                }
+            },
+            None => local,
+        };
+        Ok(utc
+            .format(&format!("%Y-%m-%dT%H:%M:%S%.{}fZ", self.precision))
+            .to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools(timezone: Option<&str>, precision: u32) -> Rc<StaticDateTimeTools> {
+        StaticDateTimeTools::new(timezone.map(|tz| tz.parse().unwrap()), precision)
+    }
+
+    #[test]
+    fn date_bounds_are_formatted_at_precision() {
+        let ms = tools(None, 3);
+        assert_eq!(
+            ms.format_from_date("2024-01-15").unwrap().unwrap(),
+            "2024-01-15T00:00:00.000"
+        );
+        assert_eq!(
+            ms.format_to_date("2024-01-15").unwrap().unwrap(),
+            "2024-01-15T23:59:59.999"
+        );
+        assert_eq!(
+            ms.format_to_date("2024-01-15T10:00:00.000")
+                .unwrap()
+                .unwrap(),
+            "2024-01-15T10:00:00.000"
+        );
+
+        let us = tools(None, 6);
+        assert_eq!(
+            us.format_from_date("2024-01-15").unwrap().unwrap(),
+            "2024-01-15T00:00:00.000000"
+        );
+        assert_eq!(
+            us.format_to_date("2024-01-15").unwrap().unwrap(),
+            "2024-01-15T23:59:59.999999"
+        );
+        assert_eq!(
+            us.format_from_date("2024-01-15T10:00:00.999")
+                .unwrap()
+                .unwrap(),
+            "2024-01-15T10:00:00.999000"
+        );
+        assert_eq!(
+            us.format_to_date("2024-01-15T10:00:00.999")
+                .unwrap()
+                .unwrap(),
+            "2024-01-15T10:00:00.999999"
+        );
+        assert_eq!(
+            us.format_to_date("2024-01-15T10:00:00.500")
+                .unwrap()
+                .unwrap(),
+            "2024-01-15T10:00:00.500000"
+        );
+    }
+
+    #[test]
+    fn unsupported_date_bounds() {
+        assert_eq!(tools(None, 3).format_from_date("15/01/2024").unwrap(), None);
+        assert_eq!(
+            tools(None, 3)
+                .format_from_date("2024-01-15T10:00:00.000000")
+                .unwrap(),
+            None
+        );
+        assert!(tools(None, 9).format_from_date("2024-01-15").is_err());
+    }
+
+    #[test]
+    fn local_timestamps_are_converted_to_utc() {
+        let tools = tools(Some("America/New_York"), 3);
+        assert_eq!(
+            tools
+                .in_db_time_zone("2024-01-15T00:00:00.000".to_string())
+                .unwrap(),
+            "2024-01-15T05:00:00.000Z"
+        );
+        assert_eq!(
+            tools
+                .in_db_time_zone("2024-07-15T23:59:59.999".to_string())
+                .unwrap(),
+            "2024-07-16T03:59:59.999Z"
+        );
+    }
+
+    #[test]
+    fn utc_timestamps_keep_their_precision() {
+        assert_eq!(
+            tools(None, 6)
+                .in_db_time_zone("2024-01-15T23:59:59.999999".to_string())
+                .unwrap(),
+            "2024-01-15T23:59:59.999999Z"
+        );
+        assert_eq!(
+            tools(None, 3)
+                .in_db_time_zone("2024-01-15".to_string())
+                .unwrap(),
+            "2024-01-15"
+        );
+    }
+
+    #[test]
+    fn dst_gap_is_shifted_forward() {
+        let tools = tools(Some("America/New_York"), 3);
+        // 02:30 doesn't exist on 2024-03-10, it's read as 03:30 EDT
+        assert_eq!(
+            tools
+                .in_db_time_zone("2024-03-10T02:30:00.000".to_string())
+                .unwrap(),
+            "2024-03-10T07:30:00.000Z"
+        );
+        assert_eq!(
+            tools
+                .in_db_time_zone("2024-03-10T03:30:00.000".to_string())
+                .unwrap(),
+            "2024-03-10T07:30:00.000Z"
+        );
+    }
+
+    #[test]
+    fn dst_overlap_resolves_to_earliest() {
+        let tools = tools(Some("America/New_York"), 3);
+        assert_eq!(
+            tools
+                .in_db_time_zone("2024-11-03T01:30:00.000".to_string())
+                .unwrap(),
+            "2024-11-03T05:30:00.000Z"
+        );
+    }
+}
@@ -1,12 +1,24 @@
+use super::binary_value::BinaryValue;
+use super::cast_policy::CastPolicy;
+use super::empty_in_list_policy::EmptyInListPolicy;
 use super::filter_operator::FilterOperator;
+use super::relative_date_range::RelativeDateRange;
+use super::value_suggestions::InvalidFilterValue;
+use crate::cube_bridge::cube_definition::CubeCalendar;
+use crate::planner::planners::SubQueryDimensionPlanner;
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::MemberSymbol;
 use crate::planner::sql_templates::filter::FilterTemplates;
-use crate::planner::{evaluate_with_context, VisitorContext};
+use crate::planner::sql_templates::PlanSqlTemplates;
+use crate::planner::{
+    evaluate_with_context, ParamType, PlannerFlag, PlanningMemoryBudget, VisitorContext,
+};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc, Weekday};
 use cubenativeutils::CubeError;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::rc::Rc;
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FilterType {
@@ -17,10 +29,12 @@ pub enum FilterType {
 pub struct BaseFilter {
     query_tools: Rc<QueryTools>,
     member_evaluator: Rc<MemberSymbol>,
-    #[allow(dead_code)]
     filter_type: FilterType,
     filter_operator: FilterOperator,
     values: Vec<Option<String>>,
+    placeholder: Option<String>,
+    trim: bool,
+    case_sensitive: bool,
     templates: FilterTemplates,
 }
 
@@ -29,15 +43,26 @@ impl PartialEq for BaseFilter {
         self.filter_type == other.filter_type
             && self.filter_operator == other.filter_operator
             && self.values == other.values
+            && self.placeholder == other.placeholder
+            && self.trim == other.trim
+            && self.case_sensitive == other.case_sensitive
     }
 }
 
 lazy_static! {
-    static ref DATE_TIME_LOCAL_MS_RE: Regex =
-        Regex::new(r"^\d\d\d\d-\d\d-\d\dT\d\d:\d\d:\d\d\.\d\d\d$").unwrap();
-    static ref DATE_TIME_LOCAL_U_RE: Regex =
-        Regex::new(r"^\d\d\d\d-\d\d-\d\dT\d\d:\d\d:\d\d\.\d\d\d\d\d\d$").unwrap();
-    static ref DATE_RE: Regex = Regex::new(r"^\d\d\d\d-\d\d-\d\d$").unwrap();
+    static ref TIMESTAMP_RE: Regex = Regex::new(
+        r"^\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d{1,9})?)?)?(?:Z|[+-]\d{2}:?\d{2})?$"
+    )
+    .unwrap();
+    // At least 10 integer digits: shorter numbers are years or compact dates like `20240101`
+    static ref EPOCH_RE: Regex = Regex::new(r"^-?\d{10,}(?:\.\d+)?$").unwrap();
+    static ref BUCKET_LABEL_RE: Regex =
+        Regex::new(r"^(\d{4})(?:-Q([1-4])|-W(\d{2})|-(\d{2})(?:-(\d{2}))?)?$").unwrap();
+    static ref UUID_RE: Regex = Regex::new(
+        r"^\{?([0-9a-fA-F]{8})-?([0-9a-fA-F]{4})-?([0-9a-fA-F]{4})-?([0-9a-fA-F]{4})-?([0-9a-fA-F]{12})\}?$"
+    )
+    .unwrap();
+    static ref COLLATION_NAME_RE: Regex = Regex::new(r"^[_a-zA-Z0-9][_a-zA-Z0-9.\-]*$").unwrap();
 }
 
 impl BaseFilter {
@@ -47,19 +72,63 @@ impl BaseFilter {
         filter_type: FilterType,
         filter_operator: FilterOperator,
         values: Option<Vec<Option<String>>>,
+        placeholder: Option<String>,
+        trim: bool,
+        case_sensitive: bool,
     ) -> Result<Rc<Self>, CubeError> {
+        if let Some(member_type) = Self::member_type(&member_evaluator) {
+            filter_operator.validate_for_member(&member_evaluator.full_name(), &member_type)?;
+            // Trimming would cast numbers, times and booleans to strings in the comparison
+            if trim && member_type != "string" {
+                return Err(CubeError::user(format!(
+                    "Option 'trim' can be applied only to string members, '{}' is of type '{}'",
+                    member_evaluator.full_name(),
+                    member_type
+                )));
+            }
+        }
+        if filter_operator.is_array_operator() && !Self::is_array_member(&member_evaluator) {
+            return Err(CubeError::user(format!(
+                "Operator '{}' can be applied only to multi-value members, '{}' is not one",
+                filter_operator,
+                member_evaluator.full_name()
+            )));
+        }
         let templates = FilterTemplates::new(query_tools.templates_render());
         let values = if let Some(values) = values {
             values
         } else {
             vec![]
         };
+        let values = if let Some(case_fold) =
+            Self::values_normalization_case_fold(&query_tools, &member_evaluator, &filter_operator)
+        {
+            values
+                .into_iter()
+                .map(|v| v.map(|v| Self::normalize_value(&v, case_fold)))
+                .collect()
+        } else {
+            values
+        };
+        let values = match Self::member_type(&member_evaluator).as_deref() {
+            Some(member_type @ ("binary" | "uuid")) => {
+                Self::coerce_values(&query_tools, &member_evaluator, member_type, values)?
+            }
+            _ => values,
+        };
+        query_tools.reserve_planning_memory(
+            &format!("filter values for {}", member_evaluator.full_name()),
+            PlanningMemoryBudget::values_size(&values),
+        )?;
         Ok(Rc::new(Self {
             query_tools,
             member_evaluator,
             filter_type,
             filter_operator,
             values,
+            placeholder,
+            trim,
+            case_sensitive,
             templates,
         }))
     }
@@ -75,6 +144,9 @@ impl BaseFilter {
             filter_type: self.filter_type.clone(),
             filter_operator,
             values,
+            placeholder: self.placeholder.clone(),
+            trim: self.trim,
+            case_sensitive: self.case_sensitive,
             templates: self.templates.clone(),
         })
     }
@@ -87,6 +159,22 @@ impl BaseFilter {
         &self.values
     }
 
+    pub fn placeholder(&self) -> &Option<String> {
+        &self.placeholder
+    }
+
+    pub fn trim(&self) -> bool {
+        self.trim
+    }
+
+    pub fn case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    pub fn filter_type(&self) -> &FilterType {
+        &self.filter_type
+    }
+
     pub fn filter_operator(&self) -> &FilterOperator {
         &self.filter_operator
     }
@@ -95,9 +183,30 @@ impl BaseFilter {
         self.member_evaluator.full_name()
     }
 
+    // Values as they can be shown outside of the query, e.g. in plan dumps
+    pub fn display_values(&self) -> Vec<Option<String>> {
+        self.values
+            .iter()
+            .map(|v| v.as_ref().map(|v| self.display_value(v)))
+            .collect()
+    }
+
     pub fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        if self.is_sub_query_member() {
+            return self.sub_query_member_where(context);
+        }
         let member_sql =
             evaluate_with_context(&self.member_evaluator, self.query_tools.clone(), context)?;
+        self.to_sql_for_column(member_sql)
+    }
+
+    // Condition of the filter applied to a column of a cube sql, e.g. from `FILTER_PARAMS`
+    pub fn to_sql_for_column(&self, member_sql: String) -> Result<String, CubeError> {
+        self.validate_values_arity()?;
+        let member_sql = self.apply_values_normalization(member_sql)?;
+        let member_sql = self.apply_collation(member_sql)?;
+        let member_sql = self.apply_trim(member_sql)?;
+        let member_sql = self.apply_member_cast(member_sql)?;
         let res = match self.filter_operator {
             FilterOperator::Equal => self.equals_where(&member_sql)?,
             FilterOperator::NotEqual => self.not_equals_where(&member_sql)?,
@@ -111,22 +220,281 @@ impl BaseFilter {
             FilterOperator::Gte => self.gte_where(&member_sql)?,
             FilterOperator::Lt => self.lt_where(&member_sql)?,
             FilterOperator::Lte => self.lte_where(&member_sql)?,
+            FilterOperator::Between => self.between_where(&member_sql, false)?,
+            FilterOperator::NotBetween => self.between_where(&member_sql, true)?,
             FilterOperator::Contains => self.contains_where(&member_sql)?,
             FilterOperator::NotContains => self.not_contains_where(&member_sql)?,
             FilterOperator::StartsWith => self.starts_with_where(&member_sql)?,
             FilterOperator::NotStartsWith => self.not_starts_with_where(&member_sql)?,
             FilterOperator::EndsWith => self.ends_with_where(&member_sql)?,
             FilterOperator::NotEndsWith => self.not_ends_with_where(&member_sql)?,
+            FilterOperator::Regex => self.regex_where(&member_sql, false)?,
+            FilterOperator::NotRegex => self.regex_where(&member_sql, true)?,
+            FilterOperator::BusinessDays => self.business_days_where(&member_sql)?,
+            FilterOperator::ExcludeHolidays => self.exclude_holidays_where(&member_sql)?,
+            FilterOperator::InBuckets => self.in_buckets_where(&member_sql)?,
+            FilterOperator::ArrayContains => self
+                .templates
+                .array_contains_all(member_sql, self.array_values()?)?,
+            FilterOperator::ArrayOverlaps => self
+                .templates
+                .array_overlaps(member_sql, self.array_values()?)?,
         };
         Ok(res)
     }
 
+    // Values allocated as params in the order `FILTER_PARAMS` functions receive them:
+    // bounds for date ranges, nothing for set checks and escaped patterns for like operators
+    pub fn filter_params(&self) -> Result<Vec<String>, CubeError> {
+        self.validate_values_arity()?;
+        match self.filter_operator {
+            FilterOperator::InDateRange | FilterOperator::InDateRangeExtended => {
+                let (from, to) = self.allocate_date_params()?;
+                Ok(from.into_iter().chain(to).collect())
+            }
+            FilterOperator::Set | FilterOperator::NotSet => Ok(vec![]),
+            FilterOperator::Contains
+            | FilterOperator::NotContains
+            | FilterOperator::StartsWith
+            | FilterOperator::NotStartsWith
+            | FilterOperator::EndsWith
+            | FilterOperator::NotEndsWith => Ok(self
+                .values
+                .iter()
+                .flatten()
+                .map(|v| self.allocate_param(&FilterTemplates::escape_like_wildcards(v)))
+                .collect()),
+            _ => Ok(self
+                .values
+                .iter()
+                .flatten()
+                .map(|v| self.allocate_param(v))
+                .collect()),
+        }
+    }
+
+    // Returns case folding flag if values of this filter should be normalized
+    fn values_normalization_case_fold(
+        query_tools: &Rc<QueryTools>,
+        member_evaluator: &Rc<MemberSymbol>,
+        filter_operator: &FilterOperator,
+    ) -> Option<bool> {
+        let normalization = query_tools.values_normalization().as_ref()?;
+        match filter_operator {
+            FilterOperator::InDateRange
+            | FilterOperator::InDateRangeExtended
+            | FilterOperator::Set
+            | FilterOperator::NotSet
+            | FilterOperator::BusinessDays
+            | FilterOperator::ExcludeHolidays
+            | FilterOperator::InBuckets
+            | FilterOperator::ArrayContains
+            | FilterOperator::ArrayOverlaps => return None,
+            _ => {}
+        }
+        match member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) if dimension.dimension_type() == "string" => {
+                Some(normalization.case_fold.unwrap_or(false))
+            }
+            _ => None,
+        }
+    }
+
+    // Binary values are bound as hex and UUIDs in the canonical form, invalid ones are
+    // rejected at plan time instead of failing the cast in the warehouse
+    fn coerce_values(
+        query_tools: &Rc<QueryTools>,
+        member_evaluator: &Rc<MemberSymbol>,
+        member_type: &str,
+        values: Vec<Option<String>>,
+    ) -> Result<Vec<Option<String>>, CubeError> {
+        values
+            .into_iter()
+            .map(|v| {
+                let Some(v) = v else {
+                    return Ok(None);
+                };
+                let (coerced, expected) = if member_type == "uuid" {
+                    (Self::canonical_uuid(&v), "a UUID")
+                } else {
+                    (
+                        BinaryValue::to_hex(&v),
+                        "0x prefixed hex or base64: prefixed base64",
+                    )
+                };
+                coerced.map(Some).ok_or_else(|| {
+                    CubeError::user(format!(
+                        "Value {} of filter for {} member '{}' is not {}",
+                        query_tools
+                            .value_redaction_policy()
+                            .display_value(member_evaluator, &v),
+                        member_type,
+                        member_evaluator.full_name(),
+                        expected
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    fn canonical_uuid(value: &str) -> Option<String> {
+        let value = value.trim();
+        if value.starts_with('{') != value.ends_with('}') {
+            return None;
+        }
+        let caps = UUID_RE.captures(value)?;
+        let parts = (1..=5).map(|i| caps[i].to_lowercase()).collect::<Vec<_>>();
+        Some(parts.join("-"))
+    }
+
+    fn normalize_value(value: &str, case_fold: bool) -> String {
+        let normalized = value.nfc().collect::<String>();
+        if case_fold {
+            normalized.to_lowercase()
+        } else {
+            normalized
+        }
+    }
+
+    fn apply_values_normalization(&self, member_sql: String) -> Result<String, CubeError> {
+        if let Some(case_fold) = Self::values_normalization_case_fold(
+            &self.query_tools,
+            &self.member_evaluator,
+            &self.filter_operator,
+        ) {
+            self.templates.normalize_unicode(&member_sql, case_fold)
+        } else {
+            Ok(member_sql)
+        }
+    }
+
+    fn is_trimmed_comparison(&self) -> bool {
+        self.trim
+            && matches!(
+                self.filter_operator,
+                FilterOperator::Equal
+                    | FilterOperator::NotEqual
+                    | FilterOperator::In
+                    | FilterOperator::NotIn
+            )
+    }
+
+    fn apply_trim(&self, sql: String) -> Result<String, CubeError> {
+        if self.is_trimmed_comparison() {
+            self.templates.trim(&sql)
+        } else {
+            Ok(sql)
+        }
+    }
+
+    // Member level collation takes precedence over the query level one.
+    // Only string dimensions are collated, date ranges and null checks are left as is.
+    fn apply_collation(&self, member_sql: String) -> Result<String, CubeError> {
+        match self.filter_operator {
+            FilterOperator::InDateRange
+            | FilterOperator::InDateRangeExtended
+            | FilterOperator::Set
+            | FilterOperator::NotSet
+            | FilterOperator::BusinessDays
+            | FilterOperator::ExcludeHolidays
+            | FilterOperator::InBuckets
+            | FilterOperator::ArrayContains
+            | FilterOperator::ArrayOverlaps => return Ok(member_sql),
+            _ => {}
+        }
+        let MemberSymbol::Dimension(dimension) = self.member_evaluator.as_ref() else {
+            return Ok(member_sql);
+        };
+        if dimension.dimension_type() != "string" {
+            return Ok(member_sql);
+        }
+        let Some(collation) = dimension
+            .collation()
+            .as_ref()
+            .or(self.query_tools.collation().as_ref())
+        else {
+            return Ok(member_sql);
+        };
+        if !COLLATION_NAME_RE.is_match(collation) {
+            return Err(CubeError::user(format!(
+                "Invalid collation name '{}'",
+                collation
+            )));
+        }
+        self.templates.collate(&member_sql, collation)
+    }
+
+    // Comparisons of members with their values which are subject to the cast policy
+    fn is_cast_comparison(&self) -> bool {
+        let is_typed_member =
+            self.is_number_member() || self.is_time_member() || self.is_boolean_member();
+        is_typed_member
+            && matches!(
+                self.filter_operator,
+                FilterOperator::Equal
+                    | FilterOperator::NotEqual
+                    | FilterOperator::In
+                    | FilterOperator::NotIn
+                    | FilterOperator::Gt
+                    | FilterOperator::Gte
+                    | FilterOperator::Lt
+                    | FilterOperator::Lte
+                    | FilterOperator::Between
+                    | FilterOperator::NotBetween
+            )
+    }
+
+    fn apply_member_cast(&self, member_sql: String) -> Result<String, CubeError> {
+        if !self.is_cast_comparison() {
+            return Ok(member_sql);
+        }
+        match self.query_tools.cast_policy() {
+            CastPolicy::Strict => {
+                self.validate_values_type(true)?;
+                Ok(member_sql)
+            }
+            policy if policy.casts_member(&self.filter_operator) => {
+                self.templates.cast(&member_sql, "string")
+            }
+            CastPolicy::CastMember | CastPolicy::Implicit | CastPolicy::CastValue => Ok(member_sql),
+        }
+    }
+
+    // Integral values are cast to BIGINT rather than a floating type, so integer columns
+    // are still compared without casting them
+    fn apply_value_cast(&self, param: String, value: Option<&str>) -> Result<String, CubeError> {
+        if self.is_binary_member() {
+            return self.templates.binary_param(&param);
+        }
+        if self.is_uuid_member() {
+            return self.templates.uuid_param(&param);
+        }
+        if !self.is_cast_comparison() || self.query_tools.cast_policy() != &CastPolicy::CastValue {
+            return Ok(param);
+        }
+        if self.is_time_member() {
+            self.templates.timestamp_param(&param)
+        } else if self.is_boolean_member() {
+            self.templates.cast(&param, "boolean")
+        } else if value.is_some_and(|v| v.trim().parse::<i64>().is_ok()) {
+            self.templates.cast(&param, "bigint")
+        } else {
+            self.templates.cast(&param, "double")
+        }
+    }
+
     fn equals_where(&self, member_sql: &str) -> Result<String, CubeError> {
         let need_null_check = self.is_need_null_chek(false);
-        if self.is_array_value() {
+        if self.is_multi_value_member() && !self.is_values_contains_null() {
+            self.templates
+                .array_contains(member_sql.to_string(), self.filter_and_allocate_values()?)
+        } else if self.is_array_value() {
+            if let Some(sql) = self.empty_in_list_where(member_sql, false)? {
+                return Ok(sql);
+            }
             self.templates.in_where(
                 member_sql.to_string(),
-                self.filter_and_allocate_values(),
+                self.filter_and_allocate_values()?,
                 need_null_check,
             )
         } else if self.is_values_contains_null() {
@@ -140,9 +508,12 @@ impl BaseFilter {
     fn not_equals_where(&self, member_sql: &str) -> Result<String, CubeError> {
         let need_null_check = self.is_need_null_chek(true);
         if self.is_array_value() {
+            if let Some(sql) = self.empty_in_list_where(member_sql, true)? {
+                return Ok(sql);
+            }
             self.templates.not_in_where(
                 member_sql.to_string(),
-                self.filter_and_allocate_values(),
+                self.filter_and_allocate_values()?,
                 need_null_check,
             )
         } else if self.is_values_contains_null() {
@@ -155,8 +526,71 @@ impl BaseFilter {
 
     fn in_date_range(&self, member_sql: &str) -> Result<String, CubeError> {
         let (from, to) = self.allocate_date_params()?;
-        self.templates
-            .time_range_filter(member_sql.to_string(), from, to)
+        self.open_time_range_filter(member_sql, from, to)
+    }
+
+    // Open-ended ranges, e.g. everything since a date, have one of the bounds null
+    fn open_time_range_filter(
+        &self,
+        member_sql: &str,
+        from: Option<String>,
+        to: Option<String>,
+    ) -> Result<String, CubeError> {
+        match (from, to) {
+            (Some(from), Some(to)) => self.time_range_filter(member_sql, from, to),
+            (Some(from), None) => self.templates.gte(member_sql.to_string(), from),
+            (None, Some(to)) if self.is_time_range_end_exclusive()? => {
+                self.templates.lt(member_sql.to_string(), to)
+            }
+            (None, Some(to)) => self.templates.lte(member_sql.to_string(), to),
+            (None, None) => self.templates.always_true(),
+        }
+    }
+
+    fn time_range_filter(
+        &self,
+        member_sql: &str,
+        from: String,
+        to: String,
+    ) -> Result<String, CubeError> {
+        if self.is_time_range_end_exclusive()? {
+            self.templates
+                .time_range_filter_exclusive(member_sql.to_string(), from, to)
+        } else {
+            self.templates
+                .time_range_filter(member_sql.to_string(), from, to)
+        }
+    }
+
+    // Exclusive end `< next_bound` doesn't miss sub-precision events on high-precision engines
+    // and helps partition pruning. Placeholders are bound as is and keep the inclusive end
+    fn is_time_range_end_exclusive(&self) -> Result<bool, CubeError> {
+        if self.placeholder.is_some() {
+            return Ok(false);
+        }
+        if self
+            .query_tools
+            .planner_flags()
+            .is_enabled(PlannerFlag::ExclusiveTimeRangeEnd)
+        {
+            return Ok(true);
+        }
+        self.templates.is_time_range_end_exclusive()
+    }
+
+    // Local timestamp of the range end moved by the smallest unit of its precision
+    fn next_timestamp(timestamp: &str) -> Result<String, CubeError> {
+        let precision = timestamp.split_once('.').map_or(0, |(_, f)| f.len());
+        let parsed = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+            .map_err(|_| CubeError::internal(format!("Can't parse timestamp {}", timestamp)))?;
+        let next = if precision > 3 {
+            parsed + Duration::microseconds(1)
+        } else {
+            parsed + Duration::milliseconds(1)
+        };
+        Ok(next
+            .format(&format!("%Y-%m-%dT%H:%M:%S%.{}f", precision))
+            .to_string())
     }
 
     fn extend_date_range_bound(
@@ -179,43 +613,82 @@ impl BaseFilter {
             Ok(date.to_string())
         }
     }
+    // Intervals are the last two values, so they follow either two bounds or a relative
+    // range. Three values used to be read as two bounds and the interval of the lower one
     fn in_date_range_extended(&self, member_sql: &str) -> Result<String, CubeError> {
         let (from, to) = self.allocate_date_params()?;
-
-        let from = if self.values.len() >= 3 {
-            self.extend_date_range_bound(from, &self.values[2], true)?
-        } else {
-            from
+        let (from_interval, to_interval) = match self.values.len() {
+            len if len >= 2 => (&self.values[len - 2], &self.values[len - 1]),
+            _ => (&None, &None),
         };
 
-        let to = if self.values.len() >= 4 {
-            self.extend_date_range_bound(to, &self.values[3], false)?
-        } else {
-            to
-        };
+        let from = from
+            .map(|from| self.extend_date_range_bound(from, from_interval, true))
+            .transpose()?;
+        let to = to
+            .map(|to| self.extend_date_range_bound(to, to_interval, false))
+            .transpose()?;
 
-        self.templates
-            .time_range_filter(member_sql.to_string(), from, to)
+        self.open_time_range_filter(member_sql, from, to)
     }
 
     fn in_where(&self, member_sql: &str) -> Result<String, CubeError> {
+        if let Some(sql) = self.empty_in_list_where(member_sql, false)? {
+            return Ok(sql);
+        }
         let need_null_check = self.is_need_null_chek(false);
         self.templates.in_where(
             member_sql.to_string(),
-            self.filter_and_allocate_values(),
+            self.filter_and_allocate_values()?,
             need_null_check,
         )
     }
 
     fn not_in_where(&self, member_sql: &str) -> Result<String, CubeError> {
+        if let Some(sql) = self.empty_in_list_where(member_sql, true)? {
+            return Ok(sql);
+        }
         let need_null_check = self.is_need_null_chek(true);
         self.templates.not_in_where(
             member_sql.to_string(),
-            self.filter_and_allocate_values(),
+            self.filter_and_allocate_values()?,
             need_null_check,
         )
     }
 
+    fn empty_in_list_where(
+        &self,
+        member_sql: &str,
+        not: bool,
+    ) -> Result<Option<String>, CubeError> {
+        if self.placeholder.is_some() || self.values.iter().any(|v| v.is_some()) {
+            return Ok(None);
+        }
+        let is_null_check = self.is_values_contains_null();
+        let sql = match self.query_tools.empty_in_list_policy() {
+            EmptyInListPolicy::Error => {
+                return Err(CubeError::user(format!(
+                    "Filter {} for member '{}' expects at least 1 non-null value, got 0",
+                    self.filter_operator,
+                    self.member_name()
+                )))
+            }
+            EmptyInListPolicy::NullCheck if is_null_check && not => {
+                self.templates.set_where(member_sql.to_string())?
+            }
+            EmptyInListPolicy::NullCheck if is_null_check => {
+                self.templates.not_set_where(member_sql.to_string())?
+            }
+            EmptyInListPolicy::AlwaysFalse | EmptyInListPolicy::NullCheck if not => {
+                self.templates.always_true()?
+            }
+            EmptyInListPolicy::AlwaysFalse | EmptyInListPolicy::NullCheck => {
+                self.templates.always_false()?
+            }
+        };
+        Ok(Some(sql))
+    }
+
     fn set_where(&self, member_sql: &str) -> Result<String, CubeError> {
         self.templates.set_where(member_sql.to_string())
     }
@@ -225,23 +698,67 @@ impl BaseFilter {
     }
 
     fn gt_where(&self, member_sql: &str) -> Result<String, CubeError> {
-        self.templates
-            .gt(member_sql.to_string(), self.first_param()?)
+        self.validate_ordering_values()?;
+        let Some(param) = self.ordering_param()? else {
+            return self.templates.always_false();
+        };
+        self.templates.gt(member_sql.to_string(), param)
     }
 
     fn gte_where(&self, member_sql: &str) -> Result<String, CubeError> {
-        self.templates
-            .gte(member_sql.to_string(), self.first_param()?)
+        self.validate_ordering_values()?;
+        let Some(param) = self.ordering_param()? else {
+            return self.templates.always_false();
+        };
+        self.templates.gte(member_sql.to_string(), param)
     }
 
     fn lt_where(&self, member_sql: &str) -> Result<String, CubeError> {
-        self.templates
-            .lt(member_sql.to_string(), self.first_param()?)
+        self.validate_ordering_values()?;
+        let Some(param) = self.ordering_param()? else {
+            return self.templates.always_false();
+        };
+        self.templates.lt(member_sql.to_string(), param)
     }
 
     fn lte_where(&self, member_sql: &str) -> Result<String, CubeError> {
-        self.templates
-            .lte(member_sql.to_string(), self.first_param()?)
+        self.validate_ordering_values()?;
+        let Some(param) = self.ordering_param()? else {
+            return self.templates.always_false();
+        };
+        self.templates.lte(member_sql.to_string(), param)
+    }
+
+    fn between_where(&self, member_sql: &str, not: bool) -> Result<String, CubeError> {
+        self.validate_ordering_values()?;
+        if self.placeholder.is_some() {
+            return Err(CubeError::user(format!(
+                "Filter {} for member '{}' doesn't support placeholders, it needs two values",
+                self.filter_operator,
+                self.member_name()
+            )));
+        }
+        let (Some(Some(from)), Some(Some(to)), 2) =
+            (self.values.first(), self.values.get(1), self.values.len())
+        else {
+            return Err(CubeError::user(format!(
+                "Filter {} for member '{}' expects two non-null values: the start and the end of the range",
+                self.filter_operator,
+                self.member_name()
+            )));
+        };
+        let from = self.apply_value_cast(self.allocate_param(from), Some(from))?;
+        let to = self.apply_value_cast(self.allocate_param(to), Some(to))?;
+        if not {
+            self.templates.not_between(
+                member_sql.to_string(),
+                from,
+                to,
+                self.is_need_null_chek(true),
+            )
+        } else {
+            self.templates.between(member_sql.to_string(), from, to)
+        }
     }
 
     fn contains_where(&self, member_sql: &str) -> Result<String, CubeError> {
@@ -253,7 +770,66 @@ impl BaseFilter {
     }
 
     fn starts_with_where(&self, member_sql: &str) -> Result<String, CubeError> {
-        self.like_or_where(member_sql, false, false, true)
+        let like_sql = self.like_or_where(member_sql, false, false, true)?;
+        if let Some(range_sql) = self.starts_with_range_where(member_sql)? {
+            Ok(format!("{} AND {}", range_sql, like_sql))
+        } else {
+            Ok(like_sql)
+        }
+    }
+
+    // Case sensitive `startsWith` on members with `startsWithRange` also gets
+    // `member >= 'abc' AND member < 'abd'`, so indexes and clustering can be used.
+    // Ranges follow code point order, which only holds for binary collations.
+    // LIKE is kept along with the range: the range only narrows the scan
+    fn starts_with_range_where(&self, member_sql: &str) -> Result<Option<String>, CubeError> {
+        let is_starts_with_range = match self.member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.is_starts_with_range(),
+            _ => false,
+        };
+        if !is_starts_with_range
+            || !self.case_sensitive
+            || self.trim
+            || self.placeholder.is_some()
+            || self.is_values_contains_null()
+            || Self::member_type(&self.member_evaluator).as_deref() != Some("string")
+        {
+            return Ok(None);
+        }
+        let mut ranges = Vec::new();
+        for value in self.values.iter().flatten() {
+            // Every prefix needs an upper bound, otherwise ranges would drop matching rows
+            let Some(upper_bound) = Self::prefix_upper_bound(value) else {
+                return Ok(None);
+            };
+            let from = self
+                .templates
+                .gte(member_sql.to_string(), self.allocate_param(value))?;
+            let to = self
+                .templates
+                .lt(member_sql.to_string(), self.allocate_param(&upper_bound))?;
+            ranges.push(format!("{} AND {}", from, to));
+        }
+        if ranges.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(format!("(({}))", ranges.join(") OR ("))))
+    }
+
+    // Smallest string greater than all strings with the prefix, e.g. `abd` for `abc`
+    fn prefix_upper_bound(prefix: &str) -> Option<String> {
+        let mut chars = prefix.chars().collect::<Vec<_>>();
+        while let Some(last) = chars.pop() {
+            let next = match last as u32 + 1 {
+                0xD800 => Some('\u{E000}'),
+                code => char::from_u32(code),
+            };
+            if let Some(next) = next {
+                chars.push(next);
+                return Some(chars.into_iter().collect());
+            }
+        }
+        None
     }
 
     fn not_starts_with_where(&self, member_sql: &str) -> Result<String, CubeError> {
@@ -268,6 +844,75 @@ impl BaseFilter {
         self.like_or_where(member_sql, true, true, false)
     }
 
+    fn regex_where(&self, member_sql: &str, not: bool) -> Result<String, CubeError> {
+        let values = self.filter_and_allocate_values()?;
+        let match_parts = values
+            .into_iter()
+            .map(|v| self.templates.regex_match(member_sql, &v, not))
+            .collect::<Result<Vec<_>, _>>()?;
+        let logical_symbol = if not { " AND " } else { " OR " };
+        let null_check = if self.is_need_null_chek(not) {
+            self.templates.or_is_null_check(member_sql.to_string())?
+        } else {
+            "".to_string()
+        };
+        Ok(format!(
+            "({}){}",
+            match_parts.join(logical_symbol),
+            null_check
+        ))
+    }
+
+    fn business_days_where(&self, member_sql: &str) -> Result<String, CubeError> {
+        let calendar = self.cube_calendar()?;
+        let Some(business_day_column) = &calendar.business_day_column else {
+            return Err(CubeError::user(format!(
+                "Calendar of cube '{}' has no businessDayColumn defined",
+                self.member_evaluator.cube_name()
+            )));
+        };
+        self.templates.calendar_exists(
+            member_sql.to_string(),
+            &calendar.table,
+            &calendar.date_column,
+            business_day_column,
+            true,
+        )
+    }
+
+    fn exclude_holidays_where(&self, member_sql: &str) -> Result<String, CubeError> {
+        let calendar = self.cube_calendar()?;
+        let Some(holiday_column) = &calendar.holiday_column else {
+            return Err(CubeError::user(format!(
+                "Calendar of cube '{}' has no holidayColumn defined",
+                self.member_evaluator.cube_name()
+            )));
+        };
+        self.templates.calendar_exists(
+            member_sql.to_string(),
+            &calendar.table,
+            &calendar.date_column,
+            holiday_column,
+            false,
+        )
+    }
+
+    fn cube_calendar(&self) -> Result<CubeCalendar, CubeError> {
+        let cube_name = self.member_evaluator.cube_name();
+        let cube = self
+            .query_tools
+            .cube_evaluator()
+            .cube_from_path(cube_name.clone())?;
+        if let Some(calendar) = &cube.static_data().calendar {
+            Ok(calendar.clone())
+        } else {
+            Err(CubeError::user(format!(
+                "Cube '{}' has no calendar table defined, {} filter can't be applied",
+                cube_name, self.filter_operator
+            )))
+        }
+    }
+
     fn like_or_where(
         &self,
         member_sql: &str,
@@ -275,12 +920,17 @@ impl BaseFilter {
         start_wild: bool,
         end_wild: bool,
     ) -> Result<String, CubeError> {
-        let values = self.filter_and_allocate_values();
+        let values = self.filter_and_allocate_like_values()?;
         let like_parts = values
             .into_iter()
             .map(|v| {
-                self.templates
-                    .ilike(member_sql, &v, start_wild, end_wild, not)
+                if self.case_sensitive {
+                    self.templates
+                        .like(member_sql, &v, start_wild, end_wild, not)
+                } else {
+                    self.templates
+                        .ilike(member_sql, &v, start_wild, end_wild, not)
+                }
             })
             .collect::<Result<Vec<_>, _>>()?;
         let logical_symbol = if not { " AND " } else { " OR " };
@@ -296,139 +946,577 @@ impl BaseFilter {
         ))
     }
 
-    fn allocate_date_params(&self) -> Result<(String, String), CubeError> {
-        if self.values.len() >= 2 {
-            let from = if let Some(from_str) = &self.values[0] {
-                self.query_tools
-                    .base_tools()
-                    .in_db_time_zone(self.format_from_date(&from_str)?)?
-            } else {
-                return Err(CubeError::user(format!(
-                    "Arguments for date range is not valid"
-                )));
-            };
-
-            let to = if let Some(to_str) = &self.values[1] {
-                self.query_tools
-                    .base_tools()
-                    .in_db_time_zone(self.format_to_date(&to_str)?)?
-            } else {
+    fn allocate_date_params(&self) -> Result<(Option<String>, Option<String>), CubeError> {
+        if let Some(placeholder) = &self.placeholder {
+            let from = self
+                .query_tools
+                .allocate_named_placeholder(&format!("{}_from", placeholder))?;
+            let to = self
+                .query_tools
+                .allocate_named_placeholder(&format!("{}_to", placeholder))?;
+            return Ok((
+                Some(self.templates.timestamp_param(&from)?),
+                Some(self.templates.timestamp_param(&to)?),
+            ));
+        }
+        let values = self.date_range_values();
+        if let [Some(value)] = values {
+            if RelativeDateRange::is_relative(value) {
+                let Some((from_str, to_str)) = RelativeDateRange::resolve(value, self.local_now()?)
+                else {
+                    return Err(CubeError::user(format!(
+                        "Unsupported relative date range {} for member '{}'. Expected e.g. 'today', 'last 7 days', 'this quarter' or 'from 3 months ago to now'",
+                        self.display_value(value),
+                        self.member_name()
+                    )));
+                };
+                let from_str = self.preview_from_date(&from_str, &to_str, (0, 0))?;
+                let (from, to) = self.allocate_date_range(&from_str, &to_str, (0, 0))?;
+                return Ok((Some(from), Some(to)));
+            }
+        }
+        if values.len() >= 2 {
+            if values[0].is_none() && values[1].is_none() {
                 return Err(CubeError::user(format!(
-                    "Arguments for date range is not valid"
+                    "At least one bound of date range is expected for member '{}'",
+                    self.member_name()
                 )));
-            };
-            let from = self.allocate_timestamp_param(&from);
-            let to = self.allocate_timestamp_param(&to);
+            }
+            let from = values[0]
+                .as_ref()
+                .map(|from_str| -> Result<_, CubeError> {
+                    let mut from_str = self.epoch_to_local_timestamp(from_str)?;
+                    if let Some(to_str) = &values[1] {
+                        let to_str = self.epoch_to_local_timestamp(to_str)?;
+                        from_str = self.preview_from_date(&from_str, &to_str, (0, 1))?;
+                    }
+                    self.allocate_from_date(&from_str, 0)
+                })
+                .transpose()?;
+            let to = values[1]
+                .as_ref()
+                .map(|to_str| -> Result<_, CubeError> {
+                    let to_str = self.epoch_to_local_timestamp(to_str)?;
+                    self.allocate_to_date(&to_str, 1)
+                })
+                .transpose()?;
             Ok((from, to))
         } else {
             Err(CubeError::user(format!(
                 "2 arguments expected for date range, got {}",
-                self.values.len()
+                values.len()
             )))
         }
     }
 
-    fn format_from_date(&self, date: &str) -> Result<String, CubeError> {
-        let precision = self.query_tools.base_tools().timestamp_precision()?;
-        if precision == 3 {
-            if DATE_TIME_LOCAL_MS_RE.is_match(date) {
-                return Ok(date.to_string());
-            }
-        } else if precision == 6 {
-            if date.len() == 23 && DATE_TIME_LOCAL_MS_RE.is_match(date) {
-                return Ok(format!("{}000", date));
-            } else if date.len() == 26 && DATE_TIME_LOCAL_U_RE.is_match(date) {
-                return Ok(date.to_string());
+    // Intervals of the bounds follow the date range values of extended date ranges
+    fn date_range_values(&self) -> &[Option<String>] {
+        match self.filter_operator {
+            FilterOperator::InDateRangeExtended if self.values.len() >= 2 => {
+                &self.values[..self.values.len() - 2]
             }
+            _ => &self.values,
+        }
+    }
+
+    // Preview queries scan the most recent day of a date range only
+    fn preview_from_date(
+        &self,
+        from_str: &str,
+        to_str: &str,
+        value_indexes: (usize, usize),
+    ) -> Result<String, CubeError> {
+        if !self.query_tools.preview() {
+            return Ok(from_str.to_string());
+        }
+        let from = self.format_from_date(from_str, value_indexes.0)?;
+        let to = self.format_to_date(to_str, value_indexes.1)?;
+        let last_day = self.format_from_date(&to[..10], value_indexes.1)?;
+        Ok(from.max(last_day))
+    }
+
+    // Allocates params for local date range bounds, `value_indexes` point
+    // to the filter values the bounds come from
+    fn allocate_date_range(
+        &self,
+        from_str: &str,
+        to_str: &str,
+        value_indexes: (usize, usize),
+    ) -> Result<(String, String), CubeError> {
+        let from = self.allocate_from_date(from_str, value_indexes.0)?;
+        let to = self.allocate_to_date(to_str, value_indexes.1)?;
+        Ok((from, to))
+    }
+
+    fn allocate_from_date(&self, from_str: &str, value_index: usize) -> Result<String, CubeError> {
+        let from = self
+            .query_tools
+            .date_time_tools()
+            .in_db_time_zone(self.format_from_date(from_str, value_index)?)?;
+        self.allocate_timestamp_param(&from)
+    }
+
+    fn allocate_to_date(&self, to_str: &str, value_index: usize) -> Result<String, CubeError> {
+        let to = self.format_to_date(to_str, value_index)?;
+        let to = if self.is_time_range_end_exclusive()? {
+            Self::next_timestamp(&to)?
         } else {
+            to
+        };
+        let to = self.query_tools.date_time_tools().in_db_time_zone(to)?;
+        self.allocate_timestamp_param(&to)
+    }
+
+    // Each value is a bucket label of a granularity inferred from its format:
+    // `2024`, `2024-Q1`, `2024-01`, `2024-W03` or `2024-01-15`
+    fn in_buckets_where(&self, member_sql: &str) -> Result<String, CubeError> {
+        let ranges = self
+            .values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.as_ref().map(|v| (i, v)))
+            .map(|(i, label)| -> Result<_, CubeError> {
+                let (from, to) = self.bucket_range(label)?;
+                let (from, to) = self.allocate_date_range(&from, &to, (i, i))?;
+                self.time_range_filter(member_sql, from, to)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if ranges.is_empty() {
             return Err(CubeError::user(format!(
-                "Unsupported timestamp precision: {}",
-                precision
+                "At least one bucket is expected for {} filter of member '{}'",
+                self.filter_operator,
+                self.member_name()
             )));
         }
+        Ok(ranges
+            .into_iter()
+            .map(|r| format!("({})", r))
+            .collect::<Vec<_>>()
+            .join(" OR "))
+    }
 
-        if DATE_RE.is_match(date) {
-            return Ok(format!(
-                "{}T00:00:00.{}",
-                date,
-                "0".repeat(precision as usize)
-            ));
+    // Returns first and last days of the bucket
+    fn bucket_range(&self, label: &str) -> Result<(String, String), CubeError> {
+        let invalid = || {
+            CubeError::user(format!(
+                "Bucket {} of {} filter for member '{}' is not valid. Expected YYYY, YYYY-Qn, YYYY-MM, YYYY-Www or YYYY-MM-DD",
+                self.display_value(label),
+                self.filter_operator,
+                self.member_name()
+            ))
+        };
+        let caps = BUCKET_LABEL_RE.captures(label).ok_or_else(invalid)?;
+        let year = caps[1].parse::<i32>().map_err(|_| invalid())?;
+        let num = |i: usize| caps.get(i).map(|m| m.as_str().parse::<u32>().unwrap_or(0));
+        let (from, to) = if let Some(quarter) = num(2) {
+            let first_month = (quarter - 1) * 3 + 1;
+            (
+                NaiveDate::from_ymd_opt(year, first_month, 1),
+                Self::last_day_of_month(year, first_month + 2),
+            )
+        } else if let Some(week) = num(3) {
+            (
+                NaiveDate::from_isoywd_opt(year, week, Weekday::Mon),
+                NaiveDate::from_isoywd_opt(year, week, Weekday::Sun),
+            )
+        } else if let Some(month) = num(4) {
+            if let Some(day) = num(5) {
+                let date = NaiveDate::from_ymd_opt(year, month, day);
+                (date, date)
+            } else {
+                (
+                    NaiveDate::from_ymd_opt(year, month, 1),
+                    Self::last_day_of_month(year, month),
+                )
+            }
+        } else {
+            (
+                NaiveDate::from_ymd_opt(year, 1, 1),
+                NaiveDate::from_ymd_opt(year, 12, 31),
+            )
+        };
+        match (from, to) {
+            (Some(from), Some(to)) => Ok((
+                from.format("%Y-%m-%d").to_string(),
+                to.format("%Y-%m-%d").to_string(),
+            )),
+            _ => Err(invalid()),
         }
-        //FIXME chrono don't support parsing date without specified format
-        Err(CubeError::user(format!(
-            "Unsupported date format: {}",
-            date
-        )))
     }
 
-    fn format_to_date(&self, date: &str) -> Result<String, CubeError> {
-        let precision = self.query_tools.base_tools().timestamp_precision()?;
-        if precision == 3 {
-            if DATE_TIME_LOCAL_MS_RE.is_match(date) {
-                return Ok(date.to_string());
-            }
-        } else if precision == 6 {
-            if date.len() == 23 && DATE_TIME_LOCAL_MS_RE.is_match(date) {
-                if date.ends_with(".999") {
-                    return Ok(format!("{}999", date));
-                }
-                return Ok(format!("{}000", date));
-            } else if date.len() == 26 && DATE_TIME_LOCAL_U_RE.is_match(date) {
-                return Ok(date.to_string());
-            }
+    fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+        let (next_year, next_month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+        NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+    }
+
+    // Event pipelines often pass epochs instead of ISO strings. Epoch is an exact instant,
+    // so it's converted to a local timestamp of the query timezone at full precision.
+    // Only values from 2001-09-09 on (10+ integer digits) are epochs, values with
+    // more than 11 integer digits are treated as milliseconds
+    fn epoch_to_local_timestamp(&self, value: &str) -> Result<String, CubeError> {
+        if !EPOCH_RE.is_match(value) {
+            return Ok(value.to_string());
+        }
+        let Ok(epoch) = value.parse::<f64>() else {
+            return Ok(value.to_string());
+        };
+        let millis = if epoch.abs() >= 1e11 {
+            epoch.round()
+        } else {
+            (epoch * 1000.0).round()
+        };
+        let Some(utc) = DateTime::<Utc>::from_timestamp_millis(millis as i64) else {
+            return Err(CubeError::user(format!(
+                "Epoch value {} is out of range",
+                self.display_value(value)
+            )));
+        };
+        let precision = self.query_tools.date_time_tools().timestamp_precision()?;
+        let format = format!("%Y-%m-%dT%H:%M:%S%.{}f", precision);
+        let local = if let Some(tz) = self.query_tools.timezone() {
+            utc.with_timezone(tz).format(&format).to_string()
+        } else {
+            utc.format(&format).to_string()
+        };
+        Ok(local)
+    }
+
+    // Current time in the query timezone, relative date ranges are resolved against it
+    fn local_now(&self) -> Result<NaiveDateTime, CubeError> {
+        let now = self.query_tools.date_time_tools().now()?;
+        Ok(if let Some(tz) = self.query_tools.timezone() {
+            now.with_timezone(tz).naive_local()
+        } else {
+            now.naive_utc()
+        })
+    }
+
+    fn format_from_date(&self, date: &str, value_index: usize) -> Result<String, CubeError> {
+        let formatted = self.query_tools.date_time_tools().format_from_date(date)?;
+        formatted.ok_or_else(|| self.unsupported_date_error(date, value_index))
+    }
+
+    fn format_to_date(&self, date: &str, value_index: usize) -> Result<String, CubeError> {
+        let formatted = self.query_tools.date_time_tools().format_to_date(date)?;
+        formatted.ok_or_else(|| self.unsupported_date_error(date, value_index))
+    }
+
+    //FIXME chrono don't support parsing date without specified format
+    fn unsupported_date_error(&self, date: &str, value_index: usize) -> CubeError {
+        let precision = self
+            .query_tools
+            .date_time_tools()
+            .timestamp_precision()
+            .unwrap_or(3);
+        self.invalid_value_error(
+            InvalidFilterValue::date(self.member_name(), value_index, date, precision),
+            date,
+            format!("Unsupported date format: {}", self.display_value(date)),
+        )
+    }
+
+    fn allocate_param(&self, param: &str) -> String {
+        self.query_tools
+            .allocate_typed_member_param(&self.member_name(), param, self.param_type())
+    }
+
+    // Members cast to a string by the cast policy are compared with string params
+    fn param_type(&self) -> ParamType {
+        if self.is_cast_comparison()
+            && self
+                .query_tools
+                .cast_policy()
+                .casts_member(&self.filter_operator)
+        {
+            return ParamType::String;
+        }
+        if self.is_number_member() {
+            ParamType::Number
+        } else if self.is_boolean_member() {
+            ParamType::Boolean
+        } else if self.is_time_member() {
+            ParamType::Timestamp
         } else {
+            ParamType::String
+        }
+    }
+
+    fn allocate_timestamp_param(&self, param: &str) -> Result<String, CubeError> {
+        let placeholder = self.allocate_param(&self.templates.timestamp_value(param)?);
+        self.templates.timestamp_param(&placeholder)
+    }
+
+    fn is_multi_value_member(&self) -> bool {
+        Self::is_array_member(&self.member_evaluator)
+    }
+
+    fn is_array_member(member_evaluator: &Rc<MemberSymbol>) -> bool {
+        match member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.is_multi_value(),
+            _ => false,
+        }
+    }
+
+    // NULL elements can't be matched in arrays, so they are skipped
+    fn array_values(&self) -> Result<Vec<String>, CubeError> {
+        let values = self.filter_and_allocate_values()?;
+        if values.is_empty() {
             return Err(CubeError::user(format!(
-                "Unsupported timestamp precision: {}",
-                precision
+                "Filter {} for member '{}' expects at least one non-null value",
+                self.filter_operator,
+                self.member_name()
             )));
         }
+        Ok(values)
+    }
 
-        if DATE_RE.is_match(date) {
-            return Ok(format!(
-                "{}T23:59:59.{}",
-                date,
-                "9".repeat(precision as usize)
-            ));
+    fn member_type(member_evaluator: &Rc<MemberSymbol>) -> Option<String> {
+        match member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => Some(dimension.dimension_type().to_string()),
+            MemberSymbol::Measure(measure) => match measure.measure_type().as_str() {
+                "string" | "time" | "boolean" => Some(measure.measure_type().clone()),
+                _ => Some("number".to_string()),
+            },
+            _ => None,
         }
-        //FIXME chrono don't support parsing date without specified format
-        Err(CubeError::user(format!(
-            "Unsupported date format: {}",
-            date
-        )))
     }
 
-    fn allocate_param(&self, param: &str) -> String {
-        self.query_tools.allocate_param(param)
+    pub fn is_number_member(&self) -> bool {
+        match self.member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.dimension_type() == "number",
+            MemberSymbol::Measure(measure) => !matches!(
+                measure.measure_type().as_str(),
+                "string" | "time" | "boolean"
+            ),
+            _ => false,
+        }
+    }
+
+    fn is_binary_member(&self) -> bool {
+        match self.member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.dimension_type() == "binary",
+            _ => false,
+        }
+    }
+
+    fn is_uuid_member(&self) -> bool {
+        match self.member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.dimension_type() == "uuid",
+            _ => false,
+        }
+    }
+
+    fn is_boolean_member(&self) -> bool {
+        match self.member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.dimension_type() == "boolean",
+            MemberSymbol::Measure(measure) => measure.measure_type() == "boolean",
+            _ => false,
+        }
+    }
+
+    fn is_time_member(&self) -> bool {
+        match self.member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.dimension_type() == "time",
+            MemberSymbol::Measure(measure) => measure.measure_type() == "time",
+            _ => false,
+        }
+    }
+
+    // Malformed operator and values combinations are rejected before anything is allocated,
+    // e.g. `contains` without values would render an empty condition
+    fn validate_values_arity(&self) -> Result<(), CubeError> {
+        if self.placeholder.is_some() {
+            return Ok(());
+        }
+        if let Some(error) = self.filter_operator.values_arity_error(&self.values) {
+            return Err(CubeError::user(format!(
+                "Filter {} for member '{}' {}",
+                self.filter_operator,
+                self.member_name(),
+                error
+            )));
+        }
+        Ok(())
+    }
+
+    // Ordering comparisons against values of the wrong type fail in the warehouse
+    // or, worse, compare lexicographically, so they are rejected at plan time
+    fn validate_ordering_values(&self) -> Result<(), CubeError> {
+        self.validate_values_type(false)
+    }
+
+    fn validate_values_type(&self, check_boolean: bool) -> Result<(), CubeError> {
+        let is_number = self.is_number_member();
+        let is_time = self.is_time_member();
+        let is_boolean = check_boolean && self.is_boolean_member();
+        if !is_number && !is_time && !is_boolean {
+            return Ok(());
+        }
+        for (i, value) in self.values.iter().enumerate() {
+            let Some(value) = value else {
+                continue;
+            };
+            let error = if is_number && value.trim().parse::<f64>().is_err() {
+                Some((
+                    InvalidFilterValue::number(self.member_name(), i, value),
+                    "a number",
+                ))
+            } else if is_time && !Self::is_valid_timestamp(value) {
+                let precision = self.query_tools.date_time_tools().timestamp_precision()?;
+                Some((
+                    InvalidFilterValue::date(self.member_name(), i, value, precision),
+                    "a date",
+                ))
+            } else if is_boolean && !Self::is_valid_boolean(value) {
+                Some((
+                    InvalidFilterValue::boolean(self.member_name(), i),
+                    "a boolean",
+                ))
+            } else {
+                None
+            };
+            if let Some((invalid_value, expected)) = error {
+                return Err(self.invalid_value_error(
+                    invalid_value,
+                    value,
+                    format!(
+                        "Value {} of {} filter for member '{}' is not {}",
+                        self.display_value(value),
+                        self.filter_operator,
+                        self.member_name(),
+                        expected
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_valid_boolean(value: &str) -> bool {
+        matches!(
+            value.trim().to_lowercase().as_str(),
+            "true" | "false" | "1" | "0"
+        )
+    }
+
+    fn is_valid_timestamp(value: &str) -> bool {
+        TIMESTAMP_RE.is_match(value) && NaiveDate::parse_from_str(&value[..10], "%Y-%m-%d").is_ok()
     }
 
-    fn allocate_timestamp_param(&self, param: &str) -> String {
-        let placeholder = self.query_tools.allocate_param(param);
-        format!("{}::timestamptz", placeholder)
+    fn invalid_value_error(
+        &self,
+        invalid_value: InvalidFilterValue,
+        value: &str,
+        message: String,
+    ) -> CubeError {
+        let invalid_value = if self.display_value(value) != value {
+            invalid_value.without_suggestion()
+        } else {
+            invalid_value
+        };
+        invalid_value.into_error(message)
+    }
+
+    fn display_value(&self, value: &str) -> String {
+        self.query_tools
+            .value_redaction_policy()
+            .display_value(&self.member_evaluator, value)
     }
 
     fn first_param(&self) -> Result<String, CubeError> {
+        if let Some(placeholder) = &self.placeholder {
+            let param = self.query_tools.allocate_named_placeholder(placeholder)?;
+            return self.apply_trim(self.apply_value_cast(param, None)?);
+        }
         if self.values.is_empty() {
             Err(CubeError::user(format!(
                 "Expected one parameter but nothing found"
             )))
         } else {
             if let Some(value) = &self.values[0] {
-                Ok(self.allocate_param(value))
+                self.apply_trim(self.apply_value_cast(self.allocate_param(value), Some(value))?)
             } else {
                 Ok("NULL".to_string())
             }
         }
     }
 
+    // Returns None when the comparison with NULL should be rendered as always false
+    fn ordering_param(&self) -> Result<Option<String>, CubeError> {
+        if self.placeholder.is_none() && matches!(self.values.first(), Some(None)) {
+            if self
+                .query_tools
+                .planner_flags()
+                .is_enabled(PlannerFlag::NullOrderingComparisonsAsFalse)
+            {
+                return Ok(None);
+            }
+            return Err(
+                InvalidFilterValue::null_ordering_comparison(self.member_name(), 0).into_error(
+                    format!(
+                        "Filter {} for member '{}' can't compare with NULL, use set or notSet operators instead",
+                        self.filter_operator,
+                        self.member_name()
+                    ),
+                ),
+            );
+        }
+        self.first_param().map(Some)
+    }
+
     fn is_need_null_chek(&self, is_not: bool) -> bool {
         let contains_null = self.is_values_contains_null();
         if is_not {
+            if self
+                .query_tools
+                .planner_flags()
+                .is_enabled(PlannerFlag::StrictNegativeNullChecks)
+            {
+                return false;
+            }
             !contains_null
         } else {
             contains_null
         }
     }
 
+    fn is_sub_query_member(&self) -> bool {
+        matches!(self.member_evaluator.as_ref(), MemberSymbol::Dimension(dimension) if dimension.is_sub_query())
+    }
+
+    // Aggregated sql of a subquery dimension is valid only within a select grouped by rows of
+    // its cube, so the filter is checked by an EXISTS correlated on the cube primary keys
+    fn sub_query_member_where(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        let source =
+            SubQueryDimensionPlanner::new(self.query_tools.clone()).plan(&self.member_evaluator)?;
+        let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
+        let alias = "sub_query".to_string();
+        let source_alias = Some(alias.clone());
+        let keys = source
+            .primary_keys
+            .iter()
+            .map(|(primary_key, column)| -> Result<_, CubeError> {
+                Ok((
+                    templates.column_reference(&source_alias, column)?,
+                    evaluate_with_context(
+                        &primary_key.member_evaluator(),
+                        self.query_tools.clone(),
+                        context.clone(),
+                    )?,
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let condition = self
+            .to_sql_for_column(templates.column_reference(&source_alias, &source.value_column)?)?;
+        let source_sql =
+            templates.query_aliased(&format!("({})", source.select.to_sql(&templates)?), &alias)?;
+        self.templates.sub_query_exists(source_sql, keys, condition)
+    }
+
     fn is_values_contains_null(&self) -> bool {
         self.values.iter().any(|v| v.is_none())
     }
@@ -437,10 +1525,851 @@ impl BaseFilter {
         self.values.len() > 1
     }
 
-    fn filter_and_allocate_values(&self) -> Vec<String> {
+    // Values are matched literally, so LIKE wildcards in them are escaped
+    fn filter_and_allocate_like_values(&self) -> Result<Vec<String>, CubeError> {
+        if self.placeholder.is_some() {
+            return self.filter_and_allocate_values();
+        }
         self.values
             .iter()
-            .filter_map(|v| v.as_ref().map(|v| self.allocate_param(&v)))
-            .collect::<Vec<_>>()
+            .filter_map(|v| {
+                v.as_ref().map(|v| {
+                    self.apply_trim(self.allocate_param(&FilterTemplates::escape_like_wildcards(v)))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    fn filter_and_allocate_values(&self) -> Result<Vec<String>, CubeError> {
+        if let Some(placeholder) = &self.placeholder {
+            // A placeholder stands for the whole values list
+            let param = self.query_tools.allocate_named_placeholder(placeholder)?;
+            return Ok(vec![self.apply_trim(self.apply_value_cast(param, None)?)?]);
+        }
+        self.values
+            .iter()
+            .filter_map(|v| {
+                v.as_ref().map(|v| {
+                    self.apply_trim(self.apply_value_cast(self.allocate_param(v), Some(v))?)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::test_fixtures::{
+        cube_definition, dimension, dimension_definition, QueryToolsFixture,
+    };
+    use crate::planner::ParamAllocation;
+
+    fn filter(
+        query_tools: &Rc<QueryTools>,
+        member: &str,
+        member_type: &str,
+        operator: FilterOperator,
+        values: Vec<Option<&str>>,
+    ) -> Rc<BaseFilter> {
+        BaseFilter::try_new(
+            query_tools.clone(),
+            dimension(member, dimension_definition(member_type)),
+            FilterType::Dimension,
+            operator,
+            Some(
+                values
+                    .into_iter()
+                    .map(|v| v.map(|v| v.to_string()))
+                    .collect(),
+            ),
+            None,
+            false,
+            false,
+        )
+        .unwrap()
+    }
+
+    fn render(query_tools: &Rc<QueryTools>, filter: &BaseFilter, column: &str) -> String {
+        query_tools
+            .render_allocated_placeholders(&filter.to_sql_for_column(column.to_string()).unwrap())
+    }
+
+    fn allocation(index: usize, value: &str, param_type: ParamType) -> ParamAllocation {
+        ParamAllocation {
+            index,
+            value: value.to_string(),
+            param_type,
+            member: Some("orders.status".to_string()),
+        }
+    }
+
+    #[test]
+    fn equals_allocates_member_param() {
+        let query_tools = QueryToolsFixture::new().build();
+        let filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::Equal,
+            vec![Some("shipped")],
+        );
+        assert_eq!(render(&query_tools, &filter, "t.status"), "t.status = $1");
+        assert_eq!(
+            query_tools.param_allocations(),
+            vec![allocation(0, "shipped", ParamType::String)]
+        );
+    }
+
+    #[test]
+    fn values_normalization_normalizes_column_and_values_to_nfc() {
+        let query_tools = QueryToolsFixture::new()
+            .template(
+                "expressions/normalize_unicode",
+                "{% if case_fold %}LOWER(NORMALIZE({{ expr }}, NFC)){% else %}NORMALIZE({{ expr }}, NFC){% endif %}",
+            )
+            .values_normalization(true)
+            .build();
+        let filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::Equal,
+            vec![Some("Cafe\u{301}")],
+        );
+        assert_eq!(
+            render(&query_tools, &filter, "t.status"),
+            "LOWER(NORMALIZE(t.status, NFC)) = $1"
+        );
+        assert_eq!(
+            query_tools.param_allocations(),
+            vec![allocation(0, "caf\u{e9}", ParamType::String)]
+        );
+    }
+
+    #[test]
+    fn values_normalization_is_refused_without_dialect_support() {
+        let query_tools = QueryToolsFixture::new().values_normalization(false).build();
+        let filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::Equal,
+            vec![Some("shipped")],
+        );
+        let err = filter
+            .to_sql_for_column("t.status".to_string())
+            .unwrap_err();
+        assert!(err.message.contains("isn't supported by this database"));
+    }
+
+    #[test]
+    fn trim_is_rejected_for_non_string_members() {
+        let query_tools = QueryToolsFixture::new().build();
+        let err = BaseFilter::try_new(
+            query_tools.clone(),
+            dimension("orders.amount", dimension_definition("number")),
+            FilterType::Dimension,
+            FilterOperator::Equal,
+            Some(vec![Some("10".to_string())]),
+            None,
+            true,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.message,
+            "Option 'trim' can be applied only to string members, 'orders.amount' is of type 'number'"
+        );
+
+        let trimmed = BaseFilter::try_new(
+            query_tools.clone(),
+            dimension("orders.status", dimension_definition("string")),
+            FilterType::Dimension,
+            FilterOperator::Equal,
+            Some(vec![Some("shipped".to_string())]),
+            None,
+            true,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            render(&query_tools, &trimmed, "t.status"),
+            "TRIM(t.status) = TRIM($1)"
+        );
+    }
+
+    #[test]
+    fn equals_with_null_checks_null_and_skips_its_param() {
+        let query_tools = QueryToolsFixture::new().build();
+        let filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::Equal,
+            vec![Some("shipped"), None],
+        );
+        assert_eq!(
+            render(&query_tools, &filter, "t.status"),
+            "t.status IN ($1) OR t.status IS NULL"
+        );
+        assert_eq!(
+            query_tools.param_allocations(),
+            vec![allocation(0, "shipped", ParamType::String)]
+        );
+    }
+
+    #[test]
+    fn not_equals_keeps_nulls_unless_strict() {
+        let query_tools = QueryToolsFixture::new().build();
+        let not_equals = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::NotEqual,
+            vec![Some("shipped")],
+        );
+        assert_eq!(
+            render(&query_tools, &not_equals, "t.status"),
+            "t.status <> $1 OR t.status IS NULL"
+        );
+
+        let query_tools = QueryToolsFixture::new()
+            .planner_flag("strict_negative_null_checks", true)
+            .build();
+        let not_equals = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::NotEqual,
+            vec![Some("shipped")],
+        );
+        assert_eq!(
+            render(&query_tools, &not_equals, "t.status"),
+            "t.status <> $1"
+        );
+    }
+
+    #[test]
+    fn in_and_not_in_allocate_params_in_values_order() {
+        let query_tools = QueryToolsFixture::new().build();
+        let in_filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::In,
+            vec![Some("new"), Some("shipped")],
+        );
+        let not_in_filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::NotIn,
+            vec![Some("lost")],
+        );
+        assert_eq!(
+            render(&query_tools, &in_filter, "t.status"),
+            "t.status IN ($1, $2)"
+        );
+        assert_eq!(
+            render(&query_tools, &not_in_filter, "t.status"),
+            "t.status NOT IN ($3) OR t.status IS NULL"
+        );
+        assert_eq!(
+            query_tools.param_allocations(),
+            vec![
+                allocation(0, "new", ParamType::String),
+                allocation(1, "shipped", ParamType::String),
+                allocation(2, "lost", ParamType::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_in_list_follows_policy() {
+        let query_tools = QueryToolsFixture::new().build();
+        let in_filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::In,
+            vec![],
+        );
+        assert!(in_filter.to_sql_for_column("t.status".to_string()).is_err());
+
+        let query_tools = QueryToolsFixture::new()
+            .empty_in_list_policy("always_false")
+            .build();
+        let in_filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::In,
+            vec![],
+        );
+        let not_in_filter = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::NotIn,
+            vec![],
+        );
+        assert_eq!(render(&query_tools, &in_filter, "t.status"), "1 = 0");
+        assert_eq!(render(&query_tools, &not_in_filter, "t.status"), "1 = 1");
+        assert_eq!(query_tools.param_allocations(), vec![]);
+    }
+
+    #[test]
+    fn set_and_not_set_allocate_nothing() {
+        let query_tools = QueryToolsFixture::new().build();
+        let set = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::Set,
+            vec![],
+        );
+        let not_set = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::NotSet,
+            vec![],
+        );
+        assert_eq!(
+            render(&query_tools, &set, "t.status"),
+            "t.status IS NOT NULL"
+        );
+        assert_eq!(
+            render(&query_tools, &not_set, "t.status"),
+            "t.status IS NULL"
+        );
+        assert_eq!(query_tools.param_allocations(), vec![]);
+    }
+
+    #[test]
+    fn ordering_operators_allocate_typed_params() {
+        let query_tools = QueryToolsFixture::new().build();
+        let operators = [
+            (FilterOperator::Gt, "t.amount > $1"),
+            (FilterOperator::Gte, "t.amount >= $2"),
+            (FilterOperator::Lt, "t.amount < $3"),
+            (FilterOperator::Lte, "t.amount <= $4"),
+        ];
+        for (operator, expected) in operators {
+            let filter = filter(
+                &query_tools,
+                "orders.amount",
+                "number",
+                operator,
+                vec![Some("10")],
+            );
+            assert_eq!(render(&query_tools, &filter, "t.amount"), expected);
+        }
+        let allocations = query_tools.param_allocations();
+        assert_eq!(allocations.len(), 4);
+        for (index, allocation) in allocations.into_iter().enumerate() {
+            assert_eq!(
+                allocation,
+                ParamAllocation {
+                    index,
+                    value: "10".to_string(),
+                    param_type: ParamType::Number,
+                    member: Some("orders.amount".to_string()),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn between_allocates_bounds_in_order() {
+        let query_tools = QueryToolsFixture::new().cast_policy("cast_value").build();
+        let between = filter(
+            &query_tools,
+            "orders.amount",
+            "number",
+            FilterOperator::Between,
+            vec![Some("1"), Some("2.5")],
+        );
+        assert_eq!(
+            render(&query_tools, &between, "t.amount"),
+            "t.amount BETWEEN CAST($1 AS BIGINT) AND CAST($2 AS DOUBLE)"
+        );
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| (a.index, a.value, a.param_type))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, "1".to_string(), ParamType::Number),
+                (1, "2.5".to_string(), ParamType::Number),
+            ]
+        );
+    }
+
+    #[test]
+    fn like_operators_allocate_escaped_values() {
+        let query_tools = QueryToolsFixture::new().build();
+        let contains = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::Contains,
+            vec![Some("50%"), None],
+        );
+        assert_eq!(
+            render(&query_tools, &contains, "t.status"),
+            "(t.status ILIKE '%' || $1|| '%' ESCAPE '\\') OR t.status IS NULL"
+        );
+        let not_starts_with = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::NotStartsWith,
+            vec![Some("a_"), Some("b")],
+        );
+        assert_eq!(
+            render(&query_tools, &not_starts_with, "t.status"),
+            "(t.status NOT ILIKE $2|| '%' ESCAPE '\\' AND t.status NOT ILIKE $3|| '%' ESCAPE '\\') OR t.status IS NULL"
+        );
+        assert_eq!(
+            query_tools.param_allocations(),
+            vec![
+                allocation(0, "50\\%", ParamType::String),
+                allocation(1, "a\\_", ParamType::String),
+                allocation(2, "b", ParamType::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn regex_allocates_pattern() {
+        let query_tools = QueryToolsFixture::new().build();
+        let regex = filter(
+            &query_tools,
+            "orders.status",
+            "string",
+            FilterOperator::NotRegex,
+            vec![Some("^a.*")],
+        );
+        assert_eq!(
+            render(&query_tools, &regex, "t.status"),
+            "(t.status !~ $1) OR t.status IS NULL"
+        );
+        assert_eq!(
+            query_tools.param_allocations(),
+            vec![allocation(0, "^a.*", ParamType::String)]
+        );
+    }
+
+    #[test]
+    fn in_date_range_allocates_bounds_in_db_time_zone() {
+        let query_tools = QueryToolsFixture::new()
+            .timezone("America/New_York")
+            .build();
+        let in_date_range = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InDateRange,
+            vec![Some("2024-01-01"), Some("2024-01-31")],
+        );
+        assert_eq!(
+            render(&query_tools, &in_date_range, "t.created_at"),
+            "t.created_at >= $1::timestamptz AND t.created_at <= $2::timestamptz"
+        );
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| (a.index, a.value, a.param_type, a.member))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    0,
+                    "2024-01-01T05:00:00.000Z".to_string(),
+                    ParamType::Timestamp,
+                    Some("orders.created_at".to_string())
+                ),
+                (
+                    1,
+                    "2024-02-01T04:59:59.999Z".to_string(),
+                    ParamType::Timestamp,
+                    Some("orders.created_at".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn in_date_range_with_exclusive_end_allocates_next_timestamp() {
+        let query_tools = QueryToolsFixture::new()
+            .planner_flag("exclusive_time_range_end", true)
+            .build();
+        let in_date_range = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InDateRange,
+            vec![Some("2024-01-01"), Some("2024-01-31")],
+        );
+        assert_eq!(
+            render(&query_tools, &in_date_range, "t.created_at"),
+            "t.created_at >= $1::timestamptz AND t.created_at < $2::timestamptz"
+        );
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| a.value)
+                .collect::<Vec<_>>(),
+            vec!["2024-01-01T00:00:00.000Z", "2024-02-01T00:00:00.000Z"]
+        );
+    }
+
+    #[test]
+    fn in_date_range_with_a_null_bound_is_open_ended() {
+        let query_tools = QueryToolsFixture::new().build();
+        let since = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InDateRange,
+            vec![Some("2024-01-01"), None],
+        );
+        assert_eq!(
+            render(&query_tools, &since, "t.created_at"),
+            "t.created_at >= $1::timestamptz"
+        );
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| a.value)
+                .collect::<Vec<_>>(),
+            vec!["2024-01-01T00:00:00.000Z"]
+        );
+
+        let unbounded = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InDateRange,
+            vec![None, None],
+        );
+        assert_eq!(
+            unbounded
+                .to_sql_for_column("t.created_at".to_string())
+                .unwrap_err()
+                .message,
+            "At least one bound of date range is expected for member 'orders.created_at'"
+        );
+    }
+
+    #[test]
+    fn in_buckets_allocates_bounds_of_every_bucket() {
+        let query_tools = QueryToolsFixture::new().build();
+        let in_buckets = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InBuckets,
+            vec![Some("2024-Q1"), Some("2024-W03")],
+        );
+        assert_eq!(
+            render(&query_tools, &in_buckets, "t.created_at"),
+            "(t.created_at >= $1::timestamptz AND t.created_at <= $2::timestamptz) OR (t.created_at >= $3::timestamptz AND t.created_at <= $4::timestamptz)"
+        );
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| a.value)
+                .collect::<Vec<_>>(),
+            vec![
+                "2024-01-01T00:00:00.000Z",
+                "2024-03-31T23:59:59.999Z",
+                "2024-01-15T00:00:00.000Z",
+                "2024-01-21T23:59:59.999Z"
+            ]
+        );
+
+        let invalid = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InBuckets,
+            vec![Some("2024-13")],
+        );
+        assert_eq!(
+            invalid
+                .to_sql_for_column("t.created_at".to_string())
+                .unwrap_err()
+                .message,
+            "Bucket 2024-13 of inBuckets filter for member 'orders.created_at' is not valid. Expected YYYY, YYYY-Qn, YYYY-MM, YYYY-Www or YYYY-MM-DD"
+        );
+    }
+
+    #[test]
+    fn exclusive_time_range_end_of_the_dialect() {
+        let query_tools = QueryToolsFixture::new()
+            .template("filters/time_range_end", "exclusive")
+            .build();
+        let until = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InDateRange,
+            vec![None, Some("2024-01-31")],
+        );
+        assert_eq!(
+            render(&query_tools, &until, "t.created_at"),
+            "t.created_at < $1::timestamptz"
+        );
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| a.value)
+                .collect::<Vec<_>>(),
+            vec!["2024-02-01T00:00:00.000Z"]
+        );
+
+        // Placeholder bounds are bound as is, so the end stays inclusive
+        let placeholder = BaseFilter::try_new(
+            query_tools.clone(),
+            dimension("orders.created_at", dimension_definition("time")),
+            FilterType::Dimension,
+            FilterOperator::InDateRange,
+            None,
+            Some("period".to_string()),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            render(&query_tools, &placeholder, "t.created_at"),
+            "t.created_at >= $:period_from:$::timestamptz AND t.created_at <= $:period_to:$::timestamptz"
+        );
+    }
+
+    #[test]
+    fn in_date_range_accepts_epoch_seconds_and_milliseconds() {
+        let query_tools = QueryToolsFixture::new()
+            .timezone("America/New_York")
+            .build();
+        let in_date_range = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InDateRange,
+            vec![Some("1704085200"), Some("1706763599999")],
+        );
+        render(&query_tools, &in_date_range, "t.created_at");
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| a.value)
+                .collect::<Vec<_>>(),
+            vec!["2024-01-01T05:00:00.000Z", "2024-02-01T04:59:59.999Z"]
+        );
+
+        let out_of_range = filter(
+            &query_tools,
+            "orders.created_at",
+            "time",
+            FilterOperator::InDateRange,
+            vec![Some("2024-01-01"), Some("99999999999999999999")],
+        );
+        assert_eq!(
+            out_of_range
+                .to_sql_for_column("t.created_at".to_string())
+                .unwrap_err()
+                .message,
+            "Epoch value 99999999999999999999 is out of range"
+        );
+    }
+
+    #[test]
+    fn relative_date_ranges_are_resolved_against_the_clock_of_the_query() {
+        // 22:00 of 2024-03-14 in New York
+        let query_tools = QueryToolsFixture::new()
+            .timezone("America/New_York")
+            .now("2024-03-15T02:00:00Z")
+            .build();
+        for value in ["today", "last 7 days"] {
+            let in_date_range = filter(
+                &query_tools,
+                "orders.created_at",
+                "time",
+                FilterOperator::InDateRange,
+                vec![Some(value)],
+            );
+            render(&query_tools, &in_date_range, "t.created_at");
+        }
+        assert_eq!(
+            query_tools
+                .param_allocations()
+                .into_iter()
+                .map(|a| a.value)
+                .collect::<Vec<_>>(),
+            vec![
+                "2024-03-14T04:00:00.000Z",
+                "2024-03-15T03:59:59.999Z",
+                "2024-03-07T05:00:00.000Z",
+                "2024-03-14T03:59:59.999Z",
+            ]
+        );
+    }
+
+    #[test]
+    fn in_date_range_keeps_years_and_compact_dates_as_dates() {
+        let query_tools = QueryToolsFixture::new().build();
+        for value in ["2024", "20240101"] {
+            let in_date_range = filter(
+                &query_tools,
+                "orders.created_at",
+                "time",
+                FilterOperator::InDateRange,
+                vec![Some(value), Some("2024-01-31")],
+            );
+            let message = in_date_range
+                .to_sql_for_column("t.created_at".to_string())
+                .unwrap_err()
+                .message;
+            assert!(
+                message.starts_with(&format!("Unsupported date format: {}. ", value)),
+                "{}",
+                message
+            );
+        }
+        assert!(query_tools.param_allocations().is_empty());
+    }
+
+    #[test]
+    fn placeholder_allocates_no_params() {
+        let query_tools = QueryToolsFixture::new().build();
+        let equals = BaseFilter::try_new(
+            query_tools.clone(),
+            dimension("orders.status", dimension_definition("string")),
+            FilterType::Dimension,
+            FilterOperator::Equal,
+            None,
+            Some("status".to_string()),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            render(&query_tools, &equals, "t.status"),
+            "t.status = $:status:$"
+        );
+        assert_eq!(query_tools.param_allocations(), vec![]);
+    }
+
+    #[test]
+    fn calendar_filters_semi_join_calendar_table_of_the_cube() {
+        let mut orders = cube_definition("orders");
+        orders.calendar = Some(CubeCalendar {
+            table: "calendar".to_string(),
+            date_column: "date".to_string(),
+            business_day_column: Some("is_business_day".to_string()),
+            holiday_column: None,
+        });
+        let query_tools = QueryToolsFixture::new()
+            .cube(orders)
+            .cube(cube_definition("customers"))
+            .build();
+
+        let business_days = filter(
+            &query_tools,
+            "orders.createdAt",
+            "time",
+            FilterOperator::BusinessDays,
+            vec![],
+        );
+        assert_eq!(
+            render(&query_tools, &business_days, "t.created_at"),
+            "EXISTS (SELECT 1 FROM calendar AS cube_calendar WHERE cube_calendar.date = CAST(t.created_at AS DATE) AND cube_calendar.is_business_day = true)"
+        );
+        assert_eq!(query_tools.param_allocations(), vec![]);
+
+        let exclude_holidays = filter(
+            &query_tools,
+            "orders.createdAt",
+            "time",
+            FilterOperator::ExcludeHolidays,
+            vec![],
+        );
+        assert_eq!(
+            exclude_holidays
+                .to_sql_for_column("t.created_at".to_string())
+                .unwrap_err()
+                .message,
+            "Calendar of cube 'orders' has no holidayColumn defined"
+        );
+
+        let without_calendar = filter(
+            &query_tools,
+            "customers.createdAt",
+            "time",
+            FilterOperator::BusinessDays,
+            vec![],
+        );
+        assert_eq!(
+            without_calendar
+                .to_sql_for_column("t.created_at".to_string())
+                .unwrap_err()
+                .message,
+            "Cube 'customers' has no calendar table defined, businessDays filter can't be applied"
+        );
+    }
+
+    #[test]
+    fn canonical_uuid() {
+        let canonical = Some("0e8a8b9c-1d2e-4f3a-8b4c-5d6e7f8a9b0c".to_string());
+        assert_eq!(
+            BaseFilter::canonical_uuid("0E8A8B9C-1D2E-4F3A-8B4C-5D6E7F8A9B0C"),
+            canonical
+        );
+        assert_eq!(
+            BaseFilter::canonical_uuid("0e8a8b9c1d2e4f3a8b4c5d6e7f8a9b0c"),
+            canonical
+        );
+        assert_eq!(
+            BaseFilter::canonical_uuid("{0e8a8b9c-1d2e-4f3a-8b4c-5d6e7f8a9b0c}"),
+            canonical
+        );
+        assert_eq!(
+            BaseFilter::canonical_uuid("{0e8a8b9c-1d2e-4f3a-8b4c-5d6e7f8a9b0c"),
+            None
+        );
+        assert_eq!(
+            BaseFilter::canonical_uuid("0e8a8b9c-1d2e-4f3a-8b4c-5d6e7f8a9b0"),
+            None
+        );
+        assert_eq!(BaseFilter::canonical_uuid("not-a-uuid"), None);
+    }
+
+    #[test]
+    fn prefix_upper_bound() {
+        assert_eq!(
+            BaseFilter::prefix_upper_bound("abc"),
+            Some("abd".to_string())
+        );
+        assert_eq!(BaseFilter::prefix_upper_bound("a"), Some("b".to_string()));
+        assert_eq!(BaseFilter::prefix_upper_bound("az"), Some("a{".to_string()));
+        assert_eq!(
+            BaseFilter::prefix_upper_bound("ab\u{10FFFF}"),
+            Some("ac".to_string())
+        );
+        assert_eq!(
+            BaseFilter::prefix_upper_bound("a\u{D7FF}"),
+            Some("a\u{E000}".to_string())
+        );
+        assert_eq!(BaseFilter::prefix_upper_bound("\u{10FFFF}"), None);
+        assert_eq!(BaseFilter::prefix_upper_bound(""), None);
     }
 }
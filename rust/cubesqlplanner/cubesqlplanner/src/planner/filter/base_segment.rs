@@ -0,0 +1,41 @@
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::MemberSymbol;
+use crate::planner::{evaluate_with_context, VisitorContext};
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+// Segment of the query, ANDed into WHERE along with dimension filters
+pub struct BaseSegment {
+    query_tools: Rc<QueryTools>,
+    member_evaluator: Rc<MemberSymbol>,
+}
+
+impl PartialEq for BaseSegment {
+    fn eq(&self, other: &Self) -> bool {
+        self.member_name() == other.member_name()
+    }
+}
+
+impl BaseSegment {
+    pub fn try_new(
+        query_tools: Rc<QueryTools>,
+        member_evaluator: Rc<MemberSymbol>,
+    ) -> Result<Rc<Self>, CubeError> {
+        Ok(Rc::new(Self {
+            query_tools,
+            member_evaluator,
+        }))
+    }
+
+    pub fn member_evaluator(&self) -> &Rc<MemberSymbol> {
+        &self.member_evaluator
+    }
+
+    pub fn member_name(&self) -> String {
+        self.member_evaluator.full_name()
+    }
+
+    pub fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        evaluate_with_context(&self.member_evaluator, self.query_tools.clone(), context)
+    }
+}
@@ -0,0 +1,107 @@
+// Filter values of binary members name their encoding explicitly: hex is `0x` or `\x`
+// prefixed, base64 is `base64:` prefixed. Bare values are rejected, since a lot of
+// strings are valid in both encodings, e.g. `AAAA`
+pub struct BinaryValue;
+
+impl BinaryValue {
+    // Lowercase hex of the value, None if it isn't a valid prefixed hex or base64
+    pub fn to_hex(value: &str) -> Option<String> {
+        let value = value.trim();
+        if let Some(base64) = value.strip_prefix("base64:") {
+            let bytes = Self::decode_base64(base64)?;
+            return Some(bytes.iter().map(|b| format!("{:02x}", b)).collect());
+        }
+        let hex = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+            .or_else(|| value.strip_prefix("\\x"))?;
+        if hex.len() % 2 == 0 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            Some(hex.to_lowercase())
+        } else {
+            None
+        }
+    }
+
+    // Standard and url-safe alphabets, padding is optional
+    fn decode_base64(value: &str) -> Option<Vec<u8>> {
+        let value = value.trim_end_matches('=');
+        if value.is_empty() || value.len() % 4 == 1 {
+            return None;
+        }
+        let mut bytes = Vec::with_capacity(value.len() * 3 / 4);
+        let mut buffer = 0u32;
+        let mut bits = 0;
+        for c in value.chars() {
+            let sextet = match c {
+                'A'..='Z' => c as u32 - 'A' as u32,
+                'a'..='z' => c as u32 - 'a' as u32 + 26,
+                '0'..='9' => c as u32 - '0' as u32 + 52,
+                '+' | '-' => 62,
+                '/' | '_' => 63,
+                _ => return None,
+            };
+            buffer = (buffer << 6) | sextet;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+                buffer &= (1 << bits) - 1;
+            }
+        }
+        Some(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(
+            BinaryValue::to_hex("0xDEADbeef"),
+            Some("deadbeef".to_string())
+        );
+        assert_eq!(BinaryValue::to_hex("0X00ff"), Some("00ff".to_string()));
+        assert_eq!(BinaryValue::to_hex("\\x0a0b"), Some("0a0b".to_string()));
+        assert_eq!(BinaryValue::to_hex(" 0x0a "), Some("0a".to_string()));
+        assert_eq!(BinaryValue::to_hex("0x"), Some("".to_string()));
+    }
+
+    #[test]
+    fn invalid_hex_is_not_read_as_base64() {
+        assert_eq!(BinaryValue::to_hex("0xzz"), None);
+        assert_eq!(BinaryValue::to_hex("0xabc"), None);
+        assert_eq!(BinaryValue::to_hex("\\xg0"), None);
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(
+            BinaryValue::to_hex("base64:AAAA"),
+            Some("000000".to_string())
+        );
+        assert_eq!(
+            BinaryValue::to_hex("base64:3q2+7w=="),
+            Some("deadbeef".to_string())
+        );
+        assert_eq!(
+            BinaryValue::to_hex("base64:3q2-7w"),
+            Some("deadbeef".to_string())
+        );
+        assert_eq!(
+            BinaryValue::to_hex("base64:3q2_7w"),
+            Some("deadbfef".to_string())
+        );
+        assert_eq!(BinaryValue::to_hex("base64:A"), None);
+        assert_eq!(BinaryValue::to_hex("base64:"), None);
+        assert_eq!(BinaryValue::to_hex("base64:AA*A"), None);
+    }
+
+    #[test]
+    fn encoding_is_required() {
+        assert_eq!(BinaryValue::to_hex("AAAA"), None);
+        assert_eq!(BinaryValue::to_hex("deadbeef"), None);
+        assert_eq!(BinaryValue::to_hex(""), None);
+    }
+}
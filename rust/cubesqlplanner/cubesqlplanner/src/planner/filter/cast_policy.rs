@@ -0,0 +1,85 @@
+use super::filter_operator::FilterOperator;
+use cubenativeutils::CubeError;
+use std::str::FromStr;
+
+// How filter values are compared with members of other types. Values are bound as text,
+// so numeric, boolean and time members are otherwise cast implicitly by the warehouse,
+// which on some of them casts the column and can't use its index
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum CastPolicy {
+    // Comparison is left to the warehouse as is
+    #[default]
+    Implicit,
+    // Values that aren't of the member type are rejected at plan time
+    Strict,
+    // Member is cast to a string and compared with the values as text. Only equality and
+    // IN are cast: text ordering differs from numeric and time ordering, so range
+    // comparisons are left to the warehouse
+    CastMember,
+    // Values are cast to the member type
+    CastValue,
+}
+
+impl FromStr for CastPolicy {
+    type Err = CubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "implicit" => Ok(Self::Implicit),
+            "strict" => Ok(Self::Strict),
+            "castmember" | "cast_member" => Ok(Self::CastMember),
+            "castvalue" | "cast_value" => Ok(Self::CastValue),
+            _ => Err(CubeError::user(format!("Unknown cast policy {}", s))),
+        }
+    }
+}
+
+impl CastPolicy {
+    pub fn casts_member(&self, operator: &FilterOperator) -> bool {
+        self == &Self::CastMember
+            && matches!(
+                operator,
+                FilterOperator::Equal
+                    | FilterOperator::NotEqual
+                    | FilterOperator::In
+                    | FilterOperator::NotIn
+            )
+    }
+
+    pub fn try_from_name(name: &Option<String>) -> Result<Self, CubeError> {
+        if let Some(name) = name {
+            Self::from_str(name)
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cast_member_only_casts_equality() {
+        let policy = CastPolicy::CastMember;
+        for operator in [
+            FilterOperator::Equal,
+            FilterOperator::NotEqual,
+            FilterOperator::In,
+            FilterOperator::NotIn,
+        ] {
+            assert!(policy.casts_member(&operator));
+        }
+        for operator in [
+            FilterOperator::Gt,
+            FilterOperator::Gte,
+            FilterOperator::Lt,
+            FilterOperator::Lte,
+            FilterOperator::Between,
+            FilterOperator::NotBetween,
+        ] {
+            assert!(!policy.casts_member(&operator));
+        }
+        assert!(!CastPolicy::CastValue.casts_member(&FilterOperator::Equal));
+    }
+}
@@ -1,14 +1,24 @@
 use super::base_filter::{BaseFilter, FilterType};
+use super::base_segment::BaseSegment;
 use super::FilterOperator;
 use crate::cube_bridge::base_query_options::FilterItem as NativeFilterItem;
 use crate::plan::filter::{FilterGroup, FilterGroupOperator, FilterItem};
 use crate::planner::query_tools::QueryTools;
-use crate::planner::sql_evaluator::Compiler;
+use crate::planner::sql_evaluator::{Compiler, MemberSymbol};
 use crate::planner::BaseTimeDimension;
 use cubenativeutils::CubeError;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value as JsonValue;
 use std::rc::Rc;
 use std::str::FromStr;
 
+lazy_static! {
+    static ref TEMPLATE_VARIABLE_RE: Regex =
+        Regex::new(r"^\{\{\s*([_a-zA-Z][_a-zA-Z0-9]*(?:\.[_a-zA-Z][_a-zA-Z0-9]*)*)\s*\}\}$")
+            .unwrap();
+}
+
 pub struct FilterCompiler<'a> {
     evaluator_compiler: &'a mut Compiler,
     query_tools: Rc<QueryTools>,
@@ -39,6 +49,18 @@ impl<'a> FilterCompiler<'a> {
         Ok(())
     }
 
+    pub fn add_segment(&mut self, segment: &String) -> Result<(), CubeError> {
+        let evaluator = self
+            .evaluator_compiler
+            .add_segment_evaluator(segment.clone())?;
+        self.dimension_filters
+            .push(FilterItem::Segment(BaseSegment::try_new(
+                self.query_tools.clone(),
+                evaluator,
+            )?));
+        Ok(())
+    }
+
     pub fn add_time_dimension_item(&mut self, item: &BaseTimeDimension) -> Result<(), CubeError> {
         if let Some(date_range) = item.get_date_range() {
             let filter = BaseFilter::try_new(
@@ -47,12 +69,49 @@ impl<'a> FilterCompiler<'a> {
                 FilterType::Dimension,
                 FilterOperator::InDateRange,
                 Some(date_range.into_iter().map(|v| Some(v)).collect()),
+                None,
+                false,
+                false,
             )?;
             self.time_dimension_filters.push(FilterItem::Item(filter));
         }
         Ok(())
     }
 
+    // Calendar mode of a time dimension is applied as a semi-join filter
+    // against the calendar table of its cube
+    pub fn add_time_dimension_calendar_item(
+        &mut self,
+        dimension: &String,
+        calendar_mode: &String,
+    ) -> Result<(), CubeError> {
+        let filter_operator = FilterOperator::from_str(calendar_mode)?;
+        if !matches!(
+            filter_operator,
+            FilterOperator::BusinessDays | FilterOperator::ExcludeHolidays
+        ) {
+            return Err(CubeError::user(format!(
+                "Unknown calendar mode '{}' for time dimension {}",
+                calendar_mode, dimension
+            )));
+        }
+        let evaluator = self
+            .evaluator_compiler
+            .add_dimension_evaluator(dimension.clone())?;
+        let filter = BaseFilter::try_new(
+            self.query_tools.clone(),
+            evaluator,
+            FilterType::Dimension,
+            filter_operator,
+            None,
+            None,
+            false,
+            false,
+        )?;
+        self.time_dimension_filters.push(FilterItem::Item(filter));
+        Ok(())
+    }
+
     pub fn extract_result(self) -> (Vec<FilterItem>, Vec<FilterItem>, Vec<FilterItem>) {
         (
             self.dimension_filters,
@@ -66,6 +125,12 @@ impl<'a> FilterCompiler<'a> {
         item: &NativeFilterItem,
         item_type: &FilterType,
     ) -> Result<FilterItem, CubeError> {
+        if item.or.is_some() && item.and.is_some() {
+            return Err(CubeError::user(
+                "Filter can't have both 'or' and 'and' branches, nest one group into another"
+                    .to_string(),
+            ));
+        }
         let group_op_and_values = if let Some(items) = &item.or {
             Some((FilterGroupOperator::Or, items))
         } else if let Some(items) = &item.and {
@@ -90,12 +155,16 @@ impl<'a> FilterCompiler<'a> {
                         .evaluator_compiler
                         .add_measure_evaluator(member.clone())?,
                 };
+                let values = self.resolve_template_variables(&evaluator, &item.values)?;
                 Ok(FilterItem::Item(BaseFilter::try_new(
                     self.query_tools.clone(),
                     evaluator,
                     item_type.clone(),
                     FilterOperator::from_str(&operator)?,
-                    item.values.clone(),
+                    values,
+                    item.placeholder.clone(),
+                    item.trim.unwrap_or(false),
+                    item.case_sensitive.unwrap_or(false),
                 )?))
             } else {
                 Err(CubeError::user(format!(
@@ -105,6 +174,92 @@ impl<'a> FilterCompiler<'a> {
         }
     }
 
+    // Values like `{{ user.region }}` are resolved from the security context.
+    // Array variables are expanded into multiple filter values.
+    fn resolve_template_variables(
+        &self,
+        member: &Rc<MemberSymbol>,
+        values: &Option<Vec<Option<String>>>,
+    ) -> Result<Option<Vec<Option<String>>>, CubeError> {
+        let Some(values) = values else {
+            return Ok(None);
+        };
+        let mut result = Vec::with_capacity(values.len());
+        for value in values {
+            let Some(caps) = value
+                .as_ref()
+                .and_then(|v| TEMPLATE_VARIABLE_RE.captures(v))
+            else {
+                result.push(value.clone());
+                continue;
+            };
+            let path = &caps[1];
+            match self.lookup_template_variable(path)? {
+                JsonValue::Array(items) => {
+                    for item in items {
+                        result.push(Some(self.template_value_to_string(member, path, item)?));
+                    }
+                }
+                item => result.push(Some(self.template_value_to_string(member, path, item)?)),
+            }
+        }
+        Ok(Some(result))
+    }
+
+    fn lookup_template_variable(&self, path: &str) -> Result<&JsonValue, CubeError> {
+        let mut parts = path.split('.');
+        match parts.next() {
+            Some("user") | Some("securityContext") => {}
+            _ => {
+                return Err(CubeError::user(format!(
+                    "Unknown template variable '{}': only 'user' and 'securityContext' variables are supported",
+                    path
+                )))
+            }
+        }
+        let mut current = self.query_tools.security_context().as_ref();
+        for part in parts {
+            current = current.and_then(|v| v.get(part));
+        }
+        match current {
+            Some(JsonValue::Null) | None => Err(CubeError::user(format!(
+                "Template variable '{}' is not defined in security context",
+                path
+            ))),
+            Some(value) => Ok(value),
+        }
+    }
+
+    fn template_value_to_string(
+        &self,
+        member: &Rc<MemberSymbol>,
+        path: &str,
+        value: &JsonValue,
+    ) -> Result<String, CubeError> {
+        let member_type = match member.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.dimension_type(),
+            _ => "number",
+        };
+        let result = match (member_type, value) {
+            ("number", JsonValue::Number(n)) => Some(n.to_string()),
+            ("number", JsonValue::String(s)) if s.parse::<f64>().is_ok() => Some(s.clone()),
+            ("boolean", JsonValue::Bool(b)) => Some(b.to_string()),
+            ("string" | "time" | "binary" | "uuid", JsonValue::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        result.ok_or_else(|| {
+            CubeError::user(format!(
+                "Template variable '{}' has value {} which is not compatible with {} member '{}'",
+                path,
+                self.query_tools
+                    .value_redaction_policy()
+                    .display_value(member, &value.to_string()),
+                member_type,
+                member.full_name()
+            ))
+        })
+    }
+
     fn get_item_type(
         &self,
         item: &NativeFilterItem,
@@ -151,3 +306,79 @@ impl<'a> FilterCompiler<'a> {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::test_fixtures::{dimension, dimension_definition, QueryToolsFixture};
+    use serde_json::json;
+
+    fn resolve(
+        query_tools: &Rc<QueryTools>,
+        member_type: &str,
+        values: Vec<&str>,
+    ) -> Result<Option<Vec<Option<String>>>, CubeError> {
+        let mut evaluator_compiler = Compiler::new(query_tools.cube_evaluator().clone());
+        let compiler = FilterCompiler::new(&mut evaluator_compiler, query_tools.clone());
+        compiler.resolve_template_variables(
+            &dimension("orders.field", dimension_definition(member_type)),
+            &Some(values.into_iter().map(|v| Some(v.to_string())).collect()),
+        )
+    }
+
+    fn values(values: Vec<&str>) -> Option<Vec<Option<String>>> {
+        Some(values.into_iter().map(|v| Some(v.to_string())).collect())
+    }
+
+    #[test]
+    fn template_variables_are_resolved_from_security_context() {
+        let query_tools = QueryToolsFixture::new()
+            .security_context(json!({ "region": "eu", "tenants": ["a", "b"], "org": { "id": 42 } }))
+            .build();
+
+        assert_eq!(
+            resolve(
+                &query_tools,
+                "string",
+                vec!["{{ user.region }}", "us", "{{securityContext.tenants}}"]
+            )
+            .unwrap(),
+            values(vec!["eu", "us", "a", "b"])
+        );
+        assert_eq!(
+            resolve(&query_tools, "number", vec!["{{ user.org.id }}"]).unwrap(),
+            values(vec!["42"])
+        );
+        // Only whole values are templates
+        assert_eq!(
+            resolve(&query_tools, "string", vec!["region {{ user.region }}"]).unwrap(),
+            values(vec!["region {{ user.region }}"])
+        );
+    }
+
+    #[test]
+    fn unresolved_template_variables_are_rejected() {
+        let query_tools = QueryToolsFixture::new()
+            .security_context(json!({ "region": "eu" }))
+            .build();
+
+        assert_eq!(
+            resolve(&query_tools, "string", vec!["{{ user.missing }}"])
+                .unwrap_err()
+                .message,
+            "Template variable 'user.missing' is not defined in security context"
+        );
+        assert_eq!(
+            resolve(&query_tools, "string", vec!["{{ env.region }}"])
+                .unwrap_err()
+                .message,
+            "Unknown template variable 'env.region': only 'user' and 'securityContext' variables are supported"
+        );
+        assert_eq!(
+            resolve(&query_tools, "number", vec!["{{ user.region }}"])
+                .unwrap_err()
+                .message,
+            "Template variable 'user.region' has value \"eu\" which is not compatible with number member 'orders.field'"
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use cubenativeutils::CubeError;
+use std::str::FromStr;
+
+// How `in` lists without non-null values are rendered: `IN ()` is invalid sql
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum EmptyInListPolicy {
+    // Filter is rejected at plan time
+    #[default]
+    Error,
+    // `in` matches no rows and `notIn` matches all of them
+    AlwaysFalse,
+    // Only null values of the list are compared: `in [null]` checks for NULL and
+    // `notIn [null]` for NOT NULL, lists without nulls behave as `AlwaysFalse`
+    NullCheck,
+}
+
+impl FromStr for EmptyInListPolicy {
+    type Err = CubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "alwaysfalse" | "always_false" => Ok(Self::AlwaysFalse),
+            "nullcheck" | "null_check" => Ok(Self::NullCheck),
+            _ => Err(CubeError::user(format!(
+                "Unknown empty in list policy {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl EmptyInListPolicy {
+    pub fn try_from_name(name: &Option<String>) -> Result<Self, CubeError> {
+        if let Some(name) = name {
+            Self::from_str(name)
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use cubenativeutils::CubeError;
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -15,12 +16,203 @@ pub enum FilterOperator {
     Gte,
     Lt,
     Lte,
+    // Inclusive range of two values
+    Between,
+    NotBetween,
     Contains,
     NotContains,
     StartsWith,
     NotStartsWith,
     NotEndsWith,
     EndsWith,
+    // Partial match of a regular expression in the dialect's flavor
+    Regex,
+    NotRegex,
+    BusinessDays,
+    ExcludeHolidays,
+    InBuckets,
+    // Array member contains all of the values
+    ArrayContains,
+    // Array member shares an element with the values, `arrayContainsAny` is an alias
+    ArrayOverlaps,
+}
+
+impl FilterOperator {
+    pub fn all() -> Vec<FilterOperator> {
+        vec![
+            Self::Equal,
+            Self::NotEqual,
+            Self::InDateRange,
+            Self::InDateRangeExtended,
+            Self::In,
+            Self::NotIn,
+            Self::Set,
+            Self::NotSet,
+            Self::Gt,
+            Self::Gte,
+            Self::Lt,
+            Self::Lte,
+            Self::Between,
+            Self::NotBetween,
+            Self::Contains,
+            Self::NotContains,
+            Self::StartsWith,
+            Self::NotStartsWith,
+            Self::NotEndsWith,
+            Self::EndsWith,
+            Self::Regex,
+            Self::NotRegex,
+            Self::BusinessDays,
+            Self::ExcludeHolidays,
+            Self::InBuckets,
+            Self::ArrayContains,
+            Self::ArrayOverlaps,
+        ]
+    }
+
+    // Member types are the ones of dimensions: string, number, time, boolean, geo, binary and uuid.
+    // Unknown types are accepted as is
+    pub fn is_applicable_to(&self, member_type: &str) -> bool {
+        match self {
+            Self::Contains
+            | Self::NotContains
+            | Self::StartsWith
+            | Self::NotStartsWith
+            | Self::EndsWith
+            | Self::NotEndsWith
+            | Self::Regex
+            | Self::NotRegex => !matches!(
+                member_type,
+                "number" | "time" | "boolean" | "geo" | "binary" | "uuid"
+            ),
+            Self::InDateRange
+            | Self::InDateRangeExtended
+            | Self::BusinessDays
+            | Self::ExcludeHolidays
+            | Self::InBuckets => !matches!(
+                member_type,
+                "string" | "number" | "boolean" | "geo" | "binary" | "uuid"
+            ),
+            Self::Gt | Self::Gte | Self::Lt | Self::Lte | Self::Between | Self::NotBetween => {
+                !matches!(member_type, "boolean" | "geo" | "binary" | "uuid")
+            }
+            Self::Equal
+            | Self::NotEqual
+            | Self::In
+            | Self::NotIn
+            | Self::Set
+            | Self::NotSet
+            | Self::ArrayContains
+            | Self::ArrayOverlaps => true,
+        }
+    }
+
+    // Number of values the operator expects as (min, max), unbounded when max is None.
+    // Values of operators without arity are ignored
+    pub fn values_arity(&self) -> Option<(usize, Option<usize>)> {
+        match self {
+            Self::Set | Self::NotSet | Self::BusinessDays | Self::ExcludeHolidays => None,
+            Self::Gt | Self::Gte | Self::Lt | Self::Lte => Some((1, Some(1))),
+            Self::Between | Self::NotBetween => Some((2, Some(2))),
+            // A relative range or two bounds
+            Self::InDateRange => Some((1, Some(2))),
+            // Date range values followed by the intervals of the bounds: a relative range
+            // and two intervals or two bounds and two intervals
+            Self::InDateRangeExtended => Some((3, Some(4))),
+            Self::Equal
+            | Self::NotEqual
+            | Self::In
+            | Self::NotIn
+            | Self::Contains
+            | Self::NotContains
+            | Self::StartsWith
+            | Self::NotStartsWith
+            | Self::EndsWith
+            | Self::NotEndsWith
+            | Self::Regex
+            | Self::NotRegex
+            | Self::InBuckets
+            | Self::ArrayContains
+            | Self::ArrayOverlaps => Some((1, None)),
+        }
+    }
+
+    // Description of the arity mismatch, e.g. "expects 2 values, got 1", None if values fit.
+    // Lists of in and notIn without non-null values are left to the empty in list policy
+    pub fn values_arity_error(&self, values: &[Option<String>]) -> Option<String> {
+        if matches!(self, Self::In | Self::NotIn) {
+            return None;
+        }
+        let (min, max) = self.values_arity()?;
+        let (count, kind) = if self.is_null_values_skipped() {
+            (
+                values.iter().filter(|v| v.is_some()).count(),
+                "non-null value",
+            )
+        } else {
+            (values.len(), "value")
+        };
+        if count >= min && max.map_or(true, |max| count <= max) {
+            return None;
+        }
+        let plural = if max == Some(1) || (max.is_none() && min == 1) {
+            ""
+        } else {
+            "s"
+        };
+        let expected = match max {
+            Some(max) if max == min => format!("{} {}{}", min, kind, plural),
+            Some(max) => format!("{} to {} {}{}", min, max, kind, plural),
+            None => format!("at least {} {}{}", min, kind, plural),
+        };
+        Some(format!("expects {}, got {}", expected, count))
+    }
+
+    // Null values are skipped by these operators, so only non-null ones count for the arity
+    pub fn is_null_values_skipped(&self) -> bool {
+        matches!(
+            self,
+            Self::In
+                | Self::NotIn
+                | Self::Contains
+                | Self::NotContains
+                | Self::StartsWith
+                | Self::NotStartsWith
+                | Self::EndsWith
+                | Self::NotEndsWith
+                | Self::Regex
+                | Self::NotRegex
+                | Self::InBuckets
+                | Self::ArrayContains
+                | Self::ArrayOverlaps
+        )
+    }
+
+    pub fn is_array_operator(&self) -> bool {
+        matches!(self, Self::ArrayContains | Self::ArrayOverlaps)
+    }
+
+    pub fn validate_for_member(
+        &self,
+        member_name: &str,
+        member_type: &str,
+    ) -> Result<(), CubeError> {
+        if self.is_applicable_to(member_type) {
+            return Ok(());
+        }
+        let applicable = Self::all()
+            .into_iter()
+            .filter(|op| op.is_applicable_to(member_type) && *op != Self::InDateRangeExtended)
+            .map(|op| op.to_string())
+            .collect::<Vec<_>>();
+        Err(CubeError::user(format!(
+            "Operator '{}' can't be applied to {} member '{}'. Supported operators: {}",
+            self,
+            member_type,
+            member_name,
+            applicable.join(", ")
+        )))
+    }
 }
 
 impl FromStr for FilterOperator {
@@ -38,14 +230,164 @@ impl FromStr for FilterOperator {
             "gte" => Ok(Self::Gte),
             "lt" => Ok(Self::Lt),
             "lte" => Ok(Self::Lte),
+            "between" => Ok(Self::Between),
+            "notbetween" => Ok(Self::NotBetween),
             "contains" => Ok(Self::Contains),
             "notcontains" => Ok(Self::NotContains),
             "startswith" => Ok(Self::StartsWith),
             "notstartswith" => Ok(Self::NotStartsWith),
             "endswith" => Ok(Self::EndsWith),
             "notendswith" => Ok(Self::NotEndsWith),
+            "regex" => Ok(Self::Regex),
+            "notregex" => Ok(Self::NotRegex),
+            "businessdays" => Ok(Self::BusinessDays),
+            "excludeholidays" => Ok(Self::ExcludeHolidays),
+            "inbuckets" => Ok(Self::InBuckets),
+            "arraycontains" => Ok(Self::ArrayContains),
+            "arrayoverlaps" | "arraycontainsany" => Ok(Self::ArrayOverlaps),
 
             _ => Err(CubeError::user(format!("Unknown filter operator {}", s))),
         }
     }
 }
+
+impl fmt::Display for FilterOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Equal => "equals",
+            Self::NotEqual => "notEquals",
+            Self::InDateRange => "inDateRange",
+            Self::InDateRangeExtended => "inDateRangeExtended",
+            Self::In => "in",
+            Self::NotIn => "notIn",
+            Self::Set => "set",
+            Self::NotSet => "notSet",
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+            Self::Between => "between",
+            Self::NotBetween => "notBetween",
+            Self::Contains => "contains",
+            Self::NotContains => "notContains",
+            Self::StartsWith => "startsWith",
+            Self::NotStartsWith => "notStartsWith",
+            Self::NotEndsWith => "notEndsWith",
+            Self::EndsWith => "endsWith",
+            Self::Regex => "regex",
+            Self::NotRegex => "notRegex",
+            Self::BusinessDays => "businessDays",
+            Self::ExcludeHolidays => "excludeHolidays",
+            Self::InBuckets => "inBuckets",
+            Self::ArrayContains => "arrayContains",
+            Self::ArrayOverlaps => "arrayOverlaps",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(non_null: usize, nulls: usize) -> Vec<Option<String>> {
+        (0..non_null)
+            .map(|i| Some(i.to_string()))
+            .chain((0..nulls).map(|_| None))
+            .collect()
+    }
+
+    // Accepted numbers of values per operator and whether nulls are left out of the count
+    fn expected_arity(operator: &FilterOperator) -> (Vec<usize>, bool) {
+        let any = (0..=6).collect::<Vec<_>>();
+        let at_least_one = (1..=6).collect::<Vec<_>>();
+        match operator {
+            FilterOperator::Set
+            | FilterOperator::NotSet
+            | FilterOperator::BusinessDays
+            | FilterOperator::ExcludeHolidays
+            | FilterOperator::In
+            | FilterOperator::NotIn => (any, false),
+            FilterOperator::Gt | FilterOperator::Gte | FilterOperator::Lt | FilterOperator::Lte => {
+                (vec![1], false)
+            }
+            FilterOperator::Between | FilterOperator::NotBetween => (vec![2], false),
+            FilterOperator::InDateRange => (vec![1, 2], false),
+            FilterOperator::InDateRangeExtended => (vec![3, 4], false),
+            FilterOperator::Equal | FilterOperator::NotEqual => (at_least_one, false),
+            FilterOperator::Contains
+            | FilterOperator::NotContains
+            | FilterOperator::StartsWith
+            | FilterOperator::NotStartsWith
+            | FilterOperator::EndsWith
+            | FilterOperator::NotEndsWith
+            | FilterOperator::Regex
+            | FilterOperator::NotRegex
+            | FilterOperator::InBuckets
+            | FilterOperator::ArrayContains
+            | FilterOperator::ArrayOverlaps => (at_least_one, true),
+        }
+    }
+
+    #[test]
+    fn values_arity_matrix() {
+        for operator in FilterOperator::all() {
+            let (accepted, skips_nulls) = expected_arity(&operator);
+            for non_null in 0..=5 {
+                for nulls in 0..=1 {
+                    let count = if skips_nulls {
+                        non_null
+                    } else {
+                        non_null + nulls
+                    };
+                    let error = operator.values_arity_error(&values(non_null, nulls));
+                    assert_eq!(
+                        error.is_none(),
+                        accepted.contains(&count),
+                        "{} with {} values and {} nulls: {:?}",
+                        operator,
+                        non_null,
+                        nulls,
+                        error
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn array_contains_any_is_overlaps_alias() {
+        assert_eq!(
+            FilterOperator::from_str("arrayContainsAny").unwrap(),
+            FilterOperator::ArrayOverlaps
+        );
+        assert_eq!(
+            FilterOperator::from_str("arrayOverlaps").unwrap(),
+            FilterOperator::ArrayOverlaps
+        );
+    }
+
+    #[test]
+    fn values_arity_error_messages() {
+        assert_eq!(
+            FilterOperator::Between.values_arity_error(&values(1, 0)),
+            Some("expects 2 values, got 1".to_string())
+        );
+        assert_eq!(
+            FilterOperator::Gt.values_arity_error(&values(2, 0)),
+            Some("expects 1 value, got 2".to_string())
+        );
+        assert_eq!(
+            FilterOperator::InDateRange.values_arity_error(&values(0, 0)),
+            Some("expects 1 to 2 values, got 0".to_string())
+        );
+        assert_eq!(
+            FilterOperator::Contains.values_arity_error(&values(0, 2)),
+            Some("expects at least 1 non-null value, got 0".to_string())
+        );
+        assert_eq!(
+            FilterOperator::Equal.values_arity_error(&values(0, 0)),
+            Some("expects at least 1 value, got 0".to_string())
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use super::base_filter::BaseFilter;
+use crate::plan::filter::{FilterGroupOperator, FilterItem};
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_templates::filter::FilterTemplates;
+use cubenativeutils::CubeError;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+// `FILTER_PARAMS.cube.member.filter(column)` and `FILTER_GROUP(...)` are rendered by JS as
+// markers with hex encoded columns. Function columns are called with value markers
+lazy_static! {
+    static ref FILTER_PARAMS_RE: Regex =
+        Regex::new(r"\$FILTER_PARAMS:([^:$]+):(column|fn):([0-9a-f]*)\$").unwrap();
+    static ref FILTER_GROUP_RE: Regex = Regex::new(r"\$FILTER_GROUP:([0-9a-f]*)\$").unwrap();
+    static ref FILTER_PARAM_VALUE_RE: Regex = Regex::new(r"\$FILTER_PARAM_VALUE:(\d+)\$").unwrap();
+}
+
+enum FilterParamsColumn {
+    Column(String),
+    Function(String),
+}
+
+struct FilterParamsArg {
+    member: String,
+    column: FilterParamsColumn,
+}
+
+// Filter rendered for a `FILTER_PARAMS` arg instead of its member
+trait FilterParamsCondition {
+    fn member_name(&self) -> String;
+    fn to_sql_for_column(&self, column: String) -> Result<String, CubeError>;
+    fn filter_params(&self) -> Result<Vec<String>, CubeError>;
+}
+
+impl FilterParamsCondition for BaseFilter {
+    fn member_name(&self) -> String {
+        BaseFilter::member_name(self)
+    }
+
+    fn to_sql_for_column(&self, column: String) -> Result<String, CubeError> {
+        BaseFilter::to_sql_for_column(self, column)
+    }
+
+    fn filter_params(&self) -> Result<Vec<String>, CubeError> {
+        BaseFilter::filter_params(self)
+    }
+}
+
+enum FilterParamsItem {
+    Group(FilterGroupOperator, Vec<FilterParamsItem>),
+    Filter(Rc<dyn FilterParamsCondition>),
+    Segment(String),
+}
+
+impl FilterParamsItem {
+    fn from_filter_item(item: &FilterItem) -> Self {
+        match item {
+            FilterItem::Group(group) => Self::Group(
+                group.operator.clone(),
+                group.items.iter().map(Self::from_filter_item).collect(),
+            ),
+            FilterItem::Item(filter) => Self::Filter(filter.clone()),
+            FilterItem::Segment(segment) => Self::Segment(segment.member_evaluator().full_name()),
+        }
+    }
+
+    fn all_members(&self) -> Vec<String> {
+        match self {
+            Self::Group(_, items) => items.iter().flat_map(|item| item.all_members()).collect(),
+            Self::Filter(filter) => vec![filter.member_name()],
+            Self::Segment(member) => vec![member.clone()],
+        }
+    }
+}
+
+// Replaces `FILTER_PARAMS` and `FILTER_GROUP` markers of a cube sql with conditions of
+// the query filters on the referenced members, or with a tautology if there are none
+pub struct FilterParamsRenderer {
+    filters: Vec<FilterParamsItem>,
+    templates: FilterTemplates,
+}
+
+impl FilterParamsRenderer {
+    pub fn new(query_tools: Rc<QueryTools>) -> Self {
+        Self {
+            filters: query_tools
+                .query_filters()
+                .iter()
+                .map(FilterParamsItem::from_filter_item)
+                .collect(),
+            templates: FilterTemplates::new(query_tools.templates_render()),
+        }
+    }
+
+    pub fn render(&self, sql: &str) -> Result<String, CubeError> {
+        let sql = Self::replace_all(&FILTER_GROUP_RE, sql, |caps| {
+            let args = Self::parse_args(&Self::decode_hex(&caps[1])?)?;
+            self.render_group(&args)
+        })?;
+        Self::replace_all(&FILTER_PARAMS_RE, &sql, |caps| {
+            let arg = Self::parse_arg(caps)?;
+            self.render_group(&[arg])
+        })
+    }
+
+    fn render_group(&self, args: &[FilterParamsArg]) -> Result<String, CubeError> {
+        let members = args
+            .iter()
+            .map(|a| a.member.clone())
+            .collect::<HashSet<_>>();
+        let items = Self::and_sub_tree(&self.filters, &members);
+        let conditions = items
+            .into_iter()
+            .map(|item| self.render_item(item, args))
+            .collect::<Result<Vec<_>, _>>()?;
+        if conditions.is_empty() {
+            return Ok(format!("({})", self.templates.always_true()?));
+        }
+        Ok(format!("({})", conditions.join(" AND ")))
+    }
+
+    // Filters of the query which reference only the given members. `and` groups are
+    // searched for matching items, `or` groups are taken only as a whole
+    fn and_sub_tree<'a>(
+        items: &'a [FilterParamsItem],
+        members: &HashSet<String>,
+    ) -> Vec<&'a FilterParamsItem> {
+        let mut result = vec![];
+        for item in items {
+            match item {
+                FilterParamsItem::Group(FilterGroupOperator::And, items) => {
+                    result.extend(Self::and_sub_tree(items, members));
+                }
+                FilterParamsItem::Segment(_) => {}
+                _ => {
+                    if item.all_members().iter().all(|m| members.contains(m)) {
+                        result.push(item);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn render_item(
+        &self,
+        item: &FilterParamsItem,
+        args: &[FilterParamsArg],
+    ) -> Result<String, CubeError> {
+        match item {
+            FilterParamsItem::Group(operator, items) => {
+                let items = items
+                    .iter()
+                    .map(|itm| self.render_item(itm, args))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(format!(
+                    "({})",
+                    items.join(&format!(" {} ", operator.to_string()))
+                ))
+            }
+            FilterParamsItem::Filter(filter) => {
+                let member = filter.member_name();
+                let Some(arg) = args.iter().find(|a| a.member == member) else {
+                    return Err(CubeError::user(format!(
+                        "FILTER_PARAMS arg not found for {}",
+                        member
+                    )));
+                };
+                match &arg.column {
+                    FilterParamsColumn::Column(column) => filter.to_sql_for_column(column.clone()),
+                    FilterParamsColumn::Function(sql) => {
+                        let params = filter.filter_params()?;
+                        if params.is_empty() {
+                            return self.templates.always_true();
+                        }
+                        Self::replace_all(&FILTER_PARAM_VALUE_RE, sql, |caps| {
+                            let index = caps[1].parse::<usize>().unwrap_or(usize::MAX);
+                            params.get(index).cloned().ok_or_else(|| {
+                                CubeError::user(format!(
+                                    "FILTER_PARAMS function for {} expects more values than its filter has",
+                                    member
+                                ))
+                            })
+                        })
+                    }
+                }
+            }
+            FilterParamsItem::Segment(_) => self.templates.always_true(),
+        }
+    }
+
+    fn parse_args(sql: &str) -> Result<Vec<FilterParamsArg>, CubeError> {
+        FILTER_PARAMS_RE
+            .captures_iter(sql)
+            .map(|caps| Self::parse_arg(&caps))
+            .collect()
+    }
+
+    fn parse_arg(caps: &Captures) -> Result<FilterParamsArg, CubeError> {
+        let sql = Self::decode_hex(&caps[3])?;
+        let column = if &caps[2] == "fn" {
+            FilterParamsColumn::Function(sql)
+        } else {
+            FilterParamsColumn::Column(sql)
+        };
+        Ok(FilterParamsArg {
+            member: caps[1].to_string(),
+            column,
+        })
+    }
+
+    fn decode_hex(value: &str) -> Result<String, CubeError> {
+        let bytes = (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(value.get(i..i + 2).unwrap_or(""), 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| CubeError::internal(format!("Invalid FILTER_PARAMS marker {}", value)))?;
+        String::from_utf8(bytes)
+            .map_err(|_| CubeError::internal(format!("Invalid FILTER_PARAMS marker {}", value)))
+    }
+
+    fn replace_all(
+        re: &Regex,
+        sql: &str,
+        mut replacement: impl FnMut(&Captures) -> Result<String, CubeError>,
+    ) -> Result<String, CubeError> {
+        let mut result = String::with_capacity(sql.len());
+        let mut last = 0;
+        for caps in re.captures_iter(sql) {
+            let m = caps.get(0).unwrap();
+            result.push_str(&sql[last..m.start()]);
+            result.push_str(&replacement(&caps)?);
+            last = m.end();
+        }
+        result.push_str(&sql[last..]);
+        Ok(result)
+    }
+
+    pub fn has_markers(sql: &str) -> bool {
+        FILTER_PARAMS_RE.is_match(sql) || FILTER_GROUP_RE.is_match(sql)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+
+    struct MockCondition {
+        member: String,
+        values: Vec<String>,
+    }
+
+    impl FilterParamsCondition for MockCondition {
+        fn member_name(&self) -> String {
+            self.member.clone()
+        }
+
+        fn to_sql_for_column(&self, column: String) -> Result<String, CubeError> {
+            Ok(format!("{} IN ({})", column, self.values.join(", ")))
+        }
+
+        fn filter_params(&self) -> Result<Vec<String>, CubeError> {
+            Ok(self.values.clone())
+        }
+    }
+
+    fn filter(member: &str, values: &[&str]) -> FilterParamsItem {
+        FilterParamsItem::Filter(Rc::new(MockCondition {
+            member: member.to_string(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+        }))
+    }
+
+    fn renderer(filters: Vec<FilterParamsItem>) -> FilterParamsRenderer {
+        FilterParamsRenderer {
+            filters,
+            templates: FilterTemplates::new(Rc::new(
+                MockSqlTemplatesRender::try_new(vec![("filters/always_true", "1 = 1")]).unwrap(),
+            )),
+        }
+    }
+
+    fn hex(value: &str) -> String {
+        value.bytes().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn column_marker(member: &str, column: &str) -> String {
+        format!("$FILTER_PARAMS:{}:column:{}$", member, hex(column))
+    }
+
+    #[test]
+    fn column_arg_renders_filter_on_column() {
+        let renderer = renderer(vec![filter("orders.status", &["$1"])]);
+        let sql = format!(
+            "SELECT * FROM orders WHERE {}",
+            column_marker("orders.status", "status")
+        );
+        assert_eq!(
+            renderer.render(&sql).unwrap(),
+            "SELECT * FROM orders WHERE (status IN ($1))"
+        );
+    }
+
+    #[test]
+    fn function_arg_receives_filter_values() {
+        let renderer = renderer(vec![filter("orders.created_at", &["$1", "$2"])]);
+        let function = "created_at BETWEEN $FILTER_PARAM_VALUE:0$ AND $FILTER_PARAM_VALUE:1$";
+        let sql = format!("$FILTER_PARAMS:orders.created_at:fn:{}$", hex(function));
+        assert_eq!(
+            renderer.render(&sql).unwrap(),
+            "(created_at BETWEEN $1 AND $2)"
+        );
+
+        let function = "created_at > $FILTER_PARAM_VALUE:2$";
+        let sql = format!("$FILTER_PARAMS:orders.created_at:fn:{}$", hex(function));
+        assert!(renderer.render(&sql).is_err());
+    }
+
+    #[test]
+    fn function_arg_without_values_is_a_tautology() {
+        let renderer = renderer(vec![filter("orders.created_at", &[])]);
+        let function = "created_at > $FILTER_PARAM_VALUE:0$";
+        let sql = format!("$FILTER_PARAMS:orders.created_at:fn:{}$", hex(function));
+        assert_eq!(renderer.render(&sql).unwrap(), "(1 = 1)");
+    }
+
+    #[test]
+    fn no_filter_is_a_tautology() {
+        let renderer = renderer(vec![filter("orders.status", &["$1"])]);
+        let sql = column_marker("orders.amount", "amount");
+        assert_eq!(renderer.render(&sql).unwrap(), "(1 = 1)");
+        assert!(FilterParamsRenderer::has_markers(&sql));
+        assert!(!FilterParamsRenderer::has_markers("amount > 0"));
+    }
+
+    #[test]
+    fn filter_group_keeps_or_groups_of_its_members_only() {
+        let renderer = renderer(vec![
+            FilterParamsItem::Group(
+                FilterGroupOperator::Or,
+                vec![
+                    filter("orders.status", &["$1"]),
+                    filter("orders.city", &["$2"]),
+                ],
+            ),
+            FilterParamsItem::Group(
+                FilterGroupOperator::Or,
+                vec![
+                    filter("orders.status", &["$3"]),
+                    filter("orders.amount", &["$4"]),
+                ],
+            ),
+            FilterParamsItem::Group(
+                FilterGroupOperator::And,
+                vec![filter("orders.city", &["$5"])],
+            ),
+        ]);
+        let markers = format!(
+            "{}{}",
+            column_marker("orders.status", "status"),
+            column_marker("orders.city", "city")
+        );
+        let sql = format!("$FILTER_GROUP:{}$", hex(&markers));
+        assert_eq!(
+            renderer.render(&sql).unwrap(),
+            "((status IN ($1) OR city IN ($2)) AND city IN ($5))"
+        );
+    }
+
+    #[test]
+    fn or_group_with_other_members_is_left_out() {
+        let renderer = renderer(vec![FilterParamsItem::Group(
+            FilterGroupOperator::Or,
+            vec![
+                filter("orders.status", &["$1"]),
+                filter("orders.amount", &["$2"]),
+            ],
+        )]);
+        let sql = column_marker("orders.status", "status");
+        assert_eq!(renderer.render(&sql).unwrap(), "(1 = 1)");
+    }
+}
@@ -1,6 +1,24 @@
 pub mod base_filter;
+pub mod base_segment;
+pub mod binary_value;
+pub mod cast_policy;
 pub mod compiler;
+pub mod empty_in_list_policy;
 pub mod filter_operator;
+pub mod filter_params;
+pub mod range_coalescing;
+pub mod relative_date_range;
+pub mod value_redaction;
+pub mod value_suggestions;
 
 pub use base_filter::BaseFilter;
+pub use base_segment::BaseSegment;
+pub use binary_value::BinaryValue;
+pub use cast_policy::CastPolicy;
+pub use empty_in_list_policy::EmptyInListPolicy;
 pub use filter_operator::FilterOperator;
+pub use filter_params::FilterParamsRenderer;
+pub use range_coalescing::RangeCoalescer;
+pub use relative_date_range::RelativeDateRange;
+pub use value_redaction::{ValueRedactionMode, ValueRedactionPolicy};
+pub use value_suggestions::InvalidFilterValue;
@@ -0,0 +1,582 @@
+use super::base_filter::BaseFilter;
+use super::filter_operator::FilterOperator;
+use crate::plan::filter::FilterGroupOperator;
+use crate::plan::{FilterGroup, FilterItem};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+// Exact value of a decimal literal: digits without leading and trailing zeros scaled by a
+// power of ten, so `0.1` equals `1e-1` and `9007199254740993` isn't rounded to its neighbour
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Decimal {
+    negative: bool,
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+impl Decimal {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (negative, unsigned) = match value.as_bytes().first()? {
+            b'-' => (true, &value[1..]),
+            b'+' => (false, &value[1..]),
+            _ => (false, value),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(i) => (&unsigned[..i], unsigned[i + 1..].parse::<i64>().ok()?),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        if !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let mut digits = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .map(|b| b - b'0')
+            .skip_while(|d| *d == 0)
+            .collect::<Vec<_>>();
+        let mut exponent = exponent.checked_sub(i64::try_from(fraction.len()).ok()?)?;
+        while digits.last() == Some(&0) {
+            digits.pop();
+            exponent = exponent.checked_add(1)?;
+        }
+        if digits.is_empty() {
+            return Some(Self {
+                negative: false,
+                digits,
+                exponent: 0,
+            });
+        }
+        Some(Self {
+            negative,
+            digits,
+            exponent,
+        })
+    }
+
+    fn cmp_abs(&self, other: &Self) -> Ordering {
+        match (self.digits.is_empty(), other.digits.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            // Position of the most significant digit first, then the digits from it
+            (false, false) => (self.digits.len() as i64 + self.exponent)
+                .cmp(&(other.digits.len() as i64 + other.exponent))
+                .then_with(|| self.digits.cmp(&other.digits)),
+        }
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_abs(other),
+            (true, true) => other.cmp_abs(self),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Clone)]
+struct NumberBound {
+    value: Decimal,
+    raw: String,
+    inclusive: bool,
+}
+
+// Missing bound is an infinite one
+#[derive(Clone)]
+struct NumberRange {
+    from: Option<NumberBound>,
+    to: Option<NumberBound>,
+}
+
+#[derive(Clone)]
+struct DateBound {
+    value: NaiveDateTime,
+    raw: String,
+    is_date: bool,
+}
+
+#[derive(Clone)]
+struct DateRange {
+    from: DateBound,
+    to: DateBound,
+}
+
+enum Range {
+    Number(NumberRange),
+    Date(DateRange),
+}
+
+struct RangeBucket {
+    member_name: String,
+    is_date: bool,
+    position: usize,
+    filter: Rc<BaseFilter>,
+    number_ranges: Vec<NumberRange>,
+    date_ranges: Vec<DateRange>,
+}
+
+impl RangeBucket {
+    fn len(&self) -> usize {
+        self.number_ranges.len() + self.date_ranges.len()
+    }
+}
+
+// Merges overlapping range predicates on the same member inside `or` groups,
+// e.g. `x between 1 and 5 or x between 3 and 10` becomes `x between 1 and 10`.
+// Only absolute values are merged, placeholders and relative date ranges are kept as is
+pub struct RangeCoalescer {}
+
+impl RangeCoalescer {
+    pub fn coalesce(items: &Vec<FilterItem>) -> Vec<FilterItem> {
+        items.iter().map(Self::coalesce_item).collect()
+    }
+
+    fn coalesce_item(item: &FilterItem) -> FilterItem {
+        match item {
+            FilterItem::Group(group) => {
+                let items = Self::coalesce(&group.items);
+                let items = if group.operator == FilterGroupOperator::Or {
+                    Self::coalesce_or_items(items)
+                } else {
+                    items
+                };
+                FilterItem::Group(Rc::new(FilterGroup::new(group.operator.clone(), items)))
+            }
+            _ => item.clone(),
+        }
+    }
+
+    fn coalesce_or_items(items: Vec<FilterItem>) -> Vec<FilterItem> {
+        let mut buckets: Vec<RangeBucket> = Vec::new();
+        let mut coalesced_positions = Vec::new();
+        for (position, item) in items.iter().enumerate() {
+            let FilterItem::Item(filter) = item else {
+                continue;
+            };
+            let Some(range) = Self::range_of(filter) else {
+                continue;
+            };
+            let member_name = filter.member_name();
+            let is_date = matches!(range, Range::Date(_));
+            let bucket = match buckets
+                .iter_mut()
+                .position(|b| b.member_name == member_name && b.is_date == is_date)
+            {
+                Some(index) => &mut buckets[index],
+                None => {
+                    buckets.push(RangeBucket {
+                        member_name,
+                        is_date,
+                        position,
+                        filter: filter.clone(),
+                        number_ranges: Vec::new(),
+                        date_ranges: Vec::new(),
+                    });
+                    buckets.last_mut().unwrap()
+                }
+            };
+            match range {
+                Range::Number(range) => bucket.number_ranges.push(range),
+                Range::Date(range) => bucket.date_ranges.push(range),
+            }
+            coalesced_positions.push((position, bucket.position));
+        }
+
+        let mut replacements = Vec::new();
+        for bucket in buckets.iter() {
+            let merged = if bucket.is_date {
+                Self::merge_date_ranges(bucket.date_ranges.clone())
+                    .into_iter()
+                    .map(|range| Self::date_range_item(&bucket.filter, range))
+                    .collect::<Vec<_>>()
+            } else {
+                Self::merge_number_ranges(bucket.number_ranges.clone())
+                    .into_iter()
+                    .map(|range| Self::number_range_item(&bucket.filter, range))
+                    .collect::<Vec<_>>()
+            };
+            // Predicates are left untouched when nothing overlaps
+            if merged.len() < bucket.len() {
+                replacements.push((bucket.position, merged));
+            }
+        }
+        if replacements.is_empty() {
+            return items;
+        }
+
+        let mut result = Vec::new();
+        for (position, item) in items.into_iter().enumerate() {
+            if let Some((_, merged)) = replacements.iter().find(|(p, _)| *p == position) {
+                result.extend(merged.iter().cloned());
+                continue;
+            }
+            let replaced_bucket = coalesced_positions
+                .iter()
+                .find(|(p, _)| *p == position)
+                .map(|(_, bucket_position)| *bucket_position);
+            if let Some(bucket_position) = replaced_bucket {
+                if replacements.iter().any(|(p, _)| *p == bucket_position) {
+                    continue;
+                }
+            }
+            result.push(item);
+        }
+        result
+    }
+
+    fn range_of(filter: &Rc<BaseFilter>) -> Option<Range> {
+        if filter.placeholder().is_some() {
+            return None;
+        }
+        let values = filter
+            .values()
+            .iter()
+            .cloned()
+            .collect::<Option<Vec<_>>>()?;
+        match filter.filter_operator() {
+            FilterOperator::InDateRange if values.len() == 2 => {
+                let from = Self::parse_date_bound(&values[0], false)?;
+                let to = Self::parse_date_bound(&values[1], true)?;
+                if from.value > to.value {
+                    return None;
+                }
+                Some(Range::Date(DateRange { from, to }))
+            }
+            FilterOperator::Between if values.len() == 2 && filter.is_number_member() => {
+                let from = Self::parse_number_bound(&values[0], true)?;
+                let to = Self::parse_number_bound(&values[1], true)?;
+                if from.value > to.value {
+                    return None;
+                }
+                Some(Range::Number(NumberRange {
+                    from: Some(from),
+                    to: Some(to),
+                }))
+            }
+            op @ (FilterOperator::Gt | FilterOperator::Gte)
+                if values.len() == 1 && filter.is_number_member() =>
+            {
+                let from = Self::parse_number_bound(&values[0], *op == FilterOperator::Gte)?;
+                Some(Range::Number(NumberRange {
+                    from: Some(from),
+                    to: None,
+                }))
+            }
+            op @ (FilterOperator::Lt | FilterOperator::Lte)
+                if values.len() == 1 && filter.is_number_member() =>
+            {
+                let to = Self::parse_number_bound(&values[0], *op == FilterOperator::Lte)?;
+                Some(Range::Number(NumberRange {
+                    from: None,
+                    to: Some(to),
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_number_bound(value: &str, inclusive: bool) -> Option<NumberBound> {
+        Some(NumberBound {
+            value: Decimal::parse(value)?,
+            raw: value.to_string(),
+            inclusive,
+        })
+    }
+
+    // Date only values cover the whole day, so the end of the range is the end of the day
+    fn parse_date_bound(value: &str, is_end: bool) -> Option<DateBound> {
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            let time = if is_end {
+                NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999)?
+            } else {
+                NaiveTime::from_hms_opt(0, 0, 0)?
+            };
+            return Some(DateBound {
+                value: date.and_time(time),
+                raw: value.to_string(),
+                is_date: true,
+            });
+        }
+        let parsed = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+        Some(DateBound {
+            value: parsed,
+            raw: value.to_string(),
+            is_date: false,
+        })
+    }
+
+    fn merge_date_ranges(mut ranges: Vec<DateRange>) -> Vec<DateRange> {
+        ranges.sort_by(|a, b| a.from.value.cmp(&b.from.value));
+        let mut result: Vec<DateRange> = Vec::new();
+        for range in ranges.into_iter() {
+            if let Some(last) = result.last_mut() {
+                // Consecutive days are merged as well: `2024-01-01..2024-01-31` and
+                // `2024-02-01..2024-02-29` cover the same time as `2024-01-01..2024-02-29`
+                let adjacent_days = last.to.is_date
+                    && range.from.is_date
+                    && last.to.value.date().succ_opt() == Some(range.from.value.date());
+                if range.from.value <= last.to.value || adjacent_days {
+                    if range.to.value > last.to.value {
+                        last.to = range.to;
+                    }
+                    continue;
+                }
+            }
+            result.push(range);
+        }
+        result
+    }
+
+    fn merge_number_ranges(mut ranges: Vec<NumberRange>) -> Vec<NumberRange> {
+        ranges.sort_by(|a, b| Self::compare_from_bounds(&a.from, &b.from));
+        let mut result: Vec<NumberRange> = Vec::new();
+        for range in ranges.into_iter() {
+            if let Some(last) = result.last_mut() {
+                let overlaps = match (&last.to, &range.from) {
+                    (None, _) | (_, None) => true,
+                    (Some(to), Some(from)) => {
+                        from.value < to.value
+                            || (from.value == to.value && (from.inclusive || to.inclusive))
+                    }
+                };
+                if overlaps {
+                    if Self::compare_to_bounds(&range.to, &last.to) == Ordering::Greater {
+                        last.to = range.to;
+                    }
+                    continue;
+                }
+            }
+            result.push(range);
+        }
+        result
+    }
+
+    // Lower bounds: infinite first, then by value with inclusive bound first
+    fn compare_from_bounds(a: &Option<NumberBound>, b: &Option<NumberBound>) -> Ordering {
+        match (a, b) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(a), Some(b)) => a
+                .value
+                .cmp(&b.value)
+                .then_with(|| b.inclusive.cmp(&a.inclusive)),
+        }
+    }
+
+    // Upper bounds: infinite last, then by value with inclusive bound last
+    fn compare_to_bounds(a: &Option<NumberBound>, b: &Option<NumberBound>) -> Ordering {
+        match (a, b) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => a
+                .value
+                .cmp(&b.value)
+                .then_with(|| a.inclusive.cmp(&b.inclusive)),
+        }
+    }
+
+    fn date_range_item(filter: &Rc<BaseFilter>, range: DateRange) -> FilterItem {
+        FilterItem::Item(filter.change_operator(
+            FilterOperator::InDateRange,
+            vec![Some(range.from.raw), Some(range.to.raw)],
+        ))
+    }
+
+    fn number_range_item(filter: &Rc<BaseFilter>, range: NumberRange) -> FilterItem {
+        match (range.from, range.to) {
+            (Some(from), Some(to)) if from.inclusive && to.inclusive => FilterItem::Item(
+                filter.change_operator(FilterOperator::Between, vec![Some(from.raw), Some(to.raw)]),
+            ),
+            (Some(from), Some(to)) => FilterItem::Group(Rc::new(FilterGroup::new(
+                FilterGroupOperator::And,
+                vec![
+                    Self::from_bound_item(filter, from),
+                    Self::to_bound_item(filter, to),
+                ],
+            ))),
+            (Some(from), None) => Self::from_bound_item(filter, from),
+            (None, Some(to)) => Self::to_bound_item(filter, to),
+            // Unbounded on both sides matches any non null value
+            (None, None) => FilterItem::Item(filter.change_operator(FilterOperator::Set, vec![])),
+        }
+    }
+
+    fn from_bound_item(filter: &Rc<BaseFilter>, bound: NumberBound) -> FilterItem {
+        let operator = if bound.inclusive {
+            FilterOperator::Gte
+        } else {
+            FilterOperator::Gt
+        };
+        FilterItem::Item(filter.change_operator(operator, vec![Some(bound.raw)]))
+    }
+
+    fn to_bound_item(filter: &Rc<BaseFilter>, bound: NumberBound) -> FilterItem {
+        let operator = if bound.inclusive {
+            FilterOperator::Lte
+        } else {
+            FilterOperator::Lt
+        };
+        FilterItem::Item(filter.change_operator(operator, vec![Some(bound.raw)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> Decimal {
+        Decimal::parse(value).unwrap()
+    }
+
+    fn range(from: Option<(&str, bool)>, to: Option<(&str, bool)>) -> NumberRange {
+        let bound = |(value, inclusive): (&str, bool)| {
+            RangeCoalescer::parse_number_bound(value, inclusive).unwrap()
+        };
+        NumberRange {
+            from: from.map(bound),
+            to: to.map(bound),
+        }
+    }
+
+    fn bounds(ranges: Vec<NumberRange>) -> Vec<(Option<(String, bool)>, Option<(String, bool)>)> {
+        let bound = |b: NumberBound| (b.raw, b.inclusive);
+        ranges
+            .into_iter()
+            .map(|r| (r.from.map(bound), r.to.map(bound)))
+            .collect()
+    }
+
+    fn bound(value: &str, inclusive: bool) -> Option<(String, bool)> {
+        Some((value.to_string(), inclusive))
+    }
+
+    #[test]
+    fn decimals_compare_exactly() {
+        assert_eq!(decimal("0.1"), decimal("1e-1"));
+        assert_eq!(decimal("10"), decimal("1.0E1"));
+        assert_eq!(decimal("-0"), decimal("0.000"));
+        assert!(decimal("0.30000000000000001") > decimal("0.3"));
+        assert!(decimal("9007199254740993") > decimal("9007199254740992"));
+        assert!(decimal("-2") < decimal("-1.5"));
+        assert!(decimal("-0.5") < decimal("0"));
+        assert!(decimal("12") < decimal("12.5"));
+        assert!(decimal("99") < decimal("100"));
+        assert!(decimal("1e999") > decimal("1e998"));
+        assert_eq!(Decimal::parse("abc"), None);
+        assert_eq!(Decimal::parse("0x10"), None);
+        assert_eq!(Decimal::parse("."), None);
+        assert_eq!(Decimal::parse("NaN"), None);
+    }
+
+    #[test]
+    fn inclusive_bounds_touching_are_merged() {
+        let merged = RangeCoalescer::merge_number_ranges(vec![
+            range(Some(("5", true)), Some(("10", true))),
+            range(Some(("1", true)), Some(("5", true))),
+        ]);
+        assert_eq!(bounds(merged), vec![(bound("1", true), bound("10", true))]);
+
+        let merged = RangeCoalescer::merge_number_ranges(vec![
+            range(None, Some(("5", false))),
+            range(Some(("5", true)), None),
+        ]);
+        assert_eq!(bounds(merged), vec![(None, None)]);
+    }
+
+    #[test]
+    fn exclusive_bounds_touching_are_kept() {
+        let merged = RangeCoalescer::merge_number_ranges(vec![
+            range(None, Some(("5", false))),
+            range(Some(("5", false)), None),
+        ]);
+        assert_eq!(
+            bounds(merged),
+            vec![(None, bound("5", false)), (bound("5", false), None)]
+        );
+    }
+
+    #[test]
+    fn bounds_differing_beyond_float_precision_are_kept() {
+        let merged = RangeCoalescer::merge_number_ranges(vec![
+            range(Some(("1", true)), Some(("9007199254740992", true))),
+            range(
+                Some(("9007199254740993", true)),
+                Some(("9007199254740999", true)),
+            ),
+        ]);
+        assert_eq!(merged.len(), 2);
+
+        let merged = RangeCoalescer::merge_number_ranges(vec![
+            range(Some(("0.1", true)), Some(("0.3", false))),
+            range(Some(("0.30000000000000001", true)), Some(("1", true))),
+        ]);
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn equal_bounds_written_differently_are_merged() {
+        let merged = RangeCoalescer::merge_number_ranges(vec![
+            range(Some(("0", true)), Some(("0.10", true))),
+            range(Some(("1e-1", true)), Some(("2", true))),
+        ]);
+        assert_eq!(bounds(merged), vec![(bound("0", true), bound("2", true))]);
+    }
+
+    #[test]
+    fn contained_range_keeps_the_wider_upper_bound() {
+        let merged = RangeCoalescer::merge_number_ranges(vec![
+            range(Some(("1", true)), Some(("10", false))),
+            range(Some(("2", true)), Some(("10", true))),
+            range(Some(("3", true)), Some(("4", true))),
+        ]);
+        assert_eq!(bounds(merged), vec![(bound("1", true), bound("10", true))]);
+    }
+
+    #[test]
+    fn adjacent_days_are_merged() {
+        let date_range = |from: &str, to: &str| DateRange {
+            from: RangeCoalescer::parse_date_bound(from, false).unwrap(),
+            to: RangeCoalescer::parse_date_bound(to, true).unwrap(),
+        };
+        let merged = RangeCoalescer::merge_date_ranges(vec![
+            date_range("2024-02-01", "2024-02-29"),
+            date_range("2024-01-01", "2024-01-31"),
+        ]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].from.raw, "2024-01-01");
+        assert_eq!(merged[0].to.raw, "2024-02-29");
+
+        let merged = RangeCoalescer::merge_date_ranges(vec![
+            date_range("2024-01-01", "2024-01-30"),
+            date_range("2024-02-01", "2024-02-29"),
+        ]);
+        assert_eq!(merged.len(), 2);
+
+        let merged = RangeCoalescer::merge_date_ranges(vec![
+            date_range("2024-01-01T00:00:00.000", "2024-01-31T23:59:59.999"),
+            date_range("2024-02-01T00:00:00.000", "2024-02-29T23:59:59.999"),
+        ]);
+        assert_eq!(merged.len(), 2);
+    }
+}
@@ -0,0 +1,292 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref CURRENT_PERIOD_RE: Regex =
+        Regex::new(r"^(this|last|next)\s+(second|minute|hour|day|week|month|quarter|year)$")
+            .unwrap();
+    static ref PERIODS_RE: Regex =
+        Regex::new(r"^(last|next)\s+(\d+)\s+(second|minute|hour|day|week|month|quarter|year)s?$")
+            .unwrap();
+    static ref FROM_TO_RE: Regex = Regex::new(r"^from\s+(.+?)\s+to\s+(.+)$").unwrap();
+    static ref OFFSET_RE: Regex = Regex::new(
+        r"^(\d+)\s+(second|minute|hour|day|week|month|quarter|year)s?\s+(ago|from now)$"
+    )
+    .unwrap();
+    static ref ABSOLUTE_RE: Regex =
+        Regex::new(r"^\d{4}-\d{2}-\d{2}(?:[t ]\d{2}:\d{2}(?::\d{2}(?:\.\d{1,9})?)?)?$").unwrap();
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Unit {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "second" => Some(Self::Second),
+            "minute" => Some(Self::Minute),
+            "hour" => Some(Self::Hour),
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            "quarter" => Some(Self::Quarter),
+            "year" => Some(Self::Year),
+            _ => None,
+        }
+    }
+
+    fn add(&self, date: NaiveDateTime, count: i64) -> Option<NaiveDateTime> {
+        let months = |months: i64| {
+            let abs = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+            if months >= 0 {
+                date.checked_add_months(abs)
+            } else {
+                date.checked_sub_months(abs)
+            }
+        };
+        match self {
+            Self::Second => date.checked_add_signed(Duration::try_seconds(count)?),
+            Self::Minute => date.checked_add_signed(Duration::try_minutes(count)?),
+            Self::Hour => date.checked_add_signed(Duration::try_hours(count)?),
+            Self::Day => date.checked_add_signed(Duration::try_days(count)?),
+            Self::Week => date.checked_add_signed(Duration::try_weeks(count)?),
+            Self::Month => months(count),
+            Self::Quarter => months(count.checked_mul(3)?),
+            Self::Year => months(count.checked_mul(12)?),
+        }
+    }
+
+    // Weeks are ISO ones and start on Monday
+    fn start_of(&self, date: NaiveDateTime) -> Option<NaiveDateTime> {
+        let day = date.date();
+        let midnight = |day: NaiveDate| day.and_time(NaiveTime::MIN);
+        match self {
+            Self::Second => date.with_nanosecond(0),
+            Self::Minute => date.with_nanosecond(0)?.with_second(0),
+            Self::Hour => date.with_nanosecond(0)?.with_second(0)?.with_minute(0),
+            Self::Day => Some(midnight(day)),
+            Self::Week => Some(midnight(
+                day - Duration::days(day.weekday().num_days_from_monday() as i64),
+            )),
+            Self::Month => Some(midnight(day.with_day(1)?)),
+            Self::Quarter => Some(midnight(NaiveDate::from_ymd_opt(
+                day.year(),
+                (day.month0() / 3) * 3 + 1,
+                1,
+            )?)),
+            Self::Year => Some(midnight(NaiveDate::from_ymd_opt(day.year(), 1, 1)?)),
+        }
+    }
+
+    fn end_of(&self, date: NaiveDateTime) -> Option<NaiveDateTime> {
+        let next = self.add(self.start_of(date)?, 1)?;
+        next.checked_sub_signed(Duration::milliseconds(1))
+    }
+}
+
+// Relative date range expressions of `inDateRange` filters, resolved against the current time
+// of the query timezone the same way the API gateway does it for JS planner:
+// `today`, `yesterday`, `tomorrow`, `this quarter`, `last 7 days`, `next 2 weeks`,
+// `from 3 months ago to now`, `from 2024-01-01 to 2 days from now`.
+// Bounds are local timestamps with millisecond precision
+pub struct RelativeDateRange;
+
+impl RelativeDateRange {
+    // Absolute timestamps have no letters except of `T` separator and `Z` timezone
+    pub fn is_relative(value: &str) -> bool {
+        value
+            .chars()
+            .any(|c| c.is_ascii_alphabetic() && !matches!(c, 'T' | 't' | 'Z' | 'z'))
+    }
+
+    pub fn resolve(value: &str, now: NaiveDateTime) -> Option<(String, String)> {
+        let value = value.trim().to_lowercase();
+        let (from, to) = Self::resolve_range(&value, now)?;
+        Some((Self::format(from), Self::format(to)))
+    }
+
+    fn resolve_range(value: &str, now: NaiveDateTime) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        if let Some(caps) = CURRENT_PERIOD_RE.captures(value) {
+            let unit = Unit::from_name(&caps[2])?;
+            let shift = match &caps[1] {
+                "last" => -1,
+                "next" => 1,
+                _ => 0,
+            };
+            let date = unit.add(now, shift)?;
+            return Some((unit.start_of(date)?, unit.end_of(date)?));
+        }
+        if let Some(caps) = PERIODS_RE.captures(value) {
+            let count = caps[2].parse::<i64>().ok()?;
+            let unit = Unit::from_name(&caps[3])?;
+            // The current period isn't included, e.g. `last 7 days` ends yesterday
+            let (from, to) = if &caps[1] == "last" {
+                (unit.add(now, -count)?, unit.add(now, -1)?)
+            } else {
+                (unit.add(now, 1)?, unit.add(now, count)?)
+            };
+            return Some((unit.start_of(from)?, unit.end_of(to)?));
+        }
+        if let Some(caps) = FROM_TO_RE.captures(value) {
+            let unit = Self::exact_unit(value);
+            let from = Self::resolve_point(&caps[1], now)?;
+            let to = Self::resolve_point(&caps[2], now)?;
+            return Some((unit.start_of(from)?, unit.end_of(to)?));
+        }
+        let point = Self::resolve_point(value, now)?;
+        let unit = Self::exact_unit(value);
+        Some((unit.start_of(point)?, unit.end_of(point)?))
+    }
+
+    fn resolve_point(value: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        let value = value.trim();
+        match value {
+            "now" => return Some(now),
+            "today" => return Some(now),
+            "yesterday" => return Unit::Day.add(now, -1),
+            "tomorrow" => return Unit::Day.add(now, 1),
+            _ => {}
+        }
+        if let Some(caps) = OFFSET_RE.captures(value) {
+            let count = caps[1].parse::<i64>().ok()?;
+            let unit = Unit::from_name(&caps[2])?;
+            let count = if &caps[3] == "ago" { -count } else { count };
+            return unit.add(now, count);
+        }
+        if ABSOLUTE_RE.is_match(value) {
+            let value = value.replace(' ', "t");
+            return NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                .map(|d| d.and_time(NaiveTime::MIN))
+                .or_else(|_| NaiveDateTime::parse_from_str(&value, "%Y-%m-%dt%H:%M:%S%.f"))
+                .or_else(|_| NaiveDateTime::parse_from_str(&value, "%Y-%m-%dt%H:%M"))
+                .ok();
+        }
+        None
+    }
+
+    // Bounds are rounded to days unless the expression is about a finer unit
+    fn exact_unit(value: &str) -> Unit {
+        [Unit::Second, Unit::Minute, Unit::Hour]
+            .into_iter()
+            .zip(["second", "minute", "hour"])
+            .find(|(_, name)| value.contains(name))
+            .map(|(unit, _)| unit)
+            .unwrap_or(Unit::Day)
+    }
+
+    fn format(date: NaiveDateTime) -> String {
+        date.format("%Y-%m-%dT%H:%M:%S%.3f").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Same cases as the `dateParser` tests of the API gateway, with `now` in the query timezone
+    fn resolve(value: &str, now: &str) -> Option<(String, String)> {
+        let now = NaiveDateTime::parse_from_str(now, "%Y-%m-%dT%H:%M:%S%.f").unwrap();
+        RelativeDateRange::resolve(value, now)
+    }
+
+    fn range(from: &str, to: &str) -> Option<(String, String)> {
+        Some((from.to_string(), to.to_string()))
+    }
+
+    const NOW: &str = "2021-03-05T13:30:00.000";
+
+    #[test]
+    fn resolves_days_aligned_ranges() {
+        assert_eq!(
+            resolve("today", NOW),
+            range("2021-03-05T00:00:00.000", "2021-03-05T23:59:59.999")
+        );
+        assert_eq!(resolve("last 1 day", NOW), resolve("yesterday", NOW));
+        assert_eq!(resolve("next 1 day", NOW), resolve("tomorrow", NOW));
+        assert_eq!(
+            resolve("from 1 days ago to now", NOW),
+            range("2021-03-04T00:00:00.000", "2021-03-05T23:59:59.999")
+        );
+        assert_eq!(
+            resolve("from 7 days ago to now", NOW),
+            range("2021-02-26T00:00:00.000", "2021-03-05T23:59:59.999")
+        );
+        assert_eq!(
+            resolve("from 7 days ago to 7 days from now", NOW),
+            Some((
+                resolve("last 7 days", NOW).unwrap().0,
+                resolve("next 7 days", NOW).unwrap().1
+            ))
+        );
+        assert_eq!(
+            resolve("next 5 days", "2021-03-05T13:00:00.000"),
+            range("2021-03-06T00:00:00.000", "2021-03-10T23:59:59.999")
+        );
+        assert_eq!(
+            resolve("from 1 quarter ago to now", "2021-05-03T12:00:00.000"),
+            range("2021-02-03T00:00:00.000", "2021-05-03T23:59:59.999")
+        );
+    }
+
+    #[test]
+    fn resolves_hours_aligned_ranges() {
+        assert_eq!(
+            resolve("last 6 hours", NOW),
+            range("2021-03-05T07:00:00.000", "2021-03-05T12:59:59.999")
+        );
+        assert_eq!(
+            resolve("from 23 hours ago to now", NOW),
+            range("2021-03-04T14:00:00.000", "2021-03-05T13:59:59.999")
+        );
+        assert_eq!(
+            resolve("from now to 23 hours from now", NOW),
+            range("2021-03-05T13:00:00.000", "2021-03-06T12:59:59.999")
+        );
+        // 2020-09-22T13:03:20.518Z in America/Los_Angeles
+        assert_eq!(
+            resolve("from 1 hour ago to now", "2020-09-22T06:03:20.518"),
+            range("2020-09-22T05:00:00.000", "2020-09-22T06:59:59.999")
+        );
+    }
+
+    #[test]
+    fn resolves_months_and_quarters_ranges() {
+        assert_eq!(
+            resolve("last 2 quarters", "2021-02-15T13:00:00.000"),
+            range("2020-07-01T00:00:00.000", "2020-12-31T23:59:59.999")
+        );
+        assert_eq!(
+            resolve("last 6 months", "2021-02-15T13:00:00.000"),
+            range("2020-08-01T00:00:00.000", "2021-01-31T23:59:59.999")
+        );
+        assert_eq!(
+            resolve("last 6 months", "2021-03-15T13:00:00.000"),
+            range("2020-09-01T00:00:00.000", "2021-02-28T23:59:59.999")
+        );
+        assert_eq!(
+            resolve("next 6 months", "2021-02-20T13:00:00.000"),
+            range("2021-03-01T00:00:00.000", "2021-08-31T23:59:59.999")
+        );
+        assert_eq!(
+            resolve("next month", "2021-03-05T13:00:00.000"),
+            range("2021-04-01T00:00:00.000", "2021-04-30T23:59:59.999")
+        );
+    }
+
+    #[test]
+    fn rejects_unexpected_dates() {
+        assert!(RelativeDateRange::is_relative("unexpected date"));
+        assert_eq!(resolve("unexpected date", NOW), None);
+        assert!(!RelativeDateRange::is_relative("2021-03-05T13:30:00.000Z"));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::planner::sql_evaluator::MemberSymbol;
+use cubenativeutils::CubeError;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const REDACTED_VALUE: &str = "<redacted>";
+const HASH_BYTES: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueRedactionMode {
+    Redact,
+    // Values are replaced with their HMAC under the deployment secret: equal values can be
+    // correlated across errors and traces, while short or guessable values can't be
+    // recovered by hashing candidates without the secret
+    Hash(String),
+}
+
+impl ValueRedactionMode {
+    pub fn try_new(mode_name: &str, secret: &Option<String>) -> Result<Self, CubeError> {
+        match mode_name.to_lowercase().as_str() {
+            "redact" => Ok(Self::Redact),
+            "hash" => match secret.as_ref().filter(|secret| !secret.is_empty()) {
+                Some(secret) => Ok(Self::Hash(secret.clone())),
+                None => Err(CubeError::user(
+                    "Hash redaction mode requires CUBEJS_REDACTION_HASH_SECRET to be set"
+                        .to_string(),
+                )),
+            },
+            _ => Err(CubeError::user(format!(
+                "Unknown redaction mode {}",
+                mode_name
+            ))),
+        }
+    }
+}
+
+// Filter values of sensitive members should never appear verbatim in errors,
+// traces or plan dumps. Member and operator context is kept as is.
+#[derive(Clone, Debug)]
+pub struct ValueRedactionPolicy {
+    mode: ValueRedactionMode,
+}
+
+impl ValueRedactionPolicy {
+    pub fn new(mode: ValueRedactionMode) -> Self {
+        Self { mode }
+    }
+
+    pub fn try_from_mode_name(
+        mode_name: &Option<String>,
+        secret: &Option<String>,
+    ) -> Result<Self, CubeError> {
+        let mode = if let Some(mode_name) = mode_name {
+            ValueRedactionMode::try_new(mode_name, secret)?
+        } else {
+            ValueRedactionMode::Redact
+        };
+        Ok(Self::new(mode))
+    }
+
+    pub fn mode(&self) -> &ValueRedactionMode {
+        &self.mode
+    }
+
+    pub fn display_value(&self, member: &MemberSymbol, value: &str) -> String {
+        if !member.is_sensitive() {
+            return value.to_string();
+        }
+        match &self.mode {
+            ValueRedactionMode::Redact => REDACTED_VALUE.to_string(),
+            ValueRedactionMode::Hash(secret) => {
+                let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+                    return REDACTED_VALUE.to_string();
+                };
+                mac.update(value.as_bytes());
+                let digest = mac.finalize().into_bytes();
+                let hash = digest[..HASH_BYTES]
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>();
+                format!("<hash:{}>", hash)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::dimension_definition::DimenstionDefinitionStatic;
+    use crate::planner::test_fixtures::{dimension, dimension_definition};
+
+    fn policy(secret: &str) -> ValueRedactionPolicy {
+        ValueRedactionPolicy::try_from_mode_name(
+            &Some("hash".to_string()),
+            &Some(secret.to_string()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn hash_mode_uses_hmac_of_the_secret() {
+        let member = dimension(
+            "users.email",
+            DimenstionDefinitionStatic {
+                sensitive: Some(true),
+                ..dimension_definition("string")
+            },
+        );
+
+        let hash = policy("secret").display_value(&member, "alice@example.com");
+        assert!(hash.starts_with("<hash:") && hash.len() == "<hash:>".len() + 2 * HASH_BYTES);
+        assert_eq!(
+            hash,
+            policy("secret").display_value(&member, "alice@example.com")
+        );
+        assert_ne!(
+            hash,
+            policy("other secret").display_value(&member, "alice@example.com")
+        );
+        assert_ne!(
+            hash,
+            policy("secret").display_value(&member, "bob@example.com")
+        );
+        assert_eq!(
+            policy("secret").display_value(
+                &dimension("users.name", dimension_definition("string")),
+                "alice"
+            ),
+            "alice"
+        );
+    }
+
+    #[test]
+    fn hash_mode_requires_secret() {
+        for secret in [None, Some(String::new())] {
+            let err = ValueRedactionPolicy::try_from_mode_name(&Some("hash".to_string()), &secret)
+                .unwrap_err();
+            assert!(err.message.contains("CUBEJS_REDACTION_HASH_SECRET"));
+        }
+        assert_eq!(
+            ValueRedactionPolicy::try_from_mode_name(&None, &None)
+                .unwrap()
+                .mode(),
+            &ValueRedactionMode::Redact
+        );
+    }
+}
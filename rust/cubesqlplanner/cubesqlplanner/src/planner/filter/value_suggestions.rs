@@ -0,0 +1,166 @@
+use cubenativeutils::{CubeError, CubeErrorCauseType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+pub const INVALID_FILTER_VALUE_MEMBER_KEY: &str = "member";
+pub const INVALID_FILTER_VALUE_INDEX_KEY: &str = "valueIndex";
+pub const INVALID_FILTER_VALUE_EXPECTED_KEY: &str = "expectedFormats";
+pub const INVALID_FILTER_VALUE_SUGGESTION_KEY: &str = "suggestion";
+pub const INVALID_FILTER_VALUE_CODE_KEY: &str = "code";
+
+pub const NULL_ORDERING_COMPARISON_ERROR_CODE: &str = "NULL_ORDERING_COMPARISON";
+
+lazy_static! {
+    static ref LOOSE_DATE_RE: Regex = Regex::new(
+        r"^(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})(?:[T ](\d{1,2}):(\d{2})(?::(\d{2})(?:\.(\d{1,9}))?)?)?(?:Z|[+-]\d{2}:?\d{2})?$"
+    )
+    .unwrap();
+    static ref NUMBER_NOISE_RE: Regex = Regex::new(r"[^0-9.,eE+\-]").unwrap();
+}
+
+// Describes a filter value that failed coercion, so UIs can point at the exact bad value
+pub struct InvalidFilterValue {
+    member: String,
+    value_index: usize,
+    expected_formats: Vec<String>,
+    suggestion: Option<String>,
+    code: Option<&'static str>,
+}
+
+impl InvalidFilterValue {
+    pub fn date(member: String, value_index: usize, value: &str, precision: u32) -> Self {
+        let fraction = "s".repeat(precision as usize);
+        Self {
+            member,
+            value_index,
+            expected_formats: vec![
+                "YYYY-MM-DD".to_string(),
+                format!("YYYY-MM-DDTHH:mm:ss.{}", fraction),
+            ],
+            suggestion: Self::suggest_date(value, precision),
+            code: None,
+        }
+    }
+
+    pub fn number(member: String, value_index: usize, value: &str) -> Self {
+        Self {
+            member,
+            value_index,
+            expected_formats: vec!["number".to_string()],
+            suggestion: Self::suggest_number(value),
+            code: None,
+        }
+    }
+
+    pub fn boolean(member: String, value_index: usize) -> Self {
+        Self {
+            member,
+            value_index,
+            expected_formats: vec!["true".to_string(), "false".to_string()],
+            suggestion: None,
+            code: None,
+        }
+    }
+
+    // NULL can't be ordered: `x > NULL` is never true and almost always a client bug
+    pub fn null_ordering_comparison(member: String, value_index: usize) -> Self {
+        Self {
+            member,
+            value_index,
+            expected_formats: vec!["non-null value".to_string()],
+            suggestion: None,
+            code: Some(NULL_ORDERING_COMPARISON_ERROR_CODE),
+        }
+    }
+
+    // Suggestion is derived from the value itself and has to be dropped when the value is redacted
+    pub fn without_suggestion(mut self) -> Self {
+        self.suggestion = None;
+        self
+    }
+
+    pub fn into_error(self, message: String) -> CubeError {
+        let message = if let Some(suggestion) = &self.suggestion {
+            format!("{}. Did you mean '{}'?", message, suggestion)
+        } else {
+            format!(
+                "{}. Expected {}",
+                message,
+                self.expected_formats.join(" or ")
+            )
+        };
+        let mut meta = HashMap::from([
+            (INVALID_FILTER_VALUE_MEMBER_KEY.to_string(), self.member),
+            (
+                INVALID_FILTER_VALUE_INDEX_KEY.to_string(),
+                self.value_index.to_string(),
+            ),
+            (
+                INVALID_FILTER_VALUE_EXPECTED_KEY.to_string(),
+                serde_json::to_string(&self.expected_formats).unwrap_or_default(),
+            ),
+        ]);
+        if let Some(suggestion) = self.suggestion {
+            meta.insert(INVALID_FILTER_VALUE_SUGGESTION_KEY.to_string(), suggestion);
+        }
+        if let Some(code) = self.code {
+            meta.insert(INVALID_FILTER_VALUE_CODE_KEY.to_string(), code.to_string());
+        }
+        CubeError {
+            message,
+            cause: CubeErrorCauseType::User(Some(meta)),
+            backtrace: None,
+        }
+    }
+
+    fn suggest_date(value: &str, precision: u32) -> Option<String> {
+        let caps = LOOSE_DATE_RE.captures(value.trim())?;
+        let num = |i: usize| caps.get(i).map(|m| m.as_str().parse::<u32>().unwrap_or(0));
+        let (year, month, day) = (caps[1].to_string(), num(2)?, num(3)?);
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        let date = format!("{}-{:02}-{:02}", year, month, day);
+        let Some(hour) = num(4) else {
+            return Some(date);
+        };
+        let (minute, second) = (num(5).unwrap_or(0), num(6).unwrap_or(0));
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        let fraction = caps.get(7).map(|m| m.as_str()).unwrap_or("");
+        let fraction = format!("{:0<width$}", fraction, width = precision as usize);
+        Some(format!(
+            "{}T{:02}:{:02}:{:02}.{}",
+            date,
+            hour,
+            minute,
+            second,
+            &fraction[..precision as usize]
+        ))
+    }
+
+    fn suggest_number(value: &str) -> Option<String> {
+        let cleaned = NUMBER_NOISE_RE.replace_all(value.trim(), "").to_string();
+        let normalized = match (cleaned.contains(','), cleaned.contains('.')) {
+            // Thousands separators: 1,234.5
+            (true, true) => cleaned.replace(',', ""),
+            // Decimal comma: 12,5
+            (true, false) if cleaned.matches(',').count() == 1 => {
+                let decimals = cleaned.split(',').nth(1).map(|s| s.len()).unwrap_or(0);
+                if decimals == 3 {
+                    cleaned.replace(',', "")
+                } else {
+                    cleaned.replace(',', ".")
+                }
+            }
+            (true, false) => cleaned.replace(',', ""),
+            _ => cleaned,
+        };
+        if normalized.is_empty() || normalized == value {
+            return None;
+        }
+        normalized.parse::<f64>().ok().map(|_| normalized)
+    }
+}
@@ -0,0 +1,246 @@
+use super::query_tools::QueryTools;
+use super::sql_templates::{PlanSqlTemplates, TemplateOrderByColumn, TemplateProjectionColumn};
+use super::{BaseMeasure, BaseMember, QueryProperties};
+use crate::plan::join::JoinType;
+use cubenativeutils::CubeError;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str::FromStr;
+
+const TIME_SERIES_ALIAS: &str = "time_series";
+const FILLED_SOURCE_ALIAS: &str = "q";
+const FILL_GROUP_SUFFIX: &str = "__fill_group";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FillPolicy {
+    // Repeats the last known value
+    Previous,
+    Zero,
+    // Interpolates between the neighbour known values, only done in post-processing
+    Linear,
+}
+
+impl FromStr for FillPolicy {
+    type Err = CubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "previous" => Ok(Self::Previous),
+            "zero" => Ok(Self::Zero),
+            "linear" => Ok(Self::Linear),
+            _ => Err(CubeError::user(format!(
+                "Unknown fill policy '{}'. Supported policies: previous, zero, linear",
+                s
+            ))),
+        }
+    }
+}
+
+// Fills gaps of a time series query: the query is joined to the generated series
+// of its time dimension and empty buckets are filled according to per-measure policies.
+// Policies that can't be rendered in sql are returned to the caller for post-processing
+pub struct GapFill {
+    query_tools: Rc<QueryTools>,
+    request: Rc<QueryProperties>,
+    policies: Vec<(Rc<BaseMeasure>, FillPolicy)>,
+}
+
+impl GapFill {
+    pub fn try_new(
+        query_tools: Rc<QueryTools>,
+        request: Rc<QueryProperties>,
+        policies: &HashMap<String, String>,
+    ) -> Result<Self, CubeError> {
+        let mut result = Vec::new();
+        // Policies are kept in the order of measures in the query
+        for measure in request.measures().iter() {
+            if let Some(policy) = policies.get(&measure.full_name()) {
+                result.push((measure.clone(), FillPolicy::from_str(policy)?));
+            }
+        }
+        if let Some(member) = policies
+            .keys()
+            .find(|name| !request.measures().iter().any(|m| &m.full_name() == *name))
+        {
+            return Err(CubeError::user(format!(
+                "Fill policy is set for '{}' which isn't a measure of the query",
+                member
+            )));
+        }
+        Ok(Self {
+            query_tools,
+            request,
+            policies: result,
+        })
+    }
+
+    // Returns filled sql and measures whose policies should be applied in post-processing
+    pub fn build(
+        &self,
+        query_sql: &str,
+        templates: &PlanSqlTemplates,
+    ) -> Result<(String, Vec<String>), CubeError> {
+        if self.policies.is_empty() {
+            return Ok((query_sql.to_string(), vec![]));
+        }
+        if !self.can_fill_in_sql(templates) {
+            return Ok((
+                query_sql.to_string(),
+                self.policies.iter().map(|(m, _)| m.full_name()).collect(),
+            ));
+        }
+        let time_dimension = &self.request.time_dimensions()[0];
+        let granularity = time_dimension.get_granularity().unwrap_or_default();
+        let date_range = time_dimension.get_date_range().unwrap_or_default();
+        let seria = self
+            .query_tools
+            .base_tools()
+            .generate_time_series(granularity, date_range.clone())?;
+        let time_series_sql = templates.time_series_select(
+            date_range.first().cloned(),
+            date_range.last().cloned(),
+            seria,
+        )?;
+
+        let time_alias = time_dimension.alias_name();
+        let series_date =
+            templates.column_reference(&Some(TIME_SERIES_ALIAS.to_string()), "date_from")?;
+        let source_date = self.source_column(&time_alias, templates)?;
+        let from = format!(
+            "{} {}",
+            templates.query_aliased(&format!("({})", time_series_sql), TIME_SERIES_ALIAS)?,
+            templates.join(
+                &templates.query_aliased(&format!("({})", query_sql), FILLED_SOURCE_ALIAS)?,
+                &templates.binary_expr(&series_date, "=", &source_date)?,
+                &JoinType::Left,
+            )?
+        );
+
+        // Joined buckets with counters of known values which group every gap
+        // with the preceding known value
+        let mut joined_projection = vec![self.projection(&series_date, &time_alias, templates)?];
+        let mut filled_projection = vec![self.projection(
+            &templates.quote_identifier(&time_alias)?,
+            &time_alias,
+            templates,
+        )?];
+        let mut post_processing = Vec::new();
+        for measure in self.request.measures().iter() {
+            let alias = measure.alias_name();
+            let source = self.source_column(&alias, templates)?;
+            joined_projection.push(self.projection(&source, &alias, templates)?);
+            let column = templates.quote_identifier(&alias)?;
+            let policy = self
+                .policies
+                .iter()
+                .find(|(m, _)| Rc::ptr_eq(m, measure))
+                .map(|(_, policy)| policy);
+            let filled = match policy {
+                Some(FillPolicy::Zero) => templates.gap_fill_zero(&column)?,
+                Some(FillPolicy::Previous) => {
+                    let group_alias = format!("{}{}", alias, FILL_GROUP_SUFFIX);
+                    joined_projection.push(self.projection(
+                        &templates.gap_fill_previous_group(&source, &series_date)?,
+                        &group_alias,
+                        templates,
+                    )?);
+                    templates
+                        .gap_fill_previous(&column, &templates.quote_identifier(&group_alias)?)?
+                }
+                Some(FillPolicy::Linear) => {
+                    post_processing.push(measure.full_name());
+                    column
+                }
+                None => column,
+            };
+            filled_projection.push(self.projection(&filled, &alias, templates)?);
+        }
+
+        let joined_sql = templates.select(
+            vec![],
+            &from,
+            joined_projection,
+            None,
+            vec![],
+            None,
+            None,
+            vec![],
+            None,
+            None,
+            false,
+        )?;
+        let order_by = vec![TemplateOrderByColumn {
+            expr: templates.order_by(
+                &templates.quote_identifier(&time_alias)?,
+                Some(1),
+                true,
+                None,
+            )?,
+        }];
+        let sql = templates.select(
+            vec![],
+            &templates.query_aliased(&format!("({})", joined_sql), "filled")?,
+            filled_projection,
+            None,
+            vec![],
+            None,
+            None,
+            order_by,
+            None,
+            None,
+            false,
+        )?;
+        Ok((sql, post_processing))
+    }
+
+    // Series can only be generated for a single time dimension with granularity and date range.
+    // Other dimensions would require a series per dimension values combination
+    fn can_fill_in_sql(&self, templates: &PlanSqlTemplates) -> bool {
+        let time_dimensions = self.request.time_dimensions();
+        templates.supports_gap_fill()
+            && self.request.dimensions().is_empty()
+            && time_dimensions.len() == 1
+            && time_dimensions[0].has_granularity()
+            && time_dimensions[0].get_date_range().is_some()
+    }
+
+    fn source_column(
+        &self,
+        alias: &str,
+        templates: &PlanSqlTemplates,
+    ) -> Result<String, CubeError> {
+        templates.column_reference(&Some(FILLED_SOURCE_ALIAS.to_string()), alias)
+    }
+
+    fn projection(
+        &self,
+        expr: &str,
+        alias: &str,
+        templates: &PlanSqlTemplates,
+    ) -> Result<TemplateProjectionColumn, CubeError> {
+        Ok(TemplateProjectionColumn {
+            expr: expr.to_string(),
+            alias: alias.to_string(),
+            aliased: templates.column_aliased(expr, alias)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_policies_are_parsed_case_insensitively() {
+        assert_eq!(
+            FillPolicy::from_str("previous").unwrap(),
+            FillPolicy::Previous
+        );
+        assert_eq!(FillPolicy::from_str("Zero").unwrap(), FillPolicy::Zero);
+        assert_eq!(FillPolicy::from_str("LINEAR").unwrap(), FillPolicy::Linear);
+        assert_eq!(
+            FillPolicy::from_str("spline").unwrap_err().message,
+            "Unknown fill policy 'spline'. Supported policies: previous, zero, linear"
+        );
+    }
+}
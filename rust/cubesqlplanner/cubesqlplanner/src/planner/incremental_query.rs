@@ -0,0 +1,161 @@
+use super::batch_query::error_result;
+use super::sql_evaluator::Compiler;
+use super::BaseQuery;
+use crate::cube_bridge::audit_log_sink::AuditLogSink;
+use crate::cube_bridge::base_query_options::{
+    BaseQueryOptions, BaseQueryOptionsStatic, QueryVariant,
+};
+use crate::cube_bridge::base_tools::BaseTools;
+use crate::cube_bridge::cancellation_token::CancellationToken;
+use crate::cube_bridge::evaluator::CubeEvaluator;
+use crate::cube_bridge::join_graph::JoinGraph;
+use crate::cube_bridge::metrics_recorder::MetricsRecorder;
+use crate::cube_bridge::table_name_resolver::TableNameResolver;
+use cubenativeutils::wrappers::inner_types::InnerTypes;
+use cubenativeutils::wrappers::object::NativeArray;
+use cubenativeutils::wrappers::NativeType;
+use cubenativeutils::wrappers::{NativeContextHolder, NativeObjectHandle};
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Options of a variant: static data is derived from the base query,
+// native services are the ones of the base query
+pub(crate) struct DerivedQueryOptions {
+    base: Rc<dyn BaseQueryOptions>,
+    static_data: BaseQueryOptionsStatic,
+}
+
+impl DerivedQueryOptions {
+    pub(crate) fn new(base: Rc<dyn BaseQueryOptions>, static_data: BaseQueryOptionsStatic) -> Self {
+        Self { base, static_data }
+    }
+}
+
+impl BaseQueryOptions for DerivedQueryOptions {
+    fn measures(&self) -> Result<Option<Vec<String>>, CubeError> {
+        Ok(self.static_data.measures.clone())
+    }
+
+    fn dimensions(&self) -> Result<Option<Vec<String>>, CubeError> {
+        Ok(self.static_data.dimensions.clone())
+    }
+
+    fn cube_evaluator(&self) -> Result<Rc<dyn CubeEvaluator>, CubeError> {
+        self.base.cube_evaluator()
+    }
+
+    fn base_tools(&self) -> Result<Rc<dyn BaseTools>, CubeError> {
+        self.base.base_tools()
+    }
+
+    fn join_graph(&self) -> Result<Rc<dyn JoinGraph>, CubeError> {
+        self.base.join_graph()
+    }
+
+    fn audit_log_sink(&self) -> Result<Option<Rc<dyn AuditLogSink>>, CubeError> {
+        self.base.audit_log_sink()
+    }
+
+    fn has_audit_log_sink(&self) -> Result<bool, CubeError> {
+        self.base.has_audit_log_sink()
+    }
+
+    fn cancellation_token(&self) -> Result<Option<Rc<dyn CancellationToken>>, CubeError> {
+        self.base.cancellation_token()
+    }
+
+    fn has_cancellation_token(&self) -> Result<bool, CubeError> {
+        self.base.has_cancellation_token()
+    }
+
+    fn table_name_resolver(&self) -> Result<Option<Rc<dyn TableNameResolver>>, CubeError> {
+        self.base.table_name_resolver()
+    }
+
+    fn has_table_name_resolver(&self) -> Result<bool, CubeError> {
+        self.base.has_table_name_resolver()
+    }
+
+    fn metrics_recorder(&self) -> Result<Option<Rc<dyn MetricsRecorder>>, CubeError> {
+        self.base.metrics_recorder()
+    }
+
+    fn has_metrics_recorder(&self) -> Result<bool, CubeError> {
+        self.base.has_metrics_recorder()
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &BaseQueryOptionsStatic {
+        &self.static_data
+    }
+}
+
+// Derives variants of a base query without planning them from scratch, e.g. for
+// cross-filtering dashboards which change a single predicate per click.
+// Variants share member symbols compiled for the base query, so only members
+// introduced by a variant are compiled.
+pub struct IncrementalQuery<IT: InnerTypes> {
+    context: NativeContextHolder<IT>,
+    options: Rc<dyn BaseQueryOptions>,
+    evaluator_compiler: Rc<RefCell<Compiler>>,
+    base: BaseQuery<IT>,
+}
+
+impl<IT: InnerTypes> IncrementalQuery<IT> {
+    pub fn try_new(
+        context: NativeContextHolder<IT>,
+        options: Rc<dyn BaseQueryOptions>,
+    ) -> Result<Self, CubeError> {
+        let evaluator_compiler = Rc::new(RefCell::new(Compiler::new(options.cube_evaluator()?)));
+        let base = BaseQuery::try_new_with_compiler(
+            context.clone(),
+            options.clone(),
+            Some(evaluator_compiler.clone()),
+        )?;
+        Ok(Self {
+            context,
+            options,
+            evaluator_compiler,
+            base,
+        })
+    }
+
+    pub fn base(&self) -> &BaseQuery<IT> {
+        &self.base
+    }
+
+    pub fn derive(&self, variant: &QueryVariant) -> Result<BaseQuery<IT>, CubeError> {
+        let options = Rc::new(DerivedQueryOptions::new(
+            self.options.clone(),
+            self.options.static_data().with_variant(variant)?,
+        ));
+        BaseQuery::try_new_with_compiler(
+            self.context.clone(),
+            options,
+            Some(self.evaluator_compiler.clone()),
+        )
+    }
+
+    // Returns `[sql, params]` of the base query followed by results of the variants.
+    // A variant which failed to plan is returned as `{ error, meta? }`
+    pub fn build_sql_and_params(
+        &self,
+        variants: &Vec<QueryVariant>,
+    ) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let res = self.context.empty_array();
+        res.set(0, self.base.build_sql_and_params()?)?;
+        for (i, variant) in variants.iter().enumerate() {
+            let item = match self.derive(variant).and_then(|q| q.build_sql_and_params()) {
+                Ok(item) => item,
+                Err(e) => error_result(self.context.clone(), &e)?,
+            };
+            res.set(i as u32 + 1, item)?;
+        }
+        Ok(NativeObjectHandle::new(res.into_object()))
+    }
+}
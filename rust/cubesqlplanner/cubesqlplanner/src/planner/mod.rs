@@ -5,25 +5,71 @@ pub mod base_measure;
 pub mod base_member;
 pub mod base_query;
 pub mod base_time_dimension;
+pub mod batch_query;
+pub mod compiled_filter_predicate;
+pub mod currency_conversion;
+pub mod date_time_tools;
 pub mod filter;
+pub mod gap_fill;
 pub mod granularity_helper;
+pub mod incremental_query;
 pub mod params_allocator;
+pub mod planner_flags;
 pub mod planners;
+pub mod planning_budget;
+pub mod planning_cancellation;
+pub mod planning_memory;
+pub mod planning_metrics;
+pub mod planning_trace;
+pub mod query_audit;
+pub mod query_explain;
+pub mod query_hash;
 pub mod query_properties;
+pub mod query_registry;
 pub mod query_tools;
+pub mod query_watermark;
+pub mod row_count_estimate;
+pub mod source_query;
 pub mod sql_evaluator;
 pub mod sql_templates;
+#[cfg(test)]
+pub mod test_fixtures;
+pub mod typed_param;
+pub mod unit_conversion;
 pub mod utils;
 pub mod visitor_context;
 
 pub use base_cube::BaseCube;
 pub use base_dimension::BaseDimension;
-pub use base_join_condition::{BaseJoinCondition, SqlJoinCondition};
+pub use base_join_condition::{
+    AsOfJoinCondition, BaseJoinCondition, SnapshotJoinCondition, SqlJoinCondition,
+};
 pub use base_measure::BaseMeasure;
 pub use base_member::{BaseMember, BaseMemberHelper};
-pub use base_query::BaseQuery;
+pub use base_query::{BaseQuery, QueryBuilder, QuerySql};
 pub use base_time_dimension::BaseTimeDimension;
+pub use batch_query::BatchQuery;
+pub use compiled_filter_predicate::CompiledFilterPredicate;
+pub use currency_conversion::{CurrencyConversion, CurrencyRatesJoin};
+pub use date_time_tools::{DateTimeTools, NativeDateTimeTools, StaticDateTimeTools};
+pub use gap_fill::{FillPolicy, GapFill};
 pub use granularity_helper::GranularityHelper;
-pub use params_allocator::ParamsAllocator;
+pub use incremental_query::IncrementalQuery;
+pub use params_allocator::{ParamAllocation, ParamsAllocator};
+pub use planner_flags::{PlannerFlag, PlannerFlags};
+pub use planning_budget::PlanningBudget;
+pub use planning_cancellation::PlanningCancellation;
+pub use planning_memory::PlanningMemoryBudget;
+pub use planning_metrics::PlanningMetrics;
+pub use planning_trace::PlanningTrace;
+pub use query_audit::QueryAudit;
+pub use query_explain::QueryExplain;
+pub use query_hash::QueryHash;
 pub use query_properties::{FullKeyAggregateMeasures, OrderByItem, QueryProperties};
+pub use query_registry::{QueryRegistry, SavedQuery, SavedQueryValidation};
+pub use query_watermark::QueryWatermark;
+pub use row_count_estimate::RowCountEstimate;
+pub use source_query::SourceQuery;
+pub use typed_param::{ParamType, TypedParam};
+pub use unit_conversion::UnitConversion;
 pub use visitor_context::{evaluate_sql_call_with_context, evaluate_with_context, VisitorContext};
@@ -1,101 +1,277 @@
 use crate::planner::sql_templates::PlanSqlTemplates;
+use crate::planner::{ParamType, TypedParam};
 use cubenativeutils::CubeError;
 use lazy_static::lazy_static;
 use regex::{Captures, Regex};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 //const PARAMS_MATCH_REGEXP = /\$(\d+)\$/g;
 lazy_static! {
     static ref PARAMS_MATCH_RE: Regex = Regex::new(r"\$_(\d+)_\$").unwrap();
+    static ref NAMED_PLACEHOLDERS_MATCH_RE: Regex =
+        Regex::new(r"\$:([_a-zA-Z][_a-zA-Z0-9]*):\$").unwrap();
+    static ref PLACEHOLDER_NAME_RE: Regex = Regex::new(r"^[_a-zA-Z][_a-zA-Z0-9]*$").unwrap();
 }
+
+// Param allocation with its position, for assertions on param order and values
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamAllocation {
+    // Position of the param in the allocation order
+    pub index: usize,
+    pub value: String,
+    pub param_type: ParamType,
+    pub member: Option<String>,
+}
+
 pub struct ParamsAllocator {
     sql_templates: PlanSqlTemplates,
-    params: Vec<String>,
+    // Params are rendered as literals for engines without bind params support
+    inline_params: bool,
+    params: Vec<TypedParam>,
+    // Member a param was allocated for, by param index
+    param_members: HashMap<usize, String>,
 }
 
 impl ParamsAllocator {
-    pub fn new(sql_templates: PlanSqlTemplates) -> ParamsAllocator {
+    pub fn new(sql_templates: PlanSqlTemplates, inline_params: bool) -> ParamsAllocator {
         ParamsAllocator {
             sql_templates,
+            inline_params,
             params: Vec::new(),
+            param_members: HashMap::new(),
         }
     }
 
+    // Params are marked in the sql until it's rendered: dialect placeholders are numbered
+    // in the sql order, which is known only then
     pub fn make_placeholder(&self, index: usize) -> String {
         format!("$_{}_$", index)
     }
 
     pub fn allocate_param(&mut self, name: &str) -> String {
-        self.params.push(name.to_string());
+        self.allocate_typed_param(name, ParamType::String)
+    }
+
+    pub fn allocate_typed_param(&mut self, name: &str, param_type: ParamType) -> String {
+        self.params
+            .push(TypedParam::new(name.to_string(), param_type));
         self.make_placeholder(self.params.len() - 1)
     }
 
-    pub fn get_params(&self) -> &Vec<String> {
+    pub fn allocate_member_param(&mut self, member: &str, name: &str) -> String {
+        self.allocate_typed_member_param(member, name, ParamType::String)
+    }
+
+    pub fn allocate_typed_member_param(
+        &mut self,
+        member: &str,
+        name: &str,
+        param_type: ParamType,
+    ) -> String {
+        let placeholder = self.allocate_typed_param(name, param_type);
+        self.param_members
+            .insert(self.params.len() - 1, member.to_string());
+        placeholder
+    }
+
+    // Named placeholders are kept in the result sql as external template variables
+    // instead of being bound to a param value
+    pub fn allocate_named_placeholder(&self, name: &str) -> Result<String, CubeError> {
+        if !PLACEHOLDER_NAME_RE.is_match(name) {
+            return Err(CubeError::user(format!(
+                "Invalid placeholder name '{}'",
+                name
+            )));
+        }
+        Ok(format!("$:{}:$", name))
+    }
+
+    pub fn get_params(&self) -> &Vec<TypedParam> {
         &self.params
     }
 
+    pub fn allocations(&self) -> Vec<ParamAllocation> {
+        self.params
+            .iter()
+            .enumerate()
+            .map(|(index, param)| ParamAllocation {
+                index,
+                value: param.value().clone(),
+                param_type: param.param_type(),
+                member: self.param_members.get(&index).cloned(),
+            })
+            .collect()
+    }
+
+    // Renders params as `$1`, `$2`, ... in the allocation order regardless of the dialect,
+    // so the sql of a fragment can be asserted along with its allocations
+    pub fn render_allocated_placeholders(&self, sql: &str) -> String {
+        PARAMS_MATCH_RE
+            .replace_all(sql, |caps: &Captures| {
+                let ind: usize = caps[1].to_string().parse().unwrap();
+                format!("${}", ind + 1)
+            })
+            .to_string()
+    }
+
     pub fn build_sql_and_params(
         &self,
         sql: &str,
         native_allocated_params: Vec<String>,
         should_reuse_params: bool,
-    ) -> Result<(String, Vec<String>), CubeError> {
+    ) -> Result<(String, Vec<TypedParam>), CubeError> {
+        let (result_sql, params, _) = self.build_sql_and_params_with_member_params(
+            sql,
+            native_allocated_params,
+            should_reuse_params,
+        )?;
+        Ok((result_sql, params))
+    }
+
+    // Params are numbered in the order they appear in the result sql, so structurally
+    // identical queries always get the same sql and can reuse a prepared statement.
+    // Also returns positions of params allocated for each member
+    pub fn build_sql_and_params_with_member_params(
+        &self,
+        sql: &str,
+        native_allocated_params: Vec<String>,
+        should_reuse_params: bool,
+    ) -> Result<(String, Vec<TypedParam>, BTreeMap<String, Vec<usize>>), CubeError> {
         let (sql, params) = self.add_native_allocated_params(sql, &native_allocated_params)?;
+        let (result_sql, params_order) =
+            self.replace_params(&sql, &params, &HashSet::new(), should_reuse_params)?;
+        let result_sql = self.replace_named_placeholders(&result_sql)?;
+        let mut member_params: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (position, index) in params_order.iter().enumerate() {
+            if let Some(member) = self.param_members.get(index) {
+                member_params
+                    .entry(member.clone())
+                    .or_default()
+                    .push(position);
+            }
+        }
+        let params_in_sql_order = params_order.iter().map(|i| params[*i].clone()).collect();
+        Ok((result_sql, params_in_sql_order, member_params))
+    }
+
+    // Binds named placeholders to regular params instead of keeping them in the sql,
+    // so the same sql can be re-rendered by substituting param values only.
+    // Returns positions of bound params for each placeholder name
+    pub fn build_sql_and_params_with_bound_placeholders(
+        &self,
+        sql: &str,
+        native_allocated_params: Vec<String>,
+        should_reuse_params: bool,
+    ) -> Result<(String, Vec<TypedParam>, HashMap<String, Vec<usize>>), CubeError> {
+        let (sql, mut params) = self.add_native_allocated_params(sql, &native_allocated_params)?;
+        let mut name_to_index: HashMap<String, usize> = HashMap::new();
+        let sql = NAMED_PLACEHOLDERS_MATCH_RE
+            .replace_all(&sql, |caps: &Captures| {
+                let name = caps[1].to_string();
+                let index = if let Some(index) = name_to_index.get(&name) {
+                    *index
+                } else {
+                    params.push(TypedParam::string(String::new()));
+                    name_to_index.insert(name, params.len() - 1);
+                    params.len() - 1
+                };
+                self.make_placeholder(index)
+            })
+            .to_string();
+        let index_to_name: HashMap<usize, String> = name_to_index
+            .into_iter()
+            .map(|(name, index)| (index, name))
+            .collect();
+
+        // Placeholders stay bound even when params are inlined: they are substituted later
+        let bound: HashSet<usize> = index_to_name.keys().cloned().collect();
+        let (result_sql, params_order) =
+            self.replace_params(&sql, &params, &bound, should_reuse_params)?;
+        let mut placeholders: HashMap<String, Vec<usize>> = HashMap::new();
         let mut params_in_sql_order = Vec::new();
+        for (position, index) in params_order.iter().enumerate() {
+            if let Some(name) = index_to_name.get(index) {
+                placeholders.entry(name.clone()).or_default().push(position);
+            }
+            params_in_sql_order.push(params[*index].clone());
+        }
+        Ok((result_sql, params_in_sql_order, placeholders))
+    }
+
+    // Returns sql with rendered params and indexes of the source params in sql order.
+    // Inlined params are rendered as literals and are not returned
+    fn replace_params(
+        &self,
+        sql: &str,
+        params: &Vec<TypedParam>,
+        bound: &HashSet<usize>,
+        should_reuse_params: bool,
+    ) -> Result<(String, Vec<usize>), CubeError> {
+        let should_reuse_params = should_reuse_params && self.sql_templates.is_indexed_param()?;
+        let mut params_order = Vec::new();
         let mut param_index_map: HashMap<usize, usize> = HashMap::new();
         let mut error = None;
-        let result_sql = if should_reuse_params {
-            PARAMS_MATCH_RE
-                .replace_all(&sql, |caps: &Captures| {
-                    let ind: usize = caps[1].to_string().parse().unwrap();
+        let result_sql = PARAMS_MATCH_RE
+            .replace_all(sql, |caps: &Captures| {
+                let ind: usize = caps[1].to_string().parse().unwrap();
+                let rendered = if self.inline_params && !bound.contains(&ind) {
+                    self.sql_templates.literal(&params[ind])
+                } else {
                     let new_index = if let Some(index) = param_index_map.get(&ind) {
                         index.clone()
                     } else {
-                        let index = params_in_sql_order.len();
-                        params_in_sql_order.push(params[ind].clone());
-                        param_index_map.insert(ind, index);
+                        let index = params_order.len();
+                        params_order.push(ind);
+                        if should_reuse_params {
+                            param_index_map.insert(ind, index);
+                        }
                         index
                     };
-                    match self.sql_templates.param(new_index) {
-                        Ok(res) => res,
-                        Err(e) => {
-                            if error.is_none() {
-                                error = Some(e);
-                            }
-                            "$error$".to_string()
+                    self.sql_templates.param(new_index)
+                };
+                match rendered {
+                    Ok(res) => res,
+                    Err(e) => {
+                        if error.is_none() {
+                            error = Some(e);
                         }
+                        "$error$".to_string()
                     }
-                })
-                .to_string()
-        } else {
-            PARAMS_MATCH_RE
-                .replace_all(&sql, |caps: &Captures| {
-                    let ind: usize = caps[1].to_string().parse().unwrap();
-                    let index = params_in_sql_order.len();
-                    params_in_sql_order.push(params[ind].clone());
-                    match self.sql_templates.param(index) {
-                        Ok(res) => res,
-                        Err(e) => {
-                            if error.is_none() {
-                                error = Some(e);
-                            }
-                            "$error$".to_string()
+                }
+            })
+            .to_string();
+        if let Some(error) = error {
+            return Err(error);
+        }
+        Ok((result_sql, params_order))
+    }
+
+    fn replace_named_placeholders(&self, sql: &str) -> Result<String, CubeError> {
+        let mut error = None;
+        let result_sql = NAMED_PLACEHOLDERS_MATCH_RE
+            .replace_all(sql, |caps: &Captures| {
+                match self.sql_templates.named_param(&caps[1]) {
+                    Ok(res) => res,
+                    Err(e) => {
+                        if error.is_none() {
+                            error = Some(e);
                         }
+                        "$error$".to_string()
                     }
-                })
-                .to_string()
-        };
+                }
+            })
+            .to_string();
         if let Some(error) = error {
             return Err(error);
         }
-        Ok((result_sql, params_in_sql_order))
+        Ok(result_sql)
     }
 
     fn add_native_allocated_params(
         &self,
         sql: &str,
         native_allocated_params: &Vec<String>,
-    ) -> Result<(String, Vec<String>), CubeError> {
+    ) -> Result<(String, Vec<TypedParam>), CubeError> {
         lazy_static! {
             static ref NATIVE_PARAMS_MATCH_RE: Regex = Regex::new(r"\$(\d+)\$").unwrap();
         }
@@ -107,7 +283,7 @@ impl ParamsAllocator {
             let sql = NATIVE_PARAMS_MATCH_RE
                 .replace_all(sql, |caps: &Captures| {
                     let ind: usize = caps[1].to_string().parse().unwrap();
-                    let param = native_allocated_params[ind].clone();
+                    let param = TypedParam::string(native_allocated_params[ind].clone());
                     result_params.push(param);
                     self.make_placeholder(result_params.len() - 1)
                 })
@@ -116,3 +292,140 @@ impl ParamsAllocator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+    use std::rc::Rc;
+
+    fn allocator(param_template: &str, inline_params: bool) -> ParamsAllocator {
+        let templates = vec![
+            ("params/param", param_template),
+            ("params/named_param", ":{{ param_name }}"),
+            ("quotes/literal", "'"),
+            ("quotes/literal_escape", "''"),
+            ("expressions/true", "TRUE"),
+            ("expressions/false", "FALSE"),
+        ];
+        let render = MockSqlTemplatesRender::try_new(templates).unwrap();
+        ParamsAllocator::new(PlanSqlTemplates::new(Rc::new(render)), inline_params)
+    }
+
+    fn values(params: &[TypedParam]) -> Vec<&str> {
+        params.iter().map(|p| p.value().as_str()).collect()
+    }
+
+    #[test]
+    fn params_are_numbered_in_sql_order() {
+        let mut allocator = allocator("${{ param_index + 1 }}", false);
+        let from = allocator.allocate_param("2024-01-01");
+        let to = allocator.allocate_param("2024-01-31");
+        let status = allocator.allocate_param("completed");
+        let sql = format!(
+            "status = {} AND created_at BETWEEN {} AND {}",
+            status, from, to
+        );
+        let (sql, params) = allocator.build_sql_and_params(&sql, vec![], false).unwrap();
+        assert_eq!(sql, "status = $1 AND created_at BETWEEN $2 AND $3");
+        assert_eq!(
+            values(&params),
+            vec!["completed", "2024-01-01", "2024-01-31"]
+        );
+    }
+
+    #[test]
+    fn repeated_params_are_reused_only_by_indexed_placeholders() {
+        let mut indexed = allocator("${{ param_index + 1 }}", false);
+        let value = indexed.allocate_param("a");
+        let sql = format!("x = {} OR y = {}", value, value);
+        let (result, params) = indexed.build_sql_and_params(&sql, vec![], true).unwrap();
+        assert_eq!(result, "x = $1 OR y = $1");
+        assert_eq!(values(&params), vec!["a"]);
+
+        let mut positional = allocator("?", false);
+        let value = positional.allocate_param("a");
+        let sql = format!("x = {} OR y = {}", value, value);
+        let (result, params) = positional.build_sql_and_params(&sql, vec![], true).unwrap();
+        assert_eq!(result, "x = ? OR y = ?");
+        assert_eq!(values(&params), vec!["a", "a"]);
+    }
+
+    #[test]
+    fn member_params_positions_follow_sql_order() {
+        let mut allocator = allocator("${{ param_index + 1 }}", false);
+        let amount =
+            allocator.allocate_typed_member_param("orders.amount", "10", ParamType::Number);
+        let status = allocator.allocate_member_param("orders.status", "new");
+        let other = allocator.allocate_member_param("orders.status", "done");
+        let sql = format!("{} {} {}", status, amount, other);
+        let (sql, params, member_params) = allocator
+            .build_sql_and_params_with_member_params(&sql, vec![], false)
+            .unwrap();
+        assert_eq!(sql, "$1 $2 $3");
+        assert_eq!(values(&params), vec!["new", "10", "done"]);
+        assert_eq!(params[1].param_type(), ParamType::Number);
+        assert_eq!(member_params["orders.status"], vec![0, 2]);
+        assert_eq!(member_params["orders.amount"], vec![1]);
+    }
+
+    #[test]
+    fn inlined_params_are_rendered_as_literals() {
+        let mut allocator = allocator("${{ param_index + 1 }}", true);
+        let amount = allocator.allocate_typed_param("10", ParamType::Number);
+        let name = allocator.allocate_param("O'Brien");
+        let sql = format!("amount > {} AND name = {}", amount, name);
+        let (sql, params) = allocator.build_sql_and_params(&sql, vec![], false).unwrap();
+        assert_eq!(sql, "amount > 10 AND name = 'O''Brien'");
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn native_params_are_appended_after_allocated_ones() {
+        let mut allocator = allocator("${{ param_index + 1 }}", false);
+        let value = allocator.allocate_param("a");
+        let sql = format!("x = $0$ AND y = {}", value);
+        let (sql, params) = allocator
+            .build_sql_and_params(&sql, vec!["native".to_string()], false)
+            .unwrap();
+        assert_eq!(sql, "x = $1 AND y = $2");
+        assert_eq!(values(&params), vec!["native", "a"]);
+    }
+
+    #[test]
+    fn named_placeholders_are_bound_once_per_name() {
+        let mut allocator = allocator("${{ param_index + 1 }}", true);
+        let value = allocator.allocate_param("a");
+        let from = allocator.allocate_named_placeholder("range_from").unwrap();
+        let sql = format!("x = {} AND y >= {} AND z >= {}", value, from, from);
+        let (result, params, placeholders) = allocator
+            .build_sql_and_params_with_bound_placeholders(&sql, vec![], true)
+            .unwrap();
+        assert_eq!(result, "x = 'a' AND y >= $1 AND z >= $1");
+        assert_eq!(params.len(), 1);
+        assert_eq!(placeholders["range_from"], vec![0]);
+
+        let (result, _) = allocator.build_sql_and_params(&sql, vec![], true).unwrap();
+        assert_eq!(result, "x = 'a' AND y >= :range_from AND z >= :range_from");
+        assert!(allocator.allocate_named_placeholder("1st").is_err());
+    }
+
+    #[test]
+    fn positional_placeholders_are_bound_at_every_position() {
+        let mut allocator = allocator("?", false);
+        let value = allocator.allocate_param("a");
+        let from = allocator.allocate_named_placeholder("range_from").unwrap();
+        let to = allocator.allocate_named_placeholder("range_to").unwrap();
+        let sql = format!(
+            "x >= {} AND x <= {} AND y = {} AND z >= {}",
+            from, to, value, from
+        );
+        let (result, params, placeholders) = allocator
+            .build_sql_and_params_with_bound_placeholders(&sql, vec![], true)
+            .unwrap();
+        assert_eq!(result, "x >= ? AND x <= ? AND y = ? AND z >= ?");
+        assert_eq!(values(&params), vec!["", "", "a", ""]);
+        assert_eq!(placeholders["range_from"], vec![0, 3]);
+        assert_eq!(placeholders["range_to"], vec![1]);
+    }
+}
@@ -0,0 +1,134 @@
+use cubenativeutils::CubeError;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PlannerFlag {
+    // Negative filters don't match NULL values, following SQL three-valued logic.
+    // By default `OR member IS NULL` is appended, e.g. to `notContains`
+    StrictNegativeNullChecks,
+    // Reproduces SQL shape of the legacy JS planner for byte-level regression comparison
+    LegacyJsCompatibility,
+    // Ordering comparisons with NULL are rendered as always false instead of failing the query
+    NullOrderingComparisonsAsFalse,
+    // Date ranges end with `< next_bound` instead of `<= 23:59:59.999`
+    ExclusiveTimeRangeEnd,
+}
+
+impl PlannerFlag {
+    pub fn all() -> Vec<PlannerFlag> {
+        vec![
+            PlannerFlag::StrictNegativeNullChecks,
+            PlannerFlag::LegacyJsCompatibility,
+            PlannerFlag::NullOrderingComparisonsAsFalse,
+            PlannerFlag::ExclusiveTimeRangeEnd,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlannerFlag::StrictNegativeNullChecks => "strict_negative_null_checks",
+            PlannerFlag::LegacyJsCompatibility => "legacy_js_compatibility",
+            PlannerFlag::NullOrderingComparisonsAsFalse => "null_ordering_comparisons_as_false",
+            PlannerFlag::ExclusiveTimeRangeEnd => "exclusive_time_range_end",
+        }
+    }
+
+    pub fn default_value(&self) -> bool {
+        match self {
+            PlannerFlag::StrictNegativeNullChecks => false,
+            PlannerFlag::LegacyJsCompatibility => false,
+            PlannerFlag::NullOrderingComparisonsAsFalse => false,
+            PlannerFlag::ExclusiveTimeRangeEnd => false,
+        }
+    }
+}
+
+impl FromStr for PlannerFlag {
+    type Err = CubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|flag| flag.name() == s)
+            .ok_or_else(|| CubeError::user(format!("Unknown planner flag: {}", s)))
+    }
+}
+
+pub struct PlannerFlags {
+    enabled: Vec<PlannerFlag>,
+}
+
+impl PlannerFlags {
+    // Flags are merged from deployment and request levels before being passed to the planner
+    pub fn try_new(flags: &Option<HashMap<String, bool>>) -> Result<Self, CubeError> {
+        let mut values = PlannerFlag::all()
+            .into_iter()
+            .map(|flag| (flag, flag.default_value()))
+            .collect::<HashMap<_, _>>();
+        if let Some(flags) = flags {
+            for (name, value) in flags.iter() {
+                values.insert(PlannerFlag::from_str(name)?, *value);
+            }
+        }
+        let enabled = PlannerFlag::all()
+            .into_iter()
+            .filter(|flag| values.get(flag).copied().unwrap_or(false))
+            .collect();
+        Ok(Self { enabled })
+    }
+
+    pub fn is_enabled(&self, flag: PlannerFlag) -> bool {
+        self.enabled.contains(&flag)
+    }
+
+    pub fn enabled_names(&self) -> Vec<String> {
+        self.enabled
+            .iter()
+            .map(|flag| flag.name().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_default_to_disabled() {
+        let flags = PlannerFlags::try_new(&None).unwrap();
+        assert!(PlannerFlag::all()
+            .into_iter()
+            .all(|flag| !flags.is_enabled(flag)));
+        assert!(flags.enabled_names().is_empty());
+    }
+
+    #[test]
+    fn flags_are_overridden_by_name() {
+        let flags = PlannerFlags::try_new(&Some(HashMap::from([
+            ("exclusive_time_range_end".to_string(), true),
+            ("strict_negative_null_checks".to_string(), true),
+            ("legacy_js_compatibility".to_string(), false),
+        ])))
+        .unwrap();
+        assert!(flags.is_enabled(PlannerFlag::StrictNegativeNullChecks));
+        assert!(flags.is_enabled(PlannerFlag::ExclusiveTimeRangeEnd));
+        assert!(!flags.is_enabled(PlannerFlag::LegacyJsCompatibility));
+        // Names are reported in declaration order to keep plan metadata stable
+        assert_eq!(
+            flags.enabled_names(),
+            vec!["strict_negative_null_checks", "exclusive_time_range_end"]
+        );
+    }
+
+    #[test]
+    fn unknown_flags_are_rejected() {
+        let err = PlannerFlags::try_new(&Some(HashMap::from([(
+            "new_join_elimination".to_string(),
+            true,
+        )])))
+        .err()
+        .unwrap();
+        assert_eq!(err.message, "Unknown planner flag: new_join_elimination");
+    }
+}
@@ -0,0 +1,76 @@
+use super::JoinPlanner;
+use crate::plan::{FilterItem, Select, SelectBuilder};
+use crate::planner::filter::FilterOperator;
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
+use crate::planner::{BaseDimension, BaseMember, QueryProperties};
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+pub struct DateRangeProbePlanner {
+    query_tools: Rc<QueryTools>,
+    query_properties: Rc<QueryProperties>,
+    join_planner: JoinPlanner,
+}
+
+impl DateRangeProbePlanner {
+    pub fn new(query_tools: Rc<QueryTools>, query_properties: Rc<QueryProperties>) -> Self {
+        Self {
+            join_planner: JoinPlanner::new(query_tools.clone()),
+            query_tools,
+            query_properties,
+        }
+    }
+
+    // Builds `SELECT MIN(dim), MAX(dim)` over the query filters.
+    // Date ranges of the probed dimension are dropped, as they are
+    // what the probe is used to resolve
+    pub fn plan(&self, dimension: Rc<BaseDimension>) -> Result<Select, CubeError> {
+        let dimension_name = dimension.full_name();
+        let time_dimensions_filters = self
+            .query_properties
+            .time_dimensions_filters()
+            .iter()
+            .filter(|item| match item {
+                FilterItem::Item(filter) => {
+                    !(filter.member_name() == dimension_name
+                        && matches!(filter.filter_operator(), FilterOperator::InDateRange))
+                }
+                FilterItem::Group(_) | FilterItem::Segment(_) => true,
+            })
+            .cloned()
+            .collect();
+        let probe_properties = QueryProperties::try_new_from_precompiled(
+            self.query_tools.clone(),
+            vec![],
+            vec![dimension.clone()],
+            vec![],
+            time_dimensions_filters,
+            self.query_properties.dimensions_filters().clone(),
+            vec![],
+            vec![],
+            None,
+            None,
+            false,
+            false,
+        )?;
+
+        let from = self
+            .join_planner
+            .make_join_node_impl(&None, probe_properties.simple_query_join()?)?;
+        let mut select_builder = SelectBuilder::new(from);
+        let member: Rc<dyn BaseMember> = dimension;
+        select_builder.add_projection_function_expression(
+            "MIN",
+            vec![member.clone()],
+            "min_value".to_string(),
+        );
+        select_builder.add_projection_function_expression(
+            "MAX",
+            vec![member],
+            "max_value".to_string(),
+        );
+        select_builder.set_filter(probe_properties.all_filters());
+        Ok(select_builder.build(SqlNodesFactory::new()))
+    }
+}
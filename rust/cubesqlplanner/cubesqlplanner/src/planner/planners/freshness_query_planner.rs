@@ -0,0 +1,85 @@
+use super::JoinPlanner;
+use crate::cube_bridge::memeber_sql::MemberSql;
+use crate::plan::SelectBuilder;
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
+use crate::planner::sql_templates::PlanSqlTemplates;
+use crate::planner::{evaluate_sql_call_with_context, BaseDimension, BaseMember, VisitorContext};
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+pub struct FreshnessQueryPlanner {
+    query_tools: Rc<QueryTools>,
+    join_planner: JoinPlanner,
+}
+
+impl FreshnessQueryPlanner {
+    pub fn new(query_tools: Rc<QueryTools>) -> Self {
+        Self {
+            join_planner: JoinPlanner::new(query_tools.clone()),
+            query_tools,
+        }
+    }
+
+    // Explicit refresh key sql takes precedence over `updatedAt` watermark.
+    // None is returned for cubes refreshed by `every` interval only
+    pub fn plan(
+        &self,
+        cube_name: &String,
+        templates: &PlanSqlTemplates,
+    ) -> Result<Option<String>, CubeError> {
+        let cube = self
+            .query_tools
+            .cube_evaluator()
+            .cube_from_path(cube_name.clone())?;
+        if let Some(refresh_key) = cube.refresh_key()? {
+            if let Some(sql) = refresh_key.sql()? {
+                return Ok(Some(self.refresh_key_sql(cube_name, sql)?));
+            }
+        }
+        if let Some(updated_at) = &cube.static_data().updated_at {
+            return Ok(Some(self.watermark_sql(cube_name, updated_at, templates)?));
+        }
+        Ok(None)
+    }
+
+    fn refresh_key_sql(
+        &self,
+        cube_name: &String,
+        sql: Rc<dyn MemberSql>,
+    ) -> Result<String, CubeError> {
+        let evaluator_compiler_cell = self.query_tools.evaluator_compiler().clone();
+        let sql_call = evaluator_compiler_cell
+            .borrow_mut()
+            .compile_sql_call(cube_name, sql)?;
+        let context = Rc::new(VisitorContext::new(&SqlNodesFactory::new()));
+        evaluate_sql_call_with_context(&sql_call, self.query_tools.clone(), context)
+    }
+
+    fn watermark_sql(
+        &self,
+        cube_name: &String,
+        updated_at: &String,
+        templates: &PlanSqlTemplates,
+    ) -> Result<String, CubeError> {
+        let evaluator_compiler_cell = self.query_tools.evaluator_compiler().clone();
+        let evaluator = evaluator_compiler_cell
+            .borrow_mut()
+            .add_dimension_evaluator(format!("{}.{}", cube_name, updated_at))?;
+        let dimension: Rc<dyn BaseMember> =
+            BaseDimension::try_new_required(evaluator, self.query_tools.clone())?;
+
+        let from = self
+            .join_planner
+            .make_join_node_with_prefix_and_join_hints(&None, vec![cube_name.clone()])?;
+        let mut select_builder = SelectBuilder::new(from);
+        select_builder.add_projection_function_expression(
+            "MAX",
+            vec![dimension],
+            "refresh_key".to_string(),
+        );
+        select_builder
+            .build(SqlNodesFactory::new())
+            .to_sql(templates)
+    }
+}
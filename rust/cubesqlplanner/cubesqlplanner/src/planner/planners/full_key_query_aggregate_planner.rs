@@ -2,6 +2,7 @@ use super::OrderPlanner;
 use crate::plan::{
     Cte, Expr, Filter, From, JoinBuilder, JoinCondition, QualifiedColumnName, Select, SelectBuilder,
 };
+use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
 use crate::planner::sql_evaluator::ReferencesBuilder;
 use crate::planner::sql_templates::PlanSqlTemplates;
@@ -14,6 +15,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 
 pub struct FullKeyAggregateQueryPlanner {
+    query_tools: Rc<QueryTools>,
     query_properties: Rc<QueryProperties>,
     order_planner: OrderPlanner,
     context_factory: SqlNodesFactory,
@@ -22,12 +24,14 @@ pub struct FullKeyAggregateQueryPlanner {
 
 impl FullKeyAggregateQueryPlanner {
     pub fn new(
+        query_tools: Rc<QueryTools>,
         query_properties: Rc<QueryProperties>,
         context_factory: SqlNodesFactory,
         // TODO get rid of this dependency
         plan_sql_templates: PlanSqlTemplates,
     ) -> Self {
         Self {
+            query_tools,
             order_planner: OrderPlanner::new(query_properties.clone()),
             query_properties,
             context_factory,
@@ -59,6 +63,7 @@ impl FullKeyAggregateQueryPlanner {
     ) -> Result<Select, CubeError> {
         let mut join_builder = JoinBuilder::new_from_subselect(joins[0].clone(), format!("q_0"));
         let dimensions_to_select = self.query_properties.dimensions_for_select();
+        let legacy_format = self.query_tools.legacy_js_compatibility();
         for (i, join) in joins.iter().enumerate().skip(1) {
             let right_alias = format!("q_{}", i);
             let left_schema = joins[i - 1].schema();
@@ -67,7 +72,9 @@ impl FullKeyAggregateQueryPlanner {
             let conditions = dimensions_to_select
                 .iter()
                 .map(|dim| {
-                    (0..i)
+                    // Legacy JS planner joins every subquery only to the previous one
+                    let left_range = if legacy_format { (i - 1)..i } else { 0..i };
+                    left_range
                         .map(|left_i| {
                             let left_alias = format!("q_{}", left_i);
                             let alias_in_left_query = left_schema.resolve_member_alias(dim);
@@ -85,9 +92,9 @@ impl FullKeyAggregateQueryPlanner {
                         .collect::<Vec<_>>()
                 })
                 .collect_vec();
-            let on = JoinCondition::new_dimension_join(conditions, true);
+            let on = JoinCondition::new_dimension_join(conditions, true, legacy_format);
             let next_alias = format!("q_{}", i);
-            if self.plan_sql_templates.supports_full_join() {
+            if !legacy_format && self.plan_sql_templates.supports_full_join() {
                 join_builder.full_join_subselect(join.clone(), next_alias, on);
             } else {
                 // TODO in case of full join is not supported there should be correct blending query that keeps NULL values
@@ -140,18 +147,19 @@ impl FullKeyAggregateQueryPlanner {
             select_builder.add_projection_member(&member, alias);
         }
 
-        let having = if self.query_properties.measures_filters().is_empty() {
-            None
-        } else {
-            let filter = Filter {
-                items: self.query_properties.measures_filters().clone(),
-            };
+        // Measures are aggregated by the subqueries and the outer query isn't grouped,
+        // so measure filters are applied in its WHERE
+        let filter = if let Some(filter) =
+            Filter::from_items(self.query_properties.measures_filters().clone())
+        {
             references_builder.resolve_references_for_filter(&filter, &mut render_references)?;
             Some(filter)
+        } else {
+            None
         };
 
         select_builder.set_order_by(self.order_planner.default_order());
-        select_builder.set_having(having);
+        select_builder.set_filter(filter);
         select_builder.set_limit(self.query_properties.row_limit());
         select_builder.set_offset(self.query_properties.offset());
         if !ctes.is_empty() {
@@ -1,10 +1,12 @@
 use super::CommonUtils;
 use crate::cube_bridge::join_definition::JoinDefinition;
 use crate::cube_bridge::memeber_sql::MemberSql;
-use crate::plan::{From, JoinBuilder, JoinCondition};
+use crate::plan::{From, JoinBuilder, JoinCondition, Schema};
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::SqlCall;
-use crate::planner::SqlJoinCondition;
+use crate::planner::{
+    AsOfJoinCondition, CurrencyRatesJoin, SnapshotJoinCondition, SqlJoinCondition,
+};
 use cubenativeutils::CubeError;
 use std::rc::Rc;
 
@@ -35,10 +37,28 @@ impl JoinPlanner {
         alias_prefix: &Option<String>,
         join: Rc<dyn JoinDefinition>,
     ) -> Result<Rc<From>, CubeError> {
+        self.make_join_node_with_currency_rates(alias_prefix, join, &vec![])
+    }
+
+    pub fn make_join_node_with_currency_rates(
+        &self,
+        alias_prefix: &Option<String>,
+        join: Rc<dyn JoinDefinition>,
+        currency_rates_joins: &Vec<Rc<CurrencyRatesJoin>>,
+    ) -> Result<Rc<From>, CubeError> {
+        self.query_tools.check_planning_stage("join planning")?;
         let root = self.utils.cube_from_path(join.static_data().root.clone())?;
         let joins = join.joins()?;
-        if joins.items().is_empty() {
-            Ok(From::new_from_cube(root, None))
+        self.query_tools.record_planning_progress(format!(
+            "planned join from {} with {} joined cubes",
+            join.static_data().root,
+            joins.items().len()
+        ));
+        if joins.items().is_empty() && currency_rates_joins.is_empty() {
+            Ok(From::new_from_cube(
+                root.clone(),
+                Some(root.default_alias_with_prefix(alias_prefix)),
+            ))
         } else {
             let mut join_builder = JoinBuilder::new_from_cube(
                 root.clone(),
@@ -48,17 +68,44 @@ impl JoinPlanner {
                 let definition = join.join()?;
                 let sql_call = self
                     .compile_join_condition(&join.static_data().original_from, definition.sql()?)?;
-                let on = JoinCondition::new_base_join(SqlJoinCondition::try_new(
-                    self.query_tools.clone(),
-                    sql_call,
-                )?);
+                let condition = SqlJoinCondition::try_new(self.query_tools.clone(), sql_call)?;
                 let cube = self
                     .utils
                     .cube_from_path(join.static_data().original_to.clone())?;
-                join_builder.left_join_cube(
-                    cube.clone(),
-                    Some(cube.default_alias_with_prefix(alias_prefix)),
-                    on,
+                let alias = cube.default_alias_with_prefix(alias_prefix);
+                let on = if let (Some(time), Some(match_time)) =
+                    (definition.as_of_time()?, definition.as_of_match_time()?)
+                {
+                    let from_cube = self
+                        .utils
+                        .cube_from_path(join.static_data().original_from.clone())?;
+                    let from_keys = self.utils.primary_keys_dimensions(from_cube.name())?;
+                    JoinCondition::new_as_of_join(AsOfJoinCondition::try_new(
+                        self.query_tools.clone(),
+                        condition,
+                        self.compile_join_condition(&join.static_data().original_from, time)?,
+                        self.compile_join_condition(&join.static_data().original_to, match_time)?,
+                        from_cube.clone(),
+                        from_cube.default_alias_with_prefix(alias_prefix),
+                        from_keys,
+                    )?)
+                } else {
+                    self.snapshot_join_condition(
+                        &join.static_data().original_from,
+                        &join.static_data().original_to,
+                        definition.valid_at()?,
+                        &alias,
+                        condition,
+                    )?
+                };
+                join_builder.left_join_cube(cube.clone(), Some(alias), on);
+            }
+            for rates_join in currency_rates_joins.iter() {
+                join_builder.left_join_table_reference(
+                    rates_join.rates_source_sql()?,
+                    Rc::new(Schema::empty()),
+                    Some(rates_join.alias().clone()),
+                    JoinCondition::new_base_join(rates_join.clone()),
                 );
             }
             let result = From::new_from_join(join_builder.build());
@@ -66,6 +113,40 @@ impl JoinPlanner {
         }
     }
 
+    // Joins to snapshot cubes are matched by time of the joining row unless
+    // the query is a point in time one, which already filters snapshot cubes
+    fn snapshot_join_condition(
+        &self,
+        from: &String,
+        to: &String,
+        valid_at: Option<Rc<dyn MemberSql>>,
+        alias: &String,
+        condition: Rc<SqlJoinCondition>,
+    ) -> Result<JoinCondition, CubeError> {
+        let validity = self
+            .query_tools
+            .cube_evaluator()
+            .cube_from_path(to.clone())?
+            .static_data()
+            .validity
+            .clone();
+        match (validity, valid_at) {
+            (Some(validity), Some(valid_at)) if self.query_tools.as_of().is_none() => {
+                let valid_at = self.compile_join_condition(from, valid_at)?;
+                Ok(JoinCondition::new_base_join(
+                    SnapshotJoinCondition::try_new(
+                        self.query_tools.clone(),
+                        condition,
+                        valid_at,
+                        alias.clone(),
+                        validity,
+                    )?,
+                ))
+            }
+            _ => Ok(JoinCondition::new_base_join(condition)),
+        }
+    }
+
     fn compile_join_condition(
         &self,
         cube_name: &String,
@@ -1,4 +1,6 @@
 pub mod common_utils;
+pub mod date_range_probe_planner;
+pub mod freshness_query_planner;
 pub mod full_key_query_aggregate_planner;
 pub mod join_planner;
 pub mod multi_stage;
@@ -6,11 +8,15 @@ pub mod multi_stage_query_planner;
 pub mod multiplied_measures_query_planner;
 pub mod order_planner;
 pub mod simple_query_planer;
+pub mod sub_query_dimension_planner;
 
 pub use common_utils::CommonUtils;
+pub use date_range_probe_planner::DateRangeProbePlanner;
+pub use freshness_query_planner::FreshnessQueryPlanner;
 pub use full_key_query_aggregate_planner::FullKeyAggregateQueryPlanner;
 pub use join_planner::JoinPlanner;
 pub use multi_stage_query_planner::MultiStageQueryPlanner;
 pub use multiplied_measures_query_planner::MultipliedMeasuresQueryPlanner;
 pub use order_planner::OrderPlanner;
 pub use simple_query_planer::SimpleQueryPlanner;
+pub use sub_query_dimension_planner::{SubQueryDimensionPlanner, SubQueryDimensionSource};
@@ -1,7 +1,9 @@
+use crate::plan::filter::FilterGroupOperator;
 use crate::plan::{FilterGroup, FilterItem};
 use crate::planner::filter::FilterOperator;
 use crate::planner::planners::multi_stage::MultiStageTimeShift;
 use crate::planner::{BaseDimension, BaseTimeDimension};
+use cubenativeutils::CubeError;
 use itertools::Itertools;
 use std::cmp::PartialEq;
 use std::collections::HashMap;
@@ -15,7 +17,7 @@ pub struct MultiStageAppliedState {
     time_dimensions_filters: Vec<FilterItem>,
     dimensions_filters: Vec<FilterItem>,
     measures_filters: Vec<FilterItem>,
-    time_shifts: HashMap<String, String>,
+    time_shifts: HashMap<String, Vec<String>>,
 }
 
 impl MultiStageAppliedState {
@@ -57,14 +59,23 @@ impl MultiStageAppliedState {
             .collect_vec();
     }
 
-    pub fn add_time_shifts(&mut self, time_shifts: Vec<MultiStageTimeShift>) {
+    // Shifts of the same time dimension are accumulated, so a measure level
+    // shift is applied on top of the query level one
+    pub fn add_time_shifts(
+        &mut self,
+        time_shifts: Vec<MultiStageTimeShift>,
+    ) -> Result<(), CubeError> {
         for ts in time_shifts.into_iter() {
-            self.time_shifts
-                .insert(ts.time_dimension.clone(), ts.interval.clone());
+            ts.add_to(
+                self.time_shifts
+                    .entry(ts.time_dimension.clone())
+                    .or_default(),
+            )?;
         }
+        Ok(())
     }
 
-    pub fn time_shifts(&self) -> &HashMap<String, String> {
+    pub fn time_shifts(&self) -> &HashMap<String, Vec<String>> {
         &self.time_shifts
     }
 
@@ -116,24 +127,48 @@ impl MultiStageAppliedState {
         member_name: &String,
         filters: &Vec<FilterItem>,
     ) -> Vec<FilterItem> {
-        let mut result = Vec::new();
-        for item in filters.iter() {
-            match item {
-                FilterItem::Group(group) => {
-                    let new_group = FilterItem::Group(Rc::new(FilterGroup::new(
+        filters
+            .iter()
+            .filter_map(|item| self.exclude_member_from_item(member_name, item))
+            .collect()
+    }
+
+    // Filter of the excluded member is replaced with true: an `and` group loses it,
+    // an `or` group becomes true as a whole instead of being narrowed to other branches
+    fn exclude_member_from_item(
+        &self,
+        member_name: &String,
+        item: &FilterItem,
+    ) -> Option<FilterItem> {
+        match item {
+            FilterItem::Group(group) => {
+                let items = group
+                    .items
+                    .iter()
+                    .map(|itm| self.exclude_member_from_item(member_name, itm))
+                    .collect::<Vec<_>>();
+                let items = match group.operator {
+                    FilterGroupOperator::And => items.into_iter().flatten().collect::<Vec<_>>(),
+                    FilterGroupOperator::Or => items.into_iter().collect::<Option<Vec<_>>>()?,
+                };
+                if items.is_empty() {
+                    None
+                } else {
+                    Some(FilterItem::Group(Rc::new(FilterGroup::new(
                         group.operator.clone(),
-                        self.extract_filters_exclude_member(member_name, &group.items),
-                    )));
-                    result.push(new_group);
+                        items,
+                    ))))
                 }
-                FilterItem::Item(itm) => {
-                    if &itm.member_name() != member_name {
-                        result.push(FilterItem::Item(itm.clone()));
-                    }
+            }
+            FilterItem::Item(itm) => {
+                if &itm.member_name() != member_name {
+                    Some(FilterItem::Item(itm.clone()))
+                } else {
+                    None
                 }
             }
+            FilterItem::Segment(_) => Some(item.clone()),
         }
-        result
     }
 
     pub fn has_filters_for_member(&self, member_name: &String) -> bool {
@@ -155,6 +190,7 @@ impl MultiStageAppliedState {
                         return true;
                     }
                 }
+                FilterItem::Segment(_) => {}
             }
         }
         false
@@ -189,7 +225,7 @@ impl MultiStageAppliedState {
                         group.operator.clone(),
                         self.expand_date_range_filter_impl(
                             member_name,
-                            filters,
+                            &group.items,
                             left_interval,
                             right_interval,
                         ),
@@ -209,6 +245,7 @@ impl MultiStageAppliedState {
                     };
                     result.push(FilterItem::Item(itm));
                 }
+                FilterItem::Segment(_) => result.push(item.clone()),
             }
         }
         result
@@ -19,42 +19,71 @@ lazy_static! {
 }
 impl MultiStageTimeShift {
     pub fn try_from_reference(reference: &TimeShiftReference) -> Result<Self, CubeError> {
-        let parsed_interval =
-            if let Some(captures) = INTERVAL_MATCH_RE.captures(&reference.interval) {
-                let duration = if let Some(duration) = captures.get(1) {
-                    duration.as_str().parse::<i64>().ok()
-                } else {
-                    None
-                };
-                let granularity = if let Some(granularity) = captures.get(2) {
-                    Some(granularity.as_str().to_owned())
-                } else {
-                    None
-                };
-                if let Some((duration, granularity)) = duration.zip(granularity) {
-                    Some((duration, granularity))
-                } else {
-                    None
-                }
+        let (duration, granularity) = Self::parse_interval(&reference.interval)?;
+        let duration = if reference.shift_type.as_ref().unwrap_or(&format!("prior")) == "next" {
+            duration * (-1)
+        } else {
+            duration
+        };
+
+        Ok(Self {
+            interval: format!("{duration} {granularity}"),
+            time_dimension: reference.time_dimension.clone(),
+        })
+    }
+
+    pub fn try_from_interval(time_dimension: String, interval: &String) -> Result<Self, CubeError> {
+        let (duration, granularity) = Self::parse_interval(interval)?;
+        Ok(Self {
+            interval: format!("{duration} {granularity}"),
+            time_dimension,
+        })
+    }
+
+    // Shifts of the same time dimension are summed per granularity, so each granularity
+    // is added to the time dimension once, e.g. `1 year` and `-1 month` stay apart
+    // while `1 year` and `1 year` are added as `2 year`
+    pub fn add_to(&self, intervals: &mut Vec<String>) -> Result<(), CubeError> {
+        let (duration, granularity) = Self::parse_interval(&self.interval)?;
+        let mut summed = false;
+        for interval in intervals.iter_mut() {
+            let (interval_duration, interval_granularity) = Self::parse_interval(interval)?;
+            if interval_granularity == granularity {
+                *interval = format!("{} {granularity}", interval_duration + duration);
+                summed = true;
+            }
+        }
+        if !summed {
+            intervals.push(self.interval.clone());
+        }
+        intervals.retain(|interval| !interval.starts_with("0 "));
+        Ok(())
+    }
+
+    fn parse_interval(interval: &String) -> Result<(i64, String), CubeError> {
+        let parsed_interval = if let Some(captures) = INTERVAL_MATCH_RE.captures(interval) {
+            let duration = if let Some(duration) = captures.get(1) {
+                duration.as_str().parse::<i64>().ok()
             } else {
                 None
             };
-        if let Some((duration, granularity)) = parsed_interval {
-            let duration = if reference.shift_type.as_ref().unwrap_or(&format!("prior")) == "next" {
-                duration * (-1)
+            let granularity = if let Some(granularity) = captures.get(2) {
+                Some(granularity.as_str().to_owned())
             } else {
-                duration
+                None
             };
-
-            Ok(Self {
-                interval: format!("{duration} {granularity}"),
-                time_dimension: reference.time_dimension.clone(),
-            })
+            if let Some((duration, granularity)) = duration.zip(granularity) {
+                Some((duration, granularity))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        if let Some(parsed_interval) = parsed_interval {
+            Ok(parsed_interval)
         } else {
-            Err(CubeError::user(format!(
-                "Invalid interval: {}",
-                reference.interval
-            )))
+            Err(CubeError::user(format!("Invalid interval: {}", interval)))
         }
     }
 }
@@ -171,4 +200,87 @@ impl MultiStageMember {
     pub fn full_name(&self) -> String {
         self.evaluation_node.full_name()
     }
+
+    // Kind of the stage along with its parameters. Stages of the same kind for the same member
+    // over the same state and inputs compute the same rows
+    pub fn stage_kind(&self) -> String {
+        match &self.member_type {
+            MultiStageMemberType::Leaf(MultiStageLeafMemberType::Measure) => "measure".to_string(),
+            MultiStageMemberType::Leaf(MultiStageLeafMemberType::TimeSeries(time_dimension)) => {
+                format!(
+                    "time_series({}, {:?})",
+                    time_dimension.full_name(),
+                    time_dimension.get_granularity()
+                )
+            }
+            MultiStageMemberType::Inode(inode) => {
+                let inode_type = match inode.inode_type() {
+                    MultiStageInodeMemberType::Rank => "rank".to_string(),
+                    MultiStageInodeMemberType::Aggregate => "aggregate".to_string(),
+                    MultiStageInodeMemberType::Calculate => "calculate".to_string(),
+                    MultiStageInodeMemberType::RollingWindow(rolling_window) => format!(
+                        "rolling_window({}, {:?}, {:?}, {})",
+                        rolling_window.time_dimension.full_name(),
+                        rolling_window.trailing,
+                        rolling_window.leading,
+                        rolling_window.offset
+                    ),
+                    MultiStageInodeMemberType::RunningTotal(running_total) => format!(
+                        "running_total({})",
+                        running_total.time_dimension.full_name()
+                    ),
+                };
+                format!(
+                    "{}(reduce_by: {:?}, add_group_by: {:?}, group_by: {:?}, ungrouped: {})",
+                    inode_type,
+                    inode.reduce_by(),
+                    inode.add_group_by(),
+                    inode.group_by(),
+                    inode.is_ungrupped()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(intervals: Vec<&str>) -> Vec<String> {
+        let mut result = Vec::new();
+        for interval in intervals {
+            MultiStageTimeShift::try_from_interval(
+                "orders.created_at".to_string(),
+                &interval.to_string(),
+            )
+            .unwrap()
+            .add_to(&mut result)
+            .unwrap();
+        }
+        result
+    }
+
+    #[test]
+    fn shifts_of_same_granularity_are_summed() {
+        assert_eq!(stack(vec!["1 year", "1 year"]), vec!["2 year"]);
+        assert_eq!(stack(vec!["1 month", "2 months"]), vec!["3 month"]);
+    }
+
+    #[test]
+    fn shifts_of_other_granularities_stay_apart() {
+        assert_eq!(
+            stack(vec!["1 year", "-1 month", "1 month", "2 day"]),
+            vec!["1 year", "2 day"]
+        );
+        assert_eq!(
+            stack(vec!["1 year", "-1 month"]),
+            vec!["1 year", "-1 month"]
+        );
+    }
+
+    #[test]
+    fn opposite_shifts_cancel_out() {
+        assert_eq!(stack(vec!["1 year", "-1 year"]), Vec::<String>::new());
+    }
 }
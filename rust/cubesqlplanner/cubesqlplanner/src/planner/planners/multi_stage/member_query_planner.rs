@@ -291,7 +291,11 @@ impl MultiStageMemberQueryPlanner {
                         .collect()
                 })
                 .collect_vec();
-            let on = JoinCondition::new_dimension_join(conditions, true);
+            let on = JoinCondition::new_dimension_join(
+                conditions,
+                true,
+                self.query_tools.legacy_js_compatibility(),
+            );
             join_builder.inner_join_table_reference(
                 input.clone(),
                 cte_schema,
@@ -385,6 +389,7 @@ impl MultiStageMemberQueryPlanner {
                 node_factory.clone(),
             );
             let full_key_aggregate_planner = FullKeyAggregateQueryPlanner::new(
+                self.query_tools.clone(),
                 cte_query_properties.clone(),
                 node_factory.clone(),
                 PlanSqlTemplates::new(self.query_tools.templates_render()),
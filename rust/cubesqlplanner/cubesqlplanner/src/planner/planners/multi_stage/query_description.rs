@@ -1,5 +1,7 @@
 use super::{MultiStageAppliedState, MultiStageMember};
 use crate::planner::sql_evaluator::MemberSymbol;
+use crate::planner::BaseMember;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::rc::Rc;
 
@@ -67,6 +69,31 @@ impl MultiStageQueryDescription {
         self.input.is_empty()
     }
 
+    // Stages are the same if they compute the same member of the same kind over the same
+    // state from the same inputs. Inputs are compared by the aliases of the stages planned
+    // in their place, see `MultiStageQueryPlanner::plan_queries`
+    pub fn is_same_stage(&self, other: &Self, planned_aliases: &HashMap<String, String>) -> bool {
+        let planned_alias = |alias: &String| planned_aliases.get(alias).unwrap_or(alias).clone();
+        self.member_name() == other.member_name()
+            && self.member.stage_kind() == other.member.stage_kind()
+            && self.state == other.state
+            && self.state.time_dimensions().len() == other.state.time_dimensions().len()
+            && self
+                .state
+                .time_dimensions()
+                .iter()
+                .zip(other.state.time_dimensions().iter())
+                .all(|(a, b)| {
+                    a.full_name() == b.full_name() && a.get_granularity() == b.get_granularity()
+                })
+            && self.input.len() == other.input.len()
+            && self
+                .input
+                .iter()
+                .zip(other.input.iter())
+                .all(|(a, b)| planned_alias(a.alias()) == planned_alias(b.alias()))
+    }
+
     pub fn is_match_member_and_state(
         &self,
         member_node: &Rc<MemberSymbol>,
@@ -75,3 +102,132 @@ impl MultiStageQueryDescription {
         member_node.full_name() == self.member_name() && state == &self.state
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        MultiStageInodeMember, MultiStageInodeMemberType, MultiStageLeafMemberType,
+        MultiStageMemberType, MultiStageTimeShift,
+    };
+    use super::*;
+    use crate::planner::test_fixtures::{dimension, dimension_definition};
+
+    fn empty_state() -> Rc<MultiStageAppliedState> {
+        MultiStageAppliedState::new(vec![], vec![], vec![], vec![], vec![])
+    }
+
+    fn leaf(
+        name: &str,
+        state: Rc<MultiStageAppliedState>,
+        alias: &str,
+    ) -> Rc<MultiStageQueryDescription> {
+        MultiStageQueryDescription::new(
+            MultiStageMember::new(
+                MultiStageMemberType::Leaf(MultiStageLeafMemberType::Measure),
+                dimension(name, dimension_definition("number")),
+            ),
+            state,
+            vec![],
+            alias.to_string(),
+        )
+    }
+
+    fn ratio(
+        name: &str,
+        input: Vec<Rc<MultiStageQueryDescription>>,
+        alias: &str,
+    ) -> Rc<MultiStageQueryDescription> {
+        MultiStageQueryDescription::new(
+            MultiStageMember::new(
+                MultiStageMemberType::Inode(MultiStageInodeMember::new(
+                    MultiStageInodeMemberType::Calculate,
+                    vec![],
+                    vec![],
+                    None,
+                    vec![],
+                    true,
+                )),
+                dimension(name, dimension_definition("number")),
+            ),
+            empty_state(),
+            input,
+            alias.to_string(),
+        )
+    }
+
+    #[test]
+    fn two_ratio_measures_share_the_denominator_stage() {
+        let total = leaf("orders.total", empty_state(), "cte_0");
+        let ratio_a = ratio(
+            "orders.ratio_a",
+            vec![
+                leaf("orders.amount_a", empty_state(), "cte_1"),
+                total.clone(),
+            ],
+            "cte_2",
+        );
+        let total_again = leaf("orders.total", empty_state(), "cte_3");
+        let ratio_b = ratio(
+            "orders.ratio_b",
+            vec![
+                leaf("orders.amount_b", empty_state(), "cte_4"),
+                total_again.clone(),
+            ],
+            "cte_5",
+        );
+        let planned_aliases = HashMap::from([("cte_3".to_string(), "cte_0".to_string())]);
+
+        assert!(total.is_same_stage(&total_again, &planned_aliases));
+        assert!(!ratio_a.is_same_stage(&ratio_b, &planned_aliases));
+        assert!(
+            !leaf("orders.amount_a", empty_state(), "cte_1").is_same_stage(
+                &leaf("orders.amount_b", empty_state(), "cte_4"),
+                &planned_aliases
+            )
+        );
+    }
+
+    #[test]
+    fn same_member_is_the_same_stage_only_for_the_same_inputs() {
+        let ratio_a = ratio(
+            "orders.ratio_a",
+            vec![
+                leaf("orders.amount_a", empty_state(), "cte_0"),
+                leaf("orders.total", empty_state(), "cte_1"),
+            ],
+            "cte_2",
+        );
+        let ratio_a_again = ratio(
+            "orders.ratio_a",
+            vec![
+                leaf("orders.amount_a", empty_state(), "cte_3"),
+                leaf("orders.total", empty_state(), "cte_4"),
+            ],
+            "cte_5",
+        );
+
+        assert!(!ratio_a.is_same_stage(&ratio_a_again, &HashMap::new()));
+        let planned_aliases = HashMap::from([
+            ("cte_3".to_string(), "cte_0".to_string()),
+            ("cte_4".to_string(), "cte_1".to_string()),
+        ]);
+        assert!(ratio_a.is_same_stage(&ratio_a_again, &planned_aliases));
+    }
+
+    #[test]
+    fn stage_kind_and_state_distinguish_stages() {
+        let total = leaf("orders.total", empty_state(), "cte_0");
+        let total_calculated = ratio("orders.total", vec![], "cte_1");
+        assert!(!total.is_same_stage(&total_calculated, &HashMap::new()));
+
+        let mut shifted_state = empty_state().clone_state();
+        shifted_state
+            .add_time_shifts(vec![MultiStageTimeShift {
+                interval: "1 year".to_string(),
+                time_dimension: "orders.created_at".to_string(),
+            }])
+            .unwrap();
+        let shifted_total = leaf("orders.total", Rc::new(shifted_state), "cte_2");
+        assert!(!total.is_same_stage(&shifted_total, &HashMap::new()));
+    }
+}
@@ -11,10 +11,11 @@ use crate::planner::sql_evaluator::collectors::member_childs;
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
 use crate::planner::sql_evaluator::MemberSymbol;
 use crate::planner::{BaseDimension, BaseMeasure};
-use crate::planner::{BaseTimeDimension, GranularityHelper, QueryProperties};
+use crate::planner::{BaseTimeDimension, GranularityHelper, PlanningMemoryBudget, QueryProperties};
 use cubenativeutils::CubeError;
 use itertools::Itertools;
 use std::collections::HashMap;
+use std::mem::size_of;
 use std::rc::Rc;
 
 pub struct MultiStageQueryPlanner {
@@ -46,13 +47,16 @@ impl MultiStageQueryPlanner {
             return Ok((vec![], vec![]));
         }
         let mut descriptions = Vec::new();
-        let state = MultiStageAppliedState::new(
+        let mut state = MultiStageAppliedState::new(
             self.query_properties.time_dimensions().clone(),
             self.query_properties.dimensions().clone(),
             self.query_properties.time_dimensions_filters().clone(),
             self.query_properties.dimensions_filters().clone(),
             self.query_properties.measures_filters().clone(),
-        );
+        )
+        .clone_state();
+        state.add_time_shifts(self.query_properties.time_shifts().clone())?;
+        let state = Rc::new(state);
 
         let top_level_ctes = multi_stage_members
             .into_iter()
@@ -68,16 +72,39 @@ impl MultiStageQueryPlanner {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        self.query_tools.reserve_planning_memory(
+            "multi-stage stages",
+            descriptions.len() * size_of::<MultiStageQueryDescription>()
+                + PlanningMemoryBudget::strings_size(descriptions.iter().map(|d| d.alias())),
+        )?;
+
         let mut cte_schemas = HashMap::new();
+        let mut planned_stages: Vec<Rc<MultiStageQueryDescription>> = Vec::new();
+        let mut planned_aliases: HashMap<String, String> = HashMap::new();
         let all_queries = descriptions
             .into_iter()
             .map(|descr| -> Result<_, CubeError> {
+                // Identical stage is computed once and referenced by all its consumers
+                if let Some(first) = planned_stages
+                    .iter()
+                    .find(|stage| stage.is_same_stage(&descr, &planned_aliases))
+                {
+                    let first_alias = first.alias().clone();
+                    let schema = cte_schemas.get(&first_alias).unwrap().clone();
+                    cte_schemas.insert(descr.alias().clone(), schema);
+                    planned_aliases.insert(descr.alias().clone(), first_alias.clone());
+                    return Ok(Rc::new(Cte::new_from_select(
+                        self.cte_select(&first_alias, &cte_schemas),
+                        descr.alias().clone(),
+                    )));
+                }
                 let res = MultiStageMemberQueryPlanner::new(
                     self.query_tools.clone(),
                     self.query_properties.clone(),
                     descr.clone(),
                 )
                 .plan_query(&cte_schemas)?;
+                planned_stages.push(descr.clone());
                 cte_schemas.insert(descr.alias().clone(), res.query().schema());
                 Ok(res)
             })
@@ -365,7 +392,7 @@ impl MultiStageQueryPlanner {
                     new_state.add_dimensions(dimensions_to_add);
                 }
                 if !multi_stage_member.time_shifts().is_empty() {
-                    new_state.add_time_shifts(multi_stage_member.time_shifts().clone());
+                    new_state.add_time_shifts(multi_stage_member.time_shifts().clone())?;
                 }
                 if state.has_filters_for_member(&member_name) {
                     new_state.remove_filter_for_member(&member_name);
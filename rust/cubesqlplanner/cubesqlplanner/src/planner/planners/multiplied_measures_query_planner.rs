@@ -10,7 +10,9 @@ use crate::planner::sql_evaluator::collectors::{
 };
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
 use crate::planner::sql_evaluator::ReferencesBuilder;
-use crate::planner::{BaseMeasure, BaseMember, BaseMemberHelper, QueryProperties};
+use crate::planner::{
+    BaseMeasure, BaseMember, BaseMemberHelper, CurrencyConversion, QueryProperties,
+};
 use cubenativeutils::CubeError;
 use itertools::Itertools;
 use std::collections::HashMap;
@@ -21,6 +23,7 @@ pub struct MultipliedMeasuresQueryPlanner {
     query_properties: Rc<QueryProperties>,
     join_planner: JoinPlanner,
     common_utils: CommonUtils,
+    currency_conversion: CurrencyConversion,
     context_factory: SqlNodesFactory,
 }
 
@@ -34,6 +37,7 @@ impl MultipliedMeasuresQueryPlanner {
             query_tools: query_tools.clone(),
             join_planner: JoinPlanner::new(query_tools.clone()),
             common_utils: CommonUtils::new(query_tools.clone()),
+            currency_conversion: CurrencyConversion::new(query_tools.clone()),
             query_properties,
             context_factory,
         }
@@ -68,12 +72,23 @@ impl MultipliedMeasuresQueryPlanner {
             }
         }
 
-        for (cube_name, measures) in measures
+        let mut measures_by_cube = measures
             .multiplied_measures
             .clone()
             .into_iter()
             .into_group_map_by(|m| m.cube_name().clone())
-        {
+            .into_iter()
+            .collect_vec();
+        // Keep subqueries in the order of cubes in the query instead of hash map order
+        let cube_names = measures
+            .multiplied_measures
+            .iter()
+            .map(|m| m.cube_name().clone())
+            .unique()
+            .collect_vec();
+        measures_by_cube
+            .sort_by_key(|(cube_name, _)| cube_names.iter().position(|c| c == cube_name));
+        for (cube_name, measures) in measures_by_cube {
             let join_multi_fact_groups = self
                 .query_properties
                 .compute_join_multi_fact_groups_with_measures(&measures)?;
@@ -153,7 +168,11 @@ impl MultipliedMeasuresQueryPlanner {
             join_builder.left_join_subselect(
                 subquery,
                 pk_cube_alias.clone(),
-                JoinCondition::new_dimension_join(conditions, false),
+                JoinCondition::new_dimension_join(
+                    conditions,
+                    false,
+                    self.query_tools.legacy_js_compatibility(),
+                ),
             );
         } else {
             let conditions = primary_keys_dimensions
@@ -171,7 +190,11 @@ impl MultipliedMeasuresQueryPlanner {
             join_builder.left_join_cube(
                 pk_cube.clone(),
                 Some(pk_cube_alias.clone()),
-                JoinCondition::new_dimension_join(conditions, false),
+                JoinCondition::new_dimension_join(
+                    conditions,
+                    false,
+                    self.query_tools.legacy_js_compatibility(),
+                ),
             );
         };
 
@@ -264,12 +287,19 @@ impl MultipliedMeasuresQueryPlanner {
         join: Rc<dyn JoinDefinition>,
         alias_prefix: String,
     ) -> Result<Rc<Select>, CubeError> {
-        let source = self
-            .join_planner
-            .make_join_node_impl(&Some(alias_prefix), join)?;
+        let alias_prefix = Some(alias_prefix);
+        let (currency_rates_joins, currency_rates_references) = self
+            .currency_conversion
+            .plan_rates_joins(measures, &alias_prefix)?;
+        let source = self.join_planner.make_join_node_with_currency_rates(
+            &alias_prefix,
+            join,
+            &currency_rates_joins,
+        )?;
 
         let mut select_builder = SelectBuilder::new(source.clone());
         let mut context_factory = self.context_factory.clone();
+        context_factory.set_currency_rates_references(currency_rates_references);
         for time_dim in self.query_properties.time_dimensions() {
             if let Some(granularity) = time_dim.get_granularity() {
                 context_factory.add_leaf_time_dimension(&time_dim.full_name(), &granularity);
@@ -24,18 +24,38 @@ impl OrderPlanner {
     ) -> Vec<OrderBy> {
         let mut result = Vec::new();
         for itm in order_by.iter() {
+            let name = itm.name().to_lowercase();
             if let Some((pos, member)) = members
                 .iter()
                 .enumerate()
-                .find(|(_, m)| m.full_name().to_lowercase() == itm.name().to_lowercase())
+                .filter_map(|(pos, m)| Self::match_rank(m, &name).map(|rank| (rank, pos, m)))
+                .min_by_key(|(rank, pos, _)| (*rank, *pos))
+                .map(|(_, pos, m)| (pos, m))
             {
-                result.push(OrderBy::new(
-                    Expr::Member(MemberExpression::new(member.clone())),
-                    pos + 1,
-                    itm.desc(),
-                ));
+                result.push(
+                    OrderBy::new(
+                        Expr::Member(MemberExpression::new(member.clone())),
+                        pos + 1,
+                        itm.desc(),
+                    )
+                    .with_nulls_first(itm.nulls_first()),
+                );
             }
         }
         result
     }
+
+    // Time dimensions with granularity are preferred over the same raw dimension,
+    // so rows are ordered by bucket boundaries and not by raw timestamps.
+    // `cube.dimension.granularity` selects the time dimension with exactly this granularity
+    fn match_rank(member: &Rc<dyn BaseMember>, name: &str) -> Option<usize> {
+        let full_name = member.full_name().to_lowercase();
+        let granularity = member.alias_suffix().map(|g| g.to_lowercase());
+        match granularity {
+            Some(granularity) if name == format!("{}.{}", full_name, granularity) => Some(0),
+            Some(_) if name == full_name => Some(1),
+            None if name == full_name => Some(2),
+            _ => None,
+        }
+    }
 }
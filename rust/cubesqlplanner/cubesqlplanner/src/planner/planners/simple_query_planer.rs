@@ -1,8 +1,8 @@
 use super::{JoinPlanner, OrderPlanner};
-use crate::plan::{Filter, Select, SelectBuilder};
+use crate::plan::{Select, SelectBuilder};
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
-use crate::planner::{BaseMember, QueryProperties};
+use crate::planner::{BaseMember, CurrencyConversion, QueryProperties};
 use cubenativeutils::CubeError;
 use std::rc::Rc;
 
@@ -10,6 +10,7 @@ pub struct SimpleQueryPlanner {
     query_properties: Rc<QueryProperties>,
     join_planner: JoinPlanner,
     order_planner: OrderPlanner,
+    currency_conversion: CurrencyConversion,
     context_factory: SqlNodesFactory,
 }
 impl SimpleQueryPlanner {
@@ -21,24 +22,24 @@ impl SimpleQueryPlanner {
         Self {
             join_planner: JoinPlanner::new(query_tools.clone()),
             order_planner: OrderPlanner::new(query_properties.clone()),
+            currency_conversion: CurrencyConversion::new(query_tools.clone()),
             query_properties,
             context_factory,
         }
     }
 
     pub fn plan(&self) -> Result<Select, CubeError> {
-        let filter = self.query_properties.all_filters();
-        let having = if self.query_properties.measures_filters().is_empty() {
-            None
-        } else {
-            Some(Filter {
-                items: self.query_properties.measures_filters().clone(),
-            })
-        };
+        let (filter, having) = self.query_properties.where_and_having_filters();
         let mut context_factory = self.context_factory.clone();
-        let from = self
-            .join_planner
-            .make_join_node_impl(&None, self.query_properties.simple_query_join()?)?;
+        let (currency_rates_joins, currency_rates_references) = self
+            .currency_conversion
+            .plan_rates_joins(self.query_properties.measures(), &None)?;
+        context_factory.set_currency_rates_references(currency_rates_references);
+        let from = self.join_planner.make_join_node_with_currency_rates(
+            &None,
+            self.query_properties.simple_query_join()?,
+            &currency_rates_joins,
+        )?;
         let mut select_builder = SelectBuilder::new(from.clone());
         for time_dim in self.query_properties.time_dimensions() {
             if let Some(granularity) = time_dim.get_granularity() {
@@ -54,6 +55,7 @@ impl SimpleQueryPlanner {
         }
         select_builder.set_filter(filter);
         select_builder.set_group_by(self.query_properties.group_by());
+        select_builder.set_grouping_sets(self.query_properties.grouping_sets().clone());
         select_builder.set_order_by(self.order_planner.default_order());
         select_builder.set_having(having);
         select_builder.set_limit(self.query_properties.row_limit());
@@ -0,0 +1,82 @@
+use super::{CommonUtils, JoinPlanner};
+use crate::plan::{Expr, MemberExpression, Select, SelectBuilder};
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::collectors::collect_join_hints;
+use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
+use crate::planner::sql_evaluator::MemberSymbol;
+use crate::planner::{BaseDimension, BaseMember};
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+// Select of a subQuery dimension aggregated per primary key of its cube
+pub struct SubQueryDimensionSource {
+    pub select: Rc<Select>,
+    // Primary key dimensions and their columns in the select
+    pub primary_keys: Vec<(Rc<dyn BaseMember>, String)>,
+    pub value_column: String,
+}
+
+pub struct SubQueryDimensionPlanner {
+    query_tools: Rc<QueryTools>,
+    join_planner: JoinPlanner,
+    utils: CommonUtils,
+}
+
+impl SubQueryDimensionPlanner {
+    pub fn new(query_tools: Rc<QueryTools>) -> Self {
+        Self {
+            join_planner: JoinPlanner::new(query_tools.clone()),
+            utils: CommonUtils::new(query_tools.clone()),
+            query_tools,
+        }
+    }
+
+    // Cubes are aliased with a prefix, so the select can be correlated with the outer query
+    pub fn plan(&self, dimension: &Rc<MemberSymbol>) -> Result<SubQueryDimensionSource, CubeError> {
+        let cube_name = dimension.cube_name();
+        let primary_keys = self.utils.primary_keys_dimensions(&cube_name)?;
+        if primary_keys.is_empty() {
+            return Err(CubeError::user(format!(
+                "SubQuery dimension '{}' requires a primary key on cube '{}'",
+                dimension.full_name(),
+                cube_name
+            )));
+        }
+        let from = self
+            .join_planner
+            .make_join_node_with_prefix_and_join_hints(
+                &Some(format!("{}_sub_query", cube_name)),
+                collect_join_hints(dimension)?,
+            )?;
+        let value = BaseDimension::try_new_required(dimension.clone(), self.query_tools.clone())?
+            .as_base_member();
+
+        let mut select_builder = SelectBuilder::new(from);
+        for primary_key in primary_keys.iter() {
+            select_builder.add_projection_member(primary_key, None);
+        }
+        select_builder.add_projection_member(&value, None);
+        select_builder.set_group_by(
+            primary_keys
+                .iter()
+                .map(|primary_key| Expr::Member(MemberExpression::new(primary_key.clone())))
+                .collect(),
+        );
+        let select = Rc::new(select_builder.build(SqlNodesFactory::new()));
+
+        let schema = select.schema();
+        let primary_keys = primary_keys
+            .into_iter()
+            .map(|primary_key| {
+                let column = schema.resolve_member_alias(&primary_key);
+                (primary_key, column)
+            })
+            .collect();
+        let value_column = schema.resolve_member_alias(&value);
+        Ok(SubQueryDimensionSource {
+            select,
+            primary_keys,
+            value_column,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use cubenativeutils::CubeError;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+pub struct PlanningBudget {
+    started_at: Option<Instant>,
+    timeout: Option<Duration>,
+    progress: RefCell<Vec<String>>,
+}
+
+impl PlanningBudget {
+    pub fn new(timeout_ms: Option<u64>) -> Self {
+        // There is no clock on wasm32-unknown-unknown, so planning isn't time limited there
+        let timeout = if cfg!(target_arch = "wasm32") {
+            None
+        } else {
+            timeout_ms.map(Duration::from_millis)
+        };
+        Self {
+            started_at: timeout.map(|_| Instant::now()),
+            timeout,
+            progress: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn record_progress(&self, item: String) {
+        if self.timeout.is_some() {
+            self.progress.borrow_mut().push(item);
+        }
+    }
+
+    pub fn check(&self, stage: &str) -> Result<(), CubeError> {
+        let (Some(timeout), Some(started_at)) = (self.timeout, self.started_at) else {
+            return Ok(());
+        };
+        let elapsed = started_at.elapsed();
+        if elapsed <= timeout {
+            return Ok(());
+        }
+        Err(CubeError::user(format!(
+            "Query planning exceeded the {}ms budget during {} ({}ms elapsed). Partial plan: {}",
+            timeout.as_millis(),
+            stage,
+            elapsed.as_millis(),
+            self.partial_plan_summary()
+        )))
+    }
+
+    fn partial_plan_summary(&self) -> String {
+        let progress = self.progress.borrow();
+        if progress.is_empty() {
+            "nothing planned yet".to_string()
+        } else {
+            progress.join("; ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exceeded_budget_reports_partial_plan() {
+        let budget = PlanningBudget::new(Some(0));
+        budget.record_progress("join of orders, customers".to_string());
+        budget.record_progress("multiplied measures of orders".to_string());
+        std::thread::sleep(Duration::from_millis(2));
+
+        let message = budget.check("rendering").unwrap_err().message;
+        assert!(message.starts_with("Query planning exceeded the 0ms budget during rendering ("));
+        assert!(message
+            .ends_with("Partial plan: join of orders, customers; multiplied measures of orders"));
+    }
+
+    #[test]
+    fn planning_without_timeout_is_never_stopped() {
+        let budget = PlanningBudget::new(None);
+        budget.record_progress("join of orders".to_string());
+        std::thread::sleep(Duration::from_millis(2));
+
+        assert!(budget.check("rendering").is_ok());
+        assert!(budget.progress.borrow().is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::cube_bridge::cancellation_token::CancellationToken;
+use cubenativeutils::{CubeError, CubeErrorCauseType};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub const CANCELLED_ERROR_META_KEY: &str = "cancelled";
+
+pub struct PlanningCancellation {
+    token: Option<Rc<dyn CancellationToken>>,
+}
+
+impl PlanningCancellation {
+    pub fn new(token: Option<Rc<dyn CancellationToken>>) -> Self {
+        Self { token }
+    }
+
+    pub fn check(&self, stage: &str) -> Result<(), CubeError> {
+        if let Some(token) = &self.token {
+            if token.is_cancelled()? {
+                return Err(Self::cancelled_error(stage));
+            }
+        }
+        Ok(())
+    }
+
+    fn cancelled_error(stage: &str) -> CubeError {
+        CubeError {
+            message: format!("Query planning was cancelled during {}", stage),
+            cause: CubeErrorCauseType::User(Some(HashMap::from([(
+                CANCELLED_ERROR_META_KEY.to_string(),
+                stage.to_string(),
+            )]))),
+            backtrace: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+    use std::cell::Cell;
+
+    struct MockCancellationToken {
+        cancelled: Cell<bool>,
+    }
+
+    impl CancellationToken for MockCancellationToken {
+        fn is_cancelled(&self) -> Result<bool, CubeError> {
+            Ok(self.cancelled.get())
+        }
+
+        fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+            self
+        }
+    }
+
+    #[test]
+    fn planning_fails_once_token_is_cancelled() {
+        let token = Rc::new(MockCancellationToken {
+            cancelled: Cell::new(false),
+        });
+        let cancellation = PlanningCancellation::new(Some(token.clone()));
+        assert!(cancellation.check("join planning").is_ok());
+
+        token.cancelled.set(true);
+        let err = cancellation.check("rendering").unwrap_err();
+        assert_eq!(err.message, "Query planning was cancelled during rendering");
+        let CubeErrorCauseType::User(Some(meta)) = err.cause else {
+            panic!("Cancellation should be a user error with meta");
+        };
+        assert_eq!(
+            meta.get(CANCELLED_ERROR_META_KEY),
+            Some(&"rendering".to_string())
+        );
+    }
+
+    #[test]
+    fn planning_without_token_is_never_cancelled() {
+        assert!(PlanningCancellation::new(None).check("rendering").is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+use cubenativeutils::CubeError;
+use std::cell::Cell;
+use std::mem::size_of;
+
+pub struct PlanningMemoryBudget {
+    limit: Option<usize>,
+    used: Cell<usize>,
+}
+
+impl PlanningMemoryBudget {
+    pub fn new(limit_bytes: Option<u64>) -> Self {
+        Self {
+            limit: limit_bytes.map(|limit| limit as usize),
+            used: Cell::new(0),
+        }
+    }
+
+    pub fn values_size(values: &Vec<Option<String>>) -> usize {
+        values
+            .iter()
+            .map(|v| size_of::<Option<String>>() + v.as_ref().map_or(0, |v| v.len()))
+            .sum()
+    }
+
+    pub fn strings_size<'a>(strings: impl Iterator<Item = &'a String>) -> usize {
+        strings.map(|s| size_of::<String>() + s.len()).sum()
+    }
+
+    pub fn reserve(&self, what: &str, bytes: usize) -> Result<(), CubeError> {
+        let used = self.used.get().saturating_add(bytes);
+        self.used.set(used);
+        match self.limit {
+            Some(limit) if used > limit => Err(CubeError::user(format!(
+                "Query plan exceeds the memory budget of {} bytes while adding {} ({} bytes required). Reduce the number of filter values or members in the query",
+                limit, what, used
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reservations_accumulate_until_the_limit() {
+        let budget = PlanningMemoryBudget::new(Some(100));
+        assert!(budget.reserve("filter values of orders.status", 60).is_ok());
+        assert!(budget.reserve("members", 40).is_ok());
+        assert_eq!(
+            budget.reserve("filter values of orders.id", 1).unwrap_err().message,
+            "Query plan exceeds the memory budget of 100 bytes while adding filter values of orders.id (101 bytes required). Reduce the number of filter values or members in the query"
+        );
+    }
+
+    #[test]
+    fn reservations_without_limit_always_fit() {
+        let budget = PlanningMemoryBudget::new(None);
+        assert!(budget.reserve("members", usize::MAX).is_ok());
+        assert!(budget.reserve("members", usize::MAX).is_ok());
+    }
+
+    #[test]
+    fn values_size_counts_string_bytes() {
+        let values = vec![Some("abc".to_string()), None];
+        assert_eq!(
+            PlanningMemoryBudget::values_size(&values),
+            2 * size_of::<Option<String>>() + 3
+        );
+        let strings = vec!["orders.count".to_string()];
+        assert_eq!(
+            PlanningMemoryBudget::strings_size(strings.iter()),
+            size_of::<String>() + 12
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::cube_bridge::metrics_recorder::MetricsRecorder;
+use cubenativeutils::CubeError;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Instant;
+
+// Reports planning metrics to the recorder of the embedder. Nothing is measured without a recorder
+pub struct PlanningMetrics {
+    recorder: Option<Rc<dyn MetricsRecorder>>,
+}
+
+impl PlanningMetrics {
+    pub fn new(recorder: Option<Rc<dyn MetricsRecorder>>) -> Self {
+        Self { recorder }
+    }
+
+    pub fn increment(
+        &self,
+        name: &str,
+        value: u64,
+        labels: &[(&str, &str)],
+    ) -> Result<(), CubeError> {
+        if let Some(recorder) = &self.recorder {
+            recorder.increment_counter(name.to_string(), value as f64, Self::labels(labels))?;
+        }
+        Ok(())
+    }
+
+    pub fn observe(
+        &self,
+        name: &str,
+        value: f64,
+        labels: &[(&str, &str)],
+    ) -> Result<(), CubeError> {
+        if let Some(recorder) = &self.recorder {
+            recorder.record_histogram(name.to_string(), value, Self::labels(labels))?;
+        }
+        Ok(())
+    }
+
+    // Runs a planning phase and records its time in milliseconds.
+    // There is no clock on wasm32-unknown-unknown, so phases aren't timed there
+    pub fn time_phase<T>(
+        &self,
+        phase: &str,
+        f: impl FnOnce() -> Result<T, CubeError>,
+    ) -> Result<T, CubeError> {
+        if self.recorder.is_none() || cfg!(target_arch = "wasm32") {
+            return f();
+        }
+        let started_at = Instant::now();
+        let result = f()?;
+        self.observe(
+            "plan_time_ms",
+            started_at.elapsed().as_secs_f64() * 1000.0,
+            &[("phase", phase)],
+        )?;
+        Ok(result)
+    }
+
+    fn labels(labels: &[(&str, &str)]) -> HashMap<String, String> {
+        labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+}
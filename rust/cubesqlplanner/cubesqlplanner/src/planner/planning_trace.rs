@@ -0,0 +1,76 @@
+use crate::cube_bridge::base_query_options::TraceContext;
+use tracing::Span;
+
+// Parsed `traceparent` header: version-trace_id-parent_id-flags
+struct TraceParent {
+    trace_id: String,
+    parent_span_id: String,
+    sampled: bool,
+}
+
+impl TraceParent {
+    // Invalid trace context is ignored as the W3C spec requires
+    fn parse(traceparent: &str) -> Option<Self> {
+        let parts = traceparent.trim().split('-').collect::<Vec<_>>();
+        let [version, trace_id, parent_span_id, flags] = parts.as_slice() else {
+            return None;
+        };
+        let is_hex = |s: &str, len: usize| {
+            s.len() == len
+                && s.chars()
+                    .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase())
+        };
+        if !is_hex(version, 2) || *version == "ff" || !is_hex(flags, 2) {
+            return None;
+        }
+        if !is_hex(trace_id, 32) || trace_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        if !is_hex(parent_span_id, 16) || parent_span_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            parent_span_id: parent_span_id.to_string(),
+            sampled: flags & 1 == 1,
+        })
+    }
+}
+
+// Spans of planning phases carry the trace context of the caller, so subscribers
+// bridging `tracing` to OpenTelemetry can put planning next to warehouse execution
+pub struct PlanningTrace {
+    parent: Option<TraceParent>,
+    tracestate: Option<String>,
+}
+
+impl PlanningTrace {
+    pub fn new(context: &Option<TraceContext>) -> Self {
+        let parent = context
+            .as_ref()
+            .and_then(|context| TraceParent::parse(&context.traceparent));
+        let tracestate = if parent.is_some() {
+            context
+                .as_ref()
+                .and_then(|context| context.tracestate.clone())
+        } else {
+            None
+        };
+        Self { parent, tracestate }
+    }
+
+    pub fn phase_span(&self, phase: &str) -> Span {
+        match &self.parent {
+            Some(parent) => tracing::info_span!(
+                "cube.plan",
+                phase,
+                trace_id = parent.trace_id.as_str(),
+                parent_span_id = parent.parent_span_id.as_str(),
+                sampled = parent.sampled,
+                tracestate = self.tracestate.as_deref().unwrap_or(""),
+            ),
+            None => tracing::info_span!("cube.plan", phase),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::query_tools::QueryTools;
+use super::{BaseMember, QueryProperties};
+use crate::cube_bridge::audit_log_sink::{QueryAuditFilter, QueryAuditRecord};
+use crate::plan::FilterItem;
+use cubenativeutils::CubeError;
+use itertools::Itertools;
+use std::rc::Rc;
+
+const REDACTED_VALUE: &str = "<redacted>";
+
+pub struct QueryAudit {
+    query_tools: Rc<QueryTools>,
+    request: Rc<QueryProperties>,
+}
+
+impl QueryAudit {
+    pub fn new(query_tools: Rc<QueryTools>, request: Rc<QueryProperties>) -> Self {
+        Self {
+            query_tools,
+            request,
+        }
+    }
+
+    pub fn build_record(&self) -> Result<QueryAuditRecord, CubeError> {
+        let filter_items = self
+            .request
+            .time_dimensions_filters()
+            .iter()
+            .chain(self.request.dimensions_filters().iter())
+            .chain(self.request.measures_filters().iter())
+            .cloned()
+            .collect_vec();
+
+        let mut filters = Vec::new();
+        for item in filter_items.iter() {
+            Self::collect_filters(item, &mut filters);
+        }
+
+        let target_cubes = self.request.target_cubes()?;
+
+        Ok(QueryAuditRecord {
+            principal: self.query_tools.principal(),
+            measures: Self::member_names(self.request.measures()),
+            dimensions: Self::member_names(self.request.dimensions()),
+            time_dimensions: Self::member_names(self.request.time_dimensions()),
+            filters,
+            target_cubes,
+            // Pre-aggregations are matched outside of this planner
+            rollups: vec![],
+            planner_flags: self.query_tools.planner_flags().enabled_names(),
+        })
+    }
+
+    fn member_names<T: BaseMember>(members: &Vec<Rc<T>>) -> Vec<String> {
+        members.iter().map(|m| m.full_name()).collect_vec()
+    }
+
+    fn collect_filters(item: &FilterItem, result: &mut Vec<QueryAuditFilter>) {
+        match item {
+            FilterItem::Group(group) => {
+                for item in group.items.iter() {
+                    Self::collect_filters(item, result);
+                }
+            }
+            FilterItem::Item(filter) => result.push(QueryAuditFilter {
+                member: filter.member_name(),
+                operator: filter.filter_operator().to_string(),
+                values: filter
+                    .values()
+                    .iter()
+                    .map(|_| REDACTED_VALUE.to_string())
+                    .collect(),
+            }),
+            FilterItem::Segment(segment) => result.push(QueryAuditFilter {
+                member: segment.member_name(),
+                operator: "segment".to_string(),
+                values: vec![],
+            }),
+        }
+    }
+}
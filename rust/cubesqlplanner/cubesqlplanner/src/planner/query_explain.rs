@@ -0,0 +1,165 @@
+use super::query_tools::QueryTools;
+use super::{BaseMember, PlannerFlag, QueryProperties};
+use crate::plan::{FilterItem, Select};
+use cubenativeutils::CubeError;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::rc::Rc;
+
+// Logical plan of a query before rendering, so tooling can show why the sql is shaped
+// the way it is. Filter values of sensitive members are redacted as in errors
+pub struct QueryExplain {
+    query_tools: Rc<QueryTools>,
+    request: Rc<QueryProperties>,
+}
+
+impl QueryExplain {
+    pub fn new(query_tools: Rc<QueryTools>, request: Rc<QueryProperties>) -> Self {
+        Self {
+            query_tools,
+            request,
+        }
+    }
+
+    pub fn build(&self, plan: &Select) -> Result<Value, CubeError> {
+        let time_dimensions = self
+            .request
+            .time_dimensions()
+            .iter()
+            .map(|td| {
+                json!({
+                    "dimension": td.base_dimension().full_name(),
+                    "granularity": td.get_granularity(),
+                    "dateRange": td.get_date_range(),
+                })
+            })
+            .collect_vec();
+        let joins = self
+            .request
+            .multi_fact_join_groups()
+            .iter()
+            .map(|(join, measures)| -> Result<_, CubeError> {
+                let joins = join
+                    .joins()?
+                    .items()
+                    .iter()
+                    .map(|item| {
+                        json!({
+                            "from": item.static_data().original_from,
+                            "to": item.static_data().original_to,
+                        })
+                    })
+                    .collect_vec();
+                Ok(json!({
+                    "root": join.static_data().root,
+                    "joins": joins,
+                    "measures": Self::member_names(measures),
+                }))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let order = self
+            .request
+            .order_by()
+            .iter()
+            .map(|item| json!({ "member": item.name(), "desc": item.desc(), "nulls_first": item.nulls_first() }))
+            .collect_vec();
+        Ok(json!({
+            "measures": Self::member_names(self.request.measures()),
+            "dimensions": Self::member_names(self.request.dimensions()),
+            "timeDimensions": time_dimensions,
+            "filters": {
+                "timeDimensions": Self::explain_filters(self.request.time_dimensions_filters()),
+                "dimensions": Self::explain_filters(self.request.dimensions_filters()),
+                "measures": Self::explain_filters(self.request.measures_filters()),
+            },
+            "joins": joins,
+            "order": order,
+            "limit": self.request.row_limit(),
+            "offset": self.request.offset(),
+            "ungrouped": self.request.ungrouped(),
+            "nullSemantics": self.null_semantics(),
+            "plannerFlags": self.query_tools.planner_flags().enabled_names(),
+            "plan": plan.explain(),
+        }))
+    }
+
+    // Negative operators, e.g. `notContains`, match NULL values unless strict SQL
+    // three-valued logic is enabled for the deployment
+    fn null_semantics(&self) -> Value {
+        let strict = self
+            .query_tools
+            .planner_flags()
+            .is_enabled(PlannerFlag::StrictNegativeNullChecks);
+        json!({
+            "negativeOperatorsMatchNull": !strict,
+        })
+    }
+
+    fn explain_filters(items: &Vec<FilterItem>) -> Vec<Value> {
+        items.iter().map(|item| item.explain()).collect_vec()
+    }
+
+    fn member_names<T: BaseMember>(members: &Vec<Rc<T>>) -> Vec<String> {
+        members.iter().map(|m| m.full_name()).collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube_bridge::base_query_options::BaseQueryOptionsStatic;
+    use crate::static_schema::StaticSchema;
+    use serde_json::{json, Value};
+
+    fn explain_query(planner_flags: Value) -> Value {
+        let schema = StaticSchema::try_new(
+            include_str!("../../tests/fixtures/static_schema/schema.json"),
+            include_str!("../../tests/fixtures/static_schema/templates.json"),
+        )
+        .unwrap();
+        let query = serde_json::from_value::<BaseQueryOptionsStatic>(json!({
+            "measures": ["orders.count"],
+            "filters": [{ "member": "orders.status", "operator": "notEquals", "values": ["shipped"] }],
+            "plannerFlags": planner_flags,
+        }))
+        .unwrap();
+        let explain = schema
+            .query_builder(query, None)
+            .unwrap()
+            .build_explain()
+            .unwrap();
+        serde_json::from_str(&explain).unwrap()
+    }
+
+    #[test]
+    fn null_semantics_follow_strict_negative_null_checks() {
+        let explain = explain_query(json!({}));
+        assert_eq!(
+            explain["nullSemantics"],
+            json!({ "negativeOperatorsMatchNull": true })
+        );
+        assert_eq!(explain["plannerFlags"], json!([]));
+
+        let explain = explain_query(json!({ "strict_negative_null_checks": true }));
+        assert_eq!(
+            explain["nullSemantics"],
+            json!({ "negativeOperatorsMatchNull": false })
+        );
+        assert_eq!(
+            explain["plannerFlags"],
+            json!(["strict_negative_null_checks"])
+        );
+    }
+
+    #[test]
+    fn planner_flags_list_flags_enabled_by_the_options() {
+        let explain = explain_query(json!({
+            "exclusive_time_range_end": true,
+            "strict_negative_null_checks": true,
+            "legacy_js_compatibility": false,
+        }));
+        assert_eq!(
+            explain["plannerFlags"],
+            json!(["strict_negative_null_checks", "exclusive_time_range_end"])
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use super::{BaseMember, QueryProperties};
+use crate::plan::FilterItem;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::rc::Rc;
+
+// Hashes of a query for dedupe, alerting on plan changes and correlating warehouse query history.
+// The plan hash doesn't depend on the dialect: it's taken from the normalized query, so the same
+// query gets the same hash on every data source. The sql hash is taken from the rendered sql text
+// without param values, so it changes with the dialect and with the planner output
+pub struct QueryHash {
+    request: Rc<QueryProperties>,
+}
+
+impl QueryHash {
+    pub fn new(request: Rc<QueryProperties>) -> Self {
+        Self { request }
+    }
+
+    pub fn plan_hash(&self) -> String {
+        Self::hash(&self.normalized_plan().to_string())
+    }
+
+    // Whitespace differences, e.g. of indentation in templates, don't change the hash
+    pub fn sql_hash(sql: &str) -> String {
+        Self::hash(&sql.split_whitespace().join(" "))
+    }
+
+    // Members and filters are sorted: their order only changes the order of columns and conditions
+    fn normalized_plan(&self) -> Value {
+        let time_dimensions = self
+            .request
+            .time_dimensions()
+            .iter()
+            .map(|td| {
+                json!({
+                    "dimension": td.base_dimension().full_name(),
+                    "granularity": td.get_granularity(),
+                    "dateRange": td.get_date_range(),
+                })
+            })
+            .sorted_by_key(|td| td.to_string())
+            .collect_vec();
+        let filters = self
+            .request
+            .time_dimensions_filters()
+            .iter()
+            .chain(self.request.dimensions_filters().iter())
+            .chain(self.request.measures_filters().iter())
+            .map(Self::normalized_filter)
+            .sorted_by_key(|f| f.to_string())
+            .collect_vec();
+        let order = self
+            .request
+            .order_by()
+            .iter()
+            .map(|item| json!({ "member": item.name(), "desc": item.desc(), "nulls_first": item.nulls_first() }))
+            .collect_vec();
+        json!({
+            "measures": Self::member_names(self.request.measures()),
+            "dimensions": Self::member_names(self.request.dimensions()),
+            "timeDimensions": time_dimensions,
+            "filters": filters,
+            "order": order,
+            "limit": self.request.row_limit(),
+            "offset": self.request.offset(),
+            "ungrouped": self.request.ungrouped(),
+            "groupingSets": self
+                .request
+                .grouping_sets()
+                .as_ref()
+                .map(|grouping_sets| format!("{:?}", grouping_sets)),
+        })
+    }
+
+    fn normalized_filter(item: &FilterItem) -> Value {
+        match item {
+            FilterItem::Group(group) => json!({
+                "operator": group.operator.to_string(),
+                "items": group
+                    .items
+                    .iter()
+                    .map(Self::normalized_filter)
+                    .sorted_by_key(|f| f.to_string())
+                    .collect_vec(),
+            }),
+            FilterItem::Item(filter) => json!({
+                "member": filter.member_name(),
+                "operator": filter.filter_operator().to_string(),
+                "values": filter.values(),
+                "placeholder": filter.placeholder(),
+            }),
+            FilterItem::Segment(segment) => json!({
+                "segment": segment.member_name(),
+            }),
+        }
+    }
+
+    fn member_names<T: BaseMember>(members: &Vec<Rc<T>>) -> Vec<String> {
+        members.iter().map(|m| m.full_name()).sorted().collect_vec()
+    }
+
+    fn hash(text: &str) -> String {
+        Sha256::digest(text.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sql_hash_ignores_whitespace() {
+        let hash = QueryHash::sql_hash("SELECT 1 FROM t");
+        assert_eq!(
+            hash,
+            "921cc305e33fbbfbb26de1413ee794062ef979eaeaee5d0d987030c6ad36397e"
+        );
+        assert_eq!(hash, QueryHash::sql_hash("SELECT 1\n  FROM  t\n"));
+        assert_ne!(hash, QueryHash::sql_hash("SELECT 2 FROM t"));
+    }
+}
@@ -1,26 +1,45 @@
 use super::filter::compiler::FilterCompiler;
+use super::filter::RangeCoalescer;
 use super::query_tools::QueryTools;
-use super::{BaseDimension, BaseMeasure, BaseMember, BaseMemberHelper, BaseTimeDimension};
+use super::{
+    BaseDimension, BaseMeasure, BaseMember, BaseMemberHelper, BaseTimeDimension,
+    PlanningMemoryBudget,
+};
 use crate::cube_bridge::base_query_options::BaseQueryOptions;
 use crate::cube_bridge::join_definition::JoinDefinition;
-use crate::plan::{Expr, Filter, FilterItem, MemberExpression};
+use crate::plan::{Expr, Filter, FilterItem, GroupingSets, MemberExpression};
+use crate::planner::planners::multi_stage::MultiStageTimeShift;
 use crate::planner::sql_evaluator::collectors::{
     collect_multiplied_measures, has_cumulative_members, has_multi_stage_members,
 };
 use cubenativeutils::CubeError;
 use itertools::Itertools;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+const MAX_SUBTOTALS_COLUMNS: usize = 31;
+
+const PREVIEW_ROW_LIMIT: usize = 1000;
+
 #[derive(Clone, Debug)]
 pub struct OrderByItem {
     name: String,
     desc: bool,
+    nulls_first: Option<bool>,
 }
 
 impl OrderByItem {
     pub fn new(name: String, desc: bool) -> Self {
-        Self { name, desc }
+        Self {
+            name,
+            desc,
+            nulls_first: None,
+        }
+    }
+
+    pub fn with_nulls_first(mut self, nulls_first: Option<bool>) -> Self {
+        self.nulls_first = nulls_first;
+        self
     }
 
     pub fn name(&self) -> &String {
@@ -30,6 +49,10 @@ impl OrderByItem {
     pub fn desc(&self) -> bool {
         self.desc
     }
+
+    pub fn nulls_first(&self) -> Option<bool> {
+        self.nulls_first
+    }
 }
 
 #[derive(Default, Clone, Debug)]
@@ -63,7 +86,9 @@ pub struct QueryProperties {
     query_tools: Rc<QueryTools>,
     ignore_cumulative: bool,
     ungrouped: bool,
+    grouping_sets: Option<GroupingSets>,
     multi_fact_join_groups: Vec<(Rc<dyn JoinDefinition>, Vec<Rc<BaseMeasure>>)>,
+    time_shifts: Vec<MultiStageTimeShift>,
 }
 
 impl QueryProperties {
@@ -71,6 +96,7 @@ impl QueryProperties {
         query_tools: Rc<QueryTools>,
         options: Rc<dyn BaseQueryOptions>,
     ) -> Result<Rc<Self>, CubeError> {
+        Self::check_select_members_limit(&options)?;
         let evaluator_compiler_cell = query_tools.evaluator_compiler().clone();
         let mut evaluator_compiler = evaluator_compiler_cell.borrow_mut();
 
@@ -86,6 +112,19 @@ impl QueryProperties {
             Vec::new()
         };
 
+        let time_shifts = if let Some(time_dimensions) = &options.static_data().time_dimensions {
+            time_dimensions
+                .iter()
+                .filter_map(|d| {
+                    d.shift.as_ref().map(|shift| {
+                        MultiStageTimeShift::try_from_interval(d.dimension.clone(), shift)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            Vec::new()
+        };
+
         let time_dimensions = if let Some(time_dimensions) = &options.static_data().time_dimensions
         {
             time_dimensions
@@ -123,11 +162,51 @@ impl QueryProperties {
                 filter_compiler.add_item(filter)?;
             }
         }
+        if let Some(segments) = &options.static_data().segments {
+            for segment in segments {
+                filter_compiler.add_segment(segment)?;
+            }
+        }
         for time_dimension in &time_dimensions {
             filter_compiler.add_time_dimension_item(time_dimension)?;
         }
+        if let Some(time_dimensions) = &options.static_data().time_dimensions {
+            for time_dimension in time_dimensions {
+                if let Some(calendar) = &time_dimension.calendar {
+                    filter_compiler
+                        .add_time_dimension_calendar_item(&time_dimension.dimension, calendar)?;
+                }
+            }
+        }
         let (dimensions_filters, time_dimensions_filters, measures_filters) =
             filter_compiler.extract_result();
+        let dimensions_filters = RangeCoalescer::coalesce(&dimensions_filters);
+        let measures_filters = RangeCoalescer::coalesce(&measures_filters);
+        query_tools.set_query_filters(
+            time_dimensions_filters
+                .iter()
+                .chain(dimensions_filters.iter())
+                .cloned()
+                .collect(),
+        );
+        query_tools.record_planning_progress(format!(
+            "resolved {} measures, {} dimensions, {} time dimensions and {} filters",
+            measures.len(),
+            dimensions.len(),
+            time_dimensions.len(),
+            dimensions_filters.len() + time_dimensions_filters.len() + measures_filters.len()
+        ));
+        let aliases = measures
+            .iter()
+            .map(|m| m.alias_name())
+            .chain(dimensions.iter().map(|d| d.alias_name()))
+            .chain(time_dimensions.iter().map(|d| d.alias_name()))
+            .collect_vec();
+        query_tools.reserve_planning_memory(
+            "member aliases",
+            PlanningMemoryBudget::strings_size(aliases.iter()),
+        )?;
+        query_tools.check_planning_stage("symbol resolution")?;
 
         //FIXME may be this filter should be applied on other place
         let time_dimensions = time_dimensions
@@ -138,8 +217,11 @@ impl QueryProperties {
         let order_by = if let Some(order) = &options.static_data().order {
             order
                 .iter()
-                .map(|o| OrderByItem::new(o.id.clone(), o.is_desc()))
-                .collect_vec()
+                .map(|o| -> Result<_, CubeError> {
+                    Ok(OrderByItem::new(o.id.clone(), o.is_desc())
+                        .with_nulls_first(o.nulls_first()?))
+                })
+                .collect::<Result<Vec<_>, _>>()?
         } else {
             Self::default_order(&dimensions, &time_dimensions, &measures)
         };
@@ -149,12 +231,19 @@ impl QueryProperties {
         } else {
             None
         };
+        let row_limit = if query_tools.preview() {
+            Some(row_limit.map_or(PREVIEW_ROW_LIMIT, |limit| limit.min(PREVIEW_ROW_LIMIT)))
+        } else {
+            row_limit
+        };
         let offset = if let Some(offset) = &options.static_data().offset {
             offset.parse::<usize>().ok()
         } else {
             None
         };
         let ungrouped = options.static_data().ungrouped.unwrap_or(false);
+        let grouping_sets =
+            Self::grouping_sets_from_options(&options, &dimensions, &time_dimensions, ungrouped)?;
 
         let multi_fact_join_groups = Self::compute_join_multi_fact_groups(
             query_tools.clone(),
@@ -165,6 +254,18 @@ impl QueryProperties {
             &dimensions_filters,
             &measures_filters,
         )?;
+        if ungrouped
+            && !options
+                .static_data()
+                .allow_ungrouped_without_primary_key
+                .unwrap_or(false)
+        {
+            Self::validate_ungrouped_primary_keys(
+                query_tools.clone(),
+                &dimensions,
+                &multi_fact_join_groups,
+            )?;
+        }
 
         Ok(Rc::new(Self {
             measures,
@@ -179,10 +280,154 @@ impl QueryProperties {
             query_tools,
             ignore_cumulative: false,
             ungrouped,
+            grouping_sets,
             multi_fact_join_groups,
+            time_shifts,
         }))
     }
 
+    // Rows of an ungrouped query are identified only when primary keys of all joined cubes are selected
+    fn validate_ungrouped_primary_keys(
+        query_tools: Rc<QueryTools>,
+        dimensions: &Vec<Rc<BaseDimension>>,
+        multi_fact_join_groups: &Vec<(Rc<dyn JoinDefinition>, Vec<Rc<BaseMeasure>>)>,
+    ) -> Result<(), CubeError> {
+        let mut cubes = Vec::new();
+        for (join, _) in multi_fact_join_groups.iter() {
+            cubes.push(join.static_data().root.clone());
+            for item in join.joins()?.items().iter() {
+                cubes.push(item.static_data().original_to.clone());
+            }
+        }
+        let primary_keys = &query_tools.cube_evaluator().static_data().primary_keys;
+        let missing_primary_keys = cubes
+            .iter()
+            .unique()
+            .flat_map(|cube| {
+                primary_keys
+                    .get(cube)
+                    .into_iter()
+                    .flatten()
+                    .map(move |key| format!("{}.{}", cube, key))
+            })
+            .filter(|key| !dimensions.iter().any(|d| &d.full_name() == key))
+            .collect_vec();
+        if !missing_primary_keys.is_empty() {
+            return Err(CubeError::user(format!(
+                "Ungrouped query requires primary keys to be present in dimensions: {}. Pass allowUngroupedWithoutPrimaryKey option to disable this check.",
+                missing_primary_keys
+                    .iter()
+                    .map(|k| format!("'{}'", k))
+                    .join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    // Subtotals are requested either as `rollup` or `cube` of all dimensions
+    // or as explicit groupings of them
+    fn grouping_sets_from_options(
+        options: &Rc<dyn BaseQueryOptions>,
+        dimensions: &Vec<Rc<BaseDimension>>,
+        time_dimensions: &Vec<Rc<BaseTimeDimension>>,
+        ungrouped: bool,
+    ) -> Result<Option<GroupingSets>, CubeError> {
+        let static_data = options.static_data();
+        let grouping_sets = match (&static_data.subtotals, &static_data.grouping_sets) {
+            (None, None) => return Ok(None),
+            (Some(_), Some(_)) => {
+                return Err(CubeError::user(format!(
+                    "Only one of subtotals and groupingSets can be set"
+                )))
+            }
+            (Some(subtotals), None) => match subtotals.to_lowercase().as_str() {
+                "rollup" => GroupingSets::Rollup,
+                "cube" => GroupingSets::Cube,
+                _ => {
+                    return Err(CubeError::user(format!(
+                        "Unknown subtotals '{}', expected 'rollup' or 'cube'",
+                        subtotals
+                    )))
+                }
+            },
+            (None, Some(sets)) => {
+                // Positions of the group by columns: dimensions go before time dimensions
+                let members = dimensions
+                    .iter()
+                    .map(|d| -> Rc<dyn BaseMember> { d.clone() })
+                    .chain(
+                        time_dimensions
+                            .iter()
+                            .map(|d| -> Rc<dyn BaseMember> { d.clone() }),
+                    )
+                    .collect_vec();
+                let sets = sets
+                    .iter()
+                    .map(|set| {
+                        set.iter()
+                            .map(|name| Self::group_by_position(&members, name))
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                GroupingSets::Sets(sets)
+            }
+        };
+        if ungrouped {
+            return Err(CubeError::user(format!(
+                "Subtotals can't be calculated for ungrouped queries"
+            )));
+        }
+        // Grouping id is a bit per group by column
+        let columns_count = dimensions.len() + time_dimensions.len();
+        if columns_count > MAX_SUBTOTALS_COLUMNS {
+            return Err(CubeError::user(format!(
+                "Subtotals can be calculated for at most {} dimensions, {} requested",
+                MAX_SUBTOTALS_COLUMNS, columns_count
+            )));
+        }
+        Ok(Some(grouping_sets))
+    }
+
+    fn group_by_position(
+        members: &Vec<Rc<dyn BaseMember>>,
+        name: &str,
+    ) -> Result<usize, CubeError> {
+        let name = name.to_lowercase();
+        members
+            .iter()
+            .position(|m| {
+                let full_name = m.full_name().to_lowercase();
+                name == full_name
+                    || m.alias_suffix().map_or(false, |granularity| {
+                        name == format!("{}.{}", full_name, granularity.to_lowercase())
+                    })
+            })
+            .ok_or_else(|| {
+                CubeError::user(format!(
+                    "Grouping set member '{}' is not a dimension of the query",
+                    name
+                ))
+            })
+    }
+
+    // Checked before any member is compiled so oversized queries fail fast
+    fn check_select_members_limit(options: &Rc<dyn BaseQueryOptions>) -> Result<(), CubeError> {
+        let static_data = options.static_data();
+        let Some(limit) = static_data.max_select_members else {
+            return Ok(());
+        };
+        let members_count = static_data.measures.as_ref().map_or(0, |m| m.len())
+            + static_data.dimensions.as_ref().map_or(0, |d| d.len())
+            + static_data.time_dimensions.as_ref().map_or(0, |d| d.len());
+        if members_count as u64 > limit {
+            return Err(CubeError::user(format!(
+                "Query selects {} members which exceeds the limit of {}. Split the query or select fewer measures and dimensions",
+                members_count, limit
+            )));
+        }
+        Ok(())
+    }
+
     pub fn try_new_from_precompiled(
         query_tools: Rc<QueryTools>,
         measures: Vec<Rc<BaseMeasure>>,
@@ -226,7 +471,9 @@ impl QueryProperties {
             query_tools,
             ignore_cumulative,
             ungrouped,
+            grouping_sets: None,
             multi_fact_join_groups,
+            time_shifts: Vec::new(),
         }))
     }
 
@@ -305,10 +552,22 @@ impl QueryProperties {
                 })
                 .collect::<Result<Vec<_>, _>>()?
         };
-        Ok(measures_to_join
+        let mut groups = measures_to_join
             .into_iter()
             .into_group_map_by(|(_, (key, _))| key.clone())
             .into_values()
+            .collect_vec();
+        // Hash map order differs between runs, so groups are kept in the order of
+        // their first measure in the query: sql and params order must not depend on it
+        groups.sort_by_key(|measures_and_join| {
+            measures_and_join
+                .iter()
+                .flat_map(|m| m.0.iter())
+                .filter_map(|m| measures.iter().position(|qm| Rc::ptr_eq(qm, m)))
+                .min()
+        });
+        Ok(groups
+            .into_iter()
             .map(|measures_and_join| {
                 (
                     measures_and_join.iter().next().unwrap().1 .1.clone(),
@@ -321,6 +580,10 @@ impl QueryProperties {
             .collect())
     }
 
+    pub fn multi_fact_join_groups(&self) -> &Vec<(Rc<dyn JoinDefinition>, Vec<Rc<BaseMeasure>>)> {
+        &self.multi_fact_join_groups
+    }
+
     pub fn is_multi_fact_join(&self) -> bool {
         self.multi_fact_join_groups.len() > 1
     }
@@ -353,6 +616,18 @@ impl QueryProperties {
         &self.time_dimensions
     }
 
+    pub fn time_shifts(&self) -> &Vec<MultiStageTimeShift> {
+        &self.time_shifts
+    }
+
+    pub fn time_shifts_by_dimension(&self) -> Result<HashMap<String, Vec<String>>, CubeError> {
+        let mut result: HashMap<String, Vec<String>> = HashMap::new();
+        for ts in self.time_shifts.iter() {
+            ts.add_to(result.entry(ts.time_dimension.clone()).or_default())?;
+        }
+        Ok(result)
+    }
+
     pub fn time_dimensions_filters(&self) -> &Vec<FilterItem> {
         &self.time_dimensions_filters
     }
@@ -386,6 +661,33 @@ impl QueryProperties {
         self.ungrouped
     }
 
+    pub fn grouping_sets(&self) -> &Option<GroupingSets> {
+        &self.grouping_sets
+    }
+
+    // Cubes referenced by query members and filters
+    pub fn target_cubes(&self) -> Result<Vec<String>, CubeError> {
+        let filter_items = self
+            .time_dimensions_filters
+            .iter()
+            .chain(self.dimensions_filters.iter())
+            .chain(self.measures_filters.iter())
+            .cloned()
+            .collect_vec();
+        let members = self.all_members(false);
+        let mut cached_data = self.query_tools.cached_data_mut();
+        let mut hints = members
+            .iter()
+            .map(|m| cached_data.join_hints_for_member(&m.member_evaluator()))
+            .collect::<Result<Vec<_>, _>>()?;
+        hints.extend(cached_data.join_hints_for_filter_item_vec(&filter_items)?);
+        Ok(hints
+            .iter()
+            .flat_map(|h| h.iter().cloned())
+            .unique()
+            .collect_vec())
+    }
+
     pub fn all_filters(&self) -> Option<Filter> {
         let items = self
             .time_dimensions_filters
@@ -400,6 +702,22 @@ impl QueryProperties {
         }
     }
 
+    // Measure filters are applied to aggregated values in HAVING. Ungrouped queries don't
+    // aggregate measures, so there they are applied in WHERE along with dimension filters
+    pub fn where_and_having_filters(&self) -> (Option<Filter>, Option<Filter>) {
+        let (having_items, where_items): (Vec<_>, Vec<_>) = self
+            .time_dimensions_filters
+            .iter()
+            .chain(self.dimensions_filters.iter())
+            .chain(self.measures_filters.iter())
+            .cloned()
+            .partition(|item| !self.ungrouped && item.is_measure_filter());
+        (
+            Filter::from_items(where_items),
+            Filter::from_items(having_items),
+        )
+    }
+
     pub fn all_dimensions_and_measures(
         &self,
         measures: &Vec<Rc<BaseMeasure>>,
@@ -515,6 +833,9 @@ impl QueryProperties {
             FilterItem::Item(item) => {
                 members.insert(item.member_name());
             }
+            FilterItem::Segment(item) => {
+                members.insert(item.member_name());
+            }
         }
     }
 
@@ -0,0 +1,167 @@
+use super::incremental_query::DerivedQueryOptions;
+use super::sql_evaluator::Compiler;
+use super::BaseQuery;
+use crate::cube_bridge::base_query_options::{BaseQueryOptions, SavedQueryDefinition};
+use cubenativeutils::wrappers::inner_types::InnerTypes;
+use cubenativeutils::wrappers::NativeContextHolder;
+use cubenativeutils::CubeError;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedQuery {
+    pub name: String,
+    pub description: Option<String>,
+    pub query: SavedQueryDefinition,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedQueryValidation {
+    pub name: String,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+// Named queries re-planned against the current schema on request, so a schema change
+// which breaks a saved query is reported by `validate`. Saved queries are stored by the
+// host along with the tenant they belong to and passed with each request, the planner
+// doesn't keep them between requests
+#[derive(Default)]
+pub struct QueryRegistry {
+    queries: BTreeMap<String, SavedQuery>,
+}
+
+impl QueryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn try_new(queries: Vec<SavedQuery>) -> Result<Self, CubeError> {
+        let mut result = Self::new();
+        for query in queries {
+            result.register(query)?;
+        }
+        Ok(result)
+    }
+
+    pub fn register(&mut self, query: SavedQuery) -> Result<(), CubeError> {
+        if query.name.trim().is_empty() {
+            return Err(CubeError::user(
+                "Saved query name can't be empty".to_string(),
+            ));
+        }
+        if self.queries.contains_key(&query.name) {
+            return Err(CubeError::user(format!(
+                "Saved query '{}' is defined more than once",
+                query.name
+            )));
+        }
+        self.queries.insert(query.name.clone(), query);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&SavedQuery, CubeError> {
+        self.queries
+            .get(name)
+            .ok_or_else(|| CubeError::user(format!("Saved query '{}' is not found", name)))
+    }
+
+    // Saved query is planned with options of the request, e.g. its timezone and security context.
+    // Filters and segments of the request, e.g. ones added by `queryRewrite`, are kept
+    pub fn plan<IT: InnerTypes>(
+        &self,
+        context: NativeContextHolder<IT>,
+        options: Rc<dyn BaseQueryOptions>,
+        name: &str,
+    ) -> Result<BaseQuery<IT>, CubeError> {
+        let saved_query = self.get(name)?;
+        Self::plan_saved_query(context, options, saved_query, None)
+    }
+
+    // Plans every saved query against the current schema. Members are compiled once
+    // for all of them
+    pub fn validate<IT: InnerTypes>(
+        &self,
+        context: NativeContextHolder<IT>,
+        options: Rc<dyn BaseQueryOptions>,
+    ) -> Result<Vec<SavedQueryValidation>, CubeError> {
+        let evaluator_compiler = Rc::new(RefCell::new(Compiler::new(options.cube_evaluator()?)));
+        let result = self
+            .queries
+            .values()
+            .map(|saved_query| {
+                let error = Self::plan_saved_query(
+                    context.clone(),
+                    options.clone(),
+                    saved_query,
+                    Some(evaluator_compiler.clone()),
+                )
+                .and_then(|query| query.build_sql_and_params())
+                .err()
+                .map(|e| e.message);
+                SavedQueryValidation {
+                    name: saved_query.name.clone(),
+                    valid: error.is_none(),
+                    error,
+                }
+            })
+            .collect();
+        Ok(result)
+    }
+
+    fn plan_saved_query<IT: InnerTypes>(
+        context: NativeContextHolder<IT>,
+        options: Rc<dyn BaseQueryOptions>,
+        saved_query: &SavedQuery,
+        evaluator_compiler: Option<Rc<RefCell<Compiler>>>,
+    ) -> Result<BaseQuery<IT>, CubeError> {
+        let static_data = options.static_data().with_saved_query(&saved_query.query);
+        let options = Rc::new(DerivedQueryOptions::new(options, static_data));
+        BaseQuery::try_new_with_compiler(context, options, evaluator_compiler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn saved_query(name: &str) -> SavedQuery {
+        serde_json::from_value(json!({
+            "name": name,
+            "query": { "measures": ["orders.count"] },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn saved_queries_are_found_by_name() {
+        let registry =
+            QueryRegistry::try_new(vec![saved_query("by_status"), saved_query("total")]).unwrap();
+        assert_eq!(registry.get("total").unwrap().name, "total");
+        assert_eq!(
+            registry.get("missing").unwrap_err().message,
+            "Saved query 'missing' is not found"
+        );
+    }
+
+    #[test]
+    fn saved_query_names_are_unique_and_not_empty() {
+        assert_eq!(
+            QueryRegistry::try_new(vec![saved_query("total"), saved_query("total")])
+                .err()
+                .unwrap()
+                .message,
+            "Saved query 'total' is defined more than once"
+        );
+        assert_eq!(
+            QueryRegistry::try_new(vec![saved_query(" ")])
+                .err()
+                .unwrap()
+                .message,
+            "Saved query name can't be empty"
+        );
+    }
+}
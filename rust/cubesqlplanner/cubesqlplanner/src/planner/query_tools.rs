@@ -1,11 +1,22 @@
+use super::filter::{CastPolicy, EmptyInListPolicy, ValueRedactionPolicy};
 use super::sql_evaluator::{Compiler, MemberSymbol};
-use super::{BaseMember, ParamsAllocator};
+use super::{
+    BaseMember, DateTimeTools, NativeDateTimeTools, ParamAllocation, ParamType, ParamsAllocator,
+    PlannerFlag, PlannerFlags, PlanningBudget, PlanningCancellation, PlanningMemoryBudget,
+    PlanningMetrics, PlanningTrace, TypedParam,
+};
+use crate::cube_bridge::base_query_options::{
+    CurrencyConversionOptions, TraceContext, ValuesNormalization,
+};
 use crate::cube_bridge::base_tools::BaseTools;
+use crate::cube_bridge::cancellation_token::CancellationToken;
 use crate::cube_bridge::evaluator::CubeEvaluator;
 use crate::cube_bridge::join_definition::JoinDefinition;
 use crate::cube_bridge::join_graph::JoinGraph;
 use crate::cube_bridge::join_item::JoinItemStatic;
+use crate::cube_bridge::metrics_recorder::MetricsRecorder;
 use crate::cube_bridge::sql_templates_render::SqlTemplatesRender;
+use crate::cube_bridge::table_name_resolver::TableNameResolver;
 use crate::plan::FilterItem;
 use crate::planner::sql_evaluator::collectors::collect_join_hints;
 use crate::planner::sql_templates::PlanSqlTemplates;
@@ -16,9 +27,11 @@ use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::cell::{Ref, RefCell, RefMut};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
+const PRINCIPAL_FIELDS: [&str; 3] = ["sub", "userId", "user_id"];
+
 pub struct QueryToolsCachedData {
     join_hints: HashMap<String, Rc<Vec<String>>>,
     join_hints_to_join_key: HashMap<Vec<Rc<Vec<String>>>, Rc<JoinKey>>,
@@ -119,12 +132,37 @@ impl QueryToolsCachedData {
 pub struct QueryTools {
     cube_evaluator: Rc<dyn CubeEvaluator>,
     base_tools: Rc<dyn BaseTools>,
+    date_time_tools: Rc<dyn DateTimeTools>,
     join_graph: Rc<dyn JoinGraph>,
     templates_render: Rc<dyn SqlTemplatesRender>,
     params_allocator: Rc<RefCell<ParamsAllocator>>,
     evaluator_compiler: Rc<RefCell<Compiler>>,
     cached_data: RefCell<QueryToolsCachedData>,
     timezone: Option<Tz>,
+    security_context: Option<serde_json::Value>,
+    value_redaction_policy: ValueRedactionPolicy,
+    cast_policy: CastPolicy,
+    empty_in_list_policy: EmptyInListPolicy,
+    collation: Option<String>,
+    values_normalization: Option<ValuesNormalization>,
+    currency_conversion: Option<CurrencyConversionOptions>,
+    table_overrides: HashMap<String, String>,
+    table_name_resolver: Option<Rc<dyn TableNameResolver>>,
+    source_date_ranges: HashMap<String, (String, String)>,
+    source_query_stack: RefCell<Vec<String>>,
+    // Dimension and date range filters of the query, `FILTER_PARAMS` of cube sql are rendered from them
+    query_filters: RefCell<Vec<FilterItem>>,
+    cancellation: PlanningCancellation,
+    budget: PlanningBudget,
+    memory_budget: PlanningMemoryBudget,
+    metrics: PlanningMetrics,
+    trace: PlanningTrace,
+    deterministic: bool,
+    planner_flags: PlannerFlags,
+    as_of: Option<String>,
+    preview: bool,
+    inline_params: bool,
+    overflow_safe_aggregation: bool,
 }
 
 impl QueryTools {
@@ -133,9 +171,36 @@ impl QueryTools {
         base_tools: Rc<dyn BaseTools>,
         join_graph: Rc<dyn JoinGraph>,
         timezone_name: Option<String>,
+        security_context: Option<serde_json::Value>,
+        redaction_mode: Option<String>,
+        redaction_secret: Option<String>,
+        cast_policy: Option<String>,
+        empty_in_list_policy: Option<String>,
+        collation: Option<String>,
+        values_normalization: Option<ValuesNormalization>,
+        currency_conversion: Option<CurrencyConversionOptions>,
+        table_overrides: Option<HashMap<String, String>>,
+        cancellation_token: Option<Rc<dyn CancellationToken>>,
+        table_name_resolver: Option<Rc<dyn TableNameResolver>>,
+        source_date_ranges: HashMap<String, (String, String)>,
+        planning_timeout_ms: Option<u64>,
+        planning_memory_budget_bytes: Option<u64>,
+        metrics_recorder: Option<Rc<dyn MetricsRecorder>>,
+        trace_context: Option<TraceContext>,
+        deterministic: bool,
+        planner_flags: Option<HashMap<String, bool>>,
+        as_of: Option<String>,
+        preview: bool,
+        inline_params: bool,
+        overflow_safe_aggregation: bool,
+        evaluator_compiler: Option<Rc<RefCell<Compiler>>>,
+        date_time_tools: Option<Rc<dyn DateTimeTools>>,
     ) -> Result<Rc<Self>, CubeError> {
+        let date_time_tools =
+            date_time_tools.unwrap_or_else(|| NativeDateTimeTools::new(base_tools.clone()));
         let templates_render = base_tools.sql_templates()?;
-        let evaluator_compiler = Rc::new(RefCell::new(Compiler::new(cube_evaluator.clone())));
+        let evaluator_compiler = evaluator_compiler
+            .unwrap_or_else(|| Rc::new(RefCell::new(Compiler::new(cube_evaluator.clone()))));
         let timezone = if let Some(timezone) = timezone_name {
             Some(
                 timezone
@@ -145,16 +210,48 @@ impl QueryTools {
         } else {
             None
         };
+        let value_redaction_policy =
+            ValueRedactionPolicy::try_from_mode_name(&redaction_mode, &redaction_secret)?;
+        let cast_policy = CastPolicy::try_from_name(&cast_policy)?;
+        let empty_in_list_policy = EmptyInListPolicy::try_from_name(&empty_in_list_policy)?;
+        let planner_flags = PlannerFlags::try_new(&planner_flags)?;
         let sql_templates = PlanSqlTemplates::new(templates_render.clone());
         Ok(Rc::new(Self {
             cube_evaluator,
             base_tools,
+            date_time_tools,
             join_graph,
             templates_render,
-            params_allocator: Rc::new(RefCell::new(ParamsAllocator::new(sql_templates))),
+            params_allocator: Rc::new(RefCell::new(ParamsAllocator::new(
+                sql_templates,
+                inline_params,
+            ))),
             evaluator_compiler,
             cached_data: RefCell::new(QueryToolsCachedData::new()),
             timezone,
+            security_context,
+            value_redaction_policy,
+            cast_policy,
+            empty_in_list_policy,
+            collation,
+            values_normalization,
+            currency_conversion,
+            table_overrides: table_overrides.unwrap_or_default(),
+            table_name_resolver,
+            source_date_ranges,
+            source_query_stack: RefCell::new(Vec::new()),
+            query_filters: RefCell::new(Vec::new()),
+            cancellation: PlanningCancellation::new(cancellation_token),
+            budget: PlanningBudget::new(planning_timeout_ms),
+            memory_budget: PlanningMemoryBudget::new(planning_memory_budget_bytes),
+            metrics: PlanningMetrics::new(metrics_recorder),
+            trace: PlanningTrace::new(&trace_context),
+            deterministic,
+            planner_flags,
+            as_of,
+            preview,
+            inline_params,
+            overflow_safe_aggregation,
         }))
     }
 
@@ -174,6 +271,157 @@ impl QueryTools {
         &self.timezone
     }
 
+    pub fn security_context(&self) -> &Option<serde_json::Value> {
+        &self.security_context
+    }
+
+    // User or tenant the query is planned for, taken from the security context
+    pub fn principal(&self) -> Option<String> {
+        let security_context = self.security_context.as_ref()?;
+        PRINCIPAL_FIELDS.iter().find_map(|field| {
+            security_context.get(field).and_then(|v| match v {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        })
+    }
+
+    pub fn value_redaction_policy(&self) -> &ValueRedactionPolicy {
+        &self.value_redaction_policy
+    }
+
+    pub fn cast_policy(&self) -> &CastPolicy {
+        &self.cast_policy
+    }
+
+    pub fn empty_in_list_policy(&self) -> &EmptyInListPolicy {
+        &self.empty_in_list_policy
+    }
+
+    pub fn collation(&self) -> &Option<String> {
+        &self.collation
+    }
+
+    pub fn values_normalization(&self) -> &Option<ValuesNormalization> {
+        &self.values_normalization
+    }
+
+    pub fn currency_conversion(&self) -> &Option<CurrencyConversionOptions> {
+        &self.currency_conversion
+    }
+
+    // Overrides are validated against the allow list before they are passed to the planner,
+    // table names are checked and quoted when cube tables are rendered
+    pub fn table_override(&self, cube_name: &str) -> Option<&String> {
+        self.table_overrides.get(cube_name)
+    }
+
+    // Preview queries cap the row limit, sample cube tables where the database can
+    // and scan only the most recent day of date ranges
+    pub fn preview(&self) -> bool {
+        self.preview
+    }
+
+    // Params are rendered as literals and the query is built without bind params
+    pub fn inline_params(&self) -> bool {
+        self.inline_params
+    }
+
+    pub fn date_time_tools(&self) -> &Rc<dyn DateTimeTools> {
+        &self.date_time_tools
+    }
+
+    pub fn set_query_filters(&self, filters: Vec<FilterItem>) {
+        *self.query_filters.borrow_mut() = filters;
+    }
+
+    pub fn query_filters(&self) -> Vec<FilterItem> {
+        self.query_filters.borrow().clone()
+    }
+
+    pub fn overflow_safe_aggregation(&self) -> bool {
+        self.overflow_safe_aggregation
+    }
+
+    // Point in time snapshot cubes are queried at
+    pub fn as_of(&self) -> Option<&String> {
+        self.as_of.as_ref()
+    }
+
+    // Date range of the query time dimensions of the cube used to prune its sources
+    pub fn source_date_range(&self, cube_name: &str) -> Option<&(String, String)> {
+        self.source_date_ranges.get(cube_name)
+    }
+
+    // Cubes whose source queries are being planned, to reject cubes built on themselves
+    pub fn enter_source_query(&self, cube_name: &str) -> Result<(), CubeError> {
+        let mut stack = self.source_query_stack.borrow_mut();
+        if stack.iter().any(|name| name == cube_name) {
+            return Err(CubeError::user(format!(
+                "Source query of cube {} depends on itself: {} -> {}",
+                cube_name,
+                stack.join(" -> "),
+                cube_name
+            )));
+        }
+        stack.push(cube_name.to_string());
+        Ok(())
+    }
+
+    pub fn exit_source_query(&self) {
+        self.source_query_stack.borrow_mut().pop();
+    }
+
+    pub fn resolve_table_name(&self, cube_name: &str, table: String) -> Result<String, CubeError> {
+        if let Some(resolver) = &self.table_name_resolver {
+            resolver.resolve_table_name(cube_name.to_string(), table)
+        } else {
+            Ok(table)
+        }
+    }
+
+    pub fn deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    pub fn planner_flags(&self) -> &PlannerFlags {
+        &self.planner_flags
+    }
+
+    pub fn legacy_js_compatibility(&self) -> bool {
+        self.planner_flags
+            .is_enabled(PlannerFlag::LegacyJsCompatibility)
+    }
+
+    pub fn metrics(&self) -> &PlanningMetrics {
+        &self.metrics
+    }
+
+    // Runs a planning phase in a span linked to the caller trace and records its time
+    pub fn planning_phase<T>(
+        &self,
+        phase: &str,
+        f: impl FnOnce() -> Result<T, CubeError>,
+    ) -> Result<T, CubeError> {
+        let span = self.trace.phase_span(phase);
+        let _entered = span.enter();
+        self.metrics.time_phase(phase, f)
+    }
+
+    pub fn check_planning_stage(&self, stage: &str) -> Result<(), CubeError> {
+        self.cancellation.check(stage)?;
+        self.budget.check(stage)
+    }
+
+    pub fn record_planning_progress(&self, item: String) {
+        self.budget.record_progress(item)
+    }
+
+    pub fn reserve_planning_memory(&self, what: &str, bytes: usize) -> Result<(), CubeError> {
+        self.memory_budget.reserve(what, bytes)
+    }
+
     pub fn cached_data(&self) -> Ref<'_, QueryToolsCachedData> {
         self.cached_data.borrow()
     }
@@ -240,14 +488,42 @@ impl QueryTools {
     pub fn allocate_param(&self, name: &str) -> String {
         self.params_allocator.borrow_mut().allocate_param(name)
     }
-    pub fn get_allocated_params(&self) -> Vec<String> {
+    pub fn allocate_member_param(&self, member: &str, name: &str) -> String {
+        self.params_allocator
+            .borrow_mut()
+            .allocate_member_param(member, name)
+    }
+    pub fn allocate_typed_member_param(
+        &self,
+        member: &str,
+        name: &str,
+        param_type: ParamType,
+    ) -> String {
+        self.params_allocator
+            .borrow_mut()
+            .allocate_typed_member_param(member, name, param_type)
+    }
+    pub fn param_allocations(&self) -> Vec<ParamAllocation> {
+        self.params_allocator.borrow().allocations()
+    }
+    pub fn render_allocated_placeholders(&self, sql: &str) -> String {
+        self.params_allocator
+            .borrow()
+            .render_allocated_placeholders(sql)
+    }
+    pub fn allocate_named_placeholder(&self, name: &str) -> Result<String, CubeError> {
+        self.params_allocator
+            .borrow()
+            .allocate_named_placeholder(name)
+    }
+    pub fn get_allocated_params(&self) -> Vec<TypedParam> {
         self.params_allocator.borrow().get_params().clone()
     }
     pub fn build_sql_and_params(
         &self,
         sql: &str,
         should_reuse_params: bool,
-    ) -> Result<(String, Vec<String>), CubeError> {
+    ) -> Result<(String, Vec<TypedParam>), CubeError> {
         let native_allocated_params = self.base_tools.get_allocated_params()?;
         self.params_allocator.borrow().build_sql_and_params(
             sql,
@@ -255,4 +531,55 @@ impl QueryTools {
             should_reuse_params,
         )
     }
+    pub fn build_sql_and_params_with_member_params(
+        &self,
+        sql: &str,
+        should_reuse_params: bool,
+    ) -> Result<(String, Vec<TypedParam>, BTreeMap<String, Vec<usize>>), CubeError> {
+        let native_allocated_params = self.base_tools.get_allocated_params()?;
+        self.params_allocator
+            .borrow()
+            .build_sql_and_params_with_member_params(
+                sql,
+                native_allocated_params,
+                should_reuse_params,
+            )
+    }
+    pub fn build_sql_and_params_with_bound_placeholders(
+        &self,
+        sql: &str,
+        should_reuse_params: bool,
+    ) -> Result<(String, Vec<TypedParam>, HashMap<String, Vec<usize>>), CubeError> {
+        let native_allocated_params = self.base_tools.get_allocated_params()?;
+        self.params_allocator
+            .borrow()
+            .build_sql_and_params_with_bound_placeholders(
+                sql,
+                native_allocated_params,
+                should_reuse_params,
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::planner::test_fixtures::QueryToolsFixture;
+
+    #[test]
+    fn source_queries_of_cubes_built_on_themselves_are_rejected() {
+        let query_tools = QueryToolsFixture::new().build();
+        query_tools.enter_source_query("customer_totals").unwrap();
+        query_tools.enter_source_query("orders_summary").unwrap();
+        assert_eq!(
+            query_tools
+                .enter_source_query("customer_totals")
+                .unwrap_err()
+                .message,
+            "Source query of cube customer_totals depends on itself: customer_totals -> orders_summary -> customer_totals"
+        );
+
+        query_tools.exit_source_query();
+        query_tools.exit_source_query();
+        assert!(query_tools.enter_source_query("customer_totals").is_ok());
+    }
 }
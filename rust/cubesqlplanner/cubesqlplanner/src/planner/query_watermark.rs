@@ -0,0 +1,83 @@
+use super::query_tools::QueryTools;
+use crate::planner::sql_templates::PlanSqlTemplates;
+use cubenativeutils::CubeError;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const WATERMARK_PREFIX: &str = "cube-wm:";
+const ANONYMOUS_PRINCIPAL: &str = "anonymous";
+const TOKEN_BYTES: usize = 8;
+
+// Short token derived from the principal with a deployment secret. Embedded into the query text,
+// so a query found in warehouse logs or screenshots can be traced back to the tenant or user
+// by recomputing tokens of known principals. The principal itself can't be read from the token
+pub struct QueryWatermark {
+    token: String,
+}
+
+impl QueryWatermark {
+    pub fn try_new(secret: &str, query_tools: &QueryTools) -> Result<Self, CubeError> {
+        let principal = query_tools
+            .principal()
+            .unwrap_or_else(|| ANONYMOUS_PRINCIPAL.to_string());
+        Ok(Self {
+            token: Self::token_for(secret, &principal)?,
+        })
+    }
+
+    pub fn token_for(secret: &str, principal: &str) -> Result<String, CubeError> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|e| CubeError::internal(format!("Invalid watermark secret: {}", e)))?;
+        mac.update(principal.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        Ok(digest[..TOKEN_BYTES]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    }
+
+    pub fn token(&self) -> &String {
+        &self.token
+    }
+
+    pub fn apply(&self, sql: &str, templates: &PlanSqlTemplates) -> Result<String, CubeError> {
+        let comment = templates.comment(&format!("{}{}", WATERMARK_PREFIX, self.token))?;
+        Ok(format!("{} {}", sql, comment))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::test_fixtures::QueryToolsFixture;
+    use serde_json::json;
+
+    #[test]
+    fn token_is_truncated_hmac_of_the_principal() {
+        assert_eq!(
+            QueryWatermark::token_for("secret", "alice").unwrap(),
+            "4360c67bc8102511"
+        );
+        assert_ne!(
+            QueryWatermark::token_for("other", "alice").unwrap(),
+            QueryWatermark::token_for("secret", "alice").unwrap()
+        );
+    }
+
+    #[test]
+    fn watermark_comment_is_appended_to_the_query() {
+        let query_tools = QueryToolsFixture::new()
+            .template("statements/comment", "/* {{ text }} */")
+            .security_context(json!({ "userId": "alice" }))
+            .build();
+        let templates = PlanSqlTemplates::new(query_tools.templates_render());
+        let watermark = QueryWatermark::try_new("secret", &query_tools).unwrap();
+        assert_eq!(
+            watermark.apply("SELECT 1", &templates).unwrap(),
+            "SELECT 1 /* cube-wm:4360c67bc8102511 */"
+        );
+
+        let anonymous = QueryWatermark::try_new("secret", &QueryToolsFixture::new().build());
+        assert_eq!(anonymous.unwrap().token(), "31d46dd9d4009b88");
+    }
+}
@@ -0,0 +1,62 @@
+use super::query_tools::QueryTools;
+use super::sql_evaluator::sql_nodes::SqlNodesFactory;
+use super::sql_templates::PlanSqlTemplates;
+use super::{evaluate_with_context, QueryProperties, VisitorContext};
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+pub struct RowCountEstimate {
+    query_tools: Rc<QueryTools>,
+    request: Rc<QueryProperties>,
+}
+
+impl RowCountEstimate {
+    pub fn new(query_tools: Rc<QueryTools>, request: Rc<QueryProperties>) -> Self {
+        Self {
+            query_tools,
+            request,
+        }
+    }
+
+    // Returns estimate sql and the strategy name which tells the caller
+    // how the estimate should be read from the result
+    pub fn build(
+        &self,
+        query_sql: &str,
+        templates: &PlanSqlTemplates,
+    ) -> Result<(String, String), CubeError> {
+        if !templates.supports_row_count_estimate() {
+            return Err(CubeError::user(format!(
+                "Row count estimation is not supported by this data source"
+            )));
+        }
+        let sql = templates.row_count_estimate(query_sql, self.base_tables()?)?;
+        let strategy = templates.row_count_estimate_strategy()?;
+        Ok((sql, strategy))
+    }
+
+    // Only cubes defined with `sqlTable` have physical tables to estimate
+    fn base_tables(&self) -> Result<Vec<String>, CubeError> {
+        let context = Rc::new(VisitorContext::new(&SqlNodesFactory::new()));
+        let evaluator_compiler_cell = self.query_tools.evaluator_compiler().clone();
+        let mut result = Vec::new();
+        for cube_name in self.request.target_cubes()? {
+            let cube = self
+                .query_tools
+                .cube_evaluator()
+                .cube_from_path(cube_name.clone())?;
+            if cube.sql_table()?.is_none() {
+                continue;
+            }
+            let evaluator = evaluator_compiler_cell
+                .borrow_mut()
+                .add_cube_table_evaluator(cube_name)?;
+            result.push(evaluate_with_context(
+                &evaluator,
+                self.query_tools.clone(),
+                context.clone(),
+            )?);
+        }
+        Ok(result)
+    }
+}
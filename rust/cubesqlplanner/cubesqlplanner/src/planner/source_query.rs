@@ -0,0 +1,121 @@
+use super::base_query::plan_query;
+use super::query_tools::QueryTools;
+use super::sql_templates::PlanSqlTemplates;
+use super::QueryProperties;
+use crate::cube_bridge::audit_log_sink::AuditLogSink;
+use crate::cube_bridge::base_query_options::{BaseQueryOptions, BaseQueryOptionsStatic};
+use crate::cube_bridge::base_tools::BaseTools;
+use crate::cube_bridge::cancellation_token::CancellationToken;
+use crate::cube_bridge::cube_definition::CubeSourceQuery;
+use crate::cube_bridge::evaluator::CubeEvaluator;
+use crate::cube_bridge::join_graph::JoinGraph;
+use crate::cube_bridge::metrics_recorder::MetricsRecorder;
+use crate::cube_bridge::table_name_resolver::TableNameResolver;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::rc::Rc;
+
+// Options of a cube source query. Members and filters come from the cube definition,
+// services are the ones of the query the cube is used in
+struct SourceQueryOptions {
+    query_tools: Rc<QueryTools>,
+    static_data: BaseQueryOptionsStatic,
+}
+
+impl BaseQueryOptions for SourceQueryOptions {
+    fn measures(&self) -> Result<Option<Vec<String>>, CubeError> {
+        Ok(self.static_data.measures.clone())
+    }
+
+    fn dimensions(&self) -> Result<Option<Vec<String>>, CubeError> {
+        Ok(self.static_data.dimensions.clone())
+    }
+
+    fn cube_evaluator(&self) -> Result<Rc<dyn CubeEvaluator>, CubeError> {
+        Ok(self.query_tools.cube_evaluator().clone())
+    }
+
+    fn base_tools(&self) -> Result<Rc<dyn BaseTools>, CubeError> {
+        Ok(self.query_tools.base_tools().clone())
+    }
+
+    fn join_graph(&self) -> Result<Rc<dyn JoinGraph>, CubeError> {
+        Ok(self.query_tools.join_graph().clone())
+    }
+
+    fn audit_log_sink(&self) -> Result<Option<Rc<dyn AuditLogSink>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_audit_log_sink(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn cancellation_token(&self) -> Result<Option<Rc<dyn CancellationToken>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_cancellation_token(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn table_name_resolver(&self) -> Result<Option<Rc<dyn TableNameResolver>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_table_name_resolver(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn metrics_recorder(&self) -> Result<Option<Rc<dyn MetricsRecorder>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_metrics_recorder(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &BaseQueryOptionsStatic {
+        &self.static_data
+    }
+}
+
+// Query a cube is built on, planned with the tools of the outer query, so its params,
+// timezone and compiled members are shared. Cubes built on other derived cubes
+// are planned recursively
+pub struct SourceQuery {
+    cube_name: String,
+    source: CubeSourceQuery,
+}
+
+impl SourceQuery {
+    pub fn new(cube_name: String, source: CubeSourceQuery) -> Self {
+        Self { cube_name, source }
+    }
+
+    pub fn to_sql(&self, query_tools: Rc<QueryTools>) -> Result<String, CubeError> {
+        if let Some(table) = &self.source.materialized {
+            return Ok(table.clone());
+        }
+        query_tools.enter_source_query(&self.cube_name)?;
+        let result = self.plan_sql(query_tools.clone());
+        query_tools.exit_source_query();
+        Ok(format!("({})", result?))
+    }
+
+    fn plan_sql(&self, query_tools: Rc<QueryTools>) -> Result<String, CubeError> {
+        let static_data = BaseQueryOptionsStatic::default().with_saved_query(&self.source.query());
+        let options = Rc::new(SourceQueryOptions {
+            query_tools: query_tools.clone(),
+            static_data,
+        });
+        let request = QueryProperties::try_new(query_tools.clone(), options)?;
+        let templates = PlanSqlTemplates::new(query_tools.templates_render());
+        let select = plan_query(query_tools, request, templates.clone())?;
+        select.to_sql(&templates)
+    }
+}
@@ -49,6 +49,14 @@ impl TraversalVisitor for CubeNamesCollector {
             MemberSymbol::CubeTable(e) => {
                 self.names.insert(e.cube_name().clone());
             }
+            MemberSymbol::Segment(e) => {
+                if e.owned_by_cube() {
+                    self.names.insert(e.cube_name().clone());
+                }
+                for name in e.get_dependent_cubes().into_iter() {
+                    self.names.insert(name);
+                }
+            }
         };
         Ok(Some(()))
     }
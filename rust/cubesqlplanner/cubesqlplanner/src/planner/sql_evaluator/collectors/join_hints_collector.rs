@@ -47,6 +47,14 @@ impl TraversalVisitor for JoinHintsCollector {
             MemberSymbol::CubeTable(e) => {
                 self.hints.push(e.cube_name().clone());
             }
+            MemberSymbol::Segment(e) => {
+                if e.owned_by_cube() {
+                    self.hints.push(e.cube_name().clone());
+                }
+                for name in e.get_dependent_cubes().into_iter() {
+                    self.hints.push(name);
+                }
+            }
         };
         Ok(Some(()))
     }
@@ -3,7 +3,7 @@ use super::dependecy::DependenciesBuilder;
 use super::symbols::MemberSymbol;
 use super::{
     CubeNameSymbolFactory, CubeTableSymbolFactory, DimensionSymbolFactory, MeasureSymbolFactory,
-    SqlCall, SymbolFactory, TraversalVisitor,
+    SegmentSymbolFactory, SqlCall, SymbolFactory, TraversalVisitor,
 };
 use crate::cube_bridge::evaluator::CubeEvaluator;
 use crate::cube_bridge::memeber_sql::MemberSql;
@@ -14,6 +14,7 @@ pub struct Compiler {
     cube_evaluator: Rc<dyn CubeEvaluator>,
     /* (type, name) */
     members: HashMap<(String, String), Rc<MemberSymbol>>,
+    cache_hits: u64,
 }
 
 impl Compiler {
@@ -21,6 +22,7 @@ impl Compiler {
         Self {
             cube_evaluator,
             members: HashMap::new(),
+            cache_hits: 0,
         }
     }
 
@@ -29,6 +31,7 @@ impl Compiler {
         measure: String,
     ) -> Result<Rc<MemberSymbol>, CubeError> {
         if let Some(exists) = self.exists_member::<MeasureSymbolFactory>(&measure) {
+            self.cache_hits += 1;
             Ok(exists.clone())
         } else {
             self.add_evaluator_impl(
@@ -43,6 +46,7 @@ impl Compiler {
         dimension: String,
     ) -> Result<Rc<MemberSymbol>, CubeError> {
         if let Some(exists) = self.exists_member::<DimensionSymbolFactory>(&dimension) {
+            self.cache_hits += 1;
             Ok(exists.clone())
         } else {
             self.add_evaluator_impl(
@@ -52,11 +56,27 @@ impl Compiler {
         }
     }
 
+    pub fn add_segment_evaluator(
+        &mut self,
+        segment: String,
+    ) -> Result<Rc<MemberSymbol>, CubeError> {
+        if let Some(exists) = self.exists_member::<SegmentSymbolFactory>(&segment) {
+            self.cache_hits += 1;
+            Ok(exists.clone())
+        } else {
+            self.add_evaluator_impl(
+                &segment,
+                SegmentSymbolFactory::try_new(&segment, self.cube_evaluator.clone())?,
+            )
+        }
+    }
+
     pub fn add_cube_name_evaluator(
         &mut self,
         cube_name: String,
     ) -> Result<Rc<MemberSymbol>, CubeError> {
         if let Some(exists) = self.exists_member::<CubeNameSymbolFactory>(&cube_name) {
+            self.cache_hits += 1;
             Ok(exists.clone())
         } else {
             self.add_evaluator_impl(
@@ -71,6 +91,7 @@ impl Compiler {
         cube_name: String,
     ) -> Result<Rc<MemberSymbol>, CubeError> {
         if let Some(exists) = self.exists_member::<CubeTableSymbolFactory>(&cube_name) {
+            self.cache_hits += 1;
             Ok(exists.clone())
         } else {
             self.add_evaluator_impl(
@@ -80,6 +101,11 @@ impl Compiler {
         }
     }
 
+    // Members requested again and taken from the compiled ones
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits
+    }
+
     pub fn join_hints(&self) -> Result<Vec<String>, CubeError> {
         let mut collector = JoinHintsCollector::new();
         for member in self.members.values() {
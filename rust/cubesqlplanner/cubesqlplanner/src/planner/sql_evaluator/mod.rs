@@ -12,10 +12,10 @@ pub use compiler::Compiler;
 pub use dependecy::{CubeDepProperty, Dependency};
 pub use references_builder::ReferencesBuilder;
 pub use sql_call::SqlCall;
-pub use sql_visitor::SqlEvaluatorVisitor;
+pub use sql_visitor::{EvaluatedSqlCache, SqlEvaluatorVisitor};
 pub use symbols::{
     CubeNameSymbol, CubeNameSymbolFactory, CubeTableSymbol, CubeTableSymbolFactory,
     DimensionSymbol, DimensionSymbolFactory, MeasureSymbol, MeasureSymbolFactory, MemberSymbol,
-    SymbolFactory,
+    SegmentSymbol, SegmentSymbolFactory, SymbolFactory, TimeAttribute,
 };
 pub use visitor::TraversalVisitor;
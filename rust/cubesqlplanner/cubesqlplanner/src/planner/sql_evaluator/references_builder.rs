@@ -118,6 +118,9 @@ impl ReferencesBuilder {
             FilterItem::Item(item) => {
                 self.validate_member(item.member_evaluator().clone(), &None)?
             }
+            FilterItem::Segment(item) => {
+                self.validate_member(item.member_evaluator().clone(), &None)?
+            }
             FilterItem::Group(group) => {
                 for itm in group.items.iter() {
                     self.validate_filter_item(itm)?
@@ -138,6 +141,11 @@ impl ReferencesBuilder {
                 &None,
                 references,
             )?,
+            FilterItem::Segment(item) => self.resolve_references_for_member(
+                item.member_evaluator().clone(),
+                &None,
+                references,
+            )?,
             FilterItem::Group(group) => {
                 for itm in group.items.iter() {
                     self.resolve_references_for_filter_item(itm, references)?
@@ -2,6 +2,7 @@ use super::dependecy::{ContextSymbolDep, CubeDepProperty, CubeDependency, Depend
 use super::sql_nodes::SqlNode;
 use super::{symbols::MemberSymbol, SqlEvaluatorVisitor};
 use crate::cube_bridge::memeber_sql::{ContextSymbolArg, MemberSql, MemberSqlArg, MemberSqlStruct};
+use crate::planner::filter::FilterParamsRenderer;
 use crate::planner::query_tools::QueryTools;
 use cubenativeutils::CubeError;
 use std::rc::Rc;
@@ -29,7 +30,22 @@ impl SqlCall {
                 self.evaluate_single_dep(&d, visitor, node_processor.clone(), query_tools.clone())
             })
             .collect::<Result<Vec<_>, _>>()?;
-        self.member_sql.call(args)
+        let sql = self.member_sql.call(args)?;
+        if self.has_filter_params_deps() && FilterParamsRenderer::has_markers(&sql) {
+            return FilterParamsRenderer::new(query_tools).render(&sql);
+        }
+        Ok(sql)
+    }
+
+    fn has_filter_params_deps(&self) -> bool {
+        self.deps.iter().any(|d| {
+            matches!(
+                d,
+                Dependency::ContextDependency(
+                    ContextSymbolDep::FilterParams | ContextSymbolDep::FilterGroup
+                )
+            )
+        })
     }
 
     pub fn extract_symbol_deps(&self, result: &mut Vec<Rc<MemberSymbol>>) {
@@ -147,11 +163,13 @@ impl SqlCall {
                 ))
             }
             ContextSymbolDep::FilterParams => MemberSqlArg::ContextSymbol(
-                ContextSymbolArg::FilterParams(query_tools.base_tools().filters_proxy()?),
-            ),
-            ContextSymbolDep::FilterGroup => MemberSqlArg::ContextSymbol(
-                ContextSymbolArg::FilterGroup(query_tools.base_tools().filter_group_function()?),
+                ContextSymbolArg::FilterParams(query_tools.base_tools().filters_proxy_for_rust()?),
             ),
+            ContextSymbolDep::FilterGroup => {
+                MemberSqlArg::ContextSymbol(ContextSymbolArg::FilterGroup(
+                    query_tools.base_tools().filter_group_function_for_rust()?,
+                ))
+            }
         };
         Ok(res)
     }
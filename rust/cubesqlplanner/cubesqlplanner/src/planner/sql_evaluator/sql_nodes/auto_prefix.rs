@@ -76,6 +76,10 @@ impl SqlNode for AutoPrefixSqlNode {
                 let cube_alias = self.resolve_cube_alias(&ev.cube_name());
                 query_tools.auto_prefix_with_cube_name(&cube_alias, &input)
             }
+            MemberSymbol::Segment(ev) => {
+                let cube_alias = self.resolve_cube_alias(&ev.cube_name());
+                query_tools.auto_prefix_with_cube_name(&cube_alias, &input)
+            }
             MemberSymbol::CubeName(_) => {
                 let cube_alias = self.resolve_cube_alias(&input);
                 query_tools.escape_column_name(&cube_alias)
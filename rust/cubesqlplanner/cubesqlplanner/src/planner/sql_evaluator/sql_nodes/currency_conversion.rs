@@ -0,0 +1,61 @@
+use super::SqlNode;
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::MemberSymbol;
+use crate::planner::sql_evaluator::SqlEvaluatorVisitor;
+use crate::planner::sql_templates::PlanSqlTemplates;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub struct CurrencyConversionSqlNode {
+    input: Rc<dyn SqlNode>,
+    rates_references: HashMap<String, String>,
+}
+
+impl CurrencyConversionSqlNode {
+    pub fn new(input: Rc<dyn SqlNode>, rates_references: HashMap<String, String>) -> Rc<Self> {
+        Rc::new(Self {
+            input,
+            rates_references,
+        })
+    }
+
+    pub fn input(&self) -> &Rc<dyn SqlNode> {
+        &self.input
+    }
+}
+
+impl SqlNode for CurrencyConversionSqlNode {
+    fn to_sql(
+        &self,
+        visitor: &SqlEvaluatorVisitor,
+        node: &Rc<MemberSymbol>,
+        query_tools: Rc<QueryTools>,
+        node_processor: Rc<dyn SqlNode>,
+    ) -> Result<String, CubeError> {
+        let input =
+            self.input
+                .to_sql(visitor, node, query_tools.clone(), node_processor.clone())?;
+        let res = match node.as_ref() {
+            MemberSymbol::Measure(ev) if !ev.is_calculated() => {
+                if let Some(rates_alias) = self.rates_references.get(&ev.full_name()) {
+                    let templates = PlanSqlTemplates::new(query_tools.templates_render());
+                    templates.currency_convert(&input, rates_alias)?
+                } else {
+                    input
+                }
+            }
+            _ => input,
+        };
+        Ok(res)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self.clone()
+    }
+
+    fn childs(&self) -> Vec<Rc<dyn SqlNode>> {
+        vec![self.input.clone()]
+    }
+}
@@ -33,6 +33,9 @@ impl SqlNode for EvaluateSqlNode {
                 ev.evaluate_sql(visitor, node_processor.clone(), query_tools.clone())
             }
             MemberSymbol::CubeName(ev) => ev.evaluate_sql(),
+            MemberSymbol::Segment(ev) => {
+                ev.evaluate_sql(visitor, node_processor.clone(), query_tools.clone())
+            }
         }
     }
 
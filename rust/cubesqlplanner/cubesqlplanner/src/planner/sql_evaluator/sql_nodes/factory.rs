@@ -1,8 +1,8 @@
 use super::leaf_time_dimension::LeafTimeDimensionNode;
 use super::{
-    AutoPrefixSqlNode, EvaluateSqlNode, FinalMeasureSqlNode, MeasureFilterSqlNode,
-    MultiStageRankNode, MultiStageWindowNode, RenderReferencesSqlNode, RollingWindowNode,
-    RootSqlNode, SqlNode, TimeShiftSqlNode, UngroupedMeasureSqlNode,
+    AutoPrefixSqlNode, CurrencyConversionSqlNode, EvaluateSqlNode, FinalMeasureSqlNode,
+    MeasureFilterSqlNode, MultiStageRankNode, MultiStageWindowNode, RenderReferencesSqlNode,
+    RollingWindowNode, RootSqlNode, SqlNode, TimeShiftSqlNode, UngroupedMeasureSqlNode,
     UngroupedQueryFinalMeasureSqlNode,
 };
 use crate::plan::schema::QualifiedColumnName;
@@ -11,7 +11,7 @@ use std::rc::Rc;
 
 #[derive(Clone)]
 pub struct SqlNodesFactory {
-    time_shifts: HashMap<String, String>,
+    time_shifts: HashMap<String, Vec<String>>,
     ungrouped: bool,
     ungrouped_measure: bool,
     render_references: HashMap<String, QualifiedColumnName>,
@@ -21,6 +21,7 @@ pub struct SqlNodesFactory {
     multi_stage_rank: Option<Vec<String>>,   //partition_by
     multi_stage_window: Option<Vec<String>>, //partition_by
     rolling_window: bool,
+    currency_rates_references: HashMap<String, String>,
 }
 
 impl SqlNodesFactory {
@@ -36,10 +37,11 @@ impl SqlNodesFactory {
             multi_stage_rank: None,
             multi_stage_window: None,
             rolling_window: false,
+            currency_rates_references: HashMap::new(),
         }
     }
 
-    pub fn set_time_shifts(&mut self, time_shifts: HashMap<String, String>) {
+    pub fn set_time_shifts(&mut self, time_shifts: HashMap<String, Vec<String>>) {
         self.time_shifts = time_shifts;
     }
 
@@ -80,6 +82,10 @@ impl SqlNodesFactory {
         self.rolling_window = value;
     }
 
+    pub fn set_currency_rates_references(&mut self, value: HashMap<String, String>) {
+        self.currency_rates_references = value;
+    }
+
     pub fn set_ungrouped_measure_references(
         &mut self,
         value: HashMap<String, QualifiedColumnName>,
@@ -109,7 +115,8 @@ impl SqlNodesFactory {
         let measure_filter_processor = MeasureFilterSqlNode::new(auto_prefix_processor.clone());
 
         let measure_processor =
-            self.add_ungrouped_measure_reference_if_needed(measure_filter_processor.clone());
+            self.add_currency_conversion_if_needed(measure_filter_processor.clone());
+        let measure_processor = self.add_ungrouped_measure_reference_if_needed(measure_processor);
         let measure_processor = self.final_measure_node_processor(measure_processor);
         let measure_processor = self
             .add_multi_stage_window_if_needed(measure_processor, measure_filter_processor.clone());
@@ -135,6 +142,14 @@ impl SqlNodesFactory {
         }
     }
 
+    fn add_currency_conversion_if_needed(&self, default: Rc<dyn SqlNode>) -> Rc<dyn SqlNode> {
+        if !self.currency_rates_references.is_empty() {
+            CurrencyConversionSqlNode::new(default, self.currency_rates_references.clone())
+        } else {
+            default
+        }
+    }
+
     fn add_multi_stage_rank_if_needed(&self, default: Rc<dyn SqlNode>) -> Rc<dyn SqlNode> {
         if let Some(partition_by) = &self.multi_stage_rank {
             MultiStageRankNode::new(default, partition_by.clone())
@@ -2,10 +2,14 @@ use super::SqlNode;
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::MemberSymbol;
 use crate::planner::sql_evaluator::SqlEvaluatorVisitor;
+use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
 use std::any::Any;
 use std::rc::Rc;
 
+const OVERFLOW_SAFE_SUM_PRECISION: u32 = 38;
+const OVERFLOW_SAFE_SUM_SCALE: u32 = 10;
+
 pub struct FinalMeasureSqlNode {
     input: Rc<dyn SqlNode>,
 }
@@ -46,6 +50,23 @@ impl SqlNode for FinalMeasureSqlNode {
                     } else {
                         &ev.measure_type()
                     };
+                    let templates = PlanSqlTemplates::new(query_tools.templates_render());
+                    let overflow_safe = ev
+                        .overflow_safe()
+                        .unwrap_or(query_tools.overflow_safe_aggregation());
+                    let input = match ev.decimal() {
+                        Some(decimal) if matches!(measure_type, "sum" | "avg") => {
+                            templates.cast_to_decimal(&input, decimal.precision, decimal.scale)?
+                        }
+                        // Wider than BIGINT and keeps the fraction of decimal inputs
+                        None if overflow_safe && measure_type == "sum" => templates
+                            .cast_to_decimal(
+                                &input,
+                                OVERFLOW_SAFE_SUM_PRECISION,
+                                OVERFLOW_SAFE_SUM_SCALE,
+                            )?,
+                        _ => input,
+                    };
 
                     format!("{}({})", measure_type, input)
                 }
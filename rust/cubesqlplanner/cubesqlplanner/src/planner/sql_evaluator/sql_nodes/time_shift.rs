@@ -2,22 +2,25 @@ use super::SqlNode;
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::MemberSymbol;
 use crate::planner::sql_evaluator::SqlEvaluatorVisitor;
+use crate::planner::sql_templates::FilterTemplates;
 use cubenativeutils::CubeError;
 use std::any::Any;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+// Intervals of a dimension are of distinct granularities and each is added on its own,
+// since not every database accepts an interval of several granularities
 pub struct TimeShiftSqlNode {
-    shifts: HashMap<String, String>,
+    shifts: HashMap<String, Vec<String>>,
     input: Rc<dyn SqlNode>,
 }
 
 impl TimeShiftSqlNode {
-    pub fn new(shifts: HashMap<String, String>, input: Rc<dyn SqlNode>) -> Rc<Self> {
+    pub fn new(shifts: HashMap<String, Vec<String>>, input: Rc<dyn SqlNode>) -> Rc<Self> {
         Rc::new(Self { shifts, input })
     }
 
-    pub fn shifts(&self) -> &HashMap<String, String> {
+    pub fn shifts(&self) -> &HashMap<String, Vec<String>> {
         &self.shifts
     }
 
@@ -38,13 +41,17 @@ impl SqlNode for TimeShiftSqlNode {
             self.input
                 .to_sql(visitor, node, query_tools.clone(), node_processor.clone())?;
         let res = match node.as_ref() {
-            MemberSymbol::Dimension(ev) => {
-                if let Some(shift) = self.shifts.get(&ev.full_name()) {
-                    format!("({input} + interval '{shift}')")
-                } else {
-                    input
+            MemberSymbol::Dimension(ev) => match self.shifts.get(&ev.full_name()) {
+                Some(intervals) if !intervals.is_empty() => {
+                    let templates = FilterTemplates::new(query_tools.templates_render());
+                    let mut res = input;
+                    for interval in intervals.iter() {
+                        res = format!("({})", templates.add_interval(res, interval.clone())?);
+                    }
+                    res
                 }
-            }
+                _ => input,
+            },
             _ => input,
         };
         Ok(res)
@@ -58,3 +65,63 @@ impl SqlNode for TimeShiftSqlNode {
         vec![self.input.clone()]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::test_fixtures::{dimension, dimension_definition, QueryToolsFixture};
+
+    struct ColumnSqlNode;
+
+    impl SqlNode for ColumnSqlNode {
+        fn to_sql(
+            &self,
+            _visitor: &SqlEvaluatorVisitor,
+            _node: &Rc<MemberSymbol>,
+            _query_tools: Rc<QueryTools>,
+            _node_processor: Rc<dyn SqlNode>,
+        ) -> Result<String, CubeError> {
+            Ok("created_at".to_string())
+        }
+
+        fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+            self.clone()
+        }
+
+        fn childs(&self) -> Vec<Rc<dyn SqlNode>> {
+            vec![]
+        }
+    }
+
+    fn render(intervals: Vec<&str>) -> String {
+        let query_tools = QueryToolsFixture::new().build();
+        let node = dimension("orders.created_at", dimension_definition("time"));
+        let shifts = HashMap::from([(
+            "orders.created_at".to_string(),
+            intervals.into_iter().map(|i| i.to_string()).collect(),
+        )]);
+        let time_shift = TimeShiftSqlNode::new(shifts, Rc::new(ColumnSqlNode));
+        let visitor = SqlEvaluatorVisitor::new(query_tools.clone());
+        time_shift
+            .to_sql(&visitor, &node, query_tools, time_shift.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn single_interval() {
+        assert_eq!(render(vec!["1 year"]), "(created_at + interval '1 year')");
+    }
+
+    #[test]
+    fn intervals_of_several_granularities_are_added_one_by_one() {
+        assert_eq!(
+            render(vec!["1 year", "-1 month"]),
+            "((created_at + interval '1 year') + interval '-1 month')"
+        );
+    }
+
+    #[test]
+    fn cancelled_shift_keeps_dimension() {
+        assert_eq!(render(vec![]), "created_at");
+    }
+}
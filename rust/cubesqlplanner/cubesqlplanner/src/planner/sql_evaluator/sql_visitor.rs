@@ -2,16 +2,57 @@ use super::sql_nodes::SqlNode;
 use super::MemberSymbol;
 use crate::planner::query_tools::QueryTools;
 use cubenativeutils::CubeError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem::Discriminant;
 use std::rc::Rc;
 
+type EvaluatedSqlKey = (Discriminant<MemberSymbol>, String);
+
+// Sql of members rendered by the root node processor of a context. It doesn't change within
+// the context, so members referenced from projection, filters, order and by other members
+// are rendered once. Nodes which render dependencies with other processors aren't cached
+pub struct EvaluatedSqlCache {
+    node_processor: Rc<dyn SqlNode>,
+    sql: RefCell<HashMap<EvaluatedSqlKey, String>>,
+}
+
+impl EvaluatedSqlCache {
+    pub fn new(node_processor: Rc<dyn SqlNode>) -> Self {
+        Self {
+            node_processor,
+            sql: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn is_root_processor(&self, node_processor: &Rc<dyn SqlNode>) -> bool {
+        Rc::as_ptr(&self.node_processor) as *const u8 == Rc::as_ptr(node_processor) as *const u8
+    }
+
+    fn key(node: &Rc<MemberSymbol>) -> EvaluatedSqlKey {
+        (std::mem::discriminant(node.as_ref()), node.full_name())
+    }
+}
+
 #[derive(Clone)]
 pub struct SqlEvaluatorVisitor {
     query_tools: Rc<QueryTools>,
+    cache: Option<Rc<EvaluatedSqlCache>>,
 }
 
 impl SqlEvaluatorVisitor {
     pub fn new(query_tools: Rc<QueryTools>) -> Self {
-        Self { query_tools }
+        Self {
+            query_tools,
+            cache: None,
+        }
+    }
+
+    pub fn new_with_cache(query_tools: Rc<QueryTools>, cache: Rc<EvaluatedSqlCache>) -> Self {
+        Self {
+            query_tools,
+            cache: Some(cache),
+        }
     }
 
     pub fn apply(
@@ -19,8 +60,125 @@ impl SqlEvaluatorVisitor {
         node: &Rc<MemberSymbol>,
         node_processor: Rc<dyn SqlNode>,
     ) -> Result<String, CubeError> {
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|cache| cache.is_root_processor(&node_processor));
+        if let Some(cache) = cache {
+            if let Some(sql) = cache.sql.borrow().get(&EvaluatedSqlCache::key(node)) {
+                return Ok(sql.clone());
+            }
+        }
         let result =
             node_processor.to_sql(self, node, self.query_tools.clone(), node_processor.clone())?;
+        if let Some(cache) = cache {
+            cache
+                .sql
+                .borrow_mut()
+                .insert(EvaluatedSqlCache::key(node), result.clone());
+        }
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::test_fixtures::{dimension, dimension_definition, QueryToolsFixture};
+    use std::any::Any;
+
+    // Renders members of the query from the shared base member, like dimensions
+    // referencing `${CUBE.base}`, and counts renders of each member
+    struct CountingSqlNode {
+        base: Rc<MemberSymbol>,
+        renders: RefCell<HashMap<String, usize>>,
+    }
+
+    impl SqlNode for CountingSqlNode {
+        fn to_sql(
+            &self,
+            visitor: &SqlEvaluatorVisitor,
+            node: &Rc<MemberSymbol>,
+            _query_tools: Rc<QueryTools>,
+            node_processor: Rc<dyn SqlNode>,
+        ) -> Result<String, CubeError> {
+            *self
+                .renders
+                .borrow_mut()
+                .entry(node.full_name())
+                .or_default() += 1;
+            if node.full_name() == self.base.full_name() {
+                return Ok("lower(status)".to_string());
+            }
+            let base = visitor.apply(&self.base, node_processor)?;
+            Ok(format!("{} || '{}'", base, node.full_name()))
+        }
+
+        fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+            self.clone()
+        }
+
+        fn childs(&self) -> Vec<Rc<dyn SqlNode>> {
+            vec![]
+        }
+    }
+
+    fn counting_node() -> Rc<CountingSqlNode> {
+        Rc::new(CountingSqlNode {
+            base: dimension("orders.base", dimension_definition("string")),
+            renders: RefCell::new(HashMap::new()),
+        })
+    }
+
+    fn members(count: usize) -> Vec<Rc<MemberSymbol>> {
+        (0..count)
+            .map(|i| dimension(&format!("orders.dim{}", i), dimension_definition("string")))
+            .collect()
+    }
+
+    #[test]
+    fn members_and_nested_dependencies_are_rendered_once() {
+        let query_tools = QueryToolsFixture::new().build();
+        let node = counting_node();
+        let node_processor: Rc<dyn SqlNode> = node.clone();
+        let visitor = SqlEvaluatorVisitor::new_with_cache(
+            query_tools,
+            Rc::new(EvaluatedSqlCache::new(node_processor.clone())),
+        );
+
+        // Members are referenced from projection, filters and order of the query
+        let members = members(60);
+        for _ in 0..3 {
+            for member in members.iter() {
+                visitor.apply(member, node_processor.clone()).unwrap();
+            }
+        }
+
+        assert_eq!(
+            visitor.apply(&members[59], node_processor).unwrap(),
+            "lower(status) || 'orders.dim59'"
+        );
+        let renders = node.renders.borrow();
+        assert_eq!(renders.len(), 61);
+        assert!(renders.values().all(|count| *count == 1));
+    }
+
+    #[test]
+    fn members_are_rendered_again_by_other_processors() {
+        let query_tools = QueryToolsFixture::new().build();
+        let root: Rc<dyn SqlNode> = counting_node();
+        let node = counting_node();
+        let node_processor: Rc<dyn SqlNode> = node.clone();
+        let visitor =
+            SqlEvaluatorVisitor::new_with_cache(query_tools, Rc::new(EvaluatedSqlCache::new(root)));
+
+        let members = members(2);
+        for _ in 0..2 {
+            for member in members.iter() {
+                visitor.apply(member, node_processor.clone()).unwrap();
+            }
+        }
+
+        assert_eq!(node.renders.borrow().get("orders.base"), Some(&4));
+    }
+}
@@ -1,14 +1,19 @@
 use super::{MemberSymbol, SymbolFactory};
-use crate::cube_bridge::cube_definition::CubeDefinition;
+use crate::cube_bridge::cube_definition::{CubeDefinition, CubeUnion};
 use crate::cube_bridge::evaluator::CubeEvaluator;
 use crate::cube_bridge::memeber_sql::MemberSql;
 use crate::planner::query_tools::QueryTools;
+use crate::planner::source_query::SourceQuery;
 use crate::planner::sql_evaluator::{sql_nodes::SqlNode, Compiler, SqlCall, SqlEvaluatorVisitor};
+use crate::planner::sql_templates::filter::FilterTemplates;
+use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::rc::Rc;
 
+const PREVIEW_SAMPLE_PERCENT: u32 = 10;
+
 pub struct CubeNameSymbol {
     cube_name: String,
 }
@@ -67,7 +72,7 @@ impl SymbolFactory for CubeNameSymbolFactory {
 
 pub struct CubeTableSymbol {
     cube_name: String,
-    member_sql: Rc<SqlCall>,
+    member_sql: Option<Rc<SqlCall>>,
     #[allow(dead_code)]
     definition: Rc<dyn CubeDefinition>,
     is_table_sql: bool,
@@ -76,7 +81,7 @@ pub struct CubeTableSymbol {
 impl CubeTableSymbol {
     pub fn new(
         cube_name: String,
-        member_sql: Rc<SqlCall>,
+        member_sql: Option<Rc<SqlCall>>,
         definition: Rc<dyn CubeDefinition>,
         is_table_sql: bool,
     ) -> Self {
@@ -93,12 +98,81 @@ impl CubeTableSymbol {
         visitor: &SqlEvaluatorVisitor,
         node_processor: Rc<dyn SqlNode>,
         query_tools: Rc<QueryTools>,
+    ) -> Result<String, CubeError> {
+        let sql = self.table_sql(visitor, node_processor, query_tools.clone())?;
+        let sql = self.preview_sql(sql, query_tools.clone())?;
+        self.snapshot_sql(sql, query_tools)
+    }
+
+    // Preview queries sample cube tables. Subqueries can't be sampled
+    fn preview_sql(&self, sql: String, query_tools: Rc<QueryTools>) -> Result<String, CubeError> {
+        if !query_tools.preview() || sql.trim_start().starts_with('(') {
+            return Ok(sql);
+        }
+        let templates = PlanSqlTemplates::new(query_tools.templates_render());
+        match templates.table_sample(&sql, PREVIEW_SAMPLE_PERCENT)? {
+            Some(sampled) => Ok(format!("(SELECT * FROM {})", sampled)),
+            None => Ok(sql),
+        }
+    }
+
+    // Point in time queries see rows of snapshot cubes which are valid at the `asOf` time
+    fn snapshot_sql(&self, sql: String, query_tools: Rc<QueryTools>) -> Result<String, CubeError> {
+        let (Some(validity), Some(as_of)) =
+            (&self.definition.static_data().validity, query_tools.as_of())
+        else {
+            return Ok(sql);
+        };
+        let as_of = query_tools.allocate_param(
+            &query_tools
+                .date_time_tools()
+                .in_db_time_zone(as_of.clone())?,
+        );
+        let filter_templates = FilterTemplates::new(query_tools.templates_render());
+        let filter = filter_templates.validity_range(
+            &filter_templates.timestamp_param(&as_of)?,
+            &validity.valid_from,
+            &validity.valid_to,
+        )?;
+        let alias = PlanSqlTemplates::new(query_tools.templates_render())
+            .quote_identifier(&format!("{}_snapshot", self.cube_name))?;
+        Ok(format!(
+            "(SELECT * FROM {} AS {} WHERE {})",
+            sql, alias, filter
+        ))
+    }
+
+    fn table_sql(
+        &self,
+        visitor: &SqlEvaluatorVisitor,
+        node_processor: Rc<dyn SqlNode>,
+        query_tools: Rc<QueryTools>,
     ) -> Result<String, CubeError> {
         lazy_static! {
             static ref SIMPLE_ASTERIX_RE: Regex =
                 Regex::new(r#"(?i)^\s*select\s+\*\s+from\s+([a-zA-Z0-9_\-`".*]+)\s*$"#).unwrap();
         }
-        let sql = self.member_sql.eval(visitor, node_processor, query_tools)?;
+        if let Some(table) = query_tools.table_override(&self.cube_name) {
+            let templates = PlanSqlTemplates::new(query_tools.templates_render());
+            let table = Self::quoted_table_override(&self.cube_name, table, &templates)?;
+            return query_tools.resolve_table_name(&self.cube_name, table);
+        }
+        if let Some(source_query) = &self.definition.static_data().source_query {
+            let res = SourceQuery::new(self.cube_name.clone(), source_query.clone())
+                .to_sql(query_tools.clone())?;
+            return query_tools.resolve_table_name(&self.cube_name, res);
+        }
+        let Some(member_sql) = &self.member_sql else {
+            let Some(union) = &self.definition.static_data().union else {
+                return Err(CubeError::internal(format!(
+                    "Cube {} has neither sql nor union",
+                    self.cube_name
+                )));
+            };
+            let res = self.union_sql(union, query_tools.clone())?;
+            return query_tools.resolve_table_name(&self.cube_name, res);
+        };
+        let sql = member_sql.eval(visitor, node_processor, query_tools.clone())?;
         let res = if self.is_table_sql {
             sql
         } else {
@@ -112,8 +186,89 @@ impl CubeTableSymbol {
                 format!("({})", sql)
             }
         };
-        Ok(res)
+        query_tools.resolve_table_name(&self.cube_name, res)
+    }
+
+    // Overrides come with the request, so only `schema.table` names are accepted
+    // and every part of them is quoted
+    fn quoted_table_override(
+        cube_name: &str,
+        table: &str,
+        templates: &PlanSqlTemplates,
+    ) -> Result<String, CubeError> {
+        lazy_static! {
+            static ref TABLE_OVERRIDE_RE: Regex =
+                Regex::new(r"^[_a-zA-Z][_a-zA-Z0-9$]*(\.[_a-zA-Z][_a-zA-Z0-9$]*)?$").unwrap();
+        }
+        if !TABLE_OVERRIDE_RE.is_match(table) {
+            return Err(CubeError::user(format!(
+                "Table override '{}' of '{}' cube isn't a 'schema.table' name",
+                table, cube_name
+            )));
+        }
+        let parts = table
+            .split('.')
+            .map(|part| templates.quote_identifier(part))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(parts.join("."))
+    }
+
+    // Branches which can't contain rows of the query date range are skipped,
+    // the rest are filtered by the date range before they are unioned
+    fn union_sql(
+        &self,
+        union: &CubeUnion,
+        query_tools: Rc<QueryTools>,
+    ) -> Result<String, CubeError> {
+        let date_range = query_tools.source_date_range(&self.cube_name);
+        let mut branches = union
+            .tables
+            .iter()
+            .filter(|branch| {
+                // Date strings are ISO formatted, so they are compared lexicographically
+                date_range.map_or(true, |(from, to)| {
+                    branch.since.as_ref().map_or(true, |since| to >= since)
+                        && branch.until.as_ref().map_or(true, |until| from < until)
+                })
+            })
+            .collect::<Vec<_>>();
+        if branches.is_empty() {
+            branches = union.tables.iter().collect();
+        }
+        let filter = match (&union.time_column, date_range) {
+            (Some(time_column), Some((from, to))) => {
+                let date_time_tools = query_tools.date_time_tools();
+                // Dates of the range are extended to the start and the end of their days
+                let from = date_time_tools
+                    .format_from_date(from)?
+                    .unwrap_or_else(|| from.clone());
+                let to = date_time_tools
+                    .format_to_date(to)?
+                    .unwrap_or_else(|| to.clone());
+                let from = query_tools.allocate_param(&date_time_tools.in_db_time_zone(from)?);
+                let to = query_tools.allocate_param(&date_time_tools.in_db_time_zone(to)?);
+                let templates = FilterTemplates::new(query_tools.templates_render());
+                Some(templates.time_range_filter(
+                    time_column.clone(),
+                    templates.timestamp_param(&from)?,
+                    templates.timestamp_param(&to)?,
+                )?)
+            }
+            _ => None,
+        };
+        let branches = branches
+            .iter()
+            .map(|branch| {
+                if let Some(filter) = &filter {
+                    format!("SELECT * FROM {} WHERE {}", branch.table, filter)
+                } else {
+                    format!("SELECT * FROM {}", branch.table)
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(format!("({})", branches.join(" UNION ALL ")))
     }
+
     pub fn cube_name(&self) -> &String {
         &self.cube_name
     }
@@ -121,7 +276,7 @@ impl CubeTableSymbol {
 
 pub struct CubeTableSymbolFactory {
     cube_name: String,
-    sql: Rc<dyn MemberSql>,
+    sql: Option<Rc<dyn MemberSql>>,
     definition: Rc<dyn CubeDefinition>,
     is_table_sql: bool,
 }
@@ -135,14 +290,16 @@ impl CubeTableSymbolFactory {
         let table_sql = definition.sql_table()?;
         let is_table_sql = table_sql.is_some();
         let sql = definition.sql()?;
-        let sql = if let Some(sql) = table_sql.or(sql) {
-            sql
-        } else {
+        let sql = table_sql.or(sql);
+        if sql.is_none()
+            && definition.static_data().union.is_none()
+            && definition.static_data().source_query.is_none()
+        {
             return Err(CubeError::user(format!(
-                "Cube {} sould have sql or sqlTable field",
+                "Cube {} sould have sql, sqlTable, union or sourceQuery field",
                 cube_name
             )));
-        };
+        }
 
         Ok(Self {
             cube_name: cube_name.clone(),
@@ -163,11 +320,15 @@ impl SymbolFactory for CubeTableSymbolFactory {
     }
 
     fn deps_names(&self) -> Result<Vec<String>, CubeError> {
-        Ok(self.sql.args_names().clone())
+        if let Some(sql) = &self.sql {
+            Ok(sql.args_names().clone())
+        } else {
+            Ok(vec![])
+        }
     }
 
     fn member_sql(&self) -> Option<Rc<dyn MemberSql>> {
-        Some(self.sql.clone())
+        self.sql.clone()
     }
 
     fn build(self, compiler: &mut Compiler) -> Result<Rc<MemberSymbol>, CubeError> {
@@ -177,7 +338,11 @@ impl SymbolFactory for CubeTableSymbolFactory {
             definition,
             is_table_sql,
         } = self;
-        let sql = compiler.compile_sql_call(&cube_name, sql)?;
+        let sql = if let Some(sql) = sql {
+            Some(compiler.compile_sql_call(&cube_name, sql)?)
+        } else {
+            None
+        };
         Ok(MemberSymbol::new_cube_table(CubeTableSymbol::new(
             cube_name,
             sql,
@@ -186,3 +351,134 @@ impl SymbolFactory for CubeTableSymbolFactory {
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::cube_definition::{CubeDefinitionStatic, CubeUnionBranch};
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+    use crate::planner::test_fixtures::{cube_definition, QueryToolsFixture};
+
+    fn templates(quote: &str, escape: &str) -> PlanSqlTemplates {
+        PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![
+                ("quotes/identifiers", quote),
+                ("quotes/escape", escape),
+            ])
+            .unwrap(),
+        ))
+    }
+
+    #[test]
+    fn table_override_is_quoted_by_parts() {
+        assert_eq!(
+            CubeTableSymbol::quoted_table_override(
+                "orders",
+                "sandbox.orders",
+                &templates("\"", "\"\"")
+            )
+            .unwrap(),
+            "\"sandbox\".\"orders\""
+        );
+        assert_eq!(
+            CubeTableSymbol::quoted_table_override("orders", "orders_v2", &templates("`", "``"))
+                .unwrap(),
+            "`orders_v2`"
+        );
+    }
+
+    #[test]
+    fn table_override_rejects_anything_but_table_names() {
+        for table in [
+            "sandbox.orders; DROP TABLE users",
+            "sandbox.(SELECT * FROM users)",
+            "sandbox.orders o JOIN users u ON 1 = 1",
+            "sandbox.\"orders\"",
+            "db.sandbox.orders",
+            "sandbox.",
+            "",
+        ] {
+            let err =
+                CubeTableSymbol::quoted_table_override("orders", table, &templates("\"", "\"\""))
+                    .unwrap_err();
+            assert_eq!(
+                err.message,
+                format!(
+                    "Table override '{}' of 'orders' cube isn't a 'schema.table' name",
+                    table
+                )
+            );
+        }
+    }
+
+    fn branch(table: &str, since: Option<&str>, until: Option<&str>) -> CubeUnionBranch {
+        CubeUnionBranch {
+            table: table.to_string(),
+            since: since.map(|s| s.to_string()),
+            until: until.map(|s| s.to_string()),
+        }
+    }
+
+    // Union source of the `events` cube over hot and cold tables split at 2024
+    fn union_sql(date_range: Option<(&str, &str)>) -> (String, Vec<String>) {
+        let union = CubeUnion {
+            time_column: Some("created_at".to_string()),
+            tables: vec![
+                branch("hot.events", Some("2024-01-01"), None),
+                branch("cold.events", None, Some("2024-01-01")),
+            ],
+        };
+        let mut fixture = QueryToolsFixture::new().cube(CubeDefinitionStatic {
+            union: Some(union.clone()),
+            ..cube_definition("events")
+        });
+        if let Some((from, to)) = date_range {
+            fixture = fixture.source_date_range("events", from, to);
+        }
+        let query_tools = fixture.build();
+        let symbol = query_tools
+            .evaluator_compiler()
+            .borrow_mut()
+            .add_cube_table_evaluator("events".to_string())
+            .unwrap();
+        let MemberSymbol::CubeTable(cube_table) = symbol.as_ref() else {
+            panic!("events isn't a cube table");
+        };
+        let sql = cube_table.union_sql(&union, query_tools.clone()).unwrap();
+        let params = query_tools
+            .param_allocations()
+            .into_iter()
+            .map(|a| a.value)
+            .collect();
+        (query_tools.render_allocated_placeholders(&sql), params)
+    }
+
+    #[test]
+    fn union_without_date_range_selects_every_table() {
+        assert_eq!(
+            union_sql(None),
+            (
+                "(SELECT * FROM hot.events UNION ALL SELECT * FROM cold.events)".to_string(),
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn union_skips_tables_outside_of_date_range() {
+        assert_eq!(
+            union_sql(Some(("2024-02-01", "2024-02-29"))),
+            (
+                "(SELECT * FROM hot.events WHERE created_at >= $1::timestamptz AND created_at <= $2::timestamptz)".to_string(),
+                vec![
+                    "2024-02-01T00:00:00.000Z".to_string(),
+                    "2024-02-29T23:59:59.999Z".to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            union_sql(Some(("2023-12-01", "2024-01-31"))).0,
+            "(SELECT * FROM hot.events WHERE created_at >= $1::timestamptz AND created_at <= $2::timestamptz UNION ALL SELECT * FROM cold.events WHERE created_at >= $1::timestamptz AND created_at <= $2::timestamptz)"
+        );
+    }
+}
@@ -1,32 +1,64 @@
-use super::{MemberSymbol, SymbolFactory};
+use super::{MemberSymbol, SymbolFactory, TimeAttribute};
 use crate::cube_bridge::dimension_definition::DimensionDefinition;
 use crate::cube_bridge::evaluator::CubeEvaluator;
 use crate::cube_bridge::memeber_sql::MemberSql;
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::{sql_nodes::SqlNode, Compiler, SqlCall, SqlEvaluatorVisitor};
+use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
+// Difference between two time members in the given unit, e.g. age in days
+pub struct DurationSymbol {
+    from: Rc<MemberSymbol>,
+    to: Rc<MemberSymbol>,
+    unit: String,
+}
+
+impl DurationSymbol {
+    pub fn from(&self) -> &Rc<MemberSymbol> {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Rc<MemberSymbol> {
+        &self.to
+    }
+
+    pub fn unit(&self) -> &String {
+        &self.unit
+    }
+}
+
 pub struct DimensionSymbol {
     cube_name: String,
     name: String,
-    member_sql: Rc<SqlCall>,
+    member_sql: Option<Rc<SqlCall>>,
     #[allow(dead_code)]
     definition: Rc<dyn DimensionDefinition>,
+    time_attribute: Option<TimeAttribute>,
+    duration: Option<DurationSymbol>,
 }
 
+// Time attributes are numbers whatever the type of the source time dimension is
+static NUMBER_TYPE: &str = "number";
+
 impl DimensionSymbol {
     pub fn new(
         cube_name: String,
         name: String,
-        member_sql: Rc<SqlCall>,
+        member_sql: Option<Rc<SqlCall>>,
         definition: Rc<dyn DimensionDefinition>,
+        time_attribute: Option<TimeAttribute>,
+        duration: Option<DurationSymbol>,
     ) -> Self {
         Self {
             cube_name,
             name,
             member_sql,
             definition,
+            time_attribute,
+            duration,
         }
     }
 
@@ -36,30 +68,151 @@ impl DimensionSymbol {
         node_processor: Rc<dyn SqlNode>,
         query_tools: Rc<QueryTools>,
     ) -> Result<String, CubeError> {
-        let sql = self.member_sql.eval(visitor, node_processor, query_tools)?;
-        Ok(sql)
+        let sql = if let Some(duration) = &self.duration {
+            let from = visitor.apply(&duration.from, node_processor.clone())?;
+            let to = visitor.apply(&duration.to, node_processor.clone())?;
+            PlanSqlTemplates::new(query_tools.templates_render()).date_diff(
+                &duration.unit,
+                &from,
+                &to,
+            )?
+        } else if let Some(member_sql) = &self.member_sql {
+            member_sql.eval(visitor, node_processor, query_tools.clone())?
+        } else {
+            return Err(CubeError::internal(format!(
+                "Dimension {} has neither sql nor duration",
+                self.full_name()
+            )));
+        };
+        let sql = if let Some(time_attribute) = &self.time_attribute {
+            let converted_tz = query_tools.base_tools().convert_tz(sql)?;
+            PlanSqlTemplates::new(query_tools.templates_render())
+                .time_attribute(time_attribute, &converted_tz)?
+        } else {
+            sql
+        };
+        if let Some(value_map) = &self.definition.static_data().value_map {
+            self.apply_value_map(sql, value_map, query_tools.clone())
+        } else {
+            Ok(sql)
+        }
+    }
+
+    // Mapped codes are replaced with their labels, unmapped values are kept as is.
+    // Applied during evaluation so filters compare against the labels as well.
+    // Value maps of the query are applied to the projection only, see `BaseQuery`
+    fn apply_value_map(
+        &self,
+        sql: String,
+        value_map: &BTreeMap<String, String>,
+        query_tools: Rc<QueryTools>,
+    ) -> Result<String, CubeError> {
+        if value_map.is_empty() {
+            return Ok(sql);
+        }
+        let when_then = value_map
+            .iter()
+            .map(|(code, label)| {
+                (
+                    query_tools.allocate_param(code),
+                    query_tools.allocate_param(label),
+                )
+            })
+            .collect();
+        PlanSqlTemplates::new(query_tools.templates_render()).case(
+            Some(sql.clone()),
+            when_then,
+            Some(sql),
+        )
     }
 
     pub fn full_name(&self) -> String {
-        format!("{}.{}", self.cube_name, self.name)
+        if let Some(time_attribute) = &self.time_attribute {
+            format!("{}.{}.{}", self.cube_name, self.name, time_attribute)
+        } else {
+            format!("{}.{}", self.cube_name, self.name)
+        }
+    }
+
+    pub fn time_attribute(&self) -> &Option<TimeAttribute> {
+        &self.time_attribute
+    }
+
+    pub fn duration(&self) -> &Option<DurationSymbol> {
+        &self.duration
     }
 
     pub fn owned_by_cube(&self) -> bool {
         self.definition.static_data().owned_by_cube.unwrap_or(true)
     }
 
+    pub fn dimension_type(&self) -> &str {
+        if self.time_attribute.is_some() {
+            NUMBER_TYPE
+        } else {
+            &self.definition.static_data().dimension_type
+        }
+    }
+
+    pub fn unit(&self) -> &Option<String> {
+        &self.definition.static_data().unit
+    }
+
+    pub fn collation(&self) -> &Option<String> {
+        &self.definition.static_data().collation
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.definition.static_data().sensitive.unwrap_or(false)
+    }
+
     pub fn is_multi_stage(&self) -> bool {
         self.definition.static_data().multi_stage.unwrap_or(false)
     }
+
+    // Backed by an array column: unnested for grouping, filtered by membership
+    pub fn is_multi_value(&self) -> bool {
+        self.time_attribute.is_none() && self.definition.static_data().multi_value.unwrap_or(false)
+    }
+
+    pub fn is_multi_value_dedupe(&self) -> bool {
+        self.definition
+            .static_data()
+            .multi_value_dedupe
+            .unwrap_or(false)
+    }
+
+    // Sql of the dimension references measures of other cubes aggregated per row of its cube
+    pub fn is_sub_query(&self) -> bool {
+        self.definition.static_data().sub_query.unwrap_or(false)
+    }
+
+    // Values of the column are ordered by code point, e.g. it has a binary collation,
+    // so `startsWith` can be narrowed to a range
+    pub fn is_starts_with_range(&self) -> bool {
+        self.definition
+            .static_data()
+            .starts_with_range
+            .unwrap_or(false)
+    }
+
     pub fn get_dependencies(&self) -> Vec<Rc<MemberSymbol>> {
         let mut deps = vec![];
-        self.member_sql.extract_symbol_deps(&mut deps);
+        if let Some(member_sql) = &self.member_sql {
+            member_sql.extract_symbol_deps(&mut deps);
+        }
+        if let Some(duration) = &self.duration {
+            deps.push(duration.from.clone());
+            deps.push(duration.to.clone());
+        }
         deps
     }
 
     pub fn get_dependent_cubes(&self) -> Vec<String> {
         let mut cubes = vec![];
-        self.member_sql.extract_cube_deps(&mut cubes);
+        if let Some(member_sql) = &self.member_sql {
+            member_sql.extract_cube_deps(&mut cubes);
+        }
         cubes
     }
 
@@ -75,8 +228,9 @@ impl DimensionSymbol {
 pub struct DimensionSymbolFactory {
     cube_name: String,
     name: String,
-    sql: Rc<dyn MemberSql>,
+    sql: Option<Rc<dyn MemberSql>>,
     definition: Rc<dyn DimensionDefinition>,
+    time_attribute: Option<TimeAttribute>,
 }
 
 impl DimensionSymbolFactory {
@@ -84,21 +238,69 @@ impl DimensionSymbolFactory {
         full_name: &String,
         cube_evaluator: Rc<dyn CubeEvaluator>,
     ) -> Result<Self, CubeError> {
+        let (full_name, time_attribute) = match TimeAttribute::split_path(full_name) {
+            Some((path, attribute)) => (path, Some(attribute)),
+            None => (full_name.clone(), None),
+        };
+        let full_name = &full_name;
         let mut iter = cube_evaluator
             .parse_path("dimensions".to_string(), full_name.clone())?
             .into_iter();
         let cube_name = iter.next().unwrap();
         let name = iter.next().unwrap();
         let definition = cube_evaluator.dimension_by_path(full_name.clone())?;
+        if let Some(time_attribute) = &time_attribute {
+            if definition.static_data().dimension_type != "time" {
+                return Err(CubeError::user(format!(
+                    "Time attribute '{}' can only be used with time dimensions, but '{}' is of {} type",
+                    time_attribute,
+                    full_name,
+                    definition.static_data().dimension_type
+                )));
+            }
+        }
+        let sql = definition.sql()?;
+        if sql.is_none() && definition.static_data().duration.is_none() {
+            return Err(CubeError::user(format!(
+                "Dimension '{}' must have either sql or duration",
+                full_name
+            )));
+        }
         Ok(Self {
             cube_name,
             name,
-            sql: definition.sql()?,
+            sql,
             definition,
+            time_attribute,
         })
     }
 }
 
+impl DimensionSymbolFactory {
+    // Duration bounds are either members of the same cube or full member paths
+    fn duration_member(
+        compiler: &mut Compiler,
+        cube_name: &String,
+        path: &String,
+    ) -> Result<Rc<MemberSymbol>, CubeError> {
+        let full_name = if path.contains('.') {
+            path.clone()
+        } else {
+            format!("{}.{}", cube_name, path)
+        };
+        let member = compiler.add_dimension_evaluator(full_name.clone())?;
+        match member.as_ref() {
+            MemberSymbol::Dimension(dimension) if dimension.dimension_type() == "time" => {
+                Ok(member.clone())
+            }
+            _ => Err(CubeError::user(format!(
+                "Duration bound '{}' must be a time dimension",
+                full_name
+            ))),
+        }
+    }
+}
+
 impl SymbolFactory for DimensionSymbolFactory {
     fn symbol_name() -> String {
         "dimension".to_string()
@@ -109,11 +311,15 @@ impl SymbolFactory for DimensionSymbolFactory {
     }
 
     fn deps_names(&self) -> Result<Vec<String>, CubeError> {
-        Ok(self.definition.sql()?.args_names().clone())
+        if let Some(sql) = &self.sql {
+            Ok(sql.args_names().clone())
+        } else {
+            Ok(vec![])
+        }
     }
 
     fn member_sql(&self) -> Option<Rc<dyn MemberSql>> {
-        Some(self.sql.clone())
+        self.sql.clone()
     }
 
     fn build(self, compiler: &mut Compiler) -> Result<Rc<MemberSymbol>, CubeError> {
@@ -122,10 +328,29 @@ impl SymbolFactory for DimensionSymbolFactory {
             name,
             sql,
             definition,
+            time_attribute,
         } = self;
-        let sql = compiler.compile_sql_call(&cube_name, sql)?;
+        let sql = if let Some(sql) = sql {
+            Some(compiler.compile_sql_call(&cube_name, sql)?)
+        } else {
+            None
+        };
+        let duration = if let Some(duration) = &definition.static_data().duration {
+            Some(DurationSymbol {
+                from: Self::duration_member(compiler, &cube_name, &duration.from)?,
+                to: Self::duration_member(compiler, &cube_name, &duration.to)?,
+                unit: duration.unit.clone(),
+            })
+        } else {
+            None
+        };
         Ok(MemberSymbol::new_dimension(DimensionSymbol::new(
-            cube_name, name, sql, definition,
+            cube_name,
+            name,
+            sql,
+            definition,
+            time_attribute,
+            duration,
         )))
     }
 }
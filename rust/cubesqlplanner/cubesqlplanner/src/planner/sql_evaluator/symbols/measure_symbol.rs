@@ -1,11 +1,13 @@
 use super::{MemberSymbol, SymbolFactory};
 use crate::cube_bridge::evaluator::CubeEvaluator;
 use crate::cube_bridge::measure_definition::{
-    MeasureDefinition, RollingWindow, TimeShiftReference,
+    MeasureCurrency, MeasureDecimal, MeasureDefinition, MeasureRounding, RollingWindow,
+    TimeShiftReference,
 };
 use crate::cube_bridge::memeber_sql::MemberSql;
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::{sql_nodes::SqlNode, Compiler, SqlCall, SqlEvaluatorVisitor};
+use crate::planner::sql_templates::PlanSqlTemplates;
 use cubenativeutils::CubeError;
 use std::rc::Rc;
 
@@ -40,6 +42,7 @@ pub struct MeasureSymbol {
     measure_filters: Vec<Rc<SqlCall>>,
     measure_order_by: Vec<MeasureOrderBy>,
     member_sql: Rc<SqlCall>,
+    denominator_sql: Option<Rc<SqlCall>>,
     is_splitted_source: bool,
 }
 
@@ -48,6 +51,7 @@ impl MeasureSymbol {
         cube_name: String,
         name: String,
         member_sql: Rc<SqlCall>,
+        denominator_sql: Option<Rc<SqlCall>>,
         definition: Rc<dyn MeasureDefinition>,
         measure_filters: Vec<Rc<SqlCall>>,
         measure_order_by: Vec<MeasureOrderBy>,
@@ -56,6 +60,7 @@ impl MeasureSymbol {
             cube_name,
             name,
             member_sql,
+            denominator_sql,
             definition,
             measure_filters,
             measure_order_by,
@@ -78,6 +83,7 @@ impl MeasureSymbol {
             self.cube_name.clone(),
             source_name.clone(),
             self.member_sql.clone(),
+            self.denominator_sql.clone(),
             self.definition().clone(),
             self.measure_filters.clone(),
             self.measure_order_by.clone(),
@@ -85,6 +91,10 @@ impl MeasureSymbol {
         (measure_with_source, source)
     }
 
+    pub fn is_sensitive(&self) -> bool {
+        self.definition.static_data().sensitive.unwrap_or(false)
+    }
+
     pub fn is_calculated(&self) -> bool {
         match self.definition.static_data().measure_type.as_str() {
             "number" | "string" | "time" | "boolean" => true,
@@ -98,13 +108,23 @@ impl MeasureSymbol {
         node_processor: Rc<dyn SqlNode>,
         query_tools: Rc<QueryTools>,
     ) -> Result<String, CubeError> {
-        let sql = self.member_sql.eval(visitor, node_processor, query_tools)?;
+        let sql = self
+            .member_sql
+            .eval(visitor, node_processor.clone(), query_tools.clone())?;
+        if let Some(denominator_sql) = &self.denominator_sql {
+            let denominator = denominator_sql.eval(visitor, node_processor, query_tools.clone())?;
+            let templates = PlanSqlTemplates::new(query_tools.templates_render());
+            return templates.safe_divide(&sql, &denominator, self.is_zero_on_division_by_zero());
+        }
         Ok(sql)
     }
 
     pub fn get_dependencies(&self) -> Vec<Rc<MemberSymbol>> {
         let mut deps = vec![];
         self.member_sql.extract_symbol_deps(&mut deps);
+        if let Some(denominator_sql) = &self.denominator_sql {
+            denominator_sql.extract_symbol_deps(&mut deps);
+        }
         for filter in self.measure_filters.iter() {
             filter.extract_symbol_deps(&mut deps);
         }
@@ -117,6 +137,9 @@ impl MeasureSymbol {
     pub fn get_dependent_cubes(&self) -> Vec<String> {
         let mut cubes = vec![];
         self.member_sql.extract_cube_deps(&mut cubes);
+        if let Some(denominator_sql) = &self.denominator_sql {
+            denominator_sql.extract_cube_deps(&mut cubes);
+        }
         for filter in self.measure_filters.iter() {
             filter.extract_cube_deps(&mut cubes);
         }
@@ -141,6 +164,30 @@ impl MeasureSymbol {
         &self.definition.static_data().rolling_window
     }
 
+    pub fn unit(&self) -> &Option<String> {
+        &self.definition.static_data().unit
+    }
+
+    pub fn currency(&self) -> &Option<MeasureCurrency> {
+        &self.definition.static_data().currency
+    }
+
+    pub fn rounding(&self) -> &Option<MeasureRounding> {
+        &self.definition.static_data().rounding
+    }
+
+    pub fn decimal(&self) -> &Option<MeasureDecimal> {
+        &self.definition.static_data().decimal
+    }
+
+    pub fn overflow_safe(&self) -> Option<bool> {
+        self.definition.static_data().overflow_safe
+    }
+
+    pub fn is_zero_on_division_by_zero(&self) -> bool {
+        self.definition.static_data().division_by_zero.as_deref() == Some("zero")
+    }
+
     pub fn is_rolling_window(&self) -> bool {
         self.rolling_window().is_some()
     }
@@ -210,7 +257,12 @@ impl MeasureSymbolFactory {
             let primary_key = primary_keys.first().unwrap();
             let key_dimension =
                 cube_evaluator.dimension_by_path(format!("{}.{}", cube_name, primary_key))?;
-            key_dimension.sql()?
+            key_dimension.sql()?.ok_or_else(|| {
+                CubeError::user(format!(
+                    "Primary key '{}.{}' of measure '{}' must have sql",
+                    cube_name, primary_key, full_name
+                ))
+            })?
         };
         Ok(Self {
             cube_name,
@@ -234,11 +286,15 @@ impl SymbolFactory for MeasureSymbolFactory {
     }
 
     fn deps_names(&self) -> Result<Vec<String>, CubeError> {
-        if let Some(member_sql) = self.definition.sql()? {
-            Ok(member_sql.args_names().clone())
+        let mut deps = if let Some(member_sql) = self.definition.sql()? {
+            member_sql.args_names().clone()
         } else {
-            Ok(vec![])
+            vec![]
+        };
+        if let Some(denominator_sql) = self.definition.denominator_sql()? {
+            deps.extend(denominator_sql.args_names().iter().cloned());
         }
+        Ok(deps)
     }
 
     fn build(self, compiler: &mut Compiler) -> Result<Rc<MemberSymbol>, CubeError> {
@@ -265,10 +321,23 @@ impl SymbolFactory for MeasureSymbolFactory {
         }
         let sql = compiler.compile_sql_call(&cube_name, sql)?;
 
+        let denominator_sql = if let Some(denominator_sql) = definition.denominator_sql()? {
+            if definition.static_data().measure_type != "number" {
+                return Err(CubeError::user(format!(
+                    "Measure '{}.{}' with denominatorSql must be of type number",
+                    cube_name, name
+                )));
+            }
+            Some(compiler.compile_sql_call(&cube_name, denominator_sql)?)
+        } else {
+            None
+        };
+
         Ok(MemberSymbol::new_measure(MeasureSymbol::new(
             cube_name,
             name,
             sql,
+            denominator_sql,
             definition,
             measure_filters,
             measure_order_by,
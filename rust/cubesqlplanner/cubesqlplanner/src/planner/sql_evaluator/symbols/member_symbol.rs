@@ -1,4 +1,4 @@
-use super::{CubeNameSymbol, CubeTableSymbol, DimensionSymbol, MeasureSymbol};
+use super::{CubeNameSymbol, CubeTableSymbol, DimensionSymbol, MeasureSymbol, SegmentSymbol};
 use std::rc::Rc;
 
 pub enum MemberSymbol {
@@ -6,6 +6,7 @@ pub enum MemberSymbol {
     Measure(MeasureSymbol),
     CubeName(CubeNameSymbol),
     CubeTable(CubeTableSymbol),
+    Segment(SegmentSymbol),
 }
 
 impl MemberSymbol {
@@ -25,12 +26,17 @@ impl MemberSymbol {
         Rc::new(Self::CubeTable(symbol))
     }
 
+    pub fn new_segment(symbol: SegmentSymbol) -> Rc<Self> {
+        Rc::new(Self::Segment(symbol))
+    }
+
     pub fn full_name(&self) -> String {
         match self {
             Self::Dimension(d) => d.full_name(),
             Self::Measure(m) => m.full_name(),
             Self::CubeName(c) => c.cube_name().clone(),
             Self::CubeTable(c) => c.cube_name().clone(),
+            Self::Segment(s) => s.full_name(),
         }
     }
     pub fn name(&self) -> String {
@@ -39,6 +45,7 @@ impl MemberSymbol {
             Self::Measure(m) => m.name().clone(),
             Self::CubeName(c) => c.cube_name().clone(),
             Self::CubeTable(c) => c.cube_name().clone(),
+            Self::Segment(s) => s.name().clone(),
         }
     }
 
@@ -48,6 +55,25 @@ impl MemberSymbol {
             Self::Measure(m) => m.cube_name().clone(),
             Self::CubeName(c) => c.cube_name().clone(),
             Self::CubeTable(c) => c.cube_name().clone(),
+            Self::Segment(s) => s.cube_name().clone(),
+        }
+    }
+    pub fn unit(&self) -> Option<String> {
+        match self {
+            Self::Dimension(d) => d.unit().clone(),
+            Self::Measure(m) => m.unit().clone(),
+            Self::CubeName(_) => None,
+            Self::CubeTable(_) => None,
+            Self::Segment(_) => None,
+        }
+    }
+    pub fn is_sensitive(&self) -> bool {
+        match self {
+            Self::Dimension(d) => d.is_sensitive(),
+            Self::Measure(m) => m.is_sensitive(),
+            Self::CubeName(_) => false,
+            Self::CubeTable(_) => false,
+            Self::Segment(_) => false,
         }
     }
     pub fn is_measure(&self) -> bool {
@@ -62,6 +88,7 @@ impl MemberSymbol {
             Self::Measure(m) => m.get_dependencies(),
             Self::CubeName(_) => vec![],
             Self::CubeTable(_) => vec![],
+            Self::Segment(s) => s.get_dependencies(),
         }
     }
 
@@ -71,6 +98,7 @@ impl MemberSymbol {
             Self::Measure(m) => m.get_dependent_cubes(),
             Self::CubeName(_) => vec![],
             Self::CubeTable(_) => vec![],
+            Self::Segment(s) => s.get_dependent_cubes(),
         }
     }
 
@@ -2,12 +2,16 @@ mod cube_symbol;
 mod dimension_symbol;
 mod measure_symbol;
 mod member_symbol;
+mod segment_symbol;
 mod symbol_factory;
+mod time_attribute;
 
 pub use cube_symbol::{
     CubeNameSymbol, CubeNameSymbolFactory, CubeTableSymbol, CubeTableSymbolFactory,
 };
-pub use dimension_symbol::{DimensionSymbol, DimensionSymbolFactory};
+pub use dimension_symbol::{DimensionSymbol, DimensionSymbolFactory, DurationSymbol};
 pub use measure_symbol::{MeasureSymbol, MeasureSymbolFactory};
 pub use member_symbol::MemberSymbol;
+pub use segment_symbol::{SegmentSymbol, SegmentSymbolFactory};
 pub use symbol_factory::SymbolFactory;
+pub use time_attribute::TimeAttribute;
@@ -0,0 +1,50 @@
+use std::fmt;
+
+// Numeric attribute derived from any time dimension without schema changes,
+// e.g. `orders.createdAt.dow`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeAttribute {
+    // ISO day of week: 1 is Monday, 7 is Sunday
+    DayOfWeek,
+    HourOfDay,
+    MonthOfYear,
+    // ISO week number
+    WeekOfYear,
+}
+
+impl TimeAttribute {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dow" => Some(Self::DayOfWeek),
+            "hour_of_day" => Some(Self::HourOfDay),
+            "month_of_year" => Some(Self::MonthOfYear),
+            "week_of_year" => Some(Self::WeekOfYear),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DayOfWeek => "dow",
+            Self::HourOfDay => "hour_of_day",
+            Self::MonthOfYear => "month_of_year",
+            Self::WeekOfYear => "week_of_year",
+        }
+    }
+
+    // Splits `cube.dimension.attribute` path into the time dimension path and the attribute
+    pub fn split_path(path: &str) -> Option<(String, Self)> {
+        let parts = path.split('.').collect::<Vec<_>>();
+        if parts.len() != 3 {
+            return None;
+        }
+        let attribute = Self::from_name(parts[2])?;
+        Some((format!("{}.{}", parts[0], parts[1]), attribute))
+    }
+}
+
+impl fmt::Display for TimeAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -1,8 +1,11 @@
+use super::PlanSqlTemplates;
 use crate::cube_bridge::sql_templates_render::SqlTemplatesRender;
 use cubenativeutils::CubeError;
 use minijinja::context;
 use std::rc::Rc;
 
+const LIKE_ESCAPE_CHAR: char = '\\';
+
 #[derive(Clone)]
 pub struct FilterTemplates {
     render: Rc<dyn SqlTemplatesRender>,
@@ -61,6 +64,126 @@ impl FilterTemplates {
         )
     }
 
+    pub fn time_range_filter_exclusive(
+        &self,
+        column: String,
+        from_timestamp: String,
+        to_timestamp: String,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"filters/time_range_filter_exclusive",
+            context! {
+                column => column,
+                from_timestamp => from_timestamp,
+                to_timestamp => to_timestamp,
+            },
+        )
+    }
+
+    // Cast of a timestamp param, e.g. `CAST(? AS DATETIMEOFFSET)`. Postgres cast is kept
+    // for templates which don't define it
+    pub fn timestamp_param(&self, param: &str) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/timestamp_param") {
+            return Ok(format!("{}::timestamptz", param));
+        }
+        self.render.render_template(
+            &"filters/timestamp_param",
+            context! {
+                param => param
+            },
+        )
+    }
+
+    // Renders a resolved `YYYY-MM-DDTHH:mm:ss.SSS[Z]` date bound in the dialect date literal
+    // format. Values in other formats are kept as is
+    pub fn timestamp_value(&self, value: &str) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/timestamp_value") {
+            return Ok(value.to_string());
+        }
+        let (body, zone) = match value.strip_suffix('Z') {
+            Some(body) => (body, "Z"),
+            None => (value, ""),
+        };
+        let Some((date, time)) = body.split_once(['T', ' ']) else {
+            return Ok(value.to_string());
+        };
+        let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+        let date_parts = date.split('-').collect::<Vec<_>>();
+        let time_parts = time.split(':').collect::<Vec<_>>();
+        let (&[year, month, day], &[hour, minute, second]) =
+            (date_parts.as_slice(), time_parts.as_slice())
+        else {
+            return Ok(value.to_string());
+        };
+        self.render.render_template(
+            &"filters/timestamp_value",
+            context! {
+                year => year,
+                month => month,
+                day => day,
+                hour => hour,
+                minute => minute,
+                second => second,
+                fraction => fraction,
+                zone => zone
+            },
+        )
+    }
+
+    // Hex string param decoded to bytes, e.g. `decode(?, 'hex')`. Only dialects with
+    // a binary type define the template
+    pub fn binary_param(&self, param: &str) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/binary_param") {
+            return Err(CubeError::user(
+                "Filters on binary dimensions are not supported by this data source".to_string(),
+            ));
+        }
+        self.render.render_template(
+            &"filters/binary_param",
+            context! {
+                param => param
+            },
+        )
+    }
+
+    // UUID param cast, e.g. `CAST(? AS UNIQUEIDENTIFIER)`. Dialects without a UUID type
+    // define the template as the param itself, they compare UUIDs as strings
+    pub fn uuid_param(&self, param: &str) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/uuid_param") {
+            return Err(CubeError::user(
+                "Filters on uuid dimensions are not supported by this data source".to_string(),
+            ));
+        }
+        self.render.render_template(
+            &"filters/uuid_param",
+            context! {
+                param => param
+            },
+        )
+    }
+
+    // Cast to one of the `types` templates, e.g. `bigint` or `string`
+    pub fn cast(&self, expr: &str, type_name: &str) -> Result<String, CubeError> {
+        let data_type = self
+            .render
+            .render_template(&format!("types/{}", type_name), context! {})?;
+        self.render.render_template(
+            &"expressions/cast",
+            context! {
+                expr => expr,
+                data_type => data_type
+            },
+        )
+    }
+
+    // Dialects opt into exclusive range ends with `filters/time_range_end` set to `exclusive`
+    pub fn is_time_range_end_exclusive(&self) -> Result<bool, CubeError> {
+        if !self.render.contains_template("filters/time_range_end") {
+            return Ok(false);
+        }
+        Ok(self.render.get_template("filters/time_range_end")? == "exclusive")
+    }
+
     pub fn in_where(
         &self,
         column: String,
@@ -104,6 +227,44 @@ impl FilterTemplates {
         )
     }
 
+    pub fn calendar_exists(
+        &self,
+        column: String,
+        table: &str,
+        date_column: &str,
+        flag_column: &str,
+        flag_value: bool,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"filters/calendar_exists",
+            context! {
+                column => column,
+                table => table,
+                calendar_alias => "cube_calendar",
+                date_column => date_column,
+                flag_column => flag_column,
+                flag_value => flag_value,
+            },
+        )
+    }
+
+    // Keys are pairs of the source column and the outer query expression it's correlated with
+    pub fn sub_query_exists(
+        &self,
+        source: String,
+        keys: Vec<(String, String)>,
+        condition: String,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"filters/sub_query_exists",
+            context! {
+                source => source,
+                keys => keys,
+                condition => condition,
+            },
+        )
+    }
+
     pub fn add_interval(&self, date: String, interval: String) -> Result<String, CubeError> {
         self.render.render_template(
             &"expressions/add_interval",
@@ -142,6 +303,35 @@ impl FilterTemplates {
         )
     }
 
+    pub fn between(&self, column: String, from: String, to: String) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"filters/between",
+            context! {
+                column => column,
+                from => from,
+                to => to
+            },
+        )
+    }
+
+    pub fn not_between(
+        &self,
+        column: String,
+        from: String,
+        to: String,
+        is_null_check: bool,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"filters/not_between",
+            context! {
+                from => from,
+                to => to,
+                is_null_check => self.additional_null_check(is_null_check, &column)?,
+                column => column,
+            },
+        )
+    }
+
     pub fn gt(&self, column: String, param: String) -> Result<String, CubeError> {
         self.render.render_template(
             &"filters/gt",
@@ -182,6 +372,68 @@ impl FilterTemplates {
         )
     }
 
+    // Array column contains any of the values
+    pub fn array_contains(&self, column: String, values: Vec<String>) -> Result<String, CubeError> {
+        let conditions = values
+            .iter()
+            .map(|value| {
+                self.render.render_template(
+                    &"filters/array_contains",
+                    context! {
+                        column => column,
+                        value => value,
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("({})", conditions.join(" OR ")))
+    }
+
+    // Array column contains all of the values. Dialects without an array containment
+    // template check the values one by one
+    pub fn array_contains_all(
+        &self,
+        column: String,
+        values: Vec<String>,
+    ) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/array_contains_all") {
+            let conditions = values
+                .into_iter()
+                .map(|value| self.array_contains(column.clone(), vec![value]))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(format!("({})", conditions.join(" AND ")));
+        }
+        self.render.render_template(
+            &"filters/array_contains_all",
+            context! {
+                column => column,
+                values_concat => values.join(", "),
+            },
+        )
+    }
+
+    // Array column shares an element with the values
+    pub fn array_overlaps(&self, column: String, values: Vec<String>) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/array_overlaps") {
+            return self.array_contains(column, values);
+        }
+        self.render.render_template(
+            &"filters/array_overlaps",
+            context! {
+                column => column,
+                values_concat => values.join(", "),
+            },
+        )
+    }
+
+    pub fn always_true(&self) -> Result<String, CubeError> {
+        Ok(self.render.get_template("filters/always_true")?.clone())
+    }
+
+    pub fn always_false(&self) -> Result<String, CubeError> {
+        Ok(self.render.get_template("filters/always_false")?.clone())
+    }
+
     pub fn additional_null_check(&self, need: bool, column: &String) -> Result<String, CubeError> {
         if need {
             self.or_is_null_check(column.clone())
@@ -190,6 +442,54 @@ impl FilterTemplates {
         }
     }
 
+    // Collation names are quoted the way the dialect expects them: as identifiers
+    // in Postgres, as string literals in Snowflake and BigQuery
+    pub fn collate(&self, expr: &str, collation: &str) -> Result<String, CubeError> {
+        let collation = self.render.render_template(
+            &"expressions/collation_name",
+            context! {
+                name => collation
+            },
+        )?;
+        self.render.render_template(
+            &"expressions/collate",
+            context! {
+                expr => expr,
+                collation => collation
+            },
+        )
+    }
+
+    pub fn trim(&self, expr: &str) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"expressions/trim",
+            context! {
+                expr => expr
+            },
+        )
+    }
+
+    // Only databases which can normalize strings to NFC, e.g. with `NORMALIZE(x, NFC)`,
+    // define the template: comparing NFC values with unnormalized columns would miss rows
+    pub fn normalize_unicode(&self, expr: &str, case_fold: bool) -> Result<String, CubeError> {
+        if !self
+            .render
+            .contains_template("expressions/normalize_unicode")
+        {
+            return Err(CubeError::user(
+                "Unicode normalization of filter values isn't supported by this database"
+                    .to_string(),
+            ));
+        }
+        self.render.render_template(
+            &"expressions/normalize_unicode",
+            context! {
+                expr => expr,
+                case_fold => case_fold
+            },
+        )
+    }
+
     pub fn ilike(
         &self,
         column: &str,
@@ -197,6 +497,36 @@ impl FilterTemplates {
         start_wild: bool,
         end_wild: bool,
         not: bool,
+    ) -> Result<String, CubeError> {
+        self.like_expression(
+            "expressions/ilike",
+            column,
+            value,
+            start_wild,
+            end_wild,
+            not,
+        )
+    }
+
+    pub fn like(
+        &self,
+        column: &str,
+        value: &str,
+        start_wild: bool,
+        end_wild: bool,
+        not: bool,
+    ) -> Result<String, CubeError> {
+        self.like_expression("expressions/like", column, value, start_wild, end_wild, not)
+    }
+
+    fn like_expression(
+        &self,
+        template: &str,
+        column: &str,
+        value: &str,
+        start_wild: bool,
+        end_wild: bool,
+        not: bool,
     ) -> Result<String, CubeError> {
         let pattern = self.render.render_template(
             &"filters/like_pattern",
@@ -206,13 +536,386 @@ impl FilterTemplates {
                 end_wild => end_wild
             },
         )?;
-        self.render.render_template(
-            &"expressions/ilike",
+        let like_expr = self.render.render_template(
+            template,
             context! {
                 expr => column,
                 negated => not,
                 pattern => pattern
             },
+        )?;
+        // Dialects without the template use backslash as the default escape character
+        if self.render.contains_template("expressions/like_escape") {
+            let escape_char = PlanSqlTemplates::new(self.render.clone())
+                .string_literal(&LIKE_ESCAPE_CHAR.to_string())?;
+            self.render.render_template(
+                "expressions/like_escape",
+                context! {
+                    like_expr => like_expr,
+                    escape_char => escape_char
+                },
+            )
+        } else {
+            Ok(like_expr)
+        }
+    }
+
+    pub fn validity_range(
+        &self,
+        value: &str,
+        valid_from: &str,
+        valid_to: &str,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"filters/validity_range",
+            context! {
+                value => value,
+                valid_from => valid_from,
+                valid_to => valid_to
+            },
+        )
+    }
+
+    pub fn escape_like_wildcards(value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        for c in value.chars() {
+            if matches!(c, '%' | '_' | LIKE_ESCAPE_CHAR) {
+                result.push(LIKE_ESCAPE_CHAR);
+            }
+            result.push(c);
+        }
+        result
+    }
+
+    // Regex syntax differs between databases, so there is no default operator
+    pub fn regex_match(&self, column: &str, value: &str, not: bool) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/regex_match") {
+            return Err(CubeError::user(
+                "Regex filters aren't supported by this database".to_string(),
+            ));
+        }
+        self.render.render_template(
+            &"filters/regex_match",
+            context! {
+                column => column,
+                value => value,
+                negated => not
+            },
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+
+    fn templates(backslash_escape: bool) -> FilterTemplates {
+        let mut templates = vec![
+            ("quotes/literal", "'"),
+            ("quotes/literal_escape", "''"),
+            (
+                "filters/like_pattern",
+                "{% if start_wild %}'%' || {% endif %}{{ value }}{% if end_wild %}|| '%'{% endif %}",
+            ),
+            (
+                "expressions/like",
+                "{{ expr }} {% if negated %}NOT {% endif %}LIKE {{ pattern }}",
+            ),
+            (
+                "expressions/like_escape",
+                "{{ like_expr }} ESCAPE {{ escape_char }}",
+            ),
+            ("expressions/collate", "{{ expr }} COLLATE {{ collation }}"),
+            ("expressions/collation_name", "\"{{ name }}\""),
+        ];
+        if backslash_escape {
+            templates.push(("quotes/literal_backslash_escape", "\\\\"));
+        }
+        FilterTemplates::new(Rc::new(MockSqlTemplatesRender::try_new(templates).unwrap()))
+    }
+
+    #[test]
+    fn collation_name_is_quoted_by_the_dialect() {
+        assert_eq!(
+            templates(false).collate("name", "en-US-x-icu").unwrap(),
+            "name COLLATE \"en-US-x-icu\""
+        );
+    }
+
+    #[test]
+    fn like_escape_char_is_a_dialect_literal() {
+        assert_eq!(
+            templates(false)
+                .like("name", "$1", true, false, false)
+                .unwrap(),
+            "name LIKE '%' || $1 ESCAPE '\\'"
+        );
+        assert_eq!(
+            templates(true)
+                .like("name", "?", false, true, true)
+                .unwrap(),
+            "name NOT LIKE ?|| '%' ESCAPE '\\\\'"
+        );
+    }
+
+    #[test]
+    fn like_without_escape_template_uses_default_escape() {
+        let templates = FilterTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![
+                ("filters/like_pattern", "{{ value }}"),
+                ("expressions/like", "{{ expr }} LIKE {{ pattern }}"),
+            ])
+            .unwrap(),
+        ));
+        assert_eq!(
+            templates.like("name", "?", false, false, false).unwrap(),
+            "name LIKE ?"
+        );
+    }
+
+    #[test]
+    fn binary_param_requires_dialect_template() {
+        let err = templates(false).binary_param("$1").unwrap_err();
+        assert_eq!(
+            err.message,
+            "Filters on binary dimensions are not supported by this data source"
+        );
+
+        let templates = FilterTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![(
+                "filters/binary_param",
+                "FROM_HEX({{ param }})",
+            )])
+            .unwrap(),
+        ));
+        assert_eq!(templates.binary_param("?").unwrap(), "FROM_HEX(?)");
+    }
+
+    #[test]
+    fn uuid_param_requires_dialect_template() {
+        let err = templates(false).uuid_param("$1").unwrap_err();
+        assert_eq!(
+            err.message,
+            "Filters on uuid dimensions are not supported by this data source"
+        );
+
+        let templates = FilterTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![("filters/uuid_param", "{{ param }}")]).unwrap(),
+        ));
+        assert_eq!(templates.uuid_param("?").unwrap(), "?");
+    }
+
+    fn between_templates() -> FilterTemplates {
+        FilterTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![
+                (
+                    "filters/between",
+                    "{{ column }} BETWEEN {{ from }} AND {{ to }}",
+                ),
+                (
+                    "filters/not_between",
+                    "{{ column }} NOT BETWEEN {{ from }} AND {{ to }}{{ is_null_check }}",
+                ),
+                ("filters/or_is_null_check", " OR {{ column }} IS NULL"),
+            ])
+            .unwrap(),
+        ))
+    }
+
+    #[test]
+    fn between_renders_both_bounds() {
+        assert_eq!(
+            between_templates()
+                .between("amount".to_string(), "$1".to_string(), "$2".to_string())
+                .unwrap(),
+            "amount BETWEEN $1 AND $2"
+        );
+    }
+
+    #[test]
+    fn not_between_keeps_nulls_only_with_null_check() {
+        let templates = between_templates();
+        assert_eq!(
+            templates
+                .not_between(
+                    "amount".to_string(),
+                    "$1".to_string(),
+                    "$2".to_string(),
+                    true
+                )
+                .unwrap(),
+            "amount NOT BETWEEN $1 AND $2 OR amount IS NULL"
+        );
+        assert_eq!(
+            templates
+                .not_between(
+                    "amount".to_string(),
+                    "$1".to_string(),
+                    "$2".to_string(),
+                    false
+                )
+                .unwrap(),
+            "amount NOT BETWEEN $1 AND $2"
+        );
+    }
+
+    #[test]
+    fn regex_match_requires_dialect_template() {
+        let err = templates(false)
+            .regex_match("name", "$1", false)
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            "Regex filters aren't supported by this database"
+        );
+
+        let templates = FilterTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![(
+                "filters/regex_match",
+                "{{ column }} {% if negated %}NOT {% endif %}REGEXP {{ value }}",
+            )])
+            .unwrap(),
+        ));
+        assert_eq!(
+            templates.regex_match("name", "?", true).unwrap(),
+            "name NOT REGEXP ?"
+        );
+    }
+
+    // Array filter templates of `PostgresQuery` and `ClickHouseQuery`
+    fn array_templates(dialect: &str) -> FilterTemplates {
+        let templates = match dialect {
+            "postgres" => vec![
+                ("filters/array_contains", "{{ value }} = ANY({{ column }})"),
+                (
+                    "filters/array_contains_all",
+                    "{{ column }} @> ARRAY[{{ values_concat }}]",
+                ),
+                (
+                    "filters/array_overlaps",
+                    "{{ column }} && ARRAY[{{ values_concat }}]",
+                ),
+            ],
+            "clickhouse" => vec![
+                ("filters/array_contains", "has({{ column }}, {{ value }})"),
+                (
+                    "filters/array_contains_all",
+                    "hasAll({{ column }}, [{{ values_concat }}])",
+                ),
+                (
+                    "filters/array_overlaps",
+                    "hasAny({{ column }}, [{{ values_concat }}])",
+                ),
+            ],
+            _ => unreachable!(),
+        };
+        FilterTemplates::new(Rc::new(MockSqlTemplatesRender::try_new(templates).unwrap()))
+    }
+
+    fn values() -> Vec<String> {
+        vec!["$1".to_string(), "$2".to_string()]
+    }
+
+    #[test]
+    fn array_operators_postgres() {
+        let templates = array_templates("postgres");
+        assert_eq!(
+            templates
+                .array_contains("tags".to_string(), values())
+                .unwrap(),
+            "($1 = ANY(tags) OR $2 = ANY(tags))"
+        );
+        assert_eq!(
+            templates
+                .array_contains_all("tags".to_string(), values())
+                .unwrap(),
+            "tags @> ARRAY[$1, $2]"
+        );
+        assert_eq!(
+            templates
+                .array_overlaps("tags".to_string(), values())
+                .unwrap(),
+            "tags && ARRAY[$1, $2]"
+        );
+    }
+
+    #[test]
+    fn array_operators_clickhouse() {
+        let templates = array_templates("clickhouse");
+        assert_eq!(
+            templates
+                .array_contains("tags".to_string(), values())
+                .unwrap(),
+            "(has(tags, $1) OR has(tags, $2))"
+        );
+        assert_eq!(
+            templates
+                .array_contains_all("tags".to_string(), values())
+                .unwrap(),
+            "hasAll(tags, [$1, $2])"
+        );
+        assert_eq!(
+            templates
+                .array_overlaps("tags".to_string(), values())
+                .unwrap(),
+            "hasAny(tags, [$1, $2])"
+        );
+    }
+
+    #[test]
+    fn array_operators_without_dialect_templates_check_values_one_by_one() {
+        let templates = FilterTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![(
+                "filters/array_contains",
+                "{{ value }} = ANY({{ column }})",
+            )])
+            .unwrap(),
+        ));
+        assert_eq!(
+            templates
+                .array_contains_all("tags".to_string(), values())
+                .unwrap(),
+            "(($1 = ANY(tags)) AND ($2 = ANY(tags)))"
+        );
+        assert_eq!(
+            templates
+                .array_overlaps("tags".to_string(), values())
+                .unwrap(),
+            "($1 = ANY(tags) OR $2 = ANY(tags))"
+        );
+    }
+
+    #[test]
+    fn escape_like_wildcards_escapes_escape_char() {
+        assert_eq!(
+            FilterTemplates::escape_like_wildcards("50%_a\\b"),
+            "50\\%\\_a\\\\b"
+        );
+    }
+
+    #[test]
+    fn sub_query_exists_correlates_every_key() {
+        let templates = FilterTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![(
+                "filters/sub_query_exists",
+                "EXISTS (SELECT 1 FROM {{ source }} WHERE {% for column, outer in keys %}\
+                 {{ column }} = {{ outer }} AND {% endfor %}({{ condition }}))",
+            )])
+            .unwrap(),
+        ));
+        assert_eq!(
+            templates
+                .sub_query_exists(
+                    "(SELECT ...) AS q".to_string(),
+                    vec![
+                        ("q.a".to_string(), "t.a".to_string()),
+                        ("q.b".to_string(), "t.b".to_string())
+                    ],
+                    "q.v > $1".to_string(),
+                )
+                .unwrap(),
+            "EXISTS (SELECT 1 FROM (SELECT ...) AS q WHERE q.a = t.a AND q.b = t.b AND (q.v > $1))"
+        );
+    }
+}
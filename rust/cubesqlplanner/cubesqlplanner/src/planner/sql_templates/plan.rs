@@ -1,6 +1,10 @@
 use super::{TemplateGroupByColumn, TemplateOrderByColumn, TemplateProjectionColumn};
+use crate::cube_bridge::base_query_options::CurrencyConversionOptions;
 use crate::cube_bridge::sql_templates_render::SqlTemplatesRender;
 use crate::plan::join::JoinType;
+use crate::plan::GroupingSets;
+use crate::planner::sql_evaluator::TimeAttribute;
+use crate::planner::{ParamType, TypedParam};
 use convert_case::{Case, Casing};
 use cubenativeutils::CubeError;
 use minijinja::context;
@@ -45,6 +49,58 @@ impl PlanSqlTemplates {
         ))
     }
 
+    // Literal of a param value for queries rendered without bind params. Values which
+    // aren't valid literals of their type are rendered as strings
+    pub fn literal(&self, param: &TypedParam) -> Result<String, CubeError> {
+        let value = param.value().trim();
+        match param.param_type() {
+            ParamType::Number if Self::is_number_literal(value) => Ok(value.to_string()),
+            ParamType::Boolean => match value.to_lowercase().as_str() {
+                "true" | "1" => Ok(self.render.get_template("expressions/true")?.clone()),
+                "false" | "0" => Ok(self.render.get_template("expressions/false")?.clone()),
+                _ => self.string_literal(param.value()),
+            },
+            _ => self.string_literal(param.value()),
+        }
+    }
+
+    pub fn string_literal(&self, value: &str) -> Result<String, CubeError> {
+        let (quote, escape) = if self.render.contains_template("quotes/literal") {
+            (
+                self.render.get_template("quotes/literal")?.clone(),
+                self.render.get_template("quotes/literal_escape")?.clone(),
+            )
+        } else {
+            ("'".to_string(), "''".to_string())
+        };
+        // Dialects where backslash starts an escape sequence in string literals
+        let value = if self
+            .render
+            .contains_template("quotes/literal_backslash_escape")
+        {
+            value.replace(
+                '\\',
+                self.render
+                    .get_template("quotes/literal_backslash_escape")?,
+            )
+        } else {
+            value.to_string()
+        };
+        Ok(format!(
+            "{}{}{}",
+            quote,
+            value.replace(&quote, &escape),
+            quote
+        ))
+    }
+
+    fn is_number_literal(value: &str) -> bool {
+        value
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+            && value.parse::<f64>().is_ok_and(|n| n.is_finite())
+    }
+
     pub fn column_aliased(&self, expr: &str, alias: &str) -> Result<String, CubeError> {
         let quoted_alias = self.quote_identifier(alias)?;
         self.render.render_template(
@@ -53,6 +109,116 @@ impl PlanSqlTemplates {
         )
     }
 
+    pub fn currency_rates_source(
+        &self,
+        options: &CurrencyConversionOptions,
+        target_currency: &str,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            "currency_conversion/rates_source",
+            context! {
+                rates_table => options.rates_table,
+                currency_column => options.currency_column,
+                target_currency_column => options.target_currency_column,
+                rate_column => options.rate_column,
+                effective_date_column => options.effective_date_column,
+                target_currency => target_currency,
+            },
+        )
+    }
+
+    pub fn currency_rates_join_condition(
+        &self,
+        rates_alias: &str,
+        currency: &str,
+        date: &str,
+    ) -> Result<String, CubeError> {
+        let rates = self.quote_identifier(rates_alias)?;
+        self.render.render_template(
+            "currency_conversion/join_condition",
+            context! { rates => rates, currency => currency, date => date },
+        )
+    }
+
+    pub fn currency_convert(&self, expr: &str, rates_alias: &str) -> Result<String, CubeError> {
+        let rates = self.quote_identifier(rates_alias)?;
+        self.render.render_template(
+            "currency_conversion/convert",
+            context! { expr => expr, rates => rates },
+        )
+    }
+
+    pub fn unit_conversion(&self, expr: &str, factor: f64) -> Result<String, CubeError> {
+        self.render.render_template(
+            "expressions/unit_conversion",
+            context! { expr => expr, factor => factor },
+        )
+    }
+
+    pub fn null_label(&self, expr: &str, label: &str) -> Result<String, CubeError> {
+        self.render.render_template(
+            "expressions/null_label",
+            context! { expr => expr, label => label },
+        )
+    }
+
+    // Only dialects with a binary type define the template
+    pub fn hex_encode(&self, expr: &str) -> Result<String, CubeError> {
+        if !self.render.contains_template("expressions/hex_encode") {
+            return Err(CubeError::user(
+                "Binary dimensions are not supported by this data source".to_string(),
+            ));
+        }
+        self.render
+            .render_template("expressions/hex_encode", context! { expr => expr })
+    }
+
+    pub fn round(&self, expr: &str, scale: u32) -> Result<String, CubeError> {
+        self.render.render_template(
+            "expressions/round",
+            context! { expr => expr, scale => scale },
+        )
+    }
+
+    // Division that yields NULL (or 0 with `zero_fallback`) when the denominator is 0.
+    // NULL numerator or denominator yields NULL in both cases
+    pub fn safe_divide(
+        &self,
+        numerator: &str,
+        denominator: &str,
+        zero_fallback: bool,
+    ) -> Result<String, CubeError> {
+        let result = self.render.render_template(
+            "expressions/safe_divide",
+            context! { numerator => numerator, denominator => denominator },
+        )?;
+        if zero_fallback {
+            self.case(
+                None,
+                vec![(format!("({}) = 0", denominator), "0".to_string())],
+                Some(result),
+            )
+        } else {
+            Ok(result)
+        }
+    }
+
+    pub fn cast_to_decimal(
+        &self,
+        expr: &str,
+        precision: u32,
+        scale: u32,
+    ) -> Result<String, CubeError> {
+        let data_type = self.render.render_template(
+            "types/decimal",
+            context! { precision => precision, scale => scale },
+        )?;
+        self.render.render_template(
+            "expressions/cast",
+            context! { expr => expr, data_type => data_type },
+        )
+    }
+
     pub fn column_reference(
         &self,
         table_name: &Option<String>,
@@ -77,6 +243,11 @@ impl PlanSqlTemplates {
         )
     }
 
+    pub fn grouping(&self, exprs: Vec<String>) -> Result<String, CubeError> {
+        self.render
+            .render_template("expressions/grouping", context! { exprs => exprs })
+    }
+
     pub fn always_true(&self) -> Result<String, CubeError> {
         Ok(self.render.get_template("filters/always_true")?.clone())
     }
@@ -94,13 +265,16 @@ impl PlanSqlTemplates {
         expr: &str,
         index: Option<usize>,
         asc: bool,
+        nulls_first: Option<bool>,
     ) -> Result<String, CubeError> {
         self.render.render_template(
             "expressions/order_by",
             context! {
                 expr => expr,
                 index => index,
-                asc => asc
+                asc => asc,
+                nulls_first => nulls_first == Some(true),
+                nulls_last => nulls_first == Some(false)
             },
         )
     }
@@ -114,6 +288,40 @@ impl PlanSqlTemplates {
         )
     }
 
+    pub fn supports_grouping_sets(&self) -> bool {
+        self.render.contains_template("statements/grouping_sets")
+    }
+
+    // Sets are rendered with expressions: positional references aren't allowed inside
+    // grouping sets on most dialects. Rollup and cube are left to the database to expand
+    pub fn grouping_sets(
+        &self,
+        items: Vec<TemplateGroupByColumn>,
+        grouping_sets: &GroupingSets,
+    ) -> Result<String, CubeError> {
+        let sets = match grouping_sets {
+            GroupingSets::Sets(_) => grouping_sets
+                .expand(items.len())
+                .into_iter()
+                .map(|set| {
+                    set.into_iter()
+                        .filter_map(|i| items.get(i).map(|item| item.expr.clone()))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        self.render.render_template(
+            "statements/grouping_sets",
+            context! {
+                rollup => grouping_sets == &GroupingSets::Rollup,
+                cube => grouping_sets == &GroupingSets::Cube,
+                sets => sets,
+                group_by => items
+            },
+        )
+    }
+
     pub fn cte(&self, query: &str, alias: &str) -> Result<String, CubeError> {
         self.render.render_template(
             "statements/cte",
@@ -124,6 +332,15 @@ impl PlanSqlTemplates {
         )
     }
 
+    pub fn comment(&self, text: &str) -> Result<String, CubeError> {
+        self.render.render_template(
+            "statements/comment",
+            context! {
+                text => text
+            },
+        )
+    }
+
     pub fn time_series_select(
         &self,
         from_date: Option<String>,
@@ -147,18 +364,25 @@ impl PlanSqlTemplates {
         projection: Vec<TemplateProjectionColumn>,
         where_condition: Option<String>,
         group_by: Vec<TemplateGroupByColumn>,
+        grouping_sets: Option<&GroupingSets>,
         having: Option<String>,
         order_by: Vec<TemplateOrderByColumn>,
         limit: Option<usize>,
         offset: Option<usize>,
         distinct: bool,
     ) -> Result<String, CubeError> {
+        let group_by = match grouping_sets {
+            Some(grouping_sets) if !group_by.is_empty() => {
+                self.grouping_sets(group_by, grouping_sets)?
+            }
+            _ => self.group_by(group_by)?,
+        };
         self.render.render_template(
             "statements/select",
             context! {
                 from_prepared => from,
                 select_concat => projection,
-                group_by => self.group_by(group_by)?,
+                group_by => group_by,
                 projection => projection,
                 order_by => order_by,
                 filter => where_condition,
@@ -188,6 +412,114 @@ impl PlanSqlTemplates {
         )
     }
 
+    // Sampled table reference, `None` where the database can't sample tables
+    pub fn table_sample(&self, table: &str, percent: u32) -> Result<Option<String>, CubeError> {
+        if !self.render.contains_template("statements/table_sample") {
+            return Ok(None);
+        }
+        self.render
+            .render_template(
+                "statements/table_sample",
+                context! { table => table, percent => percent },
+            )
+            .map(Some)
+    }
+
+    pub fn supports_as_of_join(&self) -> bool {
+        self.render.contains_template("statements/as_of_join")
+    }
+
+    // Nearest preceding row of the joined source: `ASOF JOIN` where the database has it
+    // or a lateral subquery
+    pub fn as_of_join(
+        &self,
+        source: &str,
+        alias: &str,
+        condition: &str,
+        time: &str,
+        match_time: &str,
+    ) -> Result<String, CubeError> {
+        if !self.render.contains_template("statements/as_of_join") {
+            return Err(CubeError::user(
+                "As-of joins aren't supported by this database".to_string(),
+            ));
+        }
+        let alias = self.quote_identifier(alias)?;
+        self.render.render_template(
+            "statements/as_of_join",
+            context! {
+                source => source,
+                alias => alias,
+                condition => condition,
+                time => time,
+                match_time => match_time
+            },
+        )
+    }
+
+    // As-of join for databases without `ASOF JOIN` and lateral subqueries: rows of the joining
+    // source are joined to all preceding rows of the joined one in a subquery, which keeps
+    // the nearest one for each primary key of the joining row
+    pub fn as_of_join_by_window(
+        &self,
+        from_source: &str,
+        source: &str,
+        alias: &str,
+        keys: &[String],
+        condition: &str,
+        time: &str,
+        match_time: &str,
+    ) -> Result<String, CubeError> {
+        if keys.is_empty() {
+            return Err(CubeError::user(
+                "As-of joins need a primary key of the joining cube on this database".to_string(),
+            ));
+        }
+        let quoted_alias = self.quote_identifier(alias)?;
+        let key_aliases = (0..keys.len())
+            .map(|i| self.quote_identifier(&format!("__as_of_key_{}", i)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rank = self.quote_identifier("__as_of_rank")?;
+        let keys_projection = keys
+            .iter()
+            .zip(key_aliases.iter())
+            .map(|(key, key_alias)| format!("{} AS {}", key, key_alias))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let preceding = self.join(
+            source,
+            &format!("({}) AND {} <= {}", condition, match_time, time),
+            &JoinType::Inner,
+        )?;
+        let ranked = format!(
+            "SELECT {}, {}.*, ROW_NUMBER() OVER (PARTITION BY {} ORDER BY {} DESC) AS {} FROM {} {}",
+            keys_projection,
+            quoted_alias,
+            keys.join(", "),
+            match_time,
+            rank,
+            from_source,
+            preceding
+        );
+        let nearest = format!(
+            "SELECT * FROM {} WHERE {}.{} = 1",
+            self.query_aliased(&format!("({})", ranked), alias)?,
+            quoted_alias,
+            rank
+        );
+        let on = keys
+            .iter()
+            .zip(key_aliases.iter())
+            .map(|(key, key_alias)| format!("{} = {}.{}", key, quoted_alias, key_alias))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        self.join(
+            &self.query_aliased(&format!("({})", nearest), alias)?,
+            &on,
+            &JoinType::Left,
+        )
+    }
+
     pub fn binary_expr(&self, left: &str, op: &str, right: &str) -> Result<String, CubeError> {
         self.render.render_template(
             "expressions/binary",
@@ -200,9 +532,10 @@ impl PlanSqlTemplates {
         left_column: &String,
         right_column: &String,
         null_check: bool,
+        allow_is_not_distinct_from: bool,
     ) -> Result<String, CubeError> {
         let null_check = if null_check {
-            if self.supports_is_not_distinct_from() {
+            if allow_is_not_distinct_from && self.supports_is_not_distinct_from() {
                 let is_not_distinct_from_op = self
                     .render
                     .render_template("operators/is_not_distinct_from", context! {})?;
@@ -233,11 +566,131 @@ impl PlanSqlTemplates {
             .contains_template("operators/is_not_distinct_from")
     }
 
+    pub fn supports_row_count_estimate(&self) -> bool {
+        self.render.contains_template("row_count_estimate/query")
+    }
+
+    pub fn row_count_estimate(&self, sql: &str, tables: Vec<String>) -> Result<String, CubeError> {
+        self.render.render_template(
+            "row_count_estimate/query",
+            context! { sql => sql, tables => tables },
+        )
+    }
+
+    pub fn total_count(&self, sql: &str) -> Result<String, CubeError> {
+        let total_alias = self.quote_identifier("total_count")?;
+        let original_alias = self.quote_identifier("original_query")?;
+        self.render.render_template(
+            "statements/total_count",
+            context! { sql => sql, total_alias => total_alias, original_alias => original_alias },
+        )
+    }
+
+    pub fn row_count_estimate_strategy(&self) -> Result<String, CubeError> {
+        Ok(self
+            .render
+            .get_template("row_count_estimate/strategy")?
+            .clone())
+    }
+
+    pub fn unnest(&self, expr: &str, dedupe: bool) -> Result<String, CubeError> {
+        let template = if dedupe {
+            "expressions/unnest_distinct"
+        } else {
+            "expressions/unnest"
+        };
+        if !self.render.contains_template(template) {
+            return Err(CubeError::user(
+                "Multi-value dimensions are not supported by this data source".to_string(),
+            ));
+        }
+        self.render
+            .render_template(template, context! { expr => expr })
+    }
+
+    pub fn supports_gap_fill(&self) -> bool {
+        self.render.contains_template("gap_fill/zero")
+            && self.render.contains_template("gap_fill/previous_group")
+            && self.render.contains_template("gap_fill/previous")
+    }
+
+    pub fn gap_fill_zero(&self, expr: &str) -> Result<String, CubeError> {
+        self.render
+            .render_template("gap_fill/zero", context! { expr => expr })
+    }
+
+    // Counter of known values up to the bucket, equal for a known value and the gap after it
+    pub fn gap_fill_previous_group(&self, expr: &str, order_by: &str) -> Result<String, CubeError> {
+        self.render.render_template(
+            "gap_fill/previous_group",
+            context! { expr => expr, order_by => order_by },
+        )
+    }
+
+    pub fn gap_fill_previous(&self, expr: &str, group: &str) -> Result<String, CubeError> {
+        self.render.render_template(
+            "gap_fill/previous",
+            context! { expr => expr, group => group },
+        )
+    }
+
+    pub fn time_attribute(
+        &self,
+        time_attribute: &TimeAttribute,
+        expr: &str,
+    ) -> Result<String, CubeError> {
+        let template = format!("time_attributes/{}", time_attribute.name());
+        if !self.render.contains_template(&template) {
+            return Err(CubeError::user(format!(
+                "Time attribute {} is not supported by this data source",
+                time_attribute.name()
+            )));
+        }
+        self.render
+            .render_template(&template, context! { expr => expr })
+    }
+
+    // Number of unit boundaries between two timestamps
+    pub fn date_diff(&self, unit: &str, from: &str, to: &str) -> Result<String, CubeError> {
+        self.scalar_function(
+            "DATEDIFF".to_string(),
+            vec![format!("'{}'", unit), from.to_string(), to.to_string()],
+            Some(unit.to_string()),
+            None,
+        )
+    }
+
+    pub fn case(
+        &self,
+        expr: Option<String>,
+        when_then: Vec<(String, String)>,
+        else_expr: Option<String>,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            "expressions/case",
+            context! { expr => expr, when_then => when_then, else_expr => else_expr },
+        )
+    }
+
     pub fn param(&self, param_index: usize) -> Result<String, CubeError> {
         self.render
             .render_template("params/param", context! { param_index => param_index })
     }
 
+    // Placeholders which refer to the param by its index, e.g. `$1` or `@_1`, can be repeated
+    // for the same value. Positional ones like `?` need the value to be bound once per occurrence
+    pub fn is_indexed_param(&self) -> Result<bool, CubeError> {
+        Ok(self
+            .render
+            .get_template("params/param")?
+            .contains("param_index"))
+    }
+
+    pub fn named_param(&self, param_name: &str) -> Result<String, CubeError> {
+        self.render
+            .render_template("params/named_param", context! { param_name => param_name })
+    }
+
     pub fn scalar_function(
         &self,
         scalar_function: String,
@@ -258,3 +711,368 @@ impl PlanSqlTemplates {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::sql_templates_render::MockSqlTemplatesRender;
+
+    const BASE_QUOTES: [(&str, &str); 6] = [
+        ("quotes/identifiers", "\""),
+        ("quotes/escape", "\"\""),
+        ("quotes/literal", "'"),
+        ("quotes/literal_escape", "''"),
+        ("expressions/true", "TRUE"),
+        ("expressions/false", "FALSE"),
+    ];
+
+    fn templates(backslash_escape: bool) -> PlanSqlTemplates {
+        let mut templates = BASE_QUOTES.to_vec();
+        if backslash_escape {
+            templates.push(("quotes/literal_backslash_escape", "\\\\"));
+        }
+        PlanSqlTemplates::new(Rc::new(MockSqlTemplatesRender::try_new(templates).unwrap()))
+    }
+
+    // Dialects and whether backslash is an escape character in their string literals
+    fn dialects() -> Vec<(&'static str, PlanSqlTemplates)> {
+        vec![
+            ("postgres", templates(false)),
+            ("mssql", templates(false)),
+            ("mysql", templates(true)),
+            ("clickhouse", templates(true)),
+            ("bigquery", templates(true)),
+            ("snowflake", templates(true)),
+            ("databricks", templates(true)),
+        ]
+    }
+
+    fn literal(templates: &PlanSqlTemplates, value: &str, param_type: ParamType) -> String {
+        templates
+            .literal(&TypedParam::new(value.to_string(), param_type))
+            .unwrap()
+    }
+
+    #[test]
+    fn string_literal_escapes_quotes() {
+        for (dialect, templates) in dialects() {
+            assert_eq!(
+                templates.string_literal("it's").unwrap(),
+                "'it''s'",
+                "{dialect}"
+            );
+            assert_eq!(
+                templates.string_literal("' OR 1=1 --").unwrap(),
+                "''' OR 1=1 --'",
+                "{dialect}"
+            );
+        }
+    }
+
+    #[test]
+    fn string_literal_escapes_backslashes() {
+        for (dialect, templates) in dialects() {
+            let backslash_escape = !matches!(dialect, "postgres" | "mssql");
+            let expected = if backslash_escape {
+                "'\\\\'' OR 1=1 --'"
+            } else {
+                "'\\'' OR 1=1 --'"
+            };
+            assert_eq!(
+                templates.string_literal("\\' OR 1=1 --").unwrap(),
+                expected,
+                "{dialect}"
+            );
+            let expected = if backslash_escape { "'a\\\\'" } else { "'a\\'" };
+            assert_eq!(
+                templates.string_literal("a\\").unwrap(),
+                expected,
+                "{dialect}"
+            );
+        }
+    }
+
+    #[test]
+    fn number_literal_falls_back_to_string() {
+        for (dialect, templates) in dialects() {
+            assert_eq!(
+                literal(&templates, "42", ParamType::Number),
+                "42",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, " -1.5e3 ", ParamType::Number),
+                "-1.5e3",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "1; DROP TABLE users", ParamType::Number),
+                "'1; DROP TABLE users'",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "NaN", ParamType::Number),
+                "'NaN'",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "inf", ParamType::Number),
+                "'inf'",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "1e999", ParamType::Number),
+                "'1e999'",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "0x10", ParamType::Number),
+                "'0x10'",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "1'", ParamType::Number),
+                "'1'''",
+                "{dialect}"
+            );
+        }
+    }
+
+    #[test]
+    fn safe_divide() {
+        let render = MockSqlTemplatesRender::try_new(vec![
+            (
+                "expressions/safe_divide",
+                "1.0 * ({{ numerator }}) / NULLIF({{ denominator }}, 0)",
+            ),
+            (
+                "expressions/case",
+                "CASE{% if expr %} {{ expr }}{% endif %}{% for when, then in when_then %} WHEN {{ when }} THEN {{ then }}{% endfor %}{% if else_expr %} ELSE {{ else_expr }}{% endif %} END",
+            ),
+        ])
+        .unwrap();
+        let templates = PlanSqlTemplates::new(Rc::new(render));
+        assert_eq!(
+            templates.safe_divide("a", "b", false).unwrap(),
+            "1.0 * (a) / NULLIF(b, 0)"
+        );
+        assert_eq!(
+            templates.safe_divide("a", "b", true).unwrap(),
+            "CASE WHEN (b) = 0 THEN 0 ELSE 1.0 * (a) / NULLIF(b, 0) END"
+        );
+    }
+
+    #[test]
+    fn boolean_literal() {
+        for (dialect, templates) in dialects() {
+            assert_eq!(
+                literal(&templates, "true", ParamType::Boolean),
+                "TRUE",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "0", ParamType::Boolean),
+                "FALSE",
+                "{dialect}"
+            );
+            assert_eq!(
+                literal(&templates, "yes' --", ParamType::Boolean),
+                "'yes'' --'",
+                "{dialect}"
+            );
+        }
+    }
+
+    #[test]
+    fn as_of_join_by_window_keeps_nearest_preceding_row() {
+        let mut templates = BASE_QUOTES.to_vec();
+        templates.push((
+            "statements/join",
+            "{{ join_type }} JOIN {{ source }} ON {{ condition }}",
+        ));
+        templates.push(("join_types/inner", "INNER"));
+        templates.push(("join_types/left", "LEFT"));
+        templates.push((
+            "expressions/query_aliased",
+            "{{ query }} AS {{ quoted_alias }}",
+        ));
+        let templates =
+            PlanSqlTemplates::new(Rc::new(MockSqlTemplatesRender::try_new(templates).unwrap()));
+        assert!(!templates.supports_as_of_join());
+        let sql = templates
+            .as_of_join_by_window(
+                "trades AS \"trades\"",
+                "quotes AS \"quotes\"",
+                "quotes",
+                &["\"trades\".id".to_string()],
+                "\"trades\".symbol = \"quotes\".symbol",
+                "\"trades\".ts",
+                "\"quotes\".ts",
+            )
+            .unwrap();
+        assert_eq!(
+            sql,
+            "LEFT JOIN (SELECT * FROM (SELECT \"trades\".id AS \"__as_of_key_0\", \"quotes\".*, \
+             ROW_NUMBER() OVER (PARTITION BY \"trades\".id ORDER BY \"quotes\".ts DESC) AS \"__as_of_rank\" \
+             FROM trades AS \"trades\" INNER JOIN quotes AS \"quotes\" \
+             ON (\"trades\".symbol = \"quotes\".symbol) AND \"quotes\".ts <= \"trades\".ts) AS \"quotes\" \
+             WHERE \"quotes\".\"__as_of_rank\" = 1) AS \"quotes\" \
+             ON \"trades\".id = \"quotes\".\"__as_of_key_0\""
+        );
+        assert!(templates
+            .as_of_join_by_window("t", "q", "q", &[], "1 = 1", "t.ts", "q.ts")
+            .is_err());
+    }
+
+    fn grouping_templates() -> PlanSqlTemplates {
+        let mut templates = BASE_QUOTES.to_vec();
+        templates.push((
+            "statements/grouping_sets",
+            "{% if rollup %}ROLLUP({{ group_by | map(attribute='expr') | join(', ') }})\
+             {% elif cube %}CUBE({{ group_by | map(attribute='expr') | join(', ') }})\
+             {% else %}GROUPING SETS ({% for set in sets %}({{ set | join(', ') }})\
+             {% if not loop.last %}, {% endif %}{% endfor %}){% endif %}",
+        ));
+        templates.push(("expressions/grouping", "GROUPING({{ exprs | join(', ') }})"));
+        PlanSqlTemplates::new(Rc::new(MockSqlTemplatesRender::try_new(templates).unwrap()))
+    }
+
+    fn group_by_columns(exprs: &[&str]) -> Vec<TemplateGroupByColumn> {
+        exprs
+            .iter()
+            .enumerate()
+            .map(|(i, expr)| TemplateGroupByColumn {
+                expr: expr.to_string(),
+                index: i + 1,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grouping_sets_render_subtotals() {
+        let templates = grouping_templates();
+        assert_eq!(
+            templates
+                .grouping_sets(group_by_columns(&["a", "b"]), &GroupingSets::Rollup)
+                .unwrap(),
+            "ROLLUP(a, b)"
+        );
+        assert_eq!(
+            templates
+                .grouping_sets(group_by_columns(&["a", "b"]), &GroupingSets::Cube)
+                .unwrap(),
+            "CUBE(a, b)"
+        );
+        assert_eq!(
+            templates
+                .grouping_sets(
+                    group_by_columns(&["a", "b"]),
+                    &GroupingSets::Sets(vec![vec![1], vec![]])
+                )
+                .unwrap(),
+            "GROUPING SETS ((a, b), (b), ())"
+        );
+    }
+
+    #[test]
+    fn cube_of_many_columns_is_not_expanded() {
+        let columns = (0..64).map(|i| format!("c{i}")).collect::<Vec<_>>();
+        let columns = columns.iter().map(|c| c.as_str()).collect::<Vec<_>>();
+        let sql = grouping_templates()
+            .grouping_sets(group_by_columns(&columns), &GroupingSets::Cube)
+            .unwrap();
+        assert!(sql.starts_with("CUBE(c0, c1"));
+    }
+
+    #[test]
+    fn grouping_renders_group_by_exprs() {
+        assert_eq!(
+            grouping_templates()
+                .grouping(vec!["a".to_string(), "b".to_string()])
+                .unwrap(),
+            "GROUPING(a, b)"
+        );
+    }
+
+    #[test]
+    fn time_attribute_requires_dialect_template() {
+        let err = templates(false)
+            .time_attribute(&TimeAttribute::DayOfWeek, "created_at")
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            "Time attribute dow is not supported by this data source"
+        );
+
+        let templates = PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![(
+                "time_attributes/dow",
+                "EXTRACT(ISODOW FROM {{ expr }})",
+            )])
+            .unwrap(),
+        ));
+        assert_eq!(
+            templates
+                .time_attribute(&TimeAttribute::DayOfWeek, "created_at")
+                .unwrap(),
+            "EXTRACT(ISODOW FROM created_at)"
+        );
+    }
+
+    #[test]
+    fn hex_encode_requires_dialect_template() {
+        let err = templates(false).hex_encode("payload").unwrap_err();
+        assert_eq!(
+            err.message,
+            "Binary dimensions are not supported by this data source"
+        );
+
+        let templates = PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![("expressions/hex_encode", "TO_HEX({{ expr }})")])
+                .unwrap(),
+        ));
+        assert_eq!(templates.hex_encode("payload").unwrap(), "TO_HEX(payload)");
+    }
+
+    #[test]
+    fn unnest_requires_dialect_template() {
+        let err = templates(false).unnest("tags", false).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Multi-value dimensions are not supported by this data source"
+        );
+
+        let templates = PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![
+                ("expressions/unnest", "UNNEST({{ expr }})"),
+                (
+                    "expressions/unnest_distinct",
+                    "UNNEST(ARRAY(SELECT DISTINCT UNNEST({{ expr }})))",
+                ),
+            ])
+            .unwrap(),
+        ));
+        assert_eq!(templates.unnest("tags", false).unwrap(), "UNNEST(tags)");
+        assert_eq!(
+            templates.unnest("tags", true).unwrap(),
+            "UNNEST(ARRAY(SELECT DISTINCT UNNEST(tags)))"
+        );
+    }
+
+    #[test]
+    fn date_diff_passes_unit_as_date_part() {
+        let templates = PlanSqlTemplates::new(Rc::new(
+            MockSqlTemplatesRender::try_new(vec![(
+                "functions/DATEDIFF",
+                "dateDiff('{{ date_part }}', {{ args[1] }}, {{ args[2] }})",
+            )])
+            .unwrap(),
+        ));
+        assert_eq!(
+            templates
+                .date_diff("day", "created_at", "closed_at")
+                .unwrap(),
+            "dateDiff('day', created_at, closed_at)"
+        );
+    }
+}
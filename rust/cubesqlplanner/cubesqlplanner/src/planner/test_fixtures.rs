@@ -0,0 +1,558 @@
+// Query tools backed by pure Rust doubles of the JS bridge, so filters and sql rendering
+// can be unit tested without Node.js. Params are recorded by the allocator and rendered
+// as `$1`, `$2`, ... in the allocation order, see `QueryTools::render_allocated_placeholders`
+use crate::cube_bridge::base_query_options::ValuesNormalization;
+use crate::cube_bridge::base_tools::{BaseTools, CallDep};
+use crate::cube_bridge::cube_definition::{CubeDefinition, CubeDefinitionStatic};
+use crate::cube_bridge::dimension_definition::{DimensionDefinition, DimenstionDefinitionStatic};
+use crate::cube_bridge::evaluator::{
+    CallDep as EvaluatorCallDep, CubeEvaluator, CubeEvaluatorStatic,
+};
+use crate::cube_bridge::filter_group::FilterGroup;
+use crate::cube_bridge::filter_params::FilterParams;
+use crate::cube_bridge::join_definition::JoinDefinition;
+use crate::cube_bridge::join_graph::JoinGraph;
+use crate::cube_bridge::measure_definition::MeasureDefinition;
+use crate::cube_bridge::memeber_sql::MemberSql;
+use crate::cube_bridge::refresh_key::RefreshKey;
+use crate::cube_bridge::security_context::SecurityContext;
+use crate::cube_bridge::segment_definition::SegmentDefinition;
+use crate::cube_bridge::sql_templates_render::{MockSqlTemplatesRender, SqlTemplatesRender};
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::{DimensionSymbol, MemberSymbol};
+use crate::planner::StaticDateTimeTools;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Postgres flavored templates of `BaseQuery.sqlTemplates()` used by filters
+const TEMPLATES: &[(&str, &str)] = &[
+    ("params/param", "${{ param_index + 1 }}"),
+    ("params/named_param", ":{{ param_name }}"),
+    ("quotes/identifiers", "\""),
+    ("quotes/escape", "\"\""),
+    ("quotes/literal", "'"),
+    ("quotes/literal_escape", "''"),
+    ("types/string", "STRING"),
+    ("types/boolean", "BOOLEAN"),
+    ("types/bigint", "BIGINT"),
+    ("types/double", "DOUBLE"),
+    ("expressions/cast", "CAST({{ expr }} AS {{ data_type }})"),
+    ("expressions/true", "TRUE"),
+    ("expressions/false", "FALSE"),
+    (
+        "expressions/add_interval",
+        "{{ date }} + interval '{{ interval }}'",
+    ),
+    (
+        "expressions/sub_interval",
+        "{{ date }} - interval '{{ interval }}'",
+    ),
+    ("expressions/collate", "{{ expr }} COLLATE {{ collation }}"),
+    ("expressions/collation_name", "\"{{ name }}\""),
+    ("expressions/trim", "TRIM({{ expr }})"),
+    (
+        "expressions/like",
+        "{{ expr }} {% if negated %}NOT {% endif %}LIKE {{ pattern }}",
+    ),
+    (
+        "expressions/ilike",
+        "{{ expr }} {% if negated %}NOT {% endif %}ILIKE {{ pattern }}",
+    ),
+    (
+        "expressions/like_escape",
+        "{{ like_expr }} ESCAPE {{ escape_char }}",
+    ),
+    (
+        "filters/equals",
+        "{{ column }} = {{ value }}{{ is_null_check }}",
+    ),
+    (
+        "filters/not_equals",
+        "{{ column }} <> {{ value }}{{ is_null_check }}",
+    ),
+    ("filters/or_is_null_check", " OR {{ column }} IS NULL"),
+    ("filters/set_where", "{{ column }} IS NOT NULL"),
+    ("filters/not_set_where", "{{ column }} IS NULL"),
+    (
+        "filters/in",
+        "{{ column }} IN ({{ values_concat }}){{ is_null_check }}",
+    ),
+    (
+        "filters/not_in",
+        "{{ column }} NOT IN ({{ values_concat }}){{ is_null_check }}",
+    ),
+    (
+        "filters/time_range_filter",
+        "{{ column }} >= {{ from_timestamp }} AND {{ column }} <= {{ to_timestamp }}",
+    ),
+    (
+        "filters/time_range_filter_exclusive",
+        "{{ column }} >= {{ from_timestamp }} AND {{ column }} < {{ to_timestamp }}",
+    ),
+    ("filters/time_range_end", "inclusive"),
+    ("filters/timestamp_param", "{{ param }}::timestamptz"),
+    ("filters/gt", "{{ column }} > {{ param }}"),
+    ("filters/gte", "{{ column }} >= {{ param }}"),
+    ("filters/lt", "{{ column }} < {{ param }}"),
+    ("filters/lte", "{{ column }} <= {{ param }}"),
+    (
+        "filters/between",
+        "{{ column }} BETWEEN {{ from }} AND {{ to }}",
+    ),
+    (
+        "filters/not_between",
+        "{{ column }} NOT BETWEEN {{ from }} AND {{ to }}{{ is_null_check }}",
+    ),
+    (
+        "filters/like_pattern",
+        "{% if start_wild %}'%' || {% endif %}{{ value }}{% if end_wild %}|| '%'{% endif %}",
+    ),
+    ("filters/binary_param", "decode({{ param }}, 'hex')"),
+    ("filters/uuid_param", "CAST({{ param }} AS UUID)"),
+    ("filters/always_true", "1 = 1"),
+    (
+        "filters/calendar_exists",
+        "EXISTS (SELECT 1 FROM {{ table }} AS {{ calendar_alias }} WHERE {{ calendar_alias }}.{{ date_column }} = CAST({{ column }} AS DATE) AND {{ calendar_alias }}.{{ flag_column }} = {{ flag_value }})",
+    ),
+    ("filters/always_false", "1 = 0"),
+    ("filters/array_contains", "{{ value }} = ANY({{ column }})"),
+    (
+        "filters/regex_match",
+        "{{ column }} {% if negated %}!~{% else %}~{% endif %} {{ value }}",
+    ),
+];
+
+pub struct MockBaseTools {
+    templates: Rc<dyn SqlTemplatesRender>,
+}
+
+impl BaseTools for MockBaseTools {
+    fn convert_tz(&self, field: String) -> Result<String, CubeError> {
+        Ok(field)
+    }
+
+    fn time_grouped_column(
+        &self,
+        granularity: String,
+        dimension: String,
+    ) -> Result<String, CubeError> {
+        Ok(format!("date_trunc('{}', {})", granularity, dimension))
+    }
+
+    fn sql_templates(&self) -> Result<Rc<dyn SqlTemplatesRender>, CubeError> {
+        Ok(self.templates.clone())
+    }
+
+    fn resolve_symbols_call_deps(
+        &self,
+        _cube_name: String,
+        _sql: Rc<dyn MemberSql>,
+    ) -> Result<Vec<CallDep>, CubeError> {
+        Err(unsupported("resolve_symbols_call_deps"))
+    }
+
+    fn security_context_for_rust(&self) -> Result<Rc<dyn SecurityContext>, CubeError> {
+        Err(unsupported("security_context_for_rust"))
+    }
+
+    fn filters_proxy_for_rust(&self) -> Result<Rc<dyn FilterParams>, CubeError> {
+        Err(unsupported("filters_proxy_for_rust"))
+    }
+
+    fn filter_group_function_for_rust(&self) -> Result<Rc<dyn FilterGroup>, CubeError> {
+        Err(unsupported("filter_group_function_for_rust"))
+    }
+
+    fn timestamp_precision(&self) -> Result<u32, CubeError> {
+        Ok(3)
+    }
+
+    fn in_db_time_zone(&self, date: String) -> Result<String, CubeError> {
+        Ok(date)
+    }
+
+    fn generate_time_series(
+        &self,
+        _granularity: String,
+        _date_range: Vec<String>,
+    ) -> Result<Vec<Vec<String>>, CubeError> {
+        Err(unsupported("generate_time_series"))
+    }
+
+    fn get_allocated_params(&self) -> Result<Vec<String>, CubeError> {
+        Ok(vec![])
+    }
+
+    fn all_cube_members(&self, _path: String) -> Result<Vec<String>, CubeError> {
+        Err(unsupported("all_cube_members"))
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+// Members are created by the fixture itself, so only cubes of the schema are looked up
+pub struct MockCubeEvaluator {
+    static_data: CubeEvaluatorStatic,
+    cubes: HashMap<String, Rc<MockCubeDefinition>>,
+}
+
+impl CubeEvaluator for MockCubeEvaluator {
+    fn primary_keys(&self) -> Result<HashMap<String, String>, CubeError> {
+        Ok(HashMap::new())
+    }
+
+    fn parse_path(&self, _path_type: String, _path: String) -> Result<Vec<String>, CubeError> {
+        Err(unsupported("parse_path"))
+    }
+
+    fn measure_by_path(
+        &self,
+        _measure_path: String,
+    ) -> Result<Rc<dyn MeasureDefinition>, CubeError> {
+        Err(unsupported("measure_by_path"))
+    }
+
+    fn dimension_by_path(
+        &self,
+        _measure_path: String,
+    ) -> Result<Rc<dyn DimensionDefinition>, CubeError> {
+        Err(unsupported("dimension_by_path"))
+    }
+
+    fn segment_by_path(
+        &self,
+        _segment_path: String,
+    ) -> Result<Rc<dyn SegmentDefinition>, CubeError> {
+        Err(unsupported("segment_by_path"))
+    }
+
+    fn cube_from_path(&self, cube_path: String) -> Result<Rc<dyn CubeDefinition>, CubeError> {
+        match self.cubes.get(&cube_path) {
+            Some(cube) => Ok(cube.clone()),
+            None => Err(CubeError::user(format!("Cube '{}' not found", cube_path))),
+        }
+    }
+
+    fn is_measure(&self, _path: Vec<String>) -> Result<bool, CubeError> {
+        Err(unsupported("is_measure"))
+    }
+
+    fn is_dimension(&self, _path: Vec<String>) -> Result<bool, CubeError> {
+        Err(unsupported("is_dimension"))
+    }
+
+    fn cube_exists(&self, _name: String) -> Result<bool, CubeError> {
+        Err(unsupported("cube_exists"))
+    }
+
+    fn resolve_symbols_call_deps(
+        &self,
+        _cube_name: String,
+        _sql: Rc<dyn MemberSql>,
+    ) -> Result<Vec<EvaluatorCallDep>, CubeError> {
+        Err(unsupported("resolve_symbols_call_deps"))
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &CubeEvaluatorStatic {
+        &self.static_data
+    }
+}
+
+pub struct MockJoinGraph;
+
+impl JoinGraph for MockJoinGraph {
+    fn build_join(&self, _cubes_to_join: Vec<String>) -> Result<Rc<dyn JoinDefinition>, CubeError> {
+        Err(unsupported("build_join"))
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+pub struct MockCubeDefinition {
+    static_data: CubeDefinitionStatic,
+}
+
+impl CubeDefinition for MockCubeDefinition {
+    fn sql_table(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_sql_table(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_sql(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn refresh_key(&self) -> Result<Option<Rc<dyn RefreshKey>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_refresh_key(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &CubeDefinitionStatic {
+        &self.static_data
+    }
+}
+
+pub struct MockDimensionDefinition {
+    static_data: DimenstionDefinitionStatic,
+}
+
+impl DimensionDefinition for MockDimensionDefinition {
+    fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_sql(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &DimenstionDefinitionStatic {
+        &self.static_data
+    }
+}
+
+fn unsupported(method: &str) -> CubeError {
+    CubeError::internal(format!("{} isn't supported by test fixtures", method))
+}
+
+// Definition of a cube without sql and with all the options unset
+pub fn cube_definition(name: &str) -> CubeDefinitionStatic {
+    CubeDefinitionStatic {
+        name: name.to_string(),
+        calendar: None,
+        union: None,
+        updated_at: None,
+        source_query: None,
+        validity: None,
+    }
+}
+
+// Definition of a dimension of the given type with all the other options unset
+pub fn dimension_definition(dimension_type: &str) -> DimenstionDefinitionStatic {
+    DimenstionDefinitionStatic {
+        dimension_type: dimension_type.to_string(),
+        owned_by_cube: None,
+        multi_stage: None,
+        sensitive: None,
+        collation: None,
+        unit: None,
+        duration: None,
+        multi_value: None,
+        multi_value_dedupe: None,
+        value_map: None,
+        sub_query: None,
+        starts_with_range: None,
+    }
+}
+
+// Dimension symbol without sql, filters on it are rendered with `to_sql_for_column`
+pub fn dimension(full_name: &str, definition: DimenstionDefinitionStatic) -> Rc<MemberSymbol> {
+    let (cube_name, name) = full_name.split_once('.').unwrap();
+    MemberSymbol::new_dimension(DimensionSymbol::new(
+        cube_name.to_string(),
+        name.to_string(),
+        None,
+        Rc::new(MockDimensionDefinition {
+            static_data: definition,
+        }),
+        None,
+        None,
+    ))
+}
+
+#[derive(Default)]
+pub struct QueryToolsFixture {
+    templates: Vec<(&'static str, &'static str)>,
+    cubes: Vec<CubeDefinitionStatic>,
+    removed_templates: Vec<&'static str>,
+    timezone: Option<String>,
+    security_context: Option<serde_json::Value>,
+    redaction_mode: Option<String>,
+    cast_policy: Option<String>,
+    empty_in_list_policy: Option<String>,
+    collation: Option<String>,
+    values_normalization: Option<ValuesNormalization>,
+    planner_flags: HashMap<String, bool>,
+    source_date_ranges: HashMap<String, (String, String)>,
+    inline_params: bool,
+    now: Option<String>,
+}
+
+impl QueryToolsFixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Overrides or adds a template of the default set
+    pub fn template(mut self, name: &'static str, template: &'static str) -> Self {
+        self.templates.push((name, template));
+        self
+    }
+
+    pub fn cube(mut self, cube: CubeDefinitionStatic) -> Self {
+        self.cubes.push(cube);
+        self
+    }
+
+    pub fn without_template(mut self, name: &'static str) -> Self {
+        self.removed_templates.push(name);
+        self
+    }
+
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.timezone = Some(timezone.to_string());
+        self
+    }
+
+    pub fn security_context(mut self, security_context: serde_json::Value) -> Self {
+        self.security_context = Some(security_context);
+        self
+    }
+
+    pub fn redaction_mode(mut self, mode: &str) -> Self {
+        self.redaction_mode = Some(mode.to_string());
+        self
+    }
+
+    pub fn cast_policy(mut self, policy: &str) -> Self {
+        self.cast_policy = Some(policy.to_string());
+        self
+    }
+
+    pub fn empty_in_list_policy(mut self, policy: &str) -> Self {
+        self.empty_in_list_policy = Some(policy.to_string());
+        self
+    }
+
+    pub fn collation(mut self, collation: &str) -> Self {
+        self.collation = Some(collation.to_string());
+        self
+    }
+
+    pub fn values_normalization(mut self, case_fold: bool) -> Self {
+        self.values_normalization = Some(ValuesNormalization {
+            case_fold: Some(case_fold),
+        });
+        self
+    }
+
+    pub fn planner_flag(mut self, name: &str, value: bool) -> Self {
+        self.planner_flags.insert(name.to_string(), value);
+        self
+    }
+
+    // Date range of the query time dimensions of the cube
+    pub fn source_date_range(mut self, cube_name: &str, from: &str, to: &str) -> Self {
+        self.source_date_ranges
+            .insert(cube_name.to_string(), (from.to_string(), to.to_string()));
+        self
+    }
+
+    pub fn inline_params(mut self) -> Self {
+        self.inline_params = true;
+        self
+    }
+
+    // Fixed UTC instant relative date ranges are resolved against
+    pub fn now(mut self, now: &str) -> Self {
+        self.now = Some(now.to_string());
+        self
+    }
+
+    pub fn build(self) -> Rc<QueryTools> {
+        let mut templates = TEMPLATES
+            .iter()
+            .chain(self.templates.iter())
+            .map(|(name, template)| (name.to_string(), template.to_string()))
+            .collect::<HashMap<_, _>>();
+        for name in self.removed_templates.iter() {
+            templates.remove(*name);
+        }
+        let render = MockSqlTemplatesRender::try_new(
+            templates
+                .iter()
+                .map(|(name, template)| (name.as_str(), template.as_str()))
+                .collect(),
+        )
+        .unwrap();
+        let timezone = self.timezone.as_ref().map(|tz| tz.parse().unwrap());
+        QueryTools::try_new(
+            Rc::new(MockCubeEvaluator {
+                static_data: CubeEvaluatorStatic {
+                    primary_keys: HashMap::new(),
+                },
+                cubes: self
+                    .cubes
+                    .into_iter()
+                    .map(|cube| {
+                        (
+                            cube.name.clone(),
+                            Rc::new(MockCubeDefinition { static_data: cube }),
+                        )
+                    })
+                    .collect(),
+            }),
+            Rc::new(MockBaseTools {
+                templates: Rc::new(render),
+            }),
+            Rc::new(MockJoinGraph),
+            self.timezone,
+            self.security_context,
+            self.redaction_mode,
+            None,
+            self.cast_policy,
+            self.empty_in_list_policy,
+            self.collation,
+            self.values_normalization,
+            None,
+            None,
+            None,
+            None,
+            self.source_date_ranges,
+            None,
+            None,
+            None,
+            None,
+            false,
+            Some(self.planner_flags),
+            None,
+            false,
+            self.inline_params,
+            false,
+            None,
+            Some(StaticDateTimeTools::with_now(
+                timezone,
+                3,
+                self.now.map(|now| now.parse().unwrap()),
+            )),
+        )
+        .unwrap()
+    }
+}
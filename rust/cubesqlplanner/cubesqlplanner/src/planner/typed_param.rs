@@ -0,0 +1,62 @@
+use serde::{Serialize, Serializer};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamType {
+    String,
+    Number,
+    Boolean,
+    Timestamp,
+}
+
+// Param value with the type it should be bound with. Numbers and booleans are passed
+// to drivers as native values: some of them reject strings compared with numeric columns
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedParam {
+    value: String,
+    param_type: ParamType,
+}
+
+impl TypedParam {
+    pub fn new(value: String, param_type: ParamType) -> Self {
+        Self { value, param_type }
+    }
+
+    pub fn string(value: String) -> Self {
+        Self::new(value, ParamType::String)
+    }
+
+    pub fn value(&self) -> &String {
+        &self.value
+    }
+
+    pub fn param_type(&self) -> ParamType {
+        self.param_type
+    }
+}
+
+// Values which can't be represented as the native type without loss are bound as strings
+impl Serialize for TypedParam {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = self.value.trim();
+        match self.param_type {
+            ParamType::Number => {
+                if let Ok(n) = value.parse::<i64>() {
+                    return serializer.serialize_i64(n);
+                }
+                let significant_digits = value.chars().filter(|c| c.is_ascii_digit()).count();
+                match value.parse::<f64>() {
+                    Ok(n) if n.is_finite() && significant_digits <= 15 => {
+                        serializer.serialize_f64(n)
+                    }
+                    _ => serializer.serialize_str(&self.value),
+                }
+            }
+            ParamType::Boolean => match value.to_lowercase().as_str() {
+                "true" | "1" => serializer.serialize_bool(true),
+                "false" | "0" => serializer.serialize_bool(false),
+                _ => serializer.serialize_str(&self.value),
+            },
+            ParamType::String | ParamType::Timestamp => serializer.serialize_str(&self.value),
+        }
+    }
+}
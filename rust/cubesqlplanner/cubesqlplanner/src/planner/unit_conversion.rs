@@ -0,0 +1,90 @@
+use cubenativeutils::CubeError;
+
+// (unit, quantity, factor to the quantity base unit)
+const UNITS: [(&str, &str, f64); 14] = [
+    ("bytes", "data", 1.0),
+    ("KB", "data", 1024.0),
+    ("MB", "data", 1048576.0),
+    ("GB", "data", 1073741824.0),
+    ("TB", "data", 1099511627776.0),
+    ("millimeters", "length", 0.001),
+    ("centimeters", "length", 0.01),
+    ("meters", "length", 1.0),
+    ("kilometers", "length", 1000.0),
+    ("feet", "length", 0.3048),
+    ("miles", "length", 1609.344),
+    ("grams", "mass", 1.0),
+    ("kilograms", "mass", 1000.0),
+    ("pounds", "mass", 453.59237),
+];
+
+pub struct UnitConversion {}
+
+impl UnitConversion {
+    // Only linear units are supported, so conversion commutes with
+    // aggregation and can be applied to already aggregated values
+    pub fn conversion_factor(from: &str, to: &str) -> Result<f64, CubeError> {
+        let (_, from_quantity, from_factor) = Self::find_unit(from)?;
+        let (_, to_quantity, to_factor) = Self::find_unit(to)?;
+        if from_quantity != to_quantity {
+            return Err(CubeError::user(format!(
+                "Cannot convert {} to {}: units measure different quantities",
+                from, to
+            )));
+        }
+        Ok(from_factor / to_factor)
+    }
+
+    fn find_unit(unit: &str) -> Result<(&'static str, &'static str, f64), CubeError> {
+        UNITS
+            .iter()
+            .find(|(name, _, _)| *name == unit)
+            .cloned()
+            .ok_or_else(|| CubeError::user(format!("Unknown unit '{}'", unit)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion_factor_is_ratio_of_base_unit_factors() {
+        assert_eq!(
+            UnitConversion::conversion_factor("KB", "bytes").unwrap(),
+            1024.0
+        );
+        assert_eq!(
+            UnitConversion::conversion_factor("MB", "KB").unwrap(),
+            1024.0
+        );
+        assert_eq!(
+            UnitConversion::conversion_factor("bytes", "KB").unwrap(),
+            1.0 / 1024.0
+        );
+        assert_eq!(
+            UnitConversion::conversion_factor("miles", "kilometers").unwrap(),
+            1.609344
+        );
+        assert_eq!(
+            UnitConversion::conversion_factor("grams", "grams").unwrap(),
+            1.0
+        );
+    }
+
+    #[test]
+    fn conversion_between_quantities_or_unknown_units_is_rejected() {
+        assert_eq!(
+            UnitConversion::conversion_factor("grams", "meters")
+                .unwrap_err()
+                .message,
+            "Cannot convert grams to meters: units measure different quantities"
+        );
+        assert_eq!(
+            UnitConversion::conversion_factor("parsecs", "meters")
+                .unwrap_err()
+                .message,
+            "Unknown unit 'parsecs'"
+        );
+    }
+}
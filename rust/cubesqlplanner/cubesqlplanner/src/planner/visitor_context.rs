@@ -1,23 +1,34 @@
 use super::query_tools::QueryTools;
 use super::sql_evaluator::sql_nodes::{SqlNode, SqlNodesFactory};
 use super::sql_evaluator::{MemberSymbol, SqlCall};
-use crate::planner::sql_evaluator::SqlEvaluatorVisitor;
+use crate::planner::sql_evaluator::{EvaluatedSqlCache, SqlEvaluatorVisitor};
 use cubenativeutils::CubeError;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 pub struct VisitorContext {
     node_processor: Rc<dyn SqlNode>,
+    references: HashSet<String>,
+    evaluated_sql: Rc<EvaluatedSqlCache>,
 }
 
 impl VisitorContext {
     pub fn new(nodes_factory: &SqlNodesFactory) -> Self {
+        let node_processor = nodes_factory.default_node_processor();
         Self {
-            node_processor: nodes_factory.default_node_processor(),
+            evaluated_sql: Rc::new(EvaluatedSqlCache::new(node_processor.clone())),
+            node_processor,
+            references: nodes_factory.render_references().keys().cloned().collect(),
         }
     }
 
+    // Member is rendered as a column of a subquery instead of its own sql
+    pub fn is_reference(&self, full_name: &str) -> bool {
+        self.references.contains(full_name)
+    }
+
     pub fn make_visitor(&self, query_tools: Rc<QueryTools>) -> SqlEvaluatorVisitor {
-        SqlEvaluatorVisitor::new(query_tools)
+        SqlEvaluatorVisitor::new_with_cache(query_tools, self.evaluated_sql.clone())
     }
 
     pub fn node_processor(&self) -> Rc<dyn SqlNode> {
@@ -32,7 +43,6 @@ pub fn evaluate_with_context(
 ) -> Result<String, CubeError> {
     let visitor = context.make_visitor(query_tools);
     let node_processor = context.node_processor();
-
     visitor.apply(node, node_processor)
 }
 
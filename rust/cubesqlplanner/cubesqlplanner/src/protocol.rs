@@ -0,0 +1,380 @@
+// Versioned JSON-RPC 2.0 protocol between the planner and hosts which pass serialized
+// requests: C ABI, Python bindings and `plannerProtocolCall` of the Node.js bridge.
+// It's an additional interface, not a replacement of the Node.js bridge: its other entry points,
+// e.g. `buildSqlAndParams`, keep passing JS objects the planner calls back while planning,
+// e.g. the cube evaluator and member sql functions, which a serialized request can't carry.
+// `plan` requests are planned against the schema of the host in the `static_schema` format,
+// other methods don't need a schema.
+//
+// Evolution rules:
+// - within a protocol version methods and fields are only added, added fields are optional;
+// - unknown fields are ignored, so older hosts and planners keep working with newer peers;
+// - removing a method or field, or changing its meaning, bumps PROTOCOL_VERSION;
+// - requests of a version newer than PROTOCOL_VERSION are rejected.
+// Serialized requests and responses in tests/fixtures/protocol are the contract of the current version.
+use crate::cube_bridge::base_query_options::{BaseQueryOptionsStatic, FilterItem};
+use crate::planner::TypedParam;
+use crate::static_schema::StaticSchema;
+use chrono::{DateTime, Utc};
+use cubenativeutils::{CubeError, CubeErrorCauseType};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+const JSONRPC_VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+pub const UNSUPPORTED_VERSION: i64 = -32000;
+pub const USER_ERROR: i64 = -32001;
+
+const METHODS: [&str; 4] = ["batch", "introspect", "plan", "validate"];
+
+// Sql of a planned query. Metadata holds positions of params of filtered members
+// and measures the caller fills gaps of
+#[derive(Serialize, Debug)]
+pub struct PlanResult {
+    pub sql: String,
+    pub params: Vec<TypedParam>,
+    pub metadata: Value,
+}
+
+// Schema of `plan` requests as JSON documents of the host: cube and member definitions,
+// and sql templates of the dialect, see `static_schema` module. Queries are planned
+// by the planner itself
+pub trait SchemaRuntime {
+    fn schema(&self) -> Result<String, CubeError>;
+    fn templates(&self) -> Result<String, CubeError>;
+
+    // Current time of the host relative date ranges are resolved against,
+    // system time of the planner if `None`
+    fn now(&self) -> Result<Option<DateTime<Utc>>, CubeError> {
+        Ok(None)
+    }
+}
+
+// Runtime of hosts which don't provide a schema: their `plan` requests fail with a user error,
+// other methods are answered
+pub struct NoSchemaRuntime;
+
+impl NoSchemaRuntime {
+    fn error() -> CubeError {
+        CubeError::user(
+            "Planning requires a schema runtime, which isn't provided by the host".to_string(),
+        )
+    }
+}
+
+impl SchemaRuntime for NoSchemaRuntime {
+    fn schema(&self) -> Result<String, CubeError> {
+        Err(Self::error())
+    }
+
+    fn templates(&self) -> Result<String, CubeError> {
+        Err(Self::error())
+    }
+}
+
+pub fn plan(
+    query: BaseQueryOptionsStatic,
+    runtime: &dyn SchemaRuntime,
+) -> Result<PlanResult, CubeError> {
+    let schema = StaticSchema::try_new(&runtime.schema()?, &runtime.templates()?)?;
+    let result = schema
+        .query_builder(query, runtime.now()?)?
+        .build_sql_and_params()?;
+    Ok(PlanResult {
+        sql: result.sql,
+        params: result.params,
+        metadata: serde_json::json!({
+            "memberParams": result.member_params,
+            "fillPostProcessing": result.fill_post_processing,
+        }),
+    })
+}
+
+#[derive(Deserialize)]
+struct QueryParams {
+    #[serde(deserialize_with = "deserialize_query")]
+    query: BaseQueryOptionsStatic,
+}
+
+// Hosts send limits as JSON numbers, while the planner keeps them as strings the way
+// the Node.js bridge passes them
+fn deserialize_query<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BaseQueryOptionsStatic, D::Error> {
+    let mut query = Value::deserialize(deserializer)?;
+    if let Some(query) = query.as_object_mut() {
+        for key in ["limit", "rowLimit", "offset"] {
+            if let Some(value) = query.get_mut(key) {
+                if value.is_number() {
+                    *value = Value::String(value.to_string());
+                }
+            }
+        }
+    }
+    serde_json::from_value(query).map_err(D::Error::custom)
+}
+
+#[derive(Deserialize)]
+struct BatchParams {
+    requests: Vec<Value>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: String) -> Self {
+        Self { code, message }
+    }
+}
+
+impl From<CubeError> for RpcError {
+    fn from(e: CubeError) -> Self {
+        match e.cause {
+            CubeErrorCauseType::User(_) => Self::new(USER_ERROR, e.message),
+            CubeErrorCauseType::Internal(_) => Self::new(INTERNAL_ERROR, e.message),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    pub version: u32,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            version: PROTOCOL_VERSION,
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+pub fn handle_request(request: &str, runtime: &dyn SchemaRuntime) -> String {
+    let response = match serde_json::from_str::<Value>(request) {
+        Ok(request) => handle_value(request, runtime, true),
+        Err(e) => RpcResponse::new(
+            Value::Null,
+            Err(RpcError::new(PARSE_ERROR, format!("Parse error: {}", e))),
+        ),
+    };
+    serialize_response(&response)
+}
+
+fn serialize_response(response: &RpcResponse) -> String {
+    serde_json::to_string(response).unwrap_or_else(|e| {
+        format!(
+            "{{\"jsonrpc\":\"{}\",\"version\":{},\"id\":null,\"error\":{{\"code\":{},\"message\":{:?}}}}}",
+            JSONRPC_VERSION, PROTOCOL_VERSION, INTERNAL_ERROR, e.to_string()
+        )
+    })
+}
+
+// Call of a single method with the query as its params, for hosts which pass queries
+// without JSON-RPC envelopes
+pub fn handle_query(method: &str, query: &str, runtime: &dyn SchemaRuntime) -> String {
+    let response = match serde_json::from_str::<Value>(query) {
+        Ok(query) => {
+            let request = serde_json::json!({
+                "jsonrpc": JSONRPC_VERSION,
+                "id": Value::Null,
+                "method": method,
+                "params": { "query": query },
+            });
+            handle_value(request, runtime, false)
+        }
+        Err(e) => RpcResponse::new(
+            Value::Null,
+            Err(RpcError::new(PARSE_ERROR, format!("Parse error: {}", e))),
+        ),
+    };
+    serialize_response(&response)
+}
+
+fn handle_value(request: Value, runtime: &dyn SchemaRuntime, allow_batch: bool) -> RpcResponse {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let result = dispatch(&request, runtime, allow_batch);
+    RpcResponse::new(id, result)
+}
+
+fn dispatch(
+    request: &Value,
+    runtime: &dyn SchemaRuntime,
+    allow_batch: bool,
+) -> Result<Value, RpcError> {
+    if request.get("jsonrpc").and_then(Value::as_str) != Some(JSONRPC_VERSION) {
+        return Err(RpcError::new(
+            INVALID_REQUEST,
+            format!("Invalid request: jsonrpc must be \"{}\"", JSONRPC_VERSION),
+        ));
+    }
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Err(RpcError::new(
+            INVALID_REQUEST,
+            "Invalid request: method must be a string".to_string(),
+        ));
+    };
+    if let Some(version) = request.get("version") {
+        match version.as_u64() {
+            Some(version) if version <= PROTOCOL_VERSION as u64 => {}
+            _ => {
+                return Err(RpcError::new(
+                    UNSUPPORTED_VERSION,
+                    format!(
+                        "Unsupported protocol version {}, the latest supported is {}",
+                        version, PROTOCOL_VERSION
+                    ),
+                ))
+            }
+        }
+    }
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    match method {
+        // Unlike the Node.js bridge, member sql of the schema is plain text, see `static_schema`
+        "plan" => {
+            let params = parse_params::<QueryParams>(params)?;
+            let result = plan(params.query, runtime)?;
+            to_value(&result)
+        }
+        "validate" => {
+            let params = parse_params::<QueryParams>(params)?;
+            let errors = validate_query(&params.query);
+            Ok(serde_json::json!({
+                "valid": errors.is_empty(),
+                "errors": errors,
+            }))
+        }
+        "introspect" => Ok(serde_json::json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "methods": METHODS,
+        })),
+        "batch" if allow_batch => {
+            let params = parse_params::<BatchParams>(params)?;
+            let responses = params
+                .requests
+                .into_iter()
+                .map(|request| handle_value(request, runtime, false))
+                .collect::<Vec<_>>();
+            to_value(&responses)
+        }
+        "batch" => Err(RpcError::new(
+            INVALID_PARAMS,
+            "Invalid params: batch can't be nested".to_string(),
+        )),
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))
+}
+
+// Checks of the query which don't need the schema
+fn validate_query(query: &BaseQueryOptionsStatic) -> Vec<String> {
+    let mut errors = Vec::new();
+    let members = query
+        .measures
+        .iter()
+        .flatten()
+        .chain(query.dimensions.iter().flatten())
+        .chain(
+            query
+                .time_dimensions
+                .iter()
+                .flatten()
+                .map(|td| &td.dimension),
+        );
+    for member in members {
+        validate_member_path(member, &mut errors);
+    }
+    for time_dimension in query.time_dimensions.iter().flatten() {
+        if let Some(date_range) = &time_dimension.date_range {
+            if date_range.len() != 2 {
+                errors.push(format!(
+                    "Date range of '{}' must have 2 dates, got {}",
+                    time_dimension.dimension,
+                    date_range.len()
+                ));
+            }
+        }
+    }
+    for filter in query.filters.iter().flatten() {
+        validate_filter(filter, &mut errors);
+    }
+    for (name, value) in [
+        ("limit", &query.limit),
+        ("rowLimit", &query.row_limit),
+        ("offset", &query.offset),
+    ] {
+        if let Some(value) = value {
+            if value.parse::<usize>().is_err() {
+                errors.push(format!(
+                    "{} must be a non-negative integer, got '{}'",
+                    name, value
+                ));
+            }
+        }
+    }
+    errors
+}
+
+fn validate_member_path(member: &str, errors: &mut Vec<String>) {
+    let parts = member.split('.').collect::<Vec<_>>();
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|p| p.is_empty()) {
+        errors.push(format!(
+            "'{}' isn't a member path, expected 'cube.member'",
+            member
+        ));
+    }
+}
+
+fn validate_filter(filter: &FilterItem, errors: &mut Vec<String>) {
+    if filter.or.is_some() || filter.and.is_some() {
+        for item in filter.or.iter().chain(filter.and.iter()).flatten() {
+            validate_filter(item, errors);
+        }
+        return;
+    }
+    match filter.member() {
+        Some(member) => validate_member_path(member, errors),
+        None => errors.push("Filter must have a member or be an and/or group".to_string()),
+    }
+    if filter.operator.is_none() {
+        errors.push(format!(
+            "Filter on '{}' must have an operator",
+            filter.member().cloned().unwrap_or_default()
+        ));
+    }
+}
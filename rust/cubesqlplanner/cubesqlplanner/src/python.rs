@@ -0,0 +1,73 @@
+// `cubesqlplanner` Python module, e.g. to generate SQL of a query in a notebook
+// or in unit tests of schema changes without running Cube.
+// Queries are planned by the planner against the schema runtime of the caller: `schema` and
+// `templates` callables return JSON of the cubes and of the sql templates, see `static_schema`
+use crate::protocol::{self, NoSchemaRuntime, SchemaRuntime, PROTOCOL_VERSION};
+use cubenativeutils::CubeError;
+use pyo3::prelude::*;
+
+struct PySchemaRuntime<'py> {
+    schema: &'py PyAny,
+    templates: &'py PyAny,
+}
+
+impl PySchemaRuntime<'_> {
+    fn call(callable: &PyAny, name: &str) -> Result<String, CubeError> {
+        callable
+            .call0()
+            .and_then(|value| value.extract::<String>())
+            .map_err(|e| {
+                CubeError::user(format!("Schema runtime failed to return {}: {}", name, e))
+            })
+    }
+}
+
+impl SchemaRuntime for PySchemaRuntime<'_> {
+    fn schema(&self) -> Result<String, CubeError> {
+        Self::call(self.schema, "schema")
+    }
+
+    fn templates(&self) -> Result<String, CubeError> {
+        Self::call(self.templates, "sql templates")
+    }
+}
+
+#[pyfunction]
+fn protocol_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+// Takes a JSON-RPC request of the planner protocol and returns its response, see `protocol` module.
+// `plan` requests fail unless `schema` and `templates` callables are passed
+#[pyfunction]
+#[pyo3(signature = (request, schema = None, templates = None))]
+fn call(request: &str, schema: Option<&PyAny>, templates: Option<&PyAny>) -> String {
+    match (schema, templates) {
+        (Some(schema), Some(templates)) => {
+            protocol::handle_request(request, &PySchemaRuntime { schema, templates })
+        }
+        _ => protocol::handle_request(request, &NoSchemaRuntime),
+    }
+}
+
+// Plans JSON of the query against the schema and sql templates returned by the callables
+// and returns the `plan` response
+#[pyfunction]
+fn plan(query: &str, schema: &PyAny, templates: &PyAny) -> String {
+    protocol::handle_query("plan", query, &PySchemaRuntime { schema, templates })
+}
+
+// Validates JSON of the query without the schema and returns the `validate` response
+#[pyfunction]
+fn validate(query: &str) -> String {
+    protocol::handle_query("validate", query, &NoSchemaRuntime)
+}
+
+#[pymodule]
+fn cubesqlplanner(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(protocol_version, m)?)?;
+    m.add_function(wrap_pyfunction!(call, m)?)?;
+    m.add_function(wrap_pyfunction!(plan, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    Ok(())
+}
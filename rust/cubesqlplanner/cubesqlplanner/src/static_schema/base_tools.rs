@@ -0,0 +1,133 @@
+use super::evaluator::StaticCubeEvaluator;
+use crate::cube_bridge::base_tools::{BaseTools, CallDep};
+use crate::cube_bridge::filter_group::FilterGroup;
+use crate::cube_bridge::filter_params::FilterParams;
+use crate::cube_bridge::memeber_sql::MemberSql;
+use crate::cube_bridge::security_context::SecurityContext;
+use crate::cube_bridge::sql_templates_render::{SqlTemplatesRender, StaticSqlTemplatesRender};
+use crate::planner::{DateTimeTools, StaticDateTimeTools};
+use cubenativeutils::CubeError;
+use minijinja::context;
+use std::any::Any;
+use std::rc::Rc;
+
+fn unsupported(feature: &str) -> CubeError {
+    CubeError::user(format!("{} isn't supported by static schemas", feature))
+}
+
+// Dialect of a static schema: sql comes from the templates of the host, timestamps are
+// handled by `StaticDateTimeTools`
+pub struct StaticBaseTools {
+    evaluator: Rc<StaticCubeEvaluator>,
+    templates: Rc<StaticSqlTemplatesRender>,
+    date_time_tools: Rc<StaticDateTimeTools>,
+    timezone: Option<String>,
+}
+
+impl StaticBaseTools {
+    pub fn new(
+        evaluator: Rc<StaticCubeEvaluator>,
+        templates: Rc<StaticSqlTemplatesRender>,
+        date_time_tools: Rc<StaticDateTimeTools>,
+        timezone: Option<String>,
+    ) -> Self {
+        Self {
+            evaluator,
+            templates,
+            date_time_tools,
+            timezone,
+        }
+    }
+
+    fn render_required(&self, name: &str, ctx: minijinja::Value) -> Result<String, CubeError> {
+        if !self.templates.contains_template(name) {
+            return Err(CubeError::user(format!(
+                "{} template is required by static schemas",
+                name
+            )));
+        }
+        self.templates.render_template(name, ctx)
+    }
+}
+
+impl BaseTools for StaticBaseTools {
+    fn convert_tz(&self, field: String) -> Result<String, CubeError> {
+        match self.timezone.as_deref() {
+            None | Some("UTC") | Some("Etc/UTC") => Ok(field),
+            Some(timezone) => self.render_required(
+                "expressions/convert_tz",
+                context! { expr => field, timezone => timezone },
+            ),
+        }
+    }
+
+    fn time_grouped_column(
+        &self,
+        granularity: String,
+        dimension: String,
+    ) -> Result<String, CubeError> {
+        let args = vec![format!("'{}'", granularity), dimension];
+        self.render_required(
+            "functions/DATETRUNC",
+            context! {
+                args_concat => args.join(", "),
+                args => args,
+                date_part => granularity,
+            },
+        )
+    }
+
+    fn sql_templates(&self) -> Result<Rc<dyn SqlTemplatesRender>, CubeError> {
+        Ok(self.templates.clone())
+    }
+
+    fn resolve_symbols_call_deps(
+        &self,
+        _cube_name: String,
+        _sql: Rc<dyn MemberSql>,
+    ) -> Result<Vec<CallDep>, CubeError> {
+        Err(CubeError::internal(
+            "Call deps of static schemas are resolved by the cube evaluator".to_string(),
+        ))
+    }
+
+    fn security_context_for_rust(&self) -> Result<Rc<dyn SecurityContext>, CubeError> {
+        Err(unsupported("SECURITY_CONTEXT"))
+    }
+
+    fn filters_proxy_for_rust(&self) -> Result<Rc<dyn FilterParams>, CubeError> {
+        Err(unsupported("FILTER_PARAMS"))
+    }
+
+    fn filter_group_function_for_rust(&self) -> Result<Rc<dyn FilterGroup>, CubeError> {
+        Err(unsupported("FILTER_GROUP"))
+    }
+
+    fn timestamp_precision(&self) -> Result<u32, CubeError> {
+        self.date_time_tools.timestamp_precision()
+    }
+
+    fn in_db_time_zone(&self, date: String) -> Result<String, CubeError> {
+        self.date_time_tools.in_db_time_zone(date)
+    }
+
+    fn generate_time_series(
+        &self,
+        _granularity: String,
+        _date_range: Vec<String>,
+    ) -> Result<Vec<Vec<String>>, CubeError> {
+        Err(unsupported("Time series of rolling windows"))
+    }
+
+    fn get_allocated_params(&self) -> Result<Vec<String>, CubeError> {
+        Ok(vec![])
+    }
+
+    fn all_cube_members(&self, path: String) -> Result<Vec<String>, CubeError> {
+        Ok(self.evaluator.cube(&path)?.members())
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
@@ -0,0 +1,224 @@
+use super::member_sql::StaticMemberSql;
+use crate::cube_bridge::cube_definition::{CubeDefinition, CubeDefinitionStatic};
+use crate::cube_bridge::dimension_definition::{DimensionDefinition, DimenstionDefinitionStatic};
+use crate::cube_bridge::join_item_definition::{JoinItemDefinition, JoinItemDefinitionStatic};
+use crate::cube_bridge::measure_definition::{MeasureDefinition, MeasureDefinitionStatic};
+use crate::cube_bridge::measure_filter::{MeasureFilter, MeasureFiltersVec};
+use crate::cube_bridge::member_order_by::MemberOrderByVec;
+use crate::cube_bridge::memeber_sql::MemberSql;
+use crate::cube_bridge::refresh_key::RefreshKey;
+use crate::cube_bridge::segment_definition::{SegmentDefinition, SegmentDefinitionStatic};
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::rc::Rc;
+
+fn member_sql(sql: &Option<Rc<StaticMemberSql>>) -> Option<Rc<dyn MemberSql>> {
+    sql.clone().map(|sql| sql as Rc<dyn MemberSql>)
+}
+
+pub struct StaticCubeDefinition {
+    pub static_data: CubeDefinitionStatic,
+    pub sql_table: Option<Rc<StaticMemberSql>>,
+    pub sql: Option<Rc<StaticMemberSql>>,
+}
+
+impl CubeDefinition for StaticCubeDefinition {
+    fn sql_table(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(member_sql(&self.sql_table))
+    }
+
+    fn has_sql_table(&self) -> Result<bool, CubeError> {
+        Ok(self.sql_table.is_some())
+    }
+
+    fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(member_sql(&self.sql))
+    }
+
+    fn has_sql(&self) -> Result<bool, CubeError> {
+        Ok(self.sql.is_some())
+    }
+
+    // Refresh keys are only used by the refresh scheduler, which static hosts don't run
+    fn refresh_key(&self) -> Result<Option<Rc<dyn RefreshKey>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_refresh_key(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &CubeDefinitionStatic {
+        &self.static_data
+    }
+}
+
+pub struct StaticMeasureFilter {
+    pub sql: Rc<StaticMemberSql>,
+}
+
+impl MeasureFilter for StaticMeasureFilter {
+    fn sql(&self) -> Result<Rc<dyn MemberSql>, CubeError> {
+        Ok(self.sql.clone())
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+pub struct StaticMeasureFiltersVec {
+    pub items: Vec<Rc<dyn MeasureFilter>>,
+}
+
+impl MeasureFiltersVec for StaticMeasureFiltersVec {
+    fn items(&self) -> &Vec<Rc<dyn MeasureFilter>> {
+        &self.items
+    }
+}
+
+pub struct StaticMeasureDefinition {
+    pub static_data: MeasureDefinitionStatic,
+    pub sql: Option<Rc<StaticMemberSql>>,
+    pub denominator_sql: Option<Rc<StaticMemberSql>>,
+    pub filters: Option<Rc<StaticMeasureFiltersVec>>,
+    pub cube: Rc<StaticCubeDefinition>,
+}
+
+impl MeasureDefinition for StaticMeasureDefinition {
+    fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(member_sql(&self.sql))
+    }
+
+    fn has_sql(&self) -> Result<bool, CubeError> {
+        Ok(self.sql.is_some())
+    }
+
+    fn denominator_sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(member_sql(&self.denominator_sql))
+    }
+
+    fn has_denominator_sql(&self) -> Result<bool, CubeError> {
+        Ok(self.denominator_sql.is_some())
+    }
+
+    fn cube(&self) -> Result<Rc<dyn CubeDefinition>, CubeError> {
+        Ok(self.cube.clone())
+    }
+
+    fn filters(&self) -> Result<Option<Rc<dyn MeasureFiltersVec>>, CubeError> {
+        Ok(self
+            .filters
+            .clone()
+            .map(|filters| filters as Rc<dyn MeasureFiltersVec>))
+    }
+
+    fn has_filters(&self) -> Result<bool, CubeError> {
+        Ok(self.filters.is_some())
+    }
+
+    fn order_by(&self) -> Result<Option<Rc<dyn MemberOrderByVec>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_order_by(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &MeasureDefinitionStatic {
+        &self.static_data
+    }
+}
+
+pub struct StaticDimensionDefinition {
+    pub static_data: DimenstionDefinitionStatic,
+    pub sql: Option<Rc<StaticMemberSql>>,
+}
+
+impl DimensionDefinition for StaticDimensionDefinition {
+    fn sql(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(member_sql(&self.sql))
+    }
+
+    fn has_sql(&self) -> Result<bool, CubeError> {
+        Ok(self.sql.is_some())
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &DimenstionDefinitionStatic {
+        &self.static_data
+    }
+}
+
+pub struct StaticSegmentDefinition {
+    pub static_data: SegmentDefinitionStatic,
+    pub sql: Rc<StaticMemberSql>,
+}
+
+impl SegmentDefinition for StaticSegmentDefinition {
+    fn sql(&self) -> Result<Rc<dyn MemberSql>, CubeError> {
+        Ok(self.sql.clone())
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &SegmentDefinitionStatic {
+        &self.static_data
+    }
+}
+
+pub struct StaticJoinItemDefinition {
+    pub static_data: JoinItemDefinitionStatic,
+    pub sql: Rc<StaticMemberSql>,
+}
+
+impl JoinItemDefinition for StaticJoinItemDefinition {
+    fn sql(&self) -> Result<Rc<dyn MemberSql>, CubeError> {
+        Ok(self.sql.clone())
+    }
+
+    fn valid_at(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_valid_at(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_of_time(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_as_of_time(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_of_match_time(&self) -> Result<Option<Rc<dyn MemberSql>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_as_of_match_time(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &JoinItemDefinitionStatic {
+        &self.static_data
+    }
+}
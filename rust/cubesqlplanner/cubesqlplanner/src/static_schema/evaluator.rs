@@ -0,0 +1,379 @@
+use super::definitions::{
+    StaticCubeDefinition, StaticDimensionDefinition, StaticJoinItemDefinition,
+    StaticMeasureDefinition, StaticMeasureFilter, StaticMeasureFiltersVec, StaticSegmentDefinition,
+};
+use super::member_sql::StaticMemberSql;
+use super::{CubeSchema, SchemaDefinition};
+use crate::cube_bridge::cube_definition::CubeDefinition;
+use crate::cube_bridge::dimension_definition::DimensionDefinition;
+use crate::cube_bridge::evaluator::{CallDep, CubeEvaluator, CubeEvaluatorStatic};
+use crate::cube_bridge::join_item_definition::JoinItemDefinitionStatic;
+use crate::cube_bridge::measure_definition::MeasureDefinition;
+use crate::cube_bridge::measure_filter::MeasureFilter;
+use crate::cube_bridge::memeber_sql::MemberSql;
+use crate::cube_bridge::segment_definition::SegmentDefinition;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+pub struct StaticCube {
+    pub definition: Rc<StaticCubeDefinition>,
+    pub measures: BTreeMap<String, Rc<StaticMeasureDefinition>>,
+    pub dimensions: BTreeMap<String, Rc<StaticDimensionDefinition>>,
+    pub segments: BTreeMap<String, Rc<StaticSegmentDefinition>>,
+    // Joined cube to the join definition
+    pub joins: BTreeMap<String, Rc<StaticJoinItemDefinition>>,
+}
+
+impl StaticCube {
+    pub fn has_member(&self, name: &str) -> bool {
+        self.measures.contains_key(name)
+            || self.dimensions.contains_key(name)
+            || self.segments.contains_key(name)
+    }
+
+    pub fn members(&self) -> Vec<String> {
+        self.measures
+            .keys()
+            .chain(self.dimensions.keys())
+            .chain(self.segments.keys())
+            .cloned()
+            .collect()
+    }
+}
+
+// Cube evaluator of a static schema. Member sql references are resolved the way the JS
+// data model resolves arguments of member sql functions: `CUBE` and `TABLE` are the cube
+// of the member, then members of the cube take precedence over other cubes
+pub struct StaticCubeEvaluator {
+    static_data: CubeEvaluatorStatic,
+    cubes: BTreeMap<String, StaticCube>,
+}
+
+impl StaticCubeEvaluator {
+    pub fn try_new(schema: SchemaDefinition) -> Result<Self, CubeError> {
+        let cube_names = schema
+            .cubes
+            .iter()
+            .map(|cube| cube.definition.name.clone())
+            .collect::<Vec<_>>();
+        let mut cubes = BTreeMap::new();
+        let mut primary_keys = HashMap::new();
+        for cube in schema.cubes {
+            let name = cube.definition.name.clone();
+            if cubes.contains_key(&name) {
+                return Err(CubeError::user(format!(
+                    "Cube '{}' is defined more than once",
+                    name
+                )));
+            }
+            if let Some(joined) = cube.joins.keys().find(|c| !cube_names.contains(c)) {
+                return Err(CubeError::user(format!(
+                    "Cube '{}' joins cube '{}' which doesn't exist",
+                    name, joined
+                )));
+            }
+            let keys = cube
+                .dimensions
+                .iter()
+                .filter(|(_, dimension)| dimension.primary_key.unwrap_or(false))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+            if keys.is_empty() {
+                if let Some((measure, _)) = cube.measures.iter().find(|(_, m)| m.sql.is_none()) {
+                    return Err(CubeError::user(format!(
+                        "Measure '{}.{}' without sql requires a primary key of cube '{}'",
+                        name, measure, name
+                    )));
+                }
+            } else {
+                primary_keys.insert(name.clone(), keys);
+            }
+            cubes.insert(name, Self::build_cube(cube)?);
+        }
+        Ok(Self {
+            static_data: CubeEvaluatorStatic { primary_keys },
+            cubes,
+        })
+    }
+
+    fn build_cube(cube: CubeSchema) -> Result<StaticCube, CubeError> {
+        let definition = Rc::new(StaticCubeDefinition {
+            sql_table: cube.sql_table.as_deref().map(StaticMemberSql::new),
+            sql: cube.sql.as_deref().map(StaticMemberSql::new),
+            static_data: cube.definition,
+        });
+        let measures = cube
+            .measures
+            .into_iter()
+            .map(|(name, measure)| {
+                let filters = measure.filters.map(|filters| {
+                    Rc::new(StaticMeasureFiltersVec {
+                        items: filters
+                            .into_iter()
+                            .map(|filter| -> Rc<dyn MeasureFilter> {
+                                Rc::new(StaticMeasureFilter {
+                                    sql: StaticMemberSql::new(&filter.sql),
+                                })
+                            })
+                            .collect(),
+                    })
+                });
+                let definition = StaticMeasureDefinition {
+                    sql: measure.sql.as_deref().map(StaticMemberSql::new),
+                    denominator_sql: measure.denominator_sql.as_deref().map(StaticMemberSql::new),
+                    filters,
+                    cube: definition.clone(),
+                    static_data: measure.definition,
+                };
+                (name, Rc::new(definition))
+            })
+            .collect();
+        let dimensions = cube
+            .dimensions
+            .into_iter()
+            .map(|(name, dimension)| {
+                let definition = StaticDimensionDefinition {
+                    sql: dimension.sql.as_deref().map(StaticMemberSql::new),
+                    static_data: dimension.definition,
+                };
+                (name, Rc::new(definition))
+            })
+            .collect();
+        let segments = cube
+            .segments
+            .into_iter()
+            .map(|(name, segment)| {
+                let definition = StaticSegmentDefinition {
+                    sql: StaticMemberSql::new(&segment.sql),
+                    static_data: segment.definition,
+                };
+                (name, Rc::new(definition))
+            })
+            .collect();
+        let joins = cube
+            .joins
+            .into_iter()
+            .map(|(name, join)| {
+                let definition = StaticJoinItemDefinition {
+                    static_data: JoinItemDefinitionStatic {
+                        relationship: Self::relationship(&join.relationship)?,
+                    },
+                    sql: StaticMemberSql::new(&join.sql),
+                };
+                Ok((name, Rc::new(definition)))
+            })
+            .collect::<Result<_, CubeError>>()?;
+        Ok(StaticCube {
+            definition,
+            measures,
+            dimensions,
+            segments,
+            joins,
+        })
+    }
+
+    // Relationships are normalized to the names the JS join graph uses
+    fn relationship(relationship: &str) -> Result<String, CubeError> {
+        let result = match relationship {
+            "belongsTo" | "belongs_to" | "many_to_one" | "manyToOne" => "belongsTo",
+            "hasMany" | "has_many" | "one_to_many" | "oneToMany" => "hasMany",
+            "hasOne" | "has_one" | "one_to_one" | "oneToOne" => "hasOne",
+            _ => {
+                return Err(CubeError::user(format!(
+                    "Unknown join relationship '{}'",
+                    relationship
+                )))
+            }
+        };
+        Ok(result.to_string())
+    }
+
+    pub fn cubes(&self) -> &BTreeMap<String, StaticCube> {
+        &self.cubes
+    }
+
+    pub fn cube(&self, name: &str) -> Result<&StaticCube, CubeError> {
+        self.cubes
+            .get(name)
+            .ok_or_else(|| CubeError::user(format!("Cube '{}' not found", name)))
+    }
+
+    fn member_cube<'a>(&'a self, path: &'a str) -> Result<(&'a StaticCube, &'a str), CubeError> {
+        let Some((cube_name, member)) = path.split_once('.') else {
+            return Err(CubeError::user(format!(
+                "'{}' isn't a member path, expected 'cube.member'",
+                path
+            )));
+        };
+        let cube = self.cubes.get(cube_name).ok_or_else(|| {
+            CubeError::user(format!(
+                "Cube '{}' not found for path '{}'",
+                cube_name, path
+            ))
+        })?;
+        Ok((cube, member))
+    }
+
+    fn not_found(path: &str, member: &str) -> CubeError {
+        CubeError::user(format!("'{}' not found for path '{}'", member, path))
+    }
+
+    fn is_cube_reference(&self, cube: &StaticCube, name: &str) -> bool {
+        name == "CUBE"
+            || name == "TABLE"
+            || (!cube.has_member(name) && self.cubes.contains_key(name))
+    }
+
+    // References are checked here, so errors name the member sql instead of
+    // an unresolved dependency of the compiler
+    fn check_reference(
+        &self,
+        cube_name: &str,
+        cube: &StaticCube,
+        path: &[String],
+    ) -> Result<(), CubeError> {
+        let name = &path[0];
+        let referenced = if self.is_cube_reference(cube, name) {
+            let referenced_cube = if name == "CUBE" || name == "TABLE" {
+                cube
+            } else {
+                self.cube(name)?
+            };
+            match &path[1..] {
+                [] => true,
+                [member] => referenced_cube.has_member(member),
+                _ => false,
+            }
+        } else {
+            path.len() == 1 && cube.has_member(name)
+        };
+        if referenced {
+            Ok(())
+        } else {
+            Err(CubeError::user(format!(
+                "Member sql of cube '{}' refers to '{}', which is neither a cube nor a member",
+                cube_name,
+                path.join(".")
+            )))
+        }
+    }
+}
+
+impl CubeEvaluator for StaticCubeEvaluator {
+    fn primary_keys(&self) -> Result<HashMap<String, String>, CubeError> {
+        Ok(self
+            .static_data
+            .primary_keys
+            .iter()
+            .filter_map(|(cube, keys)| Some((cube.clone(), keys.first()?.clone())))
+            .collect())
+    }
+
+    fn parse_path(&self, path_type: String, path: String) -> Result<Vec<String>, CubeError> {
+        let (cube, member) = self.member_cube(&path)?;
+        let exists = match path_type.as_str() {
+            "measures" => cube.measures.contains_key(member),
+            "dimensions" => cube.dimensions.contains_key(member),
+            "segments" => cube.segments.contains_key(member),
+            _ => {
+                return Err(CubeError::internal(format!(
+                    "Unknown path type '{}'",
+                    path_type
+                )))
+            }
+        };
+        if !exists {
+            return Err(Self::not_found(&path, member));
+        }
+        Ok(vec![
+            cube.definition.static_data.name.clone(),
+            member.to_string(),
+        ])
+    }
+
+    fn measure_by_path(
+        &self,
+        measure_path: String,
+    ) -> Result<Rc<dyn MeasureDefinition>, CubeError> {
+        let (cube, member) = self.member_cube(&measure_path)?;
+        match cube.measures.get(member) {
+            Some(measure) => Ok(measure.clone()),
+            None => Err(Self::not_found(&measure_path, member)),
+        }
+    }
+
+    fn dimension_by_path(
+        &self,
+        dimension_path: String,
+    ) -> Result<Rc<dyn DimensionDefinition>, CubeError> {
+        let (cube, member) = self.member_cube(&dimension_path)?;
+        match cube.dimensions.get(member) {
+            Some(dimension) => Ok(dimension.clone()),
+            None => Err(Self::not_found(&dimension_path, member)),
+        }
+    }
+
+    fn segment_by_path(
+        &self,
+        segment_path: String,
+    ) -> Result<Rc<dyn SegmentDefinition>, CubeError> {
+        let (cube, member) = self.member_cube(&segment_path)?;
+        match cube.segments.get(member) {
+            Some(segment) => Ok(segment.clone()),
+            None => Err(Self::not_found(&segment_path, member)),
+        }
+    }
+
+    fn cube_from_path(&self, cube_path: String) -> Result<Rc<dyn CubeDefinition>, CubeError> {
+        Ok(self.cube(&cube_path)?.definition.clone())
+    }
+
+    fn is_measure(&self, path: Vec<String>) -> Result<bool, CubeError> {
+        Ok(match path.as_slice() {
+            [cube, member] => self
+                .cubes
+                .get(cube)
+                .map_or(false, |cube| cube.measures.contains_key(member)),
+            _ => false,
+        })
+    }
+
+    fn is_dimension(&self, path: Vec<String>) -> Result<bool, CubeError> {
+        Ok(match path.as_slice() {
+            [cube, member] => self
+                .cubes
+                .get(cube)
+                .map_or(false, |cube| cube.dimensions.contains_key(member)),
+            _ => false,
+        })
+    }
+
+    fn cube_exists(&self, name: String) -> Result<bool, CubeError> {
+        Ok(self.cubes.contains_key(&name))
+    }
+
+    fn resolve_symbols_call_deps(
+        &self,
+        cube_name: String,
+        sql: Rc<dyn MemberSql>,
+    ) -> Result<Vec<CallDep>, CubeError> {
+        let sql = sql.as_any().downcast::<StaticMemberSql>().map_err(|_| {
+            CubeError::internal("Member sql of a static schema is expected".to_string())
+        })?;
+        let cube = self.cube(&cube_name)?;
+        for path in sql.references() {
+            self.check_reference(&cube_name, cube, path)?;
+        }
+        Ok(sql.call_deps(|name| self.is_cube_reference(cube, name)))
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &CubeEvaluatorStatic {
+        &self.static_data
+    }
+}
@@ -0,0 +1,218 @@
+use super::definitions::StaticJoinItemDefinition;
+use super::evaluator::StaticCubeEvaluator;
+use crate::cube_bridge::join_definition::{JoinDefinition, JoinDefinitionStatic};
+use crate::cube_bridge::join_graph::JoinGraph;
+use crate::cube_bridge::join_item::{JoinItem, JoinItemStatic, JoinItemsVec};
+use crate::cube_bridge::join_item_definition::JoinItemDefinition;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+pub struct StaticJoinItem {
+    static_data: JoinItemStatic,
+    join: Rc<StaticJoinItemDefinition>,
+}
+
+impl JoinItem for StaticJoinItem {
+    fn join(&self) -> Result<Rc<dyn JoinItemDefinition>, CubeError> {
+        Ok(self.join.clone())
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &JoinItemStatic {
+        &self.static_data
+    }
+}
+
+pub struct StaticJoinItemsVec {
+    items: Vec<Rc<dyn JoinItem>>,
+}
+
+impl JoinItemsVec for StaticJoinItemsVec {
+    fn items(&self) -> &Vec<Rc<dyn JoinItem>> {
+        &self.items
+    }
+}
+
+pub struct StaticJoinDefinition {
+    static_data: JoinDefinitionStatic,
+    joins: Rc<StaticJoinItemsVec>,
+}
+
+impl JoinDefinition for StaticJoinDefinition {
+    fn joins(&self) -> Result<Rc<dyn JoinItemsVec>, CubeError> {
+        Ok(self.joins.clone())
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &JoinDefinitionStatic {
+        &self.static_data
+    }
+}
+
+// Port of `JoinGraph.buildJoin` of the JS data model: joins are directed edges, every cube
+// to join is tried as the root and the tree with the fewest joins wins. Joined cubes are
+// visited in name order, so ties are broken the same way on every host
+pub struct StaticJoinGraph {
+    evaluator: Rc<StaticCubeEvaluator>,
+}
+
+impl StaticJoinGraph {
+    pub fn new(evaluator: Rc<StaticCubeEvaluator>) -> Self {
+        Self { evaluator }
+    }
+
+    // Shortest path of joins from `from` to `to`
+    fn path(&self, from: &str, to: &str) -> Option<Vec<Rc<StaticJoinItem>>> {
+        let cubes = self.evaluator.cubes();
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(cube) = queue.pop_front() {
+            if cube == to {
+                break;
+            }
+            for joined in cubes.get(cube).into_iter().flat_map(|c| c.joins.keys()) {
+                if joined != from && !previous.contains_key(joined.as_str()) {
+                    previous.insert(joined, cube);
+                    queue.push_back(joined);
+                }
+            }
+        }
+        let mut result = Vec::new();
+        let mut cube = to;
+        while cube != from {
+            let parent = *previous.get(cube)?;
+            result.push(Rc::new(StaticJoinItem {
+                static_data: JoinItemStatic {
+                    from: parent.to_string(),
+                    to: cube.to_string(),
+                    original_from: parent.to_string(),
+                    original_to: cube.to_string(),
+                },
+                join: cubes[parent].joins[cube].clone(),
+            }));
+            cube = parent;
+        }
+        result.reverse();
+        Some(result)
+    }
+
+    fn build_join_tree_for_root(
+        &self,
+        root: &str,
+        cubes_to_join: &[String],
+    ) -> Option<Vec<Rc<StaticJoinItem>>> {
+        let mut result: Vec<Rc<StaticJoinItem>> = Vec::new();
+        let mut joined = HashSet::new();
+        for cube in cubes_to_join.iter().filter(|c| *c != root) {
+            if !joined.insert(cube.clone()) {
+                continue;
+            }
+            for item in self.path(root, cube)? {
+                if !result.iter().any(|j| {
+                    j.static_data.from == item.static_data.from
+                        && j.static_data.to == item.static_data.to
+                }) {
+                    result.push(item);
+                }
+            }
+        }
+        Some(result)
+    }
+
+    fn is_multiplied(&self, cube: &str, joins: &[Rc<StaticJoinItem>]) -> bool {
+        let mut visited = HashSet::new();
+        self.is_multiplied_recursive(cube, joins, &mut visited)
+    }
+
+    fn is_multiplied_recursive(
+        &self,
+        cube: &str,
+        joins: &[Rc<StaticJoinItem>],
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        if !visited.insert(cube.to_string()) {
+            return false;
+        }
+        let next_joins = joins
+            .iter()
+            .filter(|j| j.static_data.from == cube || j.static_data.to == cube)
+            .collect::<Vec<_>>();
+        let next_cube = |join: &Rc<StaticJoinItem>| {
+            if join.static_data.from == cube {
+                join.static_data.to.clone()
+            } else {
+                join.static_data.from.clone()
+            }
+        };
+        if next_joins
+            .iter()
+            .any(|j| Self::multiplies(cube, j) && !visited.contains(&next_cube(j)))
+        {
+            return true;
+        }
+        next_joins
+            .iter()
+            .any(|j| self.is_multiplied_recursive(&next_cube(j), joins, visited))
+    }
+
+    fn multiplies(cube: &str, join: &StaticJoinItem) -> bool {
+        let relationship = &join.join.static_data.relationship;
+        (join.static_data.from == cube && relationship == "hasMany")
+            || (join.static_data.to == cube && relationship == "belongsTo")
+    }
+}
+
+impl JoinGraph for StaticJoinGraph {
+    fn build_join(&self, cubes_to_join: Vec<String>) -> Result<Rc<dyn JoinDefinition>, CubeError> {
+        if cubes_to_join.is_empty() {
+            return Err(CubeError::internal(
+                "Join of no cubes can't be built".to_string(),
+            ));
+        }
+        for cube in cubes_to_join.iter() {
+            self.evaluator.cube(cube)?;
+        }
+        let (root, joins) = cubes_to_join
+            .iter()
+            .filter_map(|root| Some((root, self.build_join_tree_for_root(root, &cubes_to_join)?)))
+            .min_by_key(|(_, joins)| joins.len())
+            .ok_or_else(|| {
+                CubeError::user(format!(
+                    "Can't find join path to join {}",
+                    cubes_to_join
+                        .iter()
+                        .map(|c| format!("'{}'", c))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })?;
+        let multiplication_factor = cubes_to_join
+            .iter()
+            .map(|cube| (cube.clone(), self.is_multiplied(cube, &joins)))
+            .collect();
+        Ok(Rc::new(StaticJoinDefinition {
+            static_data: JoinDefinitionStatic {
+                root: root.clone(),
+                multiplication_factor,
+            },
+            joins: Rc::new(StaticJoinItemsVec {
+                items: joins
+                    .into_iter()
+                    .map(|join| join as Rc<dyn JoinItem>)
+                    .collect(),
+            }),
+        }))
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
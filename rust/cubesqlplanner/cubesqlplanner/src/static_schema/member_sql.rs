@@ -0,0 +1,194 @@
+use crate::cube_bridge::evaluator::CallDep;
+use crate::cube_bridge::memeber_sql::{MemberSql, MemberSqlArg};
+use cubenativeutils::CubeError;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::any::Any;
+use std::ops::Range;
+use std::rc::Rc;
+
+lazy_static! {
+    static ref REFERENCE_RE: Regex = Regex::new(r"\{([A-Za-z_]\w*(?:\.[A-Za-z_]\w*)*)\}").unwrap();
+}
+
+// Member sql of a static schema: sql text with `{member}`, `{cube.member}` and `{cube}`
+// references. Arguments are the distinct first parts of the references, in the order
+// they first appear, like arguments of member sql functions of the JS data model
+pub struct StaticMemberSql {
+    sql: String,
+    references: Vec<(Range<usize>, Vec<String>)>,
+    args_names: Vec<String>,
+}
+
+impl StaticMemberSql {
+    pub fn new(sql: &str) -> Rc<Self> {
+        let references = REFERENCE_RE
+            .captures_iter(sql)
+            .map(|captures| {
+                let path = captures[1].split('.').map(|p| p.to_string()).collect();
+                (captures.get(0).unwrap().range(), path)
+            })
+            .collect::<Vec<(Range<usize>, Vec<String>)>>();
+        let mut args_names: Vec<String> = Vec::new();
+        for (_, path) in references.iter() {
+            if !args_names.contains(&path[0]) {
+                args_names.push(path[0].clone());
+            }
+        }
+        Rc::new(Self {
+            sql: sql.to_string(),
+            references,
+            args_names,
+        })
+    }
+
+    // Paths of the references, e.g. `["users", "age"]` for `{users.age}`
+    pub fn references(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.references.iter().map(|(_, path)| path)
+    }
+
+    // Dependencies of the references: parents are listed before their children.
+    // References to cubes are rendered with their `toString`, i.e. the alias of the cube
+    pub fn call_deps(&self, is_cube: impl Fn(&str) -> bool) -> Vec<CallDep> {
+        let mut result: Vec<CallDep> = Vec::new();
+        for name in self.args_names.iter() {
+            result.push(CallDep {
+                name: name.clone(),
+                parent: None,
+            });
+            let root = result.len() - 1;
+            for (_, path) in self.references.iter().filter(|(_, p)| &p[0] == name) {
+                let mut parent = root;
+                let mut children = path[1..].to_vec();
+                if children.is_empty() && is_cube(name) {
+                    children.push("toString".to_string());
+                }
+                for child in children {
+                    parent = match result
+                        .iter()
+                        .position(|dep| dep.parent == Some(parent) && dep.name == child)
+                    {
+                        Some(existing) => existing,
+                        None => {
+                            result.push(CallDep {
+                                name: child,
+                                parent: Some(parent),
+                            });
+                            result.len() - 1
+                        }
+                    };
+                }
+            }
+        }
+        result
+    }
+
+    fn render_reference(path: &[String], arg: &MemberSqlArg) -> Result<String, CubeError> {
+        match (path.split_first(), arg) {
+            (None, MemberSqlArg::String(sql)) => Ok(sql.clone()),
+            (None, MemberSqlArg::Struct(cube)) => cube.to_string_fn.clone().ok_or_else(|| {
+                CubeError::internal("Cube reference has no alias to render".to_string())
+            }),
+            (Some((name, rest)), MemberSqlArg::Struct(cube)) => match cube.properties.get(name) {
+                Some(property) => Self::render_reference(rest, property),
+                None => Err(CubeError::internal(format!(
+                    "Reference to '{}' isn't resolved",
+                    name
+                ))),
+            },
+            (Some((name, _)), MemberSqlArg::String(_)) => Err(CubeError::user(format!(
+                "Can't refer to '{}' of a member",
+                name
+            ))),
+            (_, MemberSqlArg::ContextSymbol(_)) => Err(CubeError::user(
+                "Context symbols aren't supported by static schemas".to_string(),
+            )),
+        }
+    }
+}
+
+impl MemberSql for StaticMemberSql {
+    fn call(&self, args: Vec<MemberSqlArg>) -> Result<String, CubeError> {
+        if args.len() != self.args_names.len() {
+            return Err(CubeError::internal(format!(
+                "Invalid arguments count for MemberSql call: expected {}, got {}",
+                self.args_names.len(),
+                args.len()
+            )));
+        }
+        let mut result = String::with_capacity(self.sql.len());
+        let mut last = 0;
+        for (range, path) in self.references.iter() {
+            let arg_index = self.args_names.iter().position(|n| n == &path[0]).unwrap();
+            result.push_str(&self.sql[last..range.start]);
+            result.push_str(&Self::render_reference(&path[1..], &args[arg_index])?);
+            last = range.end;
+        }
+        result.push_str(&self.sql[last..]);
+        Ok(result)
+    }
+
+    fn args_names(&self) -> &Vec<String> {
+        &self.args_names
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::memeber_sql::MemberSqlStruct;
+    use std::collections::HashMap;
+
+    fn deps(sql: &str) -> Vec<(String, Option<usize>)> {
+        StaticMemberSql::new(sql)
+            .call_deps(|name| name == "CUBE" || name == "users")
+            .into_iter()
+            .map(|dep| (dep.name, dep.parent))
+            .collect()
+    }
+
+    #[test]
+    fn references_are_resolved_like_member_sql_functions() {
+        let sql = StaticMemberSql::new("{CUBE}.amount / NULLIF({count}, 0) + {users.age}");
+        assert_eq!(sql.args_names(), &vec!["CUBE", "count", "users"]);
+        assert_eq!(
+            deps("{CUBE}.amount / NULLIF({count}, 0) + {users.age} + {CUBE.id}"),
+            vec![
+                ("CUBE".to_string(), None),
+                ("toString".to_string(), Some(0)),
+                ("id".to_string(), Some(0)),
+                ("count".to_string(), None),
+                ("users".to_string(), None),
+                ("age".to_string(), Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_are_replaced_with_sql_of_arguments() {
+        let sql = StaticMemberSql::new("{CUBE}.amount > {users.age} AND '{x}' <> '{1}'");
+        let cube = MemberSqlArg::Struct(MemberSqlStruct {
+            sql_fn: None,
+            to_string_fn: Some("\"orders\"".to_string()),
+            properties: HashMap::new(),
+        });
+        let users = MemberSqlArg::Struct(MemberSqlStruct {
+            sql_fn: None,
+            to_string_fn: None,
+            properties: HashMap::from([(
+                "age".to_string(),
+                MemberSqlArg::String("\"users\".age".to_string()),
+            )]),
+        });
+        assert_eq!(
+            sql.call(vec![cube, users, MemberSqlArg::String("'a'".to_string())])
+                .unwrap(),
+            "\"orders\".amount > \"users\".age AND 'a' <> '{1}'"
+        );
+        assert!(sql.call(vec![]).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+// Schema of hosts without a JS data model, e.g. C ABI and Python bindings: cubes whose
+// member sql is plain text with `{member}`, `{cube.member}`, `{cube}` and `{CUBE}` references,
+// planned with Rust implementations of the cube evaluator, join graph and base tools.
+//
+//   {
+//     "cubes": [{
+//       "name": "orders",
+//       "sqlTable": "public.orders",
+//       "measures": { "count": { "type": "count" } },
+//       "dimensions": {
+//         "id": { "type": "number", "sql": "{CUBE}.id", "primaryKey": true },
+//         "status": { "type": "string", "sql": "{CUBE}.status" }
+//       },
+//       "joins": {
+//         "customers": { "relationship": "many_to_one", "sql": "{CUBE}.customer_id = {customers.id}" }
+//       }
+//     }]
+//   }
+//
+// Templates are the ones of `BaseQuery.sqlTemplates()`, `{type: {name: template}}`.
+// Time dimensions also need `functions/DATETRUNC`, and `expressions/convert_tz`
+// with `expr` and `timezone` for queries in other timezones than UTC.
+// Member sql can't use context symbols such as `SECURITY_CONTEXT`: they need JS.
+mod base_tools;
+mod definitions;
+mod evaluator;
+mod join_graph;
+mod member_sql;
+mod query_options;
+
+pub use base_tools::StaticBaseTools;
+pub use definitions::{
+    StaticCubeDefinition, StaticDimensionDefinition, StaticJoinItemDefinition,
+    StaticMeasureDefinition, StaticMeasureFilter, StaticMeasureFiltersVec, StaticSegmentDefinition,
+};
+pub use evaluator::{StaticCube, StaticCubeEvaluator};
+pub use join_graph::{StaticJoinDefinition, StaticJoinGraph, StaticJoinItem, StaticJoinItemsVec};
+pub use member_sql::StaticMemberSql;
+pub use query_options::StaticQueryOptions;
+
+use crate::cube_bridge::base_query_options::BaseQueryOptionsStatic;
+use crate::cube_bridge::cube_definition::CubeDefinitionStatic;
+use crate::cube_bridge::dimension_definition::DimenstionDefinitionStatic;
+use crate::cube_bridge::measure_definition::MeasureDefinitionStatic;
+use crate::cube_bridge::segment_definition::SegmentDefinitionStatic;
+use crate::cube_bridge::sql_templates_render::StaticSqlTemplatesRender;
+use crate::planner::{QueryBuilder, StaticDateTimeTools};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use cubenativeutils::CubeError;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+#[derive(Deserialize, Debug)]
+pub struct SchemaDefinition {
+    pub cubes: Vec<CubeSchema>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CubeSchema {
+    #[serde(flatten)]
+    pub definition: CubeDefinitionStatic,
+    pub sql_table: Option<String>,
+    pub sql: Option<String>,
+    #[serde(default)]
+    pub measures: BTreeMap<String, MeasureSchema>,
+    #[serde(default)]
+    pub dimensions: BTreeMap<String, DimensionSchema>,
+    #[serde(default)]
+    pub segments: BTreeMap<String, SegmentSchema>,
+    // Joined cube to the join
+    #[serde(default)]
+    pub joins: BTreeMap<String, JoinSchema>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasureSchema {
+    #[serde(flatten)]
+    pub definition: MeasureDefinitionStatic,
+    pub sql: Option<String>,
+    pub denominator_sql: Option<String>,
+    pub filters: Option<Vec<MeasureFilterSchema>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MeasureFilterSchema {
+    pub sql: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DimensionSchema {
+    #[serde(flatten)]
+    pub definition: DimenstionDefinitionStatic,
+    pub sql: Option<String>,
+    pub primary_key: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SegmentSchema {
+    #[serde(flatten)]
+    pub definition: SegmentDefinitionStatic,
+    pub sql: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JoinSchema {
+    pub relationship: String,
+    pub sql: String,
+}
+
+pub struct StaticSchema {
+    evaluator: Rc<StaticCubeEvaluator>,
+    join_graph: Rc<StaticJoinGraph>,
+    templates: Rc<StaticSqlTemplatesRender>,
+}
+
+impl StaticSchema {
+    // Schema and templates are JSON documents of the host
+    pub fn try_new(schema: &str, templates: &str) -> Result<Self, CubeError> {
+        let schema = serde_json::from_str::<SchemaDefinition>(schema)
+            .map_err(|e| CubeError::user(format!("Invalid schema: {}", e)))?;
+        let templates = serde_json::from_str::<HashMap<String, HashMap<String, String>>>(templates)
+            .map_err(|e| CubeError::user(format!("Invalid sql templates: {}", e)))?;
+        let evaluator = Rc::new(StaticCubeEvaluator::try_new(schema)?);
+        Ok(Self {
+            join_graph: Rc::new(StaticJoinGraph::new(evaluator.clone())),
+            evaluator,
+            templates: Rc::new(StaticSqlTemplatesRender::try_new(templates)?),
+        })
+    }
+
+    // Relative date ranges are resolved against `now`, system time of the planner if `None`
+    pub fn query_builder(
+        &self,
+        query: BaseQueryOptionsStatic,
+        now: Option<DateTime<Utc>>,
+    ) -> Result<QueryBuilder, CubeError> {
+        let timezone = match &query.timezone {
+            Some(timezone) => Some(
+                timezone
+                    .parse::<Tz>()
+                    .map_err(|_| CubeError::user(format!("Unknown timezone '{}'", timezone)))?,
+            ),
+            None => None,
+        };
+        let date_time_tools = StaticDateTimeTools::with_now(timezone, 3, now);
+        let base_tools = StaticBaseTools::new(
+            self.evaluator.clone(),
+            self.templates.clone(),
+            date_time_tools.clone(),
+            query.timezone.clone(),
+        );
+        let options = StaticQueryOptions {
+            static_data: query,
+            cube_evaluator: self.evaluator.clone(),
+            base_tools: Rc::new(base_tools),
+            join_graph: self.join_graph.clone(),
+        };
+        QueryBuilder::try_new(Rc::new(options), None, Some(date_time_tools))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_bridge::evaluator::CubeEvaluator;
+    use crate::cube_bridge::join_definition::JoinDefinition;
+    use crate::cube_bridge::join_graph::JoinGraph;
+    use crate::cube_bridge::join_item::JoinItem;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn schema() -> serde_json::Value {
+        json!({
+            "cubes": [
+                {
+                    "name": "orders",
+                    "sqlTable": "public.orders",
+                    "measures": {
+                        "count": { "type": "count" },
+                        "total": { "type": "sum", "sql": "{CUBE}.amount" },
+                    },
+                    "dimensions": {
+                        "id": { "type": "number", "sql": "{CUBE}.id", "primaryKey": true },
+                        "status": { "type": "string", "sql": "{CUBE}.status" },
+                    },
+                    "joins": {
+                        "customers": {
+                            "relationship": "many_to_one",
+                            "sql": "{CUBE}.customer_id = {customers.id}",
+                        },
+                    },
+                },
+                {
+                    "name": "customers",
+                    "sqlTable": "public.customers",
+                    "dimensions": {
+                        "id": { "type": "number", "sql": "{CUBE}.id", "primaryKey": true },
+                        "city": { "type": "string", "sql": "{CUBE}.city" },
+                    },
+                },
+            ]
+        })
+    }
+
+    fn error(schema: serde_json::Value) -> String {
+        match StaticSchema::try_new(&schema.to_string(), "{}") {
+            Ok(_) => panic!("schema is expected to be rejected"),
+            Err(e) => e.message,
+        }
+    }
+
+    #[test]
+    fn schema_is_loaded_with_primary_keys_and_joins() {
+        let schema = StaticSchema::try_new(&schema().to_string(), "{}").unwrap();
+        let orders = schema.evaluator.cube("orders").unwrap();
+        assert_eq!(orders.members(), vec!["count", "total", "id", "status"]);
+        assert_eq!(
+            orders.joins["customers"].static_data.relationship,
+            "belongsTo"
+        );
+        assert_eq!(
+            schema.evaluator.static_data().primary_keys["customers"],
+            vec!["id".to_string()]
+        );
+    }
+
+    #[test]
+    fn join_path_is_built_from_any_root_of_the_query() {
+        let schema = StaticSchema::try_new(&schema().to_string(), "{}").unwrap();
+        let join = schema
+            .join_graph
+            .build_join(vec!["customers".to_string(), "orders".to_string()])
+            .unwrap();
+        assert_eq!(join.static_data().root, "orders");
+        let joins = join.joins().unwrap();
+        assert_eq!(joins.items().len(), 1);
+        assert_eq!(joins.items()[0].static_data().to, "customers");
+        assert!(join.static_data().multiplication_factor["customers"]);
+        assert!(!join.static_data().multiplication_factor["orders"]);
+    }
+
+    #[test]
+    fn relative_date_ranges_are_resolved_against_the_clock_of_the_host() {
+        let schema = StaticSchema::try_new(
+            include_str!("../../tests/fixtures/static_schema/schema.json"),
+            include_str!("../../tests/fixtures/static_schema/templates.json"),
+        )
+        .unwrap();
+        let query = serde_json::from_value::<BaseQueryOptionsStatic>(json!({
+            "measures": ["orders.count"],
+            "timeDimensions": [{ "dimension": "orders.createdAt", "dateRange": ["yesterday"] }],
+        }))
+        .unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let result = schema
+            .query_builder(query, Some(now))
+            .unwrap()
+            .build_sql_and_params()
+            .unwrap();
+        let params = serde_json::to_string(&result.params).unwrap();
+        assert!(params.contains("2024-01-14T00:00:00"), "{}", params);
+        assert!(params.contains("2024-01-14T23:59:59"), "{}", params);
+    }
+
+    #[test]
+    fn invalid_schemas_are_rejected() {
+        let mut duplicate = schema();
+        let orders = duplicate["cubes"][0].clone();
+        duplicate["cubes"].as_array_mut().unwrap().push(orders);
+        assert_eq!(error(duplicate), "Cube 'orders' is defined more than once");
+
+        let mut unknown_join = schema();
+        unknown_join["cubes"][0]["joins"]["users"] =
+            json!({ "relationship": "many_to_one", "sql": "1 = 1" });
+        assert_eq!(
+            error(unknown_join),
+            "Cube 'orders' joins cube 'users' which doesn't exist"
+        );
+
+        let mut no_primary_key = schema();
+        no_primary_key["cubes"][0]["dimensions"]["id"]["primaryKey"] = json!(false);
+        assert_eq!(
+            error(no_primary_key),
+            "Measure 'orders.count' without sql requires a primary key of cube 'orders'"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use super::base_tools::StaticBaseTools;
+use super::evaluator::StaticCubeEvaluator;
+use super::join_graph::StaticJoinGraph;
+use crate::cube_bridge::audit_log_sink::AuditLogSink;
+use crate::cube_bridge::base_query_options::{BaseQueryOptions, BaseQueryOptionsStatic};
+use crate::cube_bridge::base_tools::BaseTools;
+use crate::cube_bridge::cancellation_token::CancellationToken;
+use crate::cube_bridge::evaluator::CubeEvaluator;
+use crate::cube_bridge::join_graph::JoinGraph;
+use crate::cube_bridge::metrics_recorder::MetricsRecorder;
+use crate::cube_bridge::table_name_resolver::TableNameResolver;
+use cubenativeutils::CubeError;
+use std::any::Any;
+use std::rc::Rc;
+
+// Options of a query planned against a static schema. Hosts without Node.js don't provide
+// audit, cancellation or metrics services
+pub struct StaticQueryOptions {
+    pub static_data: BaseQueryOptionsStatic,
+    pub cube_evaluator: Rc<StaticCubeEvaluator>,
+    pub base_tools: Rc<StaticBaseTools>,
+    pub join_graph: Rc<StaticJoinGraph>,
+}
+
+impl BaseQueryOptions for StaticQueryOptions {
+    fn measures(&self) -> Result<Option<Vec<String>>, CubeError> {
+        Ok(self.static_data.measures.clone())
+    }
+
+    fn dimensions(&self) -> Result<Option<Vec<String>>, CubeError> {
+        Ok(self.static_data.dimensions.clone())
+    }
+
+    fn cube_evaluator(&self) -> Result<Rc<dyn CubeEvaluator>, CubeError> {
+        Ok(self.cube_evaluator.clone())
+    }
+
+    fn base_tools(&self) -> Result<Rc<dyn BaseTools>, CubeError> {
+        Ok(self.base_tools.clone())
+    }
+
+    fn join_graph(&self) -> Result<Rc<dyn JoinGraph>, CubeError> {
+        Ok(self.join_graph.clone())
+    }
+
+    fn audit_log_sink(&self) -> Result<Option<Rc<dyn AuditLogSink>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_audit_log_sink(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn cancellation_token(&self) -> Result<Option<Rc<dyn CancellationToken>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_cancellation_token(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn table_name_resolver(&self) -> Result<Option<Rc<dyn TableNameResolver>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_table_name_resolver(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn metrics_recorder(&self) -> Result<Option<Rc<dyn MetricsRecorder>>, CubeError> {
+        Ok(None)
+    }
+
+    fn has_metrics_recorder(&self) -> Result<bool, CubeError> {
+        Ok(false)
+    }
+
+    fn as_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn static_data(&self) -> &BaseQueryOptionsStatic {
+        &self.static_data
+    }
+}
@@ -0,0 +1,119 @@
+// Exports of the wasm32-unknown-unknown build, e.g. for schema IDEs rendering the SQL preview
+// of a query in the browser without a round trip, or validating queries before they are sent.
+// Strings are passed through the linear memory of the module: the host writes UTF-8 JSON
+// into buffers from `cubesqlplanner_alloc` and reads the response as a nul-terminated string,
+// released with `cubesqlplanner_free_string`.
+// There is no system clock on this target: the host passes the current time with the query.
+// Build with `cargo rustc --no-default-features --target wasm32-unknown-unknown --crate-type cdylib`.
+use crate::protocol::{self, NoSchemaRuntime, SchemaRuntime, PROTOCOL_VERSION};
+use chrono::{DateTime, TimeZone, Utc};
+use cubenativeutils::CubeError;
+use std::ffi::{c_char, CString};
+
+// Schema runtime of the host, read from the linear memory before planning
+struct WasmSchemaRuntime {
+    schema: String,
+    templates: String,
+    now: Option<DateTime<Utc>>,
+}
+
+impl SchemaRuntime for WasmSchemaRuntime {
+    fn schema(&self) -> Result<String, CubeError> {
+        Ok(self.schema.clone())
+    }
+
+    fn templates(&self) -> Result<String, CubeError> {
+        Ok(self.templates.clone())
+    }
+
+    fn now(&self) -> Result<Option<DateTime<Utc>>, CubeError> {
+        Ok(self.now)
+    }
+}
+
+unsafe fn read_string(s: *const u8, len: usize) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        String::from_utf8_lossy(std::slice::from_raw_parts(s, len)).into_owned()
+    }
+}
+
+fn into_c_string(response: String) -> *mut c_char {
+    // Interior nul bytes can't be passed through C strings
+    CString::new(response.replace('\0', ""))
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn cubesqlplanner_protocol_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+/// Allocates a buffer of `len` bytes for the host to write a query into.
+#[no_mangle]
+pub extern "C" fn cubesqlplanner_alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// Releases a buffer returned by `cubesqlplanner_alloc`.
+///
+/// # Safety
+/// `ptr` must be returned by `cubesqlplanner_alloc` for the same `len` and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_dealloc(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() {
+        drop(Vec::from_raw_parts(ptr, 0, len));
+    }
+}
+
+/// Plans a query against the schema and sql templates of the host and returns the `plan`
+/// response: SQL, params and metadata. `now_ms` is the current time in milliseconds since
+/// the epoch, e.g. `Date.now()`, relative date ranges are resolved against it.
+///
+/// # Safety
+/// `query`, `schema` and `templates` must point to `query_len`, `schema_len` and
+/// `templates_len` bytes of JSON of the query, of the cubes and of the sql templates.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_plan(
+    query: *const u8,
+    query_len: usize,
+    schema: *const u8,
+    schema_len: usize,
+    templates: *const u8,
+    templates_len: usize,
+    now_ms: f64,
+) -> *mut c_char {
+    let query = read_string(query, query_len);
+    let runtime = WasmSchemaRuntime {
+        schema: read_string(schema, schema_len),
+        templates: read_string(templates, templates_len),
+        now: Utc.timestamp_millis_opt(now_ms as i64).single(),
+    };
+    into_c_string(protocol::handle_query("plan", &query, &runtime))
+}
+
+/// Validates a query without the schema and returns the `validate` response.
+///
+/// # Safety
+/// `query` must point to `len` bytes of the query JSON.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_validate(query: *const u8, len: usize) -> *mut c_char {
+    let query = read_string(query, len);
+    into_c_string(protocol::handle_query("validate", &query, &NoSchemaRuntime))
+}
+
+/// Releases a string returned by this module.
+///
+/// # Safety
+/// `s` must be returned by this module and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn cubesqlplanner_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "ffi")]
+use cubesqlplanner::ffi::{
+    cubesqlplanner_call, cubesqlplanner_free_string, cubesqlplanner_plan, cubesqlplanner_validate,
+    CubeSqlPlannerSchemaRuntime,
+};
+use cubesqlplanner::protocol::{INVALID_PARAMS, USER_ERROR};
+use serde_json::{json, Value};
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::ptr;
+
+// Schema runtime of the embedder: `orders` and `customers` cubes with Postgres templates,
+// `user_data` counts released strings
+extern "C" fn schema(_user_data: *mut c_void) -> *mut c_char {
+    CString::new(include_str!("fixtures/static_schema/schema.json"))
+        .unwrap()
+        .into_raw()
+}
+
+extern "C" fn templates(_user_data: *mut c_void) -> *mut c_char {
+    CString::new(include_str!("fixtures/static_schema/templates.json"))
+        .unwrap()
+        .into_raw()
+}
+
+extern "C" fn no_templates(_user_data: *mut c_void) -> *mut c_char {
+    ptr::null_mut()
+}
+
+extern "C" fn free_string(user_data: *mut c_void, s: *mut c_char) {
+    unsafe {
+        *(user_data as *mut usize) += 1;
+        drop(CString::from_raw(s));
+    }
+}
+
+fn runtime(released: &mut usize) -> CubeSqlPlannerSchemaRuntime {
+    CubeSqlPlannerSchemaRuntime {
+        user_data: released as *mut usize as *mut c_void,
+        schema,
+        templates,
+        free_string,
+    }
+}
+
+fn call(f: impl FnOnce(*const c_char) -> *mut c_char, input: &str) -> Value {
+    let input = CString::new(input).unwrap();
+    let response = f(input.as_ptr());
+    let result =
+        serde_json::from_str(&unsafe { CStr::from_ptr(response) }.to_string_lossy()).unwrap();
+    unsafe { cubesqlplanner_free_string(response) };
+    result
+}
+
+#[test]
+fn ffi_plan_with_schema_runtime() {
+    let mut released = 0usize;
+    let runtime = runtime(&mut released);
+    let response = call(
+        |query| unsafe { cubesqlplanner_plan(query, &runtime) },
+        r#"{
+            "measures": ["orders.count"],
+            "dimensions": ["customers.city"],
+            "filters": [{ "member": "orders.status", "operator": "equals", "values": ["shipped"] }]
+        }"#,
+    );
+    let sql = response["result"]["sql"].as_str().unwrap();
+    assert!(sql.contains("FROM public.orders"), "{}", sql);
+    assert!(sql.contains("LEFT JOIN public.customers"), "{}", sql);
+    assert!(sql.contains("= $1"), "{}", sql);
+    assert_eq!(response["result"]["params"], json!(["shipped"]));
+
+    let response = call(
+        |query| unsafe { cubesqlplanner_plan(query, &runtime) },
+        r#"{ "measures": ["users.count"] }"#,
+    );
+    assert_eq!(response["error"]["code"], USER_ERROR);
+    assert_eq!(
+        response["error"]["message"],
+        "Cube 'users' not found for path 'users.count'"
+    );
+    assert_eq!(released, 4);
+}
+
+#[test]
+fn ffi_plan_without_templates() {
+    let mut released = 0usize;
+    let runtime = CubeSqlPlannerSchemaRuntime {
+        templates: no_templates,
+        ..runtime(&mut released)
+    };
+    let response = call(
+        |query| unsafe { cubesqlplanner_plan(query, &runtime) },
+        r#"{ "measures": ["orders.count"] }"#,
+    );
+    assert_eq!(response["error"]["code"], USER_ERROR);
+    assert_eq!(
+        response["error"]["message"],
+        "Schema runtime returned no sql templates"
+    );
+    assert_eq!(released, 1);
+}
+
+#[test]
+fn ffi_call_with_schema_runtime() {
+    let mut released = 0usize;
+    let runtime = runtime(&mut released);
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "method": "plan",
+        "params": { "query": { "measures": ["orders.count"] } }
+    });
+    let response = call(
+        |request| unsafe { cubesqlplanner_call(request, &runtime) },
+        &request.to_string(),
+    );
+    assert_eq!(response["id"], 3);
+    let sql = response["result"]["sql"].as_str().unwrap();
+    assert!(sql.contains("FROM public.orders"), "{}", sql);
+}
+
+#[test]
+fn ffi_plan_without_schema_runtime() {
+    let response = call(
+        |query| unsafe { cubesqlplanner_plan(query, ptr::null()) },
+        r#"{ "measures": ["orders.count"] }"#,
+    );
+    assert_eq!(response["error"]["code"], USER_ERROR);
+}
+
+#[test]
+fn ffi_validate() {
+    let response = call(
+        |query| unsafe { cubesqlplanner_validate(query) },
+        r#"{ "measures": ["orders"], "limit": "ten" }"#,
+    );
+    assert_eq!(response["result"]["valid"], false);
+    assert_eq!(response["result"]["errors"].as_array().unwrap().len(), 2);
+
+    let response = call(
+        |query| unsafe { cubesqlplanner_validate(query) },
+        r#"{ "measures": 1 }"#,
+    );
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+}
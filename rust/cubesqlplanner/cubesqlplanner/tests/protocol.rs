@@ -0,0 +1,93 @@
+use cubenativeutils::CubeError;
+use cubesqlplanner::protocol::{
+    handle_query, handle_request, NoSchemaRuntime, SchemaRuntime, PARSE_ERROR, USER_ERROR,
+};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+// Schema runtime of fixtures: `orders` and `customers` cubes with Postgres templates
+struct FixtureSchemaRuntime;
+
+impl SchemaRuntime for FixtureSchemaRuntime {
+    fn schema(&self) -> Result<String, CubeError> {
+        Ok(include_str!("fixtures/static_schema/schema.json").to_string())
+    }
+
+    fn templates(&self) -> Result<String, CubeError> {
+        Ok(include_str!("fixtures/static_schema/templates.json").to_string())
+    }
+}
+
+// Each fixture holds a serialized request and the response expected for it.
+// Fixtures are the contract of the protocol version: they may only change along with PROTOCOL_VERSION
+#[test]
+fn protocol_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/protocol");
+    let mut paths = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty());
+    for path in paths {
+        let fixture: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let request = serde_json::to_string(&fixture["request"]).unwrap();
+        let response: Value =
+            serde_json::from_str(&handle_request(&request, &FixtureSchemaRuntime)).unwrap();
+        assert_eq!(response, fixture["response"], "{}", path.display());
+    }
+}
+
+#[test]
+fn protocol_parse_error() {
+    let response: Value =
+        serde_json::from_str(&handle_request("{\"jsonrpc\":", &NoSchemaRuntime)).unwrap();
+    assert_eq!(response["id"], Value::Null);
+    assert_eq!(response["error"]["code"], PARSE_ERROR);
+}
+
+#[test]
+fn protocol_plan_without_schema_runtime() {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "plan",
+        "params": { "query": { "measures": ["orders.count"] } }
+    });
+    let response: Value = serde_json::from_str(&handle_request(
+        &serde_json::to_string(&request).unwrap(),
+        &NoSchemaRuntime,
+    ))
+    .unwrap();
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["error"]["code"], USER_ERROR);
+}
+
+#[test]
+fn protocol_handle_query() {
+    let response: Value = serde_json::from_str(&handle_query(
+        "plan",
+        r#"{
+            "measures": ["orders.total"],
+            "dimensions": ["orders.status"],
+            "filters": [{ "member": "customers.city", "operator": "equals", "values": ["Berlin"] }],
+            "limit": 10
+        }"#,
+        &FixtureSchemaRuntime,
+    ))
+    .unwrap();
+    let sql = response["result"]["sql"].as_str().unwrap();
+    assert!(sql.contains("FROM public.orders"), "{}", sql);
+    assert!(sql.contains("LEFT JOIN public.customers"), "{}", sql);
+    assert!(sql.contains("LIMIT 10"), "{}", sql);
+    assert_eq!(response["result"]["params"], json!(["Berlin"]));
+    assert_eq!(
+        response["result"]["metadata"]["memberParams"]["customers.city"],
+        json!([0])
+    );
+
+    let response: Value =
+        serde_json::from_str(&handle_query("validate", "{", &NoSchemaRuntime)).unwrap();
+    assert_eq!(response["error"]["code"], PARSE_ERROR);
+}